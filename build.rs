@@ -0,0 +1,73 @@
+//! Builds the eBPF programs in `httpulse-ebpf` for `--ebpf minimal` when
+//! `HTTPULSE_BUILD_EBPF=1` is set.
+//!
+//! They need a nightly toolchain with `rust-src` and `bpf-linker` on the
+//! PATH, so ordinary builds skip them. Without them httpulse still builds,
+//! and the collector loads the object named by `HTTPULSE_EBPF_OBJECT` at run
+//! time or reports that this binary has no eBPF programs.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const EBPF_DIR: &str = "httpulse-ebpf";
+const EBPF_BIN: &str = "httpulse";
+const BUILD_ENV: &str = "HTTPULSE_BUILD_EBPF";
+
+fn main() {
+    println!("cargo:rerun-if-changed={EBPF_DIR}/src");
+    println!("cargo:rerun-if-changed={EBPF_DIR}/Cargo.toml");
+    println!("cargo:rerun-if-env-changed={BUILD_ENV}");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("cargo sets OUT_DIR"));
+    let object = out_dir.join("httpulse-ebpf.o");
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("linux") {
+        return;
+    }
+    // An empty object stands for "built without eBPF"
+    if env::var(BUILD_ENV).as_deref() != Ok("1") {
+        fs::write(&object, []).expect("OUT_DIR is writable");
+        return;
+    }
+    if let Err(err) = build_programs(&out_dir, &object) {
+        println!("cargo:warning=eBPF collector not built: {err}");
+        fs::write(&object, []).expect("OUT_DIR is writable");
+    }
+}
+
+fn build_programs(out_dir: &std::path::Path, object: &std::path::Path) -> Result<(), String> {
+    let target = match env::var("CARGO_CFG_TARGET_ENDIAN").as_deref() {
+        Ok("big") => "bpfeb-unknown-none",
+        _ => "bpfel-unknown-none",
+    };
+    let target_dir = out_dir.join("ebpf");
+    let mut rustflags = OsString::from("-Cdebuginfo=2\x1f-Clink-arg=--btf");
+    if let Ok(arch) = env::var("CARGO_CFG_TARGET_ARCH") {
+        rustflags.push(format!("\x1f--cfg=bpf_target_arch=\"{arch}\""));
+    }
+    let status = Command::new("rustup")
+        .args(["run", "nightly", "cargo", "build", "--release", "--quiet"])
+        .args(["-Z", "build-std=core", "--target", target])
+        .arg("--manifest-path")
+        .arg(PathBuf::from(EBPF_DIR).join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .env("CARGO_ENCODED_RUSTFLAGS", rustflags)
+        // The outer build's compiler and wrappers (clippy-driver) are not for BPF
+        .env_remove("RUSTC")
+        .env_remove("RUSTC_WRAPPER")
+        .env_remove("RUSTC_WORKSPACE_WRAPPER")
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .status()
+        .map_err(|err| format!("cannot run rustup: {err}"))?;
+    if !status.success() {
+        return Err(format!(
+            "cargo build for {target} failed ({status}); install a nightly toolchain with \
+             rust-src and bpf-linker"
+        ));
+    }
+    let built = target_dir.join(target).join("release").join(EBPF_BIN);
+    fs::copy(&built, object).map_err(|err| format!("cannot copy {}: {err}", built.display()))?;
+    Ok(())
+}
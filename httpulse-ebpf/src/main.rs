@@ -0,0 +1,171 @@
+//! Tracepoint programs behind `httpulse --ebpf minimal`.
+//!
+//! Both programs key the shared `CONNECTIONS` map by connection as seen from
+//! this host: source and destination address (IPv4 as v4-mapped IPv6), then
+//! source and destination port in host order, zero padded to 40 bytes. The
+//! value holds the retransmit and duplicate-ACK counters read by userspace,
+//! followed by the duplicate-ACK detector state. Both tracepoints fire with
+//! the socket locked, so updates to one connection never race each other.
+//!
+//! Tracepoint layouts differ between kernels, so field offsets are not
+//! compiled in: userspace reads them from tracefs and sets the `*_FIELDS`
+//! globals before loading.
+
+#![no_std]
+#![no_main]
+
+use aya_ebpf::macros::{map, tracepoint};
+use aya_ebpf::maps::LruHashMap;
+use aya_ebpf::programs::TracePointContext;
+
+const AF_INET: u16 = 2;
+const AF_INET6: u16 = 10;
+/// Connections tracked at once; least recently updated ones are evicted.
+const MAP_ENTRIES: u32 = 4096;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ConnKey {
+    saddr: [u8; 16],
+    daddr: [u8; 16],
+    sport: u16,
+    dport: u16,
+    _pad: u32,
+}
+
+#[repr(C)]
+struct ConnCounters {
+    retrans: u32,
+    dup_acks: u32,
+    /// `snd_una` seen with the previous segment
+    last_una: u32,
+    /// Previous segment was a pure ACK while data was in flight
+    pending: u32,
+}
+
+#[map]
+static CONNECTIONS: LruHashMap<ConnKey, ConnCounters> =
+    LruHashMap::with_max_entries(MAP_ENTRIES, 0);
+
+/// `tcp:tcp_retransmit_skb` offsets of saddr_v6, daddr_v6, sport, dport.
+#[unsafe(no_mangle)]
+static RETRANSMIT_FIELDS: [u32; 4] = [0; 4];
+
+/// `tcp:tcp_probe` offsets of family, saddr, daddr, sport, dport, data_len,
+/// snd_nxt and snd_una.
+#[unsafe(no_mangle)]
+static TCP_PROBE_FIELDS: [u32; 8] = [0; 8];
+
+#[unsafe(link_section = "license")]
+#[unsafe(no_mangle)]
+static LICENSE: [u8; 13] = *b"Dual MIT/GPL\0";
+
+/// Offset `index` of a `*_FIELDS` global; volatile so the zeroed initializer
+/// is not folded in before userspace overwrites it.
+fn field(fields: &[u32], index: usize) -> usize {
+    // SAFETY: `fields` is a valid global and `index` is in bounds.
+    unsafe { core::ptr::read_volatile(&fields[index]) as usize }
+}
+
+/// Reads a `T` from the tracepoint record at `offset`.
+fn read<T>(ctx: &TracePointContext, offset: usize) -> Option<T> {
+    // SAFETY: bpf_probe_read checks the access; a failure yields `None`.
+    unsafe { ctx.read_at(offset) }.ok()
+}
+
+fn new_counters(retrans: u32, last_una: u32) -> ConnCounters {
+    ConnCounters {
+        retrans,
+        dup_acks: 0,
+        last_una,
+        pending: 0,
+    }
+}
+
+/// Bumps the retransmit counter of the connection.
+#[tracepoint]
+pub fn tcp_retransmit_skb(ctx: TracePointContext) -> u32 {
+    let fields = &RETRANSMIT_FIELDS;
+    // The tracepoint already v4-maps IPv4 addresses
+    let (Some(saddr), Some(daddr), Some(sport), Some(dport)) = (
+        read(&ctx, field(fields, 0)),
+        read(&ctx, field(fields, 1)),
+        read(&ctx, field(fields, 2)),
+        read(&ctx, field(fields, 3)),
+    ) else {
+        return 0;
+    };
+    let key = ConnKey {
+        saddr,
+        daddr,
+        sport,
+        dport,
+        _pad: 0,
+    };
+    match CONNECTIONS.get_ptr_mut(&key) {
+        // SAFETY: map values stay valid for the rest of the program run.
+        Some(counters) => unsafe { (*counters).retrans += 1 },
+        None => {
+            let _ = CONNECTIONS.insert(&key, &new_counters(1, 0), 0);
+        }
+    }
+    0
+}
+
+/// Counts duplicate ACKs: pure ACKs received while data is in flight that do
+/// not advance `snd_una`. The tracepoint fires before the ACK is processed,
+/// so each segment is judged when the next one shows whether `snd_una` moved.
+#[tracepoint]
+pub fn tcp_probe(ctx: TracePointContext) -> u32 {
+    let _ = try_tcp_probe(&ctx);
+    0
+}
+
+fn try_tcp_probe(ctx: &TracePointContext) -> Option<()> {
+    let fields = &TCP_PROBE_FIELDS;
+    // Addresses are stored as a sockaddr_in or sockaddr_in6
+    let (saddr_at, daddr_at) = (field(fields, 1), field(fields, 2));
+    let mut key = ConnKey {
+        saddr: [0; 16],
+        daddr: [0; 16],
+        sport: read(ctx, field(fields, 3))?,
+        dport: read(ctx, field(fields, 4))?,
+        _pad: 0,
+    };
+    match read::<u16>(ctx, field(fields, 0))? {
+        AF_INET => {
+            key.saddr[10..12].copy_from_slice(&[0xff, 0xff]);
+            key.saddr[12..].copy_from_slice(&read::<[u8; 4]>(ctx, saddr_at + 4)?);
+            key.daddr[10..12].copy_from_slice(&[0xff, 0xff]);
+            key.daddr[12..].copy_from_slice(&read::<[u8; 4]>(ctx, daddr_at + 4)?);
+        }
+        AF_INET6 => {
+            key.saddr = read(ctx, saddr_at + 8)?;
+            key.daddr = read(ctx, daddr_at + 8)?;
+        }
+        _ => return None,
+    }
+    let snd_una: u32 = read(ctx, field(fields, 7))?;
+
+    let Some(counters) = CONNECTIONS.get_ptr_mut(&key) else {
+        let _ = CONNECTIONS.insert(&key, &new_counters(0, snd_una), 0);
+        return Some(());
+    };
+    // SAFETY: map values stay valid for the rest of the program run.
+    let counters = unsafe { &mut *counters };
+    let (last_una, pending) = (counters.last_una, counters.pending);
+    counters.last_una = snd_una;
+    if pending != 0 && last_una == snd_una {
+        counters.dup_acks += 1;
+    }
+    let data_len: u16 = read(ctx, field(fields, 5))?;
+    let snd_nxt: u32 = read(ctx, field(fields, 6))?;
+    counters.pending = u32::from(data_len == 0 && snd_nxt != snd_una);
+    Some(())
+}
+
+#[cfg(not(test))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
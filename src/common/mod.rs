@@ -1,2 +1,3 @@
+pub mod fs;
 pub mod net;
 pub mod time;
@@ -1,13 +1,81 @@
+use thiserror::Error;
 use url::Url;
 
-pub fn parse_target_url(input: &str) -> Option<Url> {
+#[derive(Debug, Error, PartialEq)]
+pub enum TargetError {
+    #[error("empty target")]
+    Empty,
+    #[error("not a URL: {0}")]
+    Malformed(#[from] url::ParseError),
+    #[error("unsupported scheme {scheme:?}, expected http or https")]
+    UnsupportedScheme { scheme: String },
+    #[error("URL has no host")]
+    MissingHost,
+}
+
+/// Parses a target as typed on the command line, in a targets file or in
+/// the UI: a URL, or a bare host that gets `https://`. Says why a value is
+/// rejected and refuses URLs that parse yet cannot be probed, such as a
+/// misspelt scheme (`htps://host`).
+pub fn validate_target(input: &str) -> Result<Url, TargetError> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
-        return None;
+        return Err(TargetError::Empty);
     }
-    if trimmed.contains("://") {
-        Url::parse(trimmed).ok()
+    let url = if trimmed.contains("://") {
+        Url::parse(trimmed)?
     } else {
-        Url::parse(&format!("https://{trimmed}")).ok()
+        Url::parse(&format!("https://{trimmed}"))?
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(TargetError::UnsupportedScheme {
+            scheme: url.scheme().to_string(),
+        });
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(TargetError::MissingHost);
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_target_accepts_urls_and_bare_hosts() {
+        assert_eq!(
+            validate_target("example.com").unwrap().as_str(),
+            "https://example.com/"
+        );
+        assert_eq!(
+            validate_target(" http://10.0.0.1:8080/health ")
+                .unwrap()
+                .as_str(),
+            "http://10.0.0.1:8080/health"
+        );
+        let url = validate_target("localhost:8080").unwrap();
+        assert_eq!(url.scheme(), "https");
+        assert_eq!(url.host_str(), Some("localhost"));
+        assert_eq!(url.port_or_known_default(), Some(8080));
+    }
+
+    #[test]
+    fn validate_target_explains_rejections() {
+        assert_eq!(validate_target("  "), Err(TargetError::Empty));
+        assert_eq!(
+            validate_target("htps://foo"),
+            Err(TargetError::UnsupportedScheme {
+                scheme: "htps".to_string()
+            })
+        );
+        assert_eq!(
+            validate_target("http://"),
+            Err(TargetError::Malformed(url::ParseError::EmptyHost))
+        );
+        assert!(matches!(
+            validate_target("exa mple.com"),
+            Err(TargetError::Malformed(_))
+        ));
     }
 }
@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub trait Clock {
     fn now(&self) -> SystemTime;
@@ -12,3 +12,145 @@ impl Clock for SystemClock {
         SystemTime::now()
     }
 }
+
+/// Minutes since local midnight for `ts` (UTC where no timezone data is available).
+pub fn local_minute_of_day(ts: SystemTime) -> u16 {
+    (local_second_of_day(ts) / 60) as u16
+}
+
+/// Local wall-clock time of `ts` as `HH:MM:SS`.
+pub fn format_local_hms(ts: SystemTime) -> String {
+    let secs = local_second_of_day(ts);
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Seconds since local midnight for `ts` (UTC where no timezone data is available).
+fn local_second_of_day(ts: SystemTime) -> u32 {
+    local_time(ts).second_of_day()
+}
+
+/// Broken-down local wall-clock time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LocalTime {
+    pub second: u32,
+    pub minute: u32,
+    pub hour: u32,
+    /// Day of month, 1-31
+    pub day: u32,
+    /// Month, 1-12
+    pub month: u32,
+    /// Day of week, 0 = Sunday
+    pub weekday: u32,
+}
+
+impl LocalTime {
+    pub fn second_of_day(&self) -> u32 {
+        self.hour * 3600 + self.minute * 60 + self.second
+    }
+}
+
+/// Breaks `ts` down in local time (UTC where no timezone data is available).
+pub fn local_time(ts: SystemTime) -> LocalTime {
+    let secs = ts
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0);
+
+    #[cfg(unix)]
+    {
+        let time = secs as libc::time_t;
+        // SAFETY: zeroed `tm` is a valid out-parameter and both pointers are live.
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        if !unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+            return LocalTime {
+                second: tm.tm_sec.min(59) as u32,
+                minute: tm.tm_min as u32,
+                hour: tm.tm_hour as u32,
+                day: tm.tm_mday as u32,
+                month: tm.tm_mon as u32 + 1,
+                weekday: tm.tm_wday as u32,
+            };
+        }
+    }
+
+    utc_time(secs)
+}
+
+/// UTC timestamp of a calendar date and time of day; `None` before 1970 or
+/// for an out-of-range month or day.
+pub fn utc_from_civil(year: i64, month: u32, day: u32, second_of_day: u32) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Days-from-civil with March as the first month of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = (month as i64 + 9) % 12;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    let secs = u64::try_from(days * 86_400 + second_of_day as i64).ok()?;
+    Some(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+}
+
+/// Civil-from-days conversion for the UTC fallback.
+fn utc_time(secs: i64) -> LocalTime {
+    let days = secs.div_euclid(86_400);
+    let second_of_day = secs.rem_euclid(86_400) as u32;
+    let era_day = days + 719_468;
+    let era = era_day.div_euclid(146_097);
+    let day_of_era = era_day - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    LocalTime {
+        second: second_of_day % 60,
+        minute: second_of_day / 60 % 60,
+        hour: second_of_day / 3600,
+        day,
+        month,
+        // 1970-01-01 was a Thursday
+        weekday: (days + 4).rem_euclid(7) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn utc_fallback_breaks_down_civil_dates() {
+        // 2024-02-29 13:45:07 UTC, a Thursday
+        let time = utc_time(1_709_214_307);
+        assert_eq!(
+            time,
+            LocalTime {
+                second: 7,
+                minute: 45,
+                hour: 13,
+                day: 29,
+                month: 2,
+                weekday: 4,
+            }
+        );
+        assert_eq!(utc_time(0).weekday, 4);
+        assert_eq!(
+            utc_from_civil(2024, 2, 29, 13 * 3600 + 45 * 60 + 7),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_214_307))
+        );
+        assert_eq!(utc_from_civil(1969, 12, 31, 0), None);
+        assert_eq!(
+            local_time(UNIX_EPOCH + Duration::from_secs(1_709_214_307)).second,
+            7
+        );
+    }
+}
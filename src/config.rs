@@ -1,12 +1,23 @@
+use crate::alerts::{AlertRule, NotifyRule};
+use crate::common::time::{LocalTime, local_time};
+use crate::metrics::{StatColumn, StatsPreset};
+use crate::probe::ErrorCategory;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
 use url::Url;
 use uuid::Uuid;
 
 pub type TargetId = Uuid;
 pub type ProfileId = Uuid;
 
+/// Upper bound for `TargetConfig::jitter_pct`.
+pub const MAX_JITTER_PCT: u8 = 50;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GlobalConfig {
     pub ui_refresh_hz: u16,
@@ -15,6 +26,87 @@ pub struct GlobalConfig {
     pub link_capacity_mbps: Option<f64>,
     pub ebpf_enabled: bool,
     pub ebpf_mode: EbpfMode,
+    /// Daily local-time window during which every worker is paused
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Retention policy applied to every target
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+    /// Leave burst samples out of windows of 15m and longer, so a burst's
+    /// dense samples don't dominate their percentiles
+    #[serde(default)]
+    pub exclude_burst_from_long_windows: bool,
+    /// Count scheduled slots that passed without a probe as lost probes
+    #[serde(default)]
+    pub count_missed_as_loss: bool,
+    /// Statistics shown per metric in the metrics table
+    #[serde(default)]
+    pub stats_preset: StatsPreset,
+    /// Per-bucket statistic to chart instead of raw samples
+    #[serde(default)]
+    pub chart_stat: Option<StatColumn>,
+    /// Label the chart's time axis with wall-clock times instead of ages
+    #[serde(default)]
+    pub absolute_time_axis: bool,
+    /// Plot chart values on a log10 y axis
+    #[serde(default)]
+    pub log_scale_chart: bool,
+    /// Bell and desktop notification on sustained probe loss; off unless set
+    #[serde(default)]
+    pub notify: Option<NotifyRule>,
+    /// Client every probe worker uses
+    #[serde(default)]
+    pub backend: ProbeBackendKind,
+    /// Failure categories left off the chart
+    #[serde(default)]
+    pub hidden_error_markers: BTreeSet<ErrorCategory>,
+    /// Count timed-out probes as taking the full timeout in latency statistics
+    #[serde(default)]
+    pub include_failures_in_latency: bool,
+    /// Key overrides by action name, checked for conflicts at startup
+    #[serde(default)]
+    pub keymap: BTreeMap<String, KeyBinding>,
+    /// Let probes go through the proxy named in http_proxy/https_proxy
+    #[serde(default)]
+    pub use_proxy_env: bool,
+    /// Most download-heavy probes (a GET reading over 64 KiB) running at
+    /// once; the rest wait their turn. `None` runs them all
+    #[serde(default)]
+    pub download_slots: Option<u32>,
+    /// Where the JSON status dump (SIGUSR1 or its key) is written
+    #[serde(default = "default_status_file")]
+    pub status_file: PathBuf,
+    /// Color theme of the UI
+    #[serde(default)]
+    pub theme: ThemeName,
+}
+
+/// `$XDG_RUNTIME_DIR` is private to the user; the shared temp directory is
+/// only the fallback.
+fn default_status_file() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir)
+        .join("httpulse-status.json")
+}
+
+/// Keys bound to one action in the config file: `"pause": "space"` or
+/// `"quit": ["q", "ctrl+c"]`. An empty list unbinds the action.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl KeyBinding {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyBinding::Single(key) => std::slice::from_ref(key),
+            KeyBinding::Multiple(keys) => keys,
+        }
+    }
 }
 
 impl Default for GlobalConfig {
@@ -31,6 +123,465 @@ impl Default for GlobalConfig {
             link_capacity_mbps: None,
             ebpf_enabled: false,
             ebpf_mode: EbpfMode::Off,
+            quiet_hours: None,
+            sampling: SamplingConfig::default(),
+            exclude_burst_from_long_windows: false,
+            count_missed_as_loss: false,
+            stats_preset: StatsPreset::default(),
+            chart_stat: None,
+            absolute_time_axis: false,
+            log_scale_chart: false,
+            notify: None,
+            backend: ProbeBackendKind::default(),
+            hidden_error_markers: BTreeSet::new(),
+            include_failures_in_latency: false,
+            keymap: BTreeMap::new(),
+            use_proxy_env: false,
+            download_slots: None,
+            status_file: default_status_file(),
+            theme: ThemeName::default(),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum QuietHoursError {
+    #[error("expected HH:MM-HH:MM")]
+    Malformed,
+    #[error("start and end must differ")]
+    Empty,
+}
+
+/// A daily window such as `22:00-07:00`; windows may wrap past midnight.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QuietHours {
+    start_minute: u16,
+    end_minute: u16,
+}
+
+impl QuietHours {
+    /// Whether `minute` (minutes since midnight) falls inside the window.
+    pub fn contains(&self, minute: u16) -> bool {
+        if self.start_minute < self.end_minute {
+            (self.start_minute..self.end_minute).contains(&minute)
+        } else {
+            minute >= self.start_minute || minute < self.end_minute
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = QuietHoursError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        fn parse_clock(value: &str) -> Option<u16> {
+            let (hours, minutes) = value.trim().split_once(':')?;
+            let hours = hours.parse::<u16>().ok().filter(|h| *h < 24)?;
+            let minutes = minutes.parse::<u16>().ok().filter(|m| *m < 60)?;
+            Some(hours * 60 + minutes)
+        }
+
+        let (start, end) = input.split_once('-').ok_or(QuietHoursError::Malformed)?;
+        let start_minute = parse_clock(start).ok_or(QuietHoursError::Malformed)?;
+        let end_minute = parse_clock(end).ok_or(QuietHoursError::Malformed)?;
+        if start_minute == end_minute {
+            return Err(QuietHoursError::Empty);
+        }
+        Ok(Self {
+            start_minute,
+            end_minute,
+        })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = QuietHoursError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<QuietHours> for String {
+    fn from(value: QuietHours) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start_minute / 60,
+            self.start_minute % 60,
+            self.end_minute / 60,
+            self.end_minute % 60
+        )
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CronScheduleError {
+    #[error("expected 5 or 6 fields: [sec] min hour day month weekday")]
+    FieldCount,
+    #[error("invalid {field} field `{value}`")]
+    InvalidField { field: &'static str, value: String },
+    #[error("empty schedule; use off to probe at the interval")]
+    Empty,
+    #[error("interval must be longer than zero")]
+    ZeroInterval,
+}
+
+/// A cron-style probe schedule: `[sec] min hour day month weekday`, evaluated
+/// in local time. Fields take `*`, `N`, `A-B`, `*/S`, `A-B/S` and comma
+/// lists; months and weekdays also accept `JAN`/`MON` names (0 or 7 = Sunday).
+/// Day of month and weekday must both match.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    expression: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    weekdays: u8,
+}
+
+/// Upper bound on the skip steps `next_after` takes, enough for any date
+/// that recurs at least every four years.
+const CRON_SEARCH_STEPS: usize = 8_000;
+
+impl CronSchedule {
+    /// Whether the day and hour of `ts` fall inside the schedule, i.e. the
+    /// target is expected to fire at some point during this hour.
+    pub fn is_active_at(&self, ts: SystemTime) -> bool {
+        let time = local_time(ts);
+        self.matches_day(&time) && self.hours & (1 << time.hour) != 0
+    }
+
+    /// First whole second strictly after `ts` that matches the schedule.
+    pub fn next_after(&self, ts: SystemTime) -> Option<SystemTime> {
+        let whole_secs = ts.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let mut candidate = UNIX_EPOCH + Duration::from_secs(whole_secs + 1);
+        for _ in 0..CRON_SEARCH_STEPS {
+            let time = local_time(candidate);
+            let skip = if !self.matches_day(&time) {
+                86_400 - time.second_of_day()
+            } else if self.hours & (1 << time.hour) == 0 {
+                3600 - time.minute * 60 - time.second
+            } else if self.minutes & (1 << time.minute) == 0 {
+                60 - time.second
+            } else if self.seconds & (1 << time.second) == 0 {
+                1
+            } else {
+                return Some(candidate);
+            };
+            candidate += Duration::from_secs(skip as u64);
+        }
+        None
+    }
+
+    /// Gap between the next two fire times after `ts`, i.e. the effective
+    /// probe interval around `ts`.
+    pub fn period_after(&self, ts: SystemTime) -> Option<Duration> {
+        let next = self.next_after(ts)?;
+        let after = self.next_after(next)?;
+        after.duration_since(next).ok()
+    }
+
+    /// Fire times strictly between `from` and `to`, counting at most `limit`.
+    pub fn fires_between(&self, from: SystemTime, to: SystemTime, limit: u32) -> u32 {
+        let mut count = 0;
+        let mut cursor = from;
+        while count < limit {
+            match self.next_after(cursor) {
+                Some(next) if next < to => {
+                    count += 1;
+                    cursor = next;
+                }
+                _ => break,
+            }
+        }
+        count
+    }
+
+    fn matches_day(&self, time: &LocalTime) -> bool {
+        self.days & (1 << time.day) != 0
+            && self.months & (1 << time.month) != 0
+            && self.weekdays & (1 << time.weekday) != 0
+    }
+}
+
+/// Parses one cron field into a bitmask over `min..=max`.
+fn parse_cron_field(
+    field: &'static str,
+    value: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, CronScheduleError> {
+    let invalid = || CronScheduleError::InvalidField {
+        field,
+        value: value.to_string(),
+    };
+    let parse_value = |raw: &str| -> Option<u32> {
+        if let Some(idx) = names.iter().position(|name| name.eq_ignore_ascii_case(raw)) {
+            return Some(min + idx as u32);
+        }
+        raw.parse::<u32>().ok()
+    };
+
+    let mut mask = 0u64;
+    for part in value.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step.parse::<u32>().ok().filter(|step| *step > 0);
+                (range, step.ok_or_else(invalid)?)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start).ok_or_else(invalid)?,
+                parse_value(end).ok_or_else(invalid)?,
+            )
+        } else {
+            let start = parse_value(range).ok_or_else(invalid)?;
+            // `N/S` runs from N to the end of the field, as in Vixie cron
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronScheduleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        const MONTHS: [&str; 12] = [
+            "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+        ];
+        const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+        let fields: Vec<&str> = input.split_whitespace().collect();
+        let (second, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            _ => return Err(CronScheduleError::FieldCount),
+        };
+        let weekdays = parse_cron_field("weekday", rest[4], 0, 7, &WEEKDAYS)?;
+        Ok(Self {
+            expression: fields.join(" "),
+            seconds: parse_cron_field("second", second, 0, 59, &[])?,
+            minutes: parse_cron_field("minute", rest[0], 0, 59, &[])?,
+            hours: parse_cron_field("hour", rest[1], 0, 23, &[])? as u32,
+            days: parse_cron_field("day", rest[2], 1, 31, &[])? as u32,
+            months: parse_cron_field("month", rest[3], 1, 12, &MONTHS)? as u16,
+            // Fold 7 onto Sunday
+            weekdays: ((weekdays | weekdays >> 7) & 0x7f) as u8,
+        })
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = CronScheduleError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(value: CronSchedule) -> Self {
+        value.expression
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ExpectedStatusError {
+    #[error("expected a status such as 204, 2xx or 200-299")]
+    Malformed,
+    #[error("status codes must be between 100 and 599")]
+    OutOfRange,
+}
+
+/// An inclusive range of accepted HTTP status codes, written as `204`,
+/// `2xx` or `200-299`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ExpectedStatus {
+    min: u16,
+    max: u16,
+}
+
+impl ExpectedStatus {
+    pub fn exact(status: u16) -> Self {
+        Self {
+            min: status,
+            max: status,
+        }
+    }
+
+    pub fn matches(&self, status: u16) -> bool {
+        (self.min..=self.max).contains(&status)
+    }
+}
+
+impl FromStr for ExpectedStatus {
+    type Err = ExpectedStatusError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        fn parse_code(value: &str) -> Result<u16, ExpectedStatusError> {
+            let code = value
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| ExpectedStatusError::Malformed)?;
+            if (100..=599).contains(&code) {
+                Ok(code)
+            } else {
+                Err(ExpectedStatusError::OutOfRange)
+            }
+        }
+
+        let input = input.trim().to_ascii_lowercase();
+        if let Some(class) = input.strip_suffix("xx") {
+            let class = class
+                .parse::<u16>()
+                .map_err(|_| ExpectedStatusError::Malformed)?;
+            if !(1..=5).contains(&class) {
+                return Err(ExpectedStatusError::OutOfRange);
+            }
+            return Ok(Self {
+                min: class * 100,
+                max: class * 100 + 99,
+            });
+        }
+        if let Some((start, end)) = input.split_once('-') {
+            let (min, max) = (parse_code(start)?, parse_code(end)?);
+            if min > max {
+                return Err(ExpectedStatusError::Malformed);
+            }
+            return Ok(Self { min, max });
+        }
+        parse_code(&input).map(Self::exact)
+    }
+}
+
+impl TryFrom<String> for ExpectedStatus {
+    type Error = ExpectedStatusError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ExpectedStatus> for String {
+    fn from(value: ExpectedStatus) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for ExpectedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else if self.min.is_multiple_of(100) && self.max == self.min + 99 {
+            write!(f, "{}xx", self.min / 100)
+        } else {
+            write!(f, "{}-{}", self.min, self.max)
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ConnectToError {
+    #[error("expected host:port")]
+    Malformed,
+    #[error("port must be between 1 and 65535")]
+    InvalidPort,
+}
+
+/// Transport override that sends the connection to `host:port` while the
+/// target URL keeps supplying Host and SNI.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ConnectTo {
+    pub host: String,
+    pub port: u16,
+}
+
+impl ConnectTo {
+    /// Entry for curl's `CURLOPT_CONNECT_TO`, applied to every host and port
+    /// the URL (or a redirect) names.
+    pub fn curl_entry(&self) -> String {
+        format!("::{self}")
+    }
+}
+
+impl FromStr for ConnectTo {
+    type Err = ConnectToError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (host, port) = input
+            .trim()
+            .rsplit_once(':')
+            .ok_or(ConnectToError::Malformed)?;
+        let host = host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(host);
+        if host.is_empty() || host.contains(['/', ' ']) || port.is_empty() {
+            return Err(ConnectToError::Malformed);
+        }
+        let port = port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port > 0)
+            .ok_or(ConnectToError::InvalidPort)?;
+        Ok(Self {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl TryFrom<String> for ConnectTo {
+    type Error = ConnectToError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ConnectTo> for String {
+    fn from(value: ConnectTo) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for ConnectTo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
         }
     }
 }
@@ -46,6 +597,59 @@ pub struct TargetConfig {
     pub timeout_breakdown: Option<TimeoutBreakdown>,
     pub profiles: Vec<ProfileConfig>,
     pub sampling: SamplingConfig,
+    #[serde(default)]
+    pub alert_rules: Vec<AlertRule>,
+    /// Availability objective in percent (e.g. 99.5)
+    #[serde(default)]
+    pub slo_target: Option<f64>,
+    /// Latency budget a successful probe must meet to count as good
+    #[serde(default)]
+    pub slo_latency_ms: Option<f64>,
+    /// Random ± spread applied to every probe interval, in percent (0 = off)
+    #[serde(default)]
+    pub jitter_pct: u8,
+    /// Label shown in the target list instead of the host
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Cron-style schedule that replaces the fixed interval when set
+    #[serde(default)]
+    pub schedule: Option<CronSchedule>,
+    /// Unix domain socket the probe connects through instead of TCP
+    #[serde(default)]
+    pub unix_socket: Option<PathBuf>,
+    /// Address the probe connects to in place of the URL's host and port
+    #[serde(default)]
+    pub connect_to: Option<ConnectTo>,
+    /// Free-form labels such as `prod`; the first one groups the target in the list
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Resolve the host inside every probe, as before the per-target DNS
+    /// worker existed, instead of probing against the worker's cached answer
+    #[serde(default)]
+    pub dns_combined: bool,
+    /// How often the DNS worker resolves the host again
+    #[serde(default = "default_dns_refresh")]
+    pub dns_refresh: Duration,
+    /// Skip TLS certificate and host name verification, for staging hosts
+    /// with self-signed certificates. Only ever set by the user
+    #[serde(default)]
+    pub insecure: bool,
+    /// Probe every resolved address on its own as well, so one slow
+    /// backend behind a load balancer stands out
+    #[serde(default)]
+    pub per_ip: bool,
+}
+
+/// Written in place of every header value in a file that may leave the
+/// machine: shared target documents, recordings and saved history.
+pub const SECRET_PLACEHOLDER: &str = "<prompt>";
+
+/// Default period of the per-target DNS worker. The system resolver does not
+/// report record TTLs, so this stands in for one.
+pub const DEFAULT_DNS_REFRESH: Duration = Duration::from_secs(30);
+
+fn default_dns_refresh() -> Duration {
+    DEFAULT_DNS_REFRESH
 }
 
 impl TargetConfig {
@@ -60,7 +664,102 @@ impl TargetConfig {
             timeout_breakdown: None,
             profiles,
             sampling: SamplingConfig::default(),
+            alert_rules: Vec::new(),
+            slo_target: None,
+            slo_latency_ms: None,
+            jitter_pct: 0,
+            name: None,
+            schedule: None,
+            unix_socket: None,
+            connect_to: None,
+            tags: Vec::new(),
+            dns_combined: false,
+            dns_refresh: DEFAULT_DNS_REFRESH,
+            insecure: false,
+            per_ip: false,
+        }
+    }
+
+    /// Name for list display: the explicit label, else the URL host.
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.url.host_str().unwrap_or("?").to_string())
+    }
+
+    /// Copy of this target with fresh target and profile ids, so the copy
+    /// never shares metrics history with the original.
+    pub fn duplicate(&self) -> Self {
+        let mut copy = self.clone();
+        copy.id = Uuid::new_v4();
+        copy.name = Some(format!("{} (copy)", self.display_name()));
+        for profile in &mut copy.profiles {
+            profile.id = Uuid::new_v4();
         }
+        copy
+    }
+
+    /// Copy of this target for a file that may leave the machine, with every
+    /// header value replaced by [`SECRET_PLACEHOLDER`]. Auth is never
+    /// serialized, so it needs no redacting.
+    pub fn redacted(&self) -> Self {
+        let mut copy = self.clone();
+        for profile in &mut copy.profiles {
+            for (_, value) in &mut profile.headers {
+                *value = SecretString::new(SECRET_PLACEHOLDER);
+            }
+        }
+        copy
+    }
+
+    /// Whether probes resolve the URL host; a unix socket bypasses DNS.
+    pub fn resolves_dns(&self) -> bool {
+        self.dns_enabled && self.unix_socket.is_none()
+    }
+
+    /// Time a lookup of the host may take: the DNS budget of the timeout
+    /// breakdown, else the whole probe timeout.
+    pub fn dns_timeout(&self) -> Duration {
+        self.timeout_breakdown
+            .map_or(self.timeout_total, |breakdown| breakdown.dns)
+    }
+
+    /// Whether a DNS worker resolves the host for the probes, which then
+    /// connect to its cached answer without a lookup of their own.
+    pub fn uses_dns_worker(&self) -> bool {
+        self.resolves_dns() && !self.dns_combined
+    }
+
+    /// Whether each resolved address gets streams of its own: asked for,
+    /// and the host is looked up and connected to directly.
+    pub fn probes_per_ip(&self) -> bool {
+        self.per_ip && self.resolves_dns() && self.connect_to.is_none()
+    }
+
+    /// Where probes actually connect when the transport is overridden.
+    pub fn connect_destination(&self) -> Option<String> {
+        match (&self.unix_socket, &self.connect_to) {
+            (Some(path), _) => Some(format!("unix:{}", path.display())),
+            (None, Some(connect_to)) => Some(connect_to.to_string()),
+            (None, None) => None,
+        }
+    }
+
+    /// Probe interval for `profile`, honoring its override.
+    pub fn interval_for(&self, profile: &ProfileConfig) -> Duration {
+        profile.interval_override.unwrap_or(self.interval)
+    }
+
+    /// Start delay for the profile at `index` so that the target's profiles
+    /// are spread evenly across one interval instead of firing together.
+    pub fn stagger_offset(&self, index: usize) -> Duration {
+        let count = self.profiles.len().max(1) as u32;
+        let interval = self
+            .profiles
+            .get(index)
+            .map(|profile| self.interval_for(profile))
+            .unwrap_or(self.interval);
+        interval / count * (index as u32 % count)
     }
 }
 
@@ -73,7 +772,31 @@ pub struct ProfileConfig {
     pub conn_reuse: ConnReusePolicy,
     pub method: ProbeMethod,
     pub max_read_bytes: u32,
+    /// Download speed cap in bytes per second, so large reads do not crowd
+    /// out other probes on a slow link; `None` leaves it unlimited
+    #[serde(default)]
+    pub max_recv_speed: Option<u64>,
+    /// Legend color, as an index into the theme's profile palette; `None`
+    /// colors the profile by its position
+    #[serde(default)]
+    pub color: Option<u8>,
     pub headers: Vec<(String, SecretString)>,
+    /// Probe interval for this profile; falls back to the target interval
+    #[serde(default)]
+    pub interval_override: Option<Duration>,
+    /// Status codes counted as success; when unset any status below 400 passes
+    #[serde(default)]
+    pub expected_status: Option<ExpectedStatus>,
+    /// Substring the first `max_read_bytes` of the body must contain
+    #[serde(default)]
+    pub expect_body_contains: Option<String>,
+    /// Response headers recorded on each sample, matched case-insensitively
+    #[serde(default = "default_capture_headers")]
+    pub capture_headers: Vec<String>,
+    /// Credentials sent with every probe. Never written to the state file or
+    /// a recording; `--auth`/`--bearer` or Settings supply them each run
+    #[serde(skip)]
+    pub auth: Option<AuthConfig>,
 }
 
 impl ProfileConfig {
@@ -93,18 +816,55 @@ impl ProfileConfig {
             conn_reuse,
             method,
             max_read_bytes,
+            max_recv_speed: None,
+            color: None,
             headers: Vec::new(),
+            interval_override: None,
+            expected_status: None,
+            expect_body_contains: None,
+            capture_headers: default_capture_headers(),
+            auth: None,
         }
     }
+
+    /// Whether moving to `other` changes how connections are negotiated, so
+    /// a connection kept from before must not be reused.
+    pub fn changes_connection(&self, other: &ProfileConfig) -> bool {
+        (self.http, self.tls, self.conn_reuse) != (other.http, other.tls, other.conn_reuse)
+    }
+}
+
+/// Headers that tell which server, proxy or CDN cache answered.
+pub fn default_capture_headers() -> Vec<String> {
+    [
+        "server",
+        "via",
+        "age",
+        "x-cache",
+        "cf-cache-status",
+        "content-encoding",
+    ]
+    .map(String::from)
+    .to_vec()
 }
 
 /// Returns the default profiles based on system TLS capabilities.
 /// If `tls13_supported` is true, includes an additional TLS 1.3 profile.
-pub fn default_profiles_for_capabilities(tls13_supported: bool) -> Vec<ProfileConfig> {
+/// Without `h2_supported` the warm profiles use HTTP/1.1, since curl would
+/// quietly downgrade an h2 one anyway.
+pub fn default_profiles_for_capabilities(
+    tls13_supported: bool,
+    h2_supported: bool,
+) -> Vec<ProfileConfig> {
+    let (warm_http, warm_prefix) = if h2_supported {
+        (HttpVersion::H2, "h2")
+    } else {
+        (HttpVersion::H1, "h1")
+    };
     let mut profiles = vec![
         ProfileConfig::new(
-            "h2+tls12+warm",
-            HttpVersion::H2,
+            format!("{warm_prefix}+tls12+warm"),
+            warm_http,
             TlsVersion::Tls12,
             ConnReusePolicy::Warm,
             ProbeMethod::Get,
@@ -125,8 +885,8 @@ pub fn default_profiles_for_capabilities(tls13_supported: bool) -> Vec<ProfileCo
         profiles.insert(
             0,
             ProfileConfig::new(
-                "h2+tls13+warm",
-                HttpVersion::H2,
+                format!("{warm_prefix}+tls13+warm"),
+                warm_http,
                 TlsVersion::Tls13,
                 ConnReusePolicy::Warm,
                 ProbeMethod::Get,
@@ -141,7 +901,7 @@ pub fn default_profiles_for_capabilities(tls13_supported: bool) -> Vec<ProfileCo
 /// Returns the default profiles without TLS capability detection.
 /// For backward compatibility - uses TLS 1.2 only profiles.
 pub fn default_profiles() -> Vec<ProfileConfig> {
-    default_profiles_for_capabilities(false)
+    default_profiles_for_capabilities(false, true)
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -153,22 +913,74 @@ pub struct TimeoutBreakdown {
     pub read: Duration,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Retention policy: a sample is kept while it is among the newest
+/// `max_points_per_window` or younger than `retention`, whichever keeps more.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SamplingConfig {
     pub max_points_per_window: usize,
+    /// Age up to which samples are kept regardless of the point cap
+    #[serde(default = "default_retention")]
+    pub retention: Duration,
     pub histogram: HistogramConfig,
+    #[serde(default)]
+    pub spikes: SpikeConfig,
+    /// Keep the cold first probe of warm profiles in latency statistics
+    #[serde(default)]
+    pub include_priming_samples: bool,
+    /// Samples a profile must take before its statistics are shown and its
+    /// alert rules are evaluated
+    #[serde(default = "default_min_samples_for_stats")]
+    pub min_samples_for_stats: u64,
 }
 
 impl Default for SamplingConfig {
     fn default() -> Self {
         Self {
             max_points_per_window: 1024,
+            retention: default_retention(),
             histogram: HistogramConfig::default(),
+            spikes: SpikeConfig::default(),
+            include_priming_samples: false,
+            min_samples_for_stats: default_min_samples_for_stats(),
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl SamplingConfig {
+    /// Whether samples taken every `interval` are kept for all of `window`.
+    pub fn covers(&self, window: Duration, interval: Duration) -> bool {
+        let points = u32::try_from(self.max_points_per_window).unwrap_or(u32::MAX);
+        self.retention >= window || interval.saturating_mul(points) >= window
+    }
+}
+
+fn default_min_samples_for_stats() -> u64 {
+    5
+}
+
+/// Covers the largest default window.
+fn default_retention() -> Duration {
+    WindowSpec::H1.duration()
+}
+
+/// When a single latency sample counts as a spike: above `p99_multiple` times
+/// the window's p99, or above `absolute_ms` when set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpikeConfig {
+    pub p99_multiple: f64,
+    pub absolute_ms: Option<f64>,
+}
+
+impl Default for SpikeConfig {
+    fn default() -> Self {
+        Self {
+            p99_multiple: 3.0,
+            absolute_ms: Some(2_000.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HistogramConfig {
     pub latency_low_ms: u64,
     pub latency_high_ms: u64,
@@ -185,11 +997,26 @@ impl Default for HistogramConfig {
     }
 }
 
+impl HistogramConfig {
+    /// Raises the upper bound so a probe that runs until `timeout` is still
+    /// recorded exactly. Never narrows the configured range.
+    pub fn cover(&mut self, timeout: Duration) {
+        let timeout_ms = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+        self.latency_high_ms = self.latency_high_ms.max(timeout_ms);
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum HttpVersion {
     H1,
     H2,
+    /// HTTP/1.0 for legacy devices that reject 1.1
+    #[serde(rename = "h1.0")]
+    H1_0,
+    /// Cleartext HTTP/2 without an upgrade (h2c), for plain-http services
+    #[serde(rename = "h2c")]
+    H2PriorKnowledge,
 }
 
 impl fmt::Display for HttpVersion {
@@ -197,6 +1024,8 @@ impl fmt::Display for HttpVersion {
         match self {
             HttpVersion::H1 => f.write_str("h1"),
             HttpVersion::H2 => f.write_str("h2"),
+            HttpVersion::H1_0 => f.write_str("h1.0"),
+            HttpVersion::H2PriorKnowledge => f.write_str("h2c"),
         }
     }
 }
@@ -208,6 +1037,16 @@ pub enum TlsVersion {
     Tls13,
 }
 
+impl TlsVersion {
+    /// Protocol name as TLS libraries report it, e.g. `TLSv1.3`.
+    pub fn protocol_name(self) -> &'static str {
+        match self {
+            TlsVersion::Tls12 => "TLSv1.2",
+            TlsVersion::Tls13 => "TLSv1.3",
+        }
+    }
+}
+
 impl fmt::Display for TlsVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -238,6 +1077,8 @@ impl fmt::Display for ConnReusePolicy {
 pub enum ProbeMethod {
     Head,
     Get,
+    /// Network round trip only: ICMP echo, or TCP connect time as a fallback
+    Ping,
 }
 
 impl fmt::Display for ProbeMethod {
@@ -245,6 +1086,7 @@ impl fmt::Display for ProbeMethod {
         match self {
             ProbeMethod::Head => f.write_str("head"),
             ProbeMethod::Get => f.write_str("get"),
+            ProbeMethod::Ping => f.write_str("ping"),
         }
     }
 }
@@ -277,31 +1119,222 @@ impl fmt::Display for EbpfMode {
     }
 }
 
+/// Color theme of the UI. `Auto` picks one of the others at startup.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Auto,
+    Dark,
+    Light,
+    /// No colors at all, for `NO_COLOR` and monochrome terminals
+    Mono,
+}
+
+impl ThemeName {
+    pub fn parse_cli(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ThemeName::Auto),
+            "dark" => Some(ThemeName::Dark),
+            "light" => Some(ThemeName::Light),
+            "mono" => Some(ThemeName::Mono),
+            _ => None,
+        }
+    }
+
+    /// Next theme in the Settings cycle.
+    pub fn next(self) -> Self {
+        match self {
+            ThemeName::Auto => ThemeName::Dark,
+            ThemeName::Dark => ThemeName::Light,
+            ThemeName::Light => ThemeName::Mono,
+            ThemeName::Mono => ThemeName::Auto,
+        }
+    }
+
+    /// The theme `Auto` stands for, read from the environment through `var`:
+    /// mono when `NO_COLOR` is set, light when `COLORFGBG` names a light
+    /// background, dark otherwise. Other themes are returned as they are.
+    pub fn resolve(self, var: impl Fn(&str) -> Option<String>) -> Self {
+        if self != ThemeName::Auto {
+            return self;
+        }
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ThemeName::Mono;
+        }
+        // "15;0" is white on black; the background is the last field
+        let background = var("COLORFGBG").and_then(|value| {
+            value
+                .rsplit(';')
+                .next()
+                .and_then(|field| field.parse::<u8>().ok())
+        });
+        match background {
+            Some(7 | 15) => ThemeName::Light,
+            _ => ThemeName::Dark,
+        }
+    }
+}
+
+impl fmt::Display for ThemeName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeName::Auto => f.write_str("auto"),
+            ThemeName::Dark => f.write_str("dark"),
+            ThemeName::Light => f.write_str("light"),
+            ThemeName::Mono => f.write_str("mono"),
+        }
+    }
+}
+
+/// How workers probe a target.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeBackendKind {
+    /// Full HTTP requests through libcurl
+    #[default]
+    Curl,
+    /// DNS lookup, TCP and TLS handshakes only; no request is sent
+    TcpConnect,
+}
+
+impl ProbeBackendKind {
+    pub fn parse_cli(value: &str) -> Option<Self> {
+        match value {
+            "curl" => Some(ProbeBackendKind::Curl),
+            "tcp-connect" | "tcp_connect" => Some(ProbeBackendKind::TcpConnect),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProbeBackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeBackendKind::Curl => f.write_str("curl"),
+            ProbeBackendKind::TcpConnect => f.write_str("tcp-connect"),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum WindowSpecError {
+    #[error("expected a duration such as 90s, 15m, 6h or 1d")]
+    Malformed,
+    #[error("window must be longer than zero")]
+    Zero,
+}
+
+/// Time span aggregates are computed over. The presets keep their config
+/// names (`m1`, `m5`, `m15`, `h1`); any other span is written as its label.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(try_from = "String", into = "String")]
 pub enum WindowSpec {
     M1,
     M5,
     M15,
     H1,
+    Custom(Duration),
 }
 
 impl WindowSpec {
+    const PRESETS: [WindowSpec; 4] = [
+        WindowSpec::M1,
+        WindowSpec::M5,
+        WindowSpec::M15,
+        WindowSpec::H1,
+    ];
+
+    /// The preset of that length if there is one, so `60m` and `h1` compare equal.
+    pub fn from_duration(duration: Duration) -> Self {
+        Self::PRESETS
+            .into_iter()
+            .find(|preset| preset.duration() == duration)
+            .unwrap_or(WindowSpec::Custom(duration))
+    }
+
     pub fn duration(self) -> Duration {
         match self {
             WindowSpec::M1 => Duration::from_secs(60),
             WindowSpec::M5 => Duration::from_secs(5 * 60),
             WindowSpec::M15 => Duration::from_secs(15 * 60),
             WindowSpec::H1 => Duration::from_secs(60 * 60),
+            WindowSpec::Custom(duration) => duration,
         }
     }
 
-    pub fn label(self) -> &'static str {
+    pub fn label(self) -> String {
         match self {
-            WindowSpec::M1 => "1m",
-            WindowSpec::M5 => "5m",
-            WindowSpec::M15 => "15m",
-            WindowSpec::H1 => "60m",
+            WindowSpec::M1 => "1m".to_string(),
+            WindowSpec::M5 => "5m".to_string(),
+            WindowSpec::M15 => "15m".to_string(),
+            WindowSpec::H1 => "60m".to_string(),
+            WindowSpec::Custom(duration) => {
+                let secs = duration.as_secs();
+                if secs > 0 && secs.is_multiple_of(3600) {
+                    format!("{}h", secs / 3600)
+                } else if secs > 0 && secs.is_multiple_of(60) {
+                    format!("{}m", secs / 60)
+                } else {
+                    format!("{secs}s")
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for WindowSpec {
+    type Err = WindowSpecError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim().to_ascii_lowercase();
+        match input.as_str() {
+            "m1" => return Ok(WindowSpec::M1),
+            "m5" => return Ok(WindowSpec::M5),
+            "m15" => return Ok(WindowSpec::M15),
+            "h1" => return Ok(WindowSpec::H1),
+            _ => {}
+        }
+        let split = input
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(input.len());
+        let (value, unit) = input.split_at(split);
+        let value = value
+            .parse::<u64>()
+            .map_err(|_| WindowSpecError::Malformed)?;
+        let unit_secs = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86_400,
+            _ => return Err(WindowSpecError::Malformed),
+        };
+        let secs = value
+            .checked_mul(unit_secs)
+            .ok_or(WindowSpecError::Malformed)?;
+        if secs == 0 {
+            return Err(WindowSpecError::Zero);
+        }
+        Ok(Self::from_duration(Duration::from_secs(secs)))
+    }
+}
+
+impl TryFrom<String> for WindowSpec {
+    type Error = WindowSpecError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<WindowSpec> for String {
+    fn from(value: WindowSpec) -> Self {
+        match value {
+            WindowSpec::M1 => "m1".to_string(),
+            WindowSpec::M5 => "m5".to_string(),
+            WindowSpec::M15 => "m15".to_string(),
+            WindowSpec::H1 => "h1".to_string(),
+            WindowSpec::Custom(_) => value.label(),
         }
     }
 }
@@ -350,6 +1383,50 @@ impl From<&str> for SecretString {
     }
 }
 
+/// Credentials for a profile. Basic auth goes through curl's own
+/// username/password handling; a bearer token is sent as a header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuthConfig {
+    Basic {
+        user: String,
+        password: SecretString,
+    },
+    Bearer {
+        token: SecretString,
+    },
+}
+
+impl AuthConfig {
+    /// Parses `user:password`; the password may itself contain colons.
+    pub fn basic(credentials: &str) -> Option<Self> {
+        let (user, password) = credentials.split_once(':')?;
+        if user.is_empty() {
+            return None;
+        }
+        Some(Self::Basic {
+            user: user.to_string(),
+            password: password.into(),
+        })
+    }
+
+    pub fn bearer(token: &str) -> Option<Self> {
+        let token = token.trim();
+        (!token.is_empty()).then(|| Self::Bearer {
+            token: token.into(),
+        })
+    }
+}
+
+/// Shows the scheme and user, never the secret.
+impl fmt::Display for AuthConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { user, password } => write!(f, "basic {user}:{password}"),
+            Self::Bearer { token } => write!(f, "bearer {token}"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,6 +1446,252 @@ mod tests {
         assert_eq!(WindowSpec::M15.label(), "15m");
         assert_eq!(WindowSpec::H1.label(), "60m");
         assert_eq!(WindowSpec::H1.to_string(), "60m");
+        assert_eq!(
+            WindowSpec::Custom(Duration::from_secs(6 * 3600)).label(),
+            "6h"
+        );
+        assert_eq!(WindowSpec::Custom(Duration::from_secs(90)).label(), "90s");
+    }
+
+    #[test]
+    fn window_spec_parses_durations_and_keeps_preset_names() {
+        assert_eq!("15m".parse::<WindowSpec>(), Ok(WindowSpec::M15));
+        assert_eq!("60m".parse::<WindowSpec>(), Ok(WindowSpec::H1));
+        assert_eq!(
+            "1d".parse::<WindowSpec>(),
+            Ok(WindowSpec::Custom(Duration::from_secs(24 * 3600)))
+        );
+        assert_eq!("0h".parse::<WindowSpec>(), Err(WindowSpecError::Zero));
+        assert_eq!(
+            "soon".parse::<WindowSpec>(),
+            Err(WindowSpecError::Malformed)
+        );
+
+        let windows = vec![WindowSpec::M1, WindowSpec::H1, "6h".parse().unwrap()];
+        let json = serde_json::to_string(&windows).unwrap();
+        assert_eq!(json, r#"["m1","h1","6h"]"#);
+        let parsed: Vec<WindowSpec> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, windows);
+    }
+
+    #[test]
+    fn default_profiles_avoid_h2_without_library_support() {
+        let names = |profiles: Vec<ProfileConfig>| {
+            profiles
+                .into_iter()
+                .map(|profile| (profile.name, profile.http))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(default_profiles_for_capabilities(true, false)),
+            vec![
+                ("h1+tls13+warm".to_string(), HttpVersion::H1),
+                ("h1+tls12+warm".to_string(), HttpVersion::H1),
+                ("h1+tls12+cold".to_string(), HttpVersion::H1),
+            ]
+        );
+        assert_eq!(
+            names(default_profiles_for_capabilities(false, true))[0],
+            ("h2+tls12+warm".to_string(), HttpVersion::H2)
+        );
+    }
+
+    #[test]
+    fn stagger_offset_spreads_profiles_across_interval() {
+        let url = Url::parse("https://example.com").unwrap();
+        let mut target = TargetConfig::new(url, default_profiles());
+        target.interval = Duration::from_secs(6);
+        assert_eq!(target.stagger_offset(0), Duration::ZERO);
+        assert_eq!(target.stagger_offset(1), Duration::from_secs(3));
+
+        target.profiles[1].interval_override = Some(Duration::from_secs(2));
+        assert_eq!(
+            target.interval_for(&target.profiles[1]),
+            Duration::from_secs(2)
+        );
+        assert_eq!(target.stagger_offset(1), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn quiet_hours_parse_and_wrap_past_midnight() {
+        let quiet: QuietHours = "22:00-07:00".parse().expect("quiet hours");
+        assert_eq!(quiet.to_string(), "22:00-07:00");
+        assert!(quiet.contains(23 * 60));
+        assert!(quiet.contains(6 * 60 + 59));
+        assert!(!quiet.contains(7 * 60));
+        assert!(!quiet.contains(12 * 60));
+
+        let daytime: QuietHours = "9:30-17:00".parse().expect("quiet hours");
+        assert!(daytime.contains(9 * 60 + 30));
+        assert!(!daytime.contains(17 * 60));
+    }
+
+    #[test]
+    fn cron_schedule_parses_fields_names_and_steps() {
+        let schedule: CronSchedule = "*/15 * 9-17 * * MON-FRI".parse().expect("schedule");
+        assert_eq!(schedule.seconds, 1 | 1 << 15 | 1 << 30 | 1 << 45);
+        assert_eq!(
+            schedule.hours,
+            (9..=17).fold(0, |mask, hour| mask | 1 << hour)
+        );
+        assert_eq!(schedule.weekdays, 0b0111110);
+        assert_eq!(schedule.to_string(), "*/15 * 9-17 * * MON-FRI");
+
+        let five_field: CronSchedule = "30 2 1 jan,jul 7".parse().expect("schedule");
+        assert_eq!(five_field.seconds, 1);
+        assert_eq!(five_field.months, 1 << 1 | 1 << 7);
+        assert_eq!(five_field.weekdays, 1);
+
+        assert_eq!(
+            "* * *".parse::<CronSchedule>(),
+            Err(CronScheduleError::FieldCount)
+        );
+        assert!(matches!(
+            "0 24 * * *".parse::<CronSchedule>(),
+            Err(CronScheduleError::InvalidField { field: "hour", .. })
+        ));
+        assert!(matches!(
+            "*/0 * * * *".parse::<CronSchedule>(),
+            Err(CronScheduleError::InvalidField {
+                field: "minute",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn cron_schedule_finds_next_matching_second() {
+        // Every field matches every time of day, so only the seconds constrain it
+        let schedule: CronSchedule = "*/10 * * * * *".parse().expect("schedule");
+        let base = UNIX_EPOCH + Duration::from_secs(1_700_000_003);
+        let next = schedule.next_after(base).expect("next");
+        assert_eq!(next, UNIX_EPOCH + Duration::from_secs(1_700_000_010));
+        assert_eq!(
+            schedule.next_after(next),
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_020))
+        );
+        assert!(schedule.is_active_at(base));
+        assert_eq!(schedule.period_after(base), Some(Duration::from_secs(10)));
+        // Fires at :10 ... :50, the one at :60 is not strictly before the end
+        let minute_later = UNIX_EPOCH + Duration::from_secs(1_700_000_060);
+        assert_eq!(schedule.fires_between(base, minute_later, 100), 5);
+        assert_eq!(schedule.fires_between(base, minute_later, 2), 2);
+
+        let daily: CronSchedule = "0 0 * * *".parse().expect("schedule");
+        let midnight = daily.next_after(base).expect("next");
+        let gap = midnight.duration_since(base).expect("after base");
+        assert!(gap <= Duration::from_secs(86_400 + 3600));
+        assert_eq!(local_time(midnight).second_of_day(), 0);
+
+        let never: CronSchedule = "0 0 31 2 *".parse().expect("schedule");
+        assert_eq!(never.next_after(base), None);
+    }
+
+    #[test]
+    fn cron_schedule_serializes_as_string() {
+        let schedule: CronSchedule = "0 */5 * * * *".parse().expect("schedule");
+        let json = serde_json::to_string(&schedule).expect("serialize");
+        assert_eq!(json, "\"0 */5 * * * *\"");
+        assert_eq!(
+            serde_json::from_str::<CronSchedule>(&json).expect("deserialize"),
+            schedule
+        );
+    }
+
+    #[test]
+    fn expected_status_accepts_codes_classes_and_ranges() {
+        let exact: ExpectedStatus = "204".parse().expect("status");
+        assert!(exact.matches(204));
+        assert!(!exact.matches(200));
+        assert_eq!(exact.to_string(), "204");
+
+        let class: ExpectedStatus = "2XX".parse().expect("status");
+        assert!(class.matches(200) && class.matches(299));
+        assert!(!class.matches(301));
+        assert_eq!(class.to_string(), "2xx");
+
+        let range: ExpectedStatus = "200-302".parse().expect("status");
+        assert!(range.matches(302));
+        assert_eq!(range.to_string(), "200-302");
+
+        assert_eq!(
+            "ok".parse::<ExpectedStatus>(),
+            Err(ExpectedStatusError::Malformed)
+        );
+        assert_eq!(
+            "700".parse::<ExpectedStatus>(),
+            Err(ExpectedStatusError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn connect_to_parses_hosts_and_brackets_ipv6() {
+        let plain: ConnectTo = "backend.local:8080".parse().expect("connect_to");
+        assert_eq!(plain.host, "backend.local");
+        assert_eq!(plain.port, 8080);
+        assert_eq!(plain.curl_entry(), "::backend.local:8080");
+
+        let v6: ConnectTo = "[::1]:443".parse().expect("connect_to");
+        assert_eq!(v6.host, "::1");
+        assert_eq!(v6.to_string(), "[::1]:443");
+
+        assert_eq!(
+            "backend".parse::<ConnectTo>(),
+            Err(ConnectToError::Malformed)
+        );
+        assert_eq!(
+            "backend:0".parse::<ConnectTo>(),
+            Err(ConnectToError::InvalidPort)
+        );
+    }
+
+    #[test]
+    fn unix_socket_suppresses_dns_and_wins_destination() {
+        let url = Url::parse("http://api.internal/health").expect("url");
+        let mut config = TargetConfig::new(url, Vec::new());
+        assert!(config.resolves_dns());
+        assert_eq!(config.connect_destination(), None);
+
+        config.connect_to = Some("10.0.0.5:8080".parse().expect("connect_to"));
+        assert!(config.resolves_dns());
+        assert_eq!(
+            config.connect_destination().as_deref(),
+            Some("10.0.0.5:8080")
+        );
+
+        config.unix_socket = Some(PathBuf::from("/run/app.sock"));
+        assert!(!config.resolves_dns());
+        assert_eq!(
+            config.connect_destination().as_deref(),
+            Some("unix:/run/app.sock")
+        );
+    }
+
+    #[test]
+    fn quiet_hours_reject_invalid_input() {
+        assert_eq!(
+            "22:00".parse::<QuietHours>(),
+            Err(QuietHoursError::Malformed)
+        );
+        assert_eq!(
+            "25:00-07:00".parse::<QuietHours>(),
+            Err(QuietHoursError::Malformed)
+        );
+        assert_eq!(
+            "07:00-07:00".parse::<QuietHours>(),
+            Err(QuietHoursError::Empty)
+        );
+    }
+
+    #[test]
+    fn quiet_hours_serialize_as_string() {
+        let quiet: QuietHours = "22:00-07:00".parse().expect("quiet hours");
+        let json = serde_json::to_string(&quiet).expect("serialize");
+        assert_eq!(json, "\"22:00-07:00\"");
+        assert_eq!(
+            serde_json::from_str::<QuietHours>(&json).expect("deserialize"),
+            quiet
+        );
     }
 
     #[test]
@@ -382,4 +1705,63 @@ mod tests {
         assert!(!display_text.contains("token-value"));
         assert_eq!(display_text, "[REDACTED]");
     }
+
+    #[test]
+    fn auth_is_redacted_and_never_serialized() {
+        let auth = AuthConfig::basic("alice:s3cret:x").expect("basic");
+        assert_eq!(
+            auth,
+            AuthConfig::Basic {
+                user: "alice".to_string(),
+                password: "s3cret:x".into()
+            }
+        );
+        assert_eq!(auth.to_string(), "basic alice:[REDACTED]");
+        assert!(!format!("{auth:?}").contains("s3cret"));
+        assert!(AuthConfig::basic("no-colon").is_none());
+        assert!(AuthConfig::basic(":pw").is_none());
+        assert!(AuthConfig::bearer("  ").is_none());
+
+        let mut profile = default_profiles().remove(0);
+        profile.auth = AuthConfig::bearer("tok-123");
+        let json = serde_json::to_string(&profile).expect("serialize");
+        assert!(!json.contains("tok-123"), "{json}");
+        assert!(!json.contains("auth"), "{json}");
+        let restored: ProfileConfig = serde_json::from_str(&json).expect("deserialize");
+        assert!(restored.auth.is_none());
+    }
+
+    #[test]
+    fn auto_theme_follows_no_color_then_colorfgbg() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(ThemeName::Auto.resolve(env(&[])), ThemeName::Dark);
+        assert_eq!(
+            ThemeName::Auto.resolve(env(&[("COLORFGBG", "0;15")])),
+            ThemeName::Light
+        );
+        assert_eq!(
+            ThemeName::Auto.resolve(env(&[("COLORFGBG", "15;default;0")])),
+            ThemeName::Dark
+        );
+        assert_eq!(
+            ThemeName::Auto.resolve(env(&[("NO_COLOR", "1"), ("COLORFGBG", "0;15")])),
+            ThemeName::Mono
+        );
+        // An empty NO_COLOR does not count, and a chosen theme wins over it
+        assert_eq!(
+            ThemeName::Auto.resolve(env(&[("NO_COLOR", "")])),
+            ThemeName::Dark
+        );
+        assert_eq!(
+            ThemeName::Light.resolve(env(&[("NO_COLOR", "1")])),
+            ThemeName::Light
+        );
+    }
 }
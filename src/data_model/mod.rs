@@ -1 +1,4 @@
+mod schema;
 pub mod settings;
+
+pub use schema::{SCHEMA_VERSION, SchemaError, SchemaVersion, Versioned};
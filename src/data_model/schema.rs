@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Version of the serialized sample and config types, stamped on recordings,
+/// the state file, the status dump and `--once --json` output. A minor bump
+/// only adds optional fields; readers refuse majors newer than their own.
+/// Shared targets documents carry their own `SHARED_TARGETS_VERSION`.
+pub const SCHEMA_VERSION: SchemaVersion = SchemaVersion { major: 1, minor: 0 };
+
+/// `major.minor` version of the serialized types, written as a string such as `1.0`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SchemaVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl SchemaVersion {
+    /// Version assumed for files written before the schema was versioned.
+    pub const LEGACY: SchemaVersion = SchemaVersion { major: 1, minor: 0 };
+
+    /// [`Self::LEGACY`], for `#[serde(default)]` on version fields.
+    pub(crate) fn legacy() -> Self {
+        Self::LEGACY
+    }
+
+    /// Whether this build can read a file written with `self`.
+    pub fn check_compatible(self) -> Result<(), SchemaError> {
+        if self.major > SCHEMA_VERSION.major {
+            return Err(SchemaError::Newer {
+                found: self,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SchemaError {
+    #[error("expected a schema version such as 1.0")]
+    Malformed,
+    #[error(
+        "written with schema {found}, but this build reads up to {}.x; upgrade httpulse to read it",
+        supported.major
+    )]
+    Newer {
+        found: SchemaVersion,
+        supported: SchemaVersion,
+    },
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = SchemaError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (major, minor) = input.trim().split_once('.').ok_or(SchemaError::Malformed)?;
+        let part = |value: &str| value.parse::<u32>().map_err(|_| SchemaError::Malformed);
+        Ok(Self {
+            major: part(major)?,
+            minor: part(minor)?,
+        })
+    }
+}
+
+impl TryFrom<String> for SchemaVersion {
+    type Error = SchemaError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<SchemaVersion> for String {
+    fn from(value: SchemaVersion) -> Self {
+        value.to_string()
+    }
+}
+
+/// Just the `schema_version` of a document, read before the rest so a file
+/// from a newer major is reported as such rather than as whichever field
+/// failed to parse. Files without one predate versioning.
+#[derive(Debug, Deserialize)]
+pub struct Versioned {
+    #[serde(default = "SchemaVersion::legacy")]
+    pub schema_version: SchemaVersion,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_round_trip_as_strings_and_newer_majors_are_refused() {
+        let json = serde_json::to_string(&SCHEMA_VERSION).unwrap();
+        assert_eq!(
+            json,
+            format!("\"{}.{}\"", SCHEMA_VERSION.major, SCHEMA_VERSION.minor)
+        );
+        assert_eq!(
+            serde_json::from_str::<SchemaVersion>(&json).unwrap(),
+            SCHEMA_VERSION
+        );
+        assert_eq!("1".parse::<SchemaVersion>(), Err(SchemaError::Malformed));
+
+        let newer_minor = SchemaVersion {
+            minor: SCHEMA_VERSION.minor + 1,
+            ..SCHEMA_VERSION
+        };
+        assert_eq!(newer_minor.check_compatible(), Ok(()));
+        let newer_major = SchemaVersion {
+            major: SCHEMA_VERSION.major + 1,
+            minor: 0,
+        };
+        let err = newer_major.check_compatible().unwrap_err();
+        assert!(err.to_string().contains("upgrade httpulse"), "{err}");
+
+        let unversioned: Versioned = serde_json::from_str(r#"{"targets": []}"#).unwrap();
+        assert_eq!(unversioned.schema_version, SchemaVersion::LEGACY);
+    }
+}
@@ -1,9 +1,53 @@
-use crate::config::EbpfMode;
+use crate::config::{AuthConfig, EbpfMode, ProbeBackendKind, ThemeName, WindowSpec};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub targets: Vec<String>,
+    /// Whether `targets` came from `--target` rather than the built-in default
+    pub targets_from_cli: bool,
     pub refresh_hz: u16,
+    /// Replaces the configured windows when given
+    pub windows: Option<Vec<WindowSpec>>,
     pub ebpf_mode: EbpfMode,
+    /// Replaces the saved probe backend when given
+    pub backend: Option<ProbeBackendKind>,
+    /// Replaces the saved theme when given
+    pub theme: Option<ThemeName>,
+    pub log_file: Option<PathBuf>,
+    /// `RUST_LOG`-style directives applied to the log file
+    pub log_filter: String,
+    /// Session file every sample is written to
+    pub record: Option<PathBuf>,
+    /// Session file replayed instead of probing
+    pub replay: Option<PathBuf>,
+    /// Multiplier applied to the recorded pace when replaying
+    pub replay_speed: f64,
+    /// Seed of the synthetic sample source used instead of the network
+    pub demo: Option<u64>,
+    /// Invalid `--target` values left out under `--ignore-invalid-targets`,
+    /// each with the reason
+    pub skipped_targets: Vec<String>,
+    /// Probe every target once before starting the UI
+    pub preflight: bool,
+    /// Probe every target once, print a report and exit instead of the UI
+    pub once: bool,
+    /// Print the `once` report as JSON instead of a table
+    pub json: bool,
+    /// File of targets added on top of the others
+    pub targets_file: Option<PathBuf>,
+    /// Import targets-file URLs that are already probed
+    pub allow_duplicates: bool,
+    /// Shared targets document merged at startup
+    pub import_targets: Option<PathBuf>,
+    /// Where to write the shared targets document before exiting
+    pub export_targets: Option<PathBuf>,
+    /// Credentials for the `--target` and targets-file targets; never serialized
+    #[serde(skip)]
+    pub auth: Option<AuthConfig>,
+    /// Skip certificate verification for the `--target` targets
+    pub insecure: bool,
+    /// Reload the samples saved on the last quit
+    pub restore: bool,
 }
@@ -0,0 +1,14 @@
+mod notify;
+mod rule;
+mod state;
+
+pub use notify::{
+    DEFAULT_NOTIFY_CONSECUTIVE, NOTIFY_COOLDOWN, NOTIFY_EVAL_INTERVAL, Notification, NotifyRule,
+    NotifyRuleError, NotifyState, NotifyTransition, deliver_notification,
+};
+
+pub use rule::{
+    AlertComparison, AlertRule, AlertRuleError, AlertStat, DEFAULT_CLEAR_AFTER, format_alert_rules,
+    parse_alert_rule, parse_alert_rules,
+};
+pub use state::{ALERT_LOG_CAPACITY, AlertEvent, AlertLog, AlertRuleState, AlertTransition};
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::debug;
+
+/// Minimum time between evaluations, so `consecutive` counts seconds rather
+/// than UI frames.
+pub const NOTIFY_EVAL_INTERVAL: Duration = Duration::from_secs(1);
+/// Minimum time between two notifications for the same target.
+pub const NOTIFY_COOLDOWN: Duration = Duration::from_secs(60);
+/// Evaluations in a row used when a rule does not give a count.
+pub const DEFAULT_NOTIFY_CONSECUTIVE: u32 = 3;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum NotifyRuleError {
+    #[error("expected <loss%> [x<evaluations>], e.g. 20% x3")]
+    Malformed,
+    #[error("loss threshold must be between 0 and 100%")]
+    ThresholdOutOfRange,
+    #[error("evaluation count must be at least 1")]
+    ZeroCount,
+}
+
+/// Notify when a target's probe loss over the last minute stays above
+/// `loss_pct` for `consecutive` evaluations in a row, e.g. `20% x3`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct NotifyRule {
+    pub loss_pct: f64,
+    pub consecutive: u32,
+}
+
+impl NotifyRule {
+    fn is_breached(&self, loss_rate: f64) -> bool {
+        loss_rate * 100.0 > self.loss_pct
+    }
+}
+
+impl FromStr for NotifyRule {
+    type Err = NotifyRuleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let compact: String = input.split_whitespace().collect();
+        let compact = compact.to_ascii_lowercase();
+        let (threshold, count) = match compact.split_once('x') {
+            Some((threshold, count)) => (threshold, Some(count)),
+            None => (compact.as_str(), None),
+        };
+        let loss_pct = threshold
+            .strip_suffix('%')
+            .unwrap_or(threshold)
+            .parse::<f64>()
+            .map_err(|_| NotifyRuleError::Malformed)?;
+        if !(0.0..100.0).contains(&loss_pct) {
+            return Err(NotifyRuleError::ThresholdOutOfRange);
+        }
+        let consecutive = match count {
+            Some(count) => count.parse().map_err(|_| NotifyRuleError::Malformed)?,
+            None => DEFAULT_NOTIFY_CONSECUTIVE,
+        };
+        if consecutive == 0 {
+            return Err(NotifyRuleError::ZeroCount);
+        }
+        Ok(Self {
+            loss_pct,
+            consecutive,
+        })
+    }
+}
+
+impl TryFrom<String> for NotifyRule {
+    type Error = NotifyRuleError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<NotifyRule> for String {
+    fn from(value: NotifyRule) -> Self {
+        value.to_string()
+    }
+}
+
+impl fmt::Display for NotifyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% x{}", self.loss_pct, self.consecutive)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NotifyTransition {
+    Down,
+    Recovered,
+}
+
+/// Debounce state of one target's notifications.
+///
+/// Both directions need `consecutive` evaluations in a row, and no two
+/// notifications for the target go out within [`NOTIFY_COOLDOWN`]; a change
+/// that is still true when the cooldown ends is sent then.
+#[derive(Clone, Debug, Default)]
+pub struct NotifyState {
+    /// An outage notification went out and no recovery has followed yet
+    pub down: bool,
+    failing_streak: u32,
+    healthy_streak: u32,
+    last_sent: Option<Instant>,
+}
+
+impl NotifyState {
+    /// Feeds one loss-rate observation (0..=1). Missing data leaves the state untouched.
+    pub fn evaluate(
+        &mut self,
+        rule: &NotifyRule,
+        loss_rate: Option<f64>,
+        now: Instant,
+    ) -> Option<NotifyTransition> {
+        let loss_rate = loss_rate?;
+        if rule.is_breached(loss_rate) {
+            self.failing_streak += 1;
+            self.healthy_streak = 0;
+        } else {
+            self.healthy_streak += 1;
+            self.failing_streak = 0;
+        }
+
+        let cooled = self
+            .last_sent
+            .is_none_or(|sent| now.saturating_duration_since(sent) >= NOTIFY_COOLDOWN);
+        let transition = if !self.down && self.failing_streak >= rule.consecutive {
+            NotifyTransition::Down
+        } else if self.down && self.healthy_streak >= rule.consecutive {
+            NotifyTransition::Recovered
+        } else {
+            return None;
+        };
+        if !cooled {
+            return None;
+        }
+        self.down = transition == NotifyTransition::Down;
+        self.last_sent = Some(now);
+        Some(transition)
+    }
+}
+
+/// Message for one notification.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+}
+
+/// Rings the terminal bell and, where supported, shows a desktop
+/// notification. Delivery failures are only logged.
+pub fn deliver_notification(notification: Notification) {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    // Talking to the notification daemon can block; keep it off the UI thread
+    std::thread::spawn(move || {
+        if let Err(err) = notify_rust::Notification::new()
+            .appname("httpulse")
+            .summary(&notification.summary)
+            .body(&notification.body)
+            .show()
+        {
+            debug!("desktop notification failed: {err}");
+        }
+    });
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    debug!(
+        "desktop notifications unsupported: {}",
+        notification.summary
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(spec: &str) -> NotifyRule {
+        spec.parse().expect("rule")
+    }
+
+    #[test]
+    fn rule_parses_threshold_and_count() {
+        assert_eq!(
+            rule("20% x5"),
+            NotifyRule {
+                loss_pct: 20.0,
+                consecutive: 5
+            }
+        );
+        assert_eq!(rule("50").consecutive, DEFAULT_NOTIFY_CONSECUTIVE);
+        assert_eq!(rule("12.5%x2").to_string(), "12.5% x2");
+        assert_eq!(
+            "120%".parse::<NotifyRule>(),
+            Err(NotifyRuleError::ThresholdOutOfRange)
+        );
+        assert_eq!(
+            "20% x0".parse::<NotifyRule>(),
+            Err(NotifyRuleError::ZeroCount)
+        );
+        assert_eq!(
+            "lots".parse::<NotifyRule>(),
+            Err(NotifyRuleError::Malformed)
+        );
+    }
+
+    #[test]
+    fn notifies_after_streaks_and_once_per_direction() {
+        let rule = rule("50% x2");
+        let mut state = NotifyState::default();
+        let start = Instant::now();
+
+        assert_eq!(state.evaluate(&rule, Some(1.0), start), None);
+        assert_eq!(
+            state.evaluate(&rule, Some(1.0), start),
+            Some(NotifyTransition::Down)
+        );
+        assert_eq!(state.evaluate(&rule, Some(1.0), start), None);
+        assert_eq!(state.evaluate(&rule, None, start), None);
+
+        let later = start + NOTIFY_COOLDOWN;
+        assert_eq!(state.evaluate(&rule, Some(0.0), later), None);
+        assert_eq!(
+            state.evaluate(&rule, Some(0.0), later),
+            Some(NotifyTransition::Recovered)
+        );
+        assert_eq!(state.evaluate(&rule, Some(0.0), later), None);
+        assert!(!state.down);
+    }
+
+    #[test]
+    fn flapping_within_cooldown_defers_notifications() {
+        let rule = rule("50% x1");
+        let mut state = NotifyState::default();
+        let start = Instant::now();
+
+        assert_eq!(
+            state.evaluate(&rule, Some(1.0), start),
+            Some(NotifyTransition::Down)
+        );
+        let soon = start + Duration::from_secs(5);
+        assert_eq!(state.evaluate(&rule, Some(0.0), soon), None);
+        assert_eq!(state.evaluate(&rule, Some(1.0), soon), None);
+        assert_eq!(state.evaluate(&rule, Some(0.0), soon), None);
+        assert!(state.down);
+
+        // Still recovered once the cooldown ends: the recovery goes out then
+        assert_eq!(
+            state.evaluate(&rule, Some(0.0), start + NOTIFY_COOLDOWN),
+            Some(NotifyTransition::Recovered)
+        );
+    }
+}
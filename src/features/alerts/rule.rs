@@ -0,0 +1,322 @@
+use crate::metrics::{MetricKind, MetricStats};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Consecutive healthy evaluations (one per UI tick) required before a firing rule clears.
+pub const DEFAULT_CLEAR_AFTER: u32 = 20;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertStat {
+    P50,
+    P90,
+    P99,
+    Mean,
+}
+
+impl AlertStat {
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertStat::P50 => "p50",
+            AlertStat::P90 => "p90",
+            AlertStat::P99 => "p99",
+            AlertStat::Mean => "mean",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "p50" | "median" => Some(AlertStat::P50),
+            "p90" => Some(AlertStat::P90),
+            "p99" => Some(AlertStat::P99),
+            "mean" | "avg" => Some(AlertStat::Mean),
+            _ => None,
+        }
+    }
+
+    pub fn select(self, stats: &MetricStats) -> Option<f64> {
+        match self {
+            AlertStat::P50 => stats.p50,
+            AlertStat::P90 => stats.p90,
+            AlertStat::P99 => stats.p99,
+            AlertStat::Mean => stats.mean,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertComparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl AlertComparison {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            AlertComparison::Above => ">",
+            AlertComparison::AtLeast => ">=",
+            AlertComparison::Below => "<",
+            AlertComparison::AtMost => "<=",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            ">" => Some(AlertComparison::Above),
+            ">=" => Some(AlertComparison::AtLeast),
+            "<" => Some(AlertComparison::Below),
+            "<=" => Some(AlertComparison::AtMost),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AlertRuleError {
+    #[error("expected `<metric> [stat] <op> <threshold>`")]
+    Malformed,
+    #[error("unknown metric `{0}`")]
+    UnknownMetric(String),
+    #[error("unknown stat `{0}` (use p50/p90/p99/mean)")]
+    UnknownStat(String),
+    #[error("unknown comparison `{0}` (use >, >=, < or <=)")]
+    UnknownComparison(String),
+    #[error("invalid threshold `{0}`")]
+    InvalidThreshold(String),
+}
+
+/// A threshold rule evaluated against a windowed aggregate.
+///
+/// `threshold` is stored in the metric's native unit: milliseconds for
+/// latency metrics and a 0..1 fraction for percentage metrics.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub metric: MetricKind,
+    pub stat: AlertStat,
+    pub comparison: AlertComparison,
+    pub threshold: f64,
+    pub clear_after: u32,
+}
+
+impl AlertRule {
+    pub fn is_breached(&self, value: f64) -> bool {
+        match self.comparison {
+            AlertComparison::Above => value > self.threshold,
+            AlertComparison::AtLeast => value >= self.threshold,
+            AlertComparison::Below => value < self.threshold,
+            AlertComparison::AtMost => value <= self.threshold,
+        }
+    }
+
+    pub fn observe(&self, stats: &MetricStats) -> Option<f64> {
+        self.stat.select(stats)
+    }
+
+    /// Picks whichever of two observations is closer to breaching this rule.
+    pub fn worst_of(&self, left: Option<f64>, right: Option<f64>) -> Option<f64> {
+        match (left, right) {
+            (Some(a), Some(b)) => Some(match self.comparison {
+                AlertComparison::Above | AlertComparison::AtLeast => a.max(b),
+                AlertComparison::Below | AlertComparison::AtMost => a.min(b),
+            }),
+            (value, None) | (None, value) => value,
+        }
+    }
+
+    /// Formats an observed value in the rule's unit for display.
+    pub fn format_value(&self, value: f64) -> String {
+        match self.metric.unit() {
+            "ms" => format!("{value:.1}ms"),
+            "%" => format!("{:.2}%", value * 100.0),
+            _ => format!("{value:.1}"),
+        }
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.metric.label(),
+            self.stat.label(),
+            self.comparison.symbol(),
+            format_threshold(self.metric, self.threshold)
+        )
+    }
+}
+
+/// Parses a rule such as `total p99 > 800ms` or `probe_loss > 5%`.
+/// The stat defaults to `mean` when omitted.
+pub fn parse_alert_rule(input: &str) -> Result<AlertRule, AlertRuleError> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let (metric, stat, comparison, threshold) = match tokens.as_slice() {
+        [metric, comparison, threshold] => (*metric, None, *comparison, *threshold),
+        [metric, stat, comparison, threshold] => (*metric, Some(*stat), *comparison, *threshold),
+        _ => return Err(AlertRuleError::Malformed),
+    };
+
+    let metric = MetricKind::from_label(metric)
+        .ok_or_else(|| AlertRuleError::UnknownMetric(metric.to_string()))?;
+    let stat = match stat {
+        Some(stat) => {
+            AlertStat::parse(stat).ok_or_else(|| AlertRuleError::UnknownStat(stat.to_string()))?
+        }
+        None => AlertStat::Mean,
+    };
+    let comparison = AlertComparison::parse(comparison)
+        .ok_or_else(|| AlertRuleError::UnknownComparison(comparison.to_string()))?;
+    let threshold = parse_threshold(metric, threshold)
+        .ok_or_else(|| AlertRuleError::InvalidThreshold(threshold.to_string()))?;
+
+    Ok(AlertRule {
+        metric,
+        stat,
+        comparison,
+        threshold,
+        clear_after: DEFAULT_CLEAR_AFTER,
+    })
+}
+
+/// Parses a `;`-separated list of rules. Blank input yields no rules.
+pub fn parse_alert_rules(input: &str) -> Result<Vec<AlertRule>, AlertRuleError> {
+    input
+        .split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(parse_alert_rule)
+        .collect()
+}
+
+pub fn format_alert_rules(rules: &[AlertRule]) -> String {
+    rules
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn parse_threshold(metric: MetricKind, input: &str) -> Option<f64> {
+    let input = input.to_ascii_lowercase();
+    let value = match metric.unit() {
+        "ms" => {
+            if let Some(value) = input.strip_suffix("ms") {
+                value.parse::<f64>().ok()?
+            } else if let Some(value) = input.strip_suffix('s') {
+                value.parse::<f64>().ok()? * 1000.0
+            } else {
+                input.parse::<f64>().ok()?
+            }
+        }
+        "%" => {
+            input
+                .strip_suffix('%')
+                .unwrap_or(&input)
+                .parse::<f64>()
+                .ok()?
+                / 100.0
+        }
+        _ => input.parse::<f64>().ok()?,
+    };
+    value.is_finite().then_some(value)
+}
+
+fn format_threshold(metric: MetricKind, value: f64) -> String {
+    match metric.unit() {
+        "ms" => format!("{value}ms"),
+        "%" => format!("{}%", value * 100.0),
+        _ => format!("{value}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_alert_rule_reads_latency_rule() {
+        let rule = parse_alert_rule("total p99 > 800ms").expect("rule");
+        assert_eq!(rule.metric, MetricKind::Total);
+        assert_eq!(rule.stat, AlertStat::P99);
+        assert_eq!(rule.comparison, AlertComparison::Above);
+        assert_eq!(rule.threshold, 800.0);
+        assert_eq!(rule.clear_after, DEFAULT_CLEAR_AFTER);
+    }
+
+    #[test]
+    fn parse_alert_rule_defaults_stat_and_scales_percent() {
+        let rule = parse_alert_rule("probe_loss > 5%").expect("rule");
+        assert_eq!(rule.metric, MetricKind::ProbeLossRate);
+        assert_eq!(rule.stat, AlertStat::Mean);
+        assert!((rule.threshold - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_alert_rule_accepts_seconds_for_latency() {
+        let rule = parse_alert_rule("ttfb p50 > 1.5s").expect("rule");
+        assert_eq!(rule.threshold, 1500.0);
+    }
+
+    #[test]
+    fn parse_alert_rule_rejects_invalid_input() {
+        assert_eq!(parse_alert_rule("total"), Err(AlertRuleError::Malformed));
+        assert!(matches!(
+            parse_alert_rule("bogus > 5"),
+            Err(AlertRuleError::UnknownMetric(_))
+        ));
+        assert!(matches!(
+            parse_alert_rule("total p42 > 5"),
+            Err(AlertRuleError::UnknownStat(_))
+        ));
+        assert!(matches!(
+            parse_alert_rule("total p99 = 5"),
+            Err(AlertRuleError::UnknownComparison(_))
+        ));
+        assert!(matches!(
+            parse_alert_rule("total p99 > fast"),
+            Err(AlertRuleError::InvalidThreshold(_))
+        ));
+    }
+
+    #[test]
+    fn inclusive_comparisons_breach_at_the_threshold() {
+        let above = parse_alert_rule("total > 100").expect("rule");
+        assert!(!above.is_breached(100.0));
+        assert!(above.is_breached(100.1));
+        let at_least = parse_alert_rule("total >= 100").expect("rule");
+        assert_eq!(at_least.comparison, AlertComparison::AtLeast);
+        assert!(at_least.is_breached(100.0));
+        assert!(!at_least.is_breached(99.9));
+
+        let below = parse_alert_rule("goodput_bps < 100").expect("rule");
+        assert!(!below.is_breached(100.0));
+        assert!(below.is_breached(99.9));
+        let at_most = parse_alert_rule("goodput_bps <= 100").expect("rule");
+        assert_eq!(at_most.comparison, AlertComparison::AtMost);
+        assert!(at_most.is_breached(100.0));
+        assert!(!at_most.is_breached(100.1));
+    }
+
+    #[test]
+    fn alert_rules_roundtrip_through_display() {
+        let rules = parse_alert_rules("total p99 > 800ms; probe_loss >= 5%; goodput_bps <= 10")
+            .expect("rules");
+        assert_eq!(rules.len(), 3);
+        let text = format_alert_rules(&rules);
+        assert_eq!(parse_alert_rules(&text).expect("reparse"), rules);
+        assert!(parse_alert_rules("  ").expect("empty").is_empty());
+    }
+
+    #[test]
+    fn worst_of_prefers_value_closest_to_breach() {
+        let above = parse_alert_rule("total > 100").expect("rule");
+        assert_eq!(above.worst_of(Some(50.0), Some(150.0)), Some(150.0));
+        let below = parse_alert_rule("goodput_bps < 100").expect("rule");
+        assert_eq!(below.worst_of(Some(50.0), Some(150.0)), Some(50.0));
+        assert_eq!(below.worst_of(None, Some(150.0)), Some(150.0));
+    }
+}
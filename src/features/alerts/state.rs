@@ -0,0 +1,153 @@
+use super::rule::AlertRule;
+use crate::config::TargetId;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// Maximum number of alert events retained for the alert log overlay.
+pub const ALERT_LOG_CAPACITY: usize = 256;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AlertTransition {
+    Fired,
+    Cleared,
+}
+
+/// Hysteresis state for a single rule on a single target.
+#[derive(Clone, Debug, Default)]
+pub struct AlertRuleState {
+    pub firing: bool,
+    pub last_value: Option<f64>,
+    healthy_streak: u32,
+}
+
+impl AlertRuleState {
+    /// Feeds one observation into the rule. Missing data leaves the state untouched.
+    pub fn evaluate(&mut self, rule: &AlertRule, value: Option<f64>) -> Option<AlertTransition> {
+        let value = value?;
+        self.last_value = Some(value);
+
+        if rule.is_breached(value) {
+            self.healthy_streak = 0;
+            if !self.firing {
+                self.firing = true;
+                return Some(AlertTransition::Fired);
+            }
+            return None;
+        }
+
+        if self.firing {
+            self.healthy_streak += 1;
+            if self.healthy_streak >= rule.clear_after.max(1) {
+                self.firing = false;
+                self.healthy_streak = 0;
+                return Some(AlertTransition::Cleared);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AlertEvent {
+    pub ts: SystemTime,
+    pub target_id: TargetId,
+    pub target_label: String,
+    pub rule: String,
+    pub value: String,
+    pub transition: AlertTransition,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AlertLog {
+    events: VecDeque<AlertEvent>,
+}
+
+impl AlertLog {
+    pub fn push(&mut self, event: AlertEvent) {
+        self.events.push_back(event);
+        while self.events.len() > ALERT_LOG_CAPACITY {
+            self.events.pop_front();
+        }
+    }
+
+    /// Iterates events newest first.
+    pub fn iter_recent(&self) -> impl Iterator<Item = &AlertEvent> {
+        self.events.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::parse_alert_rule;
+    use uuid::Uuid;
+
+    #[test]
+    fn rule_fires_immediately_and_clears_after_streak() {
+        let mut rule = parse_alert_rule("total p99 > 100ms").expect("rule");
+        rule.clear_after = 3;
+        let mut state = AlertRuleState::default();
+
+        assert_eq!(state.evaluate(&rule, Some(50.0)), None);
+        assert_eq!(
+            state.evaluate(&rule, Some(150.0)),
+            Some(AlertTransition::Fired)
+        );
+        assert_eq!(state.evaluate(&rule, Some(150.0)), None);
+        assert_eq!(state.evaluate(&rule, Some(50.0)), None);
+        assert_eq!(state.evaluate(&rule, Some(50.0)), None);
+        assert_eq!(
+            state.evaluate(&rule, Some(50.0)),
+            Some(AlertTransition::Cleared)
+        );
+        assert!(!state.firing);
+    }
+
+    #[test]
+    fn breach_resets_healthy_streak() {
+        let mut rule = parse_alert_rule("total > 100").expect("rule");
+        rule.clear_after = 2;
+        let mut state = AlertRuleState::default();
+
+        state.evaluate(&rule, Some(200.0));
+        state.evaluate(&rule, Some(10.0));
+        state.evaluate(&rule, Some(200.0));
+        assert_eq!(state.evaluate(&rule, Some(10.0)), None);
+        assert!(state.firing);
+    }
+
+    #[test]
+    fn missing_value_does_not_change_state() {
+        let rule = parse_alert_rule("total > 100").expect("rule");
+        let mut state = AlertRuleState::default();
+        state.evaluate(&rule, Some(200.0));
+        assert_eq!(state.evaluate(&rule, None), None);
+        assert!(state.firing);
+    }
+
+    #[test]
+    fn alert_log_is_bounded_and_newest_first() {
+        let mut log = AlertLog::default();
+        for idx in 0..ALERT_LOG_CAPACITY + 5 {
+            log.push(AlertEvent {
+                ts: SystemTime::now(),
+                target_id: Uuid::new_v4(),
+                target_label: format!("t{idx}"),
+                rule: "total > 1".to_string(),
+                value: "2".to_string(),
+                transition: AlertTransition::Fired,
+            });
+        }
+        assert_eq!(log.len(), ALERT_LOG_CAPACITY);
+        let newest = log.iter_recent().next().expect("event");
+        assert_eq!(newest.target_label, format!("t{}", ALERT_LOG_CAPACITY + 4));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::config::TargetId;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+use super::state::{AppState, TargetRuntime};
+
+/// Free-text note attached to a target at a point in time, such as
+/// "switched to new LB".
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub ts: SystemTime,
+    pub text: String,
+}
+
+impl TargetRuntime {
+    /// Annotations at or after `since`, with their 1-based number in the list.
+    pub fn annotations_since(
+        &self,
+        since: SystemTime,
+    ) -> impl Iterator<Item = (usize, &Annotation)> {
+        self.annotations
+            .iter()
+            .enumerate()
+            .filter(move |(_, annotation)| annotation.ts >= since)
+            .map(|(index, annotation)| (index + 1, annotation))
+    }
+}
+
+impl AppState {
+    /// Attaches `text` to the target at `index`, stamped now. Blank text is
+    /// ignored. Returns the position of the new annotation.
+    pub fn add_annotation(&mut self, index: usize, text: &str) -> Option<usize> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let target = self.targets.get_mut(index)?;
+        let annotation = Annotation {
+            ts: SystemTime::now(),
+            text: text.to_string(),
+        };
+        target.annotations.push(annotation.clone());
+        self.new_annotations.push((target.config.id, annotation));
+        Some(target.annotations.len() - 1)
+    }
+
+    pub fn delete_annotation(&mut self, index: usize, annotation: usize) {
+        if let Some(target) = self.targets.get_mut(index)
+            && annotation < target.annotations.len()
+        {
+            target.annotations.remove(annotation);
+        }
+    }
+
+    /// Annotations added since the last call, for the session recording.
+    pub fn take_new_annotations(&mut self) -> Vec<(TargetId, Annotation)> {
+        std::mem::take(&mut self.new_annotations)
+    }
+
+    /// Puts recorded annotations back on their targets, e.g. from a session
+    /// being replayed. Annotations for unknown targets are dropped.
+    pub fn load_annotations(&mut self, annotations: Vec<(TargetId, Annotation)>) {
+        for (target_id, annotation) in annotations {
+            if let Some(target) = self
+                .targets
+                .iter_mut()
+                .find(|target| target.config.id == target_id)
+            {
+                target.annotations.push(annotation);
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::metrics::Baseline;
+use std::time::SystemTime;
+
+use super::state::{AppState, TargetRuntime};
+
+impl TargetRuntime {
+    /// The baseline the metrics table compares against, if one is active.
+    pub fn current_baseline(&self) -> Option<&Baseline> {
+        self.baselines.get(self.active_baseline?)
+    }
+}
+
+impl AppState {
+    /// Snapshots the window of the target's selected profile as a new baseline
+    /// and makes it active. A blank name becomes "baseline N".
+    pub fn capture_baseline(&mut self, index: usize, name: &str) -> Option<usize> {
+        let target = self.targets.get(index)?;
+        let profile = target.profiles.get(target.selected_profile)?;
+        let aggregate = self.target_aggregate(target, profile);
+        let name = match name.trim() {
+            "" => format!("baseline {}", target.baselines.len() + 1),
+            name => name.to_string(),
+        };
+        let baseline = Baseline::capture(name, profile.config.id, &aggregate, SystemTime::now());
+
+        let target = &mut self.targets[index];
+        target.baselines.push(baseline);
+        let position = target.baselines.len() - 1;
+        target.active_baseline = Some(position);
+        Some(position)
+    }
+
+    /// Makes `baseline` the active one, or clears it when it already is.
+    pub fn toggle_baseline(&mut self, index: usize, baseline: usize) {
+        let Some(target) = self.targets.get_mut(index) else {
+            return;
+        };
+        if baseline >= target.baselines.len() {
+            return;
+        }
+        target.active_baseline = if target.active_baseline == Some(baseline) {
+            None
+        } else {
+            Some(baseline)
+        };
+    }
+
+    pub fn delete_baseline(&mut self, index: usize, baseline: usize) {
+        let Some(target) = self.targets.get_mut(index) else {
+            return;
+        };
+        if baseline >= target.baselines.len() {
+            return;
+        }
+        target.baselines.remove(baseline);
+        target.active_baseline = match target.active_baseline {
+            Some(active) if active == baseline => None,
+            Some(active) if active > baseline => Some(active - 1),
+            active => active,
+        };
+    }
+}
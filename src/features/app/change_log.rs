@@ -0,0 +1,301 @@
+use crate::config::{ProfileConfig, TargetConfig, TargetId};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::time::SystemTime;
+
+use super::parsing::format_duration;
+
+/// Maximum number of user changes retained across all targets.
+pub const CHANGE_LOG_CAPACITY: usize = 300;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeEntry {
+    pub ts: SystemTime,
+    /// `None` for changes that apply to every target, such as the global pause
+    pub target_id: Option<TargetId>,
+    /// Target name at the time of the change, so entries outlive a deletion
+    pub target: String,
+    pub description: String,
+}
+
+/// Bounded log of user-initiated changes, newest last.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeLog {
+    entries: VecDeque<ChangeEntry>,
+}
+
+impl ChangeLog {
+    pub fn push(&mut self, entry: ChangeEntry) {
+        self.entries.push_back(entry);
+        while self.entries.len() > CHANGE_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Entries newest first.
+    pub fn iter_recent(&self) -> impl Iterator<Item = &ChangeEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// One line per field that differs between two configs of the same target.
+pub(super) fn describe_target_change(old: &TargetConfig, new: &TargetConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut diff = |label: &str, before: String, after: String| {
+        if before != after {
+            changes.push(format!("{label}: {before} → {after}"));
+        }
+    };
+    diff("URL", old.url.to_string(), new.url.to_string());
+    diff(
+        "Name",
+        or_none(old.name.as_ref()),
+        or_none(new.name.as_ref()),
+    );
+    diff("Tags", list(&old.tags), list(&new.tags));
+    diff(
+        "Interval",
+        format_duration(old.interval),
+        format_duration(new.interval),
+    );
+    diff(
+        "Schedule",
+        or_none(old.schedule.as_ref()),
+        or_none(new.schedule.as_ref()),
+    );
+    diff(
+        "Timeout",
+        format_duration(old.timeout_total),
+        format_duration(new.timeout_total),
+    );
+    diff("DNS", dns_mode(old), dns_mode(new));
+    diff(
+        "Unix socket",
+        or_none(old.unix_socket.as_ref().map(|path| path.display())),
+        or_none(new.unix_socket.as_ref().map(|path| path.display())),
+    );
+    diff(
+        "Connect to",
+        or_none(old.connect_to.as_ref()),
+        or_none(new.connect_to.as_ref()),
+    );
+    diff("Insecure", on_off(old.insecure), on_off(new.insecure));
+    diff("Per-IP probes", on_off(old.per_ip), on_off(new.per_ip));
+    diff(
+        "SLO target",
+        or_none(old.slo_target.map(|pct| format!("{pct}%"))),
+        or_none(new.slo_target.map(|pct| format!("{pct}%"))),
+    );
+    diff(
+        "SLO latency",
+        or_none(old.slo_latency_ms.map(|ms| format!("{ms}ms"))),
+        or_none(new.slo_latency_ms.map(|ms| format!("{ms}ms"))),
+    );
+    diff(
+        "Jitter",
+        format!("±{}%", old.jitter_pct),
+        format!("±{}%", new.jitter_pct),
+    );
+    if old.alert_rules != new.alert_rules {
+        changes.push(format!(
+            "Alert rules: {} → {}",
+            list(&old.alert_rules),
+            list(&new.alert_rules)
+        ));
+    }
+    if old.sampling != new.sampling {
+        changes.push("Sampling settings changed".to_string());
+    }
+    let ids = |config: &TargetConfig| -> Vec<_> { config.profiles.iter().map(|p| p.id).collect() };
+    if ids(old) != ids(new) {
+        let names = |config: &TargetConfig| {
+            config
+                .profiles
+                .iter()
+                .map(|profile| profile.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        changes.push(format!("Profiles: {} → {}", names(old), names(new)));
+    } else {
+        for (before, after) in old.profiles.iter().zip(&new.profiles) {
+            changes.extend(describe_profile_change(before, after));
+        }
+    }
+    changes
+}
+
+/// One line per field that differs between two configs of the same profile,
+/// each prefixed with the profile's name before the change.
+pub(super) fn describe_profile_change(old: &ProfileConfig, new: &ProfileConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut diff = |label: &str, before: String, after: String| {
+        if before != after {
+            changes.push(format!("{} {label}: {before} → {after}", old.name));
+        }
+    };
+    diff("name", old.name.clone(), new.name.clone());
+    diff("method", old.method.to_string(), new.method.to_string());
+    diff("HTTP", old.http.to_string(), new.http.to_string());
+    diff("TLS", old.tls.to_string(), new.tls.to_string());
+    diff(
+        "reuse",
+        old.conn_reuse.to_string(),
+        new.conn_reuse.to_string(),
+    );
+    diff(
+        "max read",
+        old.max_read_bytes.to_string(),
+        new.max_read_bytes.to_string(),
+    );
+    diff(
+        "recv speed",
+        or_none(old.max_recv_speed.map(|speed| format!("{speed}B/s"))),
+        or_none(new.max_recv_speed.map(|speed| format!("{speed}B/s"))),
+    );
+    diff("color", or_none(old.color), or_none(new.color));
+    diff(
+        "interval",
+        or_none(old.interval_override.map(format_duration)),
+        or_none(new.interval_override.map(format_duration)),
+    );
+    diff(
+        "expected status",
+        or_none(old.expected_status.as_ref()),
+        or_none(new.expected_status.as_ref()),
+    );
+    diff(
+        "body check",
+        or_none(old.expect_body_contains.as_ref()),
+        or_none(new.expect_body_contains.as_ref()),
+    );
+    diff(
+        "captured headers",
+        list(&old.capture_headers),
+        list(&new.capture_headers),
+    );
+    // Values may be secrets; only say which header names changed
+    let header_names = |profile: &ProfileConfig| {
+        list(
+            &profile
+                .headers
+                .iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+        )
+    };
+    if old.headers != new.headers {
+        changes.push(format!(
+            "{} headers: {} → {}",
+            old.name,
+            header_names(old),
+            header_names(new)
+        ));
+    }
+    if old.auth != new.auth {
+        changes.push(format!("{} auth changed", old.name));
+    }
+    changes
+}
+
+fn or_none(value: Option<impl Display>) -> String {
+    value.map_or_else(|| "none".to_string(), |value| value.to_string())
+}
+
+fn list(items: &[impl Display]) -> String {
+    if items.is_empty() {
+        return "none".to_string();
+    }
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub(super) fn on_off(enabled: bool) -> String {
+    if enabled { "on" } else { "off" }.to_string()
+}
+
+fn dns_mode(config: &TargetConfig) -> String {
+    if !config.dns_enabled {
+        "off".to_string()
+    } else if config.dns_combined {
+        "per probe".to_string()
+    } else {
+        format!("worker {}", format_duration(config.dns_refresh))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::parse_alert_rule;
+    use crate::config::default_profiles;
+    use std::time::Duration;
+    use url::Url;
+
+    fn entry(secs: u64) -> ChangeEntry {
+        ChangeEntry {
+            ts: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            target_id: None,
+            target: "All targets".to_string(),
+            description: format!("change {secs}"),
+        }
+    }
+
+    #[test]
+    fn log_is_bounded_and_newest_first() {
+        let mut log = ChangeLog::default();
+        for secs in 0..(CHANGE_LOG_CAPACITY as u64 + 5) {
+            log.push(entry(secs));
+        }
+        assert_eq!(log.len(), CHANGE_LOG_CAPACITY);
+        let newest = log.iter_recent().next().expect("entry");
+        assert_eq!(
+            newest.description,
+            format!("change {}", CHANGE_LOG_CAPACITY + 4)
+        );
+        assert_eq!(log.iter_recent().last().unwrap().description, "change 5");
+    }
+
+    #[test]
+    fn only_changed_fields_are_described() {
+        let old = TargetConfig::new(
+            Url::parse("https://example.com").unwrap(),
+            default_profiles(),
+        );
+        assert!(describe_target_change(&old, &old).is_empty());
+
+        let mut new = old.clone();
+        new.interval = Duration::from_secs(30);
+        new.dns_combined = true;
+        new.profiles[0].method = crate::config::ProbeMethod::Head;
+        let name = &old.profiles[0].name;
+        assert_eq!(
+            describe_target_change(&old, &new),
+            [
+                "Interval: 5s → 30s".to_string(),
+                "DNS: worker 30s → per probe".to_string(),
+                format!("{name} method: get → head"),
+            ]
+        );
+
+        // Swapping one rule for another keeps the count but is still a change
+        let mut old = old;
+        old.alert_rules = vec![parse_alert_rule("total p99 > 800ms").unwrap()];
+        let mut new = old.clone();
+        new.alert_rules = vec![parse_alert_rule("total p99 > 500ms").unwrap()];
+        let described = describe_target_change(&old, &new);
+        assert_eq!(described.len(), 1, "{described:?}");
+        assert!(described[0].starts_with("Alert rules: "), "{described:?}");
+    }
+}
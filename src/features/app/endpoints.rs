@@ -0,0 +1,282 @@
+use crate::config::{ProfileConfig, ProfileId, TargetConfig};
+use crate::metrics_aggregate::ProfileKey;
+use crate::probe::ProbeSample;
+use crate::runtime::{
+    ControlMessage, ProbeSource, WorkerHandle, endpoint_stream_id, spawn_endpoint_worker,
+};
+use crossbeam_channel::Sender;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use super::error_log::ErrorEntry;
+use super::state::{AppState, ProfileRuntime, TargetRuntime};
+use super::status::StatusNotice;
+
+/// Most per-IP streams one target runs across its profiles. A host whose
+/// answer lists dozens of addresses gets streams for the first ones only;
+/// every target streams at least one address.
+pub const MAX_ENDPOINT_STREAMS: usize = 16;
+
+/// One per-IP stream of a `per_ip` target: a profile pinned to one of the
+/// addresses the host resolves to.
+pub struct EndpointRuntime {
+    pub profile_id: ProfileId,
+    pub ip: IpAddr,
+    pub worker: WorkerHandle,
+    pub last_sample: Option<ProbeSample>,
+}
+
+/// Which streams of a `per_ip` target the detail panes read.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EndpointView {
+    /// Each profile's own stream, which follows the DNS answer
+    #[default]
+    Profile,
+    /// The chart overlays one series per address of the selected profile
+    Overlay,
+    /// Every pane reads the streams pinned to this address
+    Ip(IpAddr),
+}
+
+/// Metrics key a sample is stored under: its profile's, or the per-IP
+/// stream's when it came from one.
+pub fn sample_key(sample: &ProbeSample) -> ProfileKey {
+    ProfileKey {
+        target_id: sample.target_id,
+        profile_id: sample.endpoint.map_or(sample.profile_id, |ip| {
+            endpoint_stream_id(sample.profile_id, ip)
+        }),
+    }
+}
+
+impl TargetRuntime {
+    /// Addresses with per-IP streams, in the order they were first seen.
+    pub fn endpoint_ips(&self) -> Vec<IpAddr> {
+        let mut ips = Vec::new();
+        for endpoint in &self.endpoints {
+            if !ips.contains(&endpoint.ip) {
+                ips.push(endpoint.ip);
+            }
+        }
+        ips
+    }
+
+    /// Key of `profile`'s stream pinned to `ip`.
+    pub fn endpoint_key(&self, profile: &ProfileRuntime, ip: IpAddr) -> ProfileKey {
+        ProfileKey {
+            target_id: self.config.id,
+            profile_id: endpoint_stream_id(profile.config.id, ip),
+        }
+    }
+
+    /// Key the detail panes read for `profile`: the stream pinned to the
+    /// address picked with the endpoint view, else the profile's own.
+    pub fn view_key(&self, profile: &ProfileRuntime) -> ProfileKey {
+        match self.endpoint_view {
+            EndpointView::Ip(ip) => self.endpoint_key(profile, ip),
+            EndpointView::Profile | EndpointView::Overlay => ProfileKey {
+                target_id: self.config.id,
+                profile_id: profile.config.id,
+            },
+        }
+    }
+
+    /// Steps the endpoint view: profile streams, all addresses overlaid,
+    /// then each address in turn. Returns `false` without per-IP streams.
+    pub fn cycle_endpoint_view(&mut self) -> bool {
+        let ips = self.endpoint_ips();
+        if ips.is_empty() {
+            self.endpoint_view = EndpointView::Profile;
+            return false;
+        }
+        self.endpoint_view = match self.endpoint_view {
+            EndpointView::Profile => EndpointView::Overlay,
+            EndpointView::Overlay => EndpointView::Ip(ips[0]),
+            EndpointView::Ip(ip) => ips
+                .iter()
+                .position(|candidate| *candidate == ip)
+                .and_then(|index| ips.get(index + 1))
+                .map_or(EndpointView::Profile, |next| EndpointView::Ip(*next)),
+        };
+        true
+    }
+}
+
+impl AppState {
+    /// Starts and retires per-IP streams so every profile of a `per_ip`
+    /// target has one per recently resolved address, up to
+    /// [`MAX_ENDPOINT_STREAMS`]. An address keeps its streams until it has
+    /// been missing from [`super::ENDPOINT_GRACE_LOOKUPS`] lookups. Called
+    /// every tick; a changed address set is announced in the header.
+    pub fn sync_endpoints(&mut self, sample_tx: &Sender<ProbeSample>) {
+        let ProbeSource::Network(_) = self.probe_source else {
+            return;
+        };
+        let source = self.probe_source;
+        self.sync_endpoints_with(&mut |target, profile, ip, start_delay| {
+            spawn_endpoint_worker(
+                target.clone(),
+                profile.clone(),
+                ip,
+                start_delay,
+                source,
+                sample_tx.clone(),
+            )
+        });
+    }
+
+    /// [`Self::sync_endpoints`] with the worker spawn supplied, so tests can
+    /// stand in for the probing threads.
+    pub(super) fn sync_endpoints_with(
+        &mut self,
+        spawn: &mut dyn FnMut(&TargetConfig, &ProfileConfig, IpAddr, Duration) -> WorkerHandle,
+    ) {
+        let suspended = self.probing_suspended();
+        for target in &mut self.targets {
+            let before = target.endpoint_ips();
+            let mut wanted: Vec<IpAddr> = if target.config.probes_per_ip() {
+                target.ip_history.recently_resolved()
+            } else {
+                Vec::new()
+            };
+            // Addresses already streaming keep their place under the cap
+            wanted.sort_by_key(|ip| !before.contains(ip));
+            wanted.truncate((MAX_ENDPOINT_STREAMS / target.profiles.len().max(1)).max(1));
+
+            let profiles = &target.profiles;
+            let mut retired = Vec::new();
+            target.endpoints.retain_mut(|endpoint| {
+                let keep = wanted.contains(&endpoint.ip)
+                    && profiles.iter().any(|p| p.config.id == endpoint.profile_id);
+                if !keep {
+                    // Like a removed target: never block the UI on the join
+                    self.pending_shutdown.extend(endpoint.worker.stop());
+                    retired.push(ProfileKey {
+                        target_id: target.config.id,
+                        profile_id: endpoint_stream_id(endpoint.profile_id, endpoint.ip),
+                    });
+                }
+                keep
+            });
+            for key in retired {
+                self.metrics.remove_key(key);
+            }
+
+            for (index, profile) in target.profiles.iter().enumerate() {
+                for ip in &wanted {
+                    if target
+                        .endpoints
+                        .iter()
+                        .any(|e| e.profile_id == profile.config.id && e.ip == *ip)
+                    {
+                        continue;
+                    }
+                    let worker = spawn(
+                        &target.config,
+                        &profile.config,
+                        *ip,
+                        target.config.stagger_offset(index),
+                    );
+                    if target.paused || suspended {
+                        let _ = worker.sender.send(ControlMessage::Pause(true));
+                    }
+                    target.endpoints.push(EndpointRuntime {
+                        profile_id: profile.config.id,
+                        ip: *ip,
+                        worker,
+                        last_sample: None,
+                    });
+                }
+            }
+
+            let after = target.endpoint_ips();
+            let stale_view = match target.endpoint_view {
+                EndpointView::Profile => false,
+                EndpointView::Overlay => after.is_empty(),
+                EndpointView::Ip(ip) => !after.contains(&ip),
+            };
+            if stale_view {
+                target.endpoint_view = EndpointView::Profile;
+            }
+            let changes: Vec<String> = after
+                .iter()
+                .filter(|ip| !before.contains(ip))
+                .map(|ip| format!("+{ip}"))
+                .chain(
+                    before
+                        .iter()
+                        .filter(|ip| !after.contains(ip))
+                        .map(|ip| format!("-{ip}")),
+                )
+                .collect();
+            if !changes.is_empty() {
+                tracing::info!(target_url = %target.config.url, ?changes, "per-ip streams changed");
+                self.status_notice = Some(StatusNotice {
+                    at: Instant::now(),
+                    message: format!(
+                        "{} per-IP {}",
+                        target.config.display_name(),
+                        changes.join(" ")
+                    ),
+                    failed: false,
+                });
+            }
+        }
+    }
+
+    /// Records a sample of a per-IP stream under the stream's own key. It
+    /// does not touch the profile, whose stream is the one following DNS.
+    pub(super) fn apply_endpoint_sample(&mut self, ip: IpAddr, sample: ProbeSample) {
+        let key = sample_key(&sample);
+        let replaying = self.replaying;
+        let Some(target) = self
+            .targets
+            .iter_mut()
+            .find(|t| t.config.id == sample.target_id)
+        else {
+            return;
+        };
+        let Some(profile) = target
+            .profiles
+            .iter()
+            .find(|p| p.config.id == sample.profile_id)
+        else {
+            return;
+        };
+        if let crate::probe::ProbeResult::Err(err) = &sample.result {
+            target.error_log.push(ErrorEntry {
+                ts: sample.ts,
+                profile: format!("{} @ {ip}", profile.config.name),
+                kind: err.kind,
+                message: err.message.to_string(),
+            });
+        }
+        let position = target
+            .endpoints
+            .iter()
+            .position(|e| e.profile_id == sample.profile_id && e.ip == ip);
+        let endpoint = match position {
+            Some(index) => &mut target.endpoints[index],
+            // A replay has no workers; its streams appear as samples arrive
+            None if replaying => {
+                target.endpoints.push(EndpointRuntime {
+                    profile_id: sample.profile_id,
+                    ip,
+                    worker: WorkerHandle::idle(),
+                    last_sample: None,
+                });
+                target.endpoints.last_mut().expect("just pushed")
+            }
+            // A sample of a stream retired while it was in flight
+            None => return,
+        };
+        endpoint.last_sample = Some(sample.clone());
+        let sampling = &target.config.sampling;
+        self.metrics.push_sample(
+            key,
+            sample,
+            sampling.max_points_per_window,
+            sampling.retention,
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use crate::probe::ProbeErrorKind;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// Maximum number of probe errors retained per target.
+pub const ERROR_LOG_CAPACITY: usize = 50;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ErrorEntry {
+    pub ts: SystemTime,
+    pub profile: String,
+    pub kind: ProbeErrorKind,
+    pub message: String,
+}
+
+/// Bounded log of full probe error messages for a target.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorLog {
+    entries: VecDeque<ErrorEntry>,
+}
+
+impl ErrorLog {
+    pub fn push(&mut self, entry: ErrorEntry) {
+        self.entries.push_back(entry);
+        while self.entries.len() > ERROR_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Entries newest first.
+    pub fn iter_recent(&self) -> impl Iterator<Item = &ErrorEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(secs: u64) -> ErrorEntry {
+        ErrorEntry {
+            ts: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            profile: "h2".to_string(),
+            kind: ProbeErrorKind::HttpTimeout,
+            message: format!("timed out after {secs}s"),
+        }
+    }
+
+    #[test]
+    fn log_is_bounded_and_newest_first() {
+        let mut log = ErrorLog::default();
+        for secs in 0..(ERROR_LOG_CAPACITY as u64 + 5) {
+            log.push(entry(secs));
+        }
+        assert_eq!(log.len(), ERROR_LOG_CAPACITY);
+        let newest = log.iter_recent().next().expect("entry");
+        assert_eq!(
+            newest.message,
+            format!("timed out after {}s", ERROR_LOG_CAPACITY + 4)
+        );
+        let oldest = log.iter_recent().last().expect("entry");
+        assert_eq!(oldest.message, "timed out after 5s");
+    }
+}
@@ -0,0 +1,154 @@
+use crate::common::time::{Clock, SystemClock};
+use crate::metrics_aggregate::ProfileKey;
+use std::time::Duration;
+
+use super::state::{AppState, TargetRuntime};
+
+/// Probe intervals without a new sample before a target's data is aging.
+pub const AGING_INTERVALS: u32 = 2;
+/// Probe intervals without a new sample before a target's data is stale.
+pub const STALE_INTERVALS: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FreshnessLevel {
+    Fresh,
+    /// Older than `AGING_INTERVALS` intervals
+    Aging,
+    /// Older than `STALE_INTERVALS` intervals
+    Stale,
+}
+
+/// How old a target's newest sample is, judged against its probe interval.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Freshness {
+    pub age: Duration,
+    pub level: FreshnessLevel,
+}
+
+impl AppState {
+    /// Age of the newest stored sample of any of the target's profiles;
+    /// `None` before the first sample.
+    pub fn target_freshness(&self, target: &TargetRuntime) -> Option<Freshness> {
+        self.target_freshness_with_clock(target, &SystemClock)
+    }
+
+    pub fn target_freshness_with_clock(
+        &self,
+        target: &TargetRuntime,
+        clock: &dyn Clock,
+    ) -> Option<Freshness> {
+        let newest = target
+            .profiles
+            .iter()
+            .filter_map(|profile| {
+                self.metrics.newest_sample_ts(ProfileKey {
+                    target_id: target.config.id,
+                    profile_id: profile.config.id,
+                })
+            })
+            .max()?;
+        // The most frequently probed profile sets the expected pace
+        let interval = target
+            .profiles
+            .iter()
+            .map(|profile| target.config.interval_for(&profile.config))
+            .min()
+            .unwrap_or(target.config.interval);
+        let age = clock.now().duration_since(newest).unwrap_or_default();
+        let level = if age > interval * STALE_INTERVALS {
+            FreshnessLevel::Stale
+        } else if age > interval * AGING_INTERVALS {
+            FreshnessLevel::Aging
+        } else {
+            FreshnessLevel::Fresh
+        };
+        Some(Freshness { age, level })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GlobalConfig, TargetConfig, default_profiles};
+    use crate::features::probe::testing::SampleBuilder;
+    use std::time::SystemTime;
+    use url::Url;
+
+    struct FixedClock(SystemTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    fn app() -> AppState {
+        let mut app = AppState::new(GlobalConfig::default());
+        let mut target = TargetConfig::new(
+            Url::parse("https://api.example.com").unwrap(),
+            default_profiles(),
+        );
+        target.interval = Duration::from_secs(10);
+        app.load_replay(vec![target]);
+        app
+    }
+
+    #[test]
+    fn freshness_follows_the_age_of_the_newest_sample() {
+        let mut app = app();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let at = |secs: u64| FixedClock(start + Duration::from_secs(secs));
+        assert_eq!(
+            app.target_freshness_with_clock(&app.targets[0], &at(0)),
+            None
+        );
+
+        let target_id = app.targets[0].config.id;
+        for (profile, secs) in [(0, 0), (1, 4)] {
+            let profile_id = app.targets[0].profiles[profile].config.id;
+            app.apply_sample(
+                SampleBuilder::ok(target_id, profile_id)
+                    .at(start + Duration::from_secs(secs))
+                    .build(),
+            );
+        }
+
+        let freshness = |secs| {
+            app.target_freshness_with_clock(&app.targets[0], &at(secs))
+                .unwrap()
+        };
+        assert_eq!(
+            freshness(14),
+            Freshness {
+                age: Duration::from_secs(10),
+                level: FreshnessLevel::Fresh
+            }
+        );
+        assert_eq!(freshness(25).level, FreshnessLevel::Aging);
+        assert_eq!(freshness(54).level, FreshnessLevel::Aging);
+        assert_eq!(freshness(55).level, FreshnessLevel::Stale);
+    }
+
+    #[test]
+    fn the_shortest_profile_interval_sets_the_pace() {
+        let mut app = app();
+        let mut profile = app.targets[0].profiles[0].config.clone();
+        profile.interval_override = Some(Duration::from_secs(2));
+        let target_id = app.targets[0].config.id;
+        app.update_profile_config(target_id, profile.clone());
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        app.apply_sample(SampleBuilder::ok(target_id, profile.id).at(start).build());
+
+        let level = |secs| {
+            app.target_freshness_with_clock(
+                &app.targets[0],
+                &FixedClock(start + Duration::from_secs(secs)),
+            )
+            .unwrap()
+            .level
+        };
+        assert_eq!(level(4), FreshnessLevel::Fresh);
+        assert_eq!(level(5), FreshnessLevel::Aging);
+        assert_eq!(level(11), FreshnessLevel::Stale);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::config::{TargetId, WindowSpec};
+use crate::metrics::{MetricKind, WindowedAggregate};
+use crate::metrics_aggregate::AggregateSource;
+use crate::probe::ProbeErrorKind;
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use super::state::{AppState, ProfileRuntime, TargetRuntime};
+
+/// Overall state of a target as shown in the list, least severe first so a
+/// group can show its worst member with `max`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum TargetStatus {
+    Paused,
+    /// Outside the hours of its schedule
+    Idle,
+    Running,
+    /// Some profile has fewer samples than its statistics need
+    Warming,
+    Stalled,
+    Failing,
+    Alerting,
+}
+
+/// One line of the target list.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TargetListRow {
+    /// Header of the targets whose first tag is this name
+    Group(String),
+    Target(usize),
+}
+
+/// Merged statistics of a group, shown under its header, in its pane and
+/// in the overview.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupSummary {
+    pub targets: usize,
+    /// Worst status among the members
+    pub status: TargetStatus,
+    /// Longest window a member is read over
+    pub window: WindowSpec,
+    /// Total-latency p50 of every member probe, each over its own window
+    pub p50_ms: Option<f64>,
+    /// Total-latency p99 of every member probe, each over its own window
+    pub p99_ms: Option<f64>,
+    /// Share of failed probes across every member profile
+    pub loss_rate: Option<f64>,
+    pub goodput_bps: Option<f64>,
+    /// Failures of every member, most frequent first
+    pub errors: Vec<(ProbeErrorKind, u64)>,
+}
+
+impl TargetRuntime {
+    pub fn status(&self, now: SystemTime) -> TargetStatus {
+        if self.paused {
+            TargetStatus::Paused
+        } else if self.schedule_idle(now) {
+            TargetStatus::Idle
+        } else if self.is_alerting() {
+            TargetStatus::Alerting
+        } else if self.profiles.iter().any(|p| p.last_error.is_some()) {
+            TargetStatus::Failing
+        } else if self.stalled_profiles() > 0 {
+            TargetStatus::Stalled
+        } else if self.is_warming() {
+            TargetStatus::Warming
+        } else {
+            TargetStatus::Running
+        }
+    }
+
+    /// Samples `profile` has taken and the number its statistics need, while
+    /// it is still warming up; `None` once it has enough. The one warm-up
+    /// rule: statistics, alert rules and the target status all follow it.
+    pub fn warm_up(&self, profile: &ProfileRuntime) -> Option<(u64, u64)> {
+        let needed = self.config.sampling.min_samples_for_stats;
+        (profile.samples_seen < needed).then_some((profile.samples_seen, needed))
+    }
+
+    /// Whether some profile is still warming up.
+    pub fn is_warming(&self) -> bool {
+        self.profiles
+            .iter()
+            .any(|profile| self.warm_up(profile).is_some())
+    }
+
+    /// List group: the first tag, if any.
+    pub fn group(&self) -> Option<&str> {
+        self.config.tags.first().map(String::as_str)
+    }
+}
+
+impl AppState {
+    /// Rows of the target list: untagged targets first, then one header per
+    /// group followed by its members unless the group is collapsed.
+    pub fn target_list_rows(&self) -> Vec<TargetListRow> {
+        let mut rows = Vec::new();
+        let mut current = None;
+        for index in self.visible_targets() {
+            let group = self.targets[index].group();
+            if let Some(name) = group
+                && current != Some(name)
+            {
+                rows.push(TargetListRow::Group(name.to_string()));
+            }
+            current = group;
+            if group.is_none_or(|name| !self.collapsed_groups.contains(name)) {
+                rows.push(TargetListRow::Target(index));
+            }
+        }
+        rows
+    }
+
+    /// Position of the cursor in `rows`, if it is on one of them.
+    pub fn cursor_row(&self, rows: &[TargetListRow]) -> Option<usize> {
+        rows.iter()
+            .position(|row| match (row, &self.selected_group) {
+                (TargetListRow::Group(name), Some(selected)) => name == selected,
+                (TargetListRow::Target(index), None) => *index == self.selected_target,
+                _ => false,
+            })
+    }
+
+    pub(super) fn select_row(&mut self, row: &TargetListRow) {
+        match row {
+            TargetListRow::Group(name) => self.selected_group = Some(name.clone()),
+            TargetListRow::Target(index) => {
+                self.selected_group = None;
+                self.selected_target = *index;
+            }
+        }
+    }
+
+    /// Puts the cursor on the header of `index`'s group.
+    pub fn select_group_of(&mut self, index: usize) {
+        if let Some(name) = self.targets.get(index).and_then(TargetRuntime::group) {
+            self.selected_group = Some(name.to_string());
+        }
+    }
+
+    /// Collapses or expands the group under the cursor. Returns false when
+    /// the cursor is on a target.
+    pub fn toggle_selected_group(&mut self) -> bool {
+        let Some(name) = self.selected_group.clone() else {
+            return false;
+        };
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name);
+        }
+        true
+    }
+
+    /// Replaces the target's tags, expanding its group if it is selected so it
+    /// stays in view.
+    pub fn set_target_tags(&mut self, target_id: TargetId, tags: Vec<String>) {
+        let Some(index) = self
+            .targets
+            .iter()
+            .position(|target| target.config.id == target_id)
+        else {
+            return;
+        };
+        let mut updated = self.targets[index].config.clone();
+        updated.tags = tags;
+        self.edit_target_config(updated);
+        if index == self.selected_target {
+            self.reveal_selected_target();
+        }
+        self.ensure_selection_visible();
+    }
+
+    /// Moves the cursor onto the selected target, expanding its group.
+    pub(super) fn reveal_selected_target(&mut self) {
+        self.selected_group = None;
+        if let Some(name) = self
+            .targets
+            .get(self.selected_target)
+            .and_then(TargetRuntime::group)
+        {
+            self.collapsed_groups.remove(name);
+        }
+    }
+
+    fn group_members(&self, name: &str) -> Vec<&TargetRuntime> {
+        self.visible_targets()
+            .into_iter()
+            .map(|index| &self.targets[index])
+            .filter(|target| target.group() == Some(name))
+            .collect()
+    }
+
+    /// Statistics of every profile of the group's members, merged as one
+    /// profile. Each member is read over its own window with its own
+    /// sampling settings and failure latency.
+    fn group_aggregate(&self, members: &[&TargetRuntime]) -> Option<WindowedAggregate> {
+        let sources: Vec<_> = members
+            .iter()
+            .flat_map(|target| {
+                let window = self.target_window(target);
+                let failure_latency = self.failure_latency(target);
+                target.profiles.iter().map(move |profile| AggregateSource {
+                    key: target.view_key(profile),
+                    window,
+                    sampling: &target.config.sampling,
+                    failure_latency,
+                })
+            })
+            .collect();
+        self.metrics
+            .merged_aggregate(&sources, &self.aggregate_view())
+    }
+
+    pub fn group_summary(&self, name: &str, now: SystemTime) -> Option<GroupSummary> {
+        let members = self.group_members(name);
+        let status = members.iter().map(|target| target.status(now)).max()?;
+        let aggregate = self.group_aggregate(&members)?;
+        let stat = |metric: MetricKind| aggregate.by_metric.get(&metric);
+        let mut errors: Vec<_> = aggregate
+            .error_breakdown
+            .iter()
+            .map(|(kind, count)| (*kind, *count))
+            .collect();
+        errors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.label().cmp(b.0.label())));
+
+        Some(GroupSummary {
+            targets: members.len(),
+            status,
+            window: aggregate.window,
+            p50_ms: stat(MetricKind::Total).and_then(|stats| stats.p50),
+            p99_ms: stat(MetricKind::Total).and_then(|stats| stats.p99),
+            loss_rate: stat(MetricKind::ProbeLossRate).and_then(|stats| stats.mean),
+            goodput_bps: stat(MetricKind::GoodputBps).and_then(|stats| stats.mean),
+            errors,
+        })
+    }
+
+    /// [`Self::group_summary`] of every group with a visible member, keyed by
+    /// name, so a frame merges each group's samples once.
+    pub fn group_summaries(&self, now: SystemTime) -> BTreeMap<String, GroupSummary> {
+        let mut names: Vec<&str> = self
+            .visible_targets()
+            .into_iter()
+            .filter_map(|index| self.targets[index].group())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|name| Some((name.to_string(), self.group_summary(name, now)?)))
+            .collect()
+    }
+}
@@ -0,0 +1,212 @@
+use crate::config::TargetConfig;
+use crate::probe::ProbeSample;
+use crate::runtime::{ProbeSource, RESOLVER_PROFILE_ID};
+use crate::session::{SessionError, load_recording, write_session};
+use std::path::Path;
+
+use super::endpoints::sample_key;
+use super::state::AppState;
+
+/// Samples beyond which quitting asks for confirmation even with one target.
+pub const QUIT_CONFIRM_SAMPLES: usize = 100;
+/// Newest samples saved for `--restore`, so quitting stays quick however
+/// long the run was.
+pub const HISTORY_MAX_SAMPLES: usize = 50_000;
+
+/// What `--restore` needs from a run, copied out of the app so it can be
+/// written off the UI thread.
+pub struct HistorySnapshot {
+    targets: Vec<TargetConfig>,
+    samples: Vec<ProbeSample>,
+}
+
+impl HistorySnapshot {
+    /// Writes the snapshot as a session file at `path`. Returns how many
+    /// samples were written.
+    pub fn write(&self, path: &Path) -> Result<usize, SessionError> {
+        write_session(path, &self.targets, &self.samples)?;
+        Ok(self.samples.len())
+    }
+}
+
+impl AppState {
+    /// Whether quitting would throw away enough to be worth a confirmation:
+    /// more than one target, or more than [`QUIT_CONFIRM_SAMPLES`] samples.
+    /// A replay can simply be played again.
+    pub fn should_confirm_quit(&self) -> bool {
+        !self.replaying
+            && (self.targets.len() > 1 || self.metrics.sample_count() > QUIT_CONFIRM_SAMPLES)
+    }
+
+    /// Whether the samples are saved on exit for `--restore`: only a network
+    /// run's are, since replayed or synthetic demo samples would replace
+    /// real history.
+    pub fn saves_history(&self) -> bool {
+        !self.replaying && matches!(self.probe_source, ProbeSource::Network(_))
+    }
+
+    /// The target configs and the newest [`HISTORY_MAX_SAMPLES`] stored
+    /// samples, oldest first.
+    pub fn history_snapshot(&self) -> HistorySnapshot {
+        self.newest_history(HISTORY_MAX_SAMPLES)
+    }
+
+    fn newest_history(&self, limit: usize) -> HistorySnapshot {
+        let mut samples: Vec<_> = self.metrics.iter_samples().collect();
+        samples.sort_by_key(|sample| sample.ts);
+        let skipped = samples.len().saturating_sub(limit);
+        HistorySnapshot {
+            targets: self.targets.iter().map(|t| t.config.clone()).collect(),
+            samples: samples[skipped..].iter().map(|&s| s.clone()).collect(),
+        }
+    }
+
+    /// Writes [`Self::history_snapshot`] as a session file at `path`.
+    /// Returns how many samples were written.
+    pub fn save_history(&self, path: &Path) -> Result<usize, SessionError> {
+        self.history_snapshot().write(path)
+    }
+
+    /// Loads the samples `save_history` wrote back into the metrics of the
+    /// targets and profiles that still exist, without replaying alerts or
+    /// notifications for them. Returns how many samples were restored.
+    pub fn restore_history(&mut self, path: &Path) -> Result<usize, SessionError> {
+        let recording = load_recording(path)?;
+        let mut restored = 0;
+        for sample in recording.samples {
+            let Some(target) = self
+                .targets
+                .iter_mut()
+                .find(|target| target.config.id == sample.target_id)
+            else {
+                continue;
+            };
+            // Per-IP streams are started afresh; only their metrics come back
+            if sample.profile_id != RESOLVER_PROFILE_ID && sample.endpoint.is_none() {
+                let Some(profile) = target
+                    .profiles
+                    .iter_mut()
+                    .find(|profile| profile.config.id == sample.profile_id)
+                else {
+                    continue;
+                };
+                profile.samples_seen += 1;
+                profile.last_sample = Some(sample.clone());
+            }
+            let key = sample_key(&sample);
+            let sampling = &target.config.sampling;
+            self.metrics.push_sample(
+                key,
+                sample,
+                sampling.max_points_per_window,
+                sampling.retention,
+            );
+            restored += 1;
+        }
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GlobalConfig, TargetConfig, default_profiles};
+    use crate::features::probe::testing::SampleBuilder;
+    use crate::metrics::MetricKind;
+    use url::Url;
+
+    fn replay_app(targets: &[TargetConfig]) -> AppState {
+        let mut app = AppState::new(GlobalConfig::default());
+        app.load_replay(targets.to_vec());
+        app.replaying = false;
+        app
+    }
+
+    #[test]
+    fn quitting_asks_first_with_several_targets_or_much_history() {
+        let target = |host: &str| {
+            TargetConfig::new(
+                Url::parse(&format!("https://{host}")).unwrap(),
+                default_profiles(),
+            )
+        };
+        let mut app = replay_app(&[target("a.example")]);
+        assert!(!app.should_confirm_quit());
+
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        for _ in 0..=QUIT_CONFIRM_SAMPLES {
+            app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        }
+        assert!(app.should_confirm_quit());
+        app.replaying = true;
+        assert!(!app.should_confirm_quit());
+
+        let app = replay_app(&[target("a.example"), target("b.example")]);
+        assert!(app.should_confirm_quit());
+    }
+
+    #[test]
+    fn saved_history_is_restored_into_matching_profiles() {
+        let path =
+            std::env::temp_dir().join(format!("httpulse-history-{}.jsonl", std::process::id()));
+        let config = |host: &str| {
+            TargetConfig::new(
+                Url::parse(&format!("https://{host}")).unwrap(),
+                default_profiles(),
+            )
+        };
+        let (kept, removed) = (config("api.example.com"), config("old.example.com"));
+        let mut app = replay_app(&[kept.clone(), removed.clone()]);
+        for total_ms in [40, 60, 80] {
+            app.apply_sample(
+                SampleBuilder::ok(kept.id, kept.profiles[0].id)
+                    .total_ms(total_ms)
+                    .build(),
+            );
+        }
+        app.apply_sample(SampleBuilder::ok(removed.id, removed.profiles[0].id).build());
+        assert_eq!(app.save_history(&path).unwrap(), 4);
+
+        // Samples of a target deleted since are left out
+        let mut relaunched = replay_app(&[kept]);
+        let restored = relaunched.restore_history(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(restored.unwrap(), 3);
+        let target = &relaunched.targets[0];
+        let profile = &target.profiles[0];
+        assert_eq!(profile.samples_seen, 3);
+        assert!(profile.last_sample.is_some());
+        let total = &relaunched.target_aggregate(target, profile).by_metric[&MetricKind::Total];
+        assert_eq!((total.n, total.max), (3, Some(80.0)));
+    }
+
+    #[test]
+    fn saved_history_keeps_only_the_newest_samples() {
+        let config = TargetConfig::new(
+            Url::parse("https://api.example.com").unwrap(),
+            default_profiles(),
+        );
+        let mut app = replay_app(std::slice::from_ref(&config));
+        let start = std::time::SystemTime::now();
+        for total_ms in [10, 20, 30, 40] {
+            app.apply_sample(
+                SampleBuilder::ok(config.id, config.profiles[0].id)
+                    .at(start + std::time::Duration::from_millis(total_ms))
+                    .total_ms(total_ms)
+                    .build(),
+            );
+        }
+
+        let snapshot = app.newest_history(2);
+        let totals: Vec<_> = snapshot
+            .samples
+            .iter()
+            .map(|sample| sample.t_total.as_millis())
+            .collect();
+        assert_eq!(totals, [30, 40]);
+        assert_eq!(snapshot.targets.len(), 1);
+    }
+}
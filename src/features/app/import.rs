@@ -0,0 +1,157 @@
+use crate::config::{AuthConfig, TargetConfig};
+use crate::probe::ProbeSample;
+use crossbeam_channel::Sender;
+use std::collections::HashSet;
+use std::fmt;
+use url::Url;
+
+use super::parsing::{TargetLine, TargetsFile, parse_profile_specs};
+use super::state::AppState;
+
+/// Outcome of importing a targets file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub invalid_lines: Vec<usize>,
+    /// Lines whose URL is already probed or appeared earlier in the file
+    pub duplicate_lines: Vec<usize>,
+}
+
+fn format_lines(lines: &[usize]) -> String {
+    let numbers = lines
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if lines.len() == 1 {
+        format!("line {numbers}")
+    } else {
+        format!("lines {numbers}")
+    }
+}
+
+/// e.g. "added 10, skipped 2 invalid (lines 3, 7), 1 duplicate (line 9)"
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "added {}", self.added)?;
+        if !self.invalid_lines.is_empty() {
+            write!(
+                f,
+                ", skipped {} invalid ({})",
+                self.invalid_lines.len(),
+                format_lines(&self.invalid_lines)
+            )?;
+        }
+        if !self.duplicate_lines.is_empty() {
+            write!(
+                f,
+                ", {} duplicate ({})",
+                self.duplicate_lines.len(),
+                format_lines(&self.duplicate_lines)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl TargetsFile {
+    /// The lines to add, in order. A URL for which `known` holds, or listed
+    /// earlier in the file, is a duplicate unless `allow_duplicates`.
+    pub fn into_new_lines(
+        self,
+        allow_duplicates: bool,
+        known: impl Fn(&Url) -> bool,
+    ) -> (Vec<TargetLine>, ImportSummary) {
+        let mut summary = ImportSummary {
+            invalid_lines: self.invalid_lines,
+            ..ImportSummary::default()
+        };
+        let mut seen = HashSet::new();
+        let mut lines = Vec::new();
+        for target in self.targets {
+            let repeated = known(&target.url) || !seen.insert(target.url.clone());
+            if repeated && !allow_duplicates {
+                summary.duplicate_lines.push(target.line);
+                continue;
+            }
+            lines.push(target);
+        }
+        summary.added = lines.len();
+        (lines, summary)
+    }
+}
+
+impl TargetLine {
+    /// The line's target, on the default profiles when it names none.
+    pub fn into_config(self) -> TargetConfig {
+        let profiles = self.profiles.unwrap_or_else(|| parse_profile_specs(""));
+        let mut config = TargetConfig::new(self.url, profiles);
+        config.tags = self.tags;
+        config
+    }
+}
+
+impl AppState {
+    /// Adds every target of `file` in order. A URL that is already probed,
+    /// or listed earlier in the file, is skipped unless `allow_duplicates`.
+    pub fn import_targets(
+        &mut self,
+        file: TargetsFile,
+        allow_duplicates: bool,
+        auth: Option<&AuthConfig>,
+        sample_tx: &Sender<ProbeSample>,
+    ) -> ImportSummary {
+        let (lines, summary) = file.into_new_lines(allow_duplicates, |url| {
+            self.targets
+                .iter()
+                .any(|existing| &existing.config.url == url)
+        });
+        for target in lines {
+            self.add_target_with_auth(
+                target.url,
+                target.profiles,
+                auth.cloned(),
+                sample_tx.clone(),
+            );
+            if !target.tags.is_empty()
+                && let Some(added) = self.targets.last()
+            {
+                self.set_target_tags(added.config.id, target.tags);
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::parse_targets_file;
+
+    #[test]
+    fn import_adds_valid_lines_and_skips_duplicates() {
+        let probed = Url::parse("https://a.example/api").unwrap();
+        let known = |url: &Url| *url == probed;
+        let content = "https://a.example/api\n\
+                       https://a.example/web #edge\n\
+                       ftp://a.example/files\n\
+                       https://a.example/web\n";
+
+        let (lines, summary) = parse_targets_file(content).into_new_lines(false, known);
+        assert_eq!(
+            summary.to_string(),
+            "added 1, skipped 1 invalid (line 3), 2 duplicate (lines 1, 4)"
+        );
+        let configs: Vec<TargetConfig> = lines.into_iter().map(TargetLine::into_config).collect();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].url.path(), "/web");
+        assert_eq!(configs[0].tags, vec!["edge"]);
+
+        let (lines, summary) = parse_targets_file(content).into_new_lines(true, known);
+        assert_eq!(summary.added, 3);
+        assert_eq!(
+            lines.iter().map(|line| line.line).collect::<Vec<_>>(),
+            [1, 2, 4]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+use std::net::IpAddr;
+use std::time::SystemTime;
+
+/// Maximum number of address changes retained per target.
+pub const IP_HISTORY_CAPACITY: usize = 32;
+
+/// Lookups an address may be missing from before it stops counting as
+/// resolved, so a DNS answer rotating through a pool does not restart the
+/// per-IP streams on every refresh.
+pub const ENDPOINT_GRACE_LOOKUPS: usize = 3;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IpChange {
+    pub ts: SystemTime,
+    pub from: IpAddr,
+    pub to: IpAddr,
+}
+
+/// Resolved-address history for a target.
+///
+/// The first observed address is not counted as a change; every later
+/// observation that differs from the current address is recorded as a flip.
+#[derive(Clone, Debug, Default)]
+pub struct IpHistory {
+    current: Option<IpAddr>,
+    resolved: Vec<IpAddr>,
+    /// The last [`ENDPOINT_GRACE_LOOKUPS`] answers, newest last
+    recent_answers: VecDeque<Vec<IpAddr>>,
+    /// When a lookup last returned a different address set
+    answer_changed: Option<SystemTime>,
+    changes: VecDeque<IpChange>,
+}
+
+impl IpHistory {
+    /// Records the address used at `ts`, returning the change if it differs
+    /// from the current one.
+    pub fn record(&mut self, ts: SystemTime, ip: IpAddr) -> Option<IpChange> {
+        let previous = self.current.replace(ip)?;
+        if previous == ip {
+            return None;
+        }
+        let change = IpChange {
+            ts,
+            from: previous,
+            to: ip,
+        };
+        self.changes.push_back(change);
+        while self.changes.len() > IP_HISTORY_CAPACITY {
+            self.changes.pop_front();
+        }
+        Some(change)
+    }
+
+    /// Stores the full address set returned by the lookup at `ts`. Returns
+    /// whether it differs from the previous lookup's set, in any order; the
+    /// first lookup is not a change.
+    pub fn set_resolved(&mut self, ts: SystemTime, ips: &[IpAddr]) -> bool {
+        self.recent_answers.push_back(ips.to_vec());
+        while self.recent_answers.len() > ENDPOINT_GRACE_LOOKUPS {
+            self.recent_answers.pop_front();
+        }
+        let mut previous = std::mem::replace(&mut self.resolved, ips.to_vec());
+        let mut current = ips.to_vec();
+        previous.sort();
+        current.sort();
+        let changed = !previous.is_empty() && previous != current;
+        if changed {
+            self.answer_changed = Some(ts);
+        }
+        changed
+    }
+
+    /// When a lookup last returned a different address set.
+    pub fn answer_changed(&self) -> Option<SystemTime> {
+        self.answer_changed
+    }
+
+    pub fn current(&self) -> Option<IpAddr> {
+        self.current
+    }
+
+    pub fn previous(&self) -> Option<IpAddr> {
+        self.changes.back().map(|change| change.from)
+    }
+
+    pub fn last_change(&self) -> Option<SystemTime> {
+        self.changes.back().map(|change| change.ts)
+    }
+
+    pub fn resolved(&self) -> &[IpAddr] {
+        &self.resolved
+    }
+
+    /// Addresses in any of the last [`ENDPOINT_GRACE_LOOKUPS`] answers: the
+    /// latest answer in its order, then those only older answers held.
+    pub fn recently_resolved(&self) -> Vec<IpAddr> {
+        let mut ips = Vec::new();
+        for ip in self.recent_answers.iter().rev().flatten() {
+            if !ips.contains(ip) {
+                ips.push(*ip);
+            }
+        }
+        ips
+    }
+
+    /// Changes recorded at or after `cutoff`, oldest first.
+    pub fn changes_since(&self, cutoff: SystemTime) -> impl Iterator<Item = &IpChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.ts >= cutoff)
+    }
+
+    pub fn flips_since(&self, cutoff: SystemTime) -> usize {
+        self.changes_since(cutoff).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(192, 0, 2, last))
+    }
+
+    #[test]
+    fn first_observation_is_not_a_change() {
+        let mut history = IpHistory::default();
+        assert_eq!(history.record(SystemTime::now(), ip(1)), None);
+        assert_eq!(history.record(SystemTime::now(), ip(1)), None);
+        assert_eq!(history.current(), Some(ip(1)));
+        assert_eq!(history.previous(), None);
+        assert_eq!(history.last_change(), None);
+    }
+
+    #[test]
+    fn records_flips_and_counts_within_cutoff() {
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut history = IpHistory::default();
+        history.record(base, ip(1));
+        let change = history
+            .record(base + Duration::from_secs(10), ip(2))
+            .expect("change");
+        assert_eq!(change.from, ip(1));
+        assert_eq!(change.to, ip(2));
+        history.record(base + Duration::from_secs(20), ip(1));
+
+        assert_eq!(history.current(), Some(ip(1)));
+        assert_eq!(history.previous(), Some(ip(2)));
+        assert_eq!(history.last_change(), Some(base + Duration::from_secs(20)));
+        assert_eq!(history.flips_since(base), 2);
+        assert_eq!(history.flips_since(base + Duration::from_secs(15)), 1);
+    }
+
+    #[test]
+    fn answer_set_changes_ignore_order() {
+        let base = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let mut history = IpHistory::default();
+        assert!(!history.set_resolved(base, &[ip(1), ip(2)]));
+        assert!(!history.set_resolved(base, &[ip(2), ip(1)]));
+        assert_eq!(history.answer_changed(), None);
+
+        let later = base + Duration::from_secs(30);
+        assert!(history.set_resolved(later, &[ip(1), ip(3)]));
+        assert_eq!(history.answer_changed(), Some(later));
+        assert_eq!(history.resolved(), [ip(1), ip(3)]);
+    }
+
+    #[test]
+    fn addresses_stay_recently_resolved_for_the_grace_lookups() {
+        let base = SystemTime::UNIX_EPOCH;
+        let mut history = IpHistory::default();
+        history.set_resolved(base, &[ip(1), ip(2)]);
+        for missed in 1..ENDPOINT_GRACE_LOOKUPS {
+            history.set_resolved(base, &[ip(3), ip(1)]);
+            assert_eq!(
+                history.recently_resolved(),
+                [ip(3), ip(1), ip(2)],
+                "missed {missed}"
+            );
+        }
+        history.set_resolved(base, &[ip(3), ip(1)]);
+        assert_eq!(history.recently_resolved(), [ip(3), ip(1)]);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let base = SystemTime::UNIX_EPOCH;
+        let mut history = IpHistory::default();
+        for idx in 0..(IP_HISTORY_CAPACITY as u64 + 10) {
+            history.record(base + Duration::from_secs(idx), ip((idx % 2) as u8 + 1));
+        }
+        assert_eq!(history.flips_since(base), IP_HISTORY_CAPACITY);
+    }
+}
@@ -1,8 +1,50 @@
+mod annotation;
+mod baseline;
+mod change_log;
+mod endpoints;
+mod error_log;
+mod freshness;
+mod groups;
+mod history;
+mod import;
+mod ip_history;
+mod overview;
 mod parsing;
+mod session_stats;
+mod share;
+mod split;
 mod state;
+mod status;
+mod watchdog;
 
-pub use parsing::{apply_edit_command, parse_profile_specs, parse_target_url};
+pub use annotation::Annotation;
+pub use change_log::{CHANGE_LOG_CAPACITY, ChangeEntry, ChangeLog};
+pub use endpoints::{EndpointRuntime, EndpointView, MAX_ENDPOINT_STREAMS, sample_key};
+pub use error_log::{ERROR_LOG_CAPACITY, ErrorEntry, ErrorLog};
+pub use freshness::{AGING_INTERVALS, Freshness, FreshnessLevel, STALE_INTERVALS};
+pub use groups::{GroupSummary, TargetListRow, TargetStatus};
+pub use history::QUIT_CONFIRM_SAMPLES;
+pub use import::ImportSummary;
+pub use ip_history::{ENDPOINT_GRACE_LOOKUPS, IP_HISTORY_CAPACITY, IpChange, IpHistory};
+pub use overview::{OverviewColumn, OverviewRow, OverviewSort, sort_overview_rows};
+pub use parsing::{
+    AddTargetCommand, TargetError, TargetLine, TargetsFile, apply_edit_command, apply_schedule,
+    format_duration, parse_add_target_command, parse_duration, parse_profile_specs,
+    parse_profile_specs_strict, parse_tags, parse_targets_file, validate_target,
+};
+pub use session_stats::{PROBE_RATE_WINDOW, SessionStats};
+pub use share::{
+    PendingHeader, SHARED_TARGETS_VERSION, ShareError, SharedHeader, SharedImportSummary,
+    SharedTarget, SharedTargets,
+};
+pub use split::{SplitSide, SplitView};
 pub use state::{
-    AppState, GlobalSummary, MetricsCategory, ProfileRuntime, ProfileViewMode, TargetPaneMode,
-    TargetRuntime,
+    AppState, CERT_CRITICAL_DAYS, CERT_WARN_DAYS, ConnReusePair, GlobalSummary, MetricsCategory,
+    ProfileRuntime, ProfileViewMode, TargetPaneMode, TargetRuntime,
+};
+#[cfg(unix)]
+pub use status::install_status_dump_signal;
+pub use status::{
+    STATUS_NOTICE_DURATION, StatusNotice, request_status_dump, take_status_dump_request,
 };
+pub use watchdog::{RESTART_COOLDOWN, RESTART_INTERVALS, STALL_INTERVALS, WorkerHealth};
@@ -0,0 +1,273 @@
+use crate::metrics::MetricKind;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use super::groups::GroupSummary;
+use super::state::AppState;
+
+/// Sortable columns of the multi-target overview table.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OverviewColumn {
+    Name,
+    P50,
+    #[default]
+    P99,
+    Loss,
+    Goodput,
+}
+
+impl OverviewColumn {
+    pub const ALL: [OverviewColumn; 5] = [
+        OverviewColumn::Name,
+        OverviewColumn::P50,
+        OverviewColumn::P99,
+        OverviewColumn::Loss,
+        OverviewColumn::Goodput,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OverviewColumn::Name => "Target",
+            OverviewColumn::P50 => "P50",
+            OverviewColumn::P99 => "P99",
+            OverviewColumn::Loss => "Loss",
+            OverviewColumn::Goodput => "Goodput",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|column| *column == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Direction that puts the worst target first: slow and lossy, or low goodput.
+    fn worst_first_descending(self) -> bool {
+        !matches!(self, OverviewColumn::Name | OverviewColumn::Goodput)
+    }
+}
+
+/// Sort state of the overview; present on `AppState` while the overview is shown.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OverviewSort {
+    pub column: OverviewColumn,
+    pub descending: bool,
+}
+
+impl Default for OverviewSort {
+    fn default() -> Self {
+        let column = OverviewColumn::default();
+        Self {
+            column,
+            descending: column.worst_first_descending(),
+        }
+    }
+}
+
+impl OverviewSort {
+    /// Moves to the next column, starting it in its worst-first direction.
+    pub fn cycle_column(&mut self) {
+        self.column = self.column.next();
+        self.descending = self.column.worst_first_descending();
+    }
+
+    pub fn reverse(&mut self) {
+        self.descending = !self.descending;
+    }
+}
+
+/// One target's headline numbers for the current window, from its selected
+/// profile, or a group's over every member profile.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverviewRow {
+    /// The target, or a group's first member
+    pub index: usize,
+    pub name: String,
+    pub is_group: bool,
+    pub p50: Option<f64>,
+    pub p99: Option<f64>,
+    pub loss: Option<f64>,
+    pub goodput: Option<f64>,
+}
+
+impl OverviewRow {
+    fn value(&self, column: OverviewColumn) -> Option<f64> {
+        match column {
+            OverviewColumn::Name => None,
+            OverviewColumn::P50 => self.p50,
+            OverviewColumn::P99 => self.p99,
+            OverviewColumn::Loss => self.loss,
+            OverviewColumn::Goodput => self.goodput,
+        }
+    }
+}
+
+/// Sorts rows by `sort`; rows without a value always go last.
+pub fn sort_overview_rows(rows: &mut [OverviewRow], sort: OverviewSort) {
+    rows.sort_by(|a, b| {
+        let ordering = match sort.column {
+            OverviewColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            column => match (a.value(column), b.value(column)) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        };
+        if sort.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+impl AppState {
+    pub fn toggle_overview(&mut self) {
+        self.overview = match self.overview {
+            Some(_) => None,
+            None => Some(OverviewSort::default()),
+        };
+    }
+
+    /// Visible targets in overview sort order; empty when the overview is off.
+    pub fn overview_rows(&self) -> Vec<OverviewRow> {
+        let Some(sort) = self.overview else {
+            return Vec::new();
+        };
+        let mut rows: Vec<OverviewRow> = self
+            .visible_targets()
+            .into_iter()
+            .map(|index| {
+                let target = &self.targets[index];
+                let aggregate = target
+                    .profiles
+                    .get(target.selected_profile)
+                    .map(|profile| self.target_aggregate(target, profile));
+                let stat = |metric: MetricKind| {
+                    aggregate
+                        .as_ref()
+                        .and_then(|aggregate| aggregate.by_metric.get(&metric))
+                };
+                OverviewRow {
+                    index,
+                    name: target.config.display_name(),
+                    is_group: false,
+                    p50: stat(MetricKind::Total).and_then(|stats| stats.p50),
+                    p99: stat(MetricKind::Total).and_then(|stats| stats.p99),
+                    loss: stat(MetricKind::ProbeLossRate).and_then(|stats| stats.mean),
+                    goodput: stat(MetricKind::GoodputBps).and_then(|stats| stats.mean),
+                }
+            })
+            .collect();
+        sort_overview_rows(&mut rows, sort);
+        rows
+    }
+
+    /// One row per group of visible targets, in overview sort order, from
+    /// the frame's group summaries; empty when the overview is off.
+    pub fn overview_group_rows(&self, groups: &BTreeMap<String, GroupSummary>) -> Vec<OverviewRow> {
+        let Some(sort) = self.overview else {
+            return Vec::new();
+        };
+        let mut firsts: Vec<(&str, usize)> = Vec::new();
+        for index in self.visible_targets() {
+            if let Some(name) = self.targets[index].group()
+                && !firsts.iter().any(|(group, _)| *group == name)
+            {
+                firsts.push((name, index));
+            }
+        }
+        let mut rows: Vec<OverviewRow> = firsts
+            .into_iter()
+            .filter_map(|(name, index)| {
+                let summary = groups.get(name)?;
+                Some(OverviewRow {
+                    index,
+                    name: format!("#{name} ({})", summary.targets),
+                    is_group: true,
+                    p50: summary.p50_ms,
+                    p99: summary.p99_ms,
+                    loss: summary.loss_rate,
+                    goodput: summary.goodput_bps,
+                })
+            })
+            .collect();
+        sort_overview_rows(&mut rows, sort);
+        rows
+    }
+
+    /// Moves the selection to the next (`down`) or previous row in overview order.
+    pub fn select_overview_neighbour(&mut self, down: bool) {
+        let rows = self.overview_rows();
+        let Some(position) = rows
+            .iter()
+            .position(|row| row.index == self.selected_target)
+        else {
+            if let Some(row) = rows.first() {
+                self.selected_target = row.index;
+            }
+            return;
+        };
+        let next = if down {
+            rows.get(position + 1)
+        } else {
+            position.checked_sub(1).and_then(|idx| rows.get(idx))
+        };
+        if let Some(row) = next {
+            self.selected_target = row.index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(index: usize, name: &str, p99: Option<f64>, goodput: Option<f64>) -> OverviewRow {
+        OverviewRow {
+            index,
+            name: name.to_string(),
+            is_group: false,
+            p50: None,
+            p99,
+            loss: None,
+            goodput,
+        }
+    }
+
+    #[test]
+    fn default_sort_puts_slowest_first_and_missing_last() {
+        let mut rows = vec![
+            row(0, "a", Some(40.0), None),
+            row(1, "b", None, None),
+            row(2, "c", Some(900.0), None),
+        ];
+        sort_overview_rows(&mut rows, OverviewSort::default());
+        let order: Vec<usize> = rows.iter().map(|row| row.index).collect();
+        assert_eq!(order, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn cycling_to_goodput_sorts_lowest_first_and_reverse_flips() {
+        let mut sort = OverviewSort::default();
+        while sort.column != OverviewColumn::Goodput {
+            sort.cycle_column();
+        }
+        let mut rows = vec![
+            row(0, "a", None, Some(80.0)),
+            row(1, "b", None, Some(5.0)),
+            row(2, "c", None, None),
+        ];
+        sort_overview_rows(&mut rows, sort);
+        assert_eq!(rows[0].index, 1);
+        assert_eq!(rows[2].index, 2);
+
+        sort.reverse();
+        sort_overview_rows(&mut rows, sort);
+        assert_eq!(rows[0].index, 0);
+        assert_eq!(rows[2].index, 2);
+    }
+}
@@ -1,66 +1,142 @@
 use super::state::TargetRuntime;
 use crate::config::{
-    ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig, TlsVersion,
-    default_profiles_for_capabilities,
+    ConnReusePolicy, CronScheduleError, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig,
+    TlsVersion, default_profiles_for_capabilities,
 };
-use crate::probe_engine::detect_tls13_support;
+use crate::probe_engine::{detect_h2_support, tls13_support};
+use std::path::PathBuf;
+use url::Url;
 
-pub use crate::common::net::parse_target_url;
+pub use crate::common::net::{TargetError, validate_target};
 
 pub fn parse_profile_specs(input: &str) -> Vec<ProfileConfig> {
-    let mut profiles = Vec::new();
-    for raw in input.split(',').map(str::trim).filter(|s| !s.is_empty()) {
-        if let Some(profile) = parse_profile_spec(raw) {
-            profiles.push(profile);
-        }
-    }
+    parse_profile_specs_strict(input).0
+}
+
+/// Like `parse_profile_specs`, but also returns every token that was not
+/// understood (e.g. `h3`, `i=soon`) instead of silently ignoring it.
+pub fn parse_profile_specs_strict(input: &str) -> (Vec<ProfileConfig>, Vec<String>) {
+    let mut unknown = Vec::new();
+    let profiles: Vec<_> = input
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|raw| parse_profile_spec(raw, &mut unknown))
+        .collect();
     if profiles.is_empty() {
-        // Auto-detect TLS 1.3 support and include it if available
-        default_profiles_for_capabilities(detect_tls13_support())
+        // Include TLS 1.3 once detection has confirmed it; never wait for it
+        let defaults =
+            default_profiles_for_capabilities(tls13_support() == Some(true), detect_h2_support());
+        (defaults, unknown)
     } else {
-        profiles
+        (profiles, unknown)
     }
 }
 
-fn parse_profile_spec(input: &str) -> Option<ProfileConfig> {
+fn parse_profile_spec(input: &str, unknown: &mut Vec<String>) -> ProfileConfig {
     let mut http = None;
     let mut tls = None;
     let mut reuse = None;
     let mut method = None;
     let mut max_bytes = None;
+    let mut interval_override = None;
+    let mut expected_status = None;
+    let mut expect_body_contains = None;
 
-    for token in input.split('+').map(str::trim) {
+    for token in input.split('+').map(str::trim).filter(|s| !s.is_empty()) {
         match token {
             "h1" | "http1" | "http1.1" => http = Some(HttpVersion::H1),
             "h2" | "http2" => http = Some(HttpVersion::H2),
+            "h1.0" | "http1.0" => http = Some(HttpVersion::H1_0),
+            "h2c" => http = Some(HttpVersion::H2PriorKnowledge),
             "tls12" | "tls1.2" => tls = Some(TlsVersion::Tls12),
             "tls13" | "tls1.3" => tls = Some(TlsVersion::Tls13),
             "warm" => reuse = Some(ConnReusePolicy::Warm),
             "cold" => reuse = Some(ConnReusePolicy::Cold),
             "head" => method = Some(ProbeMethod::Head),
             "get" => method = Some(ProbeMethod::Get),
+            "ping" => method = Some(ProbeMethod::Ping),
             _ => {
-                if let Ok(bytes) = token.parse::<u32>() {
+                if let Some(value) = token
+                    .strip_prefix("i=")
+                    .or_else(|| token.strip_prefix("interval="))
+                {
+                    interval_override = parse_duration(value).filter(|d| !d.is_zero());
+                    if interval_override.is_none() {
+                        unknown.push(token.to_string());
+                    }
+                } else if let Some(value) = token.strip_prefix("status=") {
+                    expected_status = value.parse().ok();
+                    if expected_status.is_none() {
+                        unknown.push(token.to_string());
+                    }
+                } else if let Some(value) = token.strip_prefix("contains=") {
+                    expect_body_contains =
+                        Some(value.to_string()).filter(|value| !value.is_empty());
+                } else if let Ok(bytes) = token.parse::<u32>() {
                     max_bytes = Some(bytes);
+                } else {
+                    unknown.push(token.to_string());
                 }
             }
         }
     }
 
-    Some(ProfileConfig::new(
+    let mut profile = ProfileConfig::new(
         input,
         http.unwrap_or(HttpVersion::H2),
         tls.unwrap_or(TlsVersion::Tls13),
         reuse.unwrap_or(ConnReusePolicy::Warm),
         method.unwrap_or(ProbeMethod::Get),
         max_bytes.unwrap_or(4096),
-    ))
+    );
+    profile.interval_override = interval_override;
+    profile.expected_status = expected_status;
+    profile.expect_body_contains = expect_body_contains;
+    profile
+}
+
+/// The Add Target prompt, `<url> [profiles] [#tag ...]`, parsed as far as it
+/// goes so it can be previewed while typing.
+#[derive(Debug)]
+pub struct AddTargetCommand {
+    pub url: Result<Url, TargetError>,
+    /// `None` when no profiles were given, so the target gets the defaults
+    pub profiles: Option<Vec<ProfileConfig>>,
+    /// Profile tokens that were not understood
+    pub unknown_tokens: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+pub fn parse_add_target_command(input: &str) -> AddTargetCommand {
+    let mut parts = input.split_whitespace();
+    let url = validate_target(parts.next().unwrap_or_default());
+    let (tags, specs): (Vec<&str>, Vec<&str>) = parts.partition(|part| part.starts_with('#'));
+    // Space-separated specs are separate profiles, like comma-separated ones
+    let specs = specs.join(",");
+    let (profiles, unknown_tokens) = if specs.is_empty() {
+        (None, Vec::new())
+    } else {
+        let (profiles, unknown) = parse_profile_specs_strict(&specs);
+        (Some(profiles), unknown)
+    };
+    AddTargetCommand {
+        url,
+        profiles,
+        unknown_tokens,
+        tags: parse_tags(&tags.join(" ")),
+    }
 }
 
 pub fn apply_edit_command(target: &TargetRuntime, input: &str) -> Option<TargetConfig> {
     let mut updated = target.config.clone();
     let mut modified = false;
-    for token in input.split_whitespace() {
+    // Cron expressions contain spaces, so `schedule=` takes the rest of the input
+    let (fields, schedule) = match input.split_once("schedule=") {
+        Some((fields, schedule)) => (fields, Some(schedule)),
+        None => (input, None),
+    };
+    for token in fields.split_whitespace() {
         if let Some(value) = token.strip_prefix("interval=") {
             if let Some(duration) = parse_duration(value) {
                 updated.interval = duration;
@@ -81,37 +157,205 @@ pub fn apply_edit_command(target: &TargetRuntime, input: &str) -> Option<TargetC
                     updated.dns_enabled = false;
                     modified = true;
                 }
+                "worker" | "combined" => {
+                    updated.dns_enabled = true;
+                    updated.dns_combined = value == "combined";
+                    modified = true;
+                }
+                _ => {}
+            }
+        } else if let Some(value) = token.strip_prefix("dns_refresh=") {
+            if let Some(duration) = parse_duration(value).filter(|d| !d.is_zero()) {
+                updated.dns_refresh = duration;
+                modified = true;
+            }
+        } else if let Some(value) = token.strip_prefix("insecure=") {
+            match value {
+                "on" | "true" => {
+                    updated.insecure = true;
+                    modified = true;
+                }
+                "off" | "false" => {
+                    updated.insecure = false;
+                    modified = true;
+                }
                 _ => {}
             }
+        } else if let Some(value) = token.strip_prefix("per_ip=") {
+            match value {
+                "on" | "true" => {
+                    updated.per_ip = true;
+                    modified = true;
+                }
+                "off" | "false" => {
+                    updated.per_ip = false;
+                    modified = true;
+                }
+                _ => {}
+            }
+        } else if let Some(value) = token.strip_prefix("unix=") {
+            updated.unix_socket = match value {
+                "" | "off" => None,
+                path => Some(PathBuf::from(path)),
+            };
+            modified = true;
+        } else if let Some(value) = token.strip_prefix("connect_to=") {
+            if value.is_empty() || value == "off" {
+                updated.connect_to = None;
+                modified = true;
+            } else if let Ok(connect_to) = value.parse() {
+                updated.connect_to = Some(connect_to);
+                modified = true;
+            }
         }
     }
 
+    if let Some(value) = schedule {
+        // A bare `schedule=` must not swallow the tokens after it
+        if value.is_empty() || value.starts_with(char::is_whitespace) {
+            return None;
+        }
+        apply_schedule(&mut updated, value).ok()?;
+        modified = true;
+    }
+
     if modified { Some(updated) } else { None }
 }
 
-fn parse_duration(input: &str) -> Option<std::time::Duration> {
+/// Applies a `schedule=` value: a non-zero interval such as `30s` (dropping
+/// any cron schedule), `off`, or a cron expression.
+pub fn apply_schedule(config: &mut TargetConfig, value: &str) -> Result<(), CronScheduleError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(CronScheduleError::Empty);
+    } else if value.eq_ignore_ascii_case("off") {
+        config.schedule = None;
+    } else if let Some(interval) = parse_duration(value) {
+        if interval.is_zero() {
+            return Err(CronScheduleError::ZeroInterval);
+        }
+        config.interval = interval;
+        config.schedule = None;
+    } else {
+        config.schedule = Some(value.parse()?);
+    }
+    Ok(())
+}
+
+/// Tags from a space- or comma-separated list; a leading `#` is optional and
+/// repeats are dropped.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input
+        .split(|ch: char| ch.is_whitespace() || ch == ',')
+        .map(|tag| tag.trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Reads `250ms`, `30s`, `5m`, `2h` or bare seconds; `None` when malformed
+/// or too large to represent.
+pub fn parse_duration(input: &str) -> Option<std::time::Duration> {
     if let Some(value) = input.strip_suffix("ms") {
         value
             .parse::<u64>()
             .ok()
             .map(std::time::Duration::from_millis)
-    } else if let Some(value) = input.strip_suffix('s') {
+    } else {
+        let (value, unit) = if let Some(value) = input.strip_suffix('s') {
+            (value, 1)
+        } else if let Some(value) = input.strip_suffix('m') {
+            (value, 60)
+        } else if let Some(value) = input.strip_suffix('h') {
+            (value, 3600)
+        } else {
+            (input, 1)
+        };
         value
             .parse::<u64>()
-            .ok()
+            .ok()?
+            .checked_mul(unit)
             .map(std::time::Duration::from_secs)
+    }
+}
+
+/// Duration in the largest unit `parse_duration` reads back exactly.
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let secs = duration.as_secs();
+    if duration.subsec_millis() != 0 {
+        format!("{}ms", duration.as_millis())
+    } else if secs == 0 {
+        "0s".to_string()
+    } else if secs.is_multiple_of(3600) {
+        format!("{}h", secs / 3600)
+    } else if secs.is_multiple_of(60) {
+        format!("{}m", secs / 60)
     } else {
-        input
-            .parse::<u64>()
-            .ok()
-            .map(std::time::Duration::from_secs)
+        format!("{secs}s")
     }
 }
 
+/// A usable line of a targets file.
+#[derive(Debug)]
+pub struct TargetLine {
+    /// 1-based line number, for reporting
+    pub line: usize,
+    pub url: Url,
+    pub profiles: Option<Vec<ProfileConfig>>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct TargetsFile {
+    pub targets: Vec<TargetLine>,
+    /// Lines with an unusable URL or profile tokens that were not understood
+    pub invalid_lines: Vec<usize>,
+}
+
+/// Parses a targets file: one `<url> [profiles] [#tag ...]` per line, as in
+/// the Add Target prompt. Blank lines and lines starting with `#` are
+/// skipped, and a `#` followed by a space starts a trailing comment.
+pub fn parse_targets_file(content: &str) -> TargetsFile {
+    let mut file = TargetsFile::default();
+    for (index, raw) in content.lines().enumerate() {
+        let line = raw
+            .split_once(" # ")
+            .or_else(|| raw.split_once("\t# "))
+            .map_or(raw, |(line, _)| line)
+            .trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let command = parse_add_target_command(line);
+        match command.url {
+            Ok(url) if command.unknown_tokens.is_empty() => file.targets.push(TargetLine {
+                line: index + 1,
+                url,
+                profiles: command.profiles,
+                tags: command.tags,
+            }),
+            _ => file.invalid_lines.push(index + 1),
+        }
+    }
+    file
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::{MetricsCategory, ProfileViewMode, TargetPaneMode};
+    use crate::alerts::NotifyState;
+    use crate::app::{
+        EndpointView, ErrorLog, IpHistory, MetricsCategory, ProfileViewMode, TargetPaneMode,
+    };
+    use crate::config::ExpectedStatus;
+    use crate::metrics::MetricKind;
+    use crate::runtime::DnsCache;
+    use std::collections::HashSet;
     use std::time::Duration;
     use url::Url;
 
@@ -127,18 +371,137 @@ mod tests {
         assert_eq!(profile.max_read_bytes, 128);
     }
 
+    #[test]
+    fn parse_profile_spec_accepts_cleartext_versions() {
+        let profiles = parse_profile_specs("h1.0+cold, h2c+warm");
+        assert_eq!(profiles[0].http, HttpVersion::H1_0);
+        assert_eq!(profiles[1].http, HttpVersion::H2PriorKnowledge);
+        assert_eq!(profiles[1].http.to_string(), "h2c");
+        assert_eq!(
+            serde_json::to_string(&profiles[0].http).unwrap(),
+            "\"h1.0\""
+        );
+    }
+
+    #[test]
+    fn parse_profile_specs_strict_reports_unknown_tokens() {
+        let (profiles, unknown) = parse_profile_specs_strict("h3+warm, h1+i=soon+status=ok");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[1].http, HttpVersion::H1);
+        assert_eq!(unknown, vec!["h3", "i=soon", "status=ok"]);
+        assert!(parse_profile_specs_strict("h2+tls13+i=5s").1.is_empty());
+    }
+
+    #[test]
+    fn parse_add_target_command_splits_url_profiles_and_tags() {
+        let command = parse_add_target_command("api.example.com h1+cold h2+bogus #prod");
+        assert_eq!(command.url.unwrap().as_str(), "https://api.example.com/");
+        assert_eq!(command.profiles.unwrap().len(), 2);
+        assert_eq!(command.tags, vec!["prod"]);
+        assert_eq!(command.unknown_tokens, vec!["bogus"]);
+
+        let command = parse_add_target_command("htps://foo");
+        assert!(matches!(
+            command.url,
+            Err(TargetError::UnsupportedScheme { .. })
+        ));
+        assert!(command.profiles.is_none());
+        assert_eq!(
+            parse_add_target_command("").url.unwrap_err(),
+            TargetError::Empty
+        );
+    }
+
+    #[test]
+    fn parse_targets_file_skips_comments_and_reports_invalid_lines() {
+        let file = parse_targets_file(
+            "# incident hosts\n\
+             \n\
+             api.example.com\n\
+             htps://typo.example.com\n\
+             https://web.example.com h1+cold, h2+warm #edge # trailing note\n\
+             \t\n\
+             https://db.example.com h9\n",
+        );
+        let lines: Vec<_> = file.targets.iter().map(|target| target.line).collect();
+        assert_eq!(lines, vec![3, 5]);
+        assert_eq!(file.invalid_lines, vec![4, 7]);
+
+        assert_eq!(file.targets[0].url.as_str(), "https://api.example.com/");
+        assert!(file.targets[0].profiles.is_none());
+        let web = &file.targets[1];
+        let profiles = web.profiles.as_ref().expect("profiles");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].conn_reuse, ConnReusePolicy::Cold);
+        assert_eq!(web.tags, vec!["edge"]);
+    }
+
+    #[test]
+    fn parse_profile_spec_accepts_ping() {
+        let profiles = parse_profile_specs("h2+tls13+warm, ping");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[1].method, ProbeMethod::Ping);
+        assert_eq!(profiles[1].name, "ping");
+    }
+
+    #[test]
+    fn parse_profile_spec_reads_validation_tokens() {
+        let profiles = parse_profile_specs("h2+status=204+contains=ok, h1+status=2xx");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(
+            profiles[0].expected_status,
+            Some(ExpectedStatus::exact(204))
+        );
+        assert_eq!(profiles[0].expect_body_contains.as_deref(), Some("ok"));
+        assert_eq!(
+            profiles[1].expected_status.map(|status| status.to_string()),
+            Some("2xx".to_string())
+        );
+        assert_eq!(profiles[1].expect_body_contains, None);
+    }
+
+    #[test]
+    fn parse_profile_spec_reads_interval_override() {
+        let profiles = parse_profile_specs("h2+tls13+warm+i=2s, h1+tls12+cold");
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].interval_override, Some(Duration::from_secs(2)));
+        assert_eq!(profiles[1].interval_override, None);
+
+        let (profiles, unknown) = parse_profile_specs_strict("h2+i=0s");
+        assert_eq!(profiles[0].interval_override, None);
+        assert_eq!(unknown, vec!["i=0s"]);
+    }
+
     #[test]
     fn apply_edit_command_updates_target() {
         let url = Url::parse("https://google.com").unwrap();
         let target = TargetRuntime {
-            config: TargetConfig::new(url, default_profiles_for_capabilities(false)),
+            config: TargetConfig::new(url, default_profiles_for_capabilities(false, true)),
             paused: false,
-            last_ip: None,
+            ip_history: IpHistory::default(),
             profiles: Vec::new(),
             view_mode: ProfileViewMode::Single,
             selected_profile: 0,
             pane_mode: TargetPaneMode::Split,
             metrics_category: MetricsCategory::default(),
+            selected_metric: MetricKind::Total,
+            selected_metrics: HashSet::from([MetricKind::Total]),
+            alert_states: Vec::new(),
+            chart_cursor: None,
+            traceroute: None,
+            error_log: ErrorLog::default(),
+            config_changes: Vec::new(),
+            cert: None,
+            baselines: Vec::new(),
+            annotations: Vec::new(),
+            active_baseline: None,
+            notify_state: NotifyState::default(),
+            burst_until: None,
+            dns_cache: DnsCache::default(),
+            dns_worker: None,
+            endpoints: Vec::new(),
+            endpoint_view: EndpointView::default(),
+            window_override: None,
         };
 
         let updated =
@@ -146,26 +509,158 @@ mod tests {
         assert_eq!(updated.interval, std::time::Duration::from_secs(3));
         assert_eq!(updated.timeout_total, std::time::Duration::from_secs(7));
         assert!(!updated.dns_enabled);
+
+        let updated = apply_edit_command(&target, "dns=combined dns_refresh=1m").expect("update");
+        assert!(updated.dns_enabled && updated.dns_combined);
+        assert!(!updated.uses_dns_worker());
+        assert_eq!(updated.dns_refresh, std::time::Duration::from_secs(60));
+        let updated = apply_edit_command(&target, "dns=worker").expect("update");
+        assert!(updated.uses_dns_worker());
+        assert!(apply_edit_command(&target, "dns_refresh=0s").is_none());
+
+        let updated = apply_edit_command(&target, "insecure=on").expect("update");
+        assert!(updated.insecure);
+        assert!(apply_edit_command(&target, "insecure=maybe").is_none());
+
+        let updated = apply_edit_command(&target, "per_ip=on").expect("update");
+        assert!(updated.per_ip && updated.probes_per_ip());
+        assert!(apply_edit_command(&target, "per_ip=maybe").is_none());
     }
 
     #[test]
-    fn parse_target_url_adds_default_scheme() {
-        let url = parse_target_url("google.com").expect("url should parse");
-        assert_eq!(url.scheme(), "https");
-        assert_eq!(url.host_str(), Some("google.com"));
+    fn apply_edit_command_sets_interval_or_cron_schedule() {
+        let url = Url::parse("https://google.com").unwrap();
+        let mut target = TargetRuntime {
+            config: TargetConfig::new(url, default_profiles_for_capabilities(false, true)),
+            paused: false,
+            ip_history: IpHistory::default(),
+            profiles: Vec::new(),
+            view_mode: ProfileViewMode::Single,
+            selected_profile: 0,
+            pane_mode: TargetPaneMode::Split,
+            metrics_category: MetricsCategory::default(),
+            selected_metric: MetricKind::Total,
+            selected_metrics: HashSet::from([MetricKind::Total]),
+            alert_states: Vec::new(),
+            chart_cursor: None,
+            traceroute: None,
+            error_log: ErrorLog::default(),
+            config_changes: Vec::new(),
+            cert: None,
+            baselines: Vec::new(),
+            annotations: Vec::new(),
+            active_baseline: None,
+            notify_state: NotifyState::default(),
+            burst_until: None,
+            dns_cache: DnsCache::default(),
+            dns_worker: None,
+            endpoints: Vec::new(),
+            endpoint_view: EndpointView::default(),
+            window_override: None,
+        };
+
+        let updated = apply_edit_command(&target, "dns=off schedule=*/1 * 9-17 * * MON-FRI")
+            .expect("should update");
+        assert!(!updated.dns_enabled);
+        assert_eq!(
+            updated
+                .schedule
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("*/1 * 9-17 * * MON-FRI")
+        );
+
+        target.config = updated;
+        let updated = apply_edit_command(&target, "schedule=30s").expect("should update");
+        assert_eq!(updated.interval, Duration::from_secs(30));
+        assert_eq!(updated.schedule, None);
+
+        assert!(apply_edit_command(&target, "schedule=* 25 * * *").is_none());
+        assert!(apply_edit_command(&target, "schedule=0s").is_none());
+        assert!(apply_edit_command(&target, "schedule=").is_none());
+        assert!(apply_edit_command(&target, "schedule= interval=10s").is_none());
     }
 
     #[test]
-    fn parse_target_url_accepts_host_and_port() {
-        let url = parse_target_url("localhost:8080").expect("url should parse");
-        assert_eq!(url.scheme(), "https");
-        assert_eq!(url.host_str(), Some("localhost"));
-        assert_eq!(url.port_or_known_default(), Some(8080));
+    fn apply_schedule_rejects_zero_and_empty_values() {
+        let url = Url::parse("https://google.com").unwrap();
+        let mut config = TargetConfig::new(url, default_profiles_for_capabilities(false, true));
+        config.schedule = Some("*/5 * * * *".parse().unwrap());
+        let before = config.clone();
+
+        assert_eq!(
+            apply_schedule(&mut config, "0s"),
+            Err(CronScheduleError::ZeroInterval)
+        );
+        assert_eq!(
+            apply_schedule(&mut config, "0ms"),
+            Err(CronScheduleError::ZeroInterval)
+        );
+        assert_eq!(
+            apply_schedule(&mut config, "  "),
+            Err(CronScheduleError::Empty)
+        );
+        assert_eq!(config.interval, before.interval);
+        assert_eq!(config.schedule, before.schedule);
+
+        assert_eq!(apply_schedule(&mut config, "off"), Ok(()));
+        assert_eq!(config.schedule, None);
     }
 
     #[test]
-    fn parse_target_url_rejects_empty_input() {
-        assert!(parse_target_url("   ").is_none());
+    fn apply_edit_command_sets_and_clears_transport_overrides() {
+        let url = Url::parse("http://api.internal/health").unwrap();
+        let mut target = TargetRuntime {
+            config: TargetConfig::new(url, default_profiles_for_capabilities(false, true)),
+            paused: false,
+            ip_history: IpHistory::default(),
+            profiles: Vec::new(),
+            view_mode: ProfileViewMode::Single,
+            selected_profile: 0,
+            pane_mode: TargetPaneMode::Split,
+            metrics_category: MetricsCategory::default(),
+            selected_metric: MetricKind::Total,
+            selected_metrics: HashSet::from([MetricKind::Total]),
+            alert_states: Vec::new(),
+            chart_cursor: None,
+            traceroute: None,
+            error_log: ErrorLog::default(),
+            config_changes: Vec::new(),
+            cert: None,
+            baselines: Vec::new(),
+            annotations: Vec::new(),
+            active_baseline: None,
+            notify_state: NotifyState::default(),
+            burst_until: None,
+            dns_cache: DnsCache::default(),
+            dns_worker: None,
+            endpoints: Vec::new(),
+            endpoint_view: EndpointView::default(),
+            window_override: None,
+        };
+
+        let updated = apply_edit_command(&target, "unix=/run/app.sock connect_to=10.0.0.5:8080")
+            .expect("should update");
+        assert_eq!(
+            updated.unix_socket.as_deref(),
+            Some(std::path::Path::new("/run/app.sock"))
+        );
+        assert_eq!(
+            updated
+                .connect_to
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some("10.0.0.5:8080")
+        );
+
+        target.config = updated;
+        assert!(apply_edit_command(&target, "connect_to=nope").is_none());
+        let updated =
+            apply_edit_command(&target, "unix=off connect_to=off").expect("should update");
+        assert_eq!(updated.unix_socket, None);
+        assert_eq!(updated.connect_to, None);
     }
 
     #[test]
@@ -173,25 +668,71 @@ mod tests {
         assert_eq!(parse_duration("150ms"), Some(Duration::from_millis(150)));
         assert_eq!(parse_duration("2s"), Some(Duration::from_secs(2)));
         assert_eq!(parse_duration("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_duration("90m"), Some(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(2 * 3600)));
+    }
+
+    #[test]
+    fn parse_tags_strips_hashes_and_repeats() {
+        assert_eq!(
+            parse_tags("#prod #eu, prod  ##db"),
+            vec!["prod", "eu", "db"]
+        );
+        assert!(parse_tags(" # , ").is_empty());
     }
 
     #[test]
     fn parse_duration_rejects_invalid_values() {
         assert!(parse_duration("invalid").is_none());
+        // Too large for whole seconds once scaled, rather than wrapping
+        assert!(parse_duration("999999999999999999h").is_none());
+        assert!(parse_duration(&format!("{}m", u64::MAX / 30)).is_none());
+    }
+
+    #[test]
+    fn format_duration_reads_back_through_parse_duration() {
+        for duration in [
+            Duration::ZERO,
+            Duration::from_millis(250),
+            Duration::from_secs(45),
+            Duration::from_secs(300),
+            Duration::from_secs(7200),
+        ] {
+            assert_eq!(parse_duration(&format_duration(duration)), Some(duration));
+        }
+        assert_eq!(format_duration(Duration::ZERO), "0s");
     }
 
     #[test]
     fn apply_edit_command_returns_none_when_no_updates() {
         let url = Url::parse("https://google.com").unwrap();
         let target = TargetRuntime {
-            config: TargetConfig::new(url, default_profiles_for_capabilities(false)),
+            config: TargetConfig::new(url, default_profiles_for_capabilities(false, true)),
             paused: false,
-            last_ip: None,
+            ip_history: IpHistory::default(),
             profiles: Vec::new(),
             view_mode: ProfileViewMode::Single,
             selected_profile: 0,
             pane_mode: TargetPaneMode::Split,
             metrics_category: MetricsCategory::default(),
+            selected_metric: MetricKind::Total,
+            selected_metrics: HashSet::from([MetricKind::Total]),
+            alert_states: Vec::new(),
+            chart_cursor: None,
+            traceroute: None,
+            error_log: ErrorLog::default(),
+            config_changes: Vec::new(),
+            cert: None,
+            baselines: Vec::new(),
+            annotations: Vec::new(),
+            active_baseline: None,
+            notify_state: NotifyState::default(),
+            burst_until: None,
+            dns_cache: DnsCache::default(),
+            dns_worker: None,
+            endpoints: Vec::new(),
+            endpoint_view: EndpointView::default(),
+            window_override: None,
         };
 
         assert!(apply_edit_command(&target, "foo=bar dns=maybe").is_none());
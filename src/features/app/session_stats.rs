@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+/// Span over which the header's probes-per-second rate is averaged.
+pub const PROBE_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Running totals for the whole session, kept apart from the metrics store
+/// so they survive retention and cost nothing to read.
+#[derive(Clone, Debug)]
+pub struct SessionStats {
+    started: Instant,
+    pub probes: u64,
+    pub successes: u64,
+    /// Start and probe count of the rate bucket being filled
+    bucket_start: Instant,
+    bucket_probes: u64,
+    /// Rate of the last completed bucket
+    last_rate: Option<f64>,
+}
+
+impl SessionStats {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            probes: 0,
+            successes: 0,
+            bucket_start: now,
+            bucket_probes: 0,
+            last_rate: None,
+        }
+    }
+
+    pub fn record(&mut self, ok: bool, now: Instant) {
+        self.probes += 1;
+        if ok {
+            self.successes += 1;
+        }
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        if elapsed >= PROBE_RATE_WINDOW {
+            self.last_rate = Some(self.bucket_probes as f64 / elapsed.as_secs_f64());
+            self.bucket_start = now;
+            self.bucket_probes = 0;
+        }
+        self.bucket_probes += 1;
+    }
+
+    /// Share of probes that succeeded, in percent; `None` before the first.
+    pub fn success_pct(&self) -> Option<f64> {
+        (self.probes > 0).then(|| self.successes as f64 / self.probes as f64 * 100.0)
+    }
+
+    pub fn uptime(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+    }
+
+    /// Probes per second across all workers. A bucket that has run past its
+    /// window without being closed counts on its own, so the rate decays
+    /// to zero once probing stops.
+    pub fn probes_per_sec(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.bucket_start);
+        match self.last_rate {
+            Some(rate) if elapsed < PROBE_RATE_WINDOW => rate,
+            _ => self.bucket_probes as f64 / elapsed.max(Duration::from_secs(1)).as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_successes_and_errors() {
+        let start = Instant::now();
+        let mut stats = SessionStats::new(start);
+        assert_eq!(stats.success_pct(), None);
+
+        for _ in 0..3 {
+            stats.record(true, start);
+        }
+        stats.record(false, start);
+        assert_eq!(stats.probes, 4);
+        assert_eq!(stats.successes, 3);
+        assert_eq!(stats.success_pct(), Some(75.0));
+        assert_eq!(
+            stats.uptime(start + Duration::from_secs(90)),
+            Duration::from_secs(90)
+        );
+    }
+
+    #[test]
+    fn rate_averages_the_last_window_and_decays_when_idle() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut stats = SessionStats::new(start);
+
+        // Two probes a second for the first window
+        for half in 0..20 {
+            stats.record(true, start + Duration::from_millis(half * 500));
+        }
+        assert_eq!(stats.probes_per_sec(at(5)), 20.0 / 5.0);
+        stats.record(true, at(10));
+        assert_eq!(stats.probes_per_sec(at(12)), 2.0);
+
+        // Nothing arrives: once the open bucket is a window old it takes over
+        assert_eq!(stats.probes_per_sec(at(20)), 0.1);
+        assert_eq!(stats.probes_per_sec(at(110)), 0.01);
+    }
+}
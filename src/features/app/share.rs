@@ -0,0 +1,484 @@
+use crate::config::{
+    AuthConfig, ProfileConfig, ProfileId, SECRET_PLACEHOLDER, SecretString, TargetConfig, TargetId,
+};
+use crate::probe::ProbeSample;
+use crossbeam_channel::Sender;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use thiserror::Error;
+use url::Url;
+use uuid::Uuid;
+
+use super::parsing::{format_duration, parse_duration, parse_profile_specs_strict};
+use super::state::AppState;
+
+/// Version written to exported documents; newer ones are refused on import.
+/// Version 1 documents, with profiles as token strings, are still read.
+/// It versions the document layout on its own rather than following
+/// [`crate::data_model::SCHEMA_VERSION`]: a shared document holds only the
+/// hand-editable subset of a target, which changes on a different schedule.
+pub const SHARED_TARGETS_VERSION: u32 = 2;
+
+/// Target definitions in a form teammates can pass around: no history and no
+/// secrets, and every id is replaced on import.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedTargets {
+    pub version: u32,
+    pub targets: Vec<SharedTarget>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedTarget {
+    pub url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Durations as the edit prompt takes them, e.g. `30s` or `500ms`
+    pub interval: String,
+    pub timeout: String,
+    pub dns: bool,
+    pub profiles: Vec<SharedProfile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<SharedHeader>,
+}
+
+/// A profile as written to the state file, without header values or auth.
+/// Version 1 documents hold compact token strings such as
+/// `h2+tls13+warm+get+4096` instead.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SharedProfile {
+    Config(Box<ProfileConfig>),
+    Spec(String),
+}
+
+/// Profiles compare by their serialized form; `ProfileConfig` has no
+/// equality of its own.
+impl PartialEq for SharedProfile {
+    fn eq(&self, other: &Self) -> bool {
+        serde_json::to_value(self).ok() == serde_json::to_value(other).ok()
+    }
+}
+
+/// Request header of one profile. Exports always carry the placeholder as
+/// the value; importing one asks for the real value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedHeader {
+    /// Index into the target's `profiles`
+    pub profile: usize,
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ShareError {
+    #[error("not a targets document: {0}")]
+    Malformed(#[from] serde_json::Error),
+    #[error("targets document version {0} is newer than this httpulse reads")]
+    UnsupportedVersion(u32),
+}
+
+impl SharedTargets {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("plain data always serializes")
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, ShareError> {
+        let document: Self = serde_json::from_str(content)?;
+        if document.version > SHARED_TARGETS_VERSION {
+            return Err(ShareError::UnsupportedVersion(document.version));
+        }
+        Ok(document)
+    }
+
+    /// Whether `content` is a shared document rather than a line-per-target
+    /// targets file, which cannot start with a brace.
+    pub fn detect(content: &str) -> bool {
+        content.trim_start().starts_with('{')
+    }
+}
+
+/// Header of an imported profile still waiting for its value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingHeader {
+    pub target_id: TargetId,
+    pub profile_id: ProfileId,
+    pub name: String,
+}
+
+/// Outcome of importing a shared document.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SharedImportSummary {
+    pub added: usize,
+    /// URLs already probed, or listed earlier in the document
+    pub duplicates: Vec<Url>,
+    /// Targets with profile tokens or durations that were not understood
+    pub invalid: Vec<Url>,
+    /// Header values left to enter
+    pub pending_headers: usize,
+}
+
+/// e.g. "added 3, 1 duplicate (https://a.example/), 2 header values to enter"
+impl fmt::Display for SharedImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let urls = |urls: &[Url]| urls.iter().map(Url::as_str).collect::<Vec<_>>().join(", ");
+        write!(f, "added {}", self.added)?;
+        if !self.invalid.is_empty() {
+            write!(
+                f,
+                ", skipped {} invalid ({})",
+                self.invalid.len(),
+                urls(&self.invalid)
+            )?;
+        }
+        if !self.duplicates.is_empty() {
+            write!(
+                f,
+                ", {} duplicate ({})",
+                self.duplicates.len(),
+                urls(&self.duplicates)
+            )?;
+        }
+        match self.pending_headers {
+            0 => Ok(()),
+            1 => write!(f, ", 1 header value to enter"),
+            count => write!(f, ", {count} header values to enter"),
+        }
+    }
+}
+
+impl SharedTargets {
+    /// Definitions of `configs`, with header values replaced by the
+    /// placeholder. Auth is never part of it.
+    pub fn from_configs<'a>(configs: impl IntoIterator<Item = &'a TargetConfig>) -> Self {
+        let targets = configs
+            .into_iter()
+            .map(|config| SharedTarget {
+                url: config.url.clone(),
+                name: config.name.clone(),
+                interval: format_duration(config.interval),
+                timeout: format_duration(config.timeout_total),
+                dns: config.dns_enabled,
+                profiles: config
+                    .profiles
+                    .iter()
+                    .map(|profile| {
+                        SharedProfile::Config(Box::new(ProfileConfig {
+                            headers: Vec::new(),
+                            ..profile.clone()
+                        }))
+                    })
+                    .collect(),
+                tags: config.tags.clone(),
+                headers: config
+                    .profiles
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, profile)| {
+                        profile.headers.iter().map(move |(name, _)| SharedHeader {
+                            profile: index,
+                            name: name.clone(),
+                            value: SECRET_PLACEHOLDER.to_string(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        SharedTargets {
+            version: SHARED_TARGETS_VERSION,
+            targets,
+        }
+    }
+
+    /// The document's targets as configs with fresh ids, skipping URLs for
+    /// which `known` holds or that appear earlier in the document. Header
+    /// values are copied as they are, placeholders included.
+    pub fn into_configs(
+        self,
+        known: impl Fn(&Url) -> bool,
+    ) -> (Vec<TargetConfig>, SharedImportSummary) {
+        let mut summary = SharedImportSummary::default();
+        let mut seen = HashSet::new();
+        let mut configs = Vec::new();
+        for shared in self.targets {
+            if known(&shared.url) || !seen.insert(shared.url.clone()) {
+                summary.duplicates.push(shared.url);
+                continue;
+            }
+            let url = shared.url.clone();
+            let Some(config) = shared.into_config() else {
+                summary.invalid.push(url);
+                continue;
+            };
+            summary.pending_headers += config
+                .profiles
+                .iter()
+                .flat_map(|profile| &profile.headers)
+                .filter(|(_, value)| value.expose() == SECRET_PLACEHOLDER)
+                .count();
+            configs.push(config);
+        }
+        summary.added = configs.len();
+        (configs, summary)
+    }
+}
+
+impl SharedTarget {
+    /// `None` when a profile token or duration is not understood.
+    fn into_config(self) -> Option<TargetConfig> {
+        let interval = parse_duration(&self.interval).filter(|d| !d.is_zero())?;
+        let timeout = parse_duration(&self.timeout).filter(|d| !d.is_zero())?;
+        let mut profiles = Vec::new();
+        for profile in self.profiles {
+            match profile {
+                SharedProfile::Config(profile) => {
+                    if profile.interval_override.is_some_and(|d| d.is_zero()) {
+                        return None;
+                    }
+                    profiles.push(ProfileConfig {
+                        id: Uuid::new_v4(),
+                        auth: None,
+                        ..*profile
+                    });
+                }
+                SharedProfile::Spec(spec) => {
+                    let (parsed, unknown) = parse_profile_specs_strict(&spec);
+                    if !unknown.is_empty() {
+                        return None;
+                    }
+                    profiles.extend(parsed);
+                }
+            }
+        }
+        if profiles.is_empty() {
+            profiles = parse_profile_specs_strict("").0;
+        }
+
+        let mut config = TargetConfig::new(self.url, profiles);
+        config.name = self.name;
+        config.interval = interval;
+        config.timeout_total = timeout;
+        config.dns_enabled = self.dns;
+        config.tags = self.tags;
+        for header in self.headers {
+            if let Some(profile) = config.profiles.get_mut(header.profile) {
+                profile
+                    .headers
+                    .push((header.name, SecretString::new(header.value)));
+            }
+        }
+        Some(config)
+    }
+}
+
+impl AppState {
+    /// Every target's definition, with header values replaced by the
+    /// placeholder. Auth is never part of it.
+    pub fn export_targets(&self) -> SharedTargets {
+        SharedTargets::from_configs(self.targets.iter().map(|target| &target.config))
+    }
+
+    /// Adds the targets of `document` after the existing ones, skipping URLs
+    /// that are already probed. Placeholder header values are queued in
+    /// `pending_headers` for the user to fill in.
+    pub fn import_shared_targets(
+        &mut self,
+        document: SharedTargets,
+        auth: Option<&AuthConfig>,
+        sample_tx: &Sender<ProbeSample>,
+    ) -> SharedImportSummary {
+        let (configs, summary) = document.into_configs(|url| {
+            self.targets
+                .iter()
+                .any(|existing| &existing.config.url == url)
+        });
+        for mut config in configs {
+            config.sampling = self.global.sampling.clone();
+            for profile in &mut config.profiles {
+                profile.auth = auth.cloned();
+                let (pending, headers) = std::mem::take(&mut profile.headers)
+                    .into_iter()
+                    .partition(|(_, value)| value.expose() == SECRET_PLACEHOLDER);
+                profile.headers = headers;
+                self.pending_headers
+                    .extend(pending.into_iter().map(|(name, _)| PendingHeader {
+                        target_id: config.id,
+                        profile_id: profile.id,
+                        name,
+                    }));
+            }
+            self.insert_target(self.targets.len(), config, sample_tx.clone());
+        }
+        summary
+    }
+
+    /// Sets the first pending header to `value`, or drops it when `value` is
+    /// `None` or empty, and moves on to the next one.
+    pub fn resolve_pending_header(&mut self, value: Option<&str>) {
+        if self.pending_headers.is_empty() {
+            return;
+        }
+        let pending = self.pending_headers.remove(0);
+        let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+            return;
+        };
+        let Some(profile) = self
+            .targets
+            .iter()
+            .find(|target| target.config.id == pending.target_id)
+            .and_then(|target| {
+                target
+                    .profiles
+                    .iter()
+                    .find(|profile| profile.config.id == pending.profile_id)
+            })
+        else {
+            return;
+        };
+        let mut updated = profile.config.clone();
+        updated
+            .headers
+            .push((pending.name, SecretString::new(value)));
+        self.edit_profile_config(pending.target_id, updated);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ExpectedStatus, GlobalConfig, TargetConfig};
+    use crate::features::app::parsing::parse_profile_specs;
+    use crate::runtime::ProbeSource;
+    use std::time::Duration;
+
+    /// A config's shareable parts, with ids left out.
+    fn shape(config: &TargetConfig) -> impl PartialEq + fmt::Debug {
+        let profiles: Vec<_> = config
+            .profiles
+            .iter()
+            .map(|p| {
+                let headers: Vec<_> = p.headers.iter().map(|(name, _)| name.clone()).collect();
+                (
+                    (p.name.clone(), p.http, p.tls, p.conn_reuse, p.method),
+                    (p.max_read_bytes, p.max_recv_speed, p.color),
+                    p.interval_override,
+                    p.expected_status,
+                    p.expect_body_contains.clone(),
+                    headers,
+                )
+            })
+            .collect();
+        (
+            config.url.clone(),
+            config.name.clone(),
+            config.interval,
+            config.timeout_total,
+            config.dns_enabled,
+            config.tags.clone(),
+            profiles,
+        )
+    }
+
+    fn demo_app() -> AppState {
+        let mut app = AppState::new(GlobalConfig::default());
+        app.probe_source = ProbeSource::Demo(1);
+        app
+    }
+
+    #[test]
+    fn export_then_import_round_trips_definitions_without_secrets() {
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        let mut source = demo_app();
+        let mut api = TargetConfig::new(
+            Url::parse("https://api.example.com/health").unwrap(),
+            parse_profile_specs("h1+tls12+cold+head+128+i=5s+status=2xx, h2+warm"),
+        );
+        // Not expressible as a profile token string
+        api.profiles[1].expect_body_contains = Some(" ok, a+b ".to_string());
+        api.profiles[1].color = Some(3);
+        api.name = Some("API".to_string());
+        api.interval = Duration::from_millis(1500);
+        api.timeout_total = Duration::from_secs(120);
+        api.dns_enabled = false;
+        api.tags = vec!["prod".to_string(), "edge".to_string()];
+        api.profiles[1]
+            .headers
+            .push(("X-Api-Key".to_string(), SecretString::new("s3cret")));
+        let web = TargetConfig::new(
+            Url::parse("http://web.example.com").unwrap(),
+            parse_profile_specs("h1.0+ping"),
+        );
+        source.load_replay(vec![api, web]);
+
+        let json = source.export_targets().to_json();
+        assert!(!json.contains("s3cret"), "{json}");
+        assert!(json.contains(SECRET_PLACEHOLDER));
+
+        let mut imported = demo_app();
+        let summary = imported.import_shared_targets(
+            SharedTargets::from_json(&json).unwrap(),
+            None,
+            &sample_tx,
+        );
+        assert_eq!(summary.to_string(), "added 2, 1 header value to enter");
+        imported.resolve_pending_header(Some("typed-in"));
+        assert!(imported.pending_headers.is_empty());
+        let headers = &imported.targets[0].profiles[1].config.headers;
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers[0].1.expose(), "typed-in");
+
+        for (original, copy) in source.targets.iter().zip(&imported.targets) {
+            assert_eq!(shape(&original.config), shape(&copy.config));
+            assert_ne!(original.config.id, copy.config.id);
+        }
+        assert_eq!(
+            imported.targets[0].config.profiles[0].expected_status,
+            Some("2xx".parse::<ExpectedStatus>().unwrap())
+        );
+
+        // Importing the same document again only finds duplicates
+        let summary = imported.import_shared_targets(
+            SharedTargets::from_json(&json).unwrap(),
+            None,
+            &sample_tx,
+        );
+        assert_eq!(summary.added, 0);
+        assert_eq!(summary.duplicates.len(), 2);
+        assert_eq!(imported.targets.len(), 2);
+        imported.shutdown();
+    }
+
+    #[test]
+    fn import_skips_unreadable_targets_and_newer_documents() {
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        let mut app = demo_app();
+        let json = r#"{"version": 1, "targets": [
+            {"url": "https://a.example/", "interval": "1s", "timeout": "10s", "dns": true,
+             "profiles": ["h3+tls13"]},
+            {"url": "https://b.example/", "interval": "soon", "timeout": "10s", "dns": true,
+             "profiles": []},
+            {"url": "https://c.example/", "interval": "1s", "timeout": "10s", "dns": true,
+             "profiles": []}
+        ]}"#;
+        assert!(SharedTargets::detect(json));
+        assert!(!SharedTargets::detect("https://a.example h2 #edge"));
+        let summary =
+            app.import_shared_targets(SharedTargets::from_json(json).unwrap(), None, &sample_tx);
+        assert_eq!(
+            summary.to_string(),
+            "added 1, skipped 2 invalid (https://a.example/, https://b.example/)"
+        );
+        app.shutdown();
+
+        assert!(matches!(
+            SharedTargets::from_json(r#"{"version": 9, "targets": []}"#),
+            Err(ShareError::UnsupportedVersion(9))
+        ));
+        assert!(matches!(
+            SharedTargets::from_json("[]"),
+            Err(ShareError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::config::TargetId;
+
+use super::state::{AppState, TargetRuntime};
+
+/// Half of the split detail area that has input focus.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SplitSide {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Dual-pane layout: the selected target beside a pinned one. Input always
+/// goes to the selected target; `focus` says which half it is drawn in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SplitView {
+    pub pinned: TargetId,
+    pub focus: SplitSide,
+}
+
+impl AppState {
+    /// Pins the selected target into the right half, or unpins and returns
+    /// to the single pane. Returns `false` when no target is selected.
+    pub fn toggle_split(&mut self) -> bool {
+        if self.split.take().is_some() {
+            return true;
+        }
+        let Some(pinned) = self.selected_target_id() else {
+            return false;
+        };
+        self.split = Some(SplitView {
+            pinned,
+            focus: SplitSide::Left,
+        });
+        true
+    }
+
+    /// Moves focus to the other half: the target shown there becomes the
+    /// selected one and the one left behind is pinned in its place.
+    pub fn switch_split_focus(&mut self) -> bool {
+        let Some(split) = self.split else {
+            return false;
+        };
+        let Some(other) = self.target_index(split.pinned) else {
+            self.split = None;
+            return false;
+        };
+        let Some(current) = self.selected_target_id() else {
+            return false;
+        };
+        self.selected_target = other;
+        self.split = Some(SplitView {
+            pinned: current,
+            focus: match split.focus {
+                SplitSide::Left => SplitSide::Right,
+                SplitSide::Right => SplitSide::Left,
+            },
+        });
+        true
+    }
+
+    /// Targets of the left and right halves, with the side holding focus;
+    /// `None` without a split or once the pinned target is gone.
+    pub fn split_targets(&self) -> Option<(&TargetRuntime, &TargetRuntime, SplitSide)> {
+        let split = self.split?;
+        let pinned = &self.targets[self.target_index(split.pinned)?];
+        let selected = self.selected_target()?;
+        Some(match split.focus {
+            SplitSide::Left => (selected, pinned, SplitSide::Left),
+            SplitSide::Right => (pinned, selected, SplitSide::Right),
+        })
+    }
+
+    fn target_index(&self, id: TargetId) -> Option<usize> {
+        self.targets.iter().position(|t| t.config.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GlobalConfig, TargetConfig, default_profiles};
+    use url::Url;
+
+    fn app_with_targets(hosts: &[&str]) -> AppState {
+        let targets: Vec<TargetConfig> = hosts
+            .iter()
+            .map(|host| {
+                TargetConfig::new(
+                    Url::parse(&format!("https://{host}")).unwrap(),
+                    default_profiles(),
+                )
+            })
+            .collect();
+        let mut app = AppState::new(GlobalConfig::default());
+        app.load_replay(targets);
+        app.replaying = false;
+        app
+    }
+
+    #[test]
+    fn pinning_keeps_the_selection_on_the_left_until_focus_moves() {
+        let mut app = app_with_targets(&["a.example", "b.example"]);
+        app.selected_target = 0;
+        assert!(app.toggle_split());
+        app.selected_target = 1;
+
+        let (left, right, focus) = app.split_targets().unwrap();
+        assert_eq!(left.config.url.host_str(), Some("b.example"));
+        assert_eq!(right.config.url.host_str(), Some("a.example"));
+        assert_eq!(focus, SplitSide::Left);
+
+        assert!(app.switch_split_focus());
+        assert_eq!(app.selected_target, 0);
+        let (left, right, focus) = app.split_targets().unwrap();
+        assert_eq!(left.config.url.host_str(), Some("b.example"));
+        assert_eq!(right.config.url.host_str(), Some("a.example"));
+        assert_eq!(focus, SplitSide::Right);
+
+        assert!(app.toggle_split());
+        assert!(app.split.is_none());
+        assert!(!app.switch_split_focus());
+    }
+
+    #[test]
+    fn a_removed_pinned_target_falls_back_to_one_pane() {
+        let mut app = app_with_targets(&["a.example", "b.example"]);
+        app.selected_target = 1;
+        app.toggle_split();
+        app.selected_target = 0;
+        app.remove_target(1);
+        assert!(app.split.is_none());
+        assert!(app.split_targets().is_none());
+        assert!(!app.switch_split_focus());
+
+        // Removing the selected target can leave the pinned one selected
+        let mut app = app_with_targets(&["a.example", "b.example", "c.example"]);
+        app.selected_target = 2;
+        app.toggle_split();
+        app.selected_target = 1;
+        app.remove_target(1);
+        assert_eq!(app.selected_target, 1);
+        assert!(app.split.is_none());
+
+        // Any other target leaves the split alone
+        let mut app = app_with_targets(&["a.example", "b.example", "c.example"]);
+        app.selected_target = 1;
+        app.toggle_split();
+        app.selected_target = 0;
+        app.remove_target(2);
+        assert!(app.split.is_some());
+    }
+}
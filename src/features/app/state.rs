@@ -1,12 +1,44 @@
-use crate::config::{GlobalConfig, ProfileConfig, TargetConfig, default_profiles_for_capabilities};
-use crate::metrics::{MetricKind, WindowedAggregate};
-use crate::metrics_aggregate::{MetricsStore, ProfileKey};
-use crate::probe::{ProbeErrorKind, ProbeSample};
-use crate::probe_engine::detect_tls13_support;
-use crate::runtime::{ControlMessage, WorkerHandle, spawn_profile_worker};
+use super::annotation::Annotation;
+use super::change_log::{
+    ChangeEntry, ChangeLog, describe_profile_change, describe_target_change, on_off,
+};
+use super::endpoints::{EndpointRuntime, EndpointView};
+use super::error_log::{ErrorEntry, ErrorLog};
+use super::groups::TargetListRow;
+use super::ip_history::IpHistory;
+use super::overview::OverviewSort;
+use super::parsing::format_duration;
+use super::session_stats::SessionStats;
+use super::share::PendingHeader;
+use super::split::SplitView;
+use super::status::StatusNotice;
+use super::watchdog::WorkerHealth;
+use crate::alerts::{
+    AlertEvent, AlertLog, AlertRuleState, NOTIFY_EVAL_INTERVAL, Notification, NotifyState,
+    NotifyTransition,
+};
+use crate::common::time::local_minute_of_day;
+use crate::config::{
+    AuthConfig, ConnReusePolicy, GlobalConfig, ProbeBackendKind, ProfileConfig, ProfileId,
+    TargetConfig, TargetId, ThemeName, WindowSpec, default_profiles_for_capabilities,
+};
+use crate::metrics::{
+    Baseline, MetricKind, MetricStats, OutageEvent, SloCounts, SloStatus, SnapshotProfile,
+    SpikeEvent, WindowedAggregate, format_snapshot,
+};
+use crate::metrics_aggregate::{AggregateView, MetricsStore, ProfileKey};
+use crate::probe::{CertInfo, ProbeErrorKind, ProbeSample, TcpInfoCapability};
+use crate::probe_engine::{detect_h2_support, spawn_tls13_detection, tls13_support};
+use crate::runtime::{
+    BURST_DURATION, ControlMessage, DnsCache, ProbeSource, RESOLVER_PROFILE_ID, WorkerHandle,
+    backoff_interval, spawn_dns_worker, spawn_profile_worker,
+};
+use crate::traceroute::{TracerouteError, TracerouteRun, TracerouteUpdate, spawn_traceroute};
+use crossbeam_channel::{Receiver, Sender};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashSet};
-use std::net::IpAddr;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 use url::Url;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -23,6 +55,10 @@ pub enum TargetPaneMode {
     Chart,
     Metrics,
     Summary,
+    Breakdown,
+    Heatmap,
+    /// One column per configured window, to tell recent from long-standing
+    Windows,
 }
 
 /// Metrics category for tab-based navigation
@@ -83,7 +119,10 @@ impl TargetPaneMode {
             TargetPaneMode::Split => TargetPaneMode::Chart,
             TargetPaneMode::Chart => TargetPaneMode::Metrics,
             TargetPaneMode::Metrics => TargetPaneMode::Summary,
-            TargetPaneMode::Summary => TargetPaneMode::Split,
+            TargetPaneMode::Summary => TargetPaneMode::Breakdown,
+            TargetPaneMode::Breakdown => TargetPaneMode::Heatmap,
+            TargetPaneMode::Heatmap => TargetPaneMode::Windows,
+            TargetPaneMode::Windows => TargetPaneMode::Split,
         }
     }
 
@@ -93,6 +132,9 @@ impl TargetPaneMode {
             TargetPaneMode::Chart => "Chart",
             TargetPaneMode::Metrics => "Metrics",
             TargetPaneMode::Summary => "Summary",
+            TargetPaneMode::Breakdown => "Breakdown",
+            TargetPaneMode::Heatmap => "Heatmap",
+            TargetPaneMode::Windows => "Windows",
         }
     }
 }
@@ -102,21 +144,264 @@ pub struct AppState {
     pub metrics: MetricsStore,
     pub targets: Vec<TargetRuntime>,
     pub selected_target: usize,
+    /// Compare metric new targets start with
     pub selected_metric: MetricKind,
+    /// Chart metrics new targets start with
     pub selected_metrics: HashSet<MetricKind>,
-    pub window: crate::config::WindowSpec,
+    pub window: WindowSpec,
+    pub alert_log: AlertLog,
+    /// User-initiated changes to targets and their probing, newest last
+    pub change_log: ChangeLog,
+    /// All workers paused via the global pause key; per-target flags are untouched
+    pub global_paused: bool,
+    /// Inside the configured quiet hours
+    pub quiet_active: bool,
+    /// Case-insensitive hostname or tag substring narrowing the target list; empty shows all
+    pub target_filter: String,
+    /// Group header under the cursor; while `None` the cursor is on `selected_target`
+    pub selected_group: Option<String>,
+    /// Groups whose members are hidden in the target list
+    pub collapsed_groups: BTreeSet<String>,
+    /// Sort state of the multi-target overview, shown in place of the detail pane while set
+    pub overview: Option<OverviewSort>,
+    /// Pinned second target of the side-by-side layout
+    pub split: Option<SplitView>,
+    /// Imported headers whose values are asked for before probing with them
+    pub pending_headers: Vec<PendingHeader>,
+    /// Why the requested eBPF mode fell back to off, shown in the header
+    pub ebpf_notice: Option<String>,
+    /// Whether the kernel can report TCP state on this OS, detected at startup
+    pub tcp_info: TcpInfoCapability,
+    /// Theme the UI draws with: `global.theme` with `Auto` resolved
+    pub theme: ThemeName,
+    /// Outcome of the last status dump, shown in the header while recent
+    pub status_notice: Option<StatusNotice>,
+    /// Samples come from a session replay: no workers run and nothing is persisted
+    pub replaying: bool,
+    /// Probe totals since launch, shown in the header
+    pub session: SessionStats,
+    /// What new workers probe with; `--demo` swaps the network for synthetic samples
+    pub probe_source: ProbeSource,
+    /// When notifications were last evaluated
+    notify_checked: Option<Instant>,
+    /// Pending answer of the TLS 1.3 detection started at launch
+    tls13_rx: Option<Receiver<bool>>,
+    /// Targets given the TLS 1.2-only defaults before detection finished
+    tls12_defaulted: Vec<TargetId>,
+    traceroute_tx: Sender<TracerouteUpdate>,
+    traceroute_rx: Receiver<TracerouteUpdate>,
+    /// Threads of stopped workers, joined once they have finished
+    pub(super) pending_shutdown: Vec<JoinHandle<()>>,
+    /// Annotations added since the session recorder last took them
+    pub(super) new_annotations: Vec<(TargetId, Annotation)>,
 }
 
 pub struct TargetRuntime {
     pub config: TargetConfig,
     pub paused: bool,
-    pub last_ip: Option<IpAddr>,
+    /// Resolved address of the target and its recent changes
+    pub ip_history: IpHistory,
     pub profiles: Vec<ProfileRuntime>,
     pub view_mode: ProfileViewMode,
     pub selected_profile: usize,
     pub pane_mode: TargetPaneMode,
     /// Selected metrics category for tab-based navigation
     pub metrics_category: MetricsCategory,
+    /// Metric charted per profile in Compare mode; one of `selected_metrics`
+    /// while any is selected
+    pub selected_metric: MetricKind,
+    /// Metrics charted in Single mode, toggled with `1`-`8`
+    pub selected_metrics: HashSet<MetricKind>,
+    /// Hysteresis state per entry in `config.alert_rules`
+    pub alert_states: Vec<AlertRuleState>,
+    /// Timestamp of the sample under the chart cursor, when cursor mode is active
+    pub chart_cursor: Option<std::time::SystemTime>,
+    /// Most recent traceroute, kept until the next one is triggered
+    pub traceroute: Option<TracerouteRun>,
+    /// Full messages of the most recent probe errors
+    pub error_log: ErrorLog,
+    /// When the target's or a profile's config was last edited; samples on
+    /// either side differ
+    pub config_changes: Vec<SystemTime>,
+    /// Most recently collected server certificate (https targets only)
+    pub cert: Option<CertInfo>,
+    /// Snapshots to compare the current window against, oldest first
+    pub baselines: Vec<Baseline>,
+    /// Index into `baselines` shown as the metrics table's delta column
+    pub active_baseline: Option<usize>,
+    /// User notes on this target, oldest first
+    pub annotations: Vec<Annotation>,
+    /// Debounce state of the sustained-loss notification
+    pub notify_state: NotifyState,
+    /// End of the running burst, while one is running
+    pub burst_until: Option<Instant>,
+    /// Answer of the DNS worker, read by the profile workers
+    pub dns_cache: DnsCache,
+    /// Resolves the host for the profile workers; `None` for replays and
+    /// demo data
+    pub dns_worker: Option<WorkerHandle>,
+    /// Per-IP streams while `per_ip` is on, one per profile and address
+    pub endpoints: Vec<EndpointRuntime>,
+    /// Which of those streams the detail panes show
+    pub endpoint_view: EndpointView,
+    /// Window this target's panes use instead of the global one
+    pub window_override: Option<WindowSpec>,
+}
+
+impl TargetRuntime {
+    /// Adds `metric` to the chart, making it the compare metric, or removes
+    /// it. Removing the compare metric falls back to the first remaining one
+    /// in display order, so the compare metric stays selected while any is.
+    pub fn toggle_metric(&mut self, metric: MetricKind) {
+        if self.selected_metrics.remove(&metric) {
+            if metric == self.selected_metric
+                && let Some(next) = MetricKind::iter_all()
+                    .iter()
+                    .find(|kind| self.selected_metrics.contains(kind))
+            {
+                self.selected_metric = *next;
+            }
+        } else {
+            self.selected_metrics.insert(metric);
+            self.selected_metric = metric;
+        }
+    }
+
+    /// Effective interval of the profiles probing at the target's interval,
+    /// averaged; `None` until one of them has probed twice.
+    pub fn effective_interval(&self) -> Option<Duration> {
+        let gaps: Vec<Duration> = self
+            .profiles
+            .iter()
+            .filter(|profile| profile.config.interval_override.is_none())
+            .filter_map(|profile| profile.effective_interval)
+            .collect();
+        let count = u32::try_from(gaps.len()).ok().filter(|count| *count > 0)?;
+        Some(gaps.iter().sum::<Duration>() / count)
+    }
+
+    /// Every worker of the target: one per profile, the DNS worker and the
+    /// per-IP streams.
+    fn workers_mut(&mut self) -> impl Iterator<Item = &mut WorkerHandle> {
+        self.profiles
+            .iter_mut()
+            .map(|profile| &mut profile.worker)
+            .chain(self.dns_worker.as_mut())
+            .chain(
+                self.endpoints
+                    .iter_mut()
+                    .map(|endpoint| &mut endpoint.worker),
+            )
+    }
+
+    pub fn active_alerts(&self) -> usize {
+        self.alert_states
+            .iter()
+            .filter(|state| state.firing)
+            .count()
+    }
+
+    pub fn is_alerting(&self) -> bool {
+        self.alert_states.iter().any(|state| state.firing)
+    }
+
+    /// Whether the target's schedule has no fire time in the current hour;
+    /// silence is expected then.
+    pub fn schedule_idle(&self, now: SystemTime) -> bool {
+        self.config
+            .schedule
+            .as_ref()
+            .is_some_and(|schedule| !schedule.is_active_at(now))
+    }
+
+    /// Time left of the running burst.
+    pub fn burst_remaining(&self, now: Instant) -> Option<std::time::Duration> {
+        self.burst_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Longest interval any profile has backed off to after consecutive
+    /// failures; `None` while every profile probes at its configured pace.
+    pub fn backoff(&self) -> Option<std::time::Duration> {
+        if self.config.schedule.is_some() {
+            return None;
+        }
+        self.profiles
+            .iter()
+            .filter(|profile| profile.failure_streak > 0)
+            .map(|profile| {
+                let interval = self.config.interval_for(&profile.config);
+                (interval, backoff_interval(interval, profile.failure_streak))
+            })
+            .filter(|(interval, backed_off)| backed_off > interval)
+            .map(|(_, backed_off)| backed_off)
+            .max()
+    }
+
+    pub fn stalled_profiles(&self) -> usize {
+        self.profiles
+            .iter()
+            .filter(|profile| profile.health.stalled)
+            .count()
+    }
+
+    pub fn worker_restarts(&self) -> u32 {
+        self.profiles
+            .iter()
+            .map(|profile| profile.health.restarts)
+            .sum()
+    }
+
+    pub fn config_changes_since(&self, since: SystemTime) -> impl Iterator<Item = SystemTime> {
+        self.config_changes
+            .iter()
+            .copied()
+            .filter(move |ts| *ts >= since)
+    }
+
+    /// Whole days until the certificate expires; negative once it has.
+    pub fn cert_days_left(&self, now: SystemTime) -> Option<i64> {
+        let not_after = self.cert.as_ref()?.not_after?;
+        let secs = match not_after.duration_since(now) {
+            Ok(left) => left.as_secs() as i64,
+            Err(err) => -(err.duration().as_secs() as i64),
+        };
+        Some(secs.div_euclid(86_400))
+    }
+
+    /// Whether the certificate is inside the critical expiry threshold.
+    pub fn cert_expiring_soon(&self, now: SystemTime) -> bool {
+        self.cert_days_left(now)
+            .is_some_and(|days| days < CERT_CRITICAL_DAYS)
+    }
+
+    fn record_config_change(&mut self, ts: SystemTime) {
+        self.config_changes.push(ts);
+        if self.config_changes.len() > CONFIG_CHANGE_CAPACITY {
+            self.config_changes.remove(0);
+        }
+    }
+}
+
+/// Days before expiry at which the certificate is flagged as a warning.
+pub const CERT_WARN_DAYS: i64 = 30;
+/// Days before expiry at which the certificate is flagged as critical.
+pub const CERT_CRITICAL_DAYS: i64 = 7;
+
+/// Config-change markers kept per target; older ones fall outside any window.
+const CONFIG_CHANGE_CAPACITY: usize = 16;
+
+/// Weight of the newest probe gap in a profile's effective interval.
+const EFFECTIVE_INTERVAL_WEIGHT: f64 = 0.2;
+
+/// Environment `Auto` themes resolve from; tests see none, so they draw alike everywhere.
+fn theme_env(key: &str) -> Option<String> {
+    if cfg!(test) {
+        None
+    } else {
+        std::env::var(key).ok()
+    }
 }
 
 pub struct ProfileRuntime {
@@ -124,6 +409,35 @@ pub struct ProfileRuntime {
     pub worker: WorkerHandle,
     pub last_sample: Option<ProbeSample>,
     pub last_error: Option<ProbeErrorKind>,
+    /// Watchdog state: stall flag and restart count
+    pub health: WorkerHealth,
+    /// Consecutive failed probes, mirroring the worker's backoff
+    pub failure_streak: u32,
+    /// Samples received since the profile started, to tell when it has
+    /// warmed up
+    pub samples_seen: u64,
+    /// Smoothed gap between the worker's probes outside bursts; cleared
+    /// whenever the worker restarts its schedule
+    pub effective_interval: Option<Duration>,
+}
+
+impl ProfileRuntime {
+    /// Whether the profile asks for HTTP/2 but its last response came back
+    /// over an older version, e.g. because libcurl lacks HTTP/2 or the
+    /// server does not offer it.
+    pub fn http_version_fallback(&self) -> bool {
+        self.last_sample
+            .as_ref()
+            .is_some_and(|sample| sample.http_version_fallback)
+    }
+}
+
+/// Profiles of one target that differ only in connection reuse, as indices
+/// into `TargetRuntime::profiles`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ConnReusePair {
+    pub warm: usize,
+    pub cold: usize,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -132,13 +446,18 @@ pub struct GlobalSummary {
     pub requests: u64,
     pub successes: u64,
     pub timeouts: u64,
+    /// Probe slots that passed without a probe
+    pub missed: u64,
     pub errors: BTreeMap<ProbeErrorKind, u64>,
+    pub statuses: BTreeMap<u16, u64>,
+    pub slo: Option<SloStatus>,
 }
 
 impl AppState {
     pub fn new(global: GlobalConfig) -> Self {
         let mut selected_metrics = HashSet::new();
         selected_metrics.insert(MetricKind::Total);
+        let (traceroute_tx, traceroute_rx) = crossbeam_channel::unbounded();
         Self {
             global: global.clone(),
             metrics: MetricsStore::new(),
@@ -147,6 +466,30 @@ impl AppState {
             selected_metric: MetricKind::Total,
             selected_metrics,
             window: global.default_window,
+            alert_log: AlertLog::default(),
+            change_log: ChangeLog::default(),
+            global_paused: false,
+            quiet_active: false,
+            target_filter: String::new(),
+            selected_group: None,
+            collapsed_groups: BTreeSet::new(),
+            overview: None,
+            split: None,
+            pending_headers: Vec::new(),
+            ebpf_notice: None,
+            tcp_info: TcpInfoCapability::detect(),
+            theme: global.theme.resolve(theme_env),
+            status_notice: None,
+            replaying: false,
+            session: SessionStats::new(Instant::now()),
+            probe_source: ProbeSource::Network(global.backend),
+            notify_checked: None,
+            tls13_rx: None,
+            tls12_defaulted: Vec::new(),
+            traceroute_tx,
+            traceroute_rx,
+            pending_shutdown: Vec::new(),
+            new_annotations: Vec::new(),
         }
     }
 
@@ -156,73 +499,591 @@ impl AppState {
         profiles: Option<Vec<ProfileConfig>>,
         sample_tx: crossbeam_channel::Sender<ProbeSample>,
     ) {
-        let profiles =
-            profiles.unwrap_or_else(|| default_profiles_for_capabilities(detect_tls13_support()));
-        let mut target = TargetConfig::new(url, profiles.clone());
-        target.sampling = crate::config::SamplingConfig::default();
-        let mut profile_runtimes = Vec::new();
-        for profile in profiles {
-            let worker = spawn_profile_worker(target.clone(), profile.clone(), sample_tx.clone());
-            profile_runtimes.push(ProfileRuntime {
-                config: profile,
+        self.add_target_with_auth(url, profiles, None, sample_tx);
+    }
+
+    /// Like `add_target`, with `auth` set on every profile before the
+    /// workers start, so no probe goes out without it.
+    pub fn add_target_with_auth(
+        &mut self,
+        url: Url,
+        profiles: Option<Vec<ProfileConfig>>,
+        auth: Option<AuthConfig>,
+        sample_tx: crossbeam_channel::Sender<ProbeSample>,
+    ) {
+        let target = self.new_target_config(url, profiles, auth);
+        self.insert_target(self.targets.len(), target, sample_tx);
+    }
+
+    /// Adds a `--target`, with certificate verification skipped when the
+    /// user passed `--insecure`.
+    pub fn add_cli_target(
+        &mut self,
+        url: Url,
+        auth: Option<AuthConfig>,
+        insecure: bool,
+        sample_tx: crossbeam_channel::Sender<ProbeSample>,
+    ) {
+        let mut target = self.new_target_config(url, None, auth);
+        target.insecure = insecure;
+        if insecure {
+            tracing::warn!(target_url = %target.url, "certificate verification is off");
+        }
+        self.insert_target(self.targets.len(), target, sample_tx);
+    }
+
+    pub(super) fn new_target_config(
+        &mut self,
+        url: Url,
+        profiles: Option<Vec<ProfileConfig>>,
+        auth: Option<AuthConfig>,
+    ) -> TargetConfig {
+        let defaulted = profiles.is_none() && tls13_support().is_none();
+        let mut profiles = profiles.unwrap_or_else(|| {
+            default_profiles_for_capabilities(tls13_support() == Some(true), detect_h2_support())
+        });
+        for profile in &mut profiles {
+            profile.auth.clone_from(&auth);
+        }
+        let mut target = TargetConfig::new(url, profiles);
+        target.sampling = self.global.sampling.clone();
+        if defaulted {
+            self.tls12_defaulted.push(target.id);
+        }
+        target
+    }
+
+    /// Clones the target at `index` with fresh ids and places the copy right
+    /// after it. Returns the index of the copy.
+    pub fn duplicate_target(
+        &mut self,
+        index: usize,
+        sample_tx: crossbeam_channel::Sender<ProbeSample>,
+    ) -> Option<usize> {
+        let source = self.targets.get(index)?;
+        let copy = source.config.duplicate();
+        let view = (
+            source.selected_metric,
+            source.selected_metrics.clone(),
+            source.metrics_category,
+        );
+        self.insert_target(index + 1, copy, sample_tx);
+        let duplicate = &mut self.targets[index + 1];
+        (
+            duplicate.selected_metric,
+            duplicate.selected_metrics,
+            duplicate.metrics_category,
+        ) = view;
+        Some(index + 1)
+    }
+
+    /// Spawns workers for every profile of `target` and inserts it at `index`,
+    /// selecting it.
+    pub(super) fn insert_target(
+        &mut self,
+        index: usize,
+        target: TargetConfig,
+        sample_tx: crossbeam_channel::Sender<ProbeSample>,
+    ) {
+        let (dns_cache, dns_worker) = self.spawn_dns(&target, &sample_tx);
+        let workers = target
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(profile_index, profile)| {
+                spawn_profile_worker(
+                    target.clone(),
+                    profile.clone(),
+                    target.stagger_offset(profile_index),
+                    self.probe_source,
+                    dns_cache.clone(),
+                    sample_tx.clone(),
+                )
+            })
+            .collect();
+        let target_id = target.id;
+        self.place_target(index, target, workers, dns_cache, dns_worker);
+        self.log_change(Some(target_id), "Added".to_string());
+    }
+
+    /// DNS worker of a target probed over the network and the cache it fills.
+    /// It idles while the target resolves inside every probe, so switching
+    /// modes later needs no new thread.
+    fn spawn_dns(
+        &self,
+        target: &TargetConfig,
+        sample_tx: &Sender<ProbeSample>,
+    ) -> (DnsCache, Option<WorkerHandle>) {
+        let cache = DnsCache::default();
+        let worker = matches!(self.probe_source, ProbeSource::Network(_))
+            .then(|| spawn_dns_worker(target.clone(), cache.clone(), sample_tx.clone()));
+        (cache, worker)
+    }
+
+    /// Loads the targets of a recorded session without spawning workers; the
+    /// replay driver supplies their samples. Nothing is persisted on exit.
+    pub fn load_replay(&mut self, targets: Vec<TargetConfig>) {
+        self.replaying = true;
+        for target in targets {
+            let workers = target
+                .profiles
+                .iter()
+                .map(|_| WorkerHandle::idle())
+                .collect();
+            self.place_target(
+                self.targets.len(),
+                target,
+                workers,
+                DnsCache::default(),
+                None,
+            );
+        }
+        self.selected_target = 0;
+    }
+
+    /// Inserts `target` at `index` with one worker handle per profile, selecting it.
+    fn place_target(
+        &mut self,
+        index: usize,
+        mut target: TargetConfig,
+        workers: Vec<WorkerHandle>,
+        dns_cache: DnsCache,
+        dns_worker: Option<WorkerHandle>,
+    ) {
+        target.sampling.histogram.cover(target.timeout_total);
+        let profile_runtimes = target
+            .profiles
+            .iter()
+            .zip(workers)
+            .map(|(profile, worker)| ProfileRuntime {
+                config: profile.clone(),
                 worker,
                 last_sample: None,
                 last_error: None,
-            });
-        }
+                health: WorkerHealth::default(),
+                failure_streak: 0,
+                samples_seen: 0,
+                effective_interval: None,
+            })
+            .collect();
 
-        self.targets.push(TargetRuntime {
-            config: target,
-            paused: false,
-            last_ip: None,
-            profiles: profile_runtimes,
-            view_mode: ProfileViewMode::Single,
-            selected_profile: 0,
-            pane_mode: TargetPaneMode::Split,
-            metrics_category: MetricsCategory::default(),
-        });
-        self.selected_target = self.targets.len().saturating_sub(1);
+        let index = index.min(self.targets.len());
+        self.targets.insert(
+            index,
+            TargetRuntime {
+                alert_states: vec![AlertRuleState::default(); target.alert_rules.len()],
+                config: target,
+                paused: false,
+                ip_history: IpHistory::default(),
+                profiles: profile_runtimes,
+                view_mode: ProfileViewMode::Single,
+                selected_profile: 0,
+                pane_mode: TargetPaneMode::Split,
+                metrics_category: MetricsCategory::default(),
+                selected_metric: self.selected_metric,
+                selected_metrics: self.selected_metrics.clone(),
+                chart_cursor: None,
+                traceroute: None,
+                error_log: ErrorLog::default(),
+                config_changes: Vec::new(),
+                cert: None,
+                baselines: Vec::new(),
+                annotations: Vec::new(),
+                active_baseline: None,
+                notify_state: NotifyState::default(),
+                burst_until: None,
+                dns_cache,
+                dns_worker,
+                endpoints: Vec::new(),
+                endpoint_view: EndpointView::default(),
+                window_override: None,
+            },
+        );
+        self.selected_target = index;
+        if !self.target_visible(self.selected_target) {
+            self.target_filter.clear();
+        }
+        self.reveal_selected_target();
+        if self.probing_suspended() {
+            self.sync_worker_pause();
+        }
     }
 
     pub fn remove_target(&mut self, index: usize) {
         if index >= self.targets.len() {
             return;
         }
+        self.log_change(Some(self.targets[index].config.id), "Deleted".to_string());
         let mut target = self.targets.remove(index);
-        for profile in target.profiles.iter_mut() {
-            let _ = profile.worker.sender.send(ControlMessage::Stop);
-            if let Some(join) = profile.worker.join.take() {
-                let _ = join.join();
+        // A probe in flight can take up to its timeout to notice; leave the
+        // threads to `reap_workers` rather than blocking the UI on them
+        for worker in target.workers_mut() {
+            self.pending_shutdown.extend(worker.stop());
+        }
+        self.metrics.remove_target(target.config.id);
+        // Keep the same target selected; when it was the one removed, the
+        // next one takes its place, or the new last one at the tail
+        match index.cmp(&self.selected_target) {
+            std::cmp::Ordering::Less => self.selected_target -= 1,
+            std::cmp::Ordering::Equal => {
+                self.selected_target = self
+                    .selected_target
+                    .min(self.targets.len().saturating_sub(1));
             }
+            std::cmp::Ordering::Greater => {}
+        }
+        // Without its pinned target, or with it now selected, the split
+        // would show one target twice
+        if let Some(split) = self.split
+            && (split.pinned == target.config.id || self.selected_target_id() == Some(split.pinned))
+        {
+            self.split = None;
         }
-        self.selected_target = self.selected_target.saturating_sub(1);
+        self.ensure_selection_visible();
     }
 
-    pub fn toggle_pause(&mut self, index: usize) {
-        if let Some(target) = self.targets.get_mut(index) {
-            target.paused = !target.paused;
-            for profile in target.profiles.iter_mut() {
-                let _ = profile
-                    .worker
-                    .sender
-                    .send(ControlMessage::Pause(target.paused));
+    /// Joins the stopped workers that have finished. Called every tick.
+    pub fn reap_workers(&mut self) {
+        let (finished, running): (Vec<_>, Vec<_>) = self
+            .pending_shutdown
+            .drain(..)
+            .partition(JoinHandle::is_finished);
+        self.pending_shutdown = running;
+        for join in finished {
+            let _ = join.join();
+        }
+    }
+
+    /// Stopped workers whose threads have not exited yet.
+    pub fn pending_shutdown(&self) -> usize {
+        self.pending_shutdown.len()
+    }
+
+    /// Stops every worker on exit without waiting for them; the process
+    /// ends their threads.
+    pub fn shutdown(&mut self) {
+        for target in &mut self.targets {
+            for worker in target.workers_mut() {
+                drop(worker.stop());
             }
         }
     }
 
-    pub fn apply_sample(&mut self, sample: ProbeSample) {
+    fn target_visible(&self, index: usize) -> bool {
+        let Some(target) = self.targets.get(index) else {
+            return false;
+        };
+        if self.target_filter.is_empty() {
+            return true;
+        }
+        let filter = self.target_filter.to_ascii_lowercase();
+        let tag_filter = filter.strip_prefix('#').unwrap_or(&filter);
+        target
+            .config
+            .url
+            .host_str()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .contains(&filter)
+            || target
+                .config
+                .tags
+                .iter()
+                .any(|tag| tag.to_ascii_lowercase().contains(tag_filter))
+    }
+
+    /// Indices into `targets` that pass the current filter, in display order:
+    /// untagged targets first, then each group in order of first appearance.
+    pub fn visible_targets(&self) -> Vec<usize> {
+        let mut groups: Vec<&str> = Vec::new();
+        for group in self.targets.iter().filter_map(TargetRuntime::group) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        let mut visible: Vec<usize> = (0..self.targets.len())
+            .filter(|idx| self.target_visible(*idx))
+            .collect();
+        visible.sort_by_key(|idx| {
+            self.targets[*idx].group().map_or(0, |group| {
+                1 + groups.iter().position(|g| *g == group).unwrap_or(0)
+            })
+        });
+        visible
+    }
+
+    pub fn set_target_filter(&mut self, filter: &str) {
+        self.target_filter = filter.trim().to_string();
+        self.ensure_selection_visible();
+    }
+
+    /// Moves the cursor back into the list when the filter, a collapsed
+    /// group or a removal hid its row: onto the header of a collapsed group
+    /// that holds the selected target, else the nearest visible target,
+    /// preferring the next one in list order.
+    pub(super) fn ensure_selection_visible(&mut self) {
+        let rows = self.target_list_rows();
+        if self.cursor_row(&rows).is_some() {
+            return;
+        }
+        if self.target_visible(self.selected_target)
+            && let Some(group) = self.targets[self.selected_target].group()
+            && self.collapsed_groups.contains(group)
+        {
+            self.selected_group = Some(group.to_string());
+            return;
+        }
+        let targets: Vec<usize> = rows
+            .iter()
+            .filter_map(|row| match row {
+                TargetListRow::Target(index) => Some(*index),
+                TargetListRow::Group(_) => None,
+            })
+            .collect();
+        let fallback = targets
+            .iter()
+            .find(|idx| **idx > self.selected_target)
+            .or(targets.last())
+            .map(|idx| TargetListRow::Target(*idx))
+            .or_else(|| rows.first().cloned());
+        if let Some(row) = fallback {
+            self.select_row(&row);
+        }
+    }
+
+    /// Moves the cursor to the next row of the target list, headers included.
+    pub fn select_next_target(&mut self) {
+        let rows = self.target_list_rows();
+        let next = match self.cursor_row(&rows) {
+            Some(pos) => rows.get(pos + 1),
+            None => rows.first(),
+        };
+        if let Some(row) = next.cloned() {
+            self.select_row(&row);
+        }
+    }
+
+    pub fn select_prev_target(&mut self) {
+        let rows = self.target_list_rows();
+        let prev = match self.cursor_row(&rows) {
+            Some(pos) => pos.checked_sub(1).and_then(|pos| rows.get(pos)),
+            None => rows.last(),
+        };
+        if let Some(row) = prev.cloned() {
+            self.select_row(&row);
+        }
+    }
+
+    /// Swaps the selected target with its visible neighbour above (`up`) or
+    /// below within its group, keeping it selected. Returns whether the
+    /// order changed.
+    pub fn move_selected_target(&mut self, up: bool) -> bool {
+        if self.selected_group.is_some() || !self.target_visible(self.selected_target) {
+            return false;
+        }
+        let visible = self.visible_targets();
+        let Some(pos) = visible.iter().position(|idx| *idx == self.selected_target) else {
+            return false;
+        };
+        let neighbour = if up {
+            pos.checked_sub(1).map(|pos| visible[pos])
+        } else {
+            visible.get(pos + 1).copied()
+        };
+        let Some(neighbour) = neighbour.filter(|neighbour| {
+            self.targets[*neighbour].group() == self.targets[self.selected_target].group()
+        }) else {
+            return false;
+        };
+        self.targets.swap(self.selected_target, neighbour);
+        self.selected_target = neighbour;
+        true
+    }
+
+    pub fn toggle_pause(&mut self, target_id: TargetId) {
+        let suspended = self.probing_suspended();
+        let Some(target) = self.target_by_id_mut(target_id) else {
+            return;
+        };
+        target.paused = !target.paused;
+        let paused = target.paused || suspended;
+        for worker in target.workers_mut() {
+            let _ = worker.sender.send(ControlMessage::Pause(paused));
+        }
+        let description = if target.paused { "Paused" } else { "Resumed" };
+        self.log_change(Some(target_id), description.to_string());
+    }
+
+    /// True while every worker is held paused, by the global pause or quiet hours.
+    pub fn probing_suspended(&self) -> bool {
+        self.global_paused || self.quiet_active
+    }
+
+    /// Probes every profile of the target at the burst pace for
+    /// [`BURST_DURATION`]; ignored while the target is paused.
+    pub fn start_burst(&mut self, index: usize) -> bool {
+        if self.probing_suspended() || self.replaying {
+            return false;
+        }
+        let Some(target) = self.targets.get_mut(index).filter(|target| !target.paused) else {
+            return false;
+        };
+        let until = Instant::now() + BURST_DURATION;
+        target.burst_until = Some(until);
+        for profile in &target.profiles {
+            let _ = profile.worker.sender.send(ControlMessage::Burst(until));
+        }
+        true
+    }
+
+    pub fn toggle_global_pause(&mut self) {
+        self.global_paused = !self.global_paused;
+        self.sync_worker_pause();
+        let description = if self.global_paused {
+            "Paused all targets"
+        } else {
+            "Resumed all targets"
+        };
+        self.log_change(None, description.to_string());
+    }
+
+    pub fn toggle_time_axis(&mut self) {
+        self.global.absolute_time_axis = !self.global.absolute_time_axis;
+    }
+
+    pub fn toggle_log_scale(&mut self) {
+        self.global.log_scale_chart = !self.global.log_scale_chart;
+    }
+
+    /// Enters or leaves quiet hours based on the current local time.
+    pub fn refresh_quiet_hours(&mut self) {
+        self.refresh_quiet_hours_at(local_minute_of_day(SystemTime::now()));
+    }
+
+    pub fn refresh_quiet_hours_at(&mut self, minute_of_day: u16) {
+        let active = self
+            .global
+            .quiet_hours
+            .is_some_and(|quiet| quiet.contains(minute_of_day));
+        if active != self.quiet_active {
+            self.quiet_active = active;
+            self.sync_worker_pause();
+        }
+    }
+
+    /// Sends every worker its effective pause state: paused if its own target
+    /// is paused or probing is suspended globally.
+    fn sync_worker_pause(&mut self) {
+        let suspended = self.probing_suspended();
+        for target in self.targets.iter_mut() {
+            let paused = target.paused || suspended;
+            for worker in target.workers_mut() {
+                let _ = worker.sender.send(ControlMessage::Pause(paused));
+            }
+        }
+    }
+
+    /// Flags profiles whose worker has gone quiet and respawns workers that
+    /// stay silent well past their interval. Paused targets are never flagged.
+    pub fn check_worker_health(&mut self, sample_tx: &Sender<ProbeSample>) {
+        self.check_worker_health_at(SystemTime::now(), sample_tx);
+    }
+
+    fn check_worker_health_at(&mut self, now: SystemTime, sample_tx: &Sender<ProbeSample>) {
+        if self.replaying {
+            return;
+        }
+        let suspended = self.probing_suspended();
+        for target in &mut self.targets {
+            // A scheduled target's interval is the gap between its next two fires
+            let scheduled_period = target
+                .config
+                .schedule
+                .as_ref()
+                .map(|schedule| schedule.period_after(now));
+            let idle = target.schedule_idle(now) || scheduled_period == Some(None);
+            let config = &target.config;
+            let dns_cache = &target.dns_cache;
+            for profile in &mut target.profiles {
+                if target.paused || suspended || idle {
+                    profile.health.rearm(now);
+                    continue;
+                }
+                // A failing worker probes less often; don't mistake that for a stall
+                let interval = scheduled_period.flatten().unwrap_or_else(|| {
+                    backoff_interval(config.interval_for(&profile.config), profile.failure_streak)
+                });
+                let last_sample = profile.last_sample.as_ref().map(|sample| sample.ts);
+                let restart_due =
+                    profile
+                        .health
+                        .check(now, last_sample, interval, config.timeout_total);
+                if !restart_due {
+                    continue;
+                }
+
+                tracing::error!(
+                    target_url = %config.url,
+                    profile = %profile.config.name,
+                    restarts = profile.health.restarts + 1,
+                    "worker stalled, respawning"
+                );
+                // A wedged thread may never see Stop; detach it instead of blocking the UI
+                drop(profile.worker.stop());
+                profile.worker = spawn_profile_worker(
+                    config.clone(),
+                    profile.config.clone(),
+                    std::time::Duration::ZERO,
+                    self.probe_source,
+                    dns_cache.clone(),
+                    sample_tx.clone(),
+                );
+                profile.health.record_restart(now);
+            }
+        }
+    }
+
+    pub fn apply_sample(&mut self, mut sample: ProbeSample) {
         let key = ProfileKey {
             target_id: sample.target_id,
             profile_id: sample.profile_id,
         };
+        if sample.profile_id == RESOLVER_PROFILE_ID {
+            self.apply_resolver_sample(key, sample);
+            return;
+        }
+        self.session.record(
+            matches!(sample.result, crate::probe::ProbeResult::Ok),
+            Instant::now(),
+        );
+        if let Some(ip) = sample.endpoint {
+            self.apply_endpoint_sample(ip, sample);
+            return;
+        }
 
         if let Some(target) = self
             .targets
             .iter_mut()
             .find(|t| t.config.id == sample.target_id)
         {
-            if let Some(remote) = sample.remote {
-                target.last_ip = Some(remote.ip());
+            if !sample.resolved_ips.is_empty() {
+                target
+                    .ip_history
+                    .set_resolved(sample.ts, &sample.resolved_ips);
+            }
+            // Kept on the target rather than in every stored sample
+            if let Some(cert) = sample.cert.take() {
+                target.cert = Some(cert);
+            }
+            // Prefer the resolver's primary answer so warm connections pinned to an
+            // older address don't register as flips.
+            if let Some(ip) = sample
+                .resolved_ips
+                .first()
+                .copied()
+                .or_else(|| sample.remote.map(|remote| remote.ip()))
+            {
+                target.ip_history.record(sample.ts, ip);
             }
             if let Some(profile) = target
                 .profiles
@@ -230,92 +1091,888 @@ impl AppState {
                 .find(|p| p.config.id == sample.profile_id)
             {
                 profile.last_sample = Some(sample.clone());
+                profile.samples_seen += 1;
+                if !sample.burst {
+                    profile.effective_interval = sample.since_last_probe.map(|gap| {
+                        profile.effective_interval.map_or(gap, |smoothed| {
+                            smoothed.mul_f64(1.0 - EFFECTIVE_INTERVAL_WEIGHT)
+                                + gap.mul_f64(EFFECTIVE_INTERVAL_WEIGHT)
+                        })
+                    });
+                }
+                profile.failure_streak = match &sample.result {
+                    crate::probe::ProbeResult::Ok => 0,
+                    crate::probe::ProbeResult::Err(_) => profile.failure_streak.saturating_add(1),
+                };
                 profile.last_error = match &sample.result {
                     crate::probe::ProbeResult::Ok => None,
-                    crate::probe::ProbeResult::Err(err) => Some(err.kind),
+                    crate::probe::ProbeResult::Err(err) => {
+                        target.error_log.push(ErrorEntry {
+                            ts: sample.ts,
+                            profile: profile.config.name.clone(),
+                            kind: err.kind,
+                            message: err.message.to_string(),
+                        });
+                        Some(err.kind)
+                    }
                 };
-                let max_points = target.config.sampling.max_points_per_window;
-                self.metrics.push_sample(key, sample, max_points);
+                let sampling = &target.config.sampling;
+                self.metrics.push_sample(
+                    key,
+                    sample,
+                    sampling.max_points_per_window,
+                    sampling.retention,
+                );
             }
         }
     }
 
-    pub fn update_target_config(&mut self, index: usize, updated: TargetConfig) {
-        if let Some(target) = self.targets.get_mut(index) {
+    /// Records a lookup of the target's DNS worker: its answer in the address
+    /// history, a failure in the error log and its time as a sample of the
+    /// resolver stream, which the Dns metric is read from.
+    fn apply_resolver_sample(&mut self, key: ProfileKey, sample: ProbeSample) {
+        let Some(target) = self
+            .targets
+            .iter_mut()
+            .find(|t| t.config.id == sample.target_id)
+        else {
+            return;
+        };
+        match &sample.result {
+            crate::probe::ProbeResult::Ok => {
+                if target
+                    .ip_history
+                    .set_resolved(sample.ts, &sample.resolved_ips)
+                {
+                    tracing::info!(target_url = %target.config.url, ips = ?sample.resolved_ips, "dns answer changed");
+                }
+                if let Some(ip) = sample.resolved_ips.first() {
+                    target.ip_history.record(sample.ts, *ip);
+                }
+            }
+            crate::probe::ProbeResult::Err(err) => target.error_log.push(ErrorEntry {
+                ts: sample.ts,
+                profile: "dns".to_string(),
+                kind: err.kind,
+                message: err.message.to_string(),
+            }),
+        }
+        let sampling = &target.config.sampling;
+        self.metrics.push_sample(
+            key,
+            sample,
+            sampling.max_points_per_window,
+            sampling.retention,
+        );
+    }
+
+    /// Starts a background traceroute to the target's current address unless
+    /// one is already running.
+    pub fn start_traceroute(&mut self, index: usize) {
+        let Some(target) = self.targets.get_mut(index) else {
+            return;
+        };
+        if target
+            .traceroute
+            .as_ref()
+            .is_some_and(TracerouteRun::is_running)
+        {
+            return;
+        }
+        let Some(ip) = target.ip_history.current() else {
+            target.traceroute = Some(TracerouteRun::failed(TracerouteError::NoAddress));
+            return;
+        };
+        let port = target.config.url.port_or_known_default().unwrap_or(443);
+        let destination = std::net::SocketAddr::new(ip, port);
+        target.traceroute = Some(TracerouteRun::new(destination));
+        spawn_traceroute(target.config.id, destination, self.traceroute_tx.clone());
+    }
+
+    /// Starts TLS 1.3 detection in the background, confirming against the
+    /// first https target if there is one. Until it answers, targets added
+    /// without profiles get the TLS 1.2-only defaults.
+    pub fn start_tls13_detection(&mut self) {
+        let probe_url = self
+            .targets
+            .iter()
+            .map(|target| &target.config.url)
+            .find(|url| url.scheme() == "https")
+            .cloned();
+        self.tls13_rx = Some(spawn_tls13_detection(probe_url));
+    }
+
+    /// Applies the detection answer once it arrives. Returns true when
+    /// targets were upgraded, so the caller can persist them.
+    pub fn drain_tls13_detection(&mut self, sample_tx: &Sender<ProbeSample>) -> bool {
+        let Some(rx) = &self.tls13_rx else {
+            return false;
+        };
+        let supported = match rx.try_recv() {
+            Ok(supported) => supported,
+            Err(crossbeam_channel::TryRecvError::Empty) => return false,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => false,
+        };
+        self.tls13_rx = None;
+        self.apply_tls13_support(supported, sample_tx)
+    }
+
+    /// Adds the TLS 1.3 default profiles to targets that were defaulted
+    /// before detection finished, keeping their collected samples.
+    fn apply_tls13_support(&mut self, supported: bool, sample_tx: &Sender<ProbeSample>) -> bool {
+        let defaulted = std::mem::take(&mut self.tls12_defaulted);
+        if !supported {
+            return false;
+        }
+        let suspended = self.probing_suspended();
+        let mut upgraded = false;
+        for id in defaulted {
+            let Some(index) = self.targets.iter().position(|t| t.config.id == id) else {
+                continue;
+            };
+            // The new profiles authenticate like the ones already probing
+            let auth = self.targets[index]
+                .profiles
+                .first()
+                .and_then(|profile| profile.config.auth.clone());
+            let missing: Vec<ProfileConfig> =
+                default_profiles_for_capabilities(true, detect_h2_support())
+                    .into_iter()
+                    .filter(|profile| {
+                        !self.targets[index]
+                            .profiles
+                            .iter()
+                            .any(|existing| existing.config.name == profile.name)
+                    })
+                    .map(|profile| ProfileConfig {
+                        auth: auth.clone(),
+                        ..profile
+                    })
+                    .collect();
+            for profile in missing.into_iter().rev() {
+                let target = &mut self.targets[index];
+                let mut updated = target.config.clone();
+                updated.profiles.insert(0, profile.clone());
+                // Slot the new worker in after the existing ones' start offsets
+                let worker = spawn_profile_worker(
+                    updated.clone(),
+                    profile.clone(),
+                    updated.stagger_offset(updated.profiles.len() - 1),
+                    self.probe_source,
+                    target.dns_cache.clone(),
+                    sample_tx.clone(),
+                );
+                if target.paused || suspended {
+                    let _ = worker.sender.send(ControlMessage::Pause(true));
+                }
+                target.profiles.insert(
+                    0,
+                    ProfileRuntime {
+                        config: profile,
+                        worker,
+                        last_sample: None,
+                        last_error: None,
+                        health: WorkerHealth::default(),
+                        failure_streak: 0,
+                        samples_seen: 0,
+                        effective_interval: None,
+                    },
+                );
+                target.selected_profile += 1;
+                self.update_target_config(updated);
+                upgraded = true;
+            }
+        }
+        upgraded
+    }
+
+    /// Applies hop results posted by traceroute threads.
+    pub fn drain_traceroute_updates(&mut self) {
+        while let Ok(update) = self.traceroute_rx.try_recv() {
+            if let Some(run) = self
+                .targets
+                .iter_mut()
+                .find(|t| t.config.id == update.target_id)
+                .and_then(|t| t.traceroute.as_mut())
+            {
+                run.apply(update.event);
+            }
+        }
+    }
+
+    /// Replaces the config of the target with `updated.id` and forwards it to
+    /// the target's workers, without recording it in the change log.
+    pub fn update_target_config(&mut self, mut updated: TargetConfig) {
+        updated.sampling.histogram.cover(updated.timeout_total);
+        if let Some(target) = self.target_by_id_mut(updated.id) {
+            if target.config.alert_rules != updated.alert_rules {
+                target.alert_states = vec![AlertRuleState::default(); updated.alert_rules.len()];
+            }
+            // Probes must not keep connecting to an answer the worker no longer refreshes
+            if !updated.uses_dns_worker() {
+                target.dns_cache.clear();
+            }
             target.config = updated.clone();
-            for profile in target.profiles.iter_mut() {
-                let _ = profile
-                    .worker
+            for worker in target.workers_mut() {
+                let _ = worker
                     .sender
                     .send(ControlMessage::UpdateTarget(Box::new(updated.clone())));
             }
         }
     }
 
-    pub fn update_profile_config(
-        &mut self,
-        target_index: usize,
-        profile_index: usize,
-        updated: ProfileConfig,
-    ) {
-        if let Some(target) = self.targets.get_mut(target_index)
-            && let Some(profile) = target.profiles.get_mut(profile_index)
+    /// [`Self::update_target_config`] for an edit the user made: each changed
+    /// field is logged and the target's chart is marked.
+    pub fn edit_target_config(&mut self, updated: TargetConfig) {
+        let changes = self
+            .target_config(updated.id)
+            .map(|current| describe_target_change(current, &updated))
+            .unwrap_or_default();
+        let target_id = updated.id;
+        self.update_target_config(updated);
+        self.log_config_changes(target_id, changes);
+    }
+
+    /// Applies a new point cap and retention to the global default and every
+    /// target. Per-target histogram settings are left untouched.
+    pub fn set_retention_policy(&mut self, max_points: usize, retention: std::time::Duration) {
+        self.global.sampling.max_points_per_window = max_points;
+        self.global.sampling.retention = retention;
+        for index in 0..self.targets.len() {
+            let mut updated = self.targets[index].config.clone();
+            updated.sampling.max_points_per_window = max_points;
+            updated.sampling.retention = retention;
+            self.update_target_config(updated);
+        }
+        self.log_change(
+            None,
+            format!(
+                "Retention: {max_points} points or {}",
+                format_duration(retention)
+            ),
+        );
+    }
+
+    /// Only changes how windows are read, so no worker is told.
+    pub fn set_exclude_burst(&mut self, exclude: bool) {
+        self.global.exclude_burst_from_long_windows = exclude;
+        self.log_change(None, format!("Exclude burst samples: {}", on_off(exclude)));
+    }
+
+    /// Only changes how windows are read, so no worker is told.
+    pub fn set_count_missed_as_loss(&mut self, count: bool) {
+        self.global.count_missed_as_loss = count;
+        self.log_change(
+            None,
+            format!("Count missed probes as loss: {}", on_off(count)),
+        );
+    }
+
+    pub fn set_include_priming(&mut self, include: bool) {
+        self.global.sampling.include_priming_samples = include;
+        for index in 0..self.targets.len() {
+            let mut updated = self.targets[index].config.clone();
+            updated.sampling.include_priming_samples = include;
+            self.update_target_config(updated);
+        }
+        self.log_change(
+            None,
+            format!("Include priming samples: {}", on_off(include)),
+        );
+    }
+
+    /// Lets curl workers pick up a proxy from the environment, from their next probe.
+    pub fn set_use_proxy_env(&mut self, enabled: bool) {
+        self.global.use_proxy_env = enabled;
+        crate::probe_engine::set_use_proxy_env(enabled);
+    }
+
+    /// Caps how many download-heavy probes run at once, from their next probe.
+    pub fn set_download_slots(&mut self, slots: Option<u32>) {
+        self.global.download_slots = slots;
+        crate::runtime::set_download_slots(slots);
+    }
+
+    /// Switches the UI theme, resolving `Auto` from the environment.
+    pub fn set_theme(&mut self, theme: ThemeName) {
+        self.global.theme = theme;
+        self.theme = theme.resolve(theme_env);
+    }
+
+    /// The environment proxy live probes go through, if any.
+    pub fn active_proxy(&self) -> Option<&'static str> {
+        let curl = matches!(
+            self.probe_source,
+            ProbeSource::Network(ProbeBackendKind::Curl)
+        );
+        if !curl || self.replaying || !self.global.use_proxy_env {
+            return None;
+        }
+        crate::probe_engine::env_proxy()
+    }
+
+    /// Points the target at `url` while keeping its ids, so collected samples
+    /// stay attached.
+    pub fn update_target_url(&mut self, target_id: TargetId, url: Url) {
+        let Some(target) = self.target_by_id_mut(target_id) else {
+            return;
+        };
+        if target.config.url == url {
+            return;
+        }
+        if target.config.url.host_str() != url.host_str() {
+            target.ip_history = IpHistory::default();
+            target.cert = None;
+        }
+        let mut updated = target.config.clone();
+        updated.url = url;
+        self.edit_target_config(updated);
+    }
+
+    /// Replaces the config of the target's profile with `updated.id` and
+    /// forwards it to that profile's worker, without recording it in the
+    /// change log.
+    pub fn update_profile_config(&mut self, target_id: TargetId, updated: ProfileConfig) {
+        if let Some(target) = self.target_by_id_mut(target_id)
+            && let Some(profile) = target
+                .profiles
+                .iter_mut()
+                .find(|profile| profile.config.id == updated.id)
+        {
+            if let Some(persisted) = target
+                .config
+                .profiles
+                .iter_mut()
+                .find(|p| p.id == updated.id)
+            {
+                *persisted = updated.clone();
+            }
+            // A warm connection was negotiated with the old protocol settings
+            let reset_connection = profile.config.changes_connection(&updated);
+            profile.config = updated.clone();
+            let message = || ControlMessage::UpdateProfile {
+                config: Box::new(updated.clone()),
+                reset_connection,
+            };
+            let _ = profile.worker.sender.send(message());
+            for endpoint in target
+                .endpoints
+                .iter()
+                .filter(|endpoint| endpoint.profile_id == updated.id)
+            {
+                let _ = endpoint.worker.sender.send(message());
+            }
+        }
+    }
+
+    /// [`Self::update_profile_config`] for an edit the user made: each
+    /// changed field is logged and the target's chart is marked.
+    pub fn edit_profile_config(&mut self, target_id: TargetId, updated: ProfileConfig) {
+        let changes = self
+            .targets
+            .iter()
+            .find(|target| target.config.id == target_id)
+            .and_then(|target| {
+                target
+                    .profiles
+                    .iter()
+                    .find(|profile| profile.config.id == updated.id)
+            })
+            .map(|profile| describe_profile_change(&profile.config, &updated))
+            .unwrap_or_default();
+        self.update_profile_config(target_id, updated);
+        self.log_config_changes(target_id, changes);
+    }
+
+    /// Appends a user-initiated change to the change log. Edits reach it
+    /// through [`Self::edit_target_config`] and [`Self::edit_profile_config`];
+    /// updates the app makes on its own stay out of the history.
+    fn log_change(&mut self, target_id: Option<TargetId>, description: String) {
+        let target = match target_id {
+            Some(id) => match self.target_config(id) {
+                Some(config) => config.display_name(),
+                None => return,
+            },
+            None => "All targets".to_string(),
+        };
+        self.change_log.push(ChangeEntry {
+            ts: SystemTime::now(),
+            target_id,
+            target,
+            description,
+        });
+    }
+
+    /// Logs each line of a config change and marks it on the target's chart
+    /// once, so samples on either side of the edit can be told apart.
+    fn log_config_changes(&mut self, target_id: TargetId, changes: Vec<String>) {
+        if changes.is_empty() {
+            return;
+        }
+        if let Some(target) = self.target_by_id_mut(target_id) {
+            target.record_config_change(SystemTime::now());
+        }
+        for description in changes {
+            self.log_change(Some(target_id), description);
+        }
+    }
+
+    /// Renames a profile of the target. Names are trimmed and must be unique
+    /// within the target, ignoring case, so the legend tells them apart.
+    pub fn rename_profile(
+        &mut self,
+        target_id: TargetId,
+        profile_id: ProfileId,
+        name: &str,
+    ) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Profile name cannot be empty".to_string());
+        }
+        let Some(target) = self
+            .targets
+            .iter()
+            .find(|target| target.config.id == target_id)
+        else {
+            return Ok(());
+        };
+        if target.profiles.iter().any(|profile| {
+            profile.config.id != profile_id && profile.config.name.eq_ignore_ascii_case(name)
+        }) {
+            return Err(format!("This target already has a profile named {name:?}"));
+        }
+        let Some(profile) = target
+            .profiles
+            .iter()
+            .find(|profile| profile.config.id == profile_id)
+        else {
+            return Ok(());
+        };
+        let mut updated = profile.config.clone();
+        updated.name = name.to_string();
+        self.edit_profile_config(target_id, updated);
+        Ok(())
+    }
+
+    /// Evaluates every target's alert rules against the current window and
+    /// records fire/clear transitions in the alert log.
+    pub fn evaluate_alerts(&mut self) {
+        for index in 0..self.targets.len() {
+            let target = &self.targets[index];
+            if target.paused || self.probing_suspended() || target.config.alert_rules.is_empty() {
+                continue;
+            }
+            let aggregates: Vec<WindowedAggregate> = target
+                .profiles
+                .iter()
+                .map(|profile| self.target_aggregate(target, profile))
+                .collect();
+            let observations: Vec<Option<f64>> = target
+                .config
+                .alert_rules
+                .iter()
+                .map(|rule| {
+                    aggregates
+                        .iter()
+                        .zip(&target.profiles)
+                        // A warming profile's numbers would fire on one bad probe
+                        .filter(|(_, profile)| target.warm_up(profile).is_none())
+                        .filter_map(|(aggregate, _)| aggregate.by_metric.get(&rule.metric))
+                        .map(|stats| rule.observe(stats))
+                        .fold(None, |worst, value| rule.worst_of(worst, value))
+                })
+                .collect();
+
+            let target = &mut self.targets[index];
+            target
+                .alert_states
+                .resize_with(target.config.alert_rules.len(), AlertRuleState::default);
+            for ((rule, state), value) in target
+                .config
+                .alert_rules
+                .iter()
+                .zip(target.alert_states.iter_mut())
+                .zip(observations)
+            {
+                if let Some(transition) = state.evaluate(rule, value) {
+                    self.alert_log.push(AlertEvent {
+                        ts: std::time::SystemTime::now(),
+                        target_id: target.config.id,
+                        target_label: target.config.url.to_string(),
+                        rule: rule.to_string(),
+                        value: state
+                            .last_value
+                            .map(|value| rule.format_value(value))
+                            .unwrap_or_default(),
+                        transition,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Checks each target's probe loss over the last minute against the
+    /// notification rule, at most once per [`NOTIFY_EVAL_INTERVAL`], and
+    /// returns the notifications to deliver. Nothing is returned while the
+    /// rule is unset or a session is being replayed.
+    pub fn evaluate_notifications(&mut self, now: Instant) -> Vec<Notification> {
+        let Some(rule) = self.global.notify.filter(|_| !self.replaying) else {
+            return Vec::new();
+        };
+        if self
+            .notify_checked
+            .is_some_and(|checked| now.saturating_duration_since(checked) < NOTIFY_EVAL_INTERVAL)
+        {
+            return Vec::new();
+        }
+        self.notify_checked = Some(now);
+
+        let mut notifications = Vec::new();
+        for index in 0..self.targets.len() {
+            let target = &self.targets[index];
+            let loss = if target.paused || self.probing_suspended() {
+                None
+            } else {
+                self.minute_loss_rate(target)
+            };
+            let target = &mut self.targets[index];
+            let Some(transition) = target.notify_state.evaluate(&rule, loss, now) else {
+                continue;
+            };
+            let host = target.config.display_name();
+            let loss_pct = loss.unwrap_or_default() * 100.0;
+            notifications.push(match transition {
+                NotifyTransition::Down => Notification {
+                    summary: format!("{host} is failing"),
+                    body: match target.error_log.iter_recent().next() {
+                        Some(error) => format!(
+                            "{loss_pct:.0}% probe loss over 1m; last error {}: {}",
+                            error.kind.label(),
+                            error.message
+                        ),
+                        None => format!("{loss_pct:.0}% probe loss over 1m"),
+                    },
+                },
+                NotifyTransition::Recovered => Notification {
+                    summary: format!("{host} recovered"),
+                    body: format!("{loss_pct:.0}% probe loss over 1m"),
+                },
+            });
+        }
+        notifications
+    }
+
+    /// Share of failed probes across the target's profiles over the last
+    /// minute; `None` without samples.
+    fn minute_loss_rate(&self, target: &TargetRuntime) -> Option<f64> {
+        let (failed, total) = self.probe_loss(target, WindowSpec::M1);
+        (total > 0).then(|| failed / total as f64)
+    }
+
+    /// Failed and total probe counts across the target's profiles in `window`.
+    pub(super) fn probe_loss(&self, target: &TargetRuntime, window: WindowSpec) -> (f64, u64) {
+        target
+            .profiles
+            .iter()
+            .filter_map(|profile| {
+                let aggregate = self.metrics.windowed_aggregate(
+                    ProfileKey {
+                        target_id: target.config.id,
+                        profile_id: profile.config.id,
+                    },
+                    window,
+                    &target.config.sampling,
+                    &self.aggregate_view(),
+                    self.failure_latency(target),
+                );
+                let stats = aggregate.by_metric.get(&MetricKind::ProbeLossRate)?;
+                Some((stats.mean? * stats.n as f64, stats.n))
+            })
+            .fold((0.0, 0), |(failed, total), (f, n)| (failed + f, total + n))
+    }
+
+    pub fn active_alert_count(&self) -> usize {
+        self.targets.iter().map(TargetRuntime::active_alerts).sum()
+    }
+
+    /// Moves to the next configured window; a window that is no longer
+    /// configured moves to the next longer one.
+    pub fn cycle_window(&mut self) {
+        let windows = &self.global.windows;
+        let next = match windows.iter().position(|w| *w == self.window) {
+            Some(idx) => windows.get((idx + 1) % windows.len()),
+            None => windows
+                .iter()
+                .find(|w| w.duration() > self.window.duration())
+                .or_else(|| windows.first()),
+        };
+        if let Some(next) = next {
+            self.window = *next;
+        }
+    }
+
+    /// Window the target's panes and statistics cover: its own override,
+    /// else the global window.
+    pub fn target_window(&self, target: &TargetRuntime) -> WindowSpec {
+        target.window_override.unwrap_or(self.window)
+    }
+
+    /// Steps the selected target's override through the configured windows;
+    /// past the last one the target follows the global window again.
+    pub fn cycle_target_window(&mut self) {
+        let windows = self.global.windows.clone();
+        let Some(target) = self.selected_target_mut() else {
+            return;
+        };
+        let before = target.window_override;
+        target.window_override = match before {
+            None => windows.first().copied(),
+            Some(current) => match windows.iter().position(|w| *w == current) {
+                Some(idx) => windows.get(idx + 1).copied(),
+                None => windows
+                    .iter()
+                    .find(|w| w.duration() > current.duration())
+                    .copied(),
+            },
+        };
+        let after = target.window_override;
+        let target_id = target.config.id;
+        if before != after {
+            let label = |window: Option<WindowSpec>| {
+                window.map_or_else(|| "global".to_string(), |window| window.to_string())
+            };
+            self.log_change(
+                Some(target_id),
+                format!("Window: {} → {}", label(before), label(after)),
+            );
+        }
+    }
+
+    /// Number of targets with a window of their own.
+    pub fn window_overrides(&self) -> usize {
+        self.targets
+            .iter()
+            .filter(|target| target.window_override.is_some())
+            .count()
+    }
+
+    /// Largest configured window that retention cannot keep whole for some
+    /// profile, given its probe interval.
+    pub fn retention_shortfall(&self) -> Option<WindowSpec> {
+        let largest = self
+            .global
+            .windows
+            .iter()
+            .copied()
+            .max_by_key(|window| window.duration())?;
+        let span = largest.duration();
+        let covered = if self.targets.is_empty() {
+            self.global.sampling.retention >= span
+        } else {
+            self.targets.iter().all(|target| {
+                target.profiles.iter().all(|profile| {
+                    let interval = target.config.interval_for(&profile.config);
+                    target.config.sampling.covers(span, interval)
+                })
+            })
+        };
+        (!covered).then_some(largest)
+    }
+
+    pub fn cycle_pane_mode(&mut self, target_id: TargetId) {
+        if let Some(target) = self.target_by_id_mut(target_id) {
+            target.pane_mode = target.pane_mode.cycle();
+        }
+    }
+
+    /// Toggles `metric` on the selected target's chart.
+    pub fn toggle_metric(&mut self, metric: MetricKind) {
+        if let Some(target) = self.selected_target_mut() {
+            target.toggle_metric(metric);
+        }
+    }
+
+    /// Gives every target the selected target's chart metrics, compare
+    /// metric and metrics category. The metrics become the default for new
+    /// targets; the category does not, so new targets still open on
+    /// `MetricsCategory::default()`. Returns how many other targets changed.
+    pub fn copy_view_to_all_targets(&mut self) -> usize {
+        let Some(source) = self.selected_target() else {
+            return 0;
+        };
+        let (metric, metrics, category) = (
+            source.selected_metric,
+            source.selected_metrics.clone(),
+            source.metrics_category,
+        );
+        let mut changed = 0;
+        for target in &mut self.targets {
+            if target.selected_metric != metric
+                || target.selected_metrics != metrics
+                || target.metrics_category != category
+            {
+                changed += 1;
+            }
+            target.selected_metric = metric;
+            target.selected_metrics.clone_from(&metrics);
+            target.metrics_category = category;
+        }
+        self.selected_metric = metric;
+        self.selected_metrics = metrics;
+        changed
+    }
+
+    /// The selected target, or `None` when the filter hides every target.
+    pub fn selected_target(&self) -> Option<&TargetRuntime> {
+        if self.selected_group.is_some() || !self.target_visible(self.selected_target) {
+            return None;
+        }
+        self.targets.get(self.selected_target)
+    }
+
+    /// Id of the selected target, stable across reordering and deletion.
+    pub fn selected_target_id(&self) -> Option<TargetId> {
+        self.selected_target().map(|target| target.config.id)
+    }
+
+    fn target_by_id_mut(&mut self, target_id: TargetId) -> Option<&mut TargetRuntime> {
+        self.targets
+            .iter_mut()
+            .find(|target| target.config.id == target_id)
+    }
+
+    pub fn target_config(&self, target_id: crate::config::TargetId) -> Option<&TargetConfig> {
+        self.targets
+            .iter()
+            .find(|target| target.config.id == target_id)
+            .map(|target| &target.config)
+    }
+
+    pub fn selected_target_mut(&mut self) -> Option<&mut TargetRuntime> {
+        if self.selected_group.is_some() || !self.target_visible(self.selected_target) {
+            return None;
+        }
+        self.targets.get_mut(self.selected_target)
+    }
+
+    pub fn target_aggregate(
+        &self,
+        target: &TargetRuntime,
+        profile: &ProfileRuntime,
+    ) -> WindowedAggregate {
+        self.target_aggregate_in(target, profile, self.target_window(target))
+    }
+
+    /// Like `target_aggregate`, over `window` instead of the selected one.
+    pub fn target_aggregate_in(
+        &self,
+        target: &TargetRuntime,
+        profile: &ProfileRuntime,
+        window: WindowSpec,
+    ) -> WindowedAggregate {
+        let key = target.view_key(profile);
+        let mut aggregate = self.metrics.windowed_aggregate(
+            key,
+            window,
+            &target.config.sampling,
+            &self.aggregate_view(),
+            self.failure_latency(target),
+        );
+        let dns_key = self.metric_source_key(target, key, MetricKind::Dns);
+        if dns_key != key
+            && let Some(stats) = self
+                .metrics
+                .windowed_aggregate(
+                    dns_key,
+                    window,
+                    &target.config.sampling,
+                    &self.aggregate_view(),
+                    None,
+                )
+                .by_metric
+                .remove(&MetricKind::Dns)
         {
-            profile.config = updated.clone();
-            let _ = profile
-                .worker
-                .sender
-                .send(ControlMessage::UpdateProfile(Box::new(updated)));
+            aggregate.by_metric.insert(MetricKind::Dns, stats);
         }
-    }
-
-    pub fn cycle_window(&mut self) {
-        let windows = &self.global.windows;
-        if let Some(idx) = windows.iter().position(|w| *w == self.window) {
-            let next = (idx + 1) % windows.len();
-            self.window = windows[next];
+        // Cold profiles open a connection per probe on purpose
+        if profile.config.conn_reuse != ConnReusePolicy::Warm {
+            aggregate
+                .by_metric
+                .insert(MetricKind::ConnReuseRate, MetricStats::from_scalar(None, 0));
         }
+        aggregate
     }
 
-    pub fn cycle_pane_mode(&mut self, index: usize) {
-        if let Some(target) = self.targets.get_mut(index) {
-            target.pane_mode = target.pane_mode.cycle();
+    /// Key whose samples `metric` of the profile at `key` is read from: the
+    /// DNS worker's stream for Dns once it has reported, else the profile's own.
+    pub fn metric_source_key(
+        &self,
+        target: &TargetRuntime,
+        key: ProfileKey,
+        metric: MetricKind,
+    ) -> ProfileKey {
+        let resolver = ProfileKey {
+            target_id: target.config.id,
+            profile_id: RESOLVER_PROFILE_ID,
+        };
+        if metric == MetricKind::Dns
+            && target.config.uses_dns_worker()
+            && self.metrics.newest_sample_ts(resolver).is_some()
+        {
+            resolver
+        } else {
+            key
         }
     }
 
-    pub fn toggle_metric(&mut self, metric: MetricKind) {
-        if self.selected_metrics.contains(&metric) {
-            self.selected_metrics.remove(&metric);
-        } else {
-            self.selected_metrics.insert(metric);
-        }
-        if let Some(metric) = self.selected_metrics.iter().next().copied() {
-            self.selected_metric = metric;
+    /// How every target's windows are read: the link capacity and the
+    /// display toggles, which never reach the workers.
+    pub fn aggregate_view(&self) -> AggregateView {
+        AggregateView {
+            link_capacity_mbps: self.global.link_capacity_mbps,
+            exclude_burst_from_long_windows: self.global.exclude_burst_from_long_windows,
+            count_missed_as_loss: self.global.count_missed_as_loss,
         }
     }
 
-    pub fn selected_target(&self) -> Option<&TargetRuntime> {
-        self.targets.get(self.selected_target)
+    /// Latency counted for the target's timed-out probes, when the global
+    /// "include failures" setting is on.
+    pub fn failure_latency(&self, target: &TargetRuntime) -> Option<std::time::Duration> {
+        self.global
+            .include_failures_in_latency
+            .then_some(target.config.timeout_total)
     }
 
-    pub fn selected_target_mut(&mut self) -> Option<&mut TargetRuntime> {
-        self.targets.get_mut(self.selected_target)
+    /// Shareable text summary of the target over the selected window.
+    pub fn target_snapshot(&self, index: usize) -> Option<String> {
+        let target = self.targets.get(index)?;
+        let aggregates: Vec<WindowedAggregate> = target
+            .profiles
+            .iter()
+            .map(|profile| self.target_aggregate(target, profile))
+            .collect();
+        let profiles: Vec<SnapshotProfile> = target
+            .profiles
+            .iter()
+            .zip(&aggregates)
+            .map(|(profile, aggregate)| SnapshotProfile {
+                name: &profile.config.name,
+                aggregate,
+            })
+            .collect();
+        Some(format_snapshot(
+            target.config.url.as_str(),
+            self.target_window(target),
+            &profiles,
+        ))
     }
 
-    pub fn target_aggregate(
-        &self,
-        target: &TargetRuntime,
-        profile: &ProfileRuntime,
-    ) -> WindowedAggregate {
-        self.metrics.windowed_aggregate(
-            ProfileKey {
-                target_id: target.config.id,
-                profile_id: profile.config.id,
-            },
-            self.window,
-            &target.config.sampling,
-            self.global.link_capacity_mbps,
-        )
+    /// Whether retention dropped samples of any profile inside the selected window.
+    pub fn window_partially_covered(&self, target: &TargetRuntime) -> bool {
+        target
+            .profiles
+            .iter()
+            .any(|profile| !self.target_aggregate(target, profile).fully_covered)
     }
 
     pub fn target_summary(&self, target: &TargetRuntime) -> GlobalSummary {
@@ -329,9 +1986,13 @@ impl AppState {
             if let Some(total_stats) = aggregate.by_metric.get(&MetricKind::Total) {
                 summary.requests += total_stats.n;
             }
+            summary.missed += aggregate.missed_probes;
             for (kind, count) in &aggregate.error_breakdown {
                 *summary.errors.entry(*kind).or_insert(0) += count;
             }
+            for (status, count) in &aggregate.status_breakdown {
+                *summary.statuses.entry(*status).or_insert(0) += count;
+            }
         }
         let total_errors: u64 = summary.errors.values().sum();
         summary.successes = summary.requests.saturating_sub(total_errors);
@@ -341,12 +2002,134 @@ impl AppState {
             .filter(|(kind, _)| kind.is_timeout())
             .map(|(_, count)| *count)
             .sum();
+        summary.slo = target
+            .config
+            .slo_target
+            .map(|target_pct| self.target_slo_counts(target).status(target_pct));
         summary
     }
 
+    /// Spikes of every latency metric across the target's profiles in the
+    /// current window, newest first and capped at `limit`.
+    /// Outages of `profile` overlapping the target's window, oldest first.
+    pub fn profile_outages(
+        &self,
+        target: &TargetRuntime,
+        profile: &ProfileRuntime,
+    ) -> Vec<OutageEvent> {
+        let key = ProfileKey {
+            target_id: target.config.id,
+            profile_id: profile.config.id,
+        };
+        self.metrics.outages(key, self.target_window(target))
+    }
+
+    /// Outages of every profile of the target in its window, newest first,
+    /// each with the profile's name.
+    pub fn target_outages(&self, target: &TargetRuntime) -> Vec<(String, OutageEvent)> {
+        let mut outages: Vec<(String, OutageEvent)> = target
+            .profiles
+            .iter()
+            .flat_map(|profile| {
+                self.profile_outages(target, profile)
+                    .into_iter()
+                    .map(|outage| (profile.config.name.clone(), outage))
+            })
+            .collect();
+        outages.sort_by(|a, b| b.1.start.cmp(&a.1.start));
+        outages
+    }
+
+    pub fn recent_spikes(&self, target: &TargetRuntime, limit: usize) -> Vec<(String, SpikeEvent)> {
+        let mut spikes: Vec<(String, SpikeEvent)> = Vec::new();
+        for profile in &target.profiles {
+            let key = ProfileKey {
+                target_id: target.config.id,
+                profile_id: profile.config.id,
+            };
+            for &metric in MetricKind::iter_all()
+                .iter()
+                .filter(|metric| metric.is_latency_metric())
+            {
+                spikes.extend(
+                    self.metrics
+                        .spikes(
+                            key,
+                            self.target_window(target),
+                            metric,
+                            &target.config.sampling,
+                            &self.aggregate_view(),
+                        )
+                        .into_iter()
+                        .map(|spike| (profile.config.name.clone(), spike)),
+                );
+            }
+        }
+        spikes.sort_by(|a, b| b.1.ts.cmp(&a.1.ts));
+        spikes.truncate(limit);
+        spikes
+    }
+
+    /// Pairs each warm profile with the first unpaired cold profile whose
+    /// request shape (HTTP/TLS version, method, read limit, headers, auth)
+    /// matches.
+    pub fn conn_reuse_pairs(&self, target: &TargetRuntime) -> Vec<ConnReusePair> {
+        fn same_request(left: &ProfileConfig, right: &ProfileConfig) -> bool {
+            left.http == right.http
+                && left.tls == right.tls
+                && left.method == right.method
+                && left.max_read_bytes == right.max_read_bytes
+                && left.headers == right.headers
+                && left.auth == right.auth
+        }
+
+        let mut pairs = Vec::new();
+        let mut paired_cold = HashSet::new();
+        for (warm, warm_profile) in target.profiles.iter().enumerate() {
+            if warm_profile.config.conn_reuse != ConnReusePolicy::Warm {
+                continue;
+            }
+            let cold = target
+                .profiles
+                .iter()
+                .enumerate()
+                .position(|(idx, profile)| {
+                    profile.config.conn_reuse == ConnReusePolicy::Cold
+                        && !paired_cold.contains(&idx)
+                        && same_request(&warm_profile.config, &profile.config)
+                });
+            if let Some(cold) = cold {
+                paired_cold.insert(cold);
+                pairs.push(ConnReusePair { warm, cold });
+            }
+        }
+        pairs
+    }
+
+    pub fn target_slo_counts(&self, target: &TargetRuntime) -> SloCounts {
+        target
+            .profiles
+            .iter()
+            .map(|profile| {
+                self.metrics.slo_counts(
+                    ProfileKey {
+                        target_id: target.config.id,
+                        profile_id: profile.config.id,
+                    },
+                    self.target_window(target),
+                    target.config.slo_latency_ms,
+                )
+            })
+            .fold(SloCounts::default(), |acc, counts| SloCounts {
+                total: acc.total + counts.total,
+                good: acc.good + counts.good,
+            })
+    }
+
     pub fn to_persisted_state(&self) -> crate::storage::PersistedState {
         crate::storage::PersistedState {
-            version: "1".to_string(),
+            version: crate::storage::STATE_FILE_VERSION.to_string(),
+            schema_version: crate::data_model::SCHEMA_VERSION,
             global_config: self.global.clone(),
             targets: self
                 .targets
@@ -357,6 +2140,11 @@ impl AppState {
                     selected_profile: t.selected_profile,
                     pane_mode: t.pane_mode,
                     metrics_category: t.metrics_category,
+                    selected_metric: Some(t.selected_metric),
+                    selected_metrics: Some(t.selected_metrics.clone()),
+                    baselines: t.baselines.clone(),
+                    active_baseline: t.active_baseline,
+                    annotations: t.annotations.clone(),
                 })
                 .collect(),
             ui_state: crate::storage::PersistedUiState {
@@ -364,6 +2152,7 @@ impl AppState {
                 selected_metric: self.selected_metric,
                 selected_metrics: self.selected_metrics.clone(),
                 window: self.window,
+                collapsed_groups: self.collapsed_groups.clone(),
             },
         }
     }
@@ -375,11 +2164,15 @@ impl AppState {
     ) {
         for persisted_target in &state.targets {
             let profiles = persisted_target.config.profiles.clone();
+            let (dns_cache, dns_worker) = self.spawn_dns(&persisted_target.config, &sample_tx);
             let mut profile_runtimes = Vec::new();
-            for profile in &profiles {
+            for (index, profile) in profiles.iter().enumerate() {
                 let worker = spawn_profile_worker(
                     persisted_target.config.clone(),
                     profile.clone(),
+                    persisted_target.config.stagger_offset(index),
+                    self.probe_source,
+                    dns_cache.clone(),
                     sample_tx.clone(),
                 );
                 profile_runtimes.push(ProfileRuntime {
@@ -387,18 +2180,50 @@ impl AppState {
                     worker,
                     last_sample: None,
                     last_error: None,
+                    health: WorkerHealth::default(),
+                    failure_streak: 0,
+                    samples_seen: 0,
+                    effective_interval: None,
                 });
             }
 
             self.targets.push(TargetRuntime {
                 config: persisted_target.config.clone(),
                 paused: false,
-                last_ip: None,
+                ip_history: IpHistory::default(),
                 profiles: profile_runtimes,
                 view_mode: persisted_target.view_mode,
                 selected_profile: persisted_target.selected_profile,
                 pane_mode: persisted_target.pane_mode,
                 metrics_category: persisted_target.metrics_category,
+                selected_metric: persisted_target
+                    .selected_metric
+                    .unwrap_or(state.ui_state.selected_metric),
+                selected_metrics: persisted_target
+                    .selected_metrics
+                    .clone()
+                    .unwrap_or_else(|| state.ui_state.selected_metrics.clone()),
+                alert_states: vec![
+                    AlertRuleState::default();
+                    persisted_target.config.alert_rules.len()
+                ],
+                chart_cursor: None,
+                traceroute: None,
+                error_log: ErrorLog::default(),
+                config_changes: Vec::new(),
+                cert: None,
+                baselines: persisted_target.baselines.clone(),
+                active_baseline: persisted_target
+                    .active_baseline
+                    .filter(|&index| index < persisted_target.baselines.len()),
+                annotations: persisted_target.annotations.clone(),
+                notify_state: NotifyState::default(),
+                burst_until: None,
+                dns_cache,
+                dns_worker,
+                endpoints: Vec::new(),
+                endpoint_view: EndpointView::default(),
+                window_override: None,
             });
         }
 
@@ -408,6 +2233,1112 @@ impl AppState {
             .min(self.targets.len().saturating_sub(1));
         self.selected_metric = state.ui_state.selected_metric;
         self.selected_metrics = state.ui_state.selected_metrics.clone();
-        self.window = state.ui_state.window;
+        self.collapsed_groups = state.ui_state.collapsed_groups.clone();
+        // The saved window may have been dropped from the configured ones
+        self.window = if self.global.windows.contains(&state.ui_state.window) {
+            state.ui_state.window
+        } else {
+            self.global.default_window
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::endpoints::MAX_ENDPOINT_STREAMS;
+    use super::super::groups::TargetStatus;
+    use super::super::ip_history::ENDPOINT_GRACE_LOOKUPS;
+    use super::*;
+    use crate::config::{ProbeMethod, SamplingConfig};
+    use crate::features::probe::testing::SampleBuilder;
+
+    #[test]
+    fn quiet_hours_toggle_suspension_without_touching_global_pause() {
+        let mut app = AppState::new(GlobalConfig {
+            quiet_hours: Some("22:00-07:00".parse().expect("quiet hours")),
+            ..GlobalConfig::default()
+        });
+
+        app.refresh_quiet_hours_at(23 * 60);
+        assert!(app.quiet_active);
+        assert!(app.probing_suspended());
+        assert!(!app.global_paused);
+
+        app.refresh_quiet_hours_at(8 * 60);
+        assert!(!app.quiet_active);
+        assert!(!app.probing_suspended());
+
+        app.toggle_global_pause();
+        assert!(app.probing_suspended());
+        app.toggle_global_pause();
+        assert!(!app.probing_suspended());
+    }
+
+    fn runtime_with_profiles(specs: &str) -> TargetRuntime {
+        let profiles = crate::app::parse_profile_specs(specs)
+            .into_iter()
+            .map(|config| ProfileRuntime {
+                config,
+                worker: WorkerHandle::idle(),
+                last_sample: None,
+                last_error: None,
+                health: WorkerHealth::default(),
+                failure_streak: 0,
+                samples_seen: 0,
+                effective_interval: None,
+            })
+            .collect();
+        TargetRuntime {
+            config: TargetConfig::new(
+                Url::parse("https://example.com").unwrap(),
+                default_profiles_for_capabilities(false, true),
+            ),
+            paused: false,
+            ip_history: IpHistory::default(),
+            profiles,
+            view_mode: ProfileViewMode::Single,
+            selected_profile: 0,
+            pane_mode: TargetPaneMode::Split,
+            metrics_category: MetricsCategory::default(),
+            selected_metric: MetricKind::Total,
+            selected_metrics: HashSet::from([MetricKind::Total]),
+            alert_states: Vec::new(),
+            chart_cursor: None,
+            traceroute: None,
+            error_log: ErrorLog::default(),
+            config_changes: Vec::new(),
+            cert: None,
+            baselines: Vec::new(),
+            annotations: Vec::new(),
+            active_baseline: None,
+            notify_state: NotifyState::default(),
+            burst_until: None,
+            dns_cache: DnsCache::default(),
+            dns_worker: None,
+            endpoints: Vec::new(),
+            endpoint_view: EndpointView::default(),
+            window_override: None,
+        }
+    }
+
+    fn app_with_hosts(hosts: &[&str]) -> AppState {
+        let mut app = AppState::new(GlobalConfig::default());
+        for host in hosts {
+            let mut target = runtime_with_profiles("h2");
+            target.config.url = Url::parse(&format!("https://{host}")).unwrap();
+            app.targets.push(target);
+        }
+        app
+    }
+
+    fn hosts(app: &AppState) -> Vec<&str> {
+        app.targets
+            .iter()
+            .map(|target| target.config.url.host_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn remove_target_keeps_selection_on_same_target() {
+        let mut app = app_with_hosts(&["a.com", "b.com", "c.com", "d.com"]);
+        app.selected_target = 2;
+        app.remove_target(3);
+        assert_eq!(
+            app.selected_target().unwrap().config.url.host_str(),
+            Some("c.com")
+        );
+        app.remove_target(0);
+        assert_eq!(
+            app.selected_target().unwrap().config.url.host_str(),
+            Some("c.com")
+        );
+        app.remove_target(1);
+        assert_eq!(app.selected_target, 0);
+        assert_eq!(
+            app.selected_target().unwrap().config.url.host_str(),
+            Some("b.com")
+        );
+    }
+
+    #[test]
+    fn remove_target_at_head_middle_and_tail_tracks_the_selection() {
+        // (selected, removed, host selected afterwards)
+        let cases = [
+            (0, 0, "b.com"),
+            (2, 0, "c.com"),
+            (4, 0, "e.com"),
+            (0, 2, "a.com"),
+            (2, 2, "d.com"),
+            (4, 2, "e.com"),
+            (0, 4, "a.com"),
+            (2, 4, "c.com"),
+            (4, 4, "d.com"),
+        ];
+        for (selected, removed, expected) in cases {
+            let mut app = app_with_hosts(&["a.com", "b.com", "c.com", "d.com", "e.com"]);
+            app.selected_target = selected;
+            app.remove_target(removed);
+            assert_eq!(
+                app.selected_target().unwrap().config.url.host_str(),
+                Some(expected),
+                "selected {selected}, removed {removed}"
+            );
+        }
+
+        let mut app = app_with_hosts(&["a.com"]);
+        app.remove_target(0);
+        assert_eq!(app.selected_target, 0);
+        assert!(app.selected_target().is_none());
+    }
+
+    #[test]
+    fn target_updates_follow_the_id_after_reordering() {
+        let mut app = app_with_hosts(&["a.com", "b.com", "c.com"]);
+        let id = app.targets[2].config.id;
+        app.remove_target(0);
+
+        app.toggle_pause(id);
+        app.cycle_pane_mode(id);
+        let mut updated = app.targets[1].config.clone();
+        updated.slo_target = Some(99.5);
+        app.update_target_config(updated);
+
+        let target = &app.targets[1];
+        assert_eq!(target.config.id, id);
+        assert!(target.paused);
+        assert_eq!(target.pane_mode, TargetPaneMode::Split.cycle());
+        assert_eq!(target.config.slo_target, Some(99.5));
+        assert!(!app.targets[0].paused);
+    }
+
+    #[test]
+    fn remove_target_does_not_wait_for_a_probe_in_flight() {
+        // Accepts the connection but never answers, so the probe would run
+        // until its timeout
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut app = app_with_hosts(&["a.com"]);
+        let target = &mut app.targets[0];
+        target.config.url = Url::parse(&format!("http://{addr}/")).unwrap();
+        target.config.timeout_total = std::time::Duration::from_secs(30);
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        target.profiles[0].worker = spawn_profile_worker(
+            target.config.clone(),
+            target.profiles[0].config.clone(),
+            std::time::Duration::ZERO,
+            ProbeSource::default(),
+            DnsCache::default(),
+            sample_tx,
+        );
+        let key = ProfileKey {
+            target_id: target.config.id,
+            profile_id: target.profiles[0].config.id,
+        };
+        let _connection = listener.accept().unwrap();
+
+        let started = Instant::now();
+        app.remove_target(0);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(app.targets.is_empty());
+        assert_eq!(app.pending_shutdown(), 1);
+        let aggregate = app.metrics.windowed_aggregate(
+            key,
+            WindowSpec::M1,
+            &SamplingConfig::default(),
+            &AggregateView::default(),
+            None,
+        );
+        assert_eq!(aggregate.by_metric[&MetricKind::ProbeLossRate].n, 0);
+
+        // The abort flag cuts the transfer short well before the timeout
+        while app.pending_shutdown() > 0 {
+            assert!(started.elapsed() < std::time::Duration::from_secs(10));
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            app.reap_workers();
+        }
+    }
+
+    #[test]
+    fn filter_narrows_navigation_and_snaps_selection() {
+        let mut app = app_with_hosts(&["api.one.com", "web.two.com", "api.three.com", "db.com"]);
+        app.selected_target = 1;
+        app.set_target_filter("API");
+        assert_eq!(app.visible_targets(), vec![0, 2]);
+        assert_eq!(app.selected_target, 2);
+
+        app.select_next_target();
+        assert_eq!(app.selected_target, 2);
+        app.select_prev_target();
+        assert_eq!(app.selected_target, 0);
+
+        app.set_target_filter("nothing");
+        assert!(app.visible_targets().is_empty());
+        assert!(app.selected_target().is_none());
+
+        app.set_target_filter("");
+        assert!(app.selected_target().is_some());
+    }
+
+    #[test]
+    fn move_selected_target_swaps_with_visible_neighbour() {
+        let mut app = app_with_hosts(&["api.a.com", "web.b.com", "api.c.com"]);
+        app.selected_target = 0;
+        assert!(app.move_selected_target(false));
+        assert_eq!(hosts(&app), vec!["web.b.com", "api.a.com", "api.c.com"]);
+        assert_eq!(app.selected_target, 1);
+        assert!(app.move_selected_target(false));
+        assert_eq!(hosts(&app), vec!["web.b.com", "api.c.com", "api.a.com"]);
+        assert!(!app.move_selected_target(false));
+        assert_eq!(app.selected_target, 2);
+
+        let mut app = app_with_hosts(&["api.a.com", "web.b.com", "api.c.com"]);
+        app.set_target_filter("api");
+        app.selected_target = 2;
+        assert!(app.move_selected_target(true));
+        assert_eq!(hosts(&app), vec!["api.c.com", "web.b.com", "api.a.com"]);
+        assert_eq!(app.selected_target, 0);
+        assert!(!app.move_selected_target(true));
+    }
+
+    #[test]
+    fn tagged_targets_group_collapse_and_summarise_their_worst_member() {
+        let mut app = app_with_hosts(&["a.com", "b.com", "c.com", "d.com"]);
+        app.targets[0].config.tags = vec!["prod".to_string()];
+        app.targets[2].config.tags = vec!["prod".to_string(), "eu".to_string()];
+        app.set_target_tags(app.targets[3].config.id, vec!["staging".to_string()]);
+        assert_eq!(app.visible_targets(), vec![1, 0, 2, 3]);
+        assert_eq!(
+            app.target_list_rows(),
+            vec![
+                TargetListRow::Target(1),
+                TargetListRow::Group("prod".to_string()),
+                TargetListRow::Target(0),
+                TargetListRow::Target(2),
+                TargetListRow::Group("staging".to_string()),
+                TargetListRow::Target(3),
+            ]
+        );
+
+        app.selected_target = 1;
+        app.select_next_target();
+        assert_eq!(app.selected_group.as_deref(), Some("prod"));
+        assert!(app.selected_target().is_none());
+        assert!(app.toggle_selected_group());
+        app.select_next_target();
+        assert_eq!(app.selected_group.as_deref(), Some("staging"));
+        app.select_next_target();
+        assert_eq!(app.selected_target, 3);
+        assert!(app.selected_group.is_none());
+
+        // The filter matches tags; a match inside a collapsed group leaves the
+        // cursor on its header
+        app.set_target_filter("#eu");
+        assert_eq!(app.visible_targets(), vec![2]);
+        assert_eq!(app.selected_group.as_deref(), Some("prod"));
+        assert!(app.toggle_selected_group());
+        app.select_next_target();
+        assert_eq!(app.selected_target, 2);
+        app.set_target_filter("");
+
+        app.targets[2].paused = true;
+        let summary = app.group_summary("prod", SystemTime::now()).unwrap();
+        assert_eq!(summary.targets, 2);
+        assert_eq!(summary.status, TargetStatus::Warming);
+        for profile in &mut app.targets[0].profiles {
+            profile.samples_seen = 5;
+        }
+        let summary = app.group_summary("prod", SystemTime::now()).unwrap();
+        assert_eq!(summary.status, TargetStatus::Running);
+        assert_eq!(summary.loss_rate, None);
+        app.targets[0].profiles[0].last_error = Some(ProbeErrorKind::ConnectRefused);
+        let summary = app.group_summary("prod", SystemTime::now()).unwrap();
+        assert_eq!(summary.status, TargetStatus::Failing);
+        assert!(app.group_summary("none", SystemTime::now()).is_none());
+    }
+
+    #[test]
+    fn duplicate_target_gets_fresh_ids_and_independent_metrics() {
+        let mut app = AppState::new(GlobalConfig::default());
+        // Demo workers, so the copy touches no network
+        app.probe_source = ProbeSource::Demo(1);
+        let mut original = runtime_with_profiles("h2+tls12+warm, h1+tls12+cold");
+        original.config.profiles = original.profiles.iter().map(|p| p.config.clone()).collect();
+        app.targets.push(original);
+        app.targets.push(runtime_with_profiles("h2"));
+
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        assert_eq!(app.duplicate_target(0, sample_tx), Some(1));
+        assert_eq!(app.targets.len(), 3);
+        assert_eq!(app.selected_target, 1);
+
+        let (original, copy) = (&app.targets[0], &app.targets[1]);
+        assert_ne!(original.config.id, copy.config.id);
+        assert_eq!(copy.config.url, original.config.url);
+        assert_eq!(copy.config.display_name(), "example.com (copy)");
+        assert_eq!(copy.profiles.len(), original.profiles.len());
+        for (source, cloned) in original.profiles.iter().zip(&copy.profiles) {
+            assert_eq!(source.config.name, cloned.config.name);
+            assert_ne!(source.config.id, cloned.config.id);
+        }
+
+        let sample = SampleBuilder::ok(original.config.id, original.profiles[0].config.id).build();
+        app.apply_sample(sample);
+        let total_count = |target: &TargetRuntime| {
+            app.target_aggregate(target, &target.profiles[0])
+                .by_metric
+                .get(&MetricKind::Total)
+                .map_or(0, |stats| stats.n)
+        };
+        assert_eq!(total_count(&app.targets[0]), 1);
+        assert_eq!(total_count(&app.targets[1]), 0);
+
+        app.remove_target(1);
+    }
+
+    #[test]
+    fn defaults_added_before_tls13_detection_are_upgraded_when_it_confirms() {
+        let mut app = AppState::new(GlobalConfig::default());
+        // Demo workers, so neither the adds nor the upgrade touch the network
+        app.probe_source = ProbeSource::Demo(1);
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        let url = Url::parse("https://a.com").unwrap();
+        app.add_target(url.clone(), None, sample_tx.clone());
+        app.add_target(
+            url,
+            Some(crate::app::parse_profile_specs("h1+tls12+cold")),
+            sample_tx.clone(),
+        );
+        let names = |target: &TargetRuntime| -> Vec<String> {
+            target
+                .profiles
+                .iter()
+                .map(|p| p.config.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&app.targets[0]), ["h2+tls12+warm", "h1+tls12+cold"]);
+
+        assert!(app.apply_tls13_support(true, &sample_tx));
+        assert_eq!(
+            names(&app.targets[0]),
+            ["h2+tls13+warm", "h2+tls12+warm", "h1+tls12+cold"]
+        );
+        assert_eq!(app.targets[0].config.profiles.len(), 3);
+        assert_eq!(app.targets[0].selected_profile, 1);
+        assert_eq!(names(&app.targets[1]), ["h1+tls12+cold"]);
+        // Only the first answer upgrades
+        assert!(!app.apply_tls13_support(true, &sample_tx));
+
+        app.remove_target(1);
+        app.remove_target(0);
+    }
+
+    #[test]
+    fn baselines_capture_the_selected_profile_and_track_the_active_one() {
+        let mut app = app_with_hosts(&["a.com"]);
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+
+        assert_eq!(app.capture_baseline(0, "  "), Some(0));
+        assert_eq!(app.capture_baseline(0, "after deploy"), Some(1));
+        let target = &app.targets[0];
+        assert_eq!(target.baselines[0].name, "baseline 1");
+        assert_eq!(target.baselines[0].profile_id, profile_id);
+        assert_eq!(target.baselines[0].by_metric[&MetricKind::Total].n, 1);
+        assert_eq!(target.current_baseline().unwrap().name, "after deploy");
+
+        app.toggle_baseline(0, 0);
+        assert_eq!(app.targets[0].active_baseline, Some(0));
+        app.delete_baseline(0, 1);
+        assert_eq!(app.targets[0].active_baseline, Some(0));
+        app.delete_baseline(0, 0);
+        assert!(app.targets[0].baselines.is_empty());
+        assert_eq!(app.targets[0].active_baseline, None);
+    }
+
+    #[test]
+    fn annotations_are_recorded_deleted_and_persisted() {
+        let mut app = app_with_hosts(&["a.com"]);
+        assert_eq!(app.add_annotation(0, "   "), None);
+        assert_eq!(app.add_annotation(0, " switched to new LB "), Some(0));
+        assert_eq!(app.add_annotation(0, "rolled back"), Some(1));
+        assert_eq!(app.add_annotation(3, "no such target"), None);
+
+        let target_id = app.targets[0].config.id;
+        let recorded = app.take_new_annotations();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].0, target_id);
+        assert_eq!(recorded[0].1.text, "switched to new LB");
+        assert!(app.take_new_annotations().is_empty());
+
+        app.delete_annotation(0, 0);
+        let persisted = app.to_persisted_state();
+        let texts: Vec<&str> = persisted.targets[0]
+            .annotations
+            .iter()
+            .map(|annotation| annotation.text.as_str())
+            .collect();
+        assert_eq!(texts, ["rolled back"]);
+        let numbered: Vec<usize> = app.targets[0]
+            .annotations_since(SystemTime::UNIX_EPOCH)
+            .map(|(number, _)| number)
+            .collect();
+        assert_eq!(numbered, [1]);
+    }
+
+    #[test]
+    fn profiles_are_renamed_unless_the_name_is_blank_or_taken() {
+        let mut app = AppState::new(GlobalConfig::default());
+        app.targets
+            .push(runtime_with_profiles("h2+tls12+warm, h1+tls12+cold"));
+        let target_id = app.targets[0].config.id;
+        let (warm, cold) = (
+            app.targets[0].profiles[0].config.id,
+            app.targets[0].profiles[1].config.id,
+        );
+
+        app.rename_profile(target_id, warm, "  edge  ").unwrap();
+        assert_eq!(app.targets[0].profiles[0].config.name, "edge");
+        // Renaming to its own name in another case is not a collision
+        app.rename_profile(target_id, warm, "Edge").unwrap();
+        assert_eq!(app.targets[0].profiles[0].config.name, "Edge");
+
+        assert!(app.rename_profile(target_id, cold, "EDGE").is_err());
+        assert!(app.rename_profile(target_id, cold, " ").is_err());
+        assert_eq!(app.targets[0].profiles[1].config.name, "h1+tls12+cold");
+    }
+
+    #[test]
+    fn toggling_metrics_keeps_the_compare_metric_selected() {
+        let mut target = runtime_with_profiles("h2");
+        let selected = |target: &TargetRuntime| {
+            target.selected_metrics.is_empty()
+                || target.selected_metrics.contains(&target.selected_metric)
+        };
+
+        target.toggle_metric(MetricKind::Ttfb);
+        assert_eq!(target.selected_metric, MetricKind::Ttfb);
+        target.toggle_metric(MetricKind::Dns);
+        assert_eq!(target.selected_metric, MetricKind::Dns);
+        // Dropping the compare metric falls back to the first one left, in
+        // the order the metrics are listed
+        target.toggle_metric(MetricKind::Dns);
+        assert_eq!(target.selected_metric, MetricKind::Ttfb);
+        assert!(selected(&target));
+        // Dropping a metric other than the compare one leaves it alone
+        target.toggle_metric(MetricKind::Total);
+        assert_eq!(target.selected_metric, MetricKind::Ttfb);
+
+        target.toggle_metric(MetricKind::Ttfb);
+        assert!(target.selected_metrics.is_empty());
+        target.toggle_metric(MetricKind::Rtt);
+        assert_eq!(target.selected_metric, MetricKind::Rtt);
+        for metric in [
+            MetricKind::Tls,
+            MetricKind::Rtt,
+            MetricKind::Connect,
+            MetricKind::Tls,
+        ] {
+            target.toggle_metric(metric);
+            assert!(selected(&target), "{metric:?}");
+        }
+    }
+
+    #[test]
+    fn metric_selection_is_per_target_and_can_be_copied_to_all() {
+        let mut app = app_with_hosts(&["latency.example.com", "throughput.example.com"]);
+        app.selected_target = 0;
+        app.toggle_metric(MetricKind::Ttfb);
+        assert!(app.targets[0].selected_metrics.contains(&MetricKind::Ttfb));
+        assert!(!app.targets[1].selected_metrics.contains(&MetricKind::Ttfb));
+        assert!(!app.selected_metrics.contains(&MetricKind::Ttfb));
+
+        // Saved per target; older files without it fall back to the defaults
+        let mut persisted = app.to_persisted_state();
+        assert_eq!(persisted.targets[0].selected_metric, Some(MetricKind::Ttfb));
+        persisted.targets[1].selected_metrics = None;
+        let json = serde_json::to_string(&persisted.targets[1]).unwrap();
+        let restored: crate::storage::PersistedTarget = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.selected_metrics, None);
+
+        app.targets[0].metrics_category = MetricsCategory::Tcp;
+        assert_eq!(app.copy_view_to_all_targets(), 1);
+        assert_eq!(app.targets[1].selected_metric, MetricKind::Ttfb);
+        assert_eq!(
+            app.targets[1].selected_metrics,
+            app.targets[0].selected_metrics
+        );
+        assert_eq!(app.targets[1].metrics_category, MetricsCategory::Tcp);
+        assert_eq!(app.copy_view_to_all_targets(), 0);
+
+        // New targets get the copied metrics but the default category
+        app.probe_source = ProbeSource::Demo(1);
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        app.add_target(
+            Url::parse("https://new.example.com").unwrap(),
+            None,
+            sample_tx,
+        );
+        let added = &app.targets[2];
+        assert!(added.selected_metrics.contains(&MetricKind::Ttfb));
+        assert_eq!(added.selected_metric, MetricKind::Ttfb);
+        assert_eq!(added.metrics_category, MetricsCategory::default());
+        app.shutdown();
+    }
+
+    #[test]
+    fn sustained_loss_notifies_once_and_is_silent_by_default() {
+        let mut app = app_with_hosts(&["down.example.com"]);
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        for _ in 0..3 {
+            let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+            sample.result = crate::probe::ProbeResult::Err(crate::probe::ProbeError {
+                kind: ProbeErrorKind::HttpTimeout,
+                message: "timed out".into(),
+            });
+            app.apply_sample(sample);
+        }
+        let start = Instant::now();
+        assert!(app.evaluate_notifications(start).is_empty());
+
+        app.global.notify = Some("50% x2".parse().unwrap());
+        let second = start + NOTIFY_EVAL_INTERVAL;
+        assert!(app.evaluate_notifications(second).is_empty());
+        // Within the evaluation interval: not counted towards the streak
+        assert!(app.evaluate_notifications(second).is_empty());
+        let notifications = app.evaluate_notifications(second + NOTIFY_EVAL_INTERVAL);
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].summary, "down.example.com is failing");
+        assert!(notifications[0].body.starts_with("100% probe loss over 1m"));
+        assert!(app.targets[0].notify_state.down);
+    }
+
+    #[test]
+    fn failures_back_off_until_a_success_and_bursts_need_a_running_target() {
+        let mut app = app_with_hosts(&["flaky.example.com"]);
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        let interval = app.targets[0].config.interval;
+        for _ in 0..3 {
+            let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+            sample.result = crate::probe::ProbeResult::Err(crate::probe::ProbeError {
+                kind: ProbeErrorKind::HttpTimeout,
+                message: "timed out".into(),
+            });
+            app.apply_sample(sample);
+        }
+        assert_eq!(
+            app.targets[0].backoff(),
+            Some(backoff_interval(interval, 3))
+        );
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        assert_eq!(app.targets[0].backoff(), None);
+
+        app.toggle_pause(app.targets[0].config.id);
+        assert!(!app.start_burst(0));
+        app.toggle_pause(app.targets[0].config.id);
+        assert!(app.start_burst(0));
+        let left = app.targets[0]
+            .burst_remaining(Instant::now())
+            .expect("bursting");
+        assert!(left <= BURST_DURATION);
+    }
+
+    #[test]
+    fn custom_windows_cycle_and_flag_retention_that_cannot_cover_them() {
+        let day = WindowSpec::Custom(std::time::Duration::from_secs(24 * 3600));
+        let mut app = app_with_hosts(&["soak.example.com"]);
+        app.global.windows = vec![WindowSpec::M15, day];
+        app.window = WindowSpec::M5;
+        app.cycle_window();
+        assert_eq!(app.window, WindowSpec::M15);
+        app.cycle_window();
+        assert_eq!(app.window, day);
+        app.cycle_window();
+        assert_eq!(app.window, WindowSpec::M15);
+
+        assert_eq!(app.retention_shortfall(), Some(day));
+        app.set_retention_policy(1024, day.duration());
+        assert_eq!(app.retention_shortfall(), None);
+    }
+
+    #[test]
+    fn target_window_override_takes_precedence_until_cycled_past_the_last() {
+        let mut app = app_with_hosts(&["stable.example.com", "debug.example.com"]);
+        app.global.windows = vec![WindowSpec::M1, WindowSpec::M15, WindowSpec::H1];
+        app.window = WindowSpec::H1;
+        app.selected_target = 1;
+        let (target_id, profile_id) = (
+            app.targets[1].config.id,
+            app.targets[1].profiles[0].config.id,
+        );
+        let mut old = SampleBuilder::ok(target_id, profile_id).build();
+        old.ts = SystemTime::now() - std::time::Duration::from_secs(300);
+        app.apply_sample(old);
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        let requests = |app: &AppState| app.target_summary(&app.targets[1]).requests;
+        assert_eq!(requests(&app), 2);
+
+        app.cycle_target_window();
+        assert_eq!(app.targets[1].window_override, Some(WindowSpec::M1));
+        assert_eq!(app.target_window(&app.targets[1]), WindowSpec::M1);
+        assert_eq!(app.target_window(&app.targets[0]), WindowSpec::H1);
+        assert_eq!(app.window_overrides(), 1);
+        assert_eq!(requests(&app), 1);
+
+        // The global window moves on; the override stays put
+        app.cycle_window();
+        assert_eq!(app.target_window(&app.targets[0]), WindowSpec::M1);
+        assert_eq!(app.target_window(&app.targets[1]), WindowSpec::M1);
+        app.cycle_window();
+        assert_eq!(app.target_window(&app.targets[0]), WindowSpec::M15);
+        assert_eq!(app.target_window(&app.targets[1]), WindowSpec::M1);
+
+        app.cycle_target_window();
+        app.cycle_target_window();
+        assert_eq!(app.targets[1].window_override, Some(WindowSpec::H1));
+        app.cycle_target_window();
+        assert_eq!(app.targets[1].window_override, None);
+        assert_eq!(app.target_window(&app.targets[1]), WindowSpec::M15);
+        assert_eq!(app.window_overrides(), 0);
+    }
+
+    #[test]
+    fn h2_profile_answered_over_http1_is_a_fallback() {
+        let mut app = app_with_hosts(&["h2.example.com"]);
+        app.targets[0]
+            .profiles
+            .extend(runtime_with_profiles("h1").profiles);
+        let target_id = app.targets[0].config.id;
+        let h2_id = app.targets[0].profiles[0].config.id;
+        let h1_id = app.targets[0].profiles[1].config.id;
+
+        app.apply_sample(SampleBuilder::ok(target_id, h2_id).build());
+        assert!(!app.targets[0].profiles[0].http_version_fallback());
+
+        let mut sample = SampleBuilder::ok(target_id, h2_id).build();
+        sample.http_version_fallback = true;
+        app.apply_sample(sample);
+        app.apply_sample(SampleBuilder::ok(target_id, h1_id).build());
+        assert!(app.targets[0].profiles[0].http_version_fallback());
+        assert!(!app.targets[0].profiles[1].http_version_fallback());
+        // A failed probe says nothing about the version; the flag follows
+        // the last sample
+        app.apply_sample(
+            SampleBuilder::ok(target_id, h2_id)
+                .failed(ProbeErrorKind::ConnectRefused)
+                .build(),
+        );
+        assert!(!app.targets[0].profiles[0].http_version_fallback());
+    }
+
+    #[test]
+    fn certificate_moves_from_sample_to_target() {
+        let mut app = app_with_hosts(&["cert.example.com"]);
+        let target_id = app.targets[0].config.id;
+        let profile_id = app.targets[0].profiles[0].config.id;
+        let now = SystemTime::now();
+        let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+        sample.cert = Some(CertInfo {
+            not_after: Some(now + std::time::Duration::from_secs(5 * 86_400 + 60)),
+            issuer: Some("CN = Test CA".to_string()),
+            subject_alt_names: vec!["cert.example.com".to_string()],
+        });
+        app.apply_sample(sample);
+
+        let target = &app.targets[0];
+        assert!(
+            target.profiles[0]
+                .last_sample
+                .as_ref()
+                .unwrap()
+                .cert
+                .is_none()
+        );
+        assert_eq!(target.cert_days_left(now), Some(5));
+        assert!(target.cert_expiring_soon(now));
+        assert!(!target.cert_expiring_soon(now - std::time::Duration::from_secs(2 * 86_400)));
+
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        assert!(app.targets[0].cert.is_some(), "kept until the next fetch");
+        app.update_target_url(target_id, Url::parse("https://other.example.com").unwrap());
+        assert!(app.targets[0].cert.is_none());
+    }
+
+    #[test]
+    fn update_target_url_keeps_ids_and_marks_change() {
+        let mut app = app_with_hosts(&["old.example.com"]);
+        let target_id = app.targets[0].config.id;
+        let profile_id = app.targets[0].profiles[0].config.id;
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+
+        let url = Url::parse("https://new.example.com/health").unwrap();
+        app.update_target_url(target_id, url.clone());
+        let target = &app.targets[0];
+        assert_eq!(target.config.url, url);
+        assert_eq!(target.config.id, target_id);
+        assert_eq!(target.config_changes.len(), 1);
+        let total = app
+            .target_aggregate(target, &target.profiles[0])
+            .by_metric
+            .get(&MetricKind::Total)
+            .map_or(0, |stats| stats.n);
+        assert_eq!(total, 1);
+
+        app.update_target_url(target_id, url);
+        assert_eq!(app.targets[0].config_changes.len(), 1);
+    }
+
+    #[test]
+    fn every_user_mutation_is_logged_with_its_target() {
+        let mut app = app_with_hosts(&["a.example.com", "b.example.com"]);
+        app.global.windows = vec![WindowSpec::M5];
+        let target_id = app.targets[0].config.id;
+        let recent = |app: &AppState| {
+            let entry = app.change_log.iter_recent().next().expect("entry");
+            (entry.target.clone(), entry.description.clone())
+        };
+        let target = || "a.example.com".to_string();
+
+        app.toggle_pause(target_id);
+        assert_eq!(recent(&app), (target(), "Paused".to_string()));
+        app.toggle_pause(target_id);
+        assert_eq!(recent(&app), (target(), "Resumed".to_string()));
+        app.toggle_global_pause();
+        assert_eq!(
+            recent(&app),
+            ("All targets".to_string(), "Paused all targets".to_string())
+        );
+        app.toggle_global_pause();
+
+        let mut updated = app.targets[0].config.clone();
+        updated.interval = Duration::from_secs(30);
+        updated.timeout_total = Duration::from_secs(3);
+        updated.dns_enabled = false;
+        app.edit_target_config(updated);
+        let described: Vec<_> = app
+            .change_log
+            .iter_recent()
+            .take(3)
+            .map(|entry| entry.description.as_str())
+            .collect();
+        assert_eq!(
+            described,
+            [
+                "DNS: worker 30s → off",
+                "Timeout: 10s → 3s",
+                "Interval: 5s → 30s"
+            ]
+        );
+        // A single edit marks the chart once however many fields it touched
+        assert_eq!(app.targets[0].config_changes.len(), 1);
+
+        let mut profile = app.targets[0].profiles[0].config.clone();
+        profile.method = ProbeMethod::Head;
+        app.edit_profile_config(target_id, profile);
+        assert_eq!(
+            recent(&app),
+            (target(), "h2 method: get → head".to_string())
+        );
+        assert_eq!(app.targets[0].config_changes.len(), 2);
+
+        let logged = app.change_log.len();
+        app.edit_profile_config(target_id, app.targets[0].profiles[0].config.clone());
+        assert_eq!(app.change_log.len(), logged, "no-op edits are not logged");
+
+        app.selected_target = 0;
+        app.cycle_target_window();
+        assert_eq!(
+            recent(&app),
+            (target(), format!("Window: global → {}", WindowSpec::M5))
+        );
+        assert_eq!(
+            app.targets[0].config_changes.len(),
+            2,
+            "not a config change"
+        );
+
+        // Updates the app makes on its own are not user changes
+        let logged = app.change_log.len();
+        let mut updated = app.targets[0].config.clone();
+        updated.interval = Duration::from_secs(60);
+        app.update_target_config(updated);
+        assert_eq!(app.change_log.len(), logged);
+        assert_eq!(app.targets[0].config_changes.len(), 2);
+
+        // A global toggle is one entry for all targets, with no chart marks
+        app.set_exclude_burst(true);
+        assert_eq!(app.change_log.len(), logged + 1);
+        assert_eq!(
+            recent(&app),
+            (
+                "All targets".to_string(),
+                "Exclude burst samples: on".to_string()
+            )
+        );
+        assert_eq!(app.targets[0].config_changes.len(), 2);
+        assert!(app.targets[1].config_changes.is_empty());
+
+        // Demo workers, so adding a target touches no network
+        app.probe_source = ProbeSource::Demo(1);
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        app.add_target(
+            Url::parse("https://c.example.com").unwrap(),
+            None,
+            sample_tx,
+        );
+        assert_eq!(
+            recent(&app),
+            ("c.example.com".to_string(), "Added".to_string())
+        );
+        app.remove_target(0);
+        assert_eq!(recent(&app), (target(), "Deleted".to_string()));
+        app.shutdown();
+    }
+
+    #[test]
+    fn watchdog_flags_stall_and_respawns_silent_worker() {
+        let mut app = app_with_hosts(&["127.0.0.1:9"]);
+        app.targets[0].config.url = Url::parse("http://127.0.0.1:9").unwrap();
+        app.targets[0].config.interval = std::time::Duration::from_secs(1);
+        app.targets[0].config.timeout_total = std::time::Duration::from_secs(1);
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        let start = SystemTime::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+
+        app.check_worker_health_at(at(0), &sample_tx);
+        app.check_worker_health_at(at(5), &sample_tx);
+        assert_eq!(app.targets[0].stalled_profiles(), 1);
+        assert!(app.targets[0].profiles[0].worker.join.is_none());
+
+        app.targets[0].paused = true;
+        app.check_worker_health_at(at(60), &sample_tx);
+        assert_eq!(app.targets[0].stalled_profiles(), 0);
+        app.targets[0].paused = false;
+
+        app.check_worker_health_at(at(68), &sample_tx);
+        assert_eq!(app.targets[0].worker_restarts(), 1);
+        assert!(app.targets[0].profiles[0].worker.join.is_some());
+        app.check_worker_health_at(at(80), &sample_tx);
+        assert_eq!(app.targets[0].worker_restarts(), 1);
+
+        app.remove_target(0);
+    }
+
+    #[test]
+    fn replayed_targets_have_no_workers_and_are_never_respawned() {
+        let mut app = AppState::new(GlobalConfig::default());
+        let target = TargetConfig::new(
+            Url::parse("http://127.0.0.1:9").unwrap(),
+            crate::config::default_profiles(),
+        );
+        app.load_replay(vec![target]);
+        assert!(app.replaying);
+        assert_eq!(app.selected_target, 0);
+        assert!(
+            app.targets[0]
+                .profiles
+                .iter()
+                .all(|profile| profile.worker.join.is_none())
+        );
+
+        let (sample_tx, _sample_rx) = crossbeam_channel::unbounded();
+        let start = SystemTime::now();
+        app.check_worker_health_at(start, &sample_tx);
+        app.check_worker_health_at(start + std::time::Duration::from_secs(3600), &sample_tx);
+        assert_eq!(app.targets[0].worker_restarts(), 0);
+        assert!(app.targets[0].profiles[0].worker.join.is_none());
+    }
+
+    #[test]
+    fn conn_reuse_pairs_match_profiles_differing_only_in_reuse() {
+        let app = AppState::new(GlobalConfig::default());
+        let target =
+            runtime_with_profiles("h2+tls13+warm, h1+tls12+cold, h2+tls13+cold, h1+tls12+warm");
+        assert_eq!(
+            app.conn_reuse_pairs(&target),
+            vec![
+                ConnReusePair { warm: 0, cold: 2 },
+                ConnReusePair { warm: 3, cold: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn conn_reuse_pairs_skip_mismatched_request_shape() {
+        let app = AppState::new(GlobalConfig::default());
+        let target = runtime_with_profiles("h2+tls13+warm, h2+tls13+cold+head, h2+tls12+cold");
+        assert!(app.conn_reuse_pairs(&target).is_empty());
+
+        let target = runtime_with_profiles("h2+warm, h2+cold, h2+cold");
+        assert_eq!(
+            app.conn_reuse_pairs(&target),
+            vec![ConnReusePair { warm: 0, cold: 1 }]
+        );
+    }
+
+    #[test]
+    fn resolver_samples_feed_dns_and_the_answer_set() {
+        use crate::probe::ProbeErrorKind;
+
+        let mut app = app_with_hosts(&["api.example.com"]);
+        let target_id = app.targets[0].config.id;
+        let profile_id = app.targets[0].profiles[0].config.id;
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        let lookup = |ms: u64, ips: &[&str]| {
+            let mut sample = SampleBuilder::ok(target_id, RESOLVER_PROFILE_ID).build();
+            sample.t_dns = Some(std::time::Duration::from_millis(ms));
+            sample.resolved_ips = ips.iter().map(|ip| ip.parse().unwrap()).collect();
+            sample
+        };
+        let dns_mean = |app: &AppState| {
+            let target = &app.targets[0];
+            app.target_aggregate(target, &target.profiles[0]).by_metric[&MetricKind::Dns].mean
+        };
+
+        app.apply_sample(lookup(40, &["192.0.2.1", "192.0.2.2"]));
+        assert_eq!(dns_mean(&app), Some(40.0));
+        assert_eq!(app.targets[0].ip_history.resolved().len(), 2);
+        assert!(app.targets[0].ip_history.answer_changed().is_none());
+
+        app.apply_sample(lookup(40, &["192.0.2.2", "192.0.2.1"]));
+        assert!(app.targets[0].ip_history.answer_changed().is_none());
+        app.apply_sample(lookup(40, &["192.0.2.3"]));
+        assert!(app.targets[0].ip_history.answer_changed().is_some());
+
+        app.apply_sample(
+            SampleBuilder::ok(target_id, RESOLVER_PROFILE_ID)
+                .failed(ProbeErrorKind::DnsNxDomain)
+                .build(),
+        );
+        let entry = app.targets[0]
+            .error_log
+            .iter_recent()
+            .next()
+            .expect("entry");
+        assert_eq!(entry.profile, "dns");
+        assert_eq!(entry.kind, ProbeErrorKind::DnsNxDomain);
+
+        // Resolving inside every probe reads Dns from the probes again
+        app.targets[0].config.dns_combined = true;
+        assert_eq!(dns_mean(&app), Some(2.0));
+    }
+
+    #[test]
+    fn session_totals_count_probe_outcomes_but_not_lookups() {
+        let mut app = app_with_hosts(&["api.example.com", "web.example.com"]);
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        app.apply_sample(SampleBuilder::ok(target_id, profile_id).build());
+        app.apply_sample(
+            SampleBuilder::ok(target_id, profile_id)
+                .failed(ProbeErrorKind::HttpTimeout)
+                .build(),
+        );
+        app.apply_sample(SampleBuilder::ok(target_id, RESOLVER_PROFILE_ID).build());
+        assert_eq!(app.session.probes, 3);
+        assert_eq!(app.session.successes, 2);
+
+        // Totals outlive the target and its stored samples
+        app.remove_target(0);
+        assert_eq!(app.session.probes, 3);
+        let pct = app.session.success_pct().expect("pct");
+        assert!((pct - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn per_ip_streams_follow_the_resolved_set_and_keep_their_own_metrics() {
+        let mut app = app_with_hosts(&["127.0.0.1:9"]);
+        app.targets[0].config.url = Url::parse("http://127.0.0.1:9").unwrap();
+        app.targets[0].config.per_ip = true;
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        let a: std::net::IpAddr = "192.0.2.1".parse().unwrap();
+        let b: std::net::IpAddr = "192.0.2.2".parse().unwrap();
+        // Stand-in workers: each spawn is recorded instead of probing
+        let spawned = std::cell::RefCell::new(Vec::new());
+        let sync = |app: &mut AppState| {
+            app.sync_endpoints_with(&mut |_, _, ip, _| {
+                spawned.borrow_mut().push(ip);
+                WorkerHandle::idle()
+            });
+        };
+
+        app.targets[0]
+            .ip_history
+            .set_resolved(SystemTime::now(), &[a, b]);
+        sync(&mut app);
+        assert_eq!(app.targets[0].endpoint_ips(), vec![a, b]);
+        let notice = app.status_notice.take().expect("notice");
+        assert!(notice.message.ends_with("per-IP +192.0.2.1 +192.0.2.2"));
+
+        let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+        sample.endpoint = Some(b);
+        app.apply_sample(sample);
+        let profile = &app.targets[0].profiles[0];
+        assert!(profile.last_sample.is_none());
+        let endpoint_key = app.targets[0].endpoint_key(profile, b);
+        let stored = |app: &AppState, key| app.metrics.newest_sample_ts(key).is_some();
+        assert!(stored(&app, endpoint_key));
+        assert!(!stored(
+            &app,
+            ProfileKey {
+                target_id,
+                profile_id
+            }
+        ));
+
+        assert!(app.targets[0].cycle_endpoint_view());
+        assert!(app.targets[0].cycle_endpoint_view());
+        assert!(app.targets[0].cycle_endpoint_view());
+        assert_eq!(app.targets[0].endpoint_view, EndpointView::Ip(b));
+
+        // The address drops out of the answer: its stream survives the
+        // grace lookups, then it and its samples go
+        for _ in 1..ENDPOINT_GRACE_LOOKUPS {
+            app.targets[0]
+                .ip_history
+                .set_resolved(SystemTime::now(), &[a]);
+            sync(&mut app);
+            assert_eq!(app.targets[0].endpoint_ips(), vec![a, b]);
+        }
+        app.targets[0]
+            .ip_history
+            .set_resolved(SystemTime::now(), &[a]);
+        sync(&mut app);
+        assert_eq!(app.targets[0].endpoint_ips(), vec![a]);
+        assert_eq!(app.targets[0].endpoint_view, EndpointView::Profile);
+        assert!(!stored(&app, endpoint_key));
+        let notice = app.status_notice.take().expect("notice");
+        assert!(notice.message.ends_with("per-IP -192.0.2.2"));
+        assert_eq!(*spawned.borrow(), [a, b]);
+
+        // A large answer is capped, and the streaming address keeps its slot
+        let many: Vec<std::net::IpAddr> = (10..=40)
+            .map(|last| format!("192.0.2.{last}").parse().unwrap())
+            .chain([a])
+            .collect();
+        app.targets[0]
+            .ip_history
+            .set_resolved(SystemTime::now(), &many);
+        sync(&mut app);
+        assert_eq!(app.targets[0].endpoints.len(), MAX_ENDPOINT_STREAMS);
+        assert_eq!(app.targets[0].endpoint_ips()[0], a);
+
+        app.targets[0].config.per_ip = false;
+        sync(&mut app);
+        assert!(app.targets[0].endpoints.is_empty());
     }
 }
@@ -0,0 +1,202 @@
+use crate::common::fs::write_replacing;
+use crate::data_model::{SCHEMA_VERSION, SchemaVersion};
+use crate::metrics::{ProfileAggregate, TargetViewModel};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::state::AppState;
+
+/// How long the header shows the outcome of a status dump.
+pub const STATUS_NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// Set from the SIGUSR1 handler; the UI loop writes the dump on its next tick.
+static STATUS_DUMP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Asks for a status dump on the next UI tick. Only touches an atomic, so it
+/// is safe to call from a signal handler.
+pub fn request_status_dump() {
+    STATUS_DUMP_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Whether a dump was requested since the last call.
+pub fn take_status_dump_request() -> bool {
+    STATUS_DUMP_REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// Routes SIGUSR1 to `request_status_dump`.
+#[cfg(unix)]
+pub fn install_status_dump_signal() {
+    extern "C" fn on_sigusr1(_signal: libc::c_int) {
+        request_status_dump();
+    }
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        libc::signal(
+            libc::SIGUSR1,
+            on_sigusr1 as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+/// Outcome of the last status dump, shown in the header for a few seconds.
+#[derive(Clone, Debug)]
+pub struct StatusNotice {
+    pub at: Instant,
+    pub message: String,
+    pub failed: bool,
+}
+
+#[derive(Serialize)]
+struct StatusDump<'a> {
+    schema_version: SchemaVersion,
+    generated_at_unix_ms: u128,
+    targets: &'a [TargetViewModel],
+}
+
+impl AppState {
+    /// Every target's profiles aggregated over each configured window.
+    pub fn target_view_models(&self) -> Vec<TargetViewModel> {
+        self.targets
+            .iter()
+            .map(|target| TargetViewModel {
+                target_id: target.config.id,
+                url: target.config.url.to_string(),
+                profiles: target
+                    .profiles
+                    .iter()
+                    .map(|profile| ProfileAggregate {
+                        target_id: target.config.id,
+                        profile_id: profile.config.id,
+                        name: profile.config.name.clone(),
+                        windows: self
+                            .global
+                            .windows
+                            .iter()
+                            .map(|window| self.target_aggregate_in(target, profile, *window))
+                            .collect(),
+                    })
+                    .collect(),
+                candidates_hint: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Writes `target_view_models` as JSON to the configured status file.
+    /// The file is replaced by a rename, so readers never see half a dump.
+    /// The partial file gets a random name and is created exclusively, so a
+    /// planted file or symlink in a shared directory is never written through.
+    pub fn write_status_dump(&self) -> io::Result<PathBuf> {
+        let path = self.global.status_file.clone();
+        let dump = StatusDump {
+            schema_version: SCHEMA_VERSION,
+            generated_at_unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis()),
+            targets: &self.target_view_models(),
+        };
+        let content = serde_json::to_string_pretty(&dump)?;
+        write_replacing(&path, |file| file.write_all(content.as_bytes()))?;
+        Ok(path)
+    }
+
+    /// Writes the status dump and records the outcome for the header.
+    pub fn dump_status(&mut self) {
+        let (message, failed) = match self.write_status_dump() {
+            Ok(path) => (format!("status written to {}", path.display()), false),
+            Err(err) => {
+                tracing::warn!(error = %err, "status dump failed");
+                (format!("status dump failed: {err}"), true)
+            }
+        };
+        self.status_notice = Some(StatusNotice {
+            at: Instant::now(),
+            message,
+            failed,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GlobalConfig, TargetConfig, WindowSpec, default_profiles};
+    use crate::features::probe::testing::SampleBuilder;
+    use std::fs;
+    use url::Url;
+
+    #[test]
+    fn status_dump_is_written_as_json_per_target_and_window() {
+        let path =
+            std::env::temp_dir().join(format!("httpulse-status-{}.json", std::process::id()));
+        let mut app = AppState::new(GlobalConfig {
+            windows: vec![WindowSpec::M1, WindowSpec::M5],
+            status_file: path.clone(),
+            ..GlobalConfig::default()
+        });
+        app.load_replay(vec![TargetConfig::new(
+            Url::parse("https://api.example.com").unwrap(),
+            default_profiles(),
+        )]);
+        let (target_id, profile_id) = (
+            app.targets[0].config.id,
+            app.targets[0].profiles[0].config.id,
+        );
+        for total_ms in [40, 60] {
+            app.apply_sample(
+                SampleBuilder::ok(target_id, profile_id)
+                    .total_ms(total_ms)
+                    .build(),
+            );
+        }
+
+        request_status_dump();
+        assert!(take_status_dump_request());
+        assert!(!take_status_dump_request());
+        app.dump_status();
+        let notice = app.status_notice.as_ref().expect("notice");
+        assert!(!notice.failed, "{}", notice.message);
+
+        let dump: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(dump["schema_version"], SCHEMA_VERSION.to_string());
+        assert!(dump["generated_at_unix_ms"].as_u64().unwrap() > 0);
+        let target = &dump["targets"][0];
+        assert_eq!(target["url"], "https://api.example.com/");
+        let profile = &target["profiles"][0];
+        assert_eq!(profile["name"], app.targets[0].profiles[0].config.name);
+        let windows = profile["windows"].as_array().unwrap();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0]["window"], "m1");
+        assert_eq!(windows[1]["by_metric"]["total"]["n"], 2);
+        assert_eq!(windows[1]["by_metric"]["total"]["max"], 60.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn status_dump_replaces_a_symlink_instead_of_writing_through_it() {
+        let dir = std::env::temp_dir().join(format!("httpulse-status-link-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (victim, path) = (dir.join("victim"), dir.join("status.json"));
+        fs::write(&victim, "keep").unwrap();
+        std::os::unix::fs::symlink(&victim, &path).unwrap();
+
+        let app = AppState::new(GlobalConfig {
+            status_file: path.clone(),
+            ..GlobalConfig::default()
+        });
+        app.write_status_dump().unwrap();
+
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+        assert!(!fs::symlink_metadata(&path).unwrap().is_symlink());
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            2,
+            "partial file left behind"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,100 @@
+use std::time::{Duration, SystemTime};
+
+/// Probe intervals without a sample before a profile is flagged as stalled.
+pub const STALL_INTERVALS: u32 = 3;
+/// Probe intervals without a sample before its worker is respawned.
+pub const RESTART_INTERVALS: u32 = 6;
+/// Minimum time between two restarts of the same profile's worker.
+pub const RESTART_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Liveness bookkeeping for one profile worker.
+#[derive(Clone, Debug, Default)]
+pub struct WorkerHealth {
+    /// Start of the current watch period; silence before it is ignored
+    watch_since: Option<SystemTime>,
+    last_restart: Option<SystemTime>,
+    pub stalled: bool,
+    pub restarts: u32,
+}
+
+impl WorkerHealth {
+    /// Starts a fresh watch period, e.g. while the target is paused.
+    pub fn rearm(&mut self, now: SystemTime) {
+        self.watch_since = Some(now);
+        self.stalled = false;
+    }
+
+    /// Updates the stalled flag from the silence since the last sample and
+    /// returns whether the worker is due for a restart. A probe may take up
+    /// to `timeout` on top of its interval, so that is always allowed for.
+    pub fn check(
+        &mut self,
+        now: SystemTime,
+        last_sample: Option<SystemTime>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> bool {
+        let since = *self.watch_since.get_or_insert(now);
+        let last_activity = last_sample.map_or(since, |ts| ts.max(since));
+        let silence = now.duration_since(last_activity).unwrap_or_default();
+        self.stalled = silence > interval * STALL_INTERVALS + timeout;
+
+        let cooled_down = self
+            .last_restart
+            .is_none_or(|at| now.duration_since(at).unwrap_or_default() >= RESTART_COOLDOWN);
+        silence > interval * RESTART_INTERVALS + timeout && cooled_down
+    }
+
+    pub fn record_restart(&mut self, now: SystemTime) {
+        self.restarts += 1;
+        self.last_restart = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn silence_flags_stall_then_rate_limited_restart() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let interval = Duration::from_secs(5);
+        let timeout = Duration::from_secs(10);
+        let mut health = WorkerHealth::default();
+
+        assert!(!health.check(at(0), None, interval, timeout));
+        assert!(!health.check(at(20), Some(at(18)), interval, timeout));
+        assert!(!health.stalled);
+
+        // 3 * 5s + 10s = 25s of silence marks the stall
+        assert!(!health.check(at(44), Some(at(18)), interval, timeout));
+        assert!(health.stalled);
+
+        // 6 * 5s + 10s = 40s of silence triggers a restart
+        assert!(health.check(at(59), Some(at(18)), interval, timeout));
+        health.record_restart(at(59));
+        assert!(!health.check(at(70), Some(at(18)), interval, timeout));
+        assert!(health.check(at(119), Some(at(18)), interval, timeout));
+        assert_eq!(health.restarts, 1);
+
+        // A fresh sample clears the stall
+        assert!(!health.check(at(120), Some(at(119)), interval, timeout));
+        assert!(!health.stalled);
+    }
+
+    #[test]
+    fn rearm_ignores_silence_while_paused() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let interval = Duration::from_secs(1);
+        let mut health = WorkerHealth::default();
+        health.rearm(start + Duration::from_secs(600));
+        assert!(!health.check(
+            start + Duration::from_secs(601),
+            Some(start),
+            interval,
+            Duration::ZERO
+        ));
+        assert!(!health.stalled);
+    }
+}
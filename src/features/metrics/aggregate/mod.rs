@@ -1,4 +1,4 @@
 mod stats;
 mod store;
 
-pub use store::{MetricsStore, ProfileKey};
+pub use store::{AggregateSource, AggregateView, MetricsStore, ProfileKey};
@@ -33,19 +33,25 @@ pub(super) fn compute_stats(
     }
     let stddev = (variance_sum / values.len() as f64).sqrt();
 
-    let (p50, p90, p99) = if use_histogram {
+    let mut clamped = 0;
+    let (p50, p90, p95, p99, p999) = if use_histogram {
         let low = sampling.histogram.latency_low_ms.max(1) * 1_000;
         let high = sampling.histogram.latency_high_ms.max(1) * 1_000;
         let mut histogram = Histogram::<u64>::new_with_bounds(low, high, sampling.histogram.sigfig)
             .unwrap_or_else(|_| Histogram::<u64>::new(3).unwrap());
         for value in values {
             let micros = (*value * 1000.0).max(0.0) as u64;
-            let _ = histogram.record(micros);
+            if micros < low || micros > high {
+                clamped += 1;
+            }
+            histogram.saturating_record(micros.clamp(low, high));
         }
         (
             histogram.value_at_quantile(0.50) as f64 / 1000.0,
             histogram.value_at_quantile(0.90) as f64 / 1000.0,
+            histogram.value_at_quantile(0.95) as f64 / 1000.0,
             histogram.value_at_quantile(0.99) as f64 / 1000.0,
+            histogram.value_at_quantile(0.999) as f64 / 1000.0,
         )
     } else {
         let mut sorted = values.to_vec();
@@ -53,7 +59,9 @@ pub(super) fn compute_stats(
         (
             quantile(&sorted, 0.50),
             quantile(&sorted, 0.90),
+            quantile(&sorted, 0.95),
             quantile(&sorted, 0.99),
+            quantile(&sorted, 0.999),
         )
     };
 
@@ -66,7 +74,10 @@ pub(super) fn compute_stats(
         stddev: Some(stddev),
         p50: Some(p50),
         p90: Some(p90),
+        p95: Some(p95),
         p99: Some(p99),
+        p999: Some(p999),
+        clamped,
     }
 }
 
@@ -83,6 +94,14 @@ pub(super) fn sample_metric(
     metric: MetricKind,
     link_capacity_mbps: Option<f64>,
 ) -> Option<f64> {
+    // A ping measures one round trip (and its change) and nothing else
+    if sample.ping.is_some() {
+        return match metric {
+            MetricKind::Rtt | MetricKind::Total => Some(sample.t_total.as_secs_f64() * 1000.0),
+            MetricKind::Jitter => sample.jitter_ms,
+            _ => None,
+        };
+    }
     match metric {
         MetricKind::Dns => sample.t_dns.map(|t| t.as_secs_f64() * 1000.0),
         MetricKind::Connect => Some(sample.t_connect.as_secs_f64() * 1000.0),
@@ -100,7 +119,7 @@ pub(super) fn sample_metric(
             .as_ref()
             .and_then(|info| info.rttvar_us)
             .map(|v| v as f64 / 1000.0),
-        MetricKind::Jitter => None,
+        MetricKind::Jitter => sample.jitter_ms,
         MetricKind::Retrans => sample.ebpf.as_ref().map(|v| v.retrans as f64).or_else(|| {
             sample
                 .tcp_info
@@ -119,6 +138,8 @@ pub(super) fn sample_metric(
             .as_ref()
             .and_then(|info| info.lost)
             .map(|v| v as f64),
+        // A truncated transfer's bytes are capped and its time includes the abort
+        MetricKind::GoodputBps if sample.truncated => None,
         MetricKind::GoodputBps => {
             let seconds = sample.t_download.as_secs_f64();
             if seconds > 0.0 {
@@ -127,6 +148,8 @@ pub(super) fn sample_metric(
                 None
             }
         }
+        MetricKind::ResponseBytes => Some(sample.downloaded_bytes as f64),
+        MetricKind::ContentLength => sample.content_length.map(|length| length as f64),
         MetricKind::BandwidthUtilization => {
             let capacity = link_capacity_mbps?;
             let goodput = sample_metric(sample, MetricKind::GoodputBps, link_capacity_mbps)?;
@@ -143,6 +166,11 @@ pub(super) fn sample_metric(
             .as_ref()
             .and_then(|info| info.snd_ssthresh)
             .map(|v| v as f64),
-        MetricKind::ProbeLossRate => None,
+        MetricKind::ProbeLossRate
+        | MetricKind::MissedProbes
+        | MetricKind::Http4xxRate
+        | MetricKind::Http5xxRate
+        | MetricKind::CacheHitRate
+        | MetricKind::ConnReuseRate => None,
     }
 }
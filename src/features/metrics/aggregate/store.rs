@@ -1,10 +1,25 @@
-use super::super::{MetricKind, MetricStats, WindowedAggregate};
+use super::super::{
+    HistogramMatrix, MetricKind, MetricStats, OutageEvent, PHASE_METRICS, PhaseBreakdown,
+    SloCounts, SpikeEvent, StatColumn, WindowedAggregate,
+};
 use super::stats::{compute_stats, is_timeout_error, sample_metric};
 use crate::common::time::{Clock, SystemClock};
 use crate::config::{ProfileId, SamplingConfig, TargetId, WindowSpec};
-use crate::probe::{ProbeResult, ProbeSample};
-use std::collections::{HashMap, VecDeque};
-use std::time::SystemTime;
+use crate::probe::{ProbeErrorKind, ProbeResult, ProbeSample};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::mem::size_of;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Number of time buckets a window is split into for per-bucket stat series.
+pub const STAT_BUCKETS: usize = 30;
+/// Shortest window that leaves burst samples out when
+/// [`AggregateView::exclude_burst_from_long_windows`] is set.
+pub const LONG_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Outages kept per profile; the oldest are dropped past this.
+pub const MAX_OUTAGES: usize = 256;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct ProfileKey {
@@ -12,34 +27,325 @@ pub struct ProfileKey {
     pub profile_id: ProfileId,
 }
 
+/// How the app reads a window, set once for every target: none of it
+/// changes what the probes collect, so it stays out of the target configs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AggregateView {
+    /// Link capacity bandwidth utilization is measured against
+    pub link_capacity_mbps: Option<f64>,
+    /// Leave burst samples out of windows of 15m and longer, so a burst's
+    /// dense samples don't dominate their percentiles
+    pub exclude_burst_from_long_windows: bool,
+    /// Count scheduled slots that passed without a probe as lost probes
+    pub count_missed_as_loss: bool,
+}
+
+/// One profile's part of a [`MetricsStore::merged_aggregate`], read over
+/// its own window with its own sampling settings.
+#[derive(Clone, Copy, Debug)]
+pub struct AggregateSource<'a> {
+    pub key: ProfileKey,
+    pub window: WindowSpec,
+    pub sampling: &'a SamplingConfig,
+    /// Latency timed-out probes count toward Total with, if any
+    pub failure_latency: Option<Duration>,
+}
+
+/// Profile, window and failure latency of a cached aggregate, so views that
+/// count failures differently don't evict each other.
+type AggregateCacheKey = (ProfileKey, WindowSpec, Option<Duration>);
+
+/// A computed aggregate plus the conditions under which it is still exact.
+struct CachedAggregate {
+    generation: u64,
+    computed_at: SystemTime,
+    /// When the oldest sample in the window ages out; `None` if the window was empty.
+    expires_at: Option<SystemTime>,
+    sampling: SamplingConfig,
+    view: AggregateView,
+    aggregate: WindowedAggregate,
+}
+
+impl CachedAggregate {
+    fn is_valid(
+        &self,
+        generation: u64,
+        now: SystemTime,
+        sampling: &SamplingConfig,
+        view: &AggregateView,
+    ) -> bool {
+        self.generation == generation
+            && now >= self.computed_at
+            && self.expires_at.is_none_or(|expires_at| now < expires_at)
+            && self.sampling == *sampling
+            && self.view == *view
+    }
+}
+
+/// Per-profile sample ring buffers with memoised windowed aggregates.
+///
+/// Aggregates are recomputed only when a new sample arrives for the profile
+/// or the oldest sample inside the window rolls out, so repeated UI-tick
+/// queries between probes are a map lookup and a clone.
 #[derive(Default)]
 pub struct MetricsStore {
     samples: HashMap<ProfileKey, VecDeque<ProbeSample>>,
+    generations: HashMap<ProfileKey, u64>,
+    /// Timestamp of the newest sample evicted per profile
+    evicted_until: HashMap<ProfileKey, SystemTime>,
+    aggregate_cache: RefCell<HashMap<AggregateCacheKey, CachedAggregate>>,
+    recomputations: Cell<u64>,
+    /// One copy of every error message held by a stored sample, so a profile
+    /// failing the same way for hours keeps a single string
+    messages: HashSet<Arc<str>>,
+    /// Outages per profile, oldest first, recorded as samples arrive; they
+    /// outlive the samples they were found in
+    outages: HashMap<ProfileKey, VecDeque<OutageEvent>>,
+    /// Failures per kind of each profile's open outage, in first-seen order
+    open_outage_errors: HashMap<ProfileKey, Vec<(ProbeErrorKind, u32)>>,
+    /// [`sample_bytes`] summed over every stored sample, kept as they come and go
+    sample_bytes: usize,
+    /// Length of every string in `messages`
+    message_bytes: usize,
+}
+
+/// Heap bytes a sample owns besides its error message, which is shared.
+fn sample_heap_bytes(sample: &ProbeSample) -> usize {
+    let strings = [
+        &sample.negotiated.alpn,
+        &sample.negotiated.tls_version,
+        &sample.negotiated.cipher,
+    ]
+    .into_iter()
+    .flatten()
+    .map(String::capacity)
+    .sum::<usize>();
+    let headers = sample.response_headers.capacity() * size_of::<(String, String)>()
+        + sample
+            .response_headers
+            .iter()
+            .map(|(name, value)| name.capacity() + value.capacity())
+            .sum::<usize>();
+    let cert = sample.cert.as_ref().map_or(0, |cert| {
+        cert.issuer.as_ref().map_or(0, String::capacity)
+            + cert.subject_alt_names.capacity() * size_of::<String>()
+            + cert
+                .subject_alt_names
+                .iter()
+                .map(String::capacity)
+                .sum::<usize>()
+    });
+    strings + headers + cert + sample.resolved_ips.capacity() * size_of::<IpAddr>()
+}
+
+/// Bytes a stored sample accounts for: itself plus what it owns on the heap.
+fn sample_bytes(sample: &ProbeSample) -> usize {
+    size_of::<ProbeSample>() + sample_heap_bytes(sample)
+}
+
+/// Bytes of every sample in `queue`.
+fn queue_bytes(queue: &VecDeque<ProbeSample>) -> usize {
+    queue.iter().map(sample_bytes).sum()
 }
 
 impl MetricsStore {
     pub fn new() -> Self {
-        Self {
-            samples: HashMap::new(),
-        }
+        Self::default()
     }
 
-    pub fn push_sample(&mut self, key: ProfileKey, sample: ProbeSample, max_points: usize) {
+    /// Appends `sample`, recording its jitter against the profile's previous
+    /// successful, non-priming sample, then evicts the oldest samples that are both beyond
+    /// `max_points` and older than `retention` (relative to the new sample).
+    /// A zero `retention` evicts by count alone.
+    pub fn push_sample(
+        &mut self,
+        key: ProfileKey,
+        mut sample: ProbeSample,
+        max_points: usize,
+        retention: Duration,
+    ) {
+        let newest = sample.ts;
+        if let ProbeResult::Err(err) = &mut sample.result {
+            err.message = self.intern_message(&err.message);
+        }
+        let queue = self.samples.entry(key).or_default();
+        if matches!(sample.result, ProbeResult::Ok) {
+            sample.jitter_ms = queue
+                .iter()
+                .rev()
+                .find(|previous| matches!(previous.result, ProbeResult::Ok) && !previous.priming)
+                .map(|previous| {
+                    (sample.t_total.as_secs_f64() - previous.t_total.as_secs_f64()).abs() * 1000.0
+                });
+        }
+        self.track_outage(key, &sample);
+        self.sample_bytes += sample_bytes(&sample);
         let queue = self.samples.entry(key).or_default();
         queue.push_back(sample);
-        while queue.len() > max_points {
+        let mut evicted_error = false;
+        while queue.len() > max_points
+            && let Some(oldest) = queue.front()
+            && (retention.is_zero()
+                || newest
+                    .duration_since(oldest.ts)
+                    .is_ok_and(|age| age > retention))
+        {
+            let evicted = oldest.ts;
+            evicted_error |= matches!(oldest.result, ProbeResult::Err(_));
+            self.sample_bytes -= sample_bytes(oldest);
             queue.pop_front();
+            let until = self.evicted_until.entry(key).or_insert(evicted);
+            *until = (*until).max(evicted);
         }
+        if evicted_error {
+            self.trim_messages();
+        }
+        *self.generations.entry(key).or_default() += 1;
+    }
+
+    /// Opens an outage on the first failure after a success, counts further
+    /// failures into it and closes it on the next success.
+    fn track_outage(&mut self, key: ProfileKey, sample: &ProbeSample) {
+        let outages = self.outages.entry(key).or_default();
+        let open = outages.back_mut().filter(|outage| outage.end.is_none());
+        match (&sample.result, open) {
+            (ProbeResult::Err(err), Some(outage)) => {
+                let errors = self.open_outage_errors.entry(key).or_default();
+                match errors.iter_mut().find(|(kind, _)| *kind == err.kind) {
+                    Some((_, count)) => *count += 1,
+                    None => errors.push((err.kind, 1)),
+                }
+                outage.probe_count += 1;
+                // `max_by_key` keeps the last maximum; reversed, that is the earliest
+                if let Some((kind, _)) = errors.iter().rev().max_by_key(|(_, count)| *count) {
+                    outage.dominant_error = *kind;
+                }
+            }
+            (ProbeResult::Err(err), None) => {
+                outages.push_back(OutageEvent {
+                    start: sample.ts,
+                    end: None,
+                    probe_count: 1,
+                    dominant_error: err.kind,
+                });
+                if outages.len() > MAX_OUTAGES {
+                    outages.pop_front();
+                }
+                self.open_outage_errors.insert(key, vec![(err.kind, 1)]);
+            }
+            (ProbeResult::Ok, Some(outage)) => {
+                outage.end = Some(sample.ts);
+                self.open_outage_errors.remove(&key);
+            }
+            (ProbeResult::Ok, None) => {}
+        }
+    }
+
+    /// The stored copy of `message`, added if no sample holds it yet.
+    fn intern_message(&mut self, message: &Arc<str>) -> Arc<str> {
+        if let Some(shared) = self.messages.get(&**message) {
+            return Arc::clone(shared);
+        }
+        self.messages.insert(Arc::clone(message));
+        self.message_bytes += message.len();
+        Arc::clone(message)
+    }
+
+    /// Forgets messages no stored sample refers to any more.
+    fn trim_messages(&mut self) {
+        let message_bytes = &mut self.message_bytes;
+        self.messages.retain(|message| {
+            let held = Arc::strong_count(message) > 1;
+            if !held {
+                *message_bytes -= message.len();
+            }
+            held
+        });
+    }
+
+    /// Rough heap footprint of the sample history: every stored sample, what
+    /// it owns and every distinct error message once. Kept up to date as
+    /// samples arrive and leave, so reading it is free.
+    pub fn approx_bytes(&self) -> usize {
+        self.sample_bytes + self.message_bytes
+    }
+
+    /// Drops every sample and cached aggregate of the target's profiles.
+    pub fn remove_target(&mut self, target_id: TargetId) {
+        let sample_bytes = &mut self.sample_bytes;
+        self.samples.retain(|key, queue| {
+            let keep = key.target_id != target_id;
+            if !keep {
+                *sample_bytes -= queue_bytes(queue);
+            }
+            keep
+        });
+        self.generations.retain(|key, _| key.target_id != target_id);
+        self.evicted_until
+            .retain(|key, _| key.target_id != target_id);
+        self.outages.retain(|key, _| key.target_id != target_id);
+        self.open_outage_errors
+            .retain(|key, _| key.target_id != target_id);
+        self.aggregate_cache
+            .get_mut()
+            .retain(|(key, _, _), _| key.target_id != target_id);
+        self.trim_messages();
+    }
+
+    /// Drops every sample and cached aggregate stored under `key` alone.
+    pub fn remove_key(&mut self, key: ProfileKey) {
+        if let Some(queue) = self.samples.remove(&key) {
+            self.sample_bytes -= queue_bytes(&queue);
+        }
+        self.generations.remove(&key);
+        self.evicted_until.remove(&key);
+        self.outages.remove(&key);
+        self.open_outage_errors.remove(&key);
+        self.aggregate_cache
+            .get_mut()
+            .retain(|(cached, _, _), _| *cached != key);
+        self.trim_messages();
+    }
+
+    /// Number of samples held across every profile.
+    pub fn sample_count(&self) -> usize {
+        self.samples.values().map(VecDeque::len).sum()
+    }
+
+    /// Every stored sample, oldest first within each profile.
+    pub fn iter_samples(&self) -> impl Iterator<Item = &ProbeSample> {
+        self.samples.values().flatten()
+    }
+
+    /// Timestamp of the profile's newest stored sample.
+    pub fn newest_sample_ts(&self, key: ProfileKey) -> Option<SystemTime> {
+        self.samples.get(&key)?.back().map(|sample| sample.ts)
     }
 
+    /// Number of aggregates computed from scratch (cache misses) so far.
+    pub fn aggregate_recomputations(&self) -> u64 {
+        self.recomputations.get()
+    }
+
+    /// Statistics of the profile's samples in `window`. Latency metrics only
+    /// count successful probes unless `failure_latency` is set, in which case
+    /// timed-out probes count toward Total as taking that long.
     pub fn windowed_aggregate(
         &self,
         key: ProfileKey,
         window: WindowSpec,
         sampling: &SamplingConfig,
-        link_capacity_mbps: Option<f64>,
+        view: &AggregateView,
+        failure_latency: Option<Duration>,
     ) -> WindowedAggregate {
-        self.windowed_aggregate_with_clock(key, window, sampling, link_capacity_mbps, &SystemClock)
+        self.windowed_aggregate_with_clock(
+            key,
+            window,
+            sampling,
+            view,
+            failure_latency,
+            &SystemClock,
+        )
     }
 
     pub fn windowed_aggregate_with_clock(
@@ -47,28 +353,156 @@ impl MetricsStore {
         key: ProfileKey,
         window: WindowSpec,
         sampling: &SamplingConfig,
-        link_capacity_mbps: Option<f64>,
+        view: &AggregateView,
+        failure_latency: Option<Duration>,
         clock: &dyn Clock,
     ) -> WindowedAggregate {
         let now = clock.now();
-        let cutoff = now
-            .checked_sub(window.duration())
-            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let generation = self.generations.get(&key).copied().unwrap_or(0);
+        if let Some(cached) = self
+            .aggregate_cache
+            .borrow()
+            .get(&(key, window, failure_latency))
+            && cached.is_valid(generation, now, sampling, view)
+        {
+            return cached.aggregate.clone();
+        }
+
+        let aggregate = self.compute_aggregate(
+            &[AggregateSource {
+                key,
+                window,
+                sampling,
+                failure_latency,
+            }],
+            window,
+            sampling,
+            view,
+            now,
+        );
+        let cutoff = window_cutoff(now, window);
+        let expires_at = self
+            .samples
+            .get(&key)
+            .and_then(|samples| {
+                samples
+                    .iter()
+                    .map(|s| s.ts)
+                    .filter(|ts| *ts >= cutoff)
+                    .min()
+            })
+            .and_then(|oldest| oldest.checked_add(window.duration()));
+        self.recomputations.set(self.recomputations.get() + 1);
+        self.aggregate_cache.borrow_mut().insert(
+            (key, window, failure_latency),
+            CachedAggregate {
+                generation,
+                computed_at: now,
+                expires_at,
+                sampling: sampling.clone(),
+                view: *view,
+                aggregate: aggregate.clone(),
+            },
+        );
+        aggregate
+    }
+
+    /// Statistics of the union of the sources' samples, each read over its
+    /// own window, as if they were one profile: counts and breakdowns add up
+    /// and percentiles come from one histogram of every sample, never from
+    /// averaging the profiles' own percentiles. The histogram spans every
+    /// source's bounds and the result is labelled with the longest window.
+    /// `None` without sources. Not cached; groups are few.
+    pub fn merged_aggregate(
+        &self,
+        sources: &[AggregateSource<'_>],
+        view: &AggregateView,
+    ) -> Option<WindowedAggregate> {
+        let first = sources.first()?;
+        let mut sampling = first.sampling.clone();
+        for source in &sources[1..] {
+            let histogram = &source.sampling.histogram;
+            sampling.histogram.latency_low_ms = sampling
+                .histogram
+                .latency_low_ms
+                .min(histogram.latency_low_ms);
+            sampling.histogram.latency_high_ms = sampling
+                .histogram
+                .latency_high_ms
+                .max(histogram.latency_high_ms);
+            sampling.histogram.sigfig = sampling.histogram.sigfig.max(histogram.sigfig);
+        }
+        let window = sources
+            .iter()
+            .map(|source| source.window)
+            .max_by_key(|window| window.duration())
+            .unwrap_or(first.window);
+        Some(self.compute_aggregate(sources, window, &sampling, view, SystemClock.now()))
+    }
+
+    /// Statistics of the sources' samples. `window` only labels the result
+    /// and `sampling` sets the histogram; which samples are read follows
+    /// each source.
+    fn compute_aggregate(
+        &self,
+        sources: &[AggregateSource<'_>],
+        window: WindowSpec,
+        sampling: &SamplingConfig,
+        view: &AggregateView,
+        now: SystemTime,
+    ) -> WindowedAggregate {
         let mut error_breakdown = HashMap::new();
+        let mut status_breakdown: HashMap<u16, u64> = HashMap::new();
+        let mut phase_failures = HashMap::new();
         let mut total_samples = 0u64;
         let mut error_samples = 0u64;
+        let mut cache_reported = 0u64;
+        let mut cache_hits = 0u64;
+        let mut reuse_reported = 0u64;
+        let mut reused = 0u64;
+        let mut missed = 0u64;
         let mut metric_values: HashMap<MetricKind, Vec<f64>> = HashMap::new();
 
-        if let Some(samples) = self.samples.get(&key) {
-            for sample in samples.iter().filter(|s| s.ts >= cutoff) {
+        for source in sources {
+            let Some(samples) = self.samples.get(&source.key) else {
+                continue;
+            };
+            let cutoff = window_cutoff(now, source.window);
+            let skip_burst =
+                view.exclude_burst_from_long_windows && source.window.duration() >= LONG_WINDOW;
+            let skip_priming = !source.sampling.include_priming_samples;
+            let failure_latency = source.failure_latency;
+            for sample in samples
+                .iter()
+                .filter(|s| s.ts >= cutoff && !(skip_burst && s.burst))
+            {
                 total_samples += 1;
+                missed += u64::from(sample.missed_slots);
+                // curl reports 0 when no response was received
+                if let Some(status) = sample.http_status.filter(|status| *status != 0) {
+                    *status_breakdown.entry(status).or_insert(0) += 1;
+                }
+                if let Some(hit) = sample.cache_hit() {
+                    cache_reported += 1;
+                    cache_hits += u64::from(hit);
+                }
                 match &sample.result {
                     ProbeResult::Ok => {
+                        // A priming probe opens its connection by design
+                        if let Some(opened) = sample.new_connections.filter(|_| !sample.priming) {
+                            reuse_reported += 1;
+                            reused += u64::from(opened == 0);
+                        }
+                        // A priming probe still counts toward loss and statuses
                         for &metric in MetricKind::iter_all() {
-                            if metric == MetricKind::ProbeLossRate {
+                            if metric.is_window_metric()
+                                || (skip_priming && sample.priming && metric.is_latency_metric())
+                            {
                                 continue;
                             }
-                            if let Some(value) = sample_metric(sample, metric, link_capacity_mbps) {
+                            if let Some(value) =
+                                sample_metric(sample, metric, view.link_capacity_mbps)
+                            {
                                 metric_values.entry(metric).or_default().push(value);
                             }
                         }
@@ -76,30 +510,50 @@ impl MetricsStore {
                     ProbeResult::Err(err) => {
                         error_samples += 1;
                         *error_breakdown.entry(err.kind).or_insert(0) += 1;
+                        if let Some(phase) = sample.failed_phase {
+                            *phase_failures.entry(phase).or_insert(0) += 1;
+                        }
+                        if let Some(latency) = failure_latency.filter(|_| err.kind.is_timeout()) {
+                            metric_values
+                                .entry(MetricKind::Total)
+                                .or_default()
+                                .push(latency.as_secs_f64() * 1000.0);
+                        }
                     }
                 }
             }
         }
 
-        if let Some(total_values) = metric_values.get(&MetricKind::Total)
-            && total_values.len() > 1
-        {
-            let jitter_values: Vec<f64> = total_values
-                .windows(2)
-                .map(|w| (w[1] - w[0]).abs())
-                .collect();
-            metric_values.insert(MetricKind::Jitter, jitter_values);
-        }
-
         let mut by_metric = HashMap::new();
+        // Every received probe filled a slot; the missed ones filled none
+        let expected = total_samples + missed;
         for &metric in MetricKind::iter_all() {
-            if metric == MetricKind::ProbeLossRate {
-                let rate = if total_samples == 0 {
+            // Shown as a count; `missed_probes` holds it for callers
+            if metric == MetricKind::MissedProbes {
+                let count = (expected > 0).then_some(missed as f64);
+                by_metric.insert(metric, MetricStats::from_scalar(count, expected));
+                continue;
+            }
+            if metric.is_window_rate() {
+                // The cache hit and reuse rates only count probes that report them
+                let (matching, out_of) = match metric {
+                    MetricKind::Http4xxRate => {
+                        (status_class_count(&status_breakdown, 4), total_samples)
+                    }
+                    MetricKind::Http5xxRate => {
+                        (status_class_count(&status_breakdown, 5), total_samples)
+                    }
+                    MetricKind::CacheHitRate => (cache_hits, cache_reported),
+                    MetricKind::ConnReuseRate => (reused, reuse_reported),
+                    _ if view.count_missed_as_loss => (error_samples + missed, expected),
+                    _ => (error_samples, total_samples),
+                };
+                let rate = if out_of == 0 {
                     None
                 } else {
-                    Some(error_samples as f64 / total_samples as f64)
+                    Some(matching as f64 / out_of as f64)
                 };
-                by_metric.insert(metric, MetricStats::from_scalar(rate, total_samples));
+                by_metric.insert(metric, MetricStats::from_scalar(rate, out_of));
                 continue;
             }
 
@@ -114,17 +568,70 @@ impl MetricsStore {
             window,
             by_metric,
             error_breakdown,
+            status_breakdown,
+            phase_failures,
+            missed_probes: missed,
+            fully_covered: sources.iter().all(|source| {
+                self.evicted_until
+                    .get(&source.key)
+                    .is_none_or(|evicted| *evicted < window_cutoff(now, source.window))
+            }),
         }
     }
 
+    pub fn slo_counts(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        latency_budget_ms: Option<f64>,
+    ) -> SloCounts {
+        self.slo_counts_with_clock(key, window, latency_budget_ms, &SystemClock)
+    }
+
+    pub fn slo_counts_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        latency_budget_ms: Option<f64>,
+        clock: &dyn Clock,
+    ) -> SloCounts {
+        let now = clock.now();
+        let cutoff = window_cutoff(now, window);
+        let mut counts = SloCounts::default();
+
+        if let Some(samples) = self.samples.get(&key) {
+            for sample in samples.iter().filter(|s| s.ts >= cutoff) {
+                counts.total += 1;
+                let within_budget = latency_budget_ms
+                    .is_none_or(|budget| sample.t_total.as_secs_f64() * 1000.0 <= budget);
+                if matches!(sample.result, ProbeResult::Ok) && within_budget {
+                    counts.good += 1;
+                }
+            }
+        }
+
+        counts
+    }
+
+    /// `(seconds into the window, value)` of every sample with `metric`.
+    /// With `failure_latency` set, timed-out probes are plotted on Total at
+    /// that latency, matching [`Self::windowed_aggregate`].
     pub fn timeseries(
         &self,
         key: ProfileKey,
         window: WindowSpec,
         metric: MetricKind,
         link_capacity_mbps: Option<f64>,
+        failure_latency: Option<Duration>,
     ) -> Vec<(f64, f64)> {
-        self.timeseries_with_clock(key, window, metric, link_capacity_mbps, &SystemClock)
+        self.timeseries_with_clock(
+            key,
+            window,
+            metric,
+            link_capacity_mbps,
+            failure_latency,
+            &SystemClock,
+        )
     }
 
     pub fn timeseries_with_clock(
@@ -133,19 +640,26 @@ impl MetricsStore {
         window: WindowSpec,
         metric: MetricKind,
         link_capacity_mbps: Option<f64>,
+        failure_latency: Option<Duration>,
         clock: &dyn Clock,
     ) -> Vec<(f64, f64)> {
         let now = clock.now();
         let window_seconds = window.duration().as_secs_f64();
-        let cutoff = now
-            .checked_sub(window.duration())
-            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let cutoff = window_cutoff(now, window);
         let mut points = Vec::new();
 
         if let Some(samples) = self.samples.get(&key) {
             for sample in samples.iter().filter(|s| s.ts >= cutoff) {
-                if let ProbeResult::Ok = sample.result
-                    && let Some(value) = sample_metric(sample, metric, link_capacity_mbps)
+                let value = match &sample.result {
+                    ProbeResult::Ok => sample_metric(sample, metric, link_capacity_mbps),
+                    ProbeResult::Err(err)
+                        if metric == MetricKind::Total && err.kind.is_timeout() =>
+                    {
+                        failure_latency.map(|latency| latency.as_secs_f64() * 1000.0)
+                    }
+                    ProbeResult::Err(_) => None,
+                };
+                if let Some(value) = value
                     && let Ok(age) = now.duration_since(sample.ts)
                 {
                     let x = (window_seconds - age.as_secs_f64()).max(0.0);
@@ -157,37 +671,309 @@ impl MetricsStore {
         points
     }
 
-    pub fn timeout_events(&self, key: ProfileKey, window: WindowSpec) -> Vec<f64> {
-        self.timeout_events_with_clock(key, window, &SystemClock)
+    /// `stat` of `metric` computed per time bucket, one point per non-empty
+    /// bucket positioned at the bucket centre. Buckets hold few samples, so
+    /// quantiles are exact rather than histogram-based.
+    pub fn stat_timeseries(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        metric: MetricKind,
+        stat: StatColumn,
+        link_capacity_mbps: Option<f64>,
+    ) -> Vec<(f64, f64)> {
+        self.stat_timeseries_with_clock(key, window, metric, stat, link_capacity_mbps, &SystemClock)
     }
 
-    pub fn timeout_events_with_clock(
+    pub fn stat_timeseries_with_clock(
         &self,
         key: ProfileKey,
         window: WindowSpec,
+        metric: MetricKind,
+        stat: StatColumn,
+        link_capacity_mbps: Option<f64>,
         clock: &dyn Clock,
-    ) -> Vec<f64> {
+    ) -> Vec<(f64, f64)> {
+        let window_seconds = window.duration().as_secs_f64();
+        let bucket_seconds = window_seconds / STAT_BUCKETS as f64;
+        let mut buckets: Vec<Vec<f64>> = vec![Vec::new(); STAT_BUCKETS];
+        for (x, value) in
+            self.timeseries_with_clock(key, window, metric, link_capacity_mbps, None, clock)
+        {
+            let idx = ((x / bucket_seconds) as usize).min(STAT_BUCKETS - 1);
+            buckets[idx].push(value);
+        }
+
+        buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, values)| !values.is_empty())
+            .filter_map(|(idx, values)| {
+                let stats = compute_stats(values, &SamplingConfig::default(), false);
+                stat.select(&stats)
+                    .map(|value| ((idx as f64 + 0.5) * bucket_seconds, value))
+            })
+            .collect()
+    }
+
+    /// Successful samples of `metric` in the window bucketed into `cols` time
+    /// columns by `rows` value rows spaced evenly on a log scale between the
+    /// smallest and largest value. `None` without samples or cells.
+    pub fn histogram_matrix(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        metric: MetricKind,
+        cols: usize,
+        rows: usize,
+    ) -> Option<HistogramMatrix> {
+        self.histogram_matrix_with_clock(key, window, metric, cols, rows, &SystemClock)
+    }
+
+    pub fn histogram_matrix_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        metric: MetricKind,
+        cols: usize,
+        rows: usize,
+        clock: &dyn Clock,
+    ) -> Option<HistogramMatrix> {
+        if cols == 0 || rows == 0 {
+            return None;
+        }
+        let points = self.timeseries_with_clock(key, window, metric, None, None, clock);
+        let positive = points.iter().map(|(_, value)| *value).filter(|v| *v > 0.0);
+        let low = positive.clone().fold(f64::INFINITY, f64::min);
+        let high = positive.fold(0.0, f64::max);
+        if points.is_empty() || !low.is_finite() {
+            return None;
+        }
+        // A single distinct value still gets a visible band
+        let high = if high > low { high } else { low * 2.0 };
+        let ratio = (high / low).ln();
+        let bounds = (0..=rows)
+            .map(|row| low * (ratio * row as f64 / rows as f64).exp())
+            .collect();
+
+        let window_seconds = window.duration().as_secs_f64();
+        let mut counts = vec![vec![0u32; rows]; cols];
+        for (x, value) in points {
+            let col = ((x / window_seconds * cols as f64) as usize).min(cols - 1);
+            let row = if value > low {
+                ((value / low).ln() / ratio * rows as f64) as usize
+            } else {
+                0
+            };
+            counts[col][row.min(rows - 1)] += 1;
+        }
+        Some(HistogramMatrix { bounds, counts })
+    }
+
+    /// Phase durations of the newest `limit` successful probes in the window,
+    /// oldest first. Phases a probe skipped (e.g. TLS over http) count as zero.
+    pub fn recent_phases(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        limit: usize,
+    ) -> Vec<PhaseBreakdown> {
+        self.recent_phases_with_clock(key, window, limit, &SystemClock)
+    }
+
+    pub fn recent_phases_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        limit: usize,
+        clock: &dyn Clock,
+    ) -> Vec<PhaseBreakdown> {
+        let cutoff = window_cutoff(clock.now(), window);
+        let Some(samples) = self.samples.get(&key) else {
+            return Vec::new();
+        };
+        let mut phases: Vec<PhaseBreakdown> = samples
+            .iter()
+            .rev()
+            .filter(|s| s.ts >= cutoff && matches!(s.result, ProbeResult::Ok) && s.ping.is_none())
+            .take(limit)
+            .map(|sample| PhaseBreakdown {
+                ts: sample.ts,
+                phases_ms: PHASE_METRICS
+                    .map(|metric| sample_metric(sample, metric, None).unwrap_or(0.0)),
+            })
+            .collect();
+        phases.reverse();
+        phases
+    }
+
+    /// Successful samples of latency `metric` in the window that exceed
+    /// `sampling.spikes`: a multiple of the window's p99 or the absolute
+    /// ceiling, whichever is lower. Oldest first.
+    pub fn spikes(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        metric: MetricKind,
+        sampling: &SamplingConfig,
+        view: &AggregateView,
+    ) -> Vec<SpikeEvent> {
+        self.spikes_with_clock(key, window, metric, sampling, view, &SystemClock)
+    }
+
+    pub fn spikes_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        metric: MetricKind,
+        sampling: &SamplingConfig,
+        view: &AggregateView,
+        clock: &dyn Clock,
+    ) -> Vec<SpikeEvent> {
+        if !metric.is_latency_metric() {
+            return Vec::new();
+        }
+        let relative = self
+            .windowed_aggregate_with_clock(key, window, sampling, view, None, clock)
+            .by_metric
+            .get(&metric)
+            .and_then(|stats| stats.p99)
+            .map(|p99| p99 * sampling.spikes.p99_multiple);
+        let threshold = match (relative, sampling.spikes.absolute_ms) {
+            (Some(relative), Some(absolute)) => relative.min(absolute),
+            (Some(threshold), None) | (None, Some(threshold)) => threshold,
+            (None, None) => return Vec::new(),
+        };
+
+        let cutoff = window_cutoff(clock.now(), window);
+        let Some(samples) = self.samples.get(&key) else {
+            return Vec::new();
+        };
+        samples
+            .iter()
+            .filter(|s| s.ts >= cutoff && matches!(s.result, ProbeResult::Ok))
+            .filter_map(|sample| {
+                sample_metric(sample, metric, view.link_capacity_mbps)
+                    .filter(|value| *value > threshold)
+                    .map(|value| SpikeEvent {
+                        ts: sample.ts,
+                        metric,
+                        value,
+                    })
+            })
+            .collect()
+    }
+
+    /// Outages of the profile that overlap the window, oldest first: the
+    /// ones that ended inside it and the one still in progress.
+    pub fn outages(&self, key: ProfileKey, window: WindowSpec) -> Vec<OutageEvent> {
+        self.outages_with_clock(key, window, &SystemClock)
+    }
+
+    pub fn outages_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        clock: &dyn Clock,
+    ) -> Vec<OutageEvent> {
+        let cutoff = window_cutoff(clock.now(), window);
+        self.outages.get(&key).map_or_else(Vec::new, |outages| {
+            outages
+                .iter()
+                .filter(|outage| outage.end.is_none_or(|end| end >= cutoff))
+                .copied()
+                .collect()
+        })
+    }
+
+    /// X positions (seconds since the window start) and kinds of every failed
+    /// probe in the window, oldest first.
+    pub fn error_events(&self, key: ProfileKey, window: WindowSpec) -> Vec<(f64, ProbeErrorKind)> {
+        self.error_events_with_clock(key, window, &SystemClock)
+    }
+
+    pub fn error_events_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        clock: &dyn Clock,
+    ) -> Vec<(f64, ProbeErrorKind)> {
         let now = clock.now();
         let window_seconds = window.duration().as_secs_f64();
-        let cutoff = now
-            .checked_sub(window.duration())
-            .unwrap_or(SystemTime::UNIX_EPOCH);
-        let mut points = Vec::new();
+        let cutoff = window_cutoff(now, window);
+        let mut events = Vec::new();
 
         if let Some(samples) = self.samples.get(&key) {
             for sample in samples.iter().filter(|s| s.ts >= cutoff) {
                 if let ProbeResult::Err(err) = &sample.result
-                    && is_timeout_error(&err.kind)
                     && let Ok(age) = now.duration_since(sample.ts)
                 {
                     let x = (window_seconds - age.as_secs_f64()).max(0.0);
-                    points.push(x);
+                    events.push((x, err.kind));
                 }
             }
         }
 
-        points
+        events
     }
+
+    /// Chart x positions of successful, non-priming probes that had to open
+    /// a new connection.
+    pub fn new_connection_events(&self, key: ProfileKey, window: WindowSpec) -> Vec<f64> {
+        self.new_connection_events_with_clock(key, window, &SystemClock)
+    }
+
+    pub fn new_connection_events_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        clock: &dyn Clock,
+    ) -> Vec<f64> {
+        let now = clock.now();
+        let window_seconds = window.duration().as_secs_f64();
+        let cutoff = window_cutoff(now, window);
+        let Some(samples) = self.samples.get(&key) else {
+            return Vec::new();
+        };
+        samples
+            .iter()
+            .filter(|s| s.ts >= cutoff && !s.priming && matches!(s.result, ProbeResult::Ok))
+            .filter(|s| s.new_connections.is_some_and(|opened| opened > 0))
+            .filter_map(|s| now.duration_since(s.ts).ok())
+            .map(|age| (window_seconds - age.as_secs_f64()).max(0.0))
+            .collect()
+    }
+
+    /// The timeouts among [`Self::error_events`].
+    pub fn timeout_events(&self, key: ProfileKey, window: WindowSpec) -> Vec<f64> {
+        self.timeout_events_with_clock(key, window, &SystemClock)
+    }
+
+    pub fn timeout_events_with_clock(
+        &self,
+        key: ProfileKey,
+        window: WindowSpec,
+        clock: &dyn Clock,
+    ) -> Vec<f64> {
+        self.error_events_with_clock(key, window, clock)
+            .into_iter()
+            .filter(|(_, kind)| is_timeout_error(kind))
+            .map(|(x, _)| x)
+            .collect()
+    }
+}
+
+fn status_class_count(status_breakdown: &HashMap<u16, u64>, class: u16) -> u64 {
+    status_breakdown
+        .iter()
+        .filter(|(status, _)| **status / 100 == class)
+        .map(|(_, count)| *count)
+        .sum()
+}
+
+fn window_cutoff(now: SystemTime, window: WindowSpec) -> SystemTime {
+    now.checked_sub(window.duration())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
 }
 
 #[cfg(test)]
@@ -1,69 +1,36 @@
-use super::{MetricsStore, ProfileKey};
+use super::{AggregateSource, AggregateView, MetricsStore, ProfileKey, sample_bytes};
 use crate::common::time::Clock;
 use crate::config::{SamplingConfig, WindowSpec};
-use crate::metrics::MetricKind;
-use crate::probe::{NegotiatedProtocol, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample};
+use crate::features::probe::testing::SampleBuilder;
+use crate::metrics::{MetricKind, OutageSummary, StatColumn};
+use crate::probe::{
+    ErrorCategory, Phase, PingMode, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample,
+};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+/// Reads windows without link capacity or display toggles
+const PLAIN: AggregateView = AggregateView {
+    link_capacity_mbps: None,
+    exclude_burst_from_long_windows: false,
+    count_missed_as_loss: false,
+};
+
 fn ok_sample(target_id: Uuid, profile_id: Uuid, total_ms: u64) -> ProbeSample {
     ok_sample_at(SystemTime::now(), target_id, profile_id, total_ms)
 }
 
 fn ok_sample_at(ts: SystemTime, target_id: Uuid, profile_id: Uuid, total_ms: u64) -> ProbeSample {
-    let total = Duration::from_millis(total_ms);
-    ProbeSample {
-        ts,
-        target_id,
-        profile_id,
-        result: ProbeResult::Ok,
-        http_status: Some(200),
-        negotiated: NegotiatedProtocol {
-            alpn: Some("h2".to_string()),
-            tls_version: Some("TLSv1.3".to_string()),
-            cipher: None,
-        },
-        t_dns: Some(Duration::from_millis(2)),
-        t_connect: Duration::from_millis(5),
-        t_tls: Some(Duration::from_millis(8)),
-        t_ttfb: Duration::from_millis(12),
-        t_download: Duration::from_millis(total_ms.saturating_sub(12)),
-        t_total: total,
-        downloaded_bytes: 1024,
-        local: None,
-        remote: None,
-        tcp_info: None,
-        ebpf: None,
-    }
+    SampleBuilder::ok(target_id, profile_id)
+        .total_ms(total_ms)
+        .at(ts)
+        .build()
 }
 
 fn error_sample(kind: ProbeErrorKind) -> ProbeSample {
-    ProbeSample {
-        ts: SystemTime::now(),
-        target_id: Uuid::new_v4(),
-        profile_id: Uuid::new_v4(),
-        result: ProbeResult::Err(ProbeError {
-            kind,
-            message: "error".to_string(),
-        }),
-        http_status: None,
-        negotiated: NegotiatedProtocol {
-            alpn: None,
-            tls_version: None,
-            cipher: None,
-        },
-        t_dns: None,
-        t_connect: Duration::from_millis(0),
-        t_tls: None,
-        t_ttfb: Duration::from_millis(0),
-        t_download: Duration::from_millis(0),
-        t_total: Duration::from_millis(0),
-        downloaded_bytes: 0,
-        local: None,
-        remote: None,
-        tcp_info: None,
-        ebpf: None,
-    }
+    SampleBuilder::ok(Uuid::new_v4(), Uuid::new_v4())
+        .failed(kind)
+        .build()
 }
 
 struct FixedClock(SystemTime);
@@ -87,17 +54,90 @@ fn timeout_events_only_include_timeouts() {
     let mut timeout_sample = error_sample(ProbeErrorKind::HttpTimeout);
     timeout_sample.target_id = target_id;
     timeout_sample.profile_id = profile_id;
-    store.push_sample(key, timeout_sample, 16);
+    store.push_sample(key, timeout_sample, 16, Duration::ZERO);
 
     let mut non_timeout = error_sample(ProbeErrorKind::HttpStatusError);
     non_timeout.target_id = target_id;
     non_timeout.profile_id = profile_id;
-    store.push_sample(key, non_timeout, 16);
+    store.push_sample(key, non_timeout, 16, Duration::ZERO);
 
     let events = store.timeout_events(key, WindowSpec::M1);
     assert_eq!(events.len(), 1);
 }
 
+#[test]
+fn error_events_place_every_failure_with_its_kind() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let now = SystemTime::now();
+
+    let kinds = [
+        (50, ProbeErrorKind::TlsHandshakeFailed),
+        (40, ProbeErrorKind::ReadTimeout),
+        (30, ProbeErrorKind::HttpStatusError),
+        (20, ProbeErrorKind::ConnectRefused),
+    ];
+    for (age, kind) in kinds {
+        let mut sample = error_sample(kind);
+        sample.ts = now - Duration::from_secs(age);
+        sample.target_id = target_id;
+        sample.profile_id = profile_id;
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+    store.push_sample(
+        key,
+        ok_sample_at(now - Duration::from_secs(10), target_id, profile_id, 90),
+        16,
+        Duration::ZERO,
+    );
+    // Outside the one-minute window
+    let mut stale = error_sample(ProbeErrorKind::HttpTimeout);
+    stale.ts = now - Duration::from_secs(90);
+    stale.target_id = target_id;
+    stale.profile_id = profile_id;
+    store.push_sample(key, stale, 16, Duration::ZERO);
+
+    let clock = FixedClock(now);
+    let events = store.error_events_with_clock(key, WindowSpec::M1, &clock);
+    let mut kinds_by_x: Vec<(u64, ProbeErrorKind)> = events
+        .iter()
+        .map(|(x, kind)| (x.round() as u64, *kind))
+        .collect();
+    kinds_by_x.sort();
+    assert_eq!(
+        kinds_by_x,
+        vec![
+            (10, ProbeErrorKind::TlsHandshakeFailed),
+            (20, ProbeErrorKind::ReadTimeout),
+            (30, ProbeErrorKind::HttpStatusError),
+            (40, ProbeErrorKind::ConnectRefused),
+        ]
+    );
+
+    let categories: Vec<_> = events
+        .iter()
+        .filter_map(|(_, kind)| kind.category())
+        .collect();
+    assert_eq!(
+        categories,
+        vec![
+            ErrorCategory::Tls,
+            ErrorCategory::Timeout,
+            ErrorCategory::HttpStatus,
+            ErrorCategory::Other,
+        ]
+    );
+    assert_eq!(
+        store.timeout_events_with_clock(key, WindowSpec::M1, &clock),
+        vec![20.0]
+    );
+}
+
 #[test]
 fn windowed_aggregate_tracks_error_rate_and_totals() {
     let mut store = MetricsStore::new();
@@ -108,14 +148,30 @@ fn windowed_aggregate_tracks_error_rate_and_totals() {
         profile_id,
     };
 
-    store.push_sample(key, ok_sample(target_id, profile_id, 120), 16);
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 120),
+        16,
+        Duration::ZERO,
+    );
     let mut timeout_sample = error_sample(ProbeErrorKind::HttpTimeout);
     timeout_sample.target_id = target_id;
     timeout_sample.profile_id = profile_id;
-    store.push_sample(key, timeout_sample, 16);
-    store.push_sample(key, ok_sample(target_id, profile_id, 240), 16);
+    store.push_sample(key, timeout_sample, 16, Duration::ZERO);
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 240),
+        16,
+        Duration::ZERO,
+    );
 
-    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &SamplingConfig::default(), None);
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
 
     let loss_rate = aggregate
         .by_metric
@@ -140,6 +196,404 @@ fn windowed_aggregate_tracks_error_rate_and_totals() {
     );
 }
 
+#[test]
+fn windowed_aggregate_breaks_down_http_statuses() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    for (status, count) in [(200, 6), (301, 1), (429, 2), (503, 1)] {
+        for _ in 0..count {
+            let mut sample = if status >= 400 {
+                let mut sample = error_sample(ProbeErrorKind::HttpStatusError);
+                sample.target_id = target_id;
+                sample.profile_id = profile_id;
+                sample
+            } else {
+                ok_sample(target_id, profile_id, 100)
+            };
+            sample.http_status = Some(status);
+            store.push_sample(key, sample, 32, Duration::ZERO);
+        }
+    }
+    // Transport failures carry no status and must not appear in the breakdown
+    let mut refused = error_sample(ProbeErrorKind::ConnectRefused);
+    refused.target_id = target_id;
+    refused.profile_id = profile_id;
+    refused.http_status = Some(0);
+    store.push_sample(key, refused, 32, Duration::ZERO);
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+
+    assert_eq!(aggregate.status_breakdown.len(), 4);
+    assert_eq!(aggregate.status_breakdown.get(&200), Some(&6));
+    assert_eq!(aggregate.status_breakdown.get(&301), Some(&1));
+    assert_eq!(aggregate.status_breakdown.get(&429), Some(&2));
+    assert_eq!(aggregate.status_breakdown.get(&503), Some(&1));
+
+    let rate = |metric| {
+        aggregate
+            .by_metric
+            .get(&metric)
+            .and_then(|stats| stats.mean)
+            .expect("rate")
+    };
+    assert!((rate(MetricKind::Http4xxRate) - 2.0 / 11.0).abs() < 1e-6);
+    assert!((rate(MetricKind::Http5xxRate) - 1.0 / 11.0).abs() < 1e-6);
+}
+
+#[test]
+fn failure_latency_counts_timeouts_toward_total_only_when_set() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    for total_ms in [100, 200] {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, total_ms),
+            16,
+            Duration::ZERO,
+        );
+    }
+    for kind in [ProbeErrorKind::HttpTimeout, ProbeErrorKind::ConnectRefused] {
+        let mut failed = error_sample(kind);
+        failed.target_id = target_id;
+        failed.profile_id = profile_id;
+        store.push_sample(key, failed, 16, Duration::ZERO);
+    }
+    let sampling = SamplingConfig::default();
+    let timeout = Some(Duration::from_secs(10));
+
+    let successes = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let total = &successes.by_metric[&MetricKind::Total];
+    assert_eq!(total.n, 2);
+    assert_eq!(total.max, Some(200.0));
+
+    // Only the timeout counts, and only toward Total
+    let with_failures = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, timeout);
+    let total = &with_failures.by_metric[&MetricKind::Total];
+    assert_eq!(total.n, 3);
+    assert_eq!(total.max, Some(10_000.0));
+    assert_eq!(with_failures.by_metric[&MetricKind::Ttfb].n, 2);
+    assert_eq!(
+        with_failures.by_metric[&MetricKind::ProbeLossRate].mean,
+        Some(0.5)
+    );
+
+    let values = |failure_latency| -> Vec<f64> {
+        store
+            .timeseries(
+                key,
+                WindowSpec::M1,
+                MetricKind::Total,
+                None,
+                failure_latency,
+            )
+            .into_iter()
+            .map(|(_, value)| value.round())
+            .collect()
+    };
+    assert_eq!(values(None), vec![100.0, 200.0]);
+    assert_eq!(values(timeout), vec![100.0, 200.0, 10_000.0]);
+    let ttfb = store.timeseries(key, WindowSpec::M1, MetricKind::Ttfb, None, timeout);
+    assert_eq!(ttfb.len(), 2);
+}
+
+#[test]
+fn out_of_range_latencies_are_clamped_and_counted() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    for total_ms in [100, 200, 90_000] {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, total_ms),
+            16,
+            Duration::ZERO,
+        );
+    }
+    let mut sampling = SamplingConfig::default();
+
+    // The 90s sample lands on the 60s upper bound; min/max stay exact
+    let clamped = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let total = &clamped.by_metric[&MetricKind::Total];
+    assert_eq!(total.clamped, 1);
+    assert_eq!(total.max, Some(90_000.0));
+    let p99 = total.p99.expect("p99");
+    assert!((p99 - 60_000.0).abs() < 600.0, "p99 {p99}");
+    assert_eq!(clamped.by_metric[&MetricKind::Ttfb].clamped, 0);
+
+    // Widening the bounds to a 120s timeout records the sample as is
+    sampling.histogram.cover(Duration::from_secs(120));
+    assert_eq!(sampling.histogram.latency_high_ms, 120_000);
+    let widened = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let total = &widened.by_metric[&MetricKind::Total];
+    assert_eq!(total.clamped, 0);
+    let p99 = total.p99.expect("p99");
+    assert!((p99 - 90_000.0).abs() < 900.0, "p99 {p99}");
+
+    // Sub-millisecond phases are raised to the 1ms lower bound
+    let mut fast = ok_sample(target_id, profile_id, 100);
+    fast.t_dns = Some(Duration::from_micros(200));
+    store.push_sample(key, fast, 16, Duration::ZERO);
+    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let dns = &aggregate.by_metric[&MetricKind::Dns];
+    assert_eq!(dns.clamped, 1);
+    assert_eq!(dns.min, Some(0.2));
+}
+
+#[test]
+fn priming_samples_are_left_out_of_latency_unless_included() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let mut cold = ok_sample(target_id, profile_id, 400);
+    cold.priming = true;
+    store.push_sample(key, cold, 16, Duration::ZERO);
+    for total_ms in [100, 120] {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, total_ms),
+            16,
+            Duration::ZERO,
+        );
+    }
+    let mut sampling = SamplingConfig::default();
+
+    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let total = &aggregate.by_metric[&MetricKind::Total];
+    assert_eq!(total.n, 2);
+    assert_eq!(total.max, Some(120.0));
+    // The first warm sample's jitter is not taken against the cold one
+    assert_eq!(aggregate.by_metric[&MetricKind::Jitter].n, 1);
+    // It still counts as a probe
+    assert_eq!(aggregate.by_metric[&MetricKind::ProbeLossRate].n, 3);
+
+    sampling.include_priming_samples = true;
+    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    let total = &aggregate.by_metric[&MetricKind::Total];
+    assert_eq!(total.n, 3);
+    assert_eq!(total.max, Some(400.0));
+}
+
+#[test]
+fn missed_slots_are_counted_apart_from_loss_unless_asked() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    for _ in 0..3 {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, 100),
+            16,
+            Duration::ZERO,
+        );
+    }
+    // The probe after a suspend reports the slots it slept through
+    let mut woke = error_sample(ProbeErrorKind::HttpTimeout);
+    woke.target_id = target_id;
+    woke.profile_id = profile_id;
+    woke.missed_slots = 6;
+    store.push_sample(key, woke, 16, Duration::ZERO);
+
+    let sampling = SamplingConfig::default();
+    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None);
+    assert_eq!(aggregate.missed_probes, 6);
+    let missed = &aggregate.by_metric[&MetricKind::MissedProbes];
+    assert_eq!((missed.mean, missed.n), (Some(6.0), 10));
+    let loss = &aggregate.by_metric[&MetricKind::ProbeLossRate];
+    assert_eq!((loss.mean, loss.n), (Some(0.25), 4));
+
+    let view = AggregateView {
+        count_missed_as_loss: true,
+        ..PLAIN
+    };
+    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &sampling, &view, None);
+    let loss = &aggregate.by_metric[&MetricKind::ProbeLossRate];
+    assert_eq!((loss.mean, loss.n), (Some(0.7), 10));
+}
+
+#[test]
+fn failures_are_counted_by_the_phase_they_stopped_in() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 100),
+        16,
+        Duration::ZERO,
+    );
+    for (kind, phase) in [
+        (ProbeErrorKind::HttpTimeout, Some(Phase::Tls)),
+        (ProbeErrorKind::TlsHandshakeFailed, Some(Phase::Tls)),
+        (ProbeErrorKind::ConnectRefused, Some(Phase::Connect)),
+        (ProbeErrorKind::HttpStatusError, None),
+    ] {
+        let mut sample = error_sample(kind);
+        sample.target_id = target_id;
+        sample.profile_id = profile_id;
+        sample.failed_phase = phase;
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+    assert_eq!(aggregate.phase_failures.get(&Phase::Tls), Some(&2));
+    assert_eq!(aggregate.phase_failures.get(&Phase::Connect), Some(&1));
+    assert_eq!(aggregate.phase_failures.len(), 2);
+}
+
+#[test]
+fn cache_hit_rate_counts_only_responses_reporting_a_cache_status() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    for value in ["HIT", "HIT", "MISS"] {
+        let mut sample = ok_sample(target_id, profile_id, 100);
+        sample.response_headers = vec![("cf-cache-status".to_string(), value.to_string())];
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 100),
+        16,
+        Duration::ZERO,
+    );
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+
+    let hit_rate = aggregate
+        .by_metric
+        .get(&MetricKind::CacheHitRate)
+        .expect("cache hit rate");
+    assert_eq!(hit_rate.n, 3);
+    assert!((hit_rate.mean.expect("rate") - 2.0 / 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn conn_reuse_rate_leaves_out_priming_and_unreported_probes() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let mut priming = ok_sample(target_id, profile_id, 100);
+    priming.priming = true;
+    priming.new_connections = Some(1);
+    store.push_sample(key, priming, 16, Duration::ZERO);
+    for opened in [Some(0), Some(0), Some(1), None] {
+        let mut sample = ok_sample(target_id, profile_id, 100);
+        sample.new_connections = opened;
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+    let reuse = &aggregate.by_metric[&MetricKind::ConnReuseRate];
+    assert_eq!(reuse.n, 3);
+    assert!((reuse.mean.expect("rate") - 2.0 / 3.0).abs() < 1e-6);
+    assert_eq!(store.new_connection_events(key, WindowSpec::M1).len(), 1);
+}
+
+#[test]
+fn windowed_aggregate_tracks_response_size_and_content_length() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    // Body read truncated at 1 KiB while the server announced 8 KiB
+    let mut truncated = ok_sample(target_id, profile_id, 100);
+    truncated.content_length = Some(8192);
+    store.push_sample(key, truncated, 16, Duration::ZERO);
+    let mut chunked = ok_sample(target_id, profile_id, 100);
+    chunked.downloaded_bytes = 512;
+    chunked.content_length = None;
+    store.push_sample(key, chunked, 16, Duration::ZERO);
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+
+    let response = aggregate
+        .by_metric
+        .get(&MetricKind::ResponseBytes)
+        .expect("response bytes");
+    assert_eq!(response.n, 2);
+    assert_eq!(response.mean, Some(768.0));
+    let content_length = aggregate
+        .by_metric
+        .get(&MetricKind::ContentLength)
+        .expect("content length");
+    assert_eq!(content_length.n, 1);
+    assert_eq!(content_length.last, Some(8192.0));
+}
+
 #[test]
 fn push_sample_respects_max_points() {
     let mut store = MetricsStore::new();
@@ -150,11 +604,17 @@ fn push_sample_respects_max_points() {
         profile_id,
     };
 
-    store.push_sample(key, ok_sample(target_id, profile_id, 10), 2);
-    store.push_sample(key, ok_sample(target_id, profile_id, 20), 2);
-    store.push_sample(key, ok_sample(target_id, profile_id, 30), 2);
+    store.push_sample(key, ok_sample(target_id, profile_id, 10), 2, Duration::ZERO);
+    store.push_sample(key, ok_sample(target_id, profile_id, 20), 2, Duration::ZERO);
+    store.push_sample(key, ok_sample(target_id, profile_id, 30), 2, Duration::ZERO);
 
-    let aggregate = store.windowed_aggregate(key, WindowSpec::M1, &SamplingConfig::default(), None);
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
     let total_stats = aggregate
         .by_metric
         .get(&MetricKind::Total)
@@ -165,6 +625,112 @@ fn push_sample_respects_max_points() {
     assert_eq!(total_stats.last, Some(30.0));
 }
 
+fn failing_sample(target_id: Uuid, profile_id: Uuid, message: String) -> ProbeSample {
+    ProbeSample {
+        result: ProbeResult::Err(ProbeError {
+            kind: ProbeErrorKind::ConnectRefused,
+            message: message.into(),
+        }),
+        ..ok_sample(target_id, profile_id, 10)
+    }
+}
+
+#[test]
+fn repeated_error_messages_are_stored_once() {
+    let (target_id, profile_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let message = "x".repeat(1024);
+    let mut succeeding = MetricsStore::new();
+    let mut failing = MetricsStore::new();
+    for _ in 0..500 {
+        succeeding.push_sample(
+            key,
+            ok_sample(target_id, profile_id, 10),
+            500,
+            Duration::ZERO,
+        );
+        // A fresh allocation per sample, as each probe formats its own error
+        failing.push_sample(
+            key,
+            failing_sample(target_id, profile_id, message.clone()),
+            500,
+            Duration::ZERO,
+        );
+    }
+
+    assert_eq!(failing.messages.len(), 1);
+    assert_eq!(
+        failing.approx_bytes(),
+        succeeding.approx_bytes() + message.len()
+    );
+}
+
+#[test]
+fn byte_count_follows_pushes_evictions_and_removals() {
+    let (target_id, profile_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let per_sample = sample_bytes(&ok_sample(target_id, profile_id, 10));
+    let mut store = MetricsStore::new();
+    for _ in 0..100 {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, 10),
+            10,
+            Duration::ZERO,
+        );
+    }
+    assert_eq!(store.approx_bytes(), 10 * per_sample);
+
+    let other = ProfileKey {
+        target_id,
+        profile_id: Uuid::new_v4(),
+    };
+    store.push_sample(
+        other,
+        failing_sample(target_id, other.profile_id, "refused".to_string()),
+        10,
+        Duration::ZERO,
+    );
+    store.remove_key(key);
+    assert_eq!(
+        store.approx_bytes(),
+        store.iter_samples().map(sample_bytes).sum::<usize>() + "refused".len()
+    );
+    store.remove_key(other);
+    assert_eq!(store.approx_bytes(), 0);
+}
+
+#[test]
+fn evicted_error_messages_are_dropped() {
+    let (target_id, profile_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let mut store = MetricsStore::new();
+    for idx in 0..100 {
+        store.push_sample(
+            key,
+            failing_sample(target_id, profile_id, format!("refused #{idx}")),
+            10,
+            Duration::ZERO,
+        );
+    }
+    assert_eq!(store.messages.len(), 10);
+    assert!(store.messages.contains("refused #99"));
+    assert!(!store.messages.contains("refused #89"));
+
+    store.remove_target(target_id);
+    assert!(store.messages.is_empty());
+    assert_eq!(store.approx_bytes(), 0);
+}
+
 #[test]
 fn timeseries_with_clock_uses_fixed_now() {
     let mut store = MetricsStore::new();
@@ -177,16 +743,801 @@ fn timeseries_with_clock_uses_fixed_now() {
 
     let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
     let sample_ts = now - Duration::from_secs(10);
-    store.push_sample(key, ok_sample_at(sample_ts, target_id, profile_id, 120), 16);
+    store.push_sample(
+        key,
+        ok_sample_at(sample_ts, target_id, profile_id, 120),
+        16,
+        Duration::ZERO,
+    );
 
     let points = store.timeseries_with_clock(
         key,
         WindowSpec::M1,
         MetricKind::Total,
         None,
+        None,
         &FixedClock(now),
     );
     assert_eq!(points.len(), 1);
     let (x, _) = points[0];
     assert!((x - 50.0).abs() < 1e-6);
 }
+
+#[test]
+fn stat_timeseries_reduces_each_bucket_to_the_selected_stat() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    // M1 splits into 2s buckets; the first two samples share one
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    for (age_ms, total_ms) in [(10_100, 100), (10_500, 300), (30_000, 50)] {
+        store.push_sample(
+            key,
+            ok_sample_at(
+                now - Duration::from_millis(age_ms),
+                target_id,
+                profile_id,
+                total_ms,
+            ),
+            16,
+            Duration::ZERO,
+        );
+    }
+
+    let points = store.stat_timeseries_with_clock(
+        key,
+        WindowSpec::M1,
+        MetricKind::Total,
+        StatColumn::Max,
+        None,
+        &FixedClock(now),
+    );
+    assert_eq!(points, vec![(31.0, 50.0), (49.0, 300.0)]);
+}
+
+#[test]
+fn recent_phases_keeps_newest_successes_oldest_first() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    for (age, total_ms) in [(90, 40), (30, 50), (20, 60), (10, 70)] {
+        store.push_sample(
+            key,
+            ok_sample_at(
+                now - Duration::from_secs(age),
+                target_id,
+                profile_id,
+                total_ms,
+            ),
+            16,
+            Duration::ZERO,
+        );
+    }
+
+    let phases = store.recent_phases_with_clock(key, WindowSpec::M1, 2, &FixedClock(now));
+    assert_eq!(phases.len(), 2);
+    assert_eq!(phases[0].ts, now - Duration::from_secs(20));
+    // dns 2 + connect 5 + tls 8 + ttfb 12 + download (70 - 12)
+    assert_eq!(phases[1].phases_ms, [2.0, 5.0, 8.0, 12.0, 58.0]);
+    assert_eq!(phases[1].total_ms(), 85.0);
+}
+
+#[test]
+fn jitter_is_recorded_per_sample_and_charted() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 100),
+        16,
+        Duration::ZERO,
+    );
+    let mut failed = error_sample(ProbeErrorKind::HttpTimeout);
+    failed.target_id = target_id;
+    failed.profile_id = profile_id;
+    store.push_sample(key, failed, 16, Duration::ZERO);
+    for total_ms in [130, 110] {
+        store.push_sample(
+            key,
+            ok_sample(target_id, profile_id, total_ms),
+            16,
+            Duration::ZERO,
+        );
+    }
+
+    // Failures are skipped: 130 is compared with 100, then 110 with 130
+    let series = store.timeseries(key, WindowSpec::M1, MetricKind::Jitter, None, None);
+    let values: Vec<f64> = series.iter().map(|(_, value)| value.round()).collect();
+    assert_eq!(values, vec![30.0, 20.0]);
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+    let jitter = aggregate
+        .by_metric
+        .get(&MetricKind::Jitter)
+        .expect("jitter");
+    assert_eq!(jitter.n, 2);
+    assert_eq!(jitter.mean.map(f64::round), Some(25.0));
+}
+
+#[test]
+fn ping_samples_only_report_rtt_and_total() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    for total_ms in [10, 14] {
+        let mut sample = ok_sample(target_id, profile_id, total_ms);
+        sample.ping = Some(PingMode::Icmp);
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &PLAIN,
+        None,
+    );
+    let mean = |metric: MetricKind| {
+        aggregate
+            .by_metric
+            .get(&metric)
+            .and_then(|stats| stats.mean)
+    };
+    assert_eq!(mean(MetricKind::Rtt), Some(12.0));
+    assert_eq!(mean(MetricKind::Total), Some(12.0));
+    assert_eq!(mean(MetricKind::Jitter), Some(4.0));
+    assert_eq!(mean(MetricKind::Dns), None);
+    assert_eq!(mean(MetricKind::Ttfb), None);
+    assert_eq!(mean(MetricKind::ResponseBytes), None);
+    assert!(store.recent_phases(key, WindowSpec::M1, 4).is_empty());
+}
+
+#[test]
+fn spikes_flag_values_far_above_p99() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    for idx in 0..200u64 {
+        let ts = now - Duration::from_millis(50_000 - idx * 200);
+        store.push_sample(
+            key,
+            ok_sample_at(ts, target_id, profile_id, 20 + idx % 3),
+            1024,
+            Duration::ZERO,
+        );
+    }
+    let spike_ts = now - Duration::from_secs(1);
+    store.push_sample(
+        key,
+        ok_sample_at(spike_ts, target_id, profile_id, 500),
+        1024,
+        Duration::ZERO,
+    );
+
+    let mut sampling = SamplingConfig::default();
+    sampling.spikes.absolute_ms = None;
+    let spikes = store.spikes_with_clock(
+        key,
+        WindowSpec::M1,
+        MetricKind::Total,
+        &sampling,
+        &PLAIN,
+        &FixedClock(now),
+    );
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].ts, spike_ts);
+    assert_eq!(spikes[0].metric, MetricKind::Total);
+    assert_eq!(spikes[0].value, 500.0);
+
+    // Non-latency metrics are never flagged
+    assert!(
+        store
+            .spikes_with_clock(
+                key,
+                WindowSpec::M1,
+                MetricKind::ResponseBytes,
+                &sampling,
+                &PLAIN,
+                &FixedClock(now),
+            )
+            .is_empty()
+    );
+}
+
+#[test]
+fn spikes_respect_absolute_ceiling() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    for (age, total_ms) in [(30, 40), (20, 150), (10, 45)] {
+        store.push_sample(
+            key,
+            ok_sample_at(
+                now - Duration::from_secs(age),
+                target_id,
+                profile_id,
+                total_ms,
+            ),
+            16,
+            Duration::ZERO,
+        );
+    }
+
+    // Too few samples for the p99 rule to fire; the ceiling still does
+    let mut sampling = SamplingConfig::default();
+    sampling.spikes.absolute_ms = Some(100.0);
+    let spikes = store.spikes_with_clock(
+        key,
+        WindowSpec::M1,
+        MetricKind::Total,
+        &sampling,
+        &PLAIN,
+        &FixedClock(now),
+    );
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].value, 150.0);
+}
+
+#[test]
+fn slo_counts_with_clock_counts_fast_successes_only() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    let recent = now - Duration::from_secs(10);
+    store.push_sample(
+        key,
+        ok_sample_at(recent, target_id, profile_id, 100),
+        16,
+        Duration::ZERO,
+    );
+    store.push_sample(
+        key,
+        ok_sample_at(recent, target_id, profile_id, 900),
+        16,
+        Duration::ZERO,
+    );
+    let mut failed = error_sample(ProbeErrorKind::ConnectRefused);
+    failed.ts = recent;
+    store.push_sample(key, failed, 16, Duration::ZERO);
+    let stale = now - Duration::from_secs(120);
+    store.push_sample(
+        key,
+        ok_sample_at(stale, target_id, profile_id, 100),
+        16,
+        Duration::ZERO,
+    );
+
+    let clock = FixedClock(now);
+    let counts = store.slo_counts_with_clock(key, WindowSpec::M1, Some(500.0), &clock);
+    assert_eq!(counts.total, 3);
+    assert_eq!(counts.good, 1);
+
+    let without_budget = store.slo_counts_with_clock(key, WindowSpec::M1, None, &clock);
+    assert_eq!(without_budget.good, 2);
+}
+
+#[test]
+fn slo_status_reports_negative_budget_when_exceeded() {
+    let counts = crate::metrics::SloCounts {
+        total: 1000,
+        good: 985,
+    };
+    let status = counts.status(99.5);
+    assert_eq!(status.budget_allowed, 5);
+    assert_eq!(status.budget_remaining, -10);
+    let availability = status.availability_pct.expect("availability");
+    assert!((availability - 98.5).abs() < 1e-9);
+
+    let empty = crate::metrics::SloCounts::default().status(99.5);
+    assert_eq!(empty.availability_pct, None);
+    assert_eq!(empty.budget_remaining, 0);
+}
+
+#[test]
+fn repeated_aggregate_without_new_samples_hits_cache() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let sampling = SamplingConfig::default();
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    store.push_sample(
+        key,
+        ok_sample_at(now - Duration::from_secs(30), target_id, profile_id, 120),
+        16,
+        Duration::ZERO,
+    );
+
+    let clock = FixedClock(now);
+    let first =
+        store.windowed_aggregate_with_clock(key, WindowSpec::M1, &sampling, &PLAIN, None, &clock);
+    for _ in 0..1_000 {
+        store.windowed_aggregate_with_clock(key, WindowSpec::M1, &sampling, &PLAIN, None, &clock);
+    }
+    assert_eq!(store.aggregate_recomputations(), 1);
+    let cached =
+        store.windowed_aggregate_with_clock(key, WindowSpec::M1, &sampling, &PLAIN, None, &clock);
+    assert_eq!(
+        cached.by_metric[&MetricKind::Total].p50,
+        first.by_metric[&MetricKind::Total].p50
+    );
+
+    store.push_sample(
+        key,
+        ok_sample_at(now - Duration::from_secs(5), target_id, profile_id, 240),
+        16,
+        Duration::ZERO,
+    );
+    let refreshed =
+        store.windowed_aggregate_with_clock(key, WindowSpec::M1, &sampling, &PLAIN, None, &clock);
+    assert_eq!(store.aggregate_recomputations(), 2);
+    assert_eq!(refreshed.by_metric[&MetricKind::Total].n, 2);
+}
+
+#[test]
+fn cached_aggregate_expires_when_oldest_sample_leaves_window() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let sampling = SamplingConfig::default();
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+    store.push_sample(
+        key,
+        ok_sample_at(now - Duration::from_secs(50), target_id, profile_id, 120),
+        16,
+        Duration::ZERO,
+    );
+
+    let before = store.windowed_aggregate_with_clock(
+        key,
+        WindowSpec::M1,
+        &sampling,
+        &PLAIN,
+        None,
+        &FixedClock(now),
+    );
+    assert_eq!(before.by_metric[&MetricKind::Total].n, 1);
+
+    let later = store.windowed_aggregate_with_clock(
+        key,
+        WindowSpec::M1,
+        &sampling,
+        &PLAIN,
+        None,
+        &FixedClock(now + Duration::from_secs(5)),
+    );
+    assert_eq!(later.by_metric[&MetricKind::Total].n, 1);
+    assert_eq!(store.aggregate_recomputations(), 1);
+
+    let rolled = store.windowed_aggregate_with_clock(
+        key,
+        WindowSpec::M1,
+        &sampling,
+        &PLAIN,
+        None,
+        &FixedClock(now + Duration::from_secs(15)),
+    );
+    assert_eq!(rolled.by_metric[&MetricKind::Total].n, 0);
+    assert_eq!(store.aggregate_recomputations(), 2);
+}
+
+#[test]
+fn retention_keeps_window_covered_beyond_point_cap() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let sampling = SamplingConfig::default();
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000);
+    let clock = FixedClock(now);
+
+    // 120 samples one second apart with a cap of 16: retention of 5m keeps them all
+    for age in (0..120u64).rev() {
+        let sample = ok_sample_at(now - Duration::from_secs(age), target_id, profile_id, 50);
+        store.push_sample(key, sample, 16, Duration::from_secs(300));
+    }
+    let aggregate =
+        store.windowed_aggregate_with_clock(key, WindowSpec::M5, &sampling, &PLAIN, None, &clock);
+    assert_eq!(aggregate.by_metric[&MetricKind::Total].n, 120);
+    assert!(aggregate.fully_covered);
+
+    // With only 30s of retention the cap evicts samples the 1m window still needs
+    let mut store = MetricsStore::new();
+    for age in (0..120u64).rev() {
+        let sample = ok_sample_at(now - Duration::from_secs(age), target_id, profile_id, 50);
+        store.push_sample(key, sample, 16, Duration::from_secs(30));
+    }
+    let aggregate =
+        store.windowed_aggregate_with_clock(key, WindowSpec::M1, &sampling, &PLAIN, None, &clock);
+    assert_eq!(aggregate.by_metric[&MetricKind::Total].n, 31);
+    assert!(!aggregate.fully_covered);
+}
+
+#[test]
+fn long_windows_leave_burst_samples_out_when_configured() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    store.push_sample(
+        key,
+        ok_sample(target_id, profile_id, 50),
+        64,
+        Duration::ZERO,
+    );
+    for _ in 0..4 {
+        let mut burst = ok_sample(target_id, profile_id, 900);
+        burst.burst = true;
+        store.push_sample(key, burst, 64, Duration::ZERO);
+    }
+
+    let sampling = SamplingConfig::default();
+    let total_count = |window: WindowSpec, view: &AggregateView| {
+        store
+            .windowed_aggregate(key, window, &sampling, view, None)
+            .by_metric
+            .get(&MetricKind::Total)
+            .map_or(0, |stats| stats.n)
+    };
+    assert_eq!(total_count(WindowSpec::H1, &PLAIN), 5);
+    let view = AggregateView {
+        exclude_burst_from_long_windows: true,
+        ..PLAIN
+    };
+    assert_eq!(total_count(WindowSpec::H1, &view), 1);
+    assert_eq!(total_count(WindowSpec::M15, &view), 1);
+    assert_eq!(total_count(WindowSpec::M5, &view), 5);
+}
+
+#[test]
+fn histogram_matrix_buckets_time_and_log_spaced_values() {
+    let mut store = MetricsStore::new();
+    let target_id = Uuid::new_v4();
+    let profile_id = Uuid::new_v4();
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+    // Cache hits around 10ms and misses around 1s in both halves of the window
+    for (age, total_ms) in [(50, 10), (45, 1000), (10, 10), (5, 1000), (5, 10)] {
+        store.push_sample(
+            key,
+            ok_sample_at(
+                now - Duration::from_secs(age),
+                target_id,
+                profile_id,
+                total_ms,
+            ),
+            16,
+            Duration::ZERO,
+        );
+    }
+
+    let clock = FixedClock(now);
+    let matrix = store
+        .histogram_matrix_with_clock(key, WindowSpec::M1, MetricKind::Total, 2, 2, &clock)
+        .expect("samples in window");
+    assert_eq!(matrix.counts, vec![vec![1, 1], vec![2, 1]]);
+    assert_eq!(matrix.max_count(), 2);
+    assert_eq!(matrix.bounds.len(), 3);
+    assert!((matrix.bounds[1] - 100.0).abs() < 1e-6);
+    assert!(
+        store
+            .histogram_matrix_with_clock(key, WindowSpec::M1, MetricKind::Total, 0, 2, &clock)
+            .is_none()
+    );
+}
+
+/// Pushes one sample per `(age in seconds, failure)` onto a fresh profile,
+/// oldest first; `None` is a success.
+fn push_outcomes(
+    store: &mut MetricsStore,
+    now: SystemTime,
+    outcomes: &[(u64, Option<ProbeErrorKind>)],
+) -> ProfileKey {
+    let (target_id, profile_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let key = ProfileKey {
+        target_id,
+        profile_id,
+    };
+    for &(age, failure) in outcomes {
+        let ts = now - Duration::from_secs(age);
+        let sample = match failure {
+            Some(kind) => {
+                let mut sample = error_sample(kind);
+                (sample.ts, sample.target_id, sample.profile_id) = (ts, target_id, profile_id);
+                sample
+            }
+            None => ok_sample_at(ts, target_id, profile_id, 40),
+        };
+        store.push_sample(key, sample, 64, Duration::ZERO);
+    }
+    key
+}
+
+#[test]
+fn back_to_back_failures_are_one_outage_named_after_the_commonest_error() {
+    let mut store = MetricsStore::new();
+    let now = SystemTime::now();
+    let refused = Some(ProbeErrorKind::ConnectRefused);
+    let timeout = Some(ProbeErrorKind::ConnectTimeout);
+    let key = push_outcomes(
+        &mut store,
+        now,
+        &[
+            (50, None),
+            (45, timeout),
+            (40, refused),
+            (35, refused),
+            (30, timeout),
+            (25, refused),
+            (20, None),
+            (15, None),
+        ],
+    );
+
+    let outages = store.outages_with_clock(key, WindowSpec::M1, &FixedClock(now));
+    assert_eq!(outages.len(), 1);
+    let outage = outages[0];
+    assert_eq!(outage.start, now - Duration::from_secs(45));
+    assert_eq!(outage.end, Some(now - Duration::from_secs(20)));
+    assert_eq!(outage.probe_count, 5);
+    assert_eq!(outage.dominant_error, ProbeErrorKind::ConnectRefused);
+    assert_eq!(outage.duration(now), Duration::from_secs(25));
+
+    // A tie goes to the kind seen first
+    let key = push_outcomes(&mut store, now, &[(30, timeout), (25, refused), (20, None)]);
+    let outages = store.outages_with_clock(key, WindowSpec::M1, &FixedClock(now));
+    assert_eq!(outages[0].dominant_error, ProbeErrorKind::ConnectTimeout);
+}
+
+#[test]
+fn single_failed_probes_are_short_outages_of_their_own() {
+    let mut store = MetricsStore::new();
+    let now = SystemTime::now();
+    let blip = Some(ProbeErrorKind::HttpTimeout);
+    let key = push_outcomes(
+        &mut store,
+        now,
+        &[
+            (40, None),
+            (35, blip),
+            (30, None),
+            (25, None),
+            (20, blip),
+            (10, None),
+        ],
+    );
+
+    let outages = store.outages_with_clock(key, WindowSpec::M1, &FixedClock(now));
+    let shape: Vec<(u32, Duration)> = outages
+        .iter()
+        .map(|outage| (outage.probe_count, outage.duration(now)))
+        .collect();
+    assert_eq!(
+        shape,
+        vec![(1, Duration::from_secs(5)), (1, Duration::from_secs(10))]
+    );
+
+    let summary = OutageSummary::from_outages(&outages, now);
+    assert_eq!(summary.count, 2);
+    assert_eq!(summary.mttr, Some(Duration::from_millis(7500)));
+    assert_eq!(summary.longest, Some(Duration::from_secs(10)));
+}
+
+#[test]
+fn an_outage_still_in_progress_counts_up_to_now_across_the_window_edge() {
+    let mut store = MetricsStore::new();
+    let now = SystemTime::now();
+    let down = Some(ProbeErrorKind::DnsNxDomain);
+    let key = push_outcomes(
+        &mut store,
+        now,
+        &[
+            // Over before the one-minute window starts
+            (150, down),
+            (120, None),
+            // Started before the window and still failing
+            (90, down),
+            (45, down),
+            (5, down),
+        ],
+    );
+
+    let outages = store.outages_with_clock(key, WindowSpec::M1, &FixedClock(now));
+    assert_eq!(outages.len(), 1);
+    assert_eq!(outages[0].start, now - Duration::from_secs(90));
+    assert_eq!(outages[0].end, None);
+    assert_eq!(outages[0].probe_count, 3);
+
+    let summary = OutageSummary::from_outages(&outages, now);
+    assert_eq!(summary.mttr, None);
+    assert_eq!(summary.longest, Some(Duration::from_secs(90)));
+
+    let outages = store.outages_with_clock(key, WindowSpec::M5, &FixedClock(now));
+    assert_eq!(outages.len(), 2);
+}
+
+#[test]
+fn merged_aggregate_matches_one_profile_holding_every_sample() {
+    let mut store = MetricsStore::new();
+    let now = SystemTime::now();
+    let sampling = SamplingConfig::default();
+    let key = || ProfileKey {
+        target_id: Uuid::new_v4(),
+        profile_id: Uuid::new_v4(),
+    };
+    let (fast, slow, union) = (key(), key(), key());
+    // A fast endpoint and a slow, flaky one
+    let members = [
+        (
+            fast,
+            vec![(10, Some(20)), (8, Some(25)), (6, Some(30)), (4, Some(35))],
+        ),
+        (slow, vec![(9, Some(400)), (7, None), (5, Some(900))]),
+    ];
+    for (member, outcomes) in &members {
+        for &(age, total_ms) in outcomes {
+            let ts = now - Duration::from_secs(age);
+            for target in [*member, union] {
+                let sample = match total_ms {
+                    Some(total_ms) => {
+                        ok_sample_at(ts, target.target_id, target.profile_id, total_ms)
+                    }
+                    None => {
+                        let mut sample = error_sample(ProbeErrorKind::ConnectRefused);
+                        (sample.ts, sample.target_id, sample.profile_id) =
+                            (ts, target.target_id, target.profile_id);
+                        sample
+                    }
+                };
+                store.push_sample(target, sample, 64, Duration::ZERO);
+            }
+        }
+    }
+
+    let source = |key| AggregateSource {
+        key,
+        window: WindowSpec::M1,
+        sampling: &sampling,
+        failure_latency: None,
+    };
+    let merged = store
+        .merged_aggregate(&[source(fast), source(slow)], &PLAIN)
+        .unwrap();
+    let expected = store.windowed_aggregate(union, WindowSpec::M1, &sampling, &PLAIN, None);
+    for metric in [
+        MetricKind::Total,
+        MetricKind::Ttfb,
+        MetricKind::ProbeLossRate,
+    ] {
+        let (merged, expected) = (&merged.by_metric[&metric], &expected.by_metric[&metric]);
+        assert_eq!(merged.n, expected.n, "{metric:?}");
+        assert_eq!(merged.p50, expected.p50, "{metric:?}");
+        assert_eq!(merged.p99, expected.p99, "{metric:?}");
+        assert_eq!(merged.mean, expected.mean, "{metric:?}");
+    }
+    assert_eq!(merged.error_breakdown, expected.error_breakdown);
+    assert_eq!(merged.status_breakdown, expected.status_breakdown);
+    assert_eq!(merged.status_breakdown.get(&200), Some(&6));
+
+    // Averaging the members' p50s would land between them instead
+    let p50 = |key| {
+        store
+            .windowed_aggregate(key, WindowSpec::M1, &sampling, &PLAIN, None)
+            .by_metric[&MetricKind::Total]
+            .p50
+            .unwrap()
+    };
+    let averaged = (p50(fast) + p50(slow)) / 2.0;
+    let merged_p50 = merged.by_metric[&MetricKind::Total].p50.unwrap();
+    assert!(merged_p50 < 40.0, "{merged_p50}");
+    assert!(averaged > 200.0, "{averaged}");
+}
+
+#[test]
+fn merged_aggregate_reads_each_source_over_its_own_window() {
+    let mut store = MetricsStore::new();
+    let now = SystemTime::now();
+    let sampling = SamplingConfig::default();
+    let key = || ProfileKey {
+        target_id: Uuid::new_v4(),
+        profile_id: Uuid::new_v4(),
+    };
+    let (short, long) = (key(), key());
+    for member in [short, long] {
+        for age in [10, 120] {
+            let sample = ok_sample_at(
+                now - Duration::from_secs(age),
+                member.target_id,
+                member.profile_id,
+                50,
+            );
+            store.push_sample(member, sample, 64, Duration::ZERO);
+        }
+    }
+
+    let merged = store
+        .merged_aggregate(
+            &[
+                AggregateSource {
+                    key: short,
+                    window: WindowSpec::M1,
+                    sampling: &sampling,
+                    failure_latency: None,
+                },
+                AggregateSource {
+                    key: long,
+                    window: WindowSpec::M5,
+                    sampling: &sampling,
+                    failure_latency: None,
+                },
+            ],
+            &PLAIN,
+        )
+        .unwrap();
+    // The 2-minute-old sample only falls inside the longer window
+    assert_eq!(merged.by_metric[&MetricKind::Total].n, 3);
+    assert_eq!(merged.window, WindowSpec::M5);
+    assert!(store.merged_aggregate(&[], &PLAIN).is_none());
+}
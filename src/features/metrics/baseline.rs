@@ -0,0 +1,179 @@
+use crate::config::{ProfileId, WindowSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use super::{MetricKind, MetricStats, WindowedAggregate};
+
+/// Named snapshot of one profile's window statistics, kept to compare later
+/// windows against (e.g. before and after an infrastructure change).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    pub profile_id: ProfileId,
+    pub captured_at: SystemTime,
+    pub window: WindowSpec,
+    pub by_metric: HashMap<MetricKind, MetricStats>,
+}
+
+impl Baseline {
+    pub fn capture(
+        name: String,
+        profile_id: ProfileId,
+        aggregate: &WindowedAggregate,
+        captured_at: SystemTime,
+    ) -> Self {
+        Self {
+            name,
+            profile_id,
+            captured_at,
+            window: aggregate.window,
+            by_metric: aggregate.by_metric.clone(),
+        }
+    }
+}
+
+/// Whether a change moves a metric in its good or bad direction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DeltaTrend {
+    Better,
+    Worse,
+    /// No change, or a metric with no good direction (sizes)
+    Neutral,
+}
+
+/// One statistic now and in the baseline, or of one profile against
+/// another when comparing profiles.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StatDelta {
+    pub current: f64,
+    pub baseline: f64,
+}
+
+impl StatDelta {
+    /// Relative change in percent; `None` when the baseline is zero.
+    pub fn change_pct(&self) -> Option<f64> {
+        (self.baseline != 0.0).then(|| (self.current - self.baseline) / self.baseline.abs() * 100.0)
+    }
+
+    /// Absolute change, in the metric's own unit.
+    pub fn difference(&self) -> f64 {
+        self.current - self.baseline
+    }
+
+    pub fn trend(&self, metric: MetricKind) -> DeltaTrend {
+        let Some(higher_is_better) = metric.higher_is_better() else {
+            return DeltaTrend::Neutral;
+        };
+        if self.current == self.baseline {
+            DeltaTrend::Neutral
+        } else if (self.current > self.baseline) == higher_is_better {
+            DeltaTrend::Better
+        } else {
+            DeltaTrend::Worse
+        }
+    }
+}
+
+/// Current p50/p99 of a metric against a baseline; a side is `None` when
+/// either window lacks that statistic.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeltaStats {
+    pub p50: Option<StatDelta>,
+    pub p99: Option<StatDelta>,
+}
+
+impl DeltaStats {
+    pub fn between(current: &MetricStats, baseline: &MetricStats) -> Self {
+        let pair = |current: Option<f64>, baseline: Option<f64>| {
+            Some(StatDelta {
+                current: current?,
+                baseline: baseline?,
+            })
+        };
+        Self {
+            p50: pair(current.p50, baseline.p50),
+            p99: pair(current.p99, baseline.p99),
+        }
+    }
+}
+
+impl MetricStats {
+    /// p50/p99 of `other` against these statistics, as when comparing a
+    /// second profile with the first.
+    pub fn delta(&self, other: &MetricStats) -> DeltaStats {
+        DeltaStats::between(other, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(p50: Option<f64>, p99: Option<f64>) -> MetricStats {
+        let mut stats = MetricStats::empty();
+        stats.p50 = p50;
+        stats.p99 = p99;
+        stats
+    }
+
+    #[test]
+    fn delta_stats_pairs_percentiles_present_on_both_sides() {
+        let delta =
+            DeltaStats::between(&stats(Some(110.0), Some(300.0)), &stats(Some(100.0), None));
+        let p50 = delta.p50.expect("p50 on both sides");
+        assert_eq!(p50.change_pct(), Some(10.0));
+        assert_eq!(delta.p99, None);
+    }
+
+    #[test]
+    fn trend_follows_metric_direction() {
+        let up = StatDelta {
+            current: 120.0,
+            baseline: 100.0,
+        };
+        assert_eq!(up.trend(MetricKind::Total), DeltaTrend::Worse);
+        assert_eq!(up.trend(MetricKind::GoodputBps), DeltaTrend::Better);
+        assert_eq!(up.trend(MetricKind::ResponseBytes), DeltaTrend::Neutral);
+
+        let zero = StatDelta {
+            current: 3.0,
+            baseline: 0.0,
+        };
+        assert_eq!(zero.change_pct(), None);
+        assert_eq!(zero.trend(MetricKind::Retrans), DeltaTrend::Worse);
+    }
+
+    #[test]
+    fn profile_delta_reads_the_second_profile_against_the_first() {
+        let mut first = stats(Some(100.0), Some(400.0));
+        first.n = 10;
+        let mut second = stats(Some(80.0), Some(500.0));
+        second.n = 10;
+
+        let delta = first.delta(&second);
+        let p50 = delta.p50.expect("p50 on both sides");
+        assert_eq!(p50.difference(), -20.0);
+        assert_eq!(p50.change_pct(), Some(-20.0));
+        assert_eq!(p50.trend(MetricKind::Total), DeltaTrend::Better);
+        let p99 = delta.p99.expect("p99 on both sides");
+        assert_eq!(p99.difference(), 100.0);
+        assert_eq!(p99.trend(MetricKind::Total), DeltaTrend::Worse);
+    }
+
+    #[test]
+    fn baseline_round_trips_through_json() {
+        let baseline = Baseline {
+            name: "before deploy".to_string(),
+            profile_id: ProfileId::new_v4(),
+            captured_at: SystemTime::UNIX_EPOCH,
+            window: WindowSpec::M5,
+            by_metric: HashMap::from([(MetricKind::Total, stats(Some(42.0), Some(90.0)))]),
+        };
+        let json = serde_json::to_string(&baseline).unwrap();
+        let parsed: Baseline = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.profile_id, baseline.profile_id);
+        assert_eq!(parsed.window, WindowSpec::M5);
+        assert_eq!(parsed.by_metric[&MetricKind::Total].p99, Some(90.0));
+    }
+}
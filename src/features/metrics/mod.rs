@@ -1,10 +1,16 @@
 pub mod aggregate;
+mod baseline;
+mod snapshot;
 
 use crate::config::{ProfileId, TargetId, WindowSpec};
-use crate::probe::ProbeErrorKind;
+use crate::probe::{Phase, ProbeErrorKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+pub use baseline::{Baseline, DeltaStats, DeltaTrend, StatDelta};
+pub use snapshot::{SnapshotProfile, format_snapshot};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -22,11 +28,22 @@ pub enum MetricKind {
     Reordering,
     DupAcks,
     ProbeLossRate,
+    /// Scheduled probe slots that passed without a probe, e.g. while the
+    /// machine slept; `n` holds the slots expected in the window
+    MissedProbes,
+    Http4xxRate,
+    Http5xxRate,
     TransportLoss,
     GoodputBps,
     BandwidthUtilization,
+    ResponseBytes,
+    ContentLength,
     Cwnd,
     Ssthresh,
+    /// Share of responses a CDN served from cache, over those reporting it
+    CacheHitRate,
+    /// Share of successful non-priming probes that reused a connection
+    ConnReuseRate,
 }
 
 impl MetricKind {
@@ -42,7 +59,13 @@ impl MetricKind {
             | MetricKind::RttVar
             | MetricKind::Jitter => "ms",
             MetricKind::GoodputBps => "Mbps",
-            MetricKind::BandwidthUtilization | MetricKind::ProbeLossRate => "%",
+            MetricKind::ResponseBytes | MetricKind::ContentLength => "B",
+            MetricKind::BandwidthUtilization
+            | MetricKind::ProbeLossRate
+            | MetricKind::Http4xxRate
+            | MetricKind::Http5xxRate
+            | MetricKind::CacheHitRate
+            | MetricKind::ConnReuseRate => "%",
             _ => "",
         }
     }
@@ -62,11 +85,18 @@ impl MetricKind {
             MetricKind::Reordering,
             MetricKind::DupAcks,
             MetricKind::ProbeLossRate,
+            MetricKind::MissedProbes,
+            MetricKind::Http4xxRate,
+            MetricKind::Http5xxRate,
             MetricKind::TransportLoss,
             MetricKind::GoodputBps,
             MetricKind::BandwidthUtilization,
+            MetricKind::ResponseBytes,
+            MetricKind::ContentLength,
             MetricKind::Cwnd,
             MetricKind::Ssthresh,
+            MetricKind::CacheHitRate,
+            MetricKind::ConnReuseRate,
         ]
     }
 
@@ -85,11 +115,59 @@ impl MetricKind {
             MetricKind::Reordering => "reorder",
             MetricKind::DupAcks => "dupack",
             MetricKind::ProbeLossRate => "probe_loss",
+            MetricKind::MissedProbes => "missed_probes",
+            MetricKind::Http4xxRate => "http_4xx",
+            MetricKind::Http5xxRate => "http_5xx",
             MetricKind::TransportLoss => "transport_loss",
             MetricKind::GoodputBps => "goodput_bps",
             MetricKind::BandwidthUtilization => "utilization",
+            MetricKind::ResponseBytes => "response_bytes",
+            MetricKind::ContentLength => "content_length",
             MetricKind::Cwnd => "cwnd",
             MetricKind::Ssthresh => "ssthresh",
+            MetricKind::CacheHitRate => "cache_hit",
+            MetricKind::ConnReuseRate => "conn_reuse",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        let label = label.to_ascii_lowercase();
+        Self::iter_all()
+            .iter()
+            .copied()
+            .find(|metric| metric.label() == label)
+    }
+
+    /// Ratios computed over every probe in the window rather than per sample.
+    pub fn is_window_rate(self) -> bool {
+        matches!(
+            self,
+            MetricKind::ProbeLossRate
+                | MetricKind::Http4xxRate
+                | MetricKind::Http5xxRate
+                | MetricKind::CacheHitRate
+                | MetricKind::ConnReuseRate
+        )
+    }
+
+    /// Computed over the whole window rather than from each sample: the
+    /// window rates and the missed-slot count.
+    pub fn is_window_metric(self) -> bool {
+        self.is_window_rate() || self == MetricKind::MissedProbes
+    }
+
+    /// Direction in which a change is an improvement; `None` for sizes,
+    /// which are neither better nor worse.
+    pub fn higher_is_better(self) -> Option<bool> {
+        match self {
+            MetricKind::GoodputBps
+            | MetricKind::BandwidthUtilization
+            | MetricKind::Cwnd
+            | MetricKind::Ssthresh
+            | MetricKind::CacheHitRate
+            | MetricKind::ConnReuseRate => Some(true),
+            MetricKind::ResponseBytes | MetricKind::ContentLength => None,
+            _ => Some(false),
         }
     }
 
@@ -109,7 +187,7 @@ impl MetricKind {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MetricStats {
     pub n: u64,
     pub last: Option<f64>,
@@ -119,7 +197,13 @@ pub struct MetricStats {
     pub stddev: Option<f64>,
     pub p50: Option<f64>,
     pub p90: Option<f64>,
+    pub p95: Option<f64>,
     pub p99: Option<f64>,
+    pub p999: Option<f64>,
+    /// Values outside the histogram bounds that were clamped before the
+    /// percentiles were read, which makes those percentiles approximate
+    #[serde(default)]
+    pub clamped: u64,
 }
 
 impl MetricStats {
@@ -133,7 +217,10 @@ impl MetricStats {
             stddev: None,
             p50: None,
             p90: None,
+            p95: None,
             p99: None,
+            p999: None,
+            clamped: 0,
         }
     }
 
@@ -147,26 +234,297 @@ impl MetricStats {
             stddev: value.map(|_| 0.0),
             p50: value,
             p90: value,
+            p95: value,
             p99: value,
+            p999: value,
+            clamped: 0,
+        }
+    }
+}
+
+/// Request phases in the order they happen, as plotted by the breakdown pane.
+pub const PHASE_METRICS: [MetricKind; 5] = [
+    MetricKind::Dns,
+    MetricKind::Connect,
+    MetricKind::Tls,
+    MetricKind::Ttfb,
+    MetricKind::Download,
+];
+
+/// Per-phase durations (ms) of one successful probe, indexed like `PHASE_METRICS`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhaseBreakdown {
+    pub ts: SystemTime,
+    pub phases_ms: [f64; 5],
+}
+
+impl PhaseBreakdown {
+    pub fn total_ms(&self) -> f64 {
+        self.phases_ms.iter().sum()
+    }
+}
+
+/// Sample counts of one metric over a window, split into equal time columns
+/// (oldest first) and log-spaced value rows (lowest first).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistogramMatrix {
+    /// Row boundaries, one more than there are rows, ascending
+    pub bounds: Vec<f64>,
+    /// Indexed `[column][row]`
+    pub counts: Vec<Vec<u32>>,
+}
+
+impl HistogramMatrix {
+    pub fn max_count(&self) -> u32 {
+        self.counts.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+/// A latency sample flagged by the spike thresholds in `SpikeConfig`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpikeEvent {
+    pub ts: SystemTime,
+    pub metric: MetricKind,
+    pub value: f64,
+}
+
+/// A run of failed probes of one profile, from its first failure until the
+/// next success.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutageEvent {
+    /// When the first failed probe was taken
+    pub start: SystemTime,
+    /// When the first successful probe after it was taken; `None` while the
+    /// profile is still failing
+    pub end: Option<SystemTime>,
+    /// Failed probes in the outage
+    pub probe_count: u32,
+    /// Most frequent failure kind; the earliest of them on a tie
+    pub dominant_error: ProbeErrorKind,
+}
+
+impl OutageEvent {
+    /// How long the outage lasted, or has lasted by `now` while still open.
+    pub fn duration(&self, now: SystemTime) -> Duration {
+        self.end
+            .unwrap_or(now)
+            .duration_since(self.start)
+            .unwrap_or_default()
+    }
+}
+
+/// Recovery figures over the outages of a window.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OutageSummary {
+    pub count: usize,
+    /// Mean time to recovery over the outages that ended
+    pub mttr: Option<Duration>,
+    /// Longest outage, counting one still in progress up to now
+    pub longest: Option<Duration>,
+}
+
+impl OutageSummary {
+    pub fn from_outages(outages: &[OutageEvent], now: SystemTime) -> Self {
+        let recovered: Vec<Duration> = outages
+            .iter()
+            .filter(|outage| outage.end.is_some())
+            .map(|outage| outage.duration(now))
+            .collect();
+        Self {
+            count: outages.len(),
+            mttr: (!recovered.is_empty())
+                .then(|| recovered.iter().sum::<Duration>() / recovered.len() as u32),
+            longest: outages.iter().map(|outage| outage.duration(now)).max(),
+        }
+    }
+}
+
+/// A single statistic that can be shown in the metrics table or charted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatColumn {
+    Last,
+    Min,
+    Mean,
+    Max,
+    P50,
+    P90,
+    P95,
+    P99,
+    P999,
+}
+
+impl StatColumn {
+    /// Statistics offered for per-bucket chart plotting, in cycle order.
+    pub const CHARTABLE: [StatColumn; 7] = [
+        StatColumn::P50,
+        StatColumn::P90,
+        StatColumn::P95,
+        StatColumn::P99,
+        StatColumn::P999,
+        StatColumn::Mean,
+        StatColumn::Max,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatColumn::Last => "Last",
+            StatColumn::Min => "Min",
+            StatColumn::Mean => "Mean",
+            StatColumn::Max => "Max",
+            StatColumn::P50 => "P50",
+            StatColumn::P90 => "P90",
+            StatColumn::P95 => "P95",
+            StatColumn::P99 => "P99",
+            StatColumn::P999 => "P99.9",
+        }
+    }
+
+    /// Whether the column is read from the histogram rather than the raw values.
+    pub fn is_percentile(self) -> bool {
+        matches!(
+            self,
+            StatColumn::P50
+                | StatColumn::P90
+                | StatColumn::P95
+                | StatColumn::P99
+                | StatColumn::P999
+        )
+    }
+
+    pub fn select(self, stats: &MetricStats) -> Option<f64> {
+        match self {
+            StatColumn::Last => stats.last,
+            StatColumn::Min => stats.min,
+            StatColumn::Mean => stats.mean,
+            StatColumn::Max => stats.max,
+            StatColumn::P50 => stats.p50,
+            StatColumn::P90 => stats.p90,
+            StatColumn::P95 => stats.p95,
+            StatColumn::P99 => stats.p99,
+            StatColumn::P999 => stats.p999,
+        }
+    }
+}
+
+/// The three statistics shown per metric in the metrics table.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsPreset {
+    #[default]
+    P50P99Mean,
+    P50P90P99,
+    MinMeanMax,
+    LastP99Max,
+}
+
+impl StatsPreset {
+    pub const ALL: [StatsPreset; 4] = [
+        StatsPreset::P50P99Mean,
+        StatsPreset::P50P90P99,
+        StatsPreset::MinMeanMax,
+        StatsPreset::LastP99Max,
+    ];
+
+    pub fn columns(self) -> [StatColumn; 3] {
+        match self {
+            StatsPreset::P50P99Mean => [StatColumn::P50, StatColumn::P99, StatColumn::Mean],
+            StatsPreset::P50P90P99 => [StatColumn::P50, StatColumn::P90, StatColumn::P99],
+            StatsPreset::MinMeanMax => [StatColumn::Min, StatColumn::Mean, StatColumn::Max],
+            StatsPreset::LastP99Max => [StatColumn::Last, StatColumn::P99, StatColumn::Max],
+        }
+    }
+
+    /// Column labels joined with `/`, e.g. `P50/P99/Mean`.
+    pub fn label(self) -> String {
+        self.columns()
+            .iter()
+            .map(|column| column.label())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn next(self) -> Self {
+        let idx = Self::ALL
+            .iter()
+            .position(|preset| *preset == self)
+            .unwrap_or(0);
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+}
+
+/// Probe counts used for SLO/availability accounting over a window.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SloCounts {
+    pub total: u64,
+    /// Probes that succeeded and, when a latency budget is set, finished within it.
+    pub good: u64,
+}
+
+impl SloCounts {
+    pub fn bad(&self) -> u64 {
+        self.total.saturating_sub(self.good)
+    }
+
+    /// Availability as a percentage (0..=100), or `None` with no probes.
+    pub fn availability_pct(&self) -> Option<f64> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(self.good as f64 / self.total as f64 * 100.0)
+        }
+    }
+
+    /// Number of bad probes the SLO tolerates over this window.
+    pub fn allowed_bad(&self, target_pct: f64) -> u64 {
+        let allowed_fraction = (1.0 - target_pct / 100.0).clamp(0.0, 1.0);
+        (self.total as f64 * allowed_fraction).floor() as u64
+    }
+
+    pub fn status(&self, target_pct: f64) -> SloStatus {
+        let allowed = self.allowed_bad(target_pct);
+        SloStatus {
+            target_pct,
+            availability_pct: self.availability_pct(),
+            budget_allowed: allowed,
+            budget_remaining: allowed as i64 - self.bad() as i64,
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SloStatus {
+    pub target_pct: f64,
+    pub availability_pct: Option<f64>,
+    pub budget_allowed: u64,
+    /// Allowed bad probes minus observed bad probes; negative once the budget is blown.
+    pub budget_remaining: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct WindowedAggregate {
     pub window: WindowSpec,
     pub by_metric: HashMap<MetricKind, MetricStats>,
     pub error_breakdown: HashMap<ProbeErrorKind, u64>,
+    /// Probe count per HTTP status code, including successes
+    pub status_breakdown: HashMap<u16, u64>,
+    /// Failed probes per phase they stopped in
+    pub phase_failures: HashMap<Phase, u64>,
+    /// Scheduled slots that passed without a probe
+    pub missed_probes: u64,
+    /// False when evicted samples would have fallen inside the window
+    pub fully_covered: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ProfileAggregate {
     pub target_id: TargetId,
     pub profile_id: ProfileId,
+    pub name: String,
     pub windows: Vec<WindowedAggregate>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct CandidateDomain {
     pub domain: String,
     pub ip: Option<IpAddr>,
@@ -174,10 +532,30 @@ pub struct CandidateDomain {
     pub process_name: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TargetViewModel {
     pub target_id: TargetId,
     pub url: String,
     pub profiles: Vec<ProfileAggregate>,
     pub candidates_hint: Vec<CandidateDomain>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_preset_cycles_and_selects_its_columns() {
+        let mut stats = MetricStats::from_scalar(Some(10.0), 1);
+        stats.p999 = Some(42.0);
+        assert_eq!(StatColumn::P999.select(&stats), Some(42.0));
+
+        let mut preset = StatsPreset::default();
+        assert_eq!(preset.label(), "P50/P99/Mean");
+        for _ in 0..StatsPreset::ALL.len() {
+            preset = preset.next();
+        }
+        assert_eq!(preset, StatsPreset::default());
+        assert_eq!(StatsPreset::LastP99Max.label(), "Last/P99/Max");
+    }
+}
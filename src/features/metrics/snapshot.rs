@@ -0,0 +1,141 @@
+use crate::config::WindowSpec;
+use std::fmt::Write;
+
+use super::{MetricKind, WindowedAggregate};
+
+/// Latency metrics listed per profile, when they have samples.
+const SNAPSHOT_METRICS: [MetricKind; 5] = [
+    MetricKind::Total,
+    MetricKind::Ttfb,
+    MetricKind::Dns,
+    MetricKind::Connect,
+    MetricKind::Tls,
+];
+
+/// One profile's aggregate in a snapshot.
+pub struct SnapshotProfile<'a> {
+    pub name: &'a str,
+    pub aggregate: &'a WindowedAggregate,
+}
+
+/// Plain-text summary of a target over `window`, compact enough to paste
+/// into a chat: p50/p99/mean of the key latency phases, loss, goodput and
+/// the error breakdown of every profile.
+pub fn format_snapshot(url: &str, window: WindowSpec, profiles: &[SnapshotProfile<'_>]) -> String {
+    let mut text = format!("{url} · last {}\n", window.label());
+    for profile in profiles {
+        let aggregate = profile.aggregate;
+        let probes = aggregate
+            .by_metric
+            .get(&MetricKind::ProbeLossRate)
+            .map_or(0, |stats| stats.n);
+        let _ = writeln!(text, "{} ({probes} probes)", profile.name);
+
+        for metric in SNAPSHOT_METRICS {
+            let Some(stats) = aggregate.by_metric.get(&metric).filter(|stats| stats.n > 0) else {
+                continue;
+            };
+            let _ = writeln!(
+                text,
+                "  {:<8} p50 {}  p99 {}  mean {}",
+                metric.label(),
+                format_ms(stats.p50),
+                format_ms(stats.p99),
+                format_ms(stats.mean)
+            );
+        }
+
+        let loss = aggregate
+            .by_metric
+            .get(&MetricKind::ProbeLossRate)
+            .and_then(|stats| stats.mean)
+            .map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate * 100.0));
+        let goodput = aggregate
+            .by_metric
+            .get(&MetricKind::GoodputBps)
+            .and_then(|stats| stats.mean)
+            .map_or_else(
+                || "-".to_string(),
+                |bps| format!("{:.1} Mbps", bps / 1_000_000.0),
+            );
+        let _ = writeln!(text, "  loss {loss}  goodput {goodput}");
+
+        if !aggregate.error_breakdown.is_empty() {
+            let mut errors: Vec<_> = aggregate.error_breakdown.iter().collect();
+            errors.sort_by(|(a_kind, a_count), (b_kind, b_count)| {
+                b_count.cmp(a_count).then_with(|| a_kind.cmp(b_kind))
+            });
+            let errors: Vec<String> = errors
+                .into_iter()
+                .map(|(kind, count)| format!("{} {count}", kind.label()))
+                .collect();
+            let _ = writeln!(text, "  errors: {}", errors.join(", "));
+        }
+    }
+    text
+}
+
+fn format_ms(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |ms| format!("{ms:.1}ms"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::MetricStats;
+    use crate::probe::ProbeErrorKind;
+    use std::collections::HashMap;
+
+    fn latency(p50: f64, p99: f64, mean: f64) -> MetricStats {
+        let mut stats = MetricStats::from_scalar(Some(mean), 10);
+        stats.p50 = Some(p50);
+        stats.p99 = Some(p99);
+        stats
+    }
+
+    #[test]
+    fn snapshot_lists_latency_loss_goodput_and_errors() {
+        let aggregate = WindowedAggregate {
+            window: WindowSpec::M5,
+            by_metric: HashMap::from([
+                (MetricKind::Total, latency(45.24, 120.0, 52.0)),
+                (MetricKind::Ttfb, latency(30.0, 90.5, 33.3)),
+                (MetricKind::Dns, MetricStats::empty()),
+                (
+                    MetricKind::ProbeLossRate,
+                    MetricStats::from_scalar(Some(0.25), 12),
+                ),
+                (
+                    MetricKind::GoodputBps,
+                    MetricStats::from_scalar(Some(12_340_000.0), 9),
+                ),
+            ]),
+            error_breakdown: HashMap::from([
+                (ProbeErrorKind::TlsHandshakeFailed, 1),
+                (ProbeErrorKind::HttpTimeout, 2),
+            ]),
+            status_breakdown: HashMap::new(),
+            phase_failures: HashMap::new(),
+            missed_probes: 0,
+            fully_covered: true,
+        };
+        let text = format_snapshot(
+            "https://api.example.com/",
+            WindowSpec::M5,
+            &[SnapshotProfile {
+                name: "h2+tls13+warm",
+                aggregate: &aggregate,
+            }],
+        );
+
+        assert_eq!(
+            text,
+            "https://api.example.com/ · last 5m\n\
+             h2+tls13+warm (12 probes)\n\
+             \x20 total    p50 45.2ms  p99 120.0ms  mean 52.0ms\n\
+             \x20 ttfb     p50 30.0ms  p99 90.5ms  mean 33.3ms\n\
+             \x20 loss 25.0%  goodput 12.3 Mbps\n\
+             \x20 errors: http_timeout 2, tls_handshake_failed 1\n"
+        );
+    }
+}
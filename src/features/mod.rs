@@ -1,4 +1,6 @@
+pub mod alerts;
 pub mod app;
 pub mod metrics;
 pub mod probe;
+pub mod session;
 pub mod ui;
@@ -0,0 +1,286 @@
+use crate::config::{
+    ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig, TlsVersion,
+};
+use std::f64::consts::TAU;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, SystemTime};
+
+use super::model::{
+    NegotiatedProtocol, Phase, PingMode, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample,
+    TcpInfoSnapshot,
+};
+
+/// Probes per cycle of the slow latency wave.
+const WAVE_PERIOD: f64 = 120.0;
+/// Share of probes that time out.
+const TIMEOUT_RATE: f64 = 0.015;
+/// Share of probes answered with HTTP 500.
+const SERVER_ERROR_RATE: f64 = 0.01;
+/// Share of probes with a latency spike.
+const SPIKE_RATE: f64 = 0.02;
+
+/// Synthetic sample source for `--demo`: a per-profile base latency on a slow
+/// sine wave with noise, occasional spikes, timeouts and HTTP 500s.
+///
+/// The sequence depends only on the seed, the target URL and the profile
+/// name, so a demo run looks the same every time.
+pub struct DemoGenerator {
+    rng: u64,
+    step: u64,
+    base_ms: f64,
+    /// A warm connection has been set up; later warm probes skip the handshakes
+    connected: bool,
+    remote: SocketAddr,
+}
+
+impl DemoGenerator {
+    pub fn new(seed: u64, target: &TargetConfig, profile: &ProfileConfig) -> Self {
+        let mix = seed ^ fnv1a(target.url.as_str()) ^ fnv1a(&profile.name).rotate_left(17);
+        let port = target.url.port_or_known_default().unwrap_or(443);
+        Self {
+            rng: splitmix64(mix) | 1,
+            step: 0,
+            base_ms: 20.0 + (mix % 180) as f64,
+            connected: false,
+            // Documentation range (TEST-NET-1), never a real host
+            remote: SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(192, 0, 2, (mix % 250) as u8 + 1)),
+                port,
+            ),
+        }
+    }
+
+    pub fn next_sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        ts: SystemTime,
+    ) -> ProbeSample {
+        self.step += 1;
+        let wave = 1.0 + 0.35 * (TAU * self.step as f64 / WAVE_PERIOD).sin();
+        let noise = 0.85 + 0.3 * self.next_unit();
+        let spike = if self.next_unit() < SPIKE_RATE {
+            3.0 + 3.0 * self.next_unit()
+        } else {
+            1.0
+        };
+        let total_ms = self.base_ms * wave * noise * spike;
+        let outcome = self.next_unit();
+        let ms = |value: f64| Duration::from_secs_f64(value.max(0.0) / 1000.0);
+
+        let mut sample = ProbeSample {
+            ts,
+            target_id: target.id,
+            profile_id: profile.id,
+            result: ProbeResult::Ok,
+            http_status: None,
+            negotiated: NegotiatedProtocol {
+                alpn: None,
+                tls_version: None,
+                tls_version_configured: false,
+                cipher: None,
+            },
+            t_dns: None,
+            t_connect: Duration::ZERO,
+            t_tls: None,
+            t_ttfb: Duration::ZERO,
+            t_download: Duration::ZERO,
+            t_total: ms(total_ms),
+            downloaded_bytes: 0,
+            content_length: None,
+            local: None,
+            remote: Some(self.remote),
+            resolved_ips: Vec::new(),
+            tcp_info: None,
+            ebpf: None,
+            cert: None,
+            ping: None,
+            burst: false,
+            priming: false,
+            truncated: false,
+            new_connections: None,
+            insecure: false,
+            response_headers: Vec::new(),
+            endpoint: None,
+            missed_slots: 0,
+            deferred_slots: 0,
+            limiter_wait: None,
+            since_last_probe: None,
+            tcp_info_errno: None,
+            http_version_fallback: false,
+            failed_phase: None,
+            jitter_ms: None,
+        };
+
+        if profile.method == ProbeMethod::Ping {
+            // A round trip is roughly what a connect costs
+            sample.t_total = ms(total_ms * 0.3);
+            sample.t_connect = sample.t_total;
+            sample.ping = Some(PingMode::Icmp);
+            return sample;
+        }
+
+        if outcome < TIMEOUT_RATE {
+            self.connected = false;
+            sample.t_total = target.timeout_total;
+            sample.failed_phase = Some(Phase::Ttfb);
+            sample.result = ProbeResult::Err(ProbeError {
+                kind: ProbeErrorKind::HttpTimeout,
+                message: format!(
+                    "demo: no response within {}ms",
+                    target.timeout_total.as_millis()
+                )
+                .into(),
+            });
+            return sample;
+        }
+
+        let https = target.url.scheme() == "https";
+        let handshake = profile.conn_reuse == ConnReusePolicy::Cold || !self.connected;
+        self.connected = profile.conn_reuse == ConnReusePolicy::Warm;
+        let mut spent = 0.0;
+        if handshake {
+            if target.dns_enabled {
+                sample.t_dns = Some(ms(total_ms * 0.08));
+                spent += total_ms * 0.08;
+            }
+            sample.t_connect = ms(total_ms * 0.17);
+            spent += total_ms * 0.17;
+            if https {
+                sample.t_tls = Some(ms(total_ms * 0.25));
+                spent += total_ms * 0.25;
+            }
+        }
+        let remainder = total_ms - spent;
+        sample.t_ttfb = ms(remainder * 0.6);
+        sample.t_download = ms(remainder * 0.4);
+        if https {
+            sample.negotiated = NegotiatedProtocol {
+                alpn: Some(match profile.http {
+                    HttpVersion::H1 => "http/1.1".to_string(),
+                    HttpVersion::H1_0 => "http/1.0".to_string(),
+                    HttpVersion::H2 | HttpVersion::H2PriorKnowledge => "h2".to_string(),
+                }),
+                tls_version: Some(profile.tls.protocol_name().to_string()),
+                tls_version_configured: true,
+                cipher: Some(match profile.tls {
+                    TlsVersion::Tls12 => "ECDHE-RSA-AES128-GCM-SHA256".to_string(),
+                    TlsVersion::Tls13 => "TLS_AES_128_GCM_SHA256".to_string(),
+                }),
+            };
+        }
+        let rtt_us = (self.base_ms * 0.3 * 1000.0) as u32;
+        sample.tcp_info = Some(TcpInfoSnapshot {
+            rtt_us: Some(rtt_us),
+            rttvar_us: Some(rtt_us / 10),
+            total_retrans: Some(u32::from(self.next_unit() < 0.05)),
+            lost: Some(0),
+            reordering: Some(3),
+            snd_cwnd: Some(10 + (self.next_unit() * 30.0) as u32),
+            snd_ssthresh: Some(64),
+        });
+
+        if outcome < TIMEOUT_RATE + SERVER_ERROR_RATE {
+            sample.http_status = Some(500);
+            sample.downloaded_bytes = 128;
+            sample.result = ProbeResult::Err(ProbeError {
+                kind: ProbeErrorKind::HttpStatusError,
+                message: "demo: HTTP 500".into(),
+            });
+        } else {
+            sample.http_status = Some(200);
+            let bytes = if profile.method == ProbeMethod::Head {
+                0
+            } else {
+                u64::from(profile.max_read_bytes)
+            };
+            sample.downloaded_bytes = bytes;
+            sample.content_length = Some(bytes);
+        }
+        sample
+    }
+
+    /// Uniform value in `[0.0, 1.0)`.
+    fn next_unit(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use url::Url;
+
+    fn samples(seed: u64, count: usize) -> Vec<ProbeSample> {
+        let target = TargetConfig::new(
+            Url::parse("https://demo.example.com").unwrap(),
+            default_profiles(),
+        );
+        let profile = &target.profiles[0];
+        let mut generator = DemoGenerator::new(seed, &target, profile);
+        (0..count)
+            .map(|_| generator.next_sample(&target, profile, SystemTime::UNIX_EPOCH))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let totals = |seed| -> Vec<Duration> {
+            samples(seed, 50)
+                .iter()
+                .map(|sample| sample.t_total)
+                .collect()
+        };
+        assert_eq!(totals(7), totals(7));
+        assert_ne!(totals(7), totals(8));
+    }
+
+    #[test]
+    fn sequence_mixes_successes_timeouts_and_server_errors() {
+        let all = samples(1, 2_000);
+        let failed = |kind| {
+            all.iter()
+                .filter(
+                    |sample| matches!(&sample.result, ProbeResult::Err(err) if err.kind == kind),
+                )
+                .count()
+        };
+        let timeouts = failed(ProbeErrorKind::HttpTimeout);
+        let server_errors = failed(ProbeErrorKind::HttpStatusError);
+        assert!((10..60).contains(&timeouts), "{timeouts} timeouts");
+        assert!((5..45).contains(&server_errors), "{server_errors} 500s");
+
+        for sample in &all {
+            if let ProbeResult::Ok = sample.result {
+                assert_eq!(sample.http_status, Some(200));
+                let phases = sample.t_dns.unwrap_or_default()
+                    + sample.t_connect
+                    + sample.t_tls.unwrap_or_default()
+                    + sample.t_ttfb
+                    + sample.t_download;
+                assert!(phases.abs_diff(sample.t_total) < Duration::from_millis(1));
+                assert!(sample.tcp_info.is_some());
+            }
+        }
+        // The warm profile only pays for handshakes on its first connection
+        assert!(all[0].t_tls.is_some());
+        assert!(all[1..].iter().any(|sample| sample.t_tls.is_none()));
+    }
+}
@@ -0,0 +1,337 @@
+//! Kernel-side TCP counters for `--ebpf minimal`.
+//!
+//! Two tracepoint programs from the `httpulse-ebpf` crate, built by the build
+//! script and loaded with aya, count retransmits and duplicate ACKs per
+//! connection in a shared map. Workers look up the connection of each probe by
+//! its local/remote address pair and take the counts accumulated since the
+//! previous probe on that connection.
+
+#[cfg(target_os = "linux")]
+mod tracefs;
+
+use crate::config::EbpfMode;
+use crate::probe::EbpfConnStatsDelta;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Size of the per-connection key shared with the programs.
+const KEY_SIZE: usize = 40;
+
+/// Names a prebuilt object to load when the binary embeds no programs.
+#[cfg(target_os = "linux")]
+const OBJECT_ENV: &str = "HTTPULSE_EBPF_OBJECT";
+
+/// The programs as built for this target, or empty unless the build script
+/// was asked to build them and could.
+#[cfg(target_os = "linux")]
+static PROGRAMS: &[u8] = aya::include_bytes_aligned!(concat!(env!("OUT_DIR"), "/httpulse-ebpf.o"));
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum EbpfError {
+    #[error("eBPF is only supported on Linux")]
+    Unsupported,
+    #[error("this build has no eBPF programs (set HTTPULSE_EBPF_OBJECT to a prebuilt object)")]
+    NotBuilt,
+    #[error("tracefs is not mounted (mount -t tracefs nodev /sys/kernel/tracing)")]
+    NoTracefs,
+    #[error("tracepoint {name}: {message}")]
+    Tracepoint { name: &'static str, message: String },
+    #[error("{call}: permission denied (needs root or CAP_BPF and CAP_PERFMON)")]
+    Permission { call: &'static str },
+    #[error("{call}: {message}")]
+    Load { call: &'static str, message: String },
+    #[error("verifier rejected {program}: {log}")]
+    Verifier { program: &'static str, log: String },
+}
+
+#[cfg(target_os = "linux")]
+static COLLECTOR: OnceLock<Collector> = OnceLock::new();
+#[cfg(not(target_os = "linux"))]
+static COLLECTOR: OnceLock<()> = OnceLock::new();
+
+/// Loads and attaches the collector for `mode`. `Full` currently collects the
+/// same counters as `Minimal`; `Off` does nothing.
+pub fn start_ebpf(mode: EbpfMode) -> Result<(), EbpfError> {
+    if mode == EbpfMode::Off || COLLECTOR.get().is_some() {
+        return Ok(());
+    }
+    match load_collector() {
+        Ok(collector) => {
+            info!(%mode, "eBPF collector attached");
+            let _ = COLLECTOR.set(collector);
+            Ok(())
+        }
+        Err(err) => {
+            warn!(%mode, "eBPF collector unavailable: {err}");
+            Err(err)
+        }
+    }
+}
+
+/// Counters of the connection `local` → `remote` since they were last taken;
+/// `None` while no collector is running.
+pub fn take_conn_stats(local: SocketAddr, remote: SocketAddr) -> Option<EbpfConnStatsDelta> {
+    #[cfg(target_os = "linux")]
+    {
+        COLLECTOR.get()?.take(local, remote)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (local, remote);
+        None
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn load_collector() -> Result<(), EbpfError> {
+    Err(EbpfError::Unsupported)
+}
+
+#[cfg(target_os = "linux")]
+fn load_collector() -> Result<Collector, EbpfError> {
+    Collector::load()
+}
+
+/// The programs embedded at build time, else the object named by
+/// [`OBJECT_ENV`].
+#[cfg(target_os = "linux")]
+fn programs() -> Result<std::borrow::Cow<'static, [u8]>, EbpfError> {
+    if !PROGRAMS.is_empty() {
+        return Ok(std::borrow::Cow::Borrowed(PROGRAMS));
+    }
+    let path = std::env::var_os(OBJECT_ENV).ok_or(EbpfError::NotBuilt)?;
+    std::fs::read(&path)
+        .map(std::borrow::Cow::Owned)
+        .map_err(|err| EbpfError::Load {
+            call: "read eBPF object",
+            message: format!("{}: {err}", std::path::Path::new(&path).display()),
+        })
+}
+
+/// Per-connection counters as the programs store them: retransmits and
+/// duplicate ACKs, then the duplicate-ACK detector state.
+#[cfg(target_os = "linux")]
+type ConnCounters = [u32; 4];
+
+#[cfg(target_os = "linux")]
+struct Collector {
+    connections:
+        std::sync::Mutex<aya::maps::HashMap<aya::maps::MapData, [u8; KEY_SIZE], ConnCounters>>,
+    /// Holds the programs attached; dropping it detaches them
+    _programs: aya::Ebpf,
+}
+
+#[cfg(target_os = "linux")]
+impl Collector {
+    fn load() -> Result<Self, EbpfError> {
+        use aya::programs::TracePoint;
+        use tracefs::Tracepoint;
+
+        let programs = programs()?;
+        raise_memlock_limit();
+        let root = tracefs::root()?;
+        let retransmit = Tracepoint::open(&root, "tcp", "tcp_retransmit_skb")?;
+        let tcp_probe = Tracepoint::open(&root, "tcp", "tcp_probe")?;
+        let retransmit_fields = retransmit.offsets(&[
+            ("saddr_v6", 16),
+            ("daddr_v6", 16),
+            ("sport", 2),
+            ("dport", 2),
+        ])?;
+        // Addresses are stored as a sockaddr_in or sockaddr_in6
+        let tcp_probe_fields = tcp_probe.offsets(&[
+            ("family", 2),
+            ("saddr", 28),
+            ("daddr", 28),
+            ("sport", 2),
+            ("dport", 2),
+            ("data_len", 2),
+            ("snd_nxt", 4),
+            ("snd_una", 4),
+        ])?;
+
+        let mut ebpf = aya::EbpfLoader::new()
+            .set_global("RETRANSMIT_FIELDS", &retransmit_fields, true)
+            .set_global("TCP_PROBE_FIELDS", &tcp_probe_fields, true)
+            .load(&programs)
+            .map_err(|err| load_error("load", err))?;
+        for tracepoint in [&retransmit, &tcp_probe] {
+            let name = tracepoint.name;
+            let program: &mut TracePoint = ebpf
+                .program_mut(name)
+                .ok_or(EbpfError::Tracepoint {
+                    name,
+                    message: "no program for it in this build".to_string(),
+                })?
+                .try_into()
+                .map_err(|err| load_error("program", err))?;
+            program.load().map_err(|err| match err {
+                aya::programs::ProgramError::LoadError {
+                    io_error,
+                    verifier_log,
+                } if io_error.raw_os_error() != Some(libc::EPERM) => EbpfError::Verifier {
+                    program: name,
+                    log: verifier_log.to_string(),
+                },
+                err => load_error("BPF_PROG_LOAD", err),
+            })?;
+            program
+                .attach("tcp", name)
+                .map_err(|err| load_error("attach", err))?;
+        }
+        let connections = ebpf
+            .take_map("CONNECTIONS")
+            .ok_or(EbpfError::Load {
+                call: "map",
+                message: "CONNECTIONS missing from the programs".to_string(),
+            })?
+            .try_into()
+            .map_err(|err| load_error("map", err))?;
+        Ok(Self {
+            connections: std::sync::Mutex::new(connections),
+            _programs: ebpf,
+        })
+    }
+
+    /// Reads the counters and zeroes them, keeping the duplicate-ACK detector
+    /// state. A connection with no entry has seen no segments worth counting.
+    fn take(&self, local: SocketAddr, remote: SocketAddr) -> Option<EbpfConnStatsDelta> {
+        use aya::maps::MapError;
+
+        let key = conn_key(local, remote);
+        let mut connections = self.connections.lock().ok()?;
+        let mut counters = match connections.get(&key, 0) {
+            Ok(counters) => counters,
+            Err(MapError::KeyNotFound) => [0; 4],
+            Err(err) => {
+                warn!("eBPF map lookup failed: {err}");
+                return None;
+            }
+        };
+        let delta = EbpfConnStatsDelta {
+            retrans: counters[0],
+            dup_acks: counters[1],
+            conn_events: 0,
+        };
+        if delta.retrans > 0 || delta.dup_acks > 0 {
+            counters[..2].fill(0);
+            // Counts landing between the lookup and this write are lost
+            if let Err(err) = connections.insert(key, counters, BPF_EXIST) {
+                warn!("eBPF map reset failed: {err}");
+            }
+        }
+        Some(delta)
+    }
+}
+
+/// Map update flag: only overwrite an existing element.
+#[cfg(target_os = "linux")]
+const BPF_EXIST: u64 = 2;
+
+/// Maps an aya error to [`EbpfError::Permission`] when the kernel refused
+/// for lack of privileges anywhere down its source chain.
+#[cfg(target_os = "linux")]
+fn load_error(call: &'static str, err: impl std::error::Error + 'static) -> EbpfError {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&err);
+    while let Some(current) = source {
+        if current
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.raw_os_error() == Some(libc::EPERM))
+        {
+            return EbpfError::Permission { call };
+        }
+        source = current.source();
+    }
+    EbpfError::Load {
+        call,
+        message: err.to_string(),
+    }
+}
+
+/// Lifts the locked-memory limit that kernels before 5.11 charge maps and
+/// programs against; failure is left for the load itself to report.
+#[cfg(target_os = "linux")]
+fn raise_memlock_limit() {
+    let limit = libc::rlimit {
+        rlim_cur: libc::RLIM_INFINITY,
+        rlim_max: libc::RLIM_INFINITY,
+    };
+    // SAFETY: `limit` is a valid rlimit for the duration of the call.
+    unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &limit) };
+}
+
+/// Map key of a connection as seen from this host: source and destination
+/// address as IPv6 (IPv4 v4-mapped), then both ports in native byte order.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn conn_key(local: SocketAddr, remote: SocketAddr) -> [u8; KEY_SIZE] {
+    let octets = |addr: SocketAddr| match addr {
+        SocketAddr::V4(v4) => v4.ip().to_ipv6_mapped().octets(),
+        SocketAddr::V6(v6) => v6.ip().octets(),
+    };
+    let mut key = [0u8; KEY_SIZE];
+    key[..16].copy_from_slice(&octets(local));
+    key[16..32].copy_from_slice(&octets(remote));
+    key[32..34].copy_from_slice(&local.port().to_ne_bytes());
+    key[34..36].copy_from_slice(&remote.port().to_ne_bytes());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conn_key_maps_ipv4_and_orders_local_first() {
+        let key = conn_key(
+            "192.0.2.1:40000".parse().unwrap(),
+            "198.51.100.7:443".parse().unwrap(),
+        );
+        assert_eq!(&key[..10], &[0; 10]);
+        assert_eq!(&key[10..16], &[0xff, 0xff, 192, 0, 2, 1]);
+        assert_eq!(&key[26..32], &[0xff, 0xff, 198, 51, 100, 7]);
+        assert_eq!(&key[32..34], &40000u16.to_ne_bytes());
+        assert_eq!(&key[34..36], &443u16.to_ne_bytes());
+        assert_eq!(&key[36..], &[0; 4]);
+    }
+
+    /// Loads both programs through the verifier and checks a loopback
+    /// connection shows up. Skips without privileges or tracefs; any other
+    /// failure, a verifier rejection included, fails the test.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn collector_loads_and_tracks_loopback_connection() {
+        use std::io::{Read, Write};
+
+        let collector = match Collector::load() {
+            Ok(collector) => collector,
+            Err(
+                err @ (EbpfError::Permission { .. } | EbpfError::NoTracefs | EbpfError::NotBuilt),
+            ) => {
+                eprintln!("skipping: {err}");
+                return;
+            }
+            Err(err) => panic!("collector failed to load: {err}"),
+        };
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        client.write_all(b"ping").unwrap();
+        let mut buf = [0u8; 4];
+        server.read_exact(&mut buf).unwrap();
+        server.write_all(b"pong").unwrap();
+        client.read_exact(&mut buf).unwrap();
+
+        let local = client.local_addr().unwrap();
+        let remote = client.peer_addr().unwrap();
+        let tracked = collector
+            .connections
+            .lock()
+            .unwrap()
+            .get(&conn_key(local, remote), 0);
+        assert!(tracked.is_ok(), "{tracked:?}");
+        let delta = collector.take(local, remote).unwrap();
+        assert_eq!(delta.retrans, 0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::EbpfError;
+
+/// Mount points tried in order; newer kernels mount tracefs at the first.
+const TRACEFS_ROOTS: [&str; 2] = ["/sys/kernel/tracing", "/sys/kernel/debug/tracing"];
+
+/// Offset and size of one tracepoint field within the program context.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) struct TraceField {
+    pub offset: u16,
+    pub size: usize,
+}
+
+/// Field layout of a tracepoint, read from tracefs.
+#[derive(Clone, Debug)]
+pub(super) struct Tracepoint {
+    pub name: &'static str,
+    fields: HashMap<String, TraceField>,
+}
+
+impl Tracepoint {
+    pub(super) fn open(root: &Path, category: &str, name: &'static str) -> Result<Self, EbpfError> {
+        let dir = root.join("events").join(category).join(name);
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file)).map_err(|err| match err.kind() {
+                io::ErrorKind::PermissionDenied => EbpfError::Permission { call: "tracefs" },
+                io::ErrorKind::NotFound => EbpfError::Tracepoint {
+                    name,
+                    message: "not available in this kernel".to_string(),
+                },
+                _ => EbpfError::Tracepoint {
+                    name,
+                    message: err.to_string(),
+                },
+            })
+        };
+        Ok(Self {
+            name,
+            fields: parse_format(&read("format")?),
+        })
+    }
+
+    /// Offsets of `fields` in order, for the programs' `*_FIELDS` globals.
+    pub(super) fn offsets<const N: usize>(
+        &self,
+        fields: &[(&str, usize); N],
+    ) -> Result<[u32; N], EbpfError> {
+        let mut offsets = [0; N];
+        for (offset, (field, size)) in offsets.iter_mut().zip(fields) {
+            *offset = self.field(field, *size)?.offset as u32;
+        }
+        Ok(offsets)
+    }
+
+    /// Looks up `field`, insisting on the size the programs were written for.
+    fn field(&self, field: &str, size: usize) -> Result<TraceField, EbpfError> {
+        match self.fields.get(field) {
+            Some(found) if found.size == size => Ok(*found),
+            Some(found) => Err(EbpfError::Tracepoint {
+                name: self.name,
+                message: format!("field {field} is {} bytes, expected {size}", found.size),
+            }),
+            None => Err(EbpfError::Tracepoint {
+                name: self.name,
+                message: format!("missing field {field}"),
+            }),
+        }
+    }
+}
+
+/// First mounted tracefs root.
+pub(super) fn root() -> Result<PathBuf, EbpfError> {
+    for root in TRACEFS_ROOTS {
+        match std::fs::metadata(Path::new(root).join("events")) {
+            Ok(meta) if meta.is_dir() => return Ok(PathBuf::from(root)),
+            Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+                return Err(EbpfError::Permission { call: "tracefs" });
+            }
+            _ => {}
+        }
+    }
+    Err(EbpfError::NoTracefs)
+}
+
+/// Parses the `field:<decl>; offset:<n>; size:<n>;` lines of a format file,
+/// keyed by field name with any array suffix dropped.
+pub(super) fn parse_format(text: &str) -> HashMap<String, TraceField> {
+    let mut fields = HashMap::new();
+    for line in text.lines() {
+        let mut decl = None;
+        let mut offset = None;
+        let mut size = None;
+        for part in line.split(';').map(str::trim) {
+            if let Some(value) = part.strip_prefix("field:") {
+                decl = Some(value);
+            } else if let Some(value) = part.strip_prefix("offset:") {
+                offset = value.parse::<u16>().ok();
+            } else if let Some(value) = part.strip_prefix("size:") {
+                size = value.parse::<usize>().ok();
+            }
+        }
+        let (Some(decl), Some(offset), Some(size)) = (decl, offset, size) else {
+            continue;
+        };
+        let decl = decl.split('[').next().unwrap_or(decl);
+        let Some(name) = decl.split_whitespace().last() else {
+            continue;
+        };
+        fields.insert(name.to_string(), TraceField { offset, size });
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_format_reads_scalar_and_array_fields() {
+        let format = "name: tcp_probe\nID: 1519\nformat:\n\
+            \tfield:unsigned short common_type;\toffset:0;\tsize:2;\tsigned:0;\n\
+            \n\
+            \tfield:__u8 saddr[sizeof(struct sockaddr_in6)];\toffset:8;\tsize:28;\tsigned:0;\n\
+            \tfield:__u16 sport;\toffset:64;\tsize:2;\tsigned:0;\n\
+            \tfield:__u32 snd_una;\toffset:84;\tsize:4;\tsigned:0;\n\
+            \n\
+            print fmt: \"src=%pISpc\", REC->saddr\n";
+        let fields = parse_format(format);
+        assert_eq!(
+            fields.get("saddr"),
+            Some(&TraceField {
+                offset: 8,
+                size: 28
+            })
+        );
+        assert_eq!(
+            fields.get("sport"),
+            Some(&TraceField {
+                offset: 64,
+                size: 2
+            })
+        );
+        assert_eq!(fields.get("snd_una").map(|field| field.offset), Some(84));
+        assert_eq!(fields.len(), 4);
+    }
+}
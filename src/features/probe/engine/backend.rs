@@ -0,0 +1,80 @@
+use super::client::ProbeClient;
+use super::connect::ConnectClient;
+use crate::config::{ProbeBackendKind, ProfileConfig, TargetConfig};
+use crate::probe::ProbeSample;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use thiserror::Error;
+
+/// Why a probe backend could not be created.
+#[derive(Debug, Error)]
+pub enum BackendError {
+    #[error("curl handle: {0}")]
+    Curl(#[from] curl::Error),
+}
+
+/// Produces the sample of one probe for the worker loop. `pinned_ip` is the
+/// address the previous probe reached, used when the target pins its
+/// address instead of resolving.
+pub trait Sampler {
+    fn sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        pinned_ip: Option<IpAddr>,
+    ) -> ProbeSample;
+
+    /// Forgets any connection kept for reuse.
+    fn reset_connection(&mut self) {}
+
+    /// Asks the next probe to collect the server certificate. Samplers that
+    /// do not negotiate TLS ignore it.
+    fn request_cert_info(&mut self) {}
+}
+
+impl Sampler for ProbeClient {
+    fn sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        pinned_ip: Option<IpAddr>,
+    ) -> ProbeSample {
+        self.probe(target, profile, pinned_ip)
+    }
+
+    fn request_cert_info(&mut self) {
+        ProbeClient::request_cert_info(self);
+    }
+}
+
+impl Sampler for ConnectClient {
+    fn sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        pinned_ip: Option<IpAddr>,
+    ) -> ProbeSample {
+        self.probe(target, profile, pinned_ip)
+    }
+}
+
+/// Builds the network sampler configured by `kind`; once `abort` is set the
+/// probe in flight is cut short.
+pub fn create_backend(
+    kind: ProbeBackendKind,
+    abort: Arc<AtomicBool>,
+) -> Result<Box<dyn Sampler>, BackendError> {
+    Ok(match kind {
+        ProbeBackendKind::Curl => {
+            let mut client = ProbeClient::new()?;
+            client.set_abort_flag(abort);
+            Box::new(client)
+        }
+        ProbeBackendKind::TcpConnect => {
+            let mut client = ConnectClient::new();
+            client.set_abort_flag(abort);
+            Box::new(client)
+        }
+    })
+}
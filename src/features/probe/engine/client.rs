@@ -1,21 +1,44 @@
 use super::helpers::{
-    fetch_negotiated_protocol, fetch_tcp_info, is_dns_timeout_message, map_curl_error,
+    TlsSession, TransferMarks, check_body, check_status, failed_phase, fetch_cert_info,
+    fetch_negotiated_protocol, fetch_num_connects, fetch_tcp_info, fetch_tls_session,
+    is_dns_timeout_message, is_http_version_fallback, map_curl_error, parse_header_line,
     parse_socket_addr, saturating_sub,
 };
-use crate::config::{ConnReusePolicy, HttpVersion, ProfileConfig, TargetConfig, TlsVersion};
-use crate::probe::{EbpfConnStatsDelta, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample};
+use super::ping::ping;
+use super::use_proxy_env;
+use crate::config::{
+    AuthConfig, ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig, TlsVersion,
+};
+use crate::probe::{EbpfConnStatsDelta, ProbeResult, ProbeSample};
 use curl::Error as CurlError;
 use curl::easy::{
-    Easy2, Handler, HttpVersion as CurlHttpVersion, IpResolve, List, SslVersion, WriteError,
+    Auth, Easy2, Handler, HttpVersion as CurlHttpVersion, IpResolve, List, SslVersion, WriteError,
 };
 use std::net::IpAddr;
+use std::ptr::NonNull;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::SystemTime;
 
+/// Upper bound on retained body bytes when the read itself is unlimited.
+const UNLIMITED_RETAIN_BYTES: usize = 64 * 1024;
+
 #[derive(Default)]
 struct BodyCollector {
     bytes: u64,
     limit: u64,
     limit_reached: bool,
+    /// Keep the counted bytes for content validation
+    retain: bool,
+    body: Vec<u8>,
+    /// The owning easy handle, for reading the live TLS session
+    handle: Option<NonNull<curl_sys::CURL>>,
+    tls_session: Option<TlsSession>,
+    /// Names of the response headers to record, from the profile
+    capture: Vec<String>,
+    headers: Vec<(String, String)>,
+    /// Set by the owner to cut a transfer short, checked on every progress tick
+    abort: Option<Arc<AtomicBool>>,
 }
 
 impl BodyCollector {
@@ -23,10 +46,34 @@ impl BodyCollector {
         self.bytes = 0;
         self.limit = limit;
         self.limit_reached = false;
+        self.retain = false;
+        self.body.clear();
+        self.tls_session = None;
+        self.capture.clear();
+        self.headers.clear();
     }
 }
 
 impl Handler for BodyCollector {
+    fn header(&mut self, data: &[u8]) -> bool {
+        // A status line starts a new response (after a 1xx or a proxy
+        // CONNECT), so only the final response's headers are kept
+        if data.starts_with(b"HTTP/") {
+            self.headers.clear();
+        } else if let Some(header) = parse_header_line(data, &self.capture) {
+            self.headers.push(header);
+        }
+
+        // Headers only arrive once the handshake is done and the connection is
+        // still open, which is when the session pointer is valid
+        if self.tls_session.is_none()
+            && let Some(handle) = self.handle
+        {
+            self.tls_session = fetch_tls_session(handle.as_ptr());
+        }
+        true
+    }
+
     fn write(&mut self, data: &[u8]) -> Result<usize, WriteError> {
         let len = data.len() as u64;
         let take = if self.limit == 0 {
@@ -36,6 +83,16 @@ impl Handler for BodyCollector {
             len.min(remaining)
         };
 
+        if self.retain {
+            let cap = if self.limit == 0 {
+                UNLIMITED_RETAIN_BYTES
+            } else {
+                self.limit as usize
+            };
+            let keep = (take as usize).min(cap.saturating_sub(self.body.len()));
+            self.body.extend_from_slice(&data[..keep]);
+        }
+
         self.bytes = self.bytes.saturating_add(take);
         if self.limit > 0 && self.bytes >= self.limit {
             self.limit_reached = true;
@@ -45,6 +102,14 @@ impl Handler for BodyCollector {
     }
 
     fn progress(&mut self, _dltotal: f64, dlnow: f64, _ultotal: f64, _ulnow: f64) -> bool {
+        if self
+            .abort
+            .as_ref()
+            .is_some_and(|abort| abort.load(Ordering::Relaxed))
+        {
+            return false;
+        }
+
         if self.limit == 0 {
             return true;
         }
@@ -64,14 +129,32 @@ impl Handler for BodyCollector {
 
 pub struct ProbeClient {
     easy: Easy2<BodyCollector>,
+    /// Collect the server certificate on the next probe
+    cert_requested: bool,
 }
 
 impl ProbeClient {
     pub fn new() -> Result<Self, CurlError> {
         let mut easy = Easy2::new(BodyCollector::default());
+        easy.get_mut().handle = NonNull::new(easy.raw());
         easy.follow_location(false)?;
         easy.accept_encoding("")?;
-        Ok(Self { easy })
+        Ok(Self {
+            easy,
+            cert_requested: false,
+        })
+    }
+
+    /// Asks the next probe to collect the server certificate. Only a fresh
+    /// TLS handshake reports one, so a reused connection yields none.
+    pub fn request_cert_info(&mut self) {
+        self.cert_requested = true;
+    }
+
+    /// Aborts the transfer in flight, within about a second, once `abort`
+    /// is set. The probe then reports an error.
+    pub fn set_abort_flag(&mut self, abort: Arc<AtomicBool>) {
+        self.easy.get_mut().abort = Some(abort);
     }
 
     pub fn probe(
@@ -80,12 +163,16 @@ impl ProbeClient {
         profile: &ProfileConfig,
         resolved_ip: Option<IpAddr>,
     ) -> ProbeSample {
+        if profile.method == ProbeMethod::Ping {
+            return ping(target, profile, resolved_ip);
+        }
+
         let host_is_ip = target
             .url
             .host_str()
             .and_then(|host| host.parse::<IpAddr>().ok())
             .is_some();
-        let retry_on_dns_timeout = target.dns_enabled && !host_is_ip;
+        let retry_on_dns_timeout = target.resolves_dns() && !host_is_ip;
 
         let ip_modes = [IpResolve::Any, IpResolve::V4];
         let ip_modes = if retry_on_dns_timeout {
@@ -94,9 +181,11 @@ impl ProbeClient {
             &ip_modes[..1]
         };
 
+        let collect_cert = std::mem::take(&mut self.cert_requested);
         let mut last_sample = None;
         for (index, ip_mode) in ip_modes.iter().enumerate() {
-            let (sample, dns_timeout) = self.probe_once(target, profile, resolved_ip, *ip_mode);
+            let (sample, dns_timeout) =
+                self.probe_once(target, profile, resolved_ip, *ip_mode, collect_cert);
             let should_retry = dns_timeout && index + 1 < ip_modes.len();
             if should_retry {
                 last_sample = Some(sample);
@@ -114,21 +203,39 @@ impl ProbeClient {
         profile: &ProfileConfig,
         resolved_ip: Option<IpAddr>,
         ip_resolve: IpResolve,
+        collect_cert: bool,
     ) -> (ProbeSample, bool) {
         let start_ts = SystemTime::now();
-        let read_limit = if profile.method == crate::config::ProbeMethod::Head {
+        let read_limit = if profile.method == ProbeMethod::Head {
             0
         } else {
             profile.max_read_bytes as u64
         };
         self.easy.reset();
         self.easy.get_mut().reset(read_limit);
+        self.easy.get_mut().retain =
+            profile.expect_body_contains.is_some() && profile.method != ProbeMethod::Head;
+        self.easy
+            .get_mut()
+            .capture
+            .clone_from(&profile.capture_headers);
         let _ = self.easy.follow_location(false);
         let _ = self.easy.accept_encoding("");
         let _ = self.easy.progress(true);
+        // Zero lifts a cap a previous profile config set
+        let _ = self
+            .easy
+            .max_recv_speed(profile.max_recv_speed.unwrap_or(0));
         let _ = self.easy.ip_resolve(ip_resolve);
+        let _ = self.easy.certinfo(collect_cert);
+        // An empty proxy overrides the environment; curl would otherwise
+        // route through http_proxy/https_proxy without saying so
+        if !use_proxy_env() {
+            let _ = self.easy.proxy("");
+        }
 
         let url = target.url.as_str();
+        let is_https = target.url.scheme() == "https";
         let _ = self.easy.url(url);
         let _ = self.easy.timeout(target.timeout_total);
 
@@ -136,16 +243,33 @@ impl ProbeClient {
             let _ = self.easy.connect_timeout(breakdown.connect);
         }
 
+        // The URL keeps supplying Host and SNI; only the transport moves
+        if let Some(path) = &target.unix_socket {
+            let _ = self.easy.unix_socket_path(Some(path));
+        } else if let Some(connect_to) = &target.connect_to {
+            let mut list = List::new();
+            let _ = list.append(&connect_to.curl_entry());
+            let _ = self.easy.connect_to(list);
+        }
+
         let _ = self.easy.http_version(match profile.http {
             HttpVersion::H1 => CurlHttpVersion::V11,
             HttpVersion::H2 => CurlHttpVersion::V2TLS,
+            HttpVersion::H1_0 => CurlHttpVersion::V10,
+            HttpVersion::H2PriorKnowledge => CurlHttpVersion::V2PriorKnowledge,
         });
 
+        // Plain http never handshakes, so there is no version to pin; the
+        // default also clears a pin left by an earlier https URL
         let tls_version = match profile.tls {
+            _ if !is_https => SslVersion::Default,
             TlsVersion::Tls12 => SslVersion::Tlsv12,
             TlsVersion::Tls13 => SslVersion::Tlsv13,
         };
         let _ = self.easy.ssl_min_max_version(tls_version, tls_version);
+        // Only ever off when the user set `insecure` on the target
+        let _ = self.easy.ssl_verify_peer(!target.insecure);
+        let _ = self.easy.ssl_verify_host(!target.insecure);
 
         match profile.conn_reuse {
             ConnReusePolicy::Warm => {
@@ -158,20 +282,37 @@ impl ProbeClient {
             }
         }
 
-        if profile.method == crate::config::ProbeMethod::Head {
+        if profile.method == ProbeMethod::Head {
             let _ = self.easy.nobody(true);
         } else {
             let _ = self.easy.nobody(false);
         }
 
-        if !profile.headers.is_empty() {
+        let bearer = match &profile.auth {
+            Some(AuthConfig::Bearer { token }) => {
+                Some(format!("Authorization: Bearer {}", token.expose()))
+            }
+            _ => None,
+        };
+        if !profile.headers.is_empty() || bearer.is_some() {
             let mut list = List::new();
             for (name, value) in &profile.headers {
                 let header = format!("{name}: {}", value.expose());
                 let _ = list.append(&header);
             }
+            if let Some(bearer) = &bearer {
+                let _ = list.append(bearer);
+            }
             let _ = self.easy.http_headers(list);
         }
+        // curl builds the Basic header itself, so other schemes can follow
+        if let Some(AuthConfig::Basic { user, password }) = &profile.auth {
+            let _ = self.easy.username(user);
+            let _ = self.easy.password(password.expose());
+            let mut auth = Auth::new();
+            auth.basic(true);
+            let _ = self.easy.http_auth(&auth);
+        }
 
         if !target.dns_enabled
             && let Some(ip) = resolved_ip
@@ -202,13 +343,8 @@ impl ProbeClient {
         }
 
         let http_status = self.easy.response_code().ok().map(|code| code as u16);
-        if let Some(status) = http_status
-            && status >= 400
-        {
-            probe_result = ProbeResult::Err(ProbeError {
-                kind: ProbeErrorKind::HttpStatusError,
-                message: format!("HTTP status {status}"),
-            });
+        if let Some(err) = check_status(profile, http_status) {
+            probe_result = ProbeResult::Err(err);
         }
 
         let mut aborted_by_limit = false;
@@ -221,10 +357,22 @@ impl ProbeClient {
         }
         let dns_timeout = dns_timeout && !aborted_by_limit;
 
+        if matches!(probe_result, ProbeResult::Ok)
+            && self.easy.get_ref().retain
+            && let Some(err) = check_body(profile, &self.easy.get_ref().body)
+        {
+            probe_result = ProbeResult::Err(err);
+        }
+
         let t_total = self.easy.total_time().unwrap_or_default();
         let t_dns_raw = self.easy.namelookup_time().unwrap_or_default();
         let t_connect_raw = self.easy.connect_time().unwrap_or(t_dns_raw);
-        let t_tls_raw = self.easy.appconnect_time().unwrap_or(t_connect_raw);
+        // curl reports a zero appconnect time when there was no handshake
+        let t_tls_raw = if is_https {
+            self.easy.appconnect_time().unwrap_or(t_connect_raw)
+        } else {
+            t_connect_raw
+        };
         let t_ttfb_raw = self.easy.starttransfer_time().unwrap_or(t_tls_raw);
 
         let t_connect = saturating_sub(t_connect_raw, t_dns_raw);
@@ -233,12 +381,22 @@ impl ProbeClient {
         let t_download = saturating_sub(t_total, t_ttfb_raw);
 
         let downloaded_bytes = self.easy.get_ref().bytes;
-
-        let configured_tls = match profile.tls {
-            TlsVersion::Tls12 => "TLSv1.2",
-            TlsVersion::Tls13 => "TLSv1.3",
-        };
-        let negotiated = fetch_negotiated_protocol(self.easy.raw(), configured_tls);
+        // curl reports -1 when the server sent no Content-Length
+        let content_length = self
+            .easy
+            .content_length_download()
+            .ok()
+            .filter(|length| *length >= 0.0)
+            .map(|length| length as u64);
+
+        let configured_tls = is_https.then_some(profile.tls.protocol_name());
+        let negotiated = fetch_negotiated_protocol(
+            self.easy.raw(),
+            self.easy.get_ref().tls_session.as_ref(),
+            configured_tls,
+        );
+        let http_version_fallback =
+            is_http_version_fallback(profile.http, negotiated.alpn.as_deref());
 
         let local = parse_socket_addr(
             self.easy.local_ip().ok().flatten(),
@@ -249,7 +407,34 @@ impl ProbeClient {
             self.easy.primary_port().ok(),
         );
 
-        let tcp_info = fetch_tcp_info(self.easy.raw());
+        let (tcp_info, tcp_info_errno) = match fetch_tcp_info(self.easy.raw()) {
+            Ok(tcp_info) => (tcp_info, None),
+            Err(errno) => (None, Some(errno)),
+        };
+        let new_connections = fetch_num_connects(self.easy.raw());
+        // A failed connect opens no connection either; only a reused one
+        // has a local end without curl counting a new connection
+        let reused = new_connections == Some(0) && local.is_some_and(|addr| addr.port() != 0);
+        // The timings are read on failure too; they show how far it got
+        let failed_phase = match &probe_result {
+            ProbeResult::Err(err) => failed_phase(
+                err.kind,
+                &TransferMarks {
+                    namelookup: t_dns_raw,
+                    connect: t_connect_raw,
+                    appconnect: t_tls_raw,
+                    starttransfer: t_ttfb_raw,
+                },
+                is_https,
+                reused,
+            ),
+            ProbeResult::Ok => None,
+        };
+        let cert = if collect_cert {
+            fetch_cert_info(self.easy.raw())
+        } else {
+            None
+        };
 
         let sample = ProbeSample {
             ts: start_ts,
@@ -258,21 +443,40 @@ impl ProbeClient {
             result: probe_result,
             http_status,
             negotiated,
-            t_dns: if target.dns_enabled {
+            t_dns: if target.resolves_dns() {
                 Some(t_dns_raw)
             } else {
                 None
             },
             t_connect,
-            t_tls: Some(t_tls),
+            t_tls: is_https.then_some(t_tls),
             t_ttfb,
             t_download,
             t_total,
             downloaded_bytes,
+            content_length,
             local,
             remote,
+            resolved_ips: Vec::new(),
             tcp_info,
             ebpf: None::<EbpfConnStatsDelta>,
+            cert,
+            ping: None,
+            burst: false,
+            priming: false,
+            truncated: aborted_by_limit,
+            new_connections,
+            insecure: is_https && target.insecure,
+            response_headers: std::mem::take(&mut self.easy.get_mut().headers),
+            endpoint: None,
+            missed_slots: 0,
+            deferred_slots: 0,
+            limiter_wait: None,
+            since_last_probe: None,
+            tcp_info_errno,
+            failed_phase,
+            http_version_fallback,
+            jitter_ms: None,
         };
 
         (sample, dns_timeout)
@@ -56,3 +56,17 @@ fn body_collector_progress_allows_below_limit() {
     collector.reset(5);
     assert!(collector.progress(0.0, 2.0, 0.0, 0.0));
 }
+
+#[test]
+fn body_collector_retains_at_most_limit_bytes() {
+    let mut collector = BodyCollector::default();
+    collector.reset(5);
+    collector.retain = true;
+    let _ = collector.write(b"abc").expect("write");
+    let _ = collector.write(b"defgh").expect("write");
+    assert_eq!(collector.body, b"abcde");
+
+    collector.reset(5);
+    let _ = collector.write(b"abc").expect("write");
+    assert!(collector.body.is_empty());
+}
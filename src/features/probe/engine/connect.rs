@@ -0,0 +1,288 @@
+use super::helpers::fetch_stream_tcp_info;
+use super::ping::ping;
+use super::resolver::target_destination;
+use crate::config::{ProbeMethod, ProfileConfig, TargetConfig};
+use crate::probe::{
+    NegotiatedProtocol, Phase, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample,
+    TcpInfoSnapshot,
+};
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Backend that stops after the TCP handshake, for targets that should not
+/// receive requests. It resolves the host, connects with
+/// [`TcpStream::connect_timeout`] and hangs up; no TLS is negotiated and no
+/// request is sent, so the status, TLS, TTFB and download stay empty. Every
+/// probe opens a fresh connection.
+#[derive(Default)]
+pub struct ConnectClient {
+    abort: Option<Arc<AtomicBool>>,
+}
+
+/// What one connect attempt measured.
+#[derive(Default)]
+struct Connection {
+    t_dns: Duration,
+    t_connect: Duration,
+    local: Option<SocketAddr>,
+    remote: Option<SocketAddr>,
+    tcp_info: Option<TcpInfoSnapshot>,
+    tcp_info_errno: Option<i32>,
+}
+
+impl ConnectClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips the connect once `abort` is set. A connect already under way
+    /// runs until it completes or the target's timeout elapses.
+    pub fn set_abort_flag(&mut self, abort: Arc<AtomicBool>) {
+        self.abort = Some(abort);
+    }
+
+    pub fn probe(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        resolved_ip: Option<IpAddr>,
+    ) -> ProbeSample {
+        if profile.method == ProbeMethod::Ping {
+            return ping(target, profile, resolved_ip);
+        }
+
+        let start_ts = SystemTime::now();
+        let started = Instant::now();
+        let mut connection = Connection::default();
+        let outcome = if self
+            .abort
+            .as_ref()
+            .is_some_and(|abort| abort.load(Ordering::Relaxed))
+        {
+            Err((
+                Phase::Connect,
+                connect_error(ProbeErrorKind::ConnectOther, "probe aborted".to_string()),
+            ))
+        } else {
+            connect(target, resolved_ip, &mut connection)
+        };
+        let t_total = started.elapsed();
+        let (result, failed_phase) = match outcome {
+            Ok(()) => (ProbeResult::Ok, None),
+            Err((phase, err)) => (ProbeResult::Err(err), Some(phase)),
+        };
+
+        ProbeSample {
+            ts: start_ts,
+            target_id: target.id,
+            profile_id: profile.id,
+            result,
+            http_status: None,
+            negotiated: NegotiatedProtocol {
+                alpn: None,
+                tls_version: None,
+                tls_version_configured: false,
+                cipher: None,
+            },
+            t_dns: target.resolves_dns().then_some(connection.t_dns),
+            t_connect: connection.t_connect,
+            t_tls: None,
+            t_ttfb: Duration::ZERO,
+            t_download: Duration::ZERO,
+            t_total,
+            downloaded_bytes: 0,
+            content_length: None,
+            local: connection.local,
+            remote: connection.remote,
+            resolved_ips: Vec::new(),
+            tcp_info: connection.tcp_info,
+            ebpf: None,
+            cert: None,
+            ping: None,
+            burst: false,
+            priming: false,
+            truncated: false,
+            new_connections: Some(1),
+            insecure: false,
+            response_headers: Vec::new(),
+            endpoint: None,
+            missed_slots: 0,
+            deferred_slots: 0,
+            limiter_wait: None,
+            since_last_probe: None,
+            tcp_info_errno: connection.tcp_info_errno,
+            http_version_fallback: false,
+            failed_phase,
+            jitter_ms: None,
+        }
+    }
+}
+
+/// Resolves and connects, filling in `connection` as the phases complete.
+/// The connect gets the breakdown's connect budget, else what is left of
+/// the probe timeout after the lookup.
+fn connect(
+    target: &TargetConfig,
+    resolved_ip: Option<IpAddr>,
+    connection: &mut Connection,
+) -> Result<(), (Phase, ProbeError)> {
+    if let Some(path) = &target.unix_socket {
+        return connect_unix(path, connection);
+    }
+
+    let started = Instant::now();
+    let destination = target_destination(target, resolved_ip).map_err(|err| (Phase::Dns, err))?;
+    connection.t_dns = started.elapsed();
+    connection.remote = Some(destination);
+
+    let timeout = target.timeout_breakdown.map_or_else(
+        || target.timeout_total.saturating_sub(connection.t_dns),
+        |breakdown| breakdown.connect,
+    );
+    if timeout.is_zero() {
+        return Err((
+            Phase::Connect,
+            connect_error(
+                ProbeErrorKind::ConnectTimeout,
+                format!("no time left to connect to {destination}"),
+            ),
+        ));
+    }
+    let stream = TcpStream::connect_timeout(&destination, timeout).map_err(|err| {
+        let kind = match err.kind() {
+            ErrorKind::TimedOut => ProbeErrorKind::ConnectTimeout,
+            ErrorKind::ConnectionRefused => ProbeErrorKind::ConnectRefused,
+            ErrorKind::HostUnreachable | ErrorKind::NetworkUnreachable => {
+                ProbeErrorKind::ConnectNoRoute
+            }
+            _ => ProbeErrorKind::ConnectOther,
+        };
+        let message = format!("tcp connect to {destination}: {err}");
+        (Phase::Connect, connect_error(kind, message))
+    })?;
+    connection.t_connect = started.elapsed().saturating_sub(connection.t_dns);
+    connection.local = stream.local_addr().ok();
+    match fetch_stream_tcp_info(&stream) {
+        Ok(tcp_info) => connection.tcp_info = tcp_info,
+        Err(errno) => connection.tcp_info_errno = Some(errno),
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn connect_unix(
+    path: &std::path::Path,
+    connection: &mut Connection,
+) -> Result<(), (Phase, ProbeError)> {
+    let started = Instant::now();
+    std::os::unix::net::UnixStream::connect(path).map_err(|err| {
+        let kind = match err.kind() {
+            ErrorKind::ConnectionRefused | ErrorKind::NotFound => ProbeErrorKind::ConnectRefused,
+            _ => ProbeErrorKind::ConnectOther,
+        };
+        let message = format!("connect to {}: {err}", path.display());
+        (Phase::Connect, connect_error(kind, message))
+    })?;
+    connection.t_connect = started.elapsed();
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn connect_unix(
+    path: &std::path::Path,
+    _connection: &mut Connection,
+) -> Result<(), (Phase, ProbeError)> {
+    Err((
+        Phase::Connect,
+        connect_error(
+            ProbeErrorKind::ConnectOther,
+            format!("unix sockets are not supported here ({})", path.display()),
+        ),
+    ))
+}
+
+fn connect_error(kind: ProbeErrorKind, message: String) -> ProbeError {
+    ProbeError {
+        kind,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use crate::probe::Phase;
+    use std::net::{SocketAddr, TcpListener};
+    use url::Url;
+
+    fn target_at(scheme: &str, addr: SocketAddr) -> TargetConfig {
+        TargetConfig::new(
+            Url::parse(&format!("{scheme}://{addr}/health")).unwrap(),
+            default_profiles(),
+        )
+    }
+
+    #[test]
+    fn connects_without_sending_a_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let target = target_at("http", addr);
+
+        let mut client = ConnectClient::new();
+        let sample = client.probe(&target, &target.profiles[0], None);
+        assert!(
+            matches!(sample.result, ProbeResult::Ok),
+            "{:?}",
+            sample.result
+        );
+        assert_eq!(sample.remote, Some(addr));
+        assert!(sample.local.is_some());
+        assert_eq!(sample.http_status, None);
+        assert_eq!(sample.t_tls, None);
+        assert!(sample.t_total >= sample.t_connect);
+    }
+
+    #[test]
+    fn https_targets_stop_before_the_tls_handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let target = target_at("https", addr);
+
+        let mut client = ConnectClient::new();
+        let sample = client.probe(&target, &target.profiles[0], None);
+        assert!(matches!(sample.result, ProbeResult::Ok));
+        assert_eq!(sample.t_tls, None);
+        assert_eq!(sample.negotiated.tls_version, None);
+    }
+
+    #[test]
+    fn aborted_clients_do_not_connect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = target_at("http", listener.local_addr().unwrap());
+
+        let mut client = ConnectClient::new();
+        client.set_abort_flag(Arc::new(AtomicBool::new(true)));
+        let sample = client.probe(&target, &target.profiles[0], None);
+        assert!(matches!(sample.result, ProbeResult::Err(_)));
+        assert_eq!(sample.remote, None);
+    }
+
+    #[test]
+    fn refused_connections_fail_in_the_connect_phase() {
+        // Bind then drop so the port is known to be closed
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let target = target_at("http", addr);
+
+        let mut client = ConnectClient::new();
+        let sample = client.probe(&target, &target.profiles[0], None);
+        assert!(matches!(sample.result, ProbeResult::Err(_)));
+        assert_eq!(sample.failed_phase, Some(Phase::Connect));
+    }
+}
@@ -1,13 +1,39 @@
-use crate::probe::{NegotiatedProtocol, ProbeError, ProbeErrorKind, TcpInfoSnapshot};
+use crate::common::time::utc_from_civil;
+use crate::config::{HttpVersion, ProfileConfig};
+use crate::probe::{
+    CertInfo, NegotiatedProtocol, Phase, ProbeError, ProbeErrorKind, TcpInfoSnapshot,
+};
 use curl::Error as CurlError;
+use std::ffi::CStr;
 use std::net::{IpAddr, SocketAddr};
-use std::time::Duration;
+#[cfg(unix)]
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
 // CURLINFO constants not exposed by curl-sys
 // See: https://curl.se/libcurl/c/CURLINFO_HTTP_VERSION.html
 // Use curl_sys::CURLINFO type for cross-platform compatibility (i32 on Windows, u32 on Unix)
 const CURLINFO_LONG: curl_sys::CURLINFO = 0x200000;
 const CURLINFO_HTTP_VERSION: curl_sys::CURLINFO = CURLINFO_LONG + 46;
+const CURLINFO_PTR: curl_sys::CURLINFO = 0x400000;
+const CURLINFO_TLS_SSL_PTR: curl_sys::CURLINFO = CURLINFO_PTR + 45;
+/// `curl_sslbackend` value whose session pointer is an OpenSSL `SSL *`
+#[cfg(unix)]
+const CURLSSLBACKEND_OPENSSL: std::os::raw::c_int = 1;
+
+/// Mirror of `struct curl_tlssessioninfo`, which curl-sys leaves out.
+#[repr(C)]
+struct CurlTlsSessionInfo {
+    backend: std::os::raw::c_int,
+    internals: *mut std::os::raw::c_void,
+}
+
+/// TLS parameters the backend actually negotiated.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct TlsSession {
+    pub(super) version: Option<String>,
+    pub(super) cipher: Option<String>,
+}
 
 // HTTP version codes returned by CURLINFO_HTTP_VERSION
 const CURL_HTTP_VERSION_1_0: i64 = 1;
@@ -20,9 +46,13 @@ pub(super) fn map_curl_error(err: &CurlError) -> ProbeError {
     let message_lower = message.to_ascii_lowercase();
 
     let kind = if err.is_couldnt_resolve_host() || err.is_couldnt_resolve_proxy() {
-        ProbeErrorKind::DnsOther
+        classify_dns_error(&message)
     } else if err.is_operation_timedout() {
-        ProbeErrorKind::HttpTimeout
+        if is_dns_timeout_message(&message) {
+            ProbeErrorKind::DnsTimeout
+        } else {
+            ProbeErrorKind::HttpTimeout
+        }
     } else if err.is_couldnt_connect() {
         ProbeErrorKind::ConnectOther
     } else if err.is_ssl_connect_error()
@@ -45,7 +75,45 @@ pub(super) fn map_curl_error(err: &CurlError) -> ProbeError {
         ProbeErrorKind::IoError
     };
 
-    ProbeError { kind, message }
+    ProbeError {
+        kind,
+        message: message.into(),
+    }
+}
+
+/// Classifies the response status against the profile's expectation. Without
+/// one, any 4xx/5xx is an `HttpStatusError`.
+pub(super) fn check_status(profile: &ProfileConfig, status: Option<u16>) -> Option<ProbeError> {
+    let status = status.filter(|status| *status != 0)?;
+    match profile.expected_status {
+        Some(expected) if !expected.matches(status) => Some(ProbeError {
+            kind: ProbeErrorKind::ValidationFailed,
+            message: format!("expected status {expected}, got {status}").into(),
+        }),
+        Some(_) => None,
+        None if status >= 400 => Some(ProbeError {
+            kind: ProbeErrorKind::HttpStatusError,
+            message: format!("HTTP status {status}").into(),
+        }),
+        None => None,
+    }
+}
+
+/// Checks the retained body prefix for the profile's expected substring.
+pub(super) fn check_body(profile: &ProfileConfig, body: &[u8]) -> Option<ProbeError> {
+    let needle = profile.expect_body_contains.as_deref()?.as_bytes();
+    if needle.is_empty() || body.windows(needle.len()).any(|window| window == needle) {
+        return None;
+    }
+    Some(ProbeError {
+        kind: ProbeErrorKind::ValidationFailed,
+        message: format!(
+            "body does not contain {:?} (searched first {} bytes)",
+            String::from_utf8_lossy(needle),
+            body.len()
+        )
+        .into(),
+    })
 }
 
 fn is_tls_version_error(message: &str) -> bool {
@@ -59,6 +127,54 @@ pub(super) fn is_dns_timeout_message(message: &str) -> bool {
     message.to_ascii_lowercase().contains("resolving timed out")
 }
 
+/// Splits a name resolution failure by what the resolver reported. Covers
+/// curl's own messages (threaded and c-ares resolvers) and the system
+/// resolver's `getaddrinfo` errors.
+pub(super) fn classify_dns_error(message: &str) -> ProbeErrorKind {
+    let message = message.to_ascii_lowercase();
+    if message.contains("resolving timed out") || message.contains("timeout while contacting dns") {
+        ProbeErrorKind::DnsTimeout
+    } else if message.contains("server failure")
+        || message.contains("servfail")
+        || message.contains("temporary failure in name resolution")
+    {
+        ProbeErrorKind::DnsServFail
+    } else if message.contains("could not resolve host")
+        || message.contains("domain name not found")
+        || message.contains("name or service not known")
+        || message.contains("nodename nor servname")
+        || message.contains("no address associated")
+    {
+        ProbeErrorKind::DnsNxDomain
+    } else {
+        ProbeErrorKind::DnsOther
+    }
+}
+
+/// Longest header value kept on a sample, in bytes.
+const MAX_HEADER_VALUE_BYTES: usize = 128;
+
+/// Parses one raw header line into a lowercased name and a trimmed value,
+/// when the name is in `allowlist`. Values are truncated to
+/// `MAX_HEADER_VALUE_BYTES` on a character boundary.
+pub(super) fn parse_header_line(line: &[u8], allowlist: &[String]) -> Option<(String, String)> {
+    let line = String::from_utf8_lossy(line);
+    let (name, value) = line.split_once(':')?;
+    let name = name.trim();
+    if !allowlist
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(name))
+    {
+        return None;
+    }
+    let value = value.trim();
+    let mut end = value.len().min(MAX_HEADER_VALUE_BYTES);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    Some((name.to_ascii_lowercase(), value[..end].to_string()))
+}
+
 pub(super) fn parse_socket_addr(ip: Option<&str>, port: Option<u16>) -> Option<SocketAddr> {
     let port = port?;
     let ip = ip?.parse::<IpAddr>().ok()?;
@@ -69,67 +185,372 @@ pub(super) fn saturating_sub(left: Duration, right: Duration) -> Duration {
     left.checked_sub(right).unwrap_or(Duration::from_millis(0))
 }
 
-pub(super) fn fetch_tcp_info(handle: *mut curl_sys::CURL) -> Option<TcpInfoSnapshot> {
-    #[cfg(not(target_os = "linux"))]
+/// Cumulative curl timings of a transfer, from its start. curl leaves a
+/// mark at zero when the transfer never got that far.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct TransferMarks {
+    pub namelookup: Duration,
+    pub connect: Duration,
+    pub appconnect: Duration,
+    pub starttransfer: Duration,
+}
+
+/// Phase a failed transfer stopped in: the one after the last mark it
+/// reached. A reused connection skips DNS, connect and TLS, and curl
+/// reports no connect or handshake time for it, so only the response mark
+/// counts then. `None` when the failure came with a full response.
+pub(super) fn failed_phase(
+    kind: ProbeErrorKind,
+    marks: &TransferMarks,
+    https: bool,
+    reused: bool,
+) -> Option<Phase> {
+    kind.phase()?;
+    let steps = [
+        (Phase::Connect, marks.namelookup),
+        (Phase::Tls, marks.connect),
+        (Phase::Ttfb, marks.appconnect),
+        (Phase::Download, marks.starttransfer),
+    ];
+    let (reached, fallback) = if reused {
+        (&steps[3..], Phase::Ttfb)
+    } else {
+        (&steps[..], Phase::Dns)
+    };
+    let phase = reached
+        .iter()
+        .rev()
+        .find(|(_, mark)| !mark.is_zero())
+        .map_or(fallback, |(next, _)| *next);
+    // Plain HTTP has no handshake; curl leaves its mark at zero
+    Some(if phase == Phase::Tls && !https {
+        Phase::Ttfb
+    } else {
+        phase
+    })
+}
+
+/// Connections curl had to open for the last transfer; zero means an
+/// existing one was reused.
+pub(super) fn fetch_num_connects(handle: *mut curl_sys::CURL) -> Option<u32> {
+    let mut count: libc::c_long = 0;
+    // SAFETY: CURLINFO_NUM_CONNECTS writes a long into `count`
+    let rc =
+        unsafe { curl_sys::curl_easy_getinfo(handle, curl_sys::CURLINFO_NUM_CONNECTS, &mut count) };
+    if rc != curl_sys::CURLE_OK {
+        return None;
+    }
+    u32::try_from(count).ok()
+}
+
+/// Reads the kernel's TCP state for the transfer's socket. `Ok(None)` means
+/// there was no socket to ask or the OS has no such socket option; `Err`
+/// carries the errno of a `getsockopt` call the kernel refused.
+pub(super) fn fetch_tcp_info(handle: *mut curl_sys::CURL) -> Result<Option<TcpInfoSnapshot>, i32> {
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
     {
         let _ = handle;
-        None
+        Ok(None)
     }
 
-    #[cfg(target_os = "linux")]
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
     unsafe {
         let mut socket: curl_sys::curl_socket_t = curl_sys::CURL_SOCKET_BAD;
         let rc = curl_sys::curl_easy_getinfo(handle, curl_sys::CURLINFO_LASTSOCKET, &mut socket);
         if rc != curl_sys::CURLE_OK || socket == curl_sys::CURL_SOCKET_BAD {
-            return None;
+            return Ok(None);
         }
+        read_tcp_info(socket).map(Some)
+    }
+}
+
+/// Reads the kernel's TCP state for a connected stream; `Err` carries the
+/// errno of a refused `getsockopt`.
+pub(super) fn fetch_stream_tcp_info(
+    stream: &std::net::TcpStream,
+) -> Result<Option<TcpInfoSnapshot>, i32> {
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = stream;
+        Ok(None)
+    }
 
-        let mut info: libc::tcp_info = std::mem::zeroed();
-        let mut len = std::mem::size_of::<libc::tcp_info>() as libc::socklen_t;
-        let rc = libc::getsockopt(
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        use std::os::fd::AsRawFd;
+        read_tcp_info(stream.as_raw_fd()).map(Some)
+    }
+}
+
+/// Asks the kernel for TCP state on a fresh, unconnected socket. Only a
+/// missing socket option rules it out; a socket that cannot be opened says
+/// nothing, so the probes' own errno is left to explain any failure.
+pub(super) fn tcp_info_option_works() -> bool {
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        false
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    {
+        // SAFETY: a plain socket(2) call; the descriptor is closed below
+        let socket = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0) };
+        if socket < 0 {
+            return true;
+        }
+        let result = read_tcp_info(socket);
+        // SAFETY: `socket` was opened above and is not used afterwards
+        unsafe { libc::close(socket) };
+        !matches!(result, Err(errno) if errno == libc::ENOPROTOOPT || errno == libc::EOPNOTSUPP)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_tcp_info(socket: curl_sys::curl_socket_t) -> Result<TcpInfoSnapshot, i32> {
+    let mut info: libc::tcp_info = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::tcp_info>() as libc::socklen_t;
+    // SAFETY: TCP_INFO writes at most `len` bytes into `info`
+    let rc = unsafe {
+        libc::getsockopt(
             socket,
             libc::IPPROTO_TCP,
             libc::TCP_INFO,
             &mut info as *mut _ as *mut _,
             &mut len,
-        );
-        if rc != 0 {
+        )
+    };
+    if rc != 0 {
+        return Err(last_errno());
+    }
+
+    Ok(TcpInfoSnapshot {
+        rtt_us: Some(info.tcpi_rtt),
+        rttvar_us: Some(info.tcpi_rttvar),
+        total_retrans: Some(info.tcpi_total_retrans),
+        lost: Some(info.tcpi_lost),
+        reordering: Some(info.tcpi_reordering),
+        snd_cwnd: Some(info.tcpi_snd_cwnd),
+        snd_ssthresh: Some(info.tcpi_snd_ssthresh),
+    })
+}
+
+/// macOS reports RTTs in ms and the window in bytes; they are converted to
+/// Linux's µs and segments so the charts read the same on both. It counts
+/// retransmitted bytes rather than segments and has no loss or reordering
+/// counters.
+#[cfg(target_os = "macos")]
+fn read_tcp_info(socket: curl_sys::curl_socket_t) -> Result<TcpInfoSnapshot, i32> {
+    let mut info: libc::tcp_connection_info = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::tcp_connection_info>() as libc::socklen_t;
+    // SAFETY: TCP_CONNECTION_INFO writes at most `len` bytes into `info`
+    let rc = unsafe {
+        libc::getsockopt(
+            socket,
+            libc::IPPROTO_TCP,
+            libc::TCP_CONNECTION_INFO,
+            &mut info as *mut _ as *mut _,
+            &mut len,
+        )
+    };
+    if rc != 0 {
+        return Err(last_errno());
+    }
+
+    let segment = info.tcpi_maxseg.max(1);
+    let segments = |bytes: u64| u32::try_from(bytes / u64::from(segment)).unwrap_or(u32::MAX);
+    Ok(TcpInfoSnapshot {
+        rtt_us: Some(info.tcpi_srtt.saturating_mul(1000)),
+        rttvar_us: Some(info.tcpi_rttvar.saturating_mul(1000)),
+        total_retrans: Some(segments(info.tcpi_txretransmitbytes)),
+        lost: None,
+        reordering: None,
+        snd_cwnd: Some(segments(u64::from(info.tcpi_snd_cwnd))),
+        snd_ssthresh: Some(segments(u64::from(info.tcpi_snd_ssthresh))),
+    })
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn last_errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+/// Reads the leaf certificate collected with `CURLOPT_CERTINFO`. Backends
+/// without certinfo support report no certificates, which yields `None`.
+pub(super) fn fetch_cert_info(handle: *mut curl_sys::CURL) -> Option<CertInfo> {
+    if handle.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let mut info: *mut curl_sys::curl_certinfo = std::ptr::null_mut();
+        let rc = curl_sys::curl_easy_getinfo(handle, curl_sys::CURLINFO_CERTINFO, &mut info);
+        if rc != curl_sys::CURLE_OK || info.is_null() || (*info).num_of_certs <= 0 {
             return None;
         }
 
-        Some(TcpInfoSnapshot {
-            rtt_us: Some(info.tcpi_rtt),
-            rttvar_us: Some(info.tcpi_rttvar),
-            total_retrans: Some(info.tcpi_total_retrans),
-            lost: Some(info.tcpi_lost),
-            reordering: Some(info.tcpi_reordering),
-            snd_cwnd: Some(info.tcpi_snd_cwnd),
-            snd_ssthresh: Some(info.tcpi_snd_ssthresh),
-        })
+        // The chain starts with the server's own certificate
+        let mut entries = Vec::new();
+        let mut node = *(*info).certinfo;
+        while !node.is_null() {
+            if !(*node).data.is_null() {
+                entries.push(CStr::from_ptr((*node).data).to_string_lossy().into_owned());
+            }
+            node = (*node).next;
+        }
+        parse_cert_entries(&entries)
     }
 }
 
-/// Fetches the actually negotiated protocol information from curl.
-/// Returns the HTTP version actually used (not the configured preference).
-/// TLS version detection is not reliably available via curl API, so we
-/// indicate when it cannot be determined.
+/// Builds a `CertInfo` from curl's `Key:value` certinfo entries.
+pub(super) fn parse_cert_entries(entries: &[String]) -> Option<CertInfo> {
+    let mut cert = CertInfo {
+        not_after: None,
+        issuer: None,
+        subject_alt_names: Vec::new(),
+    };
+    for entry in entries {
+        let Some((key, value)) = entry.split_once(':') else {
+            continue;
+        };
+        match key {
+            "Issuer" => cert.issuer = Some(value.trim().to_string()),
+            "Expire date" => cert.not_after = parse_cert_date(value),
+            "X509v3 Subject Alternative Name" => {
+                cert.subject_alt_names = value
+                    .split(',')
+                    .map(|name| {
+                        let name = name.trim();
+                        name.split_once(':').map_or(name, |(_, value)| value)
+                    })
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    (cert.not_after.is_some() || cert.issuer.is_some()).then_some(cert)
+}
+
+/// Parses a certificate date in either OpenSSL's `Mar  1 08:00:00 2025 GMT`
+/// form or curl's own `2025-03-01 08:00:00 GMT` form.
+fn parse_cert_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let parse_clock = |clock: &str| -> Option<u32> {
+        let mut parts = clock.split(':').map(|part| part.parse::<u32>().ok());
+        let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+        (hours < 24 && minutes < 60 && seconds < 61)
+            .then_some(hours * 3600 + minutes * 60 + seconds.min(59))
+    };
+
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    match fields.as_slice() {
+        [month, day, clock, year, ..] if MONTHS.contains(month) => {
+            let month = MONTHS.iter().position(|name| name == month)? as u32 + 1;
+            utc_from_civil(
+                year.parse().ok()?,
+                month,
+                day.parse().ok()?,
+                parse_clock(clock)?,
+            )
+        }
+        [date, clock, ..] => {
+            let mut parts = date.split('-');
+            let year = parts.next()?.parse().ok()?;
+            let month = parts.next()?.parse().ok()?;
+            let day = parts.next()?.parse().ok()?;
+            utc_from_civil(year, month, day, parse_clock(clock)?)
+        }
+        _ => None,
+    }
+}
+
+/// Whether libcurl's TLS backend is OpenSSL itself. LibreSSL and BoringSSL
+/// builds report the same backend id, but their `SSL *` is not safe to hand
+/// to openssl-sys, so the version string decides.
+#[cfg(unix)]
+fn curl_uses_openssl() -> bool {
+    static USES_OPENSSL: OnceLock<bool> = OnceLock::new();
+    *USES_OPENSSL.get_or_init(|| {
+        curl::Version::get()
+            .ssl_version()
+            .and_then(|version| version.split_whitespace().next())
+            .is_some_and(|active| active.starts_with("OpenSSL/"))
+    })
+}
+
+/// Reads the live connection's TLS version and cipher. The session pointer
+/// is only valid while the connection is open, so this must run during the
+/// transfer (e.g. from a header callback). Only an OpenSSL backend exposes
+/// a session this way; others, including LibreSSL and BoringSSL, yield `None`.
+pub(super) fn fetch_tls_session(handle: *mut curl_sys::CURL) -> Option<TlsSession> {
+    if handle.is_null() {
+        return None;
+    }
+
+    unsafe {
+        let mut info: *mut CurlTlsSessionInfo = std::ptr::null_mut();
+        let rc = curl_sys::curl_easy_getinfo(handle, CURLINFO_TLS_SSL_PTR, &mut info);
+        if rc != curl_sys::CURLE_OK || info.is_null() || (*info).internals.is_null() {
+            return None;
+        }
+
+        #[cfg(unix)]
+        if (*info).backend == CURLSSLBACKEND_OPENSSL && curl_uses_openssl() {
+            let ssl = (*info).internals as *const openssl_sys::SSL;
+            let text = |ptr: *const std::os::raw::c_char| {
+                (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+            };
+            let cipher = openssl_sys::SSL_get_current_cipher(ssl);
+            return Some(TlsSession {
+                version: text(openssl_sys::SSL_get_version(ssl)),
+                cipher: if cipher.is_null() {
+                    None
+                } else {
+                    text(openssl_sys::SSL_CIPHER_get_name(cipher))
+                },
+            });
+        }
+
+        None
+    }
+}
+
+/// Fetches the actually negotiated protocol information from curl: the HTTP
+/// version used, plus the TLS version and cipher captured during the transfer.
+/// Without a captured session the TLS version falls back to `configured_tls`
+/// (None for plain http) and is marked as configured.
 pub(super) fn fetch_negotiated_protocol(
     handle: *mut curl_sys::CURL,
-    configured_tls: &str,
+    session: Option<&TlsSession>,
+    configured_tls: Option<&str>,
 ) -> NegotiatedProtocol {
     let alpn = fetch_http_version(handle);
-    // Note: libcurl does not expose CURLINFO for the negotiated TLS version.
-    // We use the configured value but could mark it as "configured" vs "actual"
-    // in a future enhancement if curl adds this capability.
-    let tls_version = Some(configured_tls.to_string());
-
-    NegotiatedProtocol {
-        alpn,
-        tls_version,
-        cipher: None,
+    match session.and_then(|session| session.version.as_ref().map(|version| (version, session))) {
+        Some((version, session)) => NegotiatedProtocol {
+            alpn,
+            tls_version: Some(version.clone()),
+            tls_version_configured: false,
+            cipher: session.cipher.clone(),
+        },
+        None => NegotiatedProtocol {
+            alpn,
+            tls_version: configured_tls.map(str::to_string),
+            tls_version_configured: configured_tls.is_some(),
+            cipher: None,
+        },
     }
 }
 
+/// Whether a profile that asked for HTTP/2 got its answer over an older
+/// version, as reported by `fetch_negotiated_protocol`.
+pub(super) fn is_http_version_fallback(requested: HttpVersion, alpn: Option<&str>) -> bool {
+    matches!(requested, HttpVersion::H2 | HttpVersion::H2PriorKnowledge)
+        && alpn.is_some_and(|alpn| alpn != "h2")
+}
+
 fn fetch_http_version(handle: *mut curl_sys::CURL) -> Option<String> {
     if handle.is_null() {
         return None;
@@ -159,9 +580,128 @@ fn fetch_http_version(handle: *mut curl_sys::CURL) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{is_dns_timeout_message, is_tls_version_error, parse_socket_addr, saturating_sub};
+    use super::{
+        TlsSession, TransferMarks, check_body, check_status, classify_dns_error, failed_phase,
+        fetch_negotiated_protocol, is_dns_timeout_message, is_http_version_fallback,
+        is_tls_version_error, parse_cert_entries, parse_header_line, parse_socket_addr,
+        saturating_sub,
+    };
+    use crate::config::{ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TlsVersion};
+    use crate::probe::{Phase, ProbeErrorKind};
     use std::time::Duration;
 
+    fn profile() -> ProfileConfig {
+        ProfileConfig::new(
+            "h2",
+            HttpVersion::H2,
+            TlsVersion::Tls13,
+            ConnReusePolicy::Warm,
+            ProbeMethod::Get,
+            64,
+        )
+    }
+
+    #[test]
+    fn only_h2_profiles_answered_over_http1_fall_back() {
+        assert!(is_http_version_fallback(HttpVersion::H2, Some("http/1.1")));
+        assert!(is_http_version_fallback(
+            HttpVersion::H2PriorKnowledge,
+            Some("http/1.0")
+        ));
+        assert!(!is_http_version_fallback(HttpVersion::H2, Some("h2")));
+        // No response, nothing negotiated
+        assert!(!is_http_version_fallback(HttpVersion::H2, None));
+        assert!(!is_http_version_fallback(HttpVersion::H1, Some("http/1.1")));
+    }
+
+    #[test]
+    fn failed_phase_follows_the_last_timing_curl_reached() {
+        let ms = Duration::from_millis;
+        let marks = |reached: &[u64]| {
+            let mark = |index: usize| ms(reached.get(index).copied().unwrap_or(0));
+            TransferMarks {
+                namelookup: mark(0),
+                connect: mark(1),
+                appconnect: mark(2),
+                starttransfer: mark(3),
+            }
+        };
+        let fresh =
+            |kind, reached: &[u64], https| failed_phase(kind, &marks(reached), https, false);
+        let reused = |kind, reached: &[u64]| failed_phase(kind, &marks(reached), true, true);
+
+        // Nothing reached: the lookup never finished
+        assert_eq!(
+            fresh(ProbeErrorKind::DnsTimeout, &[], true),
+            Some(Phase::Dns)
+        );
+        // A timeout is placed by its timings, not by its kind
+        assert_eq!(
+            fresh(ProbeErrorKind::HttpTimeout, &[3], true),
+            Some(Phase::Connect)
+        );
+        assert_eq!(
+            fresh(ProbeErrorKind::HttpTimeout, &[3, 20], true),
+            Some(Phase::Tls)
+        );
+        assert_eq!(
+            fresh(ProbeErrorKind::HttpTimeout, &[3, 20, 45], true),
+            Some(Phase::Ttfb)
+        );
+        assert_eq!(
+            fresh(ProbeErrorKind::ReadTimeout, &[3, 20, 45, 90], true),
+            Some(Phase::Download)
+        );
+        // Plain HTTP has no handshake to fail in
+        assert_eq!(
+            fresh(ProbeErrorKind::HttpTimeout, &[3, 20], false),
+            Some(Phase::Ttfb)
+        );
+        // A reused connection reports a lookup but no connect of its own
+        assert_eq!(reused(ProbeErrorKind::HttpTimeout, &[1]), Some(Phase::Ttfb));
+        assert_eq!(
+            reused(ProbeErrorKind::IoError, &[1, 0, 0, 40]),
+            Some(Phase::Download)
+        );
+        // A bad status came with a whole response
+        assert_eq!(
+            fresh(ProbeErrorKind::HttpStatusError, &[3, 20, 45, 90], true),
+            None
+        );
+    }
+
+    #[test]
+    fn check_status_uses_expectation_when_configured() {
+        let mut profile = profile();
+        assert!(check_status(&profile, Some(200)).is_none());
+        assert_eq!(
+            check_status(&profile, Some(503)).map(|err| err.kind),
+            Some(ProbeErrorKind::HttpStatusError)
+        );
+
+        profile.expected_status = Some("204".parse().expect("status"));
+        let err = check_status(&profile, Some(200)).expect("mismatch");
+        assert_eq!(err.kind, ProbeErrorKind::ValidationFailed);
+        assert_eq!(&*err.message, "expected status 204, got 200");
+        assert!(check_status(&profile, Some(204)).is_none());
+
+        profile.expected_status = Some("404".parse().expect("status"));
+        assert!(check_status(&profile, Some(404)).is_none());
+        assert!(check_status(&profile, Some(0)).is_none());
+    }
+
+    #[test]
+    fn check_body_searches_retained_prefix() {
+        let mut profile = profile();
+        assert!(check_body(&profile, b"anything").is_none());
+
+        profile.expect_body_contains = Some("\"status\":\"ok\"".to_string());
+        assert!(check_body(&profile, b"{\"status\":\"ok\"}").is_none());
+        let err = check_body(&profile, b"<html>maintenance</html>").expect("mismatch");
+        assert_eq!(err.kind, ProbeErrorKind::ValidationFailed);
+        assert!(err.message.contains("searched first 24 bytes"));
+    }
+
     #[test]
     fn dns_timeout_message_detection() {
         assert!(is_dns_timeout_message(
@@ -172,6 +712,62 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn dns_errors_are_split_by_resolver_answer() {
+        for (message, kind) in [
+            (
+                "[6] Could not resolve hostname (Could not resolve host: nope.invalid)",
+                ProbeErrorKind::DnsNxDomain,
+            ),
+            (
+                "[6] Could not resolve hostname (Could not resolve host: x.example (Domain name not found))",
+                ProbeErrorKind::DnsNxDomain,
+            ),
+            (
+                "failed to lookup address information: Name or service not known",
+                ProbeErrorKind::DnsNxDomain,
+            ),
+            (
+                "[6] Could not resolve hostname (Could not resolve host: x.example (Server failure))",
+                ProbeErrorKind::DnsServFail,
+            ),
+            (
+                "failed to lookup address information: Temporary failure in name resolution",
+                ProbeErrorKind::DnsServFail,
+            ),
+            (
+                "[28] Timeout was reached (Resolving timed out after 5000 milliseconds)",
+                ProbeErrorKind::DnsTimeout,
+            ),
+            (
+                "[6] Could not resolve hostname (Could not resolve host: x.example (Timeout while contacting DNS servers))",
+                ProbeErrorKind::DnsTimeout,
+            ),
+            (
+                "[5] Could not resolve proxy name (Could not resolve proxy: proxy.local)",
+                ProbeErrorKind::DnsOther,
+            ),
+        ] {
+            assert_eq!(classify_dns_error(message), kind, "{message}");
+        }
+    }
+
+    #[test]
+    fn parse_header_line_keeps_allowlisted_headers() {
+        let allowlist = vec!["x-cache".to_string(), "server".to_string()];
+        assert_eq!(
+            parse_header_line(b"X-Cache: Hit from cloudfront\r\n", &allowlist),
+            Some(("x-cache".to_string(), "Hit from cloudfront".to_string()))
+        );
+        assert_eq!(parse_header_line(b"set-cookie: a=b\r\n", &allowlist), None);
+        assert_eq!(parse_header_line(b"HTTP/2 200\r\n", &allowlist), None);
+        assert_eq!(parse_header_line(b"\r\n", &allowlist), None);
+
+        let long = format!("server: {}\r\n", "é".repeat(100));
+        let (_, value) = parse_header_line(long.as_bytes(), &allowlist).expect("server");
+        assert_eq!(value.len(), 128);
+    }
+
     #[test]
     fn parse_socket_addr_accepts_valid_values() {
         let addr = parse_socket_addr(Some("127.0.0.1"), Some(443)).expect("addr");
@@ -198,6 +794,61 @@ mod tests {
         assert_eq!(result, Duration::from_millis(6));
     }
 
+    #[test]
+    fn cert_entries_parse_in_both_date_forms() {
+        let entries = [
+            "Subject:CN = example.com".to_string(),
+            "Issuer:C = US, O = Let's Encrypt, CN = R11".to_string(),
+            "Expire date:Mar  1 08:00:00 2025 GMT".to_string(),
+            "X509v3 Subject Alternative Name:DNS:example.com, DNS:www.example.com".to_string(),
+        ];
+        let cert = parse_cert_entries(&entries).expect("cert");
+        assert_eq!(
+            cert.issuer.as_deref(),
+            Some("C = US, O = Let's Encrypt, CN = R11")
+        );
+        assert_eq!(
+            cert.not_after,
+            Some(std::time::UNIX_EPOCH + Duration::from_secs(1_740_816_000))
+        );
+        assert_eq!(
+            cert.subject_alt_names,
+            vec!["example.com", "www.example.com"]
+        );
+
+        let curl_form = ["Expire date:2025-03-01 08:00:00 GMT".to_string()];
+        assert_eq!(
+            parse_cert_entries(&curl_form).and_then(|cert| cert.not_after),
+            cert.not_after
+        );
+        assert_eq!(parse_cert_entries(&["Version:2".to_string()]), None);
+    }
+
+    #[test]
+    fn negotiated_protocol_prefers_reported_session() {
+        let session = TlsSession {
+            version: Some("TLSv1.2".to_string()),
+            cipher: Some("ECDHE-RSA-AES128-GCM-SHA256".to_string()),
+        };
+        let reported =
+            fetch_negotiated_protocol(std::ptr::null_mut(), Some(&session), Some("TLSv1.3"));
+        assert_eq!(reported.tls_version.as_deref(), Some("TLSv1.2"));
+        assert!(!reported.tls_version_configured);
+        assert_eq!(
+            reported.cipher.as_deref(),
+            Some("ECDHE-RSA-AES128-GCM-SHA256")
+        );
+
+        let fallback = fetch_negotiated_protocol(std::ptr::null_mut(), None, Some("TLSv1.3"));
+        assert_eq!(fallback.tls_version.as_deref(), Some("TLSv1.3"));
+        assert!(fallback.tls_version_configured);
+        assert_eq!(fallback.cipher, None);
+
+        let plain = fetch_negotiated_protocol(std::ptr::null_mut(), None, None);
+        assert_eq!(plain.tls_version, None);
+        assert!(!plain.tls_version_configured);
+    }
+
     #[test]
     fn tls_version_error_detection() {
         assert!(is_tls_version_error("ssl_min_max_version not supported"));
@@ -1,59 +1,130 @@
+mod backend;
 mod client;
+mod connect;
 mod helpers;
+mod oneshot;
+mod ping;
+mod report;
+mod resolver;
 
+pub use backend::{BackendError, Sampler, create_backend};
 pub use client::ProbeClient;
+pub use connect::ConnectClient;
+pub use oneshot::{ProbeOptions, probe_once};
+pub use report::{OnceResult, format_once_table, once_exit_code, once_json, run_once};
+pub use resolver::{lookup_target_ips, resolve_target_ips};
 
+use crossbeam_channel::Receiver;
 use curl::easy::{Easy, HttpVersion, SslVersion};
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use url::Url;
 
-/// Cached result of TLS 1.3 support detection
+/// Result of TLS 1.3 support detection, set once it has finished.
 static TLS13_SUPPORTED: OnceLock<bool> = OnceLock::new();
 
-/// Known public endpoint that supports TLS 1.3 for testing.
-const TLS13_TEST_URL: &str = "https://www.google.com";
+/// Whether curl may take a proxy from `http_proxy`/`https_proxy`; off keeps
+/// every probe direct regardless of the shell environment.
+static USE_PROXY_ENV: AtomicBool = AtomicBool::new(false);
 
-/// Detects if the system's curl/SSL library supports TLS 1.3.
-/// The result is cached after the first call.
+/// Proxy environment variables curl reads, in its order of precedence.
+const PROXY_ENV_VARS: [&str; 6] = [
+    "https_proxy",
+    "HTTPS_PROXY",
+    "http_proxy",
+    "HTTP_PROXY",
+    "all_proxy",
+    "ALL_PROXY",
+];
+
+pub fn set_use_proxy_env(enabled: bool) {
+    USE_PROXY_ENV.store(enabled, Ordering::Relaxed);
+}
+
+pub fn use_proxy_env() -> bool {
+    USE_PROXY_ENV.load(Ordering::Relaxed)
+}
+
+/// The first proxy set in the environment, read once at first use.
+pub fn env_proxy() -> Option<&'static str> {
+    static ENV_PROXY: OnceLock<Option<String>> = OnceLock::new();
+    ENV_PROXY
+        .get_or_init(|| {
+            PROXY_ENV_VARS
+                .iter()
+                .filter_map(|name| std::env::var(name).ok())
+                .find(|value| !value.trim().is_empty())
+        })
+        .as_deref()
+}
+
+/// Whether the system's curl/SSL library supports TLS 1.3; `None` until
+/// [`spawn_tls13_detection`] has an answer.
+pub fn tls13_support() -> Option<bool> {
+    TLS13_SUPPORTED.get().copied()
+}
+
+/// Detects TLS 1.3 support on a background thread and sends the answer once.
 ///
-/// This performs an actual TLS 1.3 connection test because some SSL backends
-/// (e.g., SecureTransport on macOS) accept the API configuration but fail
-/// at runtime when attempting TLS 1.3 handshakes.
-pub fn detect_tls13_support() -> bool {
-    *TLS13_SUPPORTED.get_or_init(|| {
-        let mut easy = Easy::new();
-
-        // First check if the API accepts TLS 1.3 configuration
-        if easy
-            .ssl_min_max_version(SslVersion::Tlsv13, SslVersion::Tlsv13)
-            .is_err()
-        {
-            return false;
-        }
+/// The library is asked first. Some SSL backends (e.g., SecureTransport on
+/// macOS) accept the configuration but fail at runtime, so when `probe_url` is
+/// an https URL a TLS 1.3-only handshake against it settles the answer (a
+/// server without TLS 1.3 also answers no, keeping the TLS 1.2 defaults). If
+/// it cannot be reached the library's answer stands. No other host is contacted.
+pub fn spawn_tls13_detection(probe_url: Option<Url>) -> Receiver<bool> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    std::thread::spawn(move || {
+        let supported = *TLS13_SUPPORTED.get_or_init(|| {
+            let library = library_supports_tls13();
+            match probe_url.filter(|url| url.scheme() == "https") {
+                Some(url) if library => test_tls13_connection(&url).unwrap_or(library),
+                _ => library,
+            }
+        });
+        let _ = tx.send(supported);
+    });
+    rx
+}
 
-        // API accepts it, but we need to verify runtime support
-        // by actually attempting a TLS 1.3 connection
-        if let Ok(supported) = test_tls13_connection() {
-            return supported;
-        }
+/// Whether the linked libcurl was built with HTTP/2 (nghttp2). Without it an
+/// h2 preference is ignored and every request goes out as HTTP/1.1.
+pub fn detect_h2_support() -> bool {
+    curl::Version::get().feature_http2()
+}
 
-        // If the test fails (e.g., network unavailable), fall back to false
-        // to avoid runtime errors
-        false
-    })
+/// Whether the kernel answers the TCP state socket option (`TCP_INFO` on
+/// Linux, `TCP_CONNECTION_INFO` on macOS), checked once per process.
+pub fn detect_tcp_info_support() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(helpers::tcp_info_option_works)
+}
+
+/// Whether the linked SSL backend accepts a TLS 1.3-only configuration.
+fn library_supports_tls13() -> bool {
+    let secure_transport = curl::Version::get()
+        .ssl_version()
+        .is_some_and(|version| version.contains("SecureTransport"));
+    !secure_transport
+        && Easy::new()
+            .ssl_min_max_version(SslVersion::Tlsv13, SslVersion::Tlsv13)
+            .is_ok()
 }
 
 /// Attempts an actual TLS 1.3 connection to verify runtime support.
-fn test_tls13_connection() -> Result<bool, curl::Error> {
+fn test_tls13_connection(url: &Url) -> Result<bool, curl::Error> {
     let mut easy = Easy::new();
 
-    easy.url(TLS13_TEST_URL)?;
+    easy.url(url.as_str())?;
     easy.ssl_min_max_version(SslVersion::Tlsv13, SslVersion::Tlsv13)?;
     easy.http_version(HttpVersion::V2TLS)?;
     easy.timeout(Duration::from_secs(5))?;
     easy.connect_timeout(Duration::from_secs(5))?;
     easy.nobody(true)?;
     easy.follow_location(false)?;
+    if !use_proxy_env() {
+        easy.proxy("")?;
+    }
 
     match easy.perform() {
         Ok(()) => Ok(true),
@@ -0,0 +1,87 @@
+use super::{ProbeClient, resolve_target_ips};
+use crate::config::{ProfileConfig, TargetConfig, TimeoutBreakdown};
+use crate::probe::{ProbeError, ProbeErrorKind, ProbeResult, ProbeSample};
+use std::time::Duration;
+use url::Url;
+
+/// Per-call settings for [`probe_once`].
+#[derive(Clone, Debug)]
+pub struct ProbeOptions {
+    /// Upper bound on the whole request
+    pub timeout: Duration,
+    /// Upper bound on establishing the connection; `None` leaves it to `timeout`
+    pub connect_timeout: Option<Duration>,
+    /// Resolve the host up front and report the DNS phase
+    pub dns_enabled: bool,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            connect_timeout: None,
+            dns_enabled: true,
+        }
+    }
+}
+
+/// Probes `url` once with `profile` on the calling thread, without a worker
+/// or any monitoring state.
+///
+/// A successful probe returns the full sample, including phase timings and
+/// TCP_INFO where the platform provides it. A probe that fails (DNS, connect,
+/// TLS, timeout, unexpected status or body) returns its classified error.
+///
+/// ```no_run
+/// use httpulse::config::{
+///     ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TlsVersion,
+/// };
+/// use httpulse::probe::{ProbeOptions, probe_once};
+/// use url::Url;
+///
+/// let url = Url::parse("https://example.com").unwrap();
+/// let profile = ProfileConfig::new(
+///     "h2+tls13+cold",
+///     HttpVersion::H2,
+///     TlsVersion::Tls13,
+///     ConnReusePolicy::Cold,
+///     ProbeMethod::Get,
+///     4096,
+/// );
+/// match probe_once(&url, &profile, ProbeOptions::default()) {
+///     Ok(sample) => println!("{} in {:?}", sample.http_status.unwrap_or(0), sample.t_total),
+///     Err(err) => eprintln!("probe failed: {err}"),
+/// }
+/// ```
+pub fn probe_once(
+    url: &Url,
+    profile: &ProfileConfig,
+    opts: ProbeOptions,
+) -> Result<ProbeSample, ProbeError> {
+    let mut target = TargetConfig::new(url.clone(), vec![profile.clone()]);
+    target.timeout_total = opts.timeout;
+    target.dns_enabled = opts.dns_enabled;
+    target.timeout_breakdown = opts.connect_timeout.map(|connect| TimeoutBreakdown {
+        dns: connect,
+        connect,
+        tls: opts.timeout,
+        ttfb: opts.timeout,
+        read: opts.timeout,
+    });
+
+    let mut client = ProbeClient::new().map_err(|err| ProbeError {
+        kind: ProbeErrorKind::IoError,
+        message: format!("probe client init failed: {err}").into(),
+    })?;
+    let resolved_ips = if target.resolves_dns() {
+        resolve_target_ips(&target)
+    } else {
+        Vec::new()
+    };
+    let mut sample = client.probe(&target, profile, None);
+    sample.resolved_ips = resolved_ips;
+    match sample.result {
+        ProbeResult::Ok => Ok(sample),
+        ProbeResult::Err(err) => Err(err),
+    }
+}
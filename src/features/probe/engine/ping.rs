@@ -0,0 +1,375 @@
+use super::resolver::target_destination;
+use crate::config::{ProfileConfig, TargetConfig};
+use crate::probe::{
+    NegotiatedProtocol, PingMode, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample,
+};
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Payload carried by every echo request.
+const ECHO_PAYLOAD: &[u8; 8] = b"httpulse";
+
+/// Measures the network round trip to the target host without HTTP: an ICMP
+/// echo where the platform permits one, else the TCP connect time to the
+/// target port. Only `t_total` (and the RTT metric derived from it) is set.
+pub(super) fn ping(
+    target: &TargetConfig,
+    profile: &ProfileConfig,
+    resolved_ip: Option<IpAddr>,
+) -> ProbeSample {
+    let start_ts = SystemTime::now();
+    let timeout = target
+        .timeout_breakdown
+        .map(|breakdown| breakdown.connect)
+        .unwrap_or(target.timeout_total);
+
+    let outcome = destination(target, resolved_ip).map(|destination| {
+        let (mode, rtt) = match icmp_echo(destination.ip(), timeout) {
+            Some(result) => (PingMode::Icmp, result),
+            None => (PingMode::TcpConnect, tcp_connect_time(destination, timeout)),
+        };
+        (destination, mode, rtt)
+    });
+
+    let (result, rtt, remote, ping) = match outcome {
+        Ok((destination, mode, Ok(rtt))) => (ProbeResult::Ok, rtt, Some(destination), Some(mode)),
+        Ok((destination, mode, Err(err))) => (
+            ProbeResult::Err(err),
+            Duration::ZERO,
+            Some(destination),
+            Some(mode),
+        ),
+        Err(err) => (ProbeResult::Err(err), Duration::ZERO, None, None),
+    };
+
+    ProbeSample {
+        ts: start_ts,
+        target_id: target.id,
+        profile_id: profile.id,
+        result,
+        http_status: None,
+        negotiated: NegotiatedProtocol {
+            alpn: None,
+            tls_version: None,
+            tls_version_configured: false,
+            cipher: None,
+        },
+        t_dns: None,
+        t_connect: Duration::ZERO,
+        t_tls: None,
+        t_ttfb: Duration::ZERO,
+        t_download: Duration::ZERO,
+        t_total: rtt,
+        downloaded_bytes: 0,
+        content_length: None,
+        local: None,
+        remote,
+        resolved_ips: Vec::new(),
+        tcp_info: None,
+        ebpf: None,
+        cert: None,
+        ping,
+        burst: false,
+        priming: false,
+        truncated: false,
+        new_connections: None,
+        insecure: false,
+        response_headers: Vec::new(),
+        endpoint: None,
+        missed_slots: 0,
+        deferred_slots: 0,
+        limiter_wait: None,
+        since_last_probe: None,
+        tcp_info_errno: None,
+        http_version_fallback: false,
+        failed_phase: None,
+        jitter_ms: None,
+    }
+}
+
+/// Where the ping goes; unix socket targets have no network host.
+fn destination(
+    target: &TargetConfig,
+    resolved_ip: Option<IpAddr>,
+) -> Result<SocketAddr, ProbeError> {
+    if target.unix_socket.is_some() {
+        return Err(ProbeError {
+            kind: ProbeErrorKind::PingUnsupported,
+            message: "unix socket targets have no network host to ping".into(),
+        });
+    }
+    target_destination(target, resolved_ip)
+}
+
+/// Time until the TCP handshake completes. A refusal still proves the host
+/// answered, so its RST counts as the reply.
+fn tcp_connect_time(destination: SocketAddr, timeout: Duration) -> Result<Duration, ProbeError> {
+    let started = Instant::now();
+    match TcpStream::connect_timeout(&destination, timeout) {
+        Ok(_) => Ok(started.elapsed()),
+        Err(err) if err.kind() == ErrorKind::ConnectionRefused => Ok(started.elapsed()),
+        Err(err) => {
+            let kind = match err.kind() {
+                ErrorKind::TimedOut => ProbeErrorKind::ConnectTimeout,
+                ErrorKind::HostUnreachable | ErrorKind::NetworkUnreachable => {
+                    ProbeErrorKind::ConnectNoRoute
+                }
+                ErrorKind::PermissionDenied
+                | ErrorKind::AddrNotAvailable
+                | ErrorKind::Unsupported => ProbeErrorKind::PingUnsupported,
+                _ => ProbeErrorKind::ConnectOther,
+            };
+            let message = if kind == ProbeErrorKind::PingUnsupported {
+                format!("ICMP sockets are not permitted and TCP connect is unavailable: {err}")
+            } else {
+                format!("tcp connect to {destination}: {err}")
+            };
+            Err(ProbeError {
+                kind,
+                message: message.into(),
+            })
+        }
+    }
+}
+
+/// ICMP echo request with the checksum filled in. The kernel recomputes the
+/// ICMPv6 checksum itself, since it covers the pseudo-header.
+fn echo_request(v6: bool, identifier: u16, sequence: u16) -> [u8; 16] {
+    let mut packet = [0u8; 16];
+    packet[0] = if v6 { 128 } else { 8 };
+    packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+    packet[6..8].copy_from_slice(&sequence.to_be_bytes());
+    packet[8..].copy_from_slice(ECHO_PAYLOAD);
+    if !v6 {
+        let checksum = internet_checksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+    packet
+}
+
+/// RFC 1071 ones' complement sum.
+fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Whether `icmp` is the echo reply to our request. Datagram sockets rewrite
+/// the identifier, so it is only compared when `identifier` is given.
+fn is_echo_reply(icmp: &[u8], v6: bool, identifier: Option<u16>, sequence: u16) -> bool {
+    let reply_type = if v6 { 129 } else { 0 };
+    icmp.len() >= 8
+        && icmp[0] == reply_type
+        && icmp[1] == 0
+        && identifier.is_none_or(|id| icmp[4..6] == id.to_be_bytes())
+        && icmp[6..8] == sequence.to_be_bytes()
+}
+
+/// Raw IPv4 sockets deliver the IP header in front of the ICMP message.
+fn strip_ipv4_header(packet: &[u8]) -> &[u8] {
+    let header_len = packet
+        .first()
+        .map_or(0, |byte| usize::from(byte & 0x0f) * 4);
+    packet.get(header_len..).unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn icmp_echo(_destination: IpAddr, _timeout: Duration) -> Option<Result<Duration, ProbeError>> {
+    None
+}
+
+/// Sends one echo request and waits for its reply. `None` means ICMP sockets
+/// are not permitted here and the caller should fall back to TCP.
+#[cfg(target_os = "linux")]
+fn icmp_echo(destination: IpAddr, timeout: Duration) -> Option<Result<Duration, ProbeError>> {
+    use crate::features::probe::traceroute::{from_sockaddr, to_sockaddr};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    static SEQUENCE: AtomicU16 = AtomicU16::new(0);
+
+    let v6 = destination.is_ipv6();
+    let socket_type = icmp_socket_type(v6)?;
+    let (family, protocol) = icmp_family(v6);
+    // SAFETY: plain socket(2) call; the descriptor is owned immediately below.
+    let raw = unsafe { libc::socket(family, socket_type | libc::SOCK_CLOEXEC, protocol) };
+    if raw < 0 {
+        return None;
+    }
+    // SAFETY: `raw` is a freshly created descriptor not owned elsewhere.
+    let socket = unsafe { OwnedFd::from_raw_fd(raw) };
+    let fd = socket.as_raw_fd();
+    let failed = |kind: ProbeErrorKind, call: &str| {
+        Some(Err(ProbeError {
+            kind,
+            message: format!("icmp {call}: {}", std::io::Error::last_os_error()).into(),
+        }))
+    };
+
+    // Datagram sockets pick their own identifier, so only raw replies are matched on it
+    let is_raw = socket_type == libc::SOCK_RAW;
+    let identifier = std::process::id() as u16;
+    let expected_identifier = is_raw.then_some(identifier);
+    let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let request = echo_request(v6, identifier, sequence);
+    let (addr, addr_len) = to_sockaddr(SocketAddr::new(destination, 0));
+    let started = Instant::now();
+    // SAFETY: `request` and `addr` are valid for the lengths passed.
+    let sent = unsafe {
+        libc::sendto(
+            fd,
+            request.as_ptr() as *const libc::c_void,
+            request.len(),
+            0,
+            &addr as *const libc::sockaddr_storage as *const libc::sockaddr,
+            addr_len,
+        )
+    };
+    if sent < 0 {
+        let kind = match std::io::Error::last_os_error().raw_os_error() {
+            Some(libc::ENETUNREACH | libc::EHOSTUNREACH) => ProbeErrorKind::ConnectNoRoute,
+            _ => ProbeErrorKind::ConnectOther,
+        };
+        return failed(kind, "send");
+    }
+
+    let mut buf = [0u8; 1500];
+    loop {
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            return Some(Err(ProbeError {
+                kind: ProbeErrorKind::ConnectTimeout,
+                message: format!("no echo reply from {destination} within {timeout:?}").into(),
+            }));
+        }
+        let mut poll_fd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = remaining.as_millis().clamp(1, i32::MAX as u128) as libc::c_int;
+        // SAFETY: `poll_fd` is a single valid pollfd.
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+        if ready < 0 {
+            return failed(ProbeErrorKind::IoError, "poll");
+        }
+        if ready == 0 {
+            continue;
+        }
+
+        // SAFETY: an all-zero sockaddr_storage is valid for either family.
+        let mut from: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut from_len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        // SAFETY: `buf` and `from` are valid for the lengths passed.
+        let read = unsafe {
+            libc::recvfrom(
+                fd,
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                0,
+                &mut from as *mut libc::sockaddr_storage as *mut libc::sockaddr,
+                &mut from_len,
+            )
+        };
+        if read < 0 {
+            return failed(ProbeErrorKind::IoError, "recv");
+        }
+        let rtt = started.elapsed();
+        // SAFETY: recvfrom filled `from` with an address of the socket's family.
+        let sender = unsafe {
+            from_sockaddr(&from as *const libc::sockaddr_storage as *const libc::sockaddr)
+        };
+        let packet = &buf[..read as usize];
+        let icmp = if is_raw && !v6 {
+            strip_ipv4_header(packet)
+        } else {
+            packet
+        };
+        if sender == Some(destination) && is_echo_reply(icmp, v6, expected_identifier, sequence) {
+            return Some(Ok(rtt));
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn icmp_family(v6: bool) -> (libc::c_int, libc::c_int) {
+    if v6 {
+        (libc::AF_INET6, libc::IPPROTO_ICMPV6)
+    } else {
+        (libc::AF_INET, libc::IPPROTO_ICMP)
+    }
+}
+
+/// Socket type that may send ICMP echoes for the family, detected once:
+/// unprivileged datagram sockets (`net.ipv4.ping_group_range`) first, then
+/// raw sockets (root or `CAP_NET_RAW`).
+#[cfg(target_os = "linux")]
+fn icmp_socket_type(v6: bool) -> Option<libc::c_int> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::sync::OnceLock;
+
+    static SOCKET_TYPES: [OnceLock<Option<libc::c_int>>; 2] = [OnceLock::new(), OnceLock::new()];
+
+    *SOCKET_TYPES[usize::from(v6)].get_or_init(|| {
+        let (family, protocol) = icmp_family(v6);
+        [libc::SOCK_DGRAM, libc::SOCK_RAW]
+            .into_iter()
+            .find(|socket_type| {
+                // SAFETY: plain socket(2) call; the descriptor is owned immediately below.
+                let raw =
+                    unsafe { libc::socket(family, socket_type | libc::SOCK_CLOEXEC, protocol) };
+                if raw < 0 {
+                    return false;
+                }
+                // SAFETY: `raw` is a freshly created descriptor not owned elsewhere.
+                drop(unsafe { OwnedFd::from_raw_fd(raw) });
+                true
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echo_request_checksum_verifies() {
+        let request = echo_request(false, 0x1234, 7);
+        assert_eq!(request[0], 8);
+        assert_eq!(&request[4..8], &[0x12, 0x34, 0, 7]);
+        // Summing a packet that carries its own checksum yields zero
+        assert_eq!(internet_checksum(&request), 0);
+
+        let request = echo_request(true, 0x1234, 7);
+        assert_eq!(request[0], 128);
+        assert_eq!(&request[2..4], &[0, 0]);
+    }
+
+    #[test]
+    fn echo_reply_matches_sequence_and_identifier() {
+        let mut reply = echo_request(false, 0x1234, 7);
+        reply[0] = 0;
+        assert!(is_echo_reply(&reply, false, Some(0x1234), 7));
+        assert!(is_echo_reply(&reply, false, None, 7));
+        assert!(!is_echo_reply(&reply, false, Some(0x4321), 7));
+        assert!(!is_echo_reply(&reply, false, None, 8));
+        // Our own request looped back on a raw socket is not a reply
+        assert!(!is_echo_reply(
+            &echo_request(false, 0x1234, 7),
+            false,
+            None,
+            7
+        ));
+
+        let mut packet = vec![0x45; 1];
+        packet.extend_from_slice(&[0; 19]);
+        packet.extend_from_slice(&reply);
+        assert_eq!(strip_ipv4_header(&packet), &reply);
+    }
+}
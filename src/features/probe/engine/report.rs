@@ -0,0 +1,321 @@
+use super::ProbeClient;
+use crate::config::{ProfileConfig, TargetConfig};
+use crate::data_model::{SCHEMA_VERSION, SchemaVersion};
+use crate::probe::{ProbeErrorKind, ProbeResult, ProbeSample};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// Outcome of one profile's single probe in a `--once` run.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OnceResult {
+    pub url: String,
+    pub profile: String,
+    pub ok: bool,
+    pub status: Option<u16>,
+    /// Error kind of a failed probe, e.g. `connect_refused`
+    pub error: Option<String>,
+    pub message: Option<String>,
+    pub alpn: Option<String>,
+    pub tls_version: Option<String>,
+    pub remote: Option<String>,
+    pub dns_ms: Option<f64>,
+    pub connect_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    pub ttfb_ms: Option<f64>,
+    pub download_ms: Option<f64>,
+    pub total_ms: Option<f64>,
+    /// Smoothed RTT from TCP_INFO, where the OS reports it
+    pub rtt_ms: Option<f64>,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl OnceResult {
+    pub fn from_sample(
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        sample: &ProbeSample,
+    ) -> Self {
+        let (error, message) = match &sample.result {
+            ProbeResult::Ok => (None, None),
+            ProbeResult::Err(err) => (
+                Some(err.kind.label().to_string()),
+                Some(err.message.to_string()),
+            ),
+        };
+        Self {
+            url: target.url.to_string(),
+            profile: profile.name.clone(),
+            ok: error.is_none(),
+            // curl reports 0 when no response was received
+            status: sample.http_status.filter(|status| *status != 0),
+            error,
+            message,
+            alpn: sample.negotiated.alpn.clone(),
+            tls_version: sample.negotiated.tls_version.clone(),
+            remote: sample.remote.map(|addr| addr.ip().to_string()),
+            dns_ms: sample.t_dns.map(millis),
+            connect_ms: Some(millis(sample.t_connect)),
+            tls_ms: sample.t_tls.map(millis),
+            ttfb_ms: Some(millis(sample.t_ttfb)),
+            download_ms: Some(millis(sample.t_download)),
+            total_ms: Some(millis(sample.t_total)),
+            rtt_ms: sample
+                .tcp_info
+                .as_ref()
+                .and_then(|info| info.rtt_us)
+                .map(|rtt| rtt as f64 / 1000.0),
+        }
+    }
+
+    /// A probe that could not run at all, e.g. when curl fails to start.
+    fn not_run(target: &TargetConfig, profile: &ProfileConfig, message: String) -> Self {
+        Self {
+            url: target.url.to_string(),
+            profile: profile.name.clone(),
+            ok: false,
+            status: None,
+            error: Some(ProbeErrorKind::IoError.label().to_string()),
+            message: Some(message),
+            alpn: None,
+            tls_version: None,
+            remote: None,
+            dns_ms: None,
+            connect_ms: None,
+            tls_ms: None,
+            ttfb_ms: None,
+            download_ms: None,
+            total_ms: None,
+            rtt_ms: None,
+        }
+    }
+}
+
+/// Probes every profile of every target once and returns the results in
+/// target and profile order. Targets run in parallel; a target's profiles
+/// run one after another, each on a fresh client so none reuses another's
+/// connection.
+pub fn run_once(targets: &[TargetConfig]) -> Vec<OnceResult> {
+    std::thread::scope(|scope| {
+        let probes: Vec<_> = targets
+            .iter()
+            .map(|target| {
+                scope.spawn(move || {
+                    target
+                        .profiles
+                        .iter()
+                        .map(|profile| match ProbeClient::new() {
+                            Ok(mut client) => OnceResult::from_sample(
+                                target,
+                                profile,
+                                &client.probe(target, profile, None),
+                            ),
+                            Err(err) => OnceResult::not_run(
+                                target,
+                                profile,
+                                format!("probe client init failed: {err}"),
+                            ),
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        probes
+            .into_iter()
+            .zip(targets)
+            .flat_map(|(probe, target)| {
+                probe.join().unwrap_or_else(|_| {
+                    target
+                        .profiles
+                        .iter()
+                        .map(|profile| {
+                            OnceResult::not_run(target, profile, "probe panicked".to_string())
+                        })
+                        .collect()
+                })
+            })
+            .collect()
+    })
+}
+
+/// Process exit code of a `--once` run: 0 when every probe succeeded, 1
+/// when any failed and 2 when there was nothing to probe, so the run works
+/// as a health check in CI or cron.
+pub fn once_exit_code(results: &[OnceResult]) -> i32 {
+    if results.is_empty() {
+        2
+    } else if results.iter().all(|result| result.ok) {
+        0
+    } else {
+        1
+    }
+}
+
+#[derive(Serialize)]
+struct OnceReport<'a> {
+    schema_version: SchemaVersion,
+    results: &'a [OnceResult],
+}
+
+/// The `--once --json` report: the results under a `schema_version`, like
+/// every other document httpulse writes for other programs.
+pub fn once_json(results: &[OnceResult]) -> String {
+    let report = OnceReport {
+        schema_version: SCHEMA_VERSION,
+        results,
+    };
+    serde_json::to_string_pretty(&report).expect("plain data always serializes")
+}
+
+fn format_ms(value: Option<f64>) -> String {
+    match value {
+        Some(ms) if ms >= 1000.0 => format!("{:.2}s", ms / 1000.0),
+        Some(ms) => format!("{ms:.1}ms"),
+        None => "-".to_string(),
+    }
+}
+
+/// Plain-text table of the results, one row per probe, followed by the
+/// message of every failure.
+pub fn format_once_table(results: &[OnceResult]) -> String {
+    const HEADER: [&str; 11] = [
+        "TARGET", "PROFILE", "RESULT", "PROTO", "DNS", "CONNECT", "TLS", "TTFB", "DOWNLOAD",
+        "TOTAL", "RTT",
+    ];
+    let rows: Vec<[String; 11]> = results
+        .iter()
+        .map(|result| {
+            let outcome = match (&result.error, result.status) {
+                (Some(error), _) => format!("FAIL {error}"),
+                (None, Some(status)) => format!("ok {status}"),
+                (None, None) => "ok".to_string(),
+            };
+            let proto = match (&result.alpn, &result.tls_version) {
+                (Some(alpn), Some(tls)) => format!("{alpn} {tls}"),
+                (Some(alpn), None) => alpn.clone(),
+                (None, Some(tls)) => tls.clone(),
+                (None, None) => "-".to_string(),
+            };
+            [
+                result.url.clone(),
+                result.profile.clone(),
+                outcome,
+                proto,
+                format_ms(result.dns_ms),
+                format_ms(result.connect_ms),
+                format_ms(result.tls_ms),
+                format_ms(result.ttfb_ms),
+                format_ms(result.download_ms),
+                format_ms(result.total_ms),
+                format_ms(result.rtt_ms),
+            ]
+        })
+        .collect();
+
+    let mut widths = HEADER.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    let mut write_row = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Names and outcomes read left to right, timings line up right
+                if column < 4 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .collect();
+        let _ = writeln!(out, "{}", line.join("  ").trim_end());
+    };
+    write_row(&HEADER);
+    for row in &rows {
+        write_row(&row.each_ref().map(String::as_str));
+    }
+
+    if results.iter().any(|result| !result.ok) {
+        out.push('\n');
+    }
+    for result in results.iter().filter(|result| !result.ok) {
+        let _ = writeln!(
+            out,
+            "{} [{}]: {}",
+            result.url,
+            result.profile,
+            result.message.as_deref().unwrap_or("failed")
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use crate::features::probe::testing::SampleBuilder;
+    use crate::probe::ProbeErrorKind;
+    use url::Url;
+
+    fn results() -> Vec<OnceResult> {
+        let target = TargetConfig::new(
+            Url::parse("https://example.com").unwrap(),
+            default_profiles(),
+        );
+        let sample = || SampleBuilder::ok(target.id, target.profiles[0].id).total_ms(120);
+        let mut ok = sample().build();
+        ok.negotiated.alpn = Some("h2".to_string());
+        let mut failed = sample().failed(ProbeErrorKind::ConnectRefused).build();
+        failed.http_status = Some(0);
+        vec![
+            OnceResult::from_sample(&target, &target.profiles[0], &ok),
+            OnceResult::from_sample(&target, &target.profiles[1], &failed),
+        ]
+    }
+
+    #[test]
+    fn table_lines_up_columns_and_lists_failures() {
+        let results = results();
+        let table = format_once_table(&results);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("TARGET"), "{table}");
+        assert!(
+            lines[1].contains("ok 200") && lines[1].contains("h2"),
+            "{table}"
+        );
+        assert!(lines[2].contains("FAIL connect_refused"), "{table}");
+        // Every row's TOTAL column ends where the header's does
+        let total_end = lines[0].find("TOTAL").unwrap() + "TOTAL".len();
+        assert_eq!(&lines[1][total_end - 7..total_end], "120.0ms", "{table}");
+        let profile = &results[1].profile;
+        assert!(
+            table.contains(&format!("https://example.com/ [{profile}]: ConnectRefused")),
+            "{table}"
+        );
+    }
+
+    #[test]
+    fn exit_code_fails_on_any_failed_probe_and_on_no_probes() {
+        let results = results();
+        assert_eq!(once_exit_code(&results[..1]), 0);
+        assert_eq!(once_exit_code(&results), 1);
+        assert_eq!(once_exit_code(&[]), 2);
+
+        let json: serde_json::Value = serde_json::from_str(&once_json(&results)).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION.to_string());
+        let json = &json["results"];
+        assert_eq!(json[0]["status"], 200);
+        assert_eq!(json[1]["ok"], false);
+        assert_eq!(json[1]["status"], serde_json::Value::Null);
+        assert_eq!(json[1]["error"], "connect_refused");
+    }
+}
@@ -0,0 +1,173 @@
+use crate::config::TargetConfig;
+use crate::probe::{ProbeError, ProbeErrorKind};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+/// Resolves the target host through the system resolver.
+///
+/// Returns the de-duplicated address set in resolver order. IP-literal hosts
+/// resolve to themselves; lookup failures yield an empty set and are left for
+/// the HTTP probe to classify.
+pub fn resolve_target_ips(target: &TargetConfig) -> Vec<IpAddr> {
+    lookup_target_ips(target).unwrap_or_default()
+}
+
+/// Like [`resolve_target_ips`], but says why a lookup found no address.
+pub fn lookup_target_ips(target: &TargetConfig) -> Result<Vec<IpAddr>, ProbeError> {
+    let Some(host) = target.url.host_str() else {
+        return Err(dns_error(ProbeErrorKind::DnsOther, "URL has no host"));
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![ip]);
+    }
+
+    let port = target.url.port_or_known_default().unwrap_or(443);
+    let mut ips: Vec<IpAddr> = Vec::new();
+    for addr in lookup_host(host, port, target.dns_timeout())? {
+        if !ips.contains(&addr.ip()) {
+            ips.push(addr.ip());
+        }
+    }
+    Ok(ips)
+}
+
+/// Resolves `host` through the system resolver, giving up after `timeout`.
+/// `getaddrinfo` cannot be cancelled, so the lookup runs on its own thread
+/// and one that outlives the timeout finishes there unobserved.
+pub(super) fn lookup_host(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<Vec<SocketAddr>, ProbeError> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    let query = host.to_string();
+    let spawned = thread::Builder::new()
+        .name("dns-lookup".to_string())
+        .spawn(move || {
+            let result = (query.as_str(), port)
+                .to_socket_addrs()
+                .map(Iterator::collect::<Vec<_>>);
+            let _ = tx.send(result);
+        });
+    if let Err(err) = spawned {
+        return Err(dns_error(
+            ProbeErrorKind::DnsOther,
+            &format!("could not start the lookup of {host}: {err}"),
+        ));
+    }
+    let addrs = match rx.recv_timeout(timeout) {
+        Ok(Ok(addrs)) => addrs,
+        Ok(Err(err)) => {
+            let message = format!("could not resolve {host}: {err}");
+            return Err(dns_error(classify_lookup_error(&message), &message));
+        }
+        Err(_) => {
+            return Err(dns_error(
+                ProbeErrorKind::DnsTimeout,
+                &format!("lookup of {host} timed out after {timeout:?}"),
+            ));
+        }
+    };
+    if addrs.is_empty() {
+        return Err(dns_error(
+            ProbeErrorKind::DnsNxDomain,
+            &format!("no address for {host}"),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// Host and port a connection to the target goes to: the connect-to
+/// override when set, else the URL host. `resolved_ip` stands in for the
+/// lookup when the target pins its address.
+pub(super) fn target_destination(
+    target: &TargetConfig,
+    resolved_ip: Option<IpAddr>,
+) -> Result<SocketAddr, ProbeError> {
+    let (host, port) = match &target.connect_to {
+        Some(connect_to) => (connect_to.host.clone(), connect_to.port),
+        None => (
+            target.url.host_str().unwrap_or_default().to_string(),
+            target.url.port_or_known_default().unwrap_or(443),
+        ),
+    };
+    if !target.dns_enabled
+        && target.connect_to.is_none()
+        && let Some(ip) = resolved_ip
+    {
+        return Ok(SocketAddr::new(ip, port));
+    }
+
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, port));
+    }
+    let addrs = lookup_host(host, port, target.dns_timeout())?;
+    Ok(addrs[0])
+}
+
+/// Kind of a failed `getaddrinfo`, judged from the message the platform gives.
+fn classify_lookup_error(message: &str) -> ProbeErrorKind {
+    let message = message.to_ascii_lowercase();
+    if message.contains("not known")
+        || message.contains("nodename nor servname")
+        || message.contains("no address associated")
+    {
+        ProbeErrorKind::DnsNxDomain
+    } else if message.contains("temporary failure") || message.contains("try again") {
+        ProbeErrorKind::DnsServFail
+    } else {
+        ProbeErrorKind::DnsOther
+    }
+}
+
+fn dns_error(kind: ProbeErrorKind, message: &str) -> ProbeError {
+    ProbeError {
+        kind,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use url::Url;
+
+    #[test]
+    fn ip_literal_hosts_resolve_to_themselves() {
+        let target = TargetConfig::new(
+            Url::parse("https://192.0.2.7:8443/health").unwrap(),
+            default_profiles(),
+        );
+        assert_eq!(
+            resolve_target_ips(&target),
+            vec!["192.0.2.7".parse::<IpAddr>().unwrap()]
+        );
+
+        let target = TargetConfig::new(Url::parse("http://[::1]/").unwrap(), default_profiles());
+        assert_eq!(
+            resolve_target_ips(&target),
+            vec!["::1".parse::<IpAddr>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn lookup_failures_are_classified_from_the_resolver_message() {
+        assert_eq!(
+            classify_lookup_error(
+                "failed to lookup address information: Name or service not known"
+            ),
+            ProbeErrorKind::DnsNxDomain
+        );
+        assert_eq!(
+            classify_lookup_error(
+                "failed to lookup address information: Temporary failure in name resolution"
+            ),
+            ProbeErrorKind::DnsServFail
+        );
+        assert_eq!(classify_lookup_error("timed out"), ProbeErrorKind::DnsOther);
+    }
+}
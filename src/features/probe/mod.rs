@@ -1,3 +1,8 @@
+pub mod demo;
+pub mod ebpf;
 pub mod engine;
 pub mod model;
 pub mod runtime;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod traceroute;
@@ -1,8 +1,11 @@
 use crate::config::{ProfileId, TargetId};
-use std::net::SocketAddr;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProbeSample {
     pub ts: SystemTime,
     pub target_id: TargetId,
@@ -17,19 +20,150 @@ pub struct ProbeSample {
     pub t_download: Duration,
     pub t_total: Duration,
     pub downloaded_bytes: u64,
+    /// Full body size announced by the server, even when the read was truncated
+    pub content_length: Option<u64>,
     pub local: Option<SocketAddr>,
     pub remote: Option<SocketAddr>,
+    /// Address set from the explicit resolver step; empty when DNS probing is off
+    pub resolved_ips: Vec<IpAddr>,
     pub tcp_info: Option<TcpInfoSnapshot>,
     pub ebpf: Option<EbpfConnStatsDelta>,
+    /// Server certificate, only on probes that asked for it
+    pub cert: Option<CertInfo>,
+    /// How a ping profile measured the round trip; `None` for HTTP probes
+    pub ping: Option<PingMode>,
+    /// Taken during a burst, at a faster pace than the configured interval
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub burst: bool,
+    /// First probe of a warm profile since the worker started or its
+    /// connection last failed, so it paid for a fresh connection
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub priming: bool,
+    /// The body was cut off at `max_read_bytes`, so the download time
+    /// includes the abort and says nothing about throughput
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Connections curl opened for this probe; zero when it reused one.
+    /// `None` for probes that do not go through curl
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_connections: Option<u32>,
+    /// Taken with certificate verification off (`insecure` on the target)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    /// Allowlisted headers of the final response, names lowercased
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response_headers: Vec<(String, String)>,
+    /// Address a per-IP stream is pinned to; `None` for the profile's own
+    /// stream, which follows the DNS answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<IpAddr>,
+    /// Probe slots the worker's schedule passed without probing since its
+    /// previous probe, e.g. while the machine was suspended
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub missed_slots: u32,
+    /// Slots that passed while this probe waited for a download slot (see
+    /// `limiter_wait`); counted apart from `missed_slots`, since the probe
+    /// ran, only late
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub deferred_slots: u32,
+    /// How long the probe waited for the download limiter before starting;
+    /// `None` when it did not wait. The phase timings start after the wait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limiter_wait: Option<Duration>,
+    /// Phase a failed probe stopped in; `None` for successes, for failures
+    /// that got a full response and for probes without HTTP phases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_phase: Option<Phase>,
+    /// Time since the worker's previous probe started; `None` for the first
+    /// probe after the worker started, resumed or changed its interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_last_probe: Option<Duration>,
+    /// errno of the `getsockopt` call when the kernel refused to report TCP
+    /// state for the probe's socket
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp_info_errno: Option<i32>,
+    /// An h2 profile's request was answered over HTTP/1.x, e.g. because
+    /// libcurl lacks HTTP/2 or the server does not offer it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http_version_fallback: bool,
+    /// Change in total latency from the profile's previous successful probe,
+    /// in ms; set by the metrics store when the sample is stored
+    #[serde(skip)]
+    pub jitter_ms: Option<f64>,
 }
 
-#[derive(Clone, Debug)]
+/// Leaves counters that are almost always zero out of serialized samples.
+fn is_zero(count: &u32) -> bool {
+    *count == 0
+}
+
+/// Headers whose value reports a CDN cache hit or miss, most specific first.
+pub const CACHE_STATUS_HEADERS: [&str; 2] = ["cf-cache-status", "x-cache"];
+
+impl ProbeSample {
+    /// Value of the captured response header `name`, matched case-insensitively.
+    pub fn response_header(&self, name: &str) -> Option<&str> {
+        self.response_headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether a CDN served the response from cache, read from the cache
+    /// status headers; `None` when the response carried none. When a chain of
+    /// caches reports (`x-cache: MISS, HIT`), the last entry is the edge
+    /// closest to the client.
+    pub fn cache_hit(&self) -> Option<bool> {
+        let value = CACHE_STATUS_HEADERS
+            .iter()
+            .find_map(|name| self.response_header(name))?;
+        let last = value.rsplit(',').next().unwrap_or(value);
+        Some(last.to_ascii_lowercase().contains("hit"))
+    }
+}
+
+/// Step of an HTTP probe, in the order they run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Dns,
+    Connect,
+    Tls,
+    Ttfb,
+    Download,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Dns,
+        Phase::Connect,
+        Phase::Tls,
+        Phase::Ttfb,
+        Phase::Download,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Dns => "dns",
+            Phase::Connect => "connect",
+            Phase::Tls => "tls",
+            Phase::Ttfb => "ttfb",
+            Phase::Download => "download",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ProbeResult {
     Ok,
     Err(ProbeError),
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum ProbeErrorKind {
     DnsTimeout,
     DnsNxDomain,
@@ -47,6 +181,11 @@ pub enum ProbeErrorKind {
     HttpStatusError,
     ReadTimeout,
     IoError,
+    ValidationFailed,
+    /// Not a probe failure: the certificate is close to its expiry date
+    CertExpiringSoon,
+    /// Neither an ICMP echo nor the TCP connect fallback can reach the host
+    PingUnsupported,
 }
 
 impl ProbeErrorKind {
@@ -68,6 +207,9 @@ impl ProbeErrorKind {
             ProbeErrorKind::HttpStatusError => "http_status_error",
             ProbeErrorKind::ReadTimeout => "read_timeout",
             ProbeErrorKind::IoError => "io_error",
+            ProbeErrorKind::ValidationFailed => "validation_failed",
+            ProbeErrorKind::CertExpiringSoon => "cert_expiring_soon",
+            ProbeErrorKind::PingUnsupported => "ping_unsupported",
         }
     }
 
@@ -89,6 +231,9 @@ impl ProbeErrorKind {
             ProbeErrorKind::HttpStatusError => "HTTP Status",
             ProbeErrorKind::ReadTimeout => "Read Timeout",
             ProbeErrorKind::IoError => "I/O Error",
+            ProbeErrorKind::ValidationFailed => "Validation",
+            ProbeErrorKind::CertExpiringSoon => "Cert Expiring",
+            ProbeErrorKind::PingUnsupported => "Ping Unsupported",
         }
     }
 
@@ -101,22 +246,147 @@ impl ProbeErrorKind {
                 | ProbeErrorKind::ReadTimeout
         )
     }
+
+    /// Whether the failure leaves no connection to reuse; status and
+    /// validation failures arrive over a working connection.
+    pub fn breaks_connection(&self) -> bool {
+        !matches!(
+            self,
+            ProbeErrorKind::HttpStatusError
+                | ProbeErrorKind::ValidationFailed
+                | ProbeErrorKind::CertExpiringSoon
+        )
+    }
+
+    /// Phase a failure of this kind points at, for probes without curl's
+    /// timings to tell. Status and validation failures got a full response,
+    /// so no phase failed.
+    pub fn phase(&self) -> Option<Phase> {
+        match self {
+            ProbeErrorKind::DnsTimeout
+            | ProbeErrorKind::DnsNxDomain
+            | ProbeErrorKind::DnsServFail
+            | ProbeErrorKind::DnsOther => Some(Phase::Dns),
+            ProbeErrorKind::ConnectTimeout
+            | ProbeErrorKind::ConnectRefused
+            | ProbeErrorKind::ConnectNoRoute
+            | ProbeErrorKind::ConnectOther
+            | ProbeErrorKind::PingUnsupported => Some(Phase::Connect),
+            ProbeErrorKind::TlsHandshakeFailed
+            | ProbeErrorKind::TlsVersionMismatch
+            | ProbeErrorKind::AlpnFailed => Some(Phase::Tls),
+            ProbeErrorKind::HttpTimeout | ProbeErrorKind::HttpProtocolError => Some(Phase::Ttfb),
+            ProbeErrorKind::ReadTimeout | ProbeErrorKind::IoError => Some(Phase::Download),
+            ProbeErrorKind::HttpStatusError
+            | ProbeErrorKind::ValidationFailed
+            | ProbeErrorKind::CertExpiringSoon => None,
+        }
+    }
+
+    /// Chart marker category; `None` for kinds that are not probe failures.
+    pub fn category(&self) -> Option<ErrorCategory> {
+        match self {
+            kind if kind.is_timeout() => Some(ErrorCategory::Timeout),
+            ProbeErrorKind::TlsHandshakeFailed
+            | ProbeErrorKind::TlsVersionMismatch
+            | ProbeErrorKind::AlpnFailed => Some(ErrorCategory::Tls),
+            ProbeErrorKind::HttpStatusError => Some(ErrorCategory::HttpStatus),
+            ProbeErrorKind::CertExpiringSoon => None,
+            _ => Some(ErrorCategory::Other),
+        }
+    }
+}
+
+/// Failure groups drawn as separate markers on the chart.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Timeout,
+    Tls,
+    HttpStatus,
+    /// DNS, connect, protocol and validation failures
+    Other,
+}
+
+impl ErrorCategory {
+    pub const ALL: [ErrorCategory; 4] = [
+        ErrorCategory::Timeout,
+        ErrorCategory::Tls,
+        ErrorCategory::HttpStatus,
+        ErrorCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorCategory::Timeout => "Timeout",
+            ErrorCategory::Tls => "TLS",
+            ErrorCategory::HttpStatus => "HTTP status",
+            ErrorCategory::Other => "Other error",
+        }
+    }
+
+    /// Name used when listing categories in settings.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ErrorCategory::Timeout => "timeout",
+            ErrorCategory::Tls => "tls",
+            ErrorCategory::HttpStatus => "http",
+            ErrorCategory::Other => "other",
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ProbeError {
     pub kind: ProbeErrorKind,
-    pub message: String,
+    /// Shared so a store holding many identical failures keeps one copy
+    pub message: Arc<str>,
 }
 
-#[derive(Clone, Debug)]
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.label(), self.message)
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+/// Leaf certificate details reported by the TLS backend.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CertInfo {
+    pub not_after: Option<SystemTime>,
+    pub issuer: Option<String>,
+    pub subject_alt_names: Vec<String>,
+}
+
+/// How a ping sample measured the round trip to its host.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PingMode {
+    /// ICMP echo request and reply
+    Icmp,
+    /// TCP connect time to the target port, used when ICMP sockets are not permitted
+    TcpConnect,
+}
+
+impl PingMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PingMode::Icmp => "ICMP echo",
+            PingMode::TcpConnect => "TCP connect",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NegotiatedProtocol {
     pub alpn: Option<String>,
     pub tls_version: Option<String>,
+    /// `tls_version` is the profile's setting because the backend did not report one
+    pub tls_version_configured: bool,
     pub cipher: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TcpInfoSnapshot {
     pub rtt_us: Option<u32>,
     pub rttvar_us: Option<u32>,
@@ -127,7 +397,32 @@ pub struct TcpInfoSnapshot {
     pub snd_ssthresh: Option<u32>,
 }
 
-#[derive(Clone, Debug)]
+/// Whether the kernel can report TCP state for our sockets at all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TcpInfoCapability {
+    /// `TCP_INFO` on Linux, `TCP_CONNECTION_INFO` on macOS
+    Available,
+    /// No socket option reports TCP state on this OS
+    Unsupported,
+}
+
+impl TcpInfoCapability {
+    /// Asks the running kernel rather than trusting the build target, so a
+    /// kernel or sandbox without the socket option reads as unsupported.
+    pub fn detect() -> Self {
+        if super::engine::detect_tcp_info_support() {
+            Self::Available
+        } else {
+            Self::Unsupported
+        }
+    }
+
+    pub const fn is_available(self) -> bool {
+        matches!(self, Self::Available)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EbpfConnStatsDelta {
     pub retrans: u32,
     pub dup_acks: u32,
@@ -137,6 +432,42 @@ pub struct EbpfConnStatsDelta {
 #[cfg(test)]
 mod tests {
     use super::ProbeErrorKind;
+    use crate::features::probe::testing::SampleBuilder;
+    use uuid::Uuid;
+
+    #[test]
+    fn cache_hit_reads_the_edge_cache_status() {
+        let sample = |headers: &[(&str, &str)]| {
+            headers
+                .iter()
+                .fold(
+                    SampleBuilder::ok(Uuid::new_v4(), Uuid::new_v4()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
+                .build()
+        };
+
+        assert_eq!(sample(&[]).cache_hit(), None);
+        assert_eq!(sample(&[("server", "nginx")]).cache_hit(), None);
+        assert_eq!(
+            sample(&[("cf-cache-status", "HIT")]).cache_hit(),
+            Some(true)
+        );
+        assert_eq!(
+            sample(&[("cf-cache-status", "DYNAMIC")]).cache_hit(),
+            Some(false)
+        );
+        assert_eq!(
+            sample(&[("x-cache", "Hit from cloudfront")]).cache_hit(),
+            Some(true)
+        );
+        assert_eq!(sample(&[("x-cache", "HIT, MISS")]).cache_hit(), Some(false));
+        assert_eq!(sample(&[("x-cache", "MISS, HIT")]).cache_hit(), Some(true));
+        assert_eq!(
+            sample(&[("x-cache", "MISS"), ("cf-cache-status", "HIT")]).cache_hit(),
+            Some(true)
+        );
+    }
 
     #[test]
     fn probe_error_kind_timeout_detection() {
@@ -1,108 +1,821 @@
-use crate::config::{ProfileConfig, TargetConfig};
+use crate::config::{ConnReusePolicy, ProbeBackendKind, ProbeMethod, ProfileConfig, TargetConfig};
+use crate::ebpf::take_conn_stats;
+use crate::features::probe::demo::DemoGenerator;
 use crate::probe::{ProbeError, ProbeErrorKind, ProbeResult, ProbeSample};
-use crate::probe_engine::ProbeClient;
-use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use crate::probe_engine::{Sampler, create_backend, resolve_target_ips};
+use crossbeam_channel::{Receiver, RecvTimeoutError, SendTimeoutError, Sender};
+use limiter::acquire_download_slot;
 use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread::{self, JoinHandle};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+use url::Url;
+
+mod dns;
+mod endpoint;
+mod limiter;
+
+pub use dns::{DnsCache, RESOLVER_PROFILE_ID, spawn_dns_worker};
+pub use endpoint::endpoint_stream_id;
+pub use limiter::{HEAVY_DOWNLOAD_BYTES, is_heavy_download, set_download_slots};
+
+/// Longest a scheduled worker sleeps before re-reading the clock, so wall-clock
+/// jumps and far-off fire times never strand it.
+const SCHEDULE_RECHECK: Duration = Duration::from_secs(60);
+
+/// Most missed slots one gap accounts for, bounding the count a clock jump
+/// of years could produce.
+const MAX_MISSED_SLOTS: u32 = 1_000_000;
+
+/// How often a worker re-reads the server certificate.
+const CERT_REFRESH: Duration = Duration::from_secs(3600);
+
+/// Longest interval a failing worker backs off to; a longer configured
+/// interval is kept as is.
+pub const BACKOFF_CAP: Duration = Duration::from_secs(60);
+/// Probe interval during a burst.
+pub const BURST_INTERVAL: Duration = Duration::from_millis(500);
+/// How long a burst lasts before the worker reverts to its interval.
+pub const BURST_DURATION: Duration = Duration::from_secs(30);
+
+/// Samples the sample channel holds before workers have to wait for the UI.
+pub const SAMPLE_CHANNEL_CAPACITY: usize = 4096;
+/// How long a worker waits on a full sample channel before dropping its sample.
+const SAMPLE_SEND_WAIT: Duration = Duration::from_millis(200);
+
+/// Samples dropped because the sample channel stayed full.
+static DROPPED_SAMPLES: AtomicU64 = AtomicU64::new(0);
+
+/// Samples dropped so far because the UI fell behind the workers.
+pub fn dropped_samples() -> u64 {
+    DROPPED_SAMPLES.load(Ordering::Relaxed)
+}
+
+/// The bounded channel workers send their samples over.
+pub fn sample_channel() -> (Sender<ProbeSample>, Receiver<ProbeSample>) {
+    crossbeam_channel::bounded(SAMPLE_CHANNEL_CAPACITY)
+}
 
 #[derive(Clone, Debug)]
 pub enum ControlMessage {
     UpdateTarget(Box<TargetConfig>),
-    UpdateProfile(Box<ProfileConfig>),
+    /// New profile config; `reset_connection` drops the warm connection so
+    /// the next probe already negotiates with the new protocol settings
+    UpdateProfile {
+        config: Box<ProfileConfig>,
+        reset_connection: bool,
+    },
     Pause(bool),
+    /// Probe every [`BURST_INTERVAL`] until the given instant
+    Burst(Instant),
     Stop,
 }
 
+/// Where a worker's samples come from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProbeSource {
+    /// Real probes over the network with this backend
+    Network(ProbeBackendKind),
+    /// Synthetic samples from a [`DemoGenerator`] with this seed (`--demo`)
+    Demo(u64),
+}
+
+impl Default for ProbeSource {
+    fn default() -> Self {
+        ProbeSource::Network(ProbeBackendKind::default())
+    }
+}
+
+/// Wraps the configured backend with what every network probe shares: the
+/// download limiter, certificate refreshes, the DNS worker's answer and the
+/// per-IP endpoint.
+struct NetworkSampler {
+    backend: Box<dyn Sampler>,
+    kind: ProbeBackendKind,
+    abort: Arc<AtomicBool>,
+    /// When the certificate was last read, and for which URL
+    last_cert: Option<(Instant, Url)>,
+    /// Answer of the target's DNS worker, when it has one
+    dns: DnsCache,
+    /// Address every probe connects to, for a per-IP stream
+    endpoint: Option<IpAddr>,
+}
+
+impl Sampler for NetworkSampler {
+    fn sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        pinned_ip: Option<IpAddr>,
+    ) -> ProbeSample {
+        // Held until the probe is done, so heavy downloads never overlap
+        // beyond the limit
+        let mut slot = None;
+        let mut waited = None;
+        if self.kind == ProbeBackendKind::Curl && is_heavy_download(profile) {
+            match acquire_download_slot(&self.abort) {
+                Some((held, wait)) => {
+                    slot = Some(held);
+                    waited = wait;
+                }
+                None => {
+                    return error_sample(
+                        target.id,
+                        profile.id,
+                        ProbeErrorKind::IoError,
+                        "stopped while waiting for a download slot".to_string(),
+                    );
+                }
+            }
+        }
+        let mut sample = probe_once(
+            self.backend.as_mut(),
+            target,
+            profile,
+            pinned_ip,
+            &self.dns,
+            self.endpoint,
+            &mut self.last_cert,
+        );
+        drop(slot);
+        sample.limiter_wait = waited;
+        sample
+    }
+
+    /// Replaces the backend, and the connections its client holds, with a
+    /// fresh one; the old one stays if a new one cannot be created.
+    fn reset_connection(&mut self) {
+        match create_backend(self.kind, Arc::clone(&self.abort)) {
+            Ok(backend) => {
+                self.backend = backend;
+                self.last_cert = None;
+            }
+            Err(err) => warn!("probe client reset failed: {err}"),
+        }
+    }
+}
+
+impl Sampler for DemoGenerator {
+    fn sample(
+        &mut self,
+        target: &TargetConfig,
+        profile: &ProfileConfig,
+        _pinned_ip: Option<IpAddr>,
+    ) -> ProbeSample {
+        self.next_sample(target, profile, SystemTime::now())
+    }
+}
+
 pub struct WorkerHandle {
     pub sender: Sender<ControlMessage>,
     pub join: Option<JoinHandle<()>>,
+    /// Set on stop so a transfer in flight is cut short
+    pub abort: Arc<AtomicBool>,
+}
+
+impl WorkerHandle {
+    /// Handle with no thread behind it, for profiles fed by a session replay.
+    /// Control messages sent to it are dropped.
+    pub fn idle() -> Self {
+        let (sender, _) = crossbeam_channel::unbounded();
+        Self {
+            sender,
+            join: None,
+            abort: Arc::default(),
+        }
+    }
+
+    /// Asks the worker to exit, aborting any probe in flight, without waiting
+    /// for it. Returns the thread to join once it has finished.
+    pub fn stop(&mut self) -> Option<JoinHandle<()>> {
+        self.abort.store(true, Ordering::Relaxed);
+        let _ = self.sender.send(ControlMessage::Stop);
+        self.join.take()
+    }
+}
+
+/// Effective interval after `failures` consecutive failed probes: doubled per
+/// failure, capped at [`BACKOFF_CAP`] or `interval` if that is longer.
+pub fn backoff_interval(interval: Duration, failures: u32) -> Duration {
+    let factor = 1u32.checked_shl(failures).unwrap_or(u32::MAX);
+    interval
+        .saturating_mul(factor)
+        .min(interval.max(BACKOFF_CAP))
 }
 
+/// Spawns a worker that waits `start_delay` before its first probe, so that
+/// profiles of one target can be staggered across the interval. When the
+/// target uses a DNS worker, probes connect to the answer it keeps in `dns`.
 pub fn spawn_profile_worker(
     target: TargetConfig,
     profile: ProfileConfig,
+    start_delay: Duration,
+    source: ProbeSource,
+    dns: DnsCache,
+    sample_tx: Sender<ProbeSample>,
+) -> WorkerHandle {
+    spawn_worker(target, profile, start_delay, source, dns, None, sample_tx)
+}
+
+/// Spawns the per-IP stream of `profile` that always connects to `ip`,
+/// whatever the DNS answer says; its samples carry `ip` as their endpoint.
+pub fn spawn_endpoint_worker(
+    target: TargetConfig,
+    profile: ProfileConfig,
+    ip: IpAddr,
+    start_delay: Duration,
+    source: ProbeSource,
+    sample_tx: Sender<ProbeSample>,
+) -> WorkerHandle {
+    spawn_worker(
+        target,
+        profile,
+        start_delay,
+        source,
+        DnsCache::default(),
+        Some(ip),
+        sample_tx,
+    )
+}
+
+fn spawn_worker(
+    target: TargetConfig,
+    profile: ProfileConfig,
+    start_delay: Duration,
+    source: ProbeSource,
+    dns: DnsCache,
+    endpoint: Option<IpAddr>,
     sample_tx: Sender<ProbeSample>,
 ) -> WorkerHandle {
     let (tx, rx) = crossbeam_channel::unbounded();
-    let join = thread::spawn(move || run_worker(target, profile, rx, sample_tx));
+    let abort = Arc::new(AtomicBool::new(false));
+    let worker_abort = Arc::clone(&abort);
+    let join = thread::spawn(move || {
+        let shared = NetworkShared {
+            abort: worker_abort,
+            dns,
+            endpoint,
+        };
+        run_worker(target, profile, start_delay, source, shared, rx, sample_tx)
+    });
     WorkerHandle {
         sender: tx,
         join: Some(join),
+        abort,
     }
 }
 
+/// State a network worker shares with the app: the flag that aborts its
+/// probe in flight and the target's DNS answer, plus the address a per-IP
+/// stream is pinned to.
+struct NetworkShared {
+    abort: Arc<AtomicBool>,
+    dns: DnsCache,
+    endpoint: Option<IpAddr>,
+}
+
 fn run_worker(
     mut target: TargetConfig,
     mut profile: ProfileConfig,
+    start_delay: Duration,
+    source: ProbeSource,
+    shared: NetworkShared,
     control_rx: Receiver<ControlMessage>,
     sample_tx: Sender<ProbeSample>,
+) {
+    info!(target_url = %target.url, profile = %profile.name, ?start_delay, ?source, "worker spawned");
+    match source {
+        ProbeSource::Network(kind) => match create_backend(kind, Arc::clone(&shared.abort)) {
+            Ok(backend) => probe_loop(
+                &mut target,
+                &mut profile,
+                start_delay,
+                &control_rx,
+                &sample_tx,
+                &mut NetworkSampler {
+                    backend,
+                    kind,
+                    abort: shared.abort,
+                    last_cert: None,
+                    dns: shared.dns,
+                    endpoint: shared.endpoint,
+                },
+            ),
+            Err(err) => {
+                warn!(target_url = %target.url, profile = %profile.name, "probe client init failed: {err}");
+                deliver(
+                    &sample_tx,
+                    error_sample(
+                        target.id,
+                        profile.id,
+                        ProbeErrorKind::IoError,
+                        format!("probe client init failed: {err}"),
+                    ),
+                    &DROPPED_SAMPLES,
+                );
+                return;
+            }
+        },
+        ProbeSource::Demo(seed) => {
+            let mut generator = DemoGenerator::new(seed, &target, &profile);
+            probe_loop(
+                &mut target,
+                &mut profile,
+                start_delay,
+                &control_rx,
+                &sample_tx,
+                &mut generator,
+            );
+        }
+    }
+    info!(target_url = %target.url, profile = %profile.name, "worker stopped");
+}
+
+fn probe_loop(
+    target: &mut TargetConfig,
+    profile: &mut ProfileConfig,
+    start_delay: Duration,
+    control_rx: &Receiver<ControlMessage>,
+    sample_tx: &Sender<ProbeSample>,
+    sampler: &mut impl Sampler,
 ) {
     let mut paused = false;
-    let mut resolved_ip: Option<IpAddr> = None;
-    let mut client = match ProbeClient::new() {
-        Ok(client) => client,
-        Err(err) => {
-            let _ = sample_tx.send(error_sample(
-                target.id,
-                profile.id,
-                ProbeErrorKind::IoError,
-                format!("probe client init failed: {err}"),
-            ));
-            return;
+    let mut burst_until: Option<Instant> = None;
+    let mut failures: u32 = 0;
+    let mut connection = ConnectionState::default();
+    let mut jitter = JitterRng::seeded(profile.id.as_u128() as u64);
+
+    // Wait out the stagger delay; a zero wait still drains control messages
+    // (e.g. a global pause) queued before the first probe.
+    let start_at = Instant::now() + start_delay;
+    loop {
+        let remaining = start_at.saturating_duration_since(Instant::now());
+        match control_rx.recv_timeout(remaining) {
+            Ok(message) => {
+                if !apply_control(
+                    message,
+                    target,
+                    profile,
+                    &mut paused,
+                    &mut burst_until,
+                    &mut connection,
+                    sampler,
+                ) {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
+            Err(RecvTimeoutError::Timeout) => break,
         }
-    };
+    }
 
-    // Perform initial probe immediately (don't wait for interval)
-    let sample = client.probe(&target, &profile, resolved_ip);
-    if let Some(remote) = sample.remote {
-        resolved_ip = Some(remote.ip());
+    // Perform initial probe as soon as the stagger delay has elapsed; scheduled
+    // targets wait for their first fire time instead
+    let mut last_probe: Option<SystemTime> = None;
+    // Next interval slot and the step that led to it; `None` starts a fresh
+    // grid one step from now. Slots are wall-clock times, which keep running
+    // while the machine sleeps, so a suspend shows up as skipped slots
+    let mut due: Option<(SystemTime, Duration)> = None;
+    let mut missed_since_probe: u32 = 0;
+    if !paused && target.schedule.is_none() {
+        let slot = SystemTime::now();
+        let sample = take_sample(sampler, target, profile, &mut connection);
+        last_probe = Some(sample.ts);
+        failures = next_failures(failures, &sample);
+        deliver(sample_tx, sample, &DROPPED_SAMPLES);
+        let step = interval_step(target, profile, failures, false, &mut jitter);
+        let (next, skipped) = next_slot(slot, step, SystemTime::now());
+        due = Some((next, step));
+        missed_since_probe = skipped;
     }
-    let _ = sample_tx.send(sample);
+
+    let mut last_fire: Option<SystemTime> = None;
 
     loop {
+        // Resuming or a new interval starts the grid afresh rather than
+        // catching up on slots from before the change
+        let before = cadence(target, profile, paused, burst_until);
         if paused {
             match control_rx.recv() {
-                Ok(ControlMessage::Pause(flag)) => paused = flag,
-                Ok(ControlMessage::UpdateTarget(cfg)) => target = *cfg,
-                Ok(ControlMessage::UpdateProfile(cfg)) => profile = *cfg,
-                Ok(ControlMessage::Stop) | Err(_) => break,
+                Ok(message) => {
+                    if !apply_control(
+                        message,
+                        target,
+                        profile,
+                        &mut paused,
+                        &mut burst_until,
+                        &mut connection,
+                        sampler,
+                    ) {
+                        return;
+                    }
+                }
+                Err(_) => return,
+            }
+            if cadence(target, profile, paused, burst_until) != before {
+                due = None;
+                last_probe = None;
             }
             continue;
         }
 
-        match control_rx.recv_timeout(target.interval) {
-            Ok(ControlMessage::Pause(flag)) => paused = flag,
-            Ok(ControlMessage::UpdateTarget(cfg)) => target = *cfg,
-            Ok(ControlMessage::UpdateProfile(cfg)) => profile = *cfg,
-            Ok(ControlMessage::Stop) => break,
-            Err(RecvTimeoutError::Disconnected) => break,
+        let bursting = burst_until.is_some_and(|until| Instant::now() < until);
+        let now = SystemTime::now();
+        let (wait, fire_at) = match &target.schedule {
+            Some(schedule) if !bursting => {
+                // Never fire the same slot twice, even if the wait ends a little early
+                let from = last_fire.map_or(now, |fired| fired.max(now));
+                match schedule.next_after(from) {
+                    Some(next) => {
+                        let until = next.duration_since(now).unwrap_or_default();
+                        if until > SCHEDULE_RECHECK {
+                            (SCHEDULE_RECHECK, None)
+                        } else {
+                            (until, Some(next))
+                        }
+                    }
+                    None => (SCHEDULE_RECHECK, None),
+                }
+            }
+            _ => {
+                let (slot, step) = match due {
+                    Some(due) => due,
+                    None => {
+                        let step = interval_step(target, profile, failures, bursting, &mut jitter);
+                        (now + step, step)
+                    }
+                };
+                let slot = keep_slot_ahead(slot, step, now);
+                due = Some((slot, step));
+                let until = slot.duration_since(now).unwrap_or_default();
+                (until.min(SCHEDULE_RECHECK), Some(slot))
+            }
+        };
+        match control_rx.recv_timeout(wait) {
+            Ok(message) => {
+                if !apply_control(
+                    message,
+                    target,
+                    profile,
+                    &mut paused,
+                    &mut burst_until,
+                    &mut connection,
+                    sampler,
+                ) {
+                    return;
+                }
+                if cadence(target, profile, paused, burst_until) != before {
+                    due = None;
+                    last_probe = None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => return,
             Err(RecvTimeoutError::Timeout) => {
-                let sample = client.probe(&target, &profile, resolved_ip);
-                if let Some(remote) = sample.remote {
-                    resolved_ip = Some(remote.ip());
+                let now = SystemTime::now();
+                let Some(slot) = fire_at.filter(|slot| *slot <= now) else {
+                    continue;
+                };
+                let on_schedule = target.schedule.is_some() && !bursting;
+                if on_schedule {
+                    let missed = target.schedule.as_ref().map_or(0, |schedule| {
+                        schedule.fires_between(slot, now, MAX_MISSED_SLOTS)
+                    });
+                    missed_since_probe = missed_since_probe.saturating_add(missed);
+                    last_fire = Some(slot);
                 }
-                let _ = sample_tx.send(sample);
+                let mut sample = take_sample(sampler, target, profile, &mut connection);
+                sample.burst = bursting;
+                sample.missed_slots = std::mem::take(&mut missed_since_probe);
+                sample.since_last_probe =
+                    last_probe.and_then(|previous| sample.ts.duration_since(previous).ok());
+                last_probe = Some(sample.ts);
+                failures = next_failures(failures, &sample);
+                // Slots passed while waiting for the download limiter were
+                // deferred by it, not missed
+                let waited_until = sample.limiter_wait.map(|wait| slot + wait);
+                if on_schedule {
+                    sample.deferred_slots = match (&target.schedule, waited_until) {
+                        (Some(schedule), Some(until)) => {
+                            schedule.fires_between(slot, until, MAX_MISSED_SLOTS)
+                        }
+                        _ => 0,
+                    };
+                } else {
+                    let still_bursting = burst_until.is_some_and(|until| Instant::now() < until);
+                    let step =
+                        interval_step(target, profile, failures, still_bursting, &mut jitter);
+                    let (next, skipped) = next_slot(slot, step, SystemTime::now());
+                    let deferred = deferred_slots(slot, step, waited_until, skipped);
+                    due = Some((next, step));
+                    sample.deferred_slots = deferred;
+                    missed_since_probe = skipped - deferred;
+                }
+                deliver(sample_tx, sample, &DROPPED_SAMPLES);
             }
         }
     }
 }
 
+/// What the interval grid depends on; a change in it restarts the grid.
+fn cadence(
+    target: &TargetConfig,
+    profile: &ProfileConfig,
+    paused: bool,
+    burst_until: Option<Instant>,
+) -> (bool, Option<Instant>, Duration, u8, bool) {
+    (
+        paused,
+        burst_until,
+        target.interval_for(profile),
+        target.jitter_pct,
+        target.schedule.is_some(),
+    )
+}
+
+/// Gap to the next interval slot: the burst pace during a burst, otherwise
+/// the backed-off interval with jitter applied.
+fn interval_step(
+    target: &TargetConfig,
+    profile: &ProfileConfig,
+    failures: u32,
+    bursting: bool,
+    jitter: &mut JitterRng,
+) -> Duration {
+    if bursting {
+        return BURST_INTERVAL;
+    }
+    jitter.apply(
+        backoff_interval(target.interval_for(profile), failures),
+        target.jitter_pct,
+    )
+}
+
+/// Slot after `slot` on a grid of `step`: the first one `now` has not passed
+/// yet, with the count of slots skipped on the way. A probe that overran
+/// its slot pushes the next one back by whole steps instead of bunching
+/// probes, so the grid never drifts by the probe's own duration.
+fn next_slot(slot: SystemTime, step: Duration, now: SystemTime) -> (SystemTime, u32) {
+    let next = slot + step;
+    let Ok(behind) = now.duration_since(next) else {
+        return (next, 0);
+    };
+    if behind.is_zero() || step.is_zero() {
+        return (next.max(now), 0);
+    }
+    let skipped = behind.as_nanos().div_ceil(step.as_nanos());
+    match u32::try_from(skipped) {
+        Ok(skipped) if skipped <= MAX_MISSED_SLOTS => (next + step * skipped, skipped),
+        // Too far behind to step there, e.g. after a clock jump: start over
+        _ => (now + step, MAX_MISSED_SLOTS),
+    }
+}
+
+/// How many of the `skipped` slots after `slot` had already passed when the
+/// probe's wait for the download limiter ended at `waited_until`.
+fn deferred_slots(
+    slot: SystemTime,
+    step: Duration,
+    waited_until: Option<SystemTime>,
+    skipped: u32,
+) -> u32 {
+    waited_until.map_or(0, |until| next_slot(slot, step, until).1.min(skipped))
+}
+
+/// `slot`, unless the wall clock stepped back by more than `step` since it
+/// was set: then one step from `now`, so the worker never waits out the jump.
+fn keep_slot_ahead(slot: SystemTime, step: Duration, now: SystemTime) -> SystemTime {
+    match slot.duration_since(now) {
+        Ok(ahead) if ahead > step => now + step,
+        _ => slot,
+    }
+}
+
+/// Applies one control message to the worker state; returns `false` on `Stop`.
+fn apply_control(
+    message: ControlMessage,
+    target: &mut TargetConfig,
+    profile: &mut ProfileConfig,
+    paused: &mut bool,
+    burst_until: &mut Option<Instant>,
+    connection: &mut ConnectionState,
+    sampler: &mut impl Sampler,
+) -> bool {
+    match message {
+        ControlMessage::Pause(flag) => {
+            if *paused != flag {
+                info!(target_url = %target.url, profile = %profile.name, paused = flag, "worker pause changed");
+            }
+            *paused = flag;
+        }
+        ControlMessage::Burst(until) => {
+            info!(target_url = %target.url, profile = %profile.name, "burst started");
+            *burst_until = Some(until);
+        }
+        ControlMessage::UpdateTarget(cfg) => {
+            info!(target_url = %cfg.url, profile = %profile.name, "target config updated");
+            // Drop the pinned address when the URL moved so the new host is resolved afresh
+            if target.url != cfg.url {
+                connection.resolved_ip = None;
+            }
+            *target = *cfg;
+        }
+        ControlMessage::UpdateProfile {
+            config,
+            reset_connection,
+        } => {
+            info!(target_url = %target.url, profile = %config.name, reset_connection, "profile config updated");
+            *profile = *config;
+            if reset_connection {
+                sampler.reset_connection();
+                connection.primed = false;
+            }
+        }
+        ControlMessage::Stop => return false,
+    }
+    true
+}
+
+/// Sends `sample` to the UI, waiting up to [`SAMPLE_SEND_WAIT`] while the
+/// channel is full. A sample that still does not fit is dropped and counted in
+/// `dropped` rather than stalling the worker's schedule.
+fn deliver(sample_tx: &Sender<ProbeSample>, sample: ProbeSample, dropped: &AtomicU64) {
+    if let Err(SendTimeoutError::Timeout(sample)) = sample_tx.send_timeout(sample, SAMPLE_SEND_WAIT)
+    {
+        dropped.fetch_add(1, Ordering::Relaxed);
+        warn!(profile_id = %sample.profile_id, "sample channel full, sample dropped");
+    }
+}
+
+/// Consecutive failures after `sample`; any success resets the count.
+fn next_failures(failures: u32, sample: &ProbeSample) -> u32 {
+    match sample.result {
+        ProbeResult::Ok => 0,
+        ProbeResult::Err(_) => failures.saturating_add(1),
+    }
+}
+
+/// What a worker keeps about its connection from one probe to the next.
+#[derive(Debug, Default)]
+struct ConnectionState {
+    /// Address the last probe reached, which later probes are pinned to
+    resolved_ip: Option<IpAddr>,
+    /// Whether a warm profile has a reusable connection up
+    primed: bool,
+}
+
+/// Takes one sample, pinning later probes to the address it reached. On a
+/// warm profile the first sample without a live connection is marked as
+/// priming.
+fn take_sample(
+    sampler: &mut impl Sampler,
+    target: &TargetConfig,
+    profile: &ProfileConfig,
+    connection: &mut ConnectionState,
+) -> ProbeSample {
+    let mut sample = sampler.sample(target, profile, connection.resolved_ip);
+    if let Some(remote) = sample.remote {
+        connection.resolved_ip = Some(remote.ip());
+    }
+    if profile.conn_reuse == ConnReusePolicy::Warm && profile.method != ProbeMethod::Ping {
+        sample.priming = !connection.primed;
+        connection.primed = match &sample.result {
+            ProbeResult::Ok => true,
+            ProbeResult::Err(err) => connection.primed && !err.kind.breaks_connection(),
+        };
+    }
+    sample
+}
+
+/// Runs one probe, preceded by an explicit lookup when DNS probing is enabled
+/// so the sample carries the full resolved address set. With a DNS worker
+/// the lookup is its job: the probe skips it and connects to the worker's
+/// answer, falling back to curl's own resolution while there is none. A
+/// per-IP stream never looks up: it always connects to its `endpoint`.
+fn probe_once(
+    backend: &mut dyn Sampler,
+    target: &TargetConfig,
+    profile: &ProfileConfig,
+    pinned_ip: Option<IpAddr>,
+    dns: &DnsCache,
+    endpoint: Option<IpAddr>,
+    last_cert: &mut Option<(Instant, Url)>,
+) -> ProbeSample {
+    let without_dns;
+    let (target, pinned_ip) = if endpoint.is_some() || target.uses_dns_worker() {
+        let mut copy = target.clone();
+        copy.dns_enabled = false;
+        without_dns = copy;
+        (&without_dns, endpoint.or(dns.current()).or(pinned_ip))
+    } else {
+        (target, pinned_ip)
+    };
+    // Keep asking until a fresh handshake reports the certificate, then hourly
+    // or as soon as the URL changes
+    if target.url.scheme() == "https"
+        && profile.method != ProbeMethod::Ping
+        && last_cert
+            .as_ref()
+            .is_none_or(|(fetched, url)| *url != target.url || fetched.elapsed() >= CERT_REFRESH)
+    {
+        backend.request_cert_info();
+    }
+    let resolved_ips = if target.resolves_dns() {
+        resolve_target_ips(target)
+    } else {
+        Vec::new()
+    };
+    let mut sample = backend.sample(target, profile, pinned_ip);
+    sample.resolved_ips = resolved_ips;
+    sample.endpoint = endpoint;
+    if let (Some(local), Some(remote)) = (sample.local, sample.remote) {
+        sample.ebpf = take_conn_stats(local, remote);
+    }
+    if sample.cert.is_some() {
+        *last_cert = Some((Instant::now(), target.url.clone()));
+    }
+    match &sample.result {
+        ProbeResult::Ok => debug!(
+            target_url = %target.url,
+            profile = %profile.name,
+            status = sample.http_status,
+            total_ms = sample.t_total.as_secs_f64() * 1000.0,
+            "probe ok"
+        ),
+        ProbeResult::Err(err) => warn!(
+            target_url = %target.url,
+            profile = %profile.name,
+            kind = err.kind.label(),
+            "probe failed: {}",
+            err.message
+        ),
+    }
+    sample
+}
+
+/// Small xorshift generator used to spread probe intervals; not for crypto.
+struct JitterRng(u64);
+
+impl JitterRng {
+    fn seeded(salt: u64) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(0);
+        Self((nanos ^ salt) | 1)
+    }
+
+    /// Uniform value in `[-1.0, 1.0]`.
+    fn next_signed_unit(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+
+    /// Scales `interval` by a random factor within `± pct` percent.
+    fn apply(&mut self, interval: Duration, pct: u8) -> Duration {
+        if pct == 0 {
+            return interval;
+        }
+        let spread = f64::from(pct.min(crate::config::MAX_JITTER_PCT)) / 100.0;
+        interval.mul_f64(1.0 + self.next_signed_unit() * spread)
+    }
+}
+
 fn error_sample(
     target_id: crate::config::TargetId,
     profile_id: crate::config::ProfileId,
     kind: ProbeErrorKind,
     message: String,
+) -> ProbeSample {
+    blank_sample(
+        target_id,
+        profile_id,
+        ProbeResult::Err(ProbeError {
+            kind,
+            message: message.into(),
+        }),
+    )
+}
+
+/// Sample taken now with `result` and nothing measured.
+fn blank_sample(
+    target_id: crate::config::TargetId,
+    profile_id: crate::config::ProfileId,
+    result: ProbeResult,
 ) -> ProbeSample {
     ProbeSample {
         ts: SystemTime::now(),
         target_id,
         profile_id,
-        result: ProbeResult::Err(ProbeError { kind, message }),
+        result,
         http_status: None,
         negotiated: crate::probe::NegotiatedProtocol {
             alpn: None,
             tls_version: None,
+            tls_version_configured: false,
             cipher: None,
         },
         t_dns: None,
@@ -112,9 +825,303 @@ fn error_sample(
         t_download: Duration::from_millis(0),
         t_total: Duration::from_millis(0),
         downloaded_bytes: 0,
+        content_length: None,
         local: None,
         remote: None,
+        resolved_ips: Vec::new(),
         tcp_info: None,
         ebpf: None,
+        cert: None,
+        ping: None,
+        burst: false,
+        priming: false,
+        truncated: false,
+        new_connections: None,
+        insecure: false,
+        response_headers: Vec::new(),
+        endpoint: None,
+        missed_slots: 0,
+        deferred_slots: 0,
+        limiter_wait: None,
+        since_last_probe: None,
+        tcp_info_errno: None,
+        http_version_fallback: false,
+        failed_phase: None,
+        jitter_ms: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BACKOFF_CAP, ConnectionState, ControlMessage, DnsCache, JitterRng, MAX_MISSED_SLOTS,
+        ProbeSource, Sampler, apply_control, backoff_interval, deferred_slots, deliver,
+        keep_slot_ahead, next_slot, spawn_profile_worker, take_sample,
+    };
+    use crate::config::{
+        ConnReusePolicy, ProfileConfig, TargetConfig, TlsVersion, default_profiles,
+    };
+    use crate::features::probe::testing::SampleBuilder;
+    use crate::probe::{ProbeErrorKind, ProbeSample};
+    use std::collections::VecDeque;
+    use std::net::IpAddr;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::{Duration, UNIX_EPOCH};
+    use url::Url;
+
+    /// Replays a fixed list of outcomes; `None` is a success.
+    struct Scripted(VecDeque<Option<ProbeErrorKind>>);
+
+    impl Sampler for Scripted {
+        fn sample(
+            &mut self,
+            target: &TargetConfig,
+            profile: &ProfileConfig,
+            _pinned_ip: Option<IpAddr>,
+        ) -> ProbeSample {
+            let sample = SampleBuilder::ok(target.id, profile.id);
+            match self.0.pop_front().flatten() {
+                Some(kind) => sample.failed(kind).build(),
+                None => sample.build(),
+            }
+        }
+    }
+
+    #[test]
+    fn warm_profiles_mark_the_sample_after_start_or_a_connection_failure_as_priming() {
+        let mut target = TargetConfig::new(
+            Url::parse("https://example.com").unwrap(),
+            default_profiles(),
+        );
+        let outcomes = [
+            None,
+            None,
+            Some(ProbeErrorKind::ConnectRefused),
+            None,
+            Some(ProbeErrorKind::HttpStatusError),
+            None,
+        ];
+        let priming = |target: &mut TargetConfig, reuse| -> Vec<bool> {
+            let mut profile = target.profiles[0].clone();
+            profile.conn_reuse = reuse;
+            let mut sampler = Scripted(outcomes.into_iter().collect());
+            let mut connection = ConnectionState::default();
+            (0..outcomes.len())
+                .map(|_| take_sample(&mut sampler, target, &profile, &mut connection).priming)
+                .collect()
+        };
+
+        assert_eq!(
+            priming(&mut target, ConnReusePolicy::Warm),
+            [true, false, false, true, false, false]
+        );
+        assert_eq!(priming(&mut target, ConnReusePolicy::Cold), [false; 6]);
+    }
+
+    #[test]
+    fn protocol_changes_drop_the_warm_connection_and_prime_it_again() {
+        /// Succeeds every time, counting connection resets.
+        struct Resets(u32);
+
+        impl Sampler for Resets {
+            fn sample(
+                &mut self,
+                target: &TargetConfig,
+                profile: &ProfileConfig,
+                _pinned_ip: Option<IpAddr>,
+            ) -> ProbeSample {
+                SampleBuilder::ok(target.id, profile.id).build()
+            }
+
+            fn reset_connection(&mut self) {
+                self.0 += 1;
+            }
+        }
+
+        let target = TargetConfig::new(
+            Url::parse("https://example.com").unwrap(),
+            default_profiles(),
+        );
+        let mut profile = target.profiles[0].clone();
+        profile.conn_reuse = ConnReusePolicy::Warm;
+        let mut sampler = Resets(0);
+        let mut connection = ConnectionState::default();
+        let (mut paused, mut burst_until) = (false, None);
+        let mut update = |profile: &mut ProfileConfig,
+                          connection: &mut ConnectionState,
+                          sampler: &mut Resets,
+                          changed: ProfileConfig| {
+            let reset_connection = profile.changes_connection(&changed);
+            assert!(apply_control(
+                ControlMessage::UpdateProfile {
+                    config: Box::new(changed),
+                    reset_connection,
+                },
+                &mut target.clone(),
+                profile,
+                &mut paused,
+                &mut burst_until,
+                connection,
+                sampler,
+            ));
+        };
+        let probe = |profile: &ProfileConfig, connection: &mut ConnectionState| {
+            take_sample(&mut Resets(0), &target, profile, connection).priming
+        };
+
+        assert!(probe(&profile, &mut connection));
+        assert!(!probe(&profile, &mut connection));
+
+        // A new read cap keeps the connection
+        let mut changed = profile.clone();
+        changed.max_read_bytes *= 2;
+        update(&mut profile, &mut connection, &mut sampler, changed);
+        assert_eq!(sampler.0, 0);
+        assert!(!probe(&profile, &mut connection));
+
+        let mut changed = profile.clone();
+        changed.tls = match changed.tls {
+            TlsVersion::Tls12 => TlsVersion::Tls13,
+            TlsVersion::Tls13 => TlsVersion::Tls12,
+        };
+        update(&mut profile, &mut connection, &mut sampler, changed);
+        assert_eq!(sampler.0, 1);
+        assert!(probe(&profile, &mut connection));
+    }
+
+    #[test]
+    fn full_sample_channel_drops_and_counts_instead_of_blocking() {
+        let target = TargetConfig::new(
+            Url::parse("https://example.com").unwrap(),
+            default_profiles(),
+        );
+        let mut sampler = Scripted(VecDeque::new());
+        let mut next = || sampler.sample(&target, &target.profiles[0], None);
+        let (sample_tx, sample_rx) = crossbeam_channel::bounded(2);
+        let dropped = AtomicU64::new(0);
+
+        // A worker outrunning a stalled UI fills the channel, then loses samples
+        for _ in 0..4 {
+            deliver(&sample_tx, next(), &dropped);
+        }
+        assert_eq!(sample_rx.len(), 2);
+        assert_eq!(dropped.load(Ordering::Relaxed), 2);
+
+        // Once the UI catches up, samples flow again
+        sample_rx.try_recv().unwrap();
+        deliver(&sample_tx, next(), &dropped);
+        assert_eq!(sample_rx.len(), 2);
+        assert_eq!(dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn jitter_stays_within_configured_spread() {
+        let mut rng = JitterRng::seeded(42);
+        let interval = Duration::from_secs(10);
+        assert_eq!(rng.apply(interval, 0), interval);
+        for _ in 0..1_000 {
+            let jittered = rng.apply(interval, 20);
+            assert!(jittered >= Duration::from_secs(8));
+            assert!(jittered <= Duration::from_secs(12));
+        }
+    }
+
+    #[test]
+    fn slots_stay_on_the_interval_grid_however_long_probes_take() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000);
+        let step = Duration::from_secs(5);
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // An 800ms probe every slot never pushes the schedule back
+        let mut slot = start;
+        for _ in 0..100 {
+            let (next, skipped) = next_slot(slot, step, slot + Duration::from_millis(800));
+            assert_eq!(skipped, 0);
+            slot = next;
+        }
+        assert_eq!(slot, start + step * 100);
+
+        // A 12s probe skips the two slots it overran instead of bunching
+        assert_eq!(next_slot(start, step, at(12_000)), (at(15_000), 2));
+        // Finishing exactly on the next slot fires it without skipping
+        assert_eq!(next_slot(start, step, at(5_000)), (at(5_000), 0));
+        assert_eq!(next_slot(start, step, at(5_001)), (at(10_000), 1));
+        // A ten-minute suspend during a 1s interval
+        let second = Duration::from_secs(1);
+        assert_eq!(next_slot(start, second, at(601_000)), (at(601_000), 600));
+        assert_eq!(next_slot(start, Duration::ZERO, at(3_000)), (at(3_000), 0));
+        // A clock jump of years starts a fresh grid
+        let far = start + Duration::from_secs(u64::MAX / 4);
+        assert_eq!(
+            next_slot(start, second, far),
+            (far + second, MAX_MISSED_SLOTS)
+        );
+    }
+
+    #[test]
+    fn slots_passed_waiting_for_a_download_slot_are_deferred_not_missed() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000);
+        let step = Duration::from_secs(5);
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Waited 11s, then probed for 3s: two slots passed during the wait,
+        // the one at 15s during the probe itself
+        let (_, skipped) = next_slot(start, step, at(15_500));
+        assert_eq!(skipped, 3);
+        assert_eq!(deferred_slots(start, step, Some(at(11_000)), skipped), 2);
+        assert_eq!(deferred_slots(start, step, None, skipped), 0);
+        // A short wait defers nothing
+        assert_eq!(deferred_slots(start, step, Some(at(300)), 0), 0);
+    }
+
+    #[test]
+    fn a_clock_stepping_back_never_strands_the_next_slot() {
+        let now = UNIX_EPOCH + Duration::from_secs(10_000);
+        let step = Duration::from_secs(5);
+        let slot = now + Duration::from_secs(3);
+        assert_eq!(keep_slot_ahead(slot, step, now), slot);
+        let stranded = now + Duration::from_secs(3_600);
+        assert_eq!(keep_slot_ahead(stranded, step, now), now + step);
+    }
+
+    #[test]
+    fn backoff_doubles_per_failure_up_to_the_cap() {
+        let second = Duration::from_secs(1);
+        assert_eq!(backoff_interval(second, 0), second);
+        assert_eq!(backoff_interval(second, 3), Duration::from_secs(8));
+        assert_eq!(backoff_interval(second, 10), BACKOFF_CAP);
+        assert_eq!(backoff_interval(second, u32::MAX), BACKOFF_CAP);
+
+        // A configured interval above the cap is never shortened
+        let slow = Duration::from_secs(300);
+        assert_eq!(backoff_interval(slow, 4), slow);
+    }
+
+    #[test]
+    fn demo_worker_feeds_the_sample_channel_without_network() {
+        let target = TargetConfig::new(
+            Url::parse("https://demo.example.com").unwrap(),
+            default_profiles(),
+        );
+        let profile = target.profiles[0].clone();
+        let (sample_tx, sample_rx) = crossbeam_channel::unbounded();
+        let mut worker = spawn_profile_worker(
+            target.clone(),
+            profile.clone(),
+            Duration::ZERO,
+            ProbeSource::Demo(3),
+            DnsCache::default(),
+            sample_tx,
+        );
+
+        let sample = sample_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("demo sample");
+        assert_eq!(sample.target_id, target.id);
+        assert_eq!(sample.profile_id, profile.id);
+        assert!(sample.remote.is_some());
+
+        let _ = worker.sender.send(ControlMessage::Stop);
+        worker.join.take().expect("join handle").join().unwrap();
     }
 }
@@ -0,0 +1,200 @@
+use crate::config::{ProfileId, TargetConfig};
+use crate::probe::{Phase, ProbeResult, ProbeSample};
+use crate::probe_engine::lookup_target_ips;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use std::net::IpAddr;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::{ControlMessage, DROPPED_SAMPLES, WorkerHandle, blank_sample, deliver};
+
+/// Profile id of the samples a target's DNS worker sends; no real profile
+/// has it, so the resolver stream gets its own key in the metrics store.
+pub const RESOLVER_PROFILE_ID: ProfileId = Uuid::nil();
+
+/// How many refresh periods an answer stays usable, so one failed refresh
+/// does not send every probe back to resolving on its own.
+const ANSWER_LIFETIME_REFRESHES: u32 = 2;
+
+#[derive(Clone, Debug)]
+struct DnsAnswer {
+    ips: Vec<IpAddr>,
+    expires: Instant,
+}
+
+/// The latest answer of a target's DNS worker, shared with the target's
+/// profile workers.
+#[derive(Clone, Debug, Default)]
+pub struct DnsCache(Arc<Mutex<Option<DnsAnswer>>>);
+
+impl DnsCache {
+    /// Replaces the answer; it expires after `ttl`.
+    pub fn publish(&self, ips: Vec<IpAddr>, ttl: Duration) {
+        *self.lock() = Some(DnsAnswer {
+            ips,
+            expires: Instant::now() + ttl,
+        });
+    }
+
+    pub fn clear(&self) {
+        *self.lock() = None;
+    }
+
+    /// Address probes should connect to: the first of the answer, while it
+    /// has not expired.
+    pub fn current(&self) -> Option<IpAddr> {
+        self.current_at(Instant::now())
+    }
+
+    fn current_at(&self, now: Instant) -> Option<IpAddr> {
+        self.lock()
+            .as_ref()
+            .filter(|answer| now < answer.expires)
+            .and_then(|answer| answer.ips.first().copied())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<DnsAnswer>> {
+        // The answer is plain data, so a panic elsewhere cannot leave it torn
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Spawns the worker that resolves `target`'s host every `dns_refresh`,
+/// publishes the answer to `cache` and sends each lookup as a sample under
+/// [`RESOLVER_PROFILE_ID`]. It takes the same control messages as a profile
+/// worker; profile updates and bursts do not concern it. While the target
+/// resolves inside every probe instead, it only waits for messages.
+pub fn spawn_dns_worker(
+    target: TargetConfig,
+    cache: DnsCache,
+    sample_tx: Sender<ProbeSample>,
+) -> WorkerHandle {
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let join = thread::spawn(move || run_dns_worker(target, cache, rx, sample_tx));
+    WorkerHandle {
+        sender: tx,
+        join: Some(join),
+        // A lookup cannot be interrupted; it ends on its own
+        abort: Arc::new(AtomicBool::new(false)),
+    }
+}
+
+fn run_dns_worker(
+    mut target: TargetConfig,
+    cache: DnsCache,
+    control_rx: Receiver<ControlMessage>,
+    sample_tx: Sender<ProbeSample>,
+) {
+    info!(target_url = %target.url, refresh = ?target.dns_refresh, "dns worker spawned");
+    let mut paused = false;
+    let mut last_lookup: Option<Instant> = None;
+    loop {
+        let active = !paused && target.uses_dns_worker();
+        let due = last_lookup.map_or_else(Instant::now, |at| at + target.dns_refresh);
+        if active && Instant::now() >= due {
+            last_lookup = Some(Instant::now());
+            deliver(&sample_tx, resolve(&target, &cache), &DROPPED_SAMPLES);
+            continue;
+        }
+        let message = if !active {
+            control_rx
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            control_rx.recv_timeout(due.saturating_duration_since(Instant::now()))
+        };
+        match message {
+            Ok(ControlMessage::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+            Ok(ControlMessage::Pause(flag)) => paused = flag,
+            Ok(ControlMessage::UpdateTarget(cfg)) => {
+                // A new host is looked up at once; the old answer must not be probed
+                if cfg.url != target.url || !cfg.uses_dns_worker() {
+                    cache.clear();
+                    last_lookup = None;
+                }
+                target = *cfg;
+            }
+            Ok(ControlMessage::UpdateProfile { .. } | ControlMessage::Burst(_))
+            | Err(RecvTimeoutError::Timeout) => {}
+        }
+    }
+    info!(target_url = %target.url, "dns worker stopped");
+}
+
+/// Looks the host up once, publishing a successful answer.
+fn resolve(target: &TargetConfig, cache: &DnsCache) -> ProbeSample {
+    let ts = SystemTime::now();
+    let started = Instant::now();
+    let outcome = lookup_target_ips(target);
+    let elapsed = started.elapsed();
+
+    let mut sample = blank_sample(target.id, RESOLVER_PROFILE_ID, ProbeResult::Ok);
+    sample.ts = ts;
+    sample.t_dns = Some(elapsed);
+    sample.t_total = elapsed;
+    match outcome {
+        Ok(ips) => {
+            debug!(target_url = %target.url, ?ips, "dns lookup ok");
+            cache.publish(ips.clone(), target.dns_refresh * ANSWER_LIFETIME_REFRESHES);
+            sample.resolved_ips = ips;
+        }
+        Err(err) => {
+            warn!(target_url = %target.url, kind = err.kind.label(), "dns lookup failed: {}", err.message);
+            sample.failed_phase = Some(Phase::Dns);
+            sample.result = ProbeResult::Err(err);
+        }
+    }
+    sample
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use url::Url;
+
+    #[test]
+    fn cached_answers_expire_after_their_ttl() {
+        let cache = DnsCache::default();
+        assert_eq!(cache.current(), None);
+
+        let ip: IpAddr = "192.0.2.7".parse().unwrap();
+        cache.publish(vec![ip], Duration::from_secs(60));
+        let now = Instant::now();
+        assert_eq!(cache.current_at(now), Some(ip));
+        assert_eq!(cache.current_at(now + Duration::from_secs(61)), None);
+
+        cache.clear();
+        assert_eq!(cache.current_at(now), None);
+    }
+
+    #[test]
+    fn dns_worker_publishes_its_answer_and_sends_a_resolver_sample() {
+        let target = TargetConfig::new(
+            Url::parse("http://127.0.0.1:9/").unwrap(),
+            default_profiles(),
+        );
+        let cache = DnsCache::default();
+        let (sample_tx, sample_rx) = crossbeam_channel::unbounded();
+        let mut worker = spawn_dns_worker(target.clone(), cache.clone(), sample_tx);
+
+        let sample = sample_rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("resolver sample");
+        assert_eq!(sample.target_id, target.id);
+        assert_eq!(sample.profile_id, RESOLVER_PROFILE_ID);
+        assert!(matches!(sample.result, ProbeResult::Ok));
+        assert!(sample.t_dns.is_some());
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(sample.resolved_ips, vec![loopback]);
+        assert_eq!(cache.current(), Some(loopback));
+
+        worker.stop().expect("join handle").join().unwrap();
+    }
+}
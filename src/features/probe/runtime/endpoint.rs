@@ -0,0 +1,45 @@
+use crate::config::ProfileId;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// Profile id the samples of `profile_id`'s per-IP stream to `ip` are
+/// stored under, so each stream gets its own key in the metrics store. The
+/// samples themselves keep the real profile id and name `ip` as endpoint.
+pub fn endpoint_stream_id(profile_id: ProfileId, ip: IpAddr) -> ProfileId {
+    // Mapped IPv4 addresses keep the families apart. Only the unspecified
+    // address is all zero bits, and no lookup returns it
+    let bits = match ip {
+        IpAddr::V4(v4) => v4.to_ipv6_mapped().to_bits(),
+        IpAddr::V6(v6) => v6.to_bits(),
+    };
+    Uuid::from_u128(profile_id.as_u128() ^ bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_ids_differ_per_address_and_from_the_profile() {
+        let profile = Uuid::new_v4();
+        let a: IpAddr = "192.0.2.1".parse().unwrap();
+        let b: IpAddr = "192.0.2.2".parse().unwrap();
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+
+        assert_eq!(
+            endpoint_stream_id(profile, a),
+            endpoint_stream_id(profile, a)
+        );
+        assert_ne!(
+            endpoint_stream_id(profile, a),
+            endpoint_stream_id(profile, b)
+        );
+        assert_ne!(
+            endpoint_stream_id(profile, a),
+            endpoint_stream_id(profile, v6)
+        );
+        for ip in [a, b, v6] {
+            assert_ne!(endpoint_stream_id(profile, ip), profile);
+        }
+    }
+}
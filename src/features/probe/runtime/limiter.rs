@@ -0,0 +1,142 @@
+use crate::config::{ProbeMethod, ProfileConfig};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Body cap above which a GET counts as download-heavy and needs a slot
+/// from the download limiter; the default 4 KiB probes never wait.
+pub const HEAVY_DOWNLOAD_BYTES: u32 = 64 * 1024;
+
+/// How often a waiting worker checks whether it was asked to stop.
+const ABORT_RECHECK: Duration = Duration::from_millis(100);
+
+/// Slots shared by every worker, so at most `limit` download-heavy probes
+/// run at once.
+static DOWNLOADS: DownloadLimiter = DownloadLimiter::new();
+
+/// Caps how many download-heavy probes run at once; `None` runs them all.
+pub fn set_download_slots(limit: Option<u32>) {
+    DOWNLOADS.set_limit(limit);
+}
+
+/// Whether probes of `profile` take a slot from the download limiter.
+pub fn is_heavy_download(profile: &ProfileConfig) -> bool {
+    profile.method == ProbeMethod::Get && profile.max_read_bytes > HEAVY_DOWNLOAD_BYTES
+}
+
+/// Waits for a download slot, giving up once `abort` is set. Returns the
+/// slot, held until dropped, and how long the probe waited for it when all
+/// slots were taken.
+pub(super) fn acquire_download_slot(
+    abort: &AtomicBool,
+) -> Option<(DownloadSlot<'static>, Option<Duration>)> {
+    DOWNLOADS.acquire(abort)
+}
+
+#[derive(Debug, Default)]
+struct Slots {
+    limit: Option<u32>,
+    in_use: u32,
+}
+
+/// Counting semaphore whose size can change while workers wait on it.
+pub(super) struct DownloadLimiter {
+    slots: Mutex<Slots>,
+    freed: Condvar,
+}
+
+impl DownloadLimiter {
+    pub(super) const fn new() -> Self {
+        Self {
+            slots: Mutex::new(Slots {
+                limit: None,
+                in_use: 0,
+            }),
+            freed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Slots> {
+        self.slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(super) fn set_limit(&self, limit: Option<u32>) {
+        // Zero slots would stall every heavy probe for good
+        self.lock().limit = limit.map(|limit| limit.max(1));
+        self.freed.notify_all();
+    }
+
+    pub(super) fn acquire(
+        &self,
+        abort: &AtomicBool,
+    ) -> Option<(DownloadSlot<'_>, Option<Duration>)> {
+        let started = Instant::now();
+        let mut slots = self.lock();
+        let mut waited = false;
+        while slots.limit.is_some_and(|limit| slots.in_use >= limit) {
+            if abort.load(Ordering::Relaxed) {
+                return None;
+            }
+            waited = true;
+            slots = self
+                .freed
+                .wait_timeout(slots, ABORT_RECHECK)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        slots.in_use += 1;
+        let waited = waited.then(|| started.elapsed());
+        Some((DownloadSlot { limiter: self }, waited))
+    }
+}
+
+/// A held download slot, given back on drop.
+pub(super) struct DownloadSlot<'a> {
+    limiter: &'a DownloadLimiter,
+}
+
+impl Drop for DownloadSlot<'_> {
+    fn drop(&mut self) {
+        let mut slots = self.limiter.lock();
+        slots.in_use = slots.in_use.saturating_sub(1);
+        drop(slots);
+        self.limiter.freed.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn waiters_get_a_slot_once_one_is_freed_and_give_up_on_abort() {
+        let limiter = Arc::new(DownloadLimiter::new());
+        limiter.set_limit(Some(1));
+        let never = AtomicBool::new(false);
+        let (held, waited) = limiter.acquire(&never).unwrap();
+        assert_eq!(waited, None);
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            thread::spawn(move || {
+                let never = AtomicBool::new(false);
+                limiter.acquire(&never).map(|(_, waited)| waited)
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        drop(held);
+        let waited = waiter.join().unwrap().unwrap().unwrap();
+        assert!(waited >= Duration::from_millis(40), "{waited:?}");
+
+        let (_held, _) = limiter.acquire(&never).unwrap();
+        assert!(limiter.acquire(&AtomicBool::new(true)).is_none());
+
+        // Lifting the limit lets the blocked probe through at once
+        limiter.set_limit(None);
+        assert!(limiter.acquire(&never).is_some());
+    }
+}
@@ -0,0 +1,106 @@
+//! Synthetic samples for tests.
+
+use super::model::{NegotiatedProtocol, ProbeError, ProbeErrorKind, ProbeResult, ProbeSample};
+use crate::config::{ProfileId, TargetId};
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+
+/// Builds a [`ProbeSample`]: a successful h2/TLS 1.3 probe taken now, with
+/// phases that add up to `total_ms`.
+pub(crate) struct SampleBuilder {
+    sample: ProbeSample,
+}
+
+impl SampleBuilder {
+    pub(crate) fn ok(target_id: TargetId, profile_id: ProfileId) -> Self {
+        let ms = Duration::from_millis;
+        Self {
+            sample: ProbeSample {
+                ts: SystemTime::now(),
+                target_id,
+                profile_id,
+                result: ProbeResult::Ok,
+                http_status: Some(200),
+                negotiated: NegotiatedProtocol {
+                    alpn: Some("h2".to_string()),
+                    tls_version: Some("TLSv1.3".to_string()),
+                    tls_version_configured: false,
+                    cipher: None,
+                },
+                t_dns: Some(ms(2)),
+                t_connect: ms(5),
+                t_tls: Some(ms(8)),
+                t_ttfb: ms(12),
+                t_download: ms(38),
+                t_total: ms(50),
+                downloaded_bytes: 1024,
+                content_length: Some(1024),
+                local: None,
+                remote: None,
+                resolved_ips: Vec::new(),
+                tcp_info: None,
+                ebpf: None,
+                cert: None,
+                ping: None,
+                burst: false,
+                priming: false,
+                truncated: false,
+                new_connections: None,
+                insecure: false,
+                response_headers: Vec::new(),
+                endpoint: None,
+                missed_slots: 0,
+                deferred_slots: 0,
+                limiter_wait: None,
+                since_last_probe: None,
+                tcp_info_errno: None,
+                http_version_fallback: false,
+                failed_phase: None,
+                jitter_ms: None,
+            },
+        }
+    }
+
+    /// Sets the total latency; the download phase absorbs the difference.
+    pub(crate) fn total_ms(mut self, total_ms: u64) -> Self {
+        let total = Duration::from_millis(total_ms);
+        self.sample.t_download = total.saturating_sub(self.sample.t_ttfb);
+        self.sample.t_total = total;
+        self
+    }
+
+    pub(crate) fn at(mut self, ts: SystemTime) -> Self {
+        self.sample.ts = ts;
+        self
+    }
+
+    pub(crate) fn remote(mut self, addr: SocketAddr) -> Self {
+        self.sample.remote = Some(addr);
+        self
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: &str) -> Self {
+        self.sample
+            .response_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Turns the sample into a failure of `kind` with no response, in the
+    /// phase the kind points at.
+    pub(crate) fn failed(mut self, kind: ProbeErrorKind) -> Self {
+        self.sample.failed_phase = kind.phase();
+        self.sample.result = ProbeResult::Err(ProbeError {
+            kind,
+            message: format!("{kind:?}").into(),
+        });
+        self.sample.http_status = None;
+        self.sample.downloaded_bytes = 0;
+        self.sample.content_length = None;
+        self
+    }
+
+    pub(crate) fn build(self) -> ProbeSample {
+        self.sample
+    }
+}
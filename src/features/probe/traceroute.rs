@@ -0,0 +1,452 @@
+use crate::config::TargetId;
+use crossbeam_channel::Sender;
+use std::net::{IpAddr, SocketAddr};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Maximum TTL probed before giving up on reaching the target.
+pub const TRACEROUTE_MAX_HOPS: u8 = 30;
+/// How long each TTL-limited connect waits for a reply.
+pub const TRACEROUTE_HOP_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceHop {
+    pub ttl: u8,
+    /// Router (or target) that answered; `None` when the hop stayed silent
+    pub addr: Option<IpAddr>,
+    pub rtt: Option<Duration>,
+    /// The target itself answered at this TTL
+    pub reached: bool,
+}
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum TracerouteError {
+    #[error("traceroute is not supported on this platform")]
+    Unsupported,
+    #[error("no resolved address for this target yet")]
+    NoAddress,
+    #[error("socket error: {0}")]
+    Socket(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum TracerouteEvent {
+    Hop(TraceHop),
+    Finished,
+    Failed(TracerouteError),
+}
+
+#[derive(Clone, Debug)]
+pub struct TracerouteUpdate {
+    pub target_id: TargetId,
+    pub event: TracerouteEvent,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TracerouteStatus {
+    Running,
+    Finished,
+    Failed,
+}
+
+/// Result of the most recent traceroute for a target, filled in as hops arrive.
+#[derive(Clone, Debug)]
+pub struct TracerouteRun {
+    /// Address being traced; `None` when the run could not start
+    pub destination: Option<SocketAddr>,
+    pub started: SystemTime,
+    pub hops: Vec<TraceHop>,
+    pub status: TracerouteStatus,
+    pub error: Option<TracerouteError>,
+}
+
+impl TracerouteRun {
+    pub fn new(destination: SocketAddr) -> Self {
+        Self {
+            destination: Some(destination),
+            started: SystemTime::now(),
+            hops: Vec::new(),
+            status: TracerouteStatus::Running,
+            error: None,
+        }
+    }
+
+    /// A run that failed before any probe was sent.
+    pub fn failed(error: TracerouteError) -> Self {
+        Self {
+            destination: None,
+            started: SystemTime::now(),
+            hops: Vec::new(),
+            status: TracerouteStatus::Failed,
+            error: Some(error),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == TracerouteStatus::Running
+    }
+
+    pub fn apply(&mut self, event: TracerouteEvent) {
+        match event {
+            TracerouteEvent::Hop(hop) => self.hops.push(hop),
+            TracerouteEvent::Finished => self.status = TracerouteStatus::Finished,
+            TracerouteEvent::Failed(err) => {
+                self.status = TracerouteStatus::Failed;
+                self.error = Some(err);
+            }
+        }
+    }
+}
+
+/// Runs a traceroute on a background thread, posting each hop and the final
+/// outcome to `updates`.
+pub fn spawn_traceroute(
+    target_id: TargetId,
+    destination: SocketAddr,
+    updates: Sender<TracerouteUpdate>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let result = trace(
+            destination,
+            TRACEROUTE_MAX_HOPS,
+            TRACEROUTE_HOP_TIMEOUT,
+            |hop| {
+                let _ = updates.send(TracerouteUpdate {
+                    target_id,
+                    event: TracerouteEvent::Hop(hop),
+                });
+            },
+        );
+        let event = match result {
+            Ok(()) => TracerouteEvent::Finished,
+            Err(err) => TracerouteEvent::Failed(err),
+        };
+        let _ = updates.send(TracerouteUpdate { target_id, event });
+    })
+}
+
+/// Traces the path to `destination` with successive TTL-limited TCP connects
+/// to the target port, stopping once the target answers or `max_hops` is hit.
+///
+/// Intermediate routers are identified from the ICMP time-exceeded errors the
+/// kernel queues on the socket, so no raw-socket privileges are needed.
+pub fn trace(
+    destination: SocketAddr,
+    max_hops: u8,
+    hop_timeout: Duration,
+    mut on_hop: impl FnMut(TraceHop),
+) -> Result<(), TracerouteError> {
+    for ttl in 1..=max_hops {
+        let hop = probe_hop(destination, ttl, hop_timeout)?;
+        let reached = hop.reached;
+        on_hop(hop);
+        if reached {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_hop(
+    _destination: SocketAddr,
+    _ttl: u8,
+    _timeout: Duration,
+) -> Result<TraceHop, TracerouteError> {
+    Err(TracerouteError::Unsupported)
+}
+
+#[cfg(target_os = "linux")]
+fn probe_hop(
+    destination: SocketAddr,
+    ttl: u8,
+    timeout: Duration,
+) -> Result<TraceHop, TracerouteError> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::Instant;
+
+    let socket_error = |call: &str| {
+        TracerouteError::Socket(format!("{call}: {}", std::io::Error::last_os_error()))
+    };
+    let set_option =
+        |fd: libc::c_int, level: libc::c_int, name: libc::c_int, value: libc::c_int| {
+            // SAFETY: `value` outlives the call and the length matches its type.
+            let rc = unsafe {
+                libc::setsockopt(
+                    fd,
+                    level,
+                    name,
+                    &value as *const libc::c_int as *const libc::c_void,
+                    std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+                )
+            };
+            if rc == 0 {
+                Ok(())
+            } else {
+                Err(socket_error("setsockopt"))
+            }
+        };
+
+    let family = match destination {
+        SocketAddr::V4(_) => libc::AF_INET,
+        SocketAddr::V6(_) => libc::AF_INET6,
+    };
+    // SAFETY: plain socket(2) call; the descriptor is owned immediately below.
+    let raw = unsafe {
+        libc::socket(
+            family,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            0,
+        )
+    };
+    if raw < 0 {
+        return Err(socket_error("socket"));
+    }
+    // SAFETY: `raw` is a freshly created descriptor not owned elsewhere.
+    let socket = unsafe { OwnedFd::from_raw_fd(raw) };
+    let fd = socket.as_raw_fd();
+
+    match destination {
+        SocketAddr::V4(_) => {
+            set_option(fd, libc::IPPROTO_IP, libc::IP_TTL, ttl as libc::c_int)?;
+            set_option(fd, libc::IPPROTO_IP, libc::IP_RECVERR, 1)?;
+        }
+        SocketAddr::V6(_) => {
+            set_option(
+                fd,
+                libc::IPPROTO_IPV6,
+                libc::IPV6_UNICAST_HOPS,
+                ttl as libc::c_int,
+            )?;
+            set_option(fd, libc::IPPROTO_IPV6, libc::IPV6_RECVERR, 1)?;
+        }
+    }
+
+    let (addr, addr_len) = to_sockaddr(destination);
+    let started = Instant::now();
+    // SAFETY: `addr` is a valid sockaddr of `addr_len` bytes.
+    let rc = unsafe {
+        libc::connect(
+            fd,
+            &addr as *const libc::sockaddr_storage as *const libc::sockaddr,
+            addr_len,
+        )
+    };
+    if rc != 0 {
+        let err = std::io::Error::last_os_error();
+        if err.raw_os_error() != Some(libc::EINPROGRESS) {
+            return Ok(classify_error(
+                fd,
+                destination,
+                ttl,
+                err.raw_os_error(),
+                started.elapsed(),
+            ));
+        }
+    }
+
+    let mut poll_fd = libc::pollfd {
+        fd,
+        events: libc::POLLOUT,
+        revents: 0,
+    };
+    let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as libc::c_int;
+    // SAFETY: `poll_fd` is a single valid pollfd.
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+    let elapsed = started.elapsed();
+    if ready < 0 {
+        return Err(socket_error("poll"));
+    }
+    if ready == 0 {
+        return Ok(TraceHop {
+            ttl,
+            addr: None,
+            rtt: None,
+            reached: false,
+        });
+    }
+
+    let mut so_error: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    // SAFETY: `so_error`/`len` describe a valid c_int buffer.
+    let rc = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_ERROR,
+            &mut so_error as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if rc != 0 {
+        return Err(socket_error("getsockopt"));
+    }
+    Ok(classify_error(
+        fd,
+        destination,
+        ttl,
+        (so_error != 0).then_some(so_error),
+        elapsed,
+    ))
+}
+
+/// Turns a connect outcome into a hop: success or refusal means the target
+/// answered; anything else is attributed to the ICMP sender, if one was queued.
+#[cfg(target_os = "linux")]
+fn classify_error(
+    fd: libc::c_int,
+    destination: SocketAddr,
+    ttl: u8,
+    errno: Option<libc::c_int>,
+    rtt: Duration,
+) -> TraceHop {
+    match errno {
+        None | Some(libc::ECONNREFUSED) | Some(libc::ECONNRESET) => TraceHop {
+            ttl,
+            addr: Some(destination.ip()),
+            rtt: Some(rtt),
+            reached: true,
+        },
+        Some(_) => {
+            let addr = read_icmp_offender(fd);
+            TraceHop {
+                ttl,
+                addr,
+                rtt: addr.map(|_| rtt),
+                reached: addr == Some(destination.ip()),
+            }
+        }
+    }
+}
+
+/// Reads the offender address of the last ICMP error from the socket's error queue.
+#[cfg(target_os = "linux")]
+fn read_icmp_offender(fd: libc::c_int) -> Option<IpAddr> {
+    let mut control = [0u8; 512];
+    // SAFETY: zeroed msghdr is a valid empty message header.
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = control.len() as _;
+
+    // SAFETY: `msg` points at `control`, which stays alive for the call.
+    let rc = unsafe { libc::recvmsg(fd, &mut msg, libc::MSG_ERRQUEUE) };
+    if rc < 0 {
+        return None;
+    }
+
+    // SAFETY: cmsg traversal stays within `msg_controllen` via the libc macros,
+    // and the offender sockaddr immediately follows `sock_extended_err`.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            let header = &*cmsg;
+            let is_recverr = (header.cmsg_level == libc::IPPROTO_IP
+                && header.cmsg_type == libc::IP_RECVERR)
+                || (header.cmsg_level == libc::IPPROTO_IPV6
+                    && header.cmsg_type == libc::IPV6_RECVERR);
+            if is_recverr {
+                let err = libc::CMSG_DATA(cmsg) as *const libc::sock_extended_err;
+                let offender = err.add(1) as *const libc::sockaddr;
+                return from_sockaddr(offender);
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn to_sockaddr(addr: SocketAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    // SAFETY: an all-zero sockaddr_storage is valid for either family.
+    let mut storage: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let len = match addr {
+        SocketAddr::V4(v4) => {
+            let sin = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: v4.port().to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(v4.ip().octets()),
+                },
+                sin_zero: [0; 8],
+            };
+            // SAFETY: sockaddr_storage is large enough for sockaddr_in.
+            unsafe {
+                std::ptr::write(&mut storage as *mut _ as *mut libc::sockaddr_in, sin);
+            }
+            std::mem::size_of::<libc::sockaddr_in>()
+        }
+        SocketAddr::V6(v6) => {
+            let sin6 = libc::sockaddr_in6 {
+                sin6_family: libc::AF_INET6 as libc::sa_family_t,
+                sin6_port: v6.port().to_be(),
+                sin6_flowinfo: v6.flowinfo(),
+                sin6_addr: libc::in6_addr {
+                    s6_addr: v6.ip().octets(),
+                },
+                sin6_scope_id: v6.scope_id(),
+            };
+            // SAFETY: sockaddr_storage is large enough for sockaddr_in6.
+            unsafe {
+                std::ptr::write(&mut storage as *mut _ as *mut libc::sockaddr_in6, sin6);
+            }
+            std::mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    (storage, len as libc::socklen_t)
+}
+
+/// # Safety
+/// `addr` must point at a readable sockaddr of the family it declares.
+#[cfg(target_os = "linux")]
+pub(crate) unsafe fn from_sockaddr(addr: *const libc::sockaddr) -> Option<IpAddr> {
+    // SAFETY: guaranteed by the caller.
+    unsafe {
+        match (*addr).sa_family as libc::c_int {
+            libc::AF_INET => {
+                let sin = &*(addr as *const libc::sockaddr_in);
+                Some(IpAddr::from(sin.sin_addr.s_addr.to_ne_bytes()))
+            }
+            libc::AF_INET6 => {
+                let sin6 = &*(addr as *const libc::sockaddr_in6);
+                Some(IpAddr::from(sin6.sin6_addr.s6_addr))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_collects_hops_and_outcome() {
+        let destination: SocketAddr = "192.0.2.1:443".parse().unwrap();
+        let mut run = TracerouteRun::new(destination);
+        assert!(run.is_running());
+        run.apply(TracerouteEvent::Hop(TraceHop {
+            ttl: 1,
+            addr: None,
+            rtt: None,
+            reached: false,
+        }));
+        run.apply(TracerouteEvent::Failed(TracerouteError::Unsupported));
+        assert_eq!(run.hops.len(), 1);
+        assert_eq!(run.status, TracerouteStatus::Failed);
+        assert_eq!(run.error, Some(TracerouteError::Unsupported));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn trace_to_loopback_reaches_target_on_first_hop() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let destination = listener.local_addr().expect("addr");
+        let mut hops = Vec::new();
+        trace(destination, 4, Duration::from_secs(1), |hop| hops.push(hop)).expect("trace");
+
+        assert_eq!(hops.len(), 1);
+        assert!(hops[0].reached);
+        assert_eq!(hops[0].addr, Some(destination.ip()));
+    }
+}
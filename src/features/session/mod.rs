@@ -0,0 +1,332 @@
+//! Session recordings: every sample of a run, plus the target configs needed
+//! to show them again, as one JSON record per line.
+
+mod recorder;
+mod replay;
+
+use crate::app::Annotation;
+use crate::config::{TargetConfig, TargetId};
+use crate::data_model::{SchemaError, SchemaVersion, Versioned};
+use crate::probe::ProbeSample;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::time::SystemTime;
+use thiserror::Error;
+
+pub use recorder::{SessionRecorder, write_session};
+pub use replay::{parse_replay_speed, spawn_replay};
+
+const SESSION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("session file: {0}")]
+    Io(#[from] io::Error),
+    #[error("session file line {line}: {message}")]
+    Malformed { line: usize, message: String },
+    #[error("session file has no header; was it written with --record?")]
+    MissingHeader,
+    #[error("session format version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("session file: {0}")]
+    Schema(#[from] SchemaError),
+}
+
+/// First record of a session file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    /// Version of the sample and config types in the file; recordings made
+    /// before it was stamped are read as the first schema
+    #[serde(default = "SchemaVersion::legacy")]
+    pub schema_version: SchemaVersion,
+    pub recorded_at: SystemTime,
+    /// Targets configured when recording started
+    pub targets: Vec<TargetConfig>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SessionRecord {
+    Header(SessionHeader),
+    /// Target added after recording started, written before its first sample
+    Target(Box<TargetConfig>),
+    Sample(Box<ProbeSample>),
+    /// Note the user attached to a target during the session
+    Annotation {
+        target_id: TargetId,
+        annotation: Annotation,
+    },
+}
+
+/// The header line, read for its schema version alone.
+#[derive(Deserialize)]
+struct HeaderLine {
+    header: Versioned,
+}
+
+/// A session file read back for replay.
+#[derive(Clone, Debug)]
+pub struct Recording {
+    pub header: SessionHeader,
+    /// Header targets followed by those added during the session
+    pub targets: Vec<TargetConfig>,
+    /// Samples in the order they were recorded
+    pub samples: Vec<ProbeSample>,
+    /// Annotations in the order they were added
+    pub annotations: Vec<(TargetId, Annotation)>,
+}
+
+/// Reads a whole session file. A torn last line, as left by a killed
+/// recording, is ignored.
+pub fn load_recording(path: &Path) -> Result<Recording, SessionError> {
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut lines = reader.lines().enumerate().peekable();
+    let mut header = None;
+    let mut targets = Vec::new();
+    let mut seen: HashSet<TargetId> = HashSet::new();
+    let mut samples = Vec::new();
+    let mut annotations = Vec::new();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if header.is_none()
+            && let Ok(found) = serde_json::from_str::<HeaderLine>(&line)
+        {
+            found.header.schema_version.check_compatible()?;
+        }
+        let record = match serde_json::from_str::<SessionRecord>(&line) {
+            Ok(record) => record,
+            Err(_) if lines.peek().is_none() && header.is_some() => break,
+            Err(err) => {
+                return Err(SessionError::Malformed {
+                    line: index + 1,
+                    message: err.to_string(),
+                });
+            }
+        };
+        match record {
+            SessionRecord::Header(found) if header.is_none() => {
+                if found.version != SESSION_FORMAT_VERSION {
+                    return Err(SessionError::UnsupportedVersion(found.version));
+                }
+                for target in &found.targets {
+                    if seen.insert(target.id) {
+                        targets.push(target.clone());
+                    }
+                }
+                header = Some(found);
+            }
+            SessionRecord::Header(_) => {
+                return Err(SessionError::Malformed {
+                    line: index + 1,
+                    message: "second header".to_string(),
+                });
+            }
+            _ if header.is_none() => return Err(SessionError::MissingHeader),
+            SessionRecord::Target(target) => {
+                if seen.insert(target.id) {
+                    targets.push(*target);
+                }
+            }
+            SessionRecord::Sample(sample) => samples.push(*sample),
+            SessionRecord::Annotation {
+                target_id,
+                annotation,
+            } => annotations.push((target_id, annotation)),
+        }
+    }
+
+    Ok(Recording {
+        header: header.ok_or(SessionError::MissingHeader)?,
+        targets,
+        samples,
+        annotations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_profiles;
+    use crate::features::probe::testing::SampleBuilder;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use url::Url;
+
+    fn target(host: &str) -> TargetConfig {
+        TargetConfig::new(
+            Url::parse(&format!("https://{host}")).unwrap(),
+            default_profiles(),
+        )
+    }
+
+    fn sample(target: &TargetConfig, total_ms: u64) -> ProbeSample {
+        SampleBuilder::ok(target.id, target.profiles[0].id)
+            .total_ms(total_ms)
+            .remote("192.0.2.1:443".parse().unwrap())
+            .build()
+    }
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("httpulse-{name}-{}.jsonl", std::process::id()))
+    }
+
+    #[test]
+    fn recording_round_trips_header_late_targets_and_samples() {
+        let path = scratch_path("session-round-trip");
+        let first = target("a.example");
+        let late = target("b.example");
+
+        let mut recorder = SessionRecorder::create(&path, std::slice::from_ref(&first)).unwrap();
+        recorder.record(&sample(&first, 10), Some(&first)).unwrap();
+        recorder.record(&sample(&late, 20), Some(&late)).unwrap();
+        recorder.record(&sample(&late, 30), Some(&late)).unwrap();
+        let note = Annotation {
+            ts: SystemTime::now(),
+            text: "switched to new LB".to_string(),
+        };
+        recorder.annotate(late.id, &note).unwrap();
+        drop(recorder);
+
+        let recording = load_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let ids: Vec<TargetId> = recording.targets.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![first.id, late.id]);
+        let totals: Vec<u128> = recording
+            .samples
+            .iter()
+            .map(|s| s.t_total.as_millis())
+            .collect();
+        assert_eq!(totals, vec![10, 20, 30]);
+        assert_eq!(recording.samples[0].remote, sample(&first, 0).remote);
+        assert_eq!(recording.annotations, vec![(late.id, note)]);
+    }
+
+    #[test]
+    fn recordings_and_saved_sessions_never_hold_header_values() {
+        let secret = "Bearer tok-123";
+        let with_header = |host: &str| {
+            let mut config = target(host);
+            config.profiles[0]
+                .headers
+                .push(("Authorization".to_string(), secret.into()));
+            config
+        };
+        let (first, late) = (with_header("a.example"), with_header("b.example"));
+        let path = scratch_path("session-redacted");
+        let mut recorder = SessionRecorder::create(&path, std::slice::from_ref(&first)).unwrap();
+        recorder.record(&sample(&late, 20), Some(&late)).unwrap();
+        drop(recorder);
+        let recorded = std::fs::read_to_string(&path).unwrap();
+
+        write_session(&path, &[first.clone(), late], [&sample(&first, 10)]).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        let recording = load_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        for contents in [&recorded, &saved] {
+            assert!(!contents.contains(secret), "{contents}");
+        }
+        let (name, value) = &recording.targets[0].profiles[0].headers[0];
+        assert_eq!(
+            (name.as_str(), value.expose()),
+            ("Authorization", crate::config::SECRET_PLACEHOLDER)
+        );
+        // The caller's configs keep their values
+        assert_eq!(first.profiles[0].headers[0].1.expose(), secret);
+    }
+
+    /// Rewrites every line of the session file at `path` as JSON values.
+    fn rewrite_lines(path: &Path, edit: impl Fn(usize, &mut serde_json::Value)) {
+        let contents = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<String> = contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut value: serde_json::Value = serde_json::from_str(line).unwrap();
+                edit(index, &mut value);
+                value.to_string()
+            })
+            .collect();
+        std::fs::write(path, lines.join("\n")).unwrap();
+    }
+
+    #[test]
+    fn recordings_from_other_minor_versions_load_but_newer_majors_are_refused() {
+        let path = scratch_path("session-schema");
+        let first = target("a.example");
+        let mut late = sample(&first, 10);
+        late.deferred_slots = 2;
+        late.limiter_wait = Some(Duration::from_millis(300));
+        let mut recorder = SessionRecorder::create(&path, std::slice::from_ref(&first)).unwrap();
+        recorder.record(&sample(&first, 10), Some(&first)).unwrap();
+        recorder.record(&late, Some(&first)).unwrap();
+        drop(recorder);
+        let recording = load_recording(&path).unwrap();
+        assert_eq!(
+            recording.header.schema_version,
+            crate::data_model::SCHEMA_VERSION
+        );
+        assert_eq!(recording.samples[1].limiter_wait, late.limiter_wait);
+
+        // A newer minor adds fields this build does not know; an older writer
+        // left out the ones added since, and had no schema version at all
+        rewrite_lines(&path, |index, value| {
+            if index == 0 {
+                let header = value["header"].as_object_mut().unwrap();
+                header.remove("schema_version");
+                header.insert("host_os".into(), "linux".into());
+            } else {
+                let sample = value["sample"].as_object_mut().unwrap();
+                sample.remove("deferred_slots");
+                sample.remove("limiter_wait");
+                sample.insert("tls_resumed".into(), true.into());
+            }
+        });
+        let recording = load_recording(&path).unwrap();
+        assert_eq!(recording.header.schema_version, SchemaVersion::LEGACY);
+        assert_eq!(recording.samples.len(), 2);
+        assert_eq!(recording.samples[1].deferred_slots, 0);
+        assert_eq!(recording.samples[1].limiter_wait, None);
+
+        rewrite_lines(&path, |index, value| {
+            if index == 0 {
+                value["header"]["schema_version"] = "2.0".into();
+                // A newer major may change anything, so the error must not
+                // depend on the rest of the header still parsing
+                value["header"]["targets"] = "renamed".into();
+            }
+        });
+        let err = load_recording(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            err,
+            SessionError::Schema(SchemaError::Newer { .. })
+        ));
+        assert!(err.to_string().contains("schema 2.0"), "{err}");
+    }
+
+    #[test]
+    fn load_tolerates_a_torn_last_line_but_requires_a_header() {
+        let path = scratch_path("session-torn");
+        let first = target("a.example");
+        let mut recorder = SessionRecorder::create(&path, std::slice::from_ref(&first)).unwrap();
+        recorder.record(&sample(&first, 10), Some(&first)).unwrap();
+        drop(recorder);
+        let mut contents = std::fs::read_to_string(&path).unwrap();
+        contents.push_str("{\"sample\":{\"ts\":");
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(load_recording(&path).unwrap().samples.len(), 1);
+
+        let without_header: String = contents.lines().skip(1).collect::<Vec<_>>().join("\n");
+        std::fs::write(&path, without_header).unwrap();
+        let err = load_recording(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(err, SessionError::MissingHeader));
+    }
+}
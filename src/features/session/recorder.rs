@@ -0,0 +1,112 @@
+use crate::app::Annotation;
+use crate::common::fs::{create_private, write_replacing};
+use crate::config::{TargetConfig, TargetId};
+use crate::data_model::SCHEMA_VERSION;
+use crate::probe::ProbeSample;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use super::{SESSION_FORMAT_VERSION, SessionError, SessionHeader, SessionRecord};
+
+/// Writes a session file for `--record`, one line per sample as it arrives.
+/// Recordings are meant to be attached to bug reports, so target configs go
+/// in [`TargetConfig::redacted`].
+pub struct SessionRecorder {
+    writer: BufWriter<File>,
+    /// Targets whose config is already in the file
+    written: HashSet<TargetId>,
+}
+
+impl SessionRecorder {
+    /// Creates (or truncates) `path`, readable only by the owner, and writes
+    /// the header with `targets`.
+    pub fn create(path: &Path, targets: &[TargetConfig]) -> Result<Self, SessionError> {
+        let mut recorder = Self {
+            writer: BufWriter::new(create_private(path)?),
+            written: targets.iter().map(|target| target.id).collect(),
+        };
+        recorder.write(&SessionRecord::Header(SessionHeader {
+            version: SESSION_FORMAT_VERSION,
+            schema_version: SCHEMA_VERSION,
+            recorded_at: SystemTime::now(),
+            targets: targets.iter().map(TargetConfig::redacted).collect(),
+        }))?;
+        recorder.flush()?;
+        Ok(recorder)
+    }
+
+    /// Appends `sample`, preceded by `target`'s config the first time a target
+    /// added after the header shows up.
+    pub fn record(
+        &mut self,
+        sample: &ProbeSample,
+        target: Option<&TargetConfig>,
+    ) -> Result<(), SessionError> {
+        if let Some(target) = target
+            && self.written.insert(target.id)
+        {
+            self.write(&SessionRecord::Target(Box::new(target.redacted())))?;
+        }
+        self.write(&SessionRecord::Sample(Box::new(sample.clone())))
+    }
+
+    /// Appends a note the user attached to a target.
+    pub fn annotate(
+        &mut self,
+        target_id: TargetId,
+        annotation: &Annotation,
+    ) -> Result<(), SessionError> {
+        self.write(&SessionRecord::Annotation {
+            target_id,
+            annotation: annotation.clone(),
+        })
+    }
+
+    /// Pushes buffered records to the file. The UI calls this once per tick,
+    /// so a killed session loses at most that tick's records.
+    pub fn flush(&mut self) -> Result<(), SessionError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write(&mut self, record: &SessionRecord) -> Result<(), SessionError> {
+        write_record(&mut self.writer, record)?;
+        Ok(())
+    }
+}
+
+/// Writes a whole session at once, `targets` redacted in the header and then
+/// `samples` in order. The file is written privately and replaced by a
+/// rename, so an interrupted write leaves the previous one intact.
+pub fn write_session<'a>(
+    path: &Path,
+    targets: &[TargetConfig],
+    samples: impl IntoIterator<Item = &'a ProbeSample>,
+) -> Result<(), SessionError> {
+    let header = SessionRecord::Header(SessionHeader {
+        version: SESSION_FORMAT_VERSION,
+        schema_version: SCHEMA_VERSION,
+        recorded_at: SystemTime::now(),
+        targets: targets.iter().map(TargetConfig::redacted).collect(),
+    });
+    write_replacing(path, |file| {
+        let mut writer = BufWriter::new(file);
+        write_record(&mut writer, &header)?;
+        for sample in samples {
+            write_record(
+                &mut writer,
+                &SessionRecord::Sample(Box::new(sample.clone())),
+            )?;
+        }
+        writer.flush()
+    })?;
+    Ok(())
+}
+
+fn write_record(writer: &mut impl Write, record: &SessionRecord) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n")
+}
@@ -0,0 +1,71 @@
+use crate::probe::ProbeSample;
+use crossbeam_channel::Sender;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::info;
+
+/// Parses a replay speed such as `1x`, `10x` or `0.5`; must be positive.
+pub fn parse_replay_speed(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = value.strip_suffix(['x', 'X']).unwrap_or(value);
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|speed| speed.is_finite() && *speed > 0.0)
+}
+
+/// Feeds `samples` into `sample_tx` with their recorded spacing divided by
+/// `speed`. Each sample is stamped with its delivery time, so windows and
+/// charts treat the replay like a live session.
+pub fn spawn_replay(
+    samples: Vec<ProbeSample>,
+    speed: f64,
+    sample_tx: Sender<ProbeSample>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let Some(first) = samples.first().map(|sample| sample.ts) else {
+            return;
+        };
+        let started = Instant::now();
+        let total = samples.len();
+        for mut sample in samples {
+            let due = started + replay_offset(first, sample.ts, speed);
+            if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                thread::sleep(wait);
+            }
+            sample.ts = SystemTime::now();
+            if sample_tx.send(sample).is_err() {
+                return;
+            }
+        }
+        info!(samples = total, "session replay finished");
+    })
+}
+
+/// When a sample recorded at `ts` is due, relative to the replay start.
+/// Samples recorded out of order are delivered right away.
+fn replay_offset(first: SystemTime, ts: SystemTime, speed: f64) -> Duration {
+    ts.duration_since(first).unwrap_or_default().div_f64(speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_replay_speed_accepts_multipliers() {
+        assert_eq!(parse_replay_speed("10x"), Some(10.0));
+        assert_eq!(parse_replay_speed("1"), Some(1.0));
+        assert_eq!(parse_replay_speed("0.5X"), Some(0.5));
+        assert_eq!(parse_replay_speed("0x"), None);
+        assert_eq!(parse_replay_speed("fast"), None);
+    }
+
+    #[test]
+    fn replay_offset_scales_recorded_spacing() {
+        let first = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let later = first + Duration::from_secs(30);
+        assert_eq!(replay_offset(first, later, 10.0), Duration::from_secs(3));
+        assert_eq!(replay_offset(later, first, 10.0), Duration::ZERO);
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{self, Write};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies `text` to the system clipboard with an OSC 52 escape sequence,
+/// which the terminal forwards even over SSH. Inside tmux the sequence is
+/// wrapped for passthrough (tmux needs `set -g allow-passthrough on`).
+pub(super) fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let sequence = osc52_sequence(text, std::env::var_os("TMUX").is_some());
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()));
+    if tmux {
+        format!("\x1bPtmux;\x1b{sequence}\x1b\\")
+    } else {
+        sequence
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - 6 * index)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_pads_partial_groups() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode("p99 · 120ms".as_bytes()), "cDk5IMK3IDEyMG1z");
+    }
+
+    #[test]
+    fn osc52_wraps_for_tmux_passthrough() {
+        assert_eq!(osc52_sequence("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(
+            osc52_sequence("hi", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+        );
+    }
+}
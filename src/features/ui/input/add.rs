@@ -1,8 +1,11 @@
-use crate::app::{AppState, parse_profile_specs, parse_target_url};
+use crate::app::{
+    AppState, SharedTargets, StatusNotice, parse_add_target_command, parse_targets_file,
+};
 use crate::probe::ProbeSample;
 use crate::storage;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use url::Url;
+use std::path::PathBuf;
+use std::time::Instant;
 
 use super::super::state::InputMode;
 
@@ -15,25 +18,75 @@ pub(in crate::features::ui) fn handle_input_key(
 ) {
     match key.code {
         KeyCode::Esc => {
-            *input_mode = InputMode::Normal;
+            if *input_mode == InputMode::HeaderValue {
+                app.resolve_pending_header(None);
+            }
+            *input_mode = after_input(app);
             input_buffer.clear();
         }
         KeyCode::Enter => {
             match *input_mode {
                 InputMode::AddTarget => {
-                    if let Some((url, profiles)) = parse_add_command(input_buffer) {
-                        app.add_target(url, profiles, sample_tx.clone());
+                    let command = parse_add_target_command(input_buffer);
+                    // The preview says why; keep the prompt open to fix it
+                    let Ok(url) = command.url else {
+                        return;
+                    };
+                    app.add_target(url, command.profiles, sample_tx.clone());
+                    if !command.tags.is_empty()
+                        && let Some(target_id) = app.selected_target_id()
+                    {
+                        app.set_target_tags(target_id, command.tags);
+                    }
+                    let _ = storage::save(&app.to_persisted_state());
+                }
+                InputMode::ImportTargets => import_targets_file(input_buffer, app, sample_tx),
+                InputMode::ExportTargets => export_targets_file(input_buffer, app),
+                InputMode::HeaderValue => {
+                    app.resolve_pending_header(Some(input_buffer));
+                    let _ = storage::save(&app.to_persisted_state());
+                }
+                InputMode::AnnotationText => {
+                    if app.selected_target().is_some()
+                        && app
+                            .add_annotation(app.selected_target, input_buffer)
+                            .is_some()
+                    {
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                }
+                InputMode::BaselineName => {
+                    if app.selected_target().is_some()
+                        && app
+                            .capture_baseline(app.selected_target, input_buffer)
+                            .is_some()
+                    {
                         let _ = storage::save(&app.to_persisted_state());
                     }
                 }
                 InputMode::Normal
+                | InputMode::Filter
+                | InputMode::Snapshot
                 | InputMode::Help
                 | InputMode::Glossary
                 | InputMode::Settings
                 | InputMode::SettingsEdit(_)
-                | InputMode::ConfirmDelete => {}
+                | InputMode::ConfirmDelete
+                | InputMode::ConfirmQuit
+                | InputMode::AlertLog
+                | InputMode::ErrorLog
+                | InputMode::ChangeLog
+                | InputMode::ConnReuse
+                | InputMode::Spikes
+                | InputMode::Outages
+                | InputMode::ChartCursor
+                | InputMode::MetricsFocus
+                | InputMode::MetricDetail
+                | InputMode::Traceroute
+                | InputMode::Baselines
+                | InputMode::Annotations => {}
             }
-            *input_mode = InputMode::Normal;
+            *input_mode = after_input(app);
             input_buffer.clear();
         }
         KeyCode::Backspace => {
@@ -49,14 +102,82 @@ pub(in crate::features::ui) fn handle_input_key(
     }
 }
 
-fn parse_add_command(input: &str) -> Option<(Url, Option<Vec<crate::config::ProfileConfig>>)> {
-    let mut parts = input.split_whitespace();
-    let url_text = parts.next()?;
-    let url = parse_target_url(url_text)?;
-    let rest = parts.collect::<Vec<_>>().join(" ");
-    if rest.is_empty() {
-        Some((url, None))
+/// Imported headers still waiting for a value are asked for before anything
+/// else.
+fn after_input(app: &AppState) -> InputMode {
+    if app.pending_headers.is_empty() {
+        InputMode::Normal
     } else {
-        Some((url, Some(parse_profile_specs(&rest))))
+        InputMode::HeaderValue
+    }
+}
+
+/// Expands a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    let path = path.trim();
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
     }
 }
+
+/// Adds the targets of the file at `path` and reports the outcome in the
+/// header. The file is either a shared JSON document or one URL per line.
+fn import_targets_file(
+    path: &str,
+    app: &mut AppState,
+    sample_tx: &crossbeam_channel::Sender<ProbeSample>,
+) {
+    let path = expand_home(path);
+    let (message, failed) = match std::fs::read_to_string(&path) {
+        Ok(content) if SharedTargets::detect(&content) => {
+            match SharedTargets::from_json(&content) {
+                Ok(document) => {
+                    let summary = app.import_shared_targets(document, None, sample_tx);
+                    if summary.added > 0 {
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    let failed = summary.added == 0 || !summary.invalid.is_empty();
+                    (summary.to_string(), failed)
+                }
+                Err(err) => (format!("{}: {err}", path.display()), true),
+            }
+        }
+        Ok(content) => {
+            let summary = app.import_targets(parse_targets_file(&content), false, None, sample_tx);
+            if summary.added > 0 {
+                let _ = storage::save(&app.to_persisted_state());
+            }
+            let failed = summary.added == 0 || !summary.invalid_lines.is_empty();
+            (summary.to_string(), failed)
+        }
+        Err(err) => (format!("cannot read {}: {err}", path.display()), true),
+    };
+    app.status_notice = Some(StatusNotice {
+        at: Instant::now(),
+        message,
+        failed,
+    });
+}
+
+/// Writes every target's shareable definition to `path` as JSON.
+fn export_targets_file(path: &str, app: &mut AppState) {
+    let path = expand_home(path);
+    let document = app.export_targets();
+    let (message, failed) = match std::fs::write(&path, document.to_json()) {
+        Ok(()) => (
+            format!(
+                "exported {} targets to {}",
+                document.targets.len(),
+                path.display()
+            ),
+            false,
+        ),
+        Err(err) => (format!("cannot write {}: {err}", path.display()), true),
+    };
+    app.status_notice = Some(StatusNotice {
+        at: Instant::now(),
+        message,
+        failed,
+    });
+}
@@ -1,4 +1,5 @@
 use crate::app::AppState;
+use crate::settings::{Action, Keymap};
 use crate::storage;
 use crossterm::event::{KeyCode, KeyEvent};
 
@@ -21,3 +22,19 @@ pub(in crate::features::ui) fn handle_confirm_delete_key(
         _ => {}
     }
 }
+
+/// Returns whether to quit; the quit key itself confirms.
+pub(in crate::features::ui) fn handle_confirm_quit_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) -> bool {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => true,
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            *input_mode = InputMode::Normal;
+            false
+        }
+        _ => keymap.action(&key) == Some(Action::Quit),
+    }
+}
@@ -0,0 +1,103 @@
+use crate::app::{AppState, TargetPaneMode};
+use crate::settings::{Action, Keymap};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::super::render::{chart_sample_positions, chart_timestamp_to_x, chart_x_to_timestamp};
+use super::super::state::InputMode;
+
+/// Places the cursor on the newest plotted sample. Returns false when the
+/// selected target has no chart visible or nothing to point at.
+pub(in crate::features::ui) fn enter_chart_cursor(app: &mut AppState) -> bool {
+    let Some(target) = app.selected_target() else {
+        return false;
+    };
+    if !matches!(
+        target.pane_mode,
+        TargetPaneMode::Split | TargetPaneMode::Chart
+    ) {
+        return false;
+    }
+    let Some(&newest) = chart_sample_positions(app, target).last() else {
+        return false;
+    };
+    let ts = chart_x_to_timestamp(app.target_window(target), newest);
+    if let Some(target) = app.selected_target_mut() {
+        target.chart_cursor = Some(ts);
+    }
+    true
+}
+
+pub(in crate::features::ui) fn handle_chart_cursor_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+) {
+    let action = keymap.action(&key);
+    if key.code == KeyCode::Esc || matches!(action, Some(Action::Quit | Action::ChartCursor)) {
+        if let Some(target) = app.selected_target_mut() {
+            target.chart_cursor = None;
+        }
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    match action {
+        Some(Action::CursorOlder) => step_cursor(app, StepDirection::Older),
+        Some(Action::CursorNewer) => step_cursor(app, StepDirection::Newer),
+        Some(Action::CursorOldest) => jump_cursor(app, StepDirection::Older),
+        Some(Action::CursorNewest) => jump_cursor(app, StepDirection::Newer),
+        _ => {}
+    }
+}
+
+#[derive(Clone, Copy)]
+enum StepDirection {
+    Older,
+    Newer,
+}
+
+/// Tolerance for treating a re-derived x position as the same sample.
+const SAME_SAMPLE_EPSILON: f64 = 1e-3;
+
+fn step_cursor(app: &mut AppState, direction: StepDirection) {
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let Some(cursor) = target.chart_cursor else {
+        return;
+    };
+    let positions = chart_sample_positions(app, target);
+    let current = chart_timestamp_to_x(app.target_window(target), cursor);
+    let next = match direction {
+        StepDirection::Older => positions
+            .iter()
+            .rev()
+            .find(|x| **x < current - SAME_SAMPLE_EPSILON),
+        StepDirection::Newer => positions
+            .iter()
+            .find(|x| **x > current + SAME_SAMPLE_EPSILON),
+    };
+    if let Some(&x) = next {
+        let ts = chart_x_to_timestamp(app.target_window(target), x);
+        if let Some(target) = app.selected_target_mut() {
+            target.chart_cursor = Some(ts);
+        }
+    }
+}
+
+fn jump_cursor(app: &mut AppState, direction: StepDirection) {
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let positions = chart_sample_positions(app, target);
+    let edge = match direction {
+        StepDirection::Older => positions.first(),
+        StepDirection::Newer => positions.last(),
+    };
+    if let Some(&x) = edge {
+        let ts = chart_x_to_timestamp(app.target_window(target), x);
+        if let Some(target) = app.selected_target_mut() {
+            target.chart_cursor = Some(ts);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::app::AppState;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::super::state::InputMode;
+
+/// Incremental hostname filter: every edit is applied to the list immediately.
+pub(in crate::features::ui) fn handle_filter_key(
+    key: KeyEvent,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+    input_buffer: &mut String,
+) {
+    match key.code {
+        KeyCode::Esc => {
+            app.set_target_filter("");
+            *input_mode = InputMode::Normal;
+            input_buffer.clear();
+        }
+        KeyCode::Enter => {
+            *input_mode = InputMode::Normal;
+            input_buffer.clear();
+        }
+        KeyCode::Backspace => {
+            input_buffer.pop();
+            app.set_target_filter(input_buffer);
+        }
+        KeyCode::Char(ch) => {
+            input_buffer.push(ch);
+            app.set_target_filter(input_buffer);
+        }
+        _ => {}
+    }
+}
@@ -1,23 +1,227 @@
+use crate::app::AppState;
+use crate::settings::{Action, Keymap};
+use crate::storage;
 use crossterm::event::{KeyCode, KeyEvent};
 
 use super::super::state::{GLOSSARY_PAGE_COUNT, InputMode};
 
-pub(in crate::features::ui) fn handle_help_key(key: KeyEvent, input_mode: &mut InputMode) {
+/// Popups close on Esc, on the quit key or on the key that opened them.
+fn closes(key: &KeyEvent, keymap: &Keymap, opened_by: Action) -> bool {
+    key.code == KeyCode::Esc
+        || keymap
+            .action(key)
+            .is_some_and(|action| action == Action::Quit || action == opened_by)
+}
+
+pub(in crate::features::ui) fn handle_help_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if closes(&key, keymap, Action::Help) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_alert_log_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if closes(&key, keymap, Action::AlertLog) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_conn_reuse_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if closes(&key, keymap, Action::ConnReuse) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_spikes_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if closes(&key, keymap, Action::Spikes) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_outages_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if closes(&key, keymap, Action::Outages) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_snapshot_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if key.code == KeyCode::Enter || closes(&key, keymap, Action::CopySnapshot) {
+        *input_mode = InputMode::Normal;
+    }
+}
+
+pub(in crate::features::ui) fn handle_error_log_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &AppState,
+    input_mode: &mut InputMode,
+    scroll: &mut usize,
+) {
+    if closes(&key, keymap, Action::ErrorLog) {
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    let len = app
+        .selected_target()
+        .map(|target| target.error_log.len())
+        .unwrap_or(0);
+    match keymap.action(&key) {
+        Some(Action::Down) => {
+            if *scroll + 1 < len {
+                *scroll += 1;
+            }
+        }
+        Some(Action::Up) => {
+            *scroll = scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+pub(in crate::features::ui) fn handle_change_log_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &AppState,
+    input_mode: &mut InputMode,
+    scroll: &mut usize,
+) {
+    if closes(&key, keymap, Action::ChangeLog) {
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    match keymap.action(&key) {
+        Some(Action::Down) => {
+            if *scroll + 1 < app.change_log.len() {
+                *scroll += 1;
+            }
+        }
+        Some(Action::Up) => {
+            *scroll = scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+/// Baseline picker: Enter toggles the highlighted baseline as the comparison,
+/// `d` deletes it.
+pub(in crate::features::ui) fn handle_baselines_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+    cursor: &mut usize,
+) {
+    if closes(&key, keymap, Action::Baselines) {
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    let index = app.selected_target;
+    let len = app
+        .selected_target()
+        .map(|target| target.baselines.len())
+        .unwrap_or(0);
+    match key.code {
+        _ if keymap.is(&key, Action::Down) => {
+            if *cursor + 1 < len {
+                *cursor += 1;
+            }
+        }
+        _ if keymap.is(&key, Action::Up) => {
+            *cursor = cursor.saturating_sub(1);
+        }
+        KeyCode::Enter if *cursor < len => {
+            app.toggle_baseline(index, *cursor);
+            let _ = storage::save(&app.to_persisted_state());
+        }
+        KeyCode::Char('d') if *cursor < len => {
+            app.delete_baseline(index, *cursor);
+            *cursor = (*cursor).min(len.saturating_sub(2));
+            let _ = storage::save(&app.to_persisted_state());
+        }
+        _ => {}
+    }
+}
+
+pub(in crate::features::ui) fn handle_annotations_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+    cursor: &mut usize,
+) {
+    if closes(&key, keymap, Action::Annotations) {
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    let index = app.selected_target;
+    let len = app
+        .selected_target()
+        .map(|target| target.annotations.len())
+        .unwrap_or(0);
+    match key.code {
+        _ if keymap.is(&key, Action::Down) => {
+            if *cursor + 1 < len {
+                *cursor += 1;
+            }
+        }
+        _ if keymap.is(&key, Action::Up) => {
+            *cursor = cursor.saturating_sub(1);
+        }
+        KeyCode::Char('d') if *cursor < len => {
+            app.delete_annotation(index, *cursor);
+            *cursor = (*cursor).min(len.saturating_sub(2));
+            let _ = storage::save(&app.to_persisted_state());
+        }
+        _ => {}
+    }
+}
+
+pub(in crate::features::ui) fn handle_traceroute_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+) {
     match key.code {
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+        _ if closes(&key, keymap, Action::Traceroute) => {
             *input_mode = InputMode::Normal;
         }
+        KeyCode::Char('r') => app.start_traceroute(app.selected_target),
         _ => {}
     }
 }
 
 pub(in crate::features::ui) fn handle_glossary_key(
     key: KeyEvent,
+    keymap: &Keymap,
     input_mode: &mut InputMode,
     glossary_page: &mut usize,
 ) {
     match key.code {
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('G') => {
+        _ if closes(&key, keymap, Action::Glossary) => {
             *input_mode = InputMode::Normal;
         }
         KeyCode::Left | KeyCode::Char('h') => {
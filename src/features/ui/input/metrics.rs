@@ -0,0 +1,83 @@
+use crate::app::{AppState, TargetPaneMode};
+use crate::settings::{Action, Keymap};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::super::render::metrics_for_category;
+use super::super::state::InputMode;
+
+/// Puts the cursor on the first row of the metrics table. Returns false when
+/// the selected target shows no metrics table.
+pub(in crate::features::ui) fn enter_metrics_focus(
+    app: &AppState,
+    metric_cursor: &mut Option<usize>,
+) -> bool {
+    if app.overview.is_some() {
+        return false;
+    }
+    let Some(target) = app.selected_target() else {
+        return false;
+    };
+    if !matches!(
+        target.pane_mode,
+        TargetPaneMode::Split | TargetPaneMode::Metrics
+    ) {
+        return false;
+    }
+    *metric_cursor = Some(0);
+    true
+}
+
+pub(in crate::features::ui) fn handle_metrics_focus_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    app: &mut AppState,
+    input_mode: &mut InputMode,
+    metric_cursor: &mut Option<usize>,
+) {
+    let Some(target) = app.selected_target_mut() else {
+        *metric_cursor = None;
+        *input_mode = InputMode::Normal;
+        return;
+    };
+    if key.code == KeyCode::Esc {
+        *metric_cursor = None;
+        *input_mode = InputMode::Normal;
+        return;
+    }
+    let rows = metrics_for_category(target.metrics_category).len();
+    let cursor = metric_cursor.unwrap_or(0);
+    match keymap.action(&key) {
+        Some(Action::Quit | Action::FocusMetrics) => {
+            *metric_cursor = None;
+            *input_mode = InputMode::Normal;
+        }
+        Some(Action::Down) => *metric_cursor = Some((cursor + 1) % rows),
+        Some(Action::Up) => *metric_cursor = Some((cursor + rows - 1) % rows),
+        // Switching tabs starts again at the top of the new category
+        Some(Action::NextCategory) => {
+            target.metrics_category = target.metrics_category.next();
+            *metric_cursor = Some(0);
+        }
+        Some(Action::PrevCategory) => {
+            target.metrics_category = target.metrics_category.prev();
+            *metric_cursor = Some(0);
+        }
+        Some(Action::Activate) => *input_mode = InputMode::MetricDetail,
+        _ => {}
+    }
+}
+
+/// The detail popup closes back into the focused table.
+pub(in crate::features::ui) fn handle_metric_detail_key(
+    key: KeyEvent,
+    keymap: &Keymap,
+    input_mode: &mut InputMode,
+) {
+    if key.code == KeyCode::Esc
+        || keymap
+            .action(&key)
+            .is_some_and(|action| matches!(action, Action::Quit | Action::Activate))
+    {
+        *input_mode = InputMode::MetricsFocus;
+    }
+}
@@ -1,11 +1,23 @@
 mod add;
 mod confirm;
+mod cursor;
+mod filter;
 mod help;
+mod metrics;
+mod mouse;
 mod normal;
 mod settings;
 
 pub(super) use add::handle_input_key;
-pub(super) use confirm::handle_confirm_delete_key;
-pub(super) use help::{handle_glossary_key, handle_help_key};
-pub(super) use normal::handle_normal_key;
+pub(super) use confirm::{handle_confirm_delete_key, handle_confirm_quit_key};
+pub(super) use cursor::{enter_chart_cursor, handle_chart_cursor_key};
+pub(super) use filter::handle_filter_key;
+pub(super) use help::{
+    handle_alert_log_key, handle_annotations_key, handle_baselines_key, handle_change_log_key,
+    handle_conn_reuse_key, handle_error_log_key, handle_glossary_key, handle_help_key,
+    handle_outages_key, handle_snapshot_key, handle_spikes_key, handle_traceroute_key,
+};
+pub(super) use metrics::{enter_metrics_focus, handle_metric_detail_key, handle_metrics_focus_key};
+pub(super) use mouse::handle_mouse_event;
+pub(super) use normal::handle_normal_action;
 pub(super) use settings::{handle_settings_edit_key, handle_settings_key};
@@ -0,0 +1,52 @@
+use crate::app::AppState;
+use crate::storage;
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+use super::super::state::{HitRegistry, HitTarget};
+
+pub(in crate::features::ui) fn handle_mouse_event(
+    event: MouseEvent,
+    app: &mut AppState,
+    hits: &HitRegistry,
+) {
+    let Some(hit) = hits.hit(event.column, event.row) else {
+        return;
+    };
+    match (event.kind, hit) {
+        (MouseEventKind::Down(MouseButton::Left), HitTarget::TargetRow(idx)) => {
+            if idx < app.targets.len() {
+                app.selected_group = None;
+                app.selected_target = idx;
+            }
+        }
+        (MouseEventKind::Down(MouseButton::Left), HitTarget::GroupRow(idx)) => {
+            app.select_group_of(idx);
+            if app.toggle_selected_group() {
+                let _ = storage::save(&app.to_persisted_state());
+            }
+        }
+        (MouseEventKind::Down(MouseButton::Left), HitTarget::CategoryTab(category)) => {
+            if let Some(target) = app.selected_target_mut() {
+                target.metrics_category = category;
+            }
+        }
+        (MouseEventKind::Down(MouseButton::Left), HitTarget::PaneLabel) => {
+            if let Some(target_id) = app.selected_target_id() {
+                app.cycle_pane_mode(target_id);
+            }
+        }
+        (
+            MouseEventKind::ScrollDown,
+            HitTarget::TargetList | HitTarget::TargetRow(_) | HitTarget::GroupRow(_),
+        ) => {
+            app.select_next_target();
+        }
+        (
+            MouseEventKind::ScrollUp,
+            HitTarget::TargetList | HitTarget::TargetRow(_) | HitTarget::GroupRow(_),
+        ) => {
+            app.select_prev_target();
+        }
+        _ => {}
+    }
+}
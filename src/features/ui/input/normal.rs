@@ -1,52 +1,203 @@
-use crate::app::{AppState, ProfileViewMode};
+use crate::app::{AppState, ProfileViewMode, StatusNotice, TargetPaneMode};
 use crate::metrics::MetricKind;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::probe::ProbeSample;
+use crate::settings::Action;
+use crate::storage;
+use std::time::Instant;
 
-use super::super::state::{InputMode, SettingsState};
+use super::super::clipboard::copy_to_clipboard;
+use super::super::state::{InputMode, OverlayState, SettingsState};
+use super::{enter_chart_cursor, enter_metrics_focus};
 
-pub(in crate::features::ui) fn handle_normal_key(
-    key: KeyEvent,
+/// Chart series toggled by `Action::ToggleMetric(1..=8)`.
+const TOGGLE_METRICS: [MetricKind; 8] = [
+    MetricKind::Total,
+    MetricKind::Dns,
+    MetricKind::Connect,
+    MetricKind::Tls,
+    MetricKind::Ttfb,
+    MetricKind::Download,
+    MetricKind::Rtt,
+    MetricKind::Retrans,
+];
+
+pub(in crate::features::ui) fn handle_normal_action(
+    action: Action,
     app: &mut AppState,
     input_mode: &mut InputMode,
     input_buffer: &mut String,
     settings_state: &mut SettingsState,
-    glossary_page: &mut usize,
+    overlays: &mut OverlayState,
+    sample_tx: &crossbeam_channel::Sender<ProbeSample>,
 ) -> bool {
-    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
-        return true;
-    }
-    match key.code {
-        KeyCode::Char('q') => return true,
-        KeyCode::Char('?') => {
+    match action {
+        // Losing a long collection to a stray keypress is worth a prompt
+        Action::Quit if app.should_confirm_quit() => {
+            *input_mode = InputMode::ConfirmQuit;
+        }
+        Action::Quit => return true,
+        Action::Help => {
             *input_mode = InputMode::Help;
         }
-        KeyCode::Char('G') => {
+        Action::Glossary => {
             *input_mode = InputMode::Glossary;
-            *glossary_page = 0;
+            overlays.glossary_page = 0;
+        }
+        Action::AlertLog => {
+            *input_mode = InputMode::AlertLog;
+        }
+        Action::ChangeLog => {
+            overlays.change_log_scroll = 0;
+            *input_mode = InputMode::ChangeLog;
+        }
+        Action::Spikes => {
+            if app.selected_target().is_some() {
+                *input_mode = InputMode::Spikes;
+            }
+        }
+        Action::Outages => {
+            if app.selected_target().is_some() {
+                *input_mode = InputMode::Outages;
+            }
+        }
+        Action::ErrorLog => {
+            if app.selected_target().is_some() {
+                overlays.error_log_scroll = 0;
+                *input_mode = InputMode::ErrorLog;
+            }
+        }
+        Action::CopySnapshot => {
+            if app.selected_target().is_some()
+                && let Some(text) = app.target_snapshot(app.selected_target)
+            {
+                overlays.snapshot_error = copy_to_clipboard(&text).err().map(|err| err.to_string());
+                overlays.snapshot = text;
+                *input_mode = InputMode::Snapshot;
+            }
+        }
+        Action::DumpStatus => app.dump_status(),
+        Action::Overview => app.toggle_overview(),
+        Action::SplitView => {
+            app.toggle_split();
+        }
+        Action::SplitFocus => {
+            app.switch_split_focus();
+        }
+        Action::OverviewSort => {
+            if let Some(sort) = app.overview.as_mut() {
+                sort.cycle_column();
+            }
+        }
+        Action::OverviewReverse => {
+            if let Some(sort) = app.overview.as_mut() {
+                sort.reverse();
+            }
+        }
+        // Activate on an overview row opens that target's detail view
+        Action::Activate if app.overview.is_some() => app.overview = None,
+        // Activate on a group header collapses or expands it
+        Action::Activate if app.selected_group.is_some() => {
+            app.toggle_selected_group();
+            let _ = storage::save(&app.to_persisted_state());
+        }
+        // Activate on a pane showing the Summary errors row opens the same log
+        Action::Activate => {
+            if let Some(target) = app.selected_target()
+                && matches!(
+                    target.pane_mode,
+                    TargetPaneMode::Split | TargetPaneMode::Summary
+                )
+                && !target.error_log.is_empty()
+            {
+                overlays.error_log_scroll = 0;
+                *input_mode = InputMode::ErrorLog;
+            }
+        }
+        Action::Traceroute => {
+            if let Some(target) = app.selected_target() {
+                if target.traceroute.is_none() {
+                    app.start_traceroute(app.selected_target);
+                }
+                *input_mode = InputMode::Traceroute;
+            }
+        }
+        Action::CaptureBaseline => {
+            if app.selected_target().is_some() {
+                *input_mode = InputMode::BaselineName;
+                input_buffer.clear();
+            }
+        }
+        Action::Baselines => {
+            if let Some(target) = app.selected_target() {
+                overlays.baseline_cursor = target.active_baseline.unwrap_or(0);
+                *input_mode = InputMode::Baselines;
+            }
+        }
+        Action::Annotate => {
+            if app.selected_target().is_some() {
+                *input_mode = InputMode::AnnotationText;
+                input_buffer.clear();
+            }
+        }
+        Action::Annotations => {
+            if let Some(target) = app.selected_target() {
+                overlays.annotation_cursor = target.annotations.len().saturating_sub(1);
+                *input_mode = InputMode::Annotations;
+            }
+        }
+        Action::Burst => {
+            if app.selected_target().is_some() {
+                app.start_burst(app.selected_target);
+            }
         }
-        KeyCode::Char('S') => {
+        Action::Settings | Action::EditTarget => {
             *input_mode = InputMode::Settings;
             settings_state.selected = 0;
             settings_state.clear_notice();
         }
-        KeyCode::Char('a') => {
+        Action::AddTarget => {
             *input_mode = InputMode::AddTarget;
             input_buffer.clear();
         }
-        KeyCode::Char('e') => {
-            *input_mode = InputMode::Settings;
-            settings_state.selected = 0;
-            settings_state.clear_notice();
+        Action::ImportTargets => {
+            *input_mode = InputMode::ImportTargets;
+            input_buffer.clear();
+        }
+        Action::ExportTargets => {
+            *input_mode = InputMode::ExportTargets;
+            input_buffer.clear();
         }
-        KeyCode::Char('d') => {
-            if !app.targets.is_empty() {
+        Action::Filter => {
+            *input_mode = InputMode::Filter;
+            input_buffer.clear();
+            input_buffer.push_str(&app.target_filter);
+        }
+        Action::ClearFilter => {
+            if !app.target_filter.is_empty() {
+                app.set_target_filter("");
+            }
+        }
+        Action::DuplicateTarget => {
+            if app.selected_target().is_some()
+                && app
+                    .duplicate_target(app.selected_target, sample_tx.clone())
+                    .is_some()
+            {
+                let _ = storage::save(&app.to_persisted_state());
+            }
+        }
+        Action::DeleteTarget => {
+            if app.selected_target().is_some() {
                 *input_mode = InputMode::ConfirmDelete;
             }
         }
-        KeyCode::Char('p') => {
-            app.toggle_pause(app.selected_target);
+        Action::Pause => {
+            if let Some(target_id) = app.selected_target_id() {
+                app.toggle_pause(target_id);
+            }
         }
-        KeyCode::Char('c') => {
+        Action::PauseAll => app.toggle_global_pause(),
+        Action::CompareToggle => {
             if let Some(target) = app.selected_target_mut() {
                 target.view_mode = match target.view_mode {
                     ProfileViewMode::Single => ProfileViewMode::Compare,
@@ -54,43 +205,85 @@ pub(in crate::features::ui) fn handle_normal_key(
                 };
             }
         }
-        KeyCode::Char('g') => app.cycle_pane_mode(app.selected_target),
-        KeyCode::Char('w') => app.cycle_window(),
-        KeyCode::Down | KeyCode::Char('j') => {
-            if app.selected_target + 1 < app.targets.len() {
-                app.selected_target += 1;
+        Action::CycleEndpoint => {
+            if let Some(target) = app.selected_target_mut() {
+                target.cycle_endpoint_view();
+            }
+        }
+        Action::ConnReuse => {
+            if app.selected_target().is_some() {
+                *input_mode = InputMode::ConnReuse;
+            }
+        }
+        Action::CyclePane => {
+            if let Some(target_id) = app.selected_target_id() {
+                app.cycle_pane_mode(target_id);
+            }
+        }
+        Action::ChartCursor => {
+            if enter_chart_cursor(app) {
+                *input_mode = InputMode::ChartCursor;
             }
         }
-        KeyCode::Up | KeyCode::Char('k') => {
-            app.selected_target = app.selected_target.saturating_sub(1);
+        // Only meaningful while the chart cursor is shown
+        Action::CursorOlder | Action::CursorNewer | Action::CursorOldest | Action::CursorNewest => {
+        }
+        Action::FocusMetrics => {
+            if enter_metrics_focus(app, &mut overlays.metric_cursor) {
+                *input_mode = InputMode::MetricsFocus;
+            }
         }
-        KeyCode::Tab => {
+        Action::CycleWindow => app.cycle_window(),
+        Action::TargetWindow => app.cycle_target_window(),
+        Action::TimeAxis => app.toggle_time_axis(),
+        Action::LogScale => app.toggle_log_scale(),
+        Action::MoveTargetDown => move_selected_target(app, false),
+        Action::MoveTargetUp => move_selected_target(app, true),
+        Action::Down if app.overview.is_some() => app.select_overview_neighbour(true),
+        Action::Up if app.overview.is_some() => app.select_overview_neighbour(false),
+        Action::Down => app.select_next_target(),
+        Action::Up => app.select_prev_target(),
+        Action::NextProfile => {
             if let Some(target) = app.selected_target_mut()
                 && !target.profiles.is_empty()
             {
                 target.selected_profile = (target.selected_profile + 1) % target.profiles.len();
             }
         }
-        KeyCode::Char('1') => app.toggle_metric(MetricKind::Total),
-        KeyCode::Char('2') => app.toggle_metric(MetricKind::Dns),
-        KeyCode::Char('3') => app.toggle_metric(MetricKind::Connect),
-        KeyCode::Char('4') => app.toggle_metric(MetricKind::Tls),
-        KeyCode::Char('5') => app.toggle_metric(MetricKind::Ttfb),
-        KeyCode::Char('6') => app.toggle_metric(MetricKind::Download),
-        KeyCode::Char('7') => app.toggle_metric(MetricKind::Rtt),
-        KeyCode::Char('8') => app.toggle_metric(MetricKind::Retrans),
+        Action::ToggleMetric(n) => {
+            if let Some(&metric) = TOGGLE_METRICS.get(usize::from(n).wrapping_sub(1)) {
+                app.toggle_metric(metric);
+            }
+        }
+        Action::CopyView => {
+            if app.selected_target().is_some() {
+                let changed = app.copy_view_to_all_targets();
+                let noun = if changed == 1 { "target" } else { "targets" };
+                app.status_notice = Some(StatusNotice {
+                    at: Instant::now(),
+                    message: format!("view copied to {changed} other {noun}"),
+                    failed: false,
+                });
+                let _ = storage::save(&app.to_persisted_state());
+            }
+        }
         // Metrics category navigation
-        KeyCode::Char(']') => {
+        Action::NextCategory => {
             if let Some(target) = app.selected_target_mut() {
                 target.metrics_category = target.metrics_category.next();
             }
         }
-        KeyCode::Char('[') => {
+        Action::PrevCategory => {
             if let Some(target) = app.selected_target_mut() {
                 target.metrics_category = target.metrics_category.prev();
             }
         }
-        _ => {}
     }
     false
 }
+
+fn move_selected_target(app: &mut AppState, up: bool) {
+    if app.move_selected_target(up) {
+        let _ = storage::save(&app.to_persisted_state());
+    }
+}
@@ -1,9 +1,23 @@
-use crate::app::{AppState, apply_edit_command};
+use crate::alerts::parse_alert_rules;
+use crate::app::{AppState, apply_edit_command, apply_schedule, parse_tags};
+use crate::config::{
+    ConnReusePolicy, ConnectTo, HttpVersion, ProbeMethod, ProfileConfig, TlsVersion,
+};
+use crate::metrics::StatColumn;
+use crate::probe::ProbeSample;
 use crate::storage;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
 
 use super::super::render::{seed_settings_input, settings_rows};
-use super::super::state::{InputMode, SettingsField, SettingsState, parse_link_capacity_mbps};
+use super::super::state::{
+    InputMode, SettingsField, SettingsState, parse_auth, parse_capture_headers,
+    parse_download_slots, parse_expected_status, parse_hidden_error_markers,
+    parse_interval_override, parse_jitter_pct, parse_link_capacity_mbps, parse_max_points,
+    parse_max_read_bytes, parse_notify_rule, parse_quiet_hours, parse_recv_speed, parse_retention,
+    parse_slo_latency_ms, parse_slo_target, parse_url_edit,
+};
+use super::super::theme::SERIES_NAMES;
 
 pub(in crate::features::ui) fn handle_settings_key(
     key: KeyEvent,
@@ -11,6 +25,7 @@ pub(in crate::features::ui) fn handle_settings_key(
     input_mode: &mut InputMode,
     input_buffer: &mut String,
     settings_state: &mut SettingsState,
+    sample_tx: &crossbeam_channel::Sender<ProbeSample>,
 ) {
     let rows = settings_rows(app);
     settings_state.clamp(rows.len());
@@ -28,28 +43,192 @@ pub(in crate::features::ui) fn handle_settings_key(
             settings_state.select_prev(rows.len());
             settings_state.clear_notice();
         }
+        KeyCode::Tab => {
+            if let Some(target) = app.selected_target_mut()
+                && target.profiles.len() > 1
+            {
+                target.selected_profile = (target.selected_profile + 1) % target.profiles.len();
+                settings_state.notice = Some(format!(
+                    "Now editing profile {}",
+                    target.profiles[target.selected_profile].config.name
+                ));
+            }
+        }
         KeyCode::Enter | KeyCode::Char(' ') => {
             settings_state.clear_notice();
             if let Some(row) = rows.get(settings_state.selected) {
                 match row.field {
-                    SettingsField::TargetDnsEnabled => {
+                    SettingsField::BurstSamples => {
+                        let exclude = !app.global.exclude_burst_from_long_windows;
+                        app.set_exclude_burst(exclude);
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::PrimingSamples => {
+                        let include = !app.global.sampling.include_priming_samples;
+                        app.set_include_priming(include);
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::MissedSlots => {
+                        let count = !app.global.count_missed_as_loss;
+                        app.set_count_missed_as_loss(count);
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::IncludeFailures => {
+                        app.global.include_failures_in_latency =
+                            !app.global.include_failures_in_latency;
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::ProxyEnv => {
+                        let enabled = !app.global.use_proxy_env;
+                        app.set_use_proxy_env(enabled);
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::StatsColumns => {
+                        app.global.stats_preset = app.global.stats_preset.next();
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::ChartStat => {
+                        app.global.chart_stat = next_chart_stat(app.global.chart_stat);
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::Theme => {
+                        app.set_theme(app.global.theme.next());
+                        let _ = storage::save(&app.to_persisted_state());
+                    }
+                    SettingsField::TargetDnsMode => {
+                        if let Some(target) = app.selected_target() {
+                            // DNS worker, then resolving in every probe, then off
+                            let mut updated = target.config.clone();
+                            (updated.dns_enabled, updated.dns_combined) =
+                                match (updated.dns_enabled, updated.dns_combined) {
+                                    (true, false) => (true, true),
+                                    (true, true) => (false, false),
+                                    (false, _) => (true, false),
+                                };
+                            app.edit_target_config(updated);
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::TargetInsecure => {
+                        if let Some(target) = app.selected_target() {
+                            let mut updated = target.config.clone();
+                            updated.insecure = !updated.insecure;
+                            app.edit_target_config(updated);
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::TargetPerIp => {
                         if let Some(target) = app.selected_target() {
                             let mut updated = target.config.clone();
-                            updated.dns_enabled = !updated.dns_enabled;
-                            app.update_target_config(app.selected_target, updated);
+                            updated.per_ip = !updated.per_ip;
+                            app.edit_target_config(updated);
                             let _ = storage::save(&app.to_persisted_state());
                         }
                     }
                     SettingsField::TargetPane => {
-                        app.cycle_pane_mode(app.selected_target);
+                        if let Some(target_id) = app.selected_target_id() {
+                            app.cycle_pane_mode(target_id);
+                        }
                     }
                     SettingsField::TargetPaused => {
-                        app.toggle_pause(app.selected_target);
+                        if let Some(target_id) = app.selected_target_id() {
+                            app.toggle_pause(target_id);
+                        }
+                    }
+                    SettingsField::ProfileMethod => {
+                        let is_ping = app
+                            .selected_target()
+                            .and_then(|target| target.profiles.get(target.selected_profile))
+                            .is_some_and(|profile| profile.config.method == ProbeMethod::Ping);
+                        if is_ping {
+                            settings_state.notice =
+                                Some("A ping profile sends no HTTP request".to_string());
+                        } else if edit_selected_profile(app, |profile| {
+                            profile.method = match profile.method {
+                                ProbeMethod::Head => ProbeMethod::Get,
+                                _ => ProbeMethod::Head,
+                            };
+                        }) {
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::ProfileHttp => {
+                        if edit_selected_profile(app, |profile| {
+                            profile.http = match profile.http {
+                                HttpVersion::H1 => HttpVersion::H2,
+                                HttpVersion::H2 => HttpVersion::H2PriorKnowledge,
+                                HttpVersion::H2PriorKnowledge => HttpVersion::H1_0,
+                                HttpVersion::H1_0 => HttpVersion::H1,
+                            };
+                        }) {
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::ProfileTls => {
+                        if edit_selected_profile(app, |profile| {
+                            profile.tls = match profile.tls {
+                                TlsVersion::Tls12 => TlsVersion::Tls13,
+                                TlsVersion::Tls13 => TlsVersion::Tls12,
+                            };
+                        }) {
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::ProfileReuse => {
+                        if edit_selected_profile(app, |profile| {
+                            profile.conn_reuse = match profile.conn_reuse {
+                                ConnReusePolicy::Warm => ConnReusePolicy::Cold,
+                                ConnReusePolicy::Cold => ConnReusePolicy::Warm,
+                            };
+                        }) {
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::ProfileColor => {
+                        if edit_selected_profile(app, |profile| {
+                            profile.color = next_profile_color(profile.color);
+                        }) {
+                            let _ = storage::save(&app.to_persisted_state());
+                        }
+                    }
+                    SettingsField::TargetDuplicate => {
+                        if let Some(index) =
+                            app.duplicate_target(app.selected_target, sample_tx.clone())
+                        {
+                            let _ = storage::save(&app.to_persisted_state());
+                            settings_state.notice = Some(format!(
+                                "Now editing {}",
+                                app.targets[index].config.display_name()
+                            ));
+                        }
                     }
                     SettingsField::UiRefreshHz
+                    | SettingsField::ErrorMarkers
                     | SettingsField::LinkCapacityMbps
+                    | SettingsField::QuietHours
+                    | SettingsField::Notifications
+                    | SettingsField::Retention
+                    | SettingsField::MaxPoints
+                    | SettingsField::TargetUrl
+                    | SettingsField::TargetTags
                     | SettingsField::TargetInterval
-                    | SettingsField::TargetTimeout => {
+                    | SettingsField::TargetTimeout
+                    | SettingsField::TargetAlerts
+                    | SettingsField::TargetSloTarget
+                    | SettingsField::TargetSloLatency
+                    | SettingsField::TargetJitter
+                    | SettingsField::TargetSchedule
+                    | SettingsField::TargetUnixSocket
+                    | SettingsField::TargetConnectTo
+                    | SettingsField::ProfileName
+                    | SettingsField::ProfileMaxReadBytes
+                    | SettingsField::ProfileRecvSpeed
+                    | SettingsField::DownloadSlots
+                    | SettingsField::ProfileInterval
+                    | SettingsField::ProfileExpectedStatus
+                    | SettingsField::ProfileExpectBody
+                    | SettingsField::ProfileCaptureHeaders
+                    | SettingsField::ProfileAuth => {
                         *input_mode = InputMode::SettingsEdit(row.field);
                         input_buffer.clear();
                         input_buffer.push_str(&seed_settings_input(app, row.field));
@@ -74,6 +253,7 @@ pub(in crate::features::ui) fn handle_settings_edit_key(
             *input_mode = InputMode::Settings;
             input_buffer.clear();
             settings_state.clear_notice();
+            settings_state.pending_url = None;
         }
         KeyCode::Enter => {
             let trimmed = input_buffer.trim();
@@ -101,11 +281,59 @@ pub(in crate::features::ui) fn handle_settings_edit_key(
                         settings_state.notice = Some(message.to_string());
                     }
                 },
+                SettingsField::Retention => match parse_retention(trimmed) {
+                    Ok(value) => {
+                        let max_points = app.global.sampling.max_points_per_window;
+                        app.set_retention_policy(max_points, value);
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::MaxPoints => match parse_max_points(trimmed) {
+                    Ok(value) => {
+                        let retention = app.global.sampling.retention;
+                        app.set_retention_policy(value, retention);
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::QuietHours => match parse_quiet_hours(trimmed) {
+                    Ok(value) => {
+                        app.global.quiet_hours = value;
+                        app.refresh_quiet_hours();
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
+                SettingsField::Notifications => match parse_notify_rule(trimmed) {
+                    Ok(value) => {
+                        app.global.notify = value;
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
+                SettingsField::ErrorMarkers => match parse_hidden_error_markers(trimmed) {
+                    Ok(hidden) => {
+                        app.global.hidden_error_markers = hidden;
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
                 SettingsField::TargetInterval => {
                     if let Some(target) = app.selected_target() {
                         let command = format!("interval={trimmed}");
                         if let Some(updated) = apply_edit_command(target, &command) {
-                            app.update_target_config(app.selected_target, updated);
+                            app.edit_target_config(updated);
                             applied = true;
                         } else {
                             settings_state.notice = Some("Invalid interval value".to_string());
@@ -116,16 +344,272 @@ pub(in crate::features::ui) fn handle_settings_edit_key(
                     if let Some(target) = app.selected_target() {
                         let command = format!("timeout={trimmed}");
                         if let Some(updated) = apply_edit_command(target, &command) {
-                            app.update_target_config(app.selected_target, updated);
+                            app.edit_target_config(updated);
                             applied = true;
                         } else {
                             settings_state.notice = Some("Invalid timeout value".to_string());
                         }
                     }
                 }
-                SettingsField::TargetDnsEnabled
+                SettingsField::TargetAlerts => {
+                    if let Some(target) = app.selected_target() {
+                        match parse_alert_rules(trimmed) {
+                            Ok(rules) => {
+                                let mut updated = target.config.clone();
+                                updated.alert_rules = rules;
+                                app.edit_target_config(updated);
+                                applied = true;
+                            }
+                            Err(err) => {
+                                settings_state.notice = Some(err.to_string());
+                            }
+                        }
+                    }
+                }
+                SettingsField::TargetSloTarget => match parse_slo_target(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target() {
+                            let mut updated = target.config.clone();
+                            updated.slo_target = value;
+                            app.edit_target_config(updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::TargetSloLatency => match parse_slo_latency_ms(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target() {
+                            let mut updated = target.config.clone();
+                            updated.slo_latency_ms = value;
+                            app.edit_target_config(updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::TargetUrl => match parse_url_edit(trimmed) {
+                    Ok(url) => {
+                        if let Some(target) = app.selected_target() {
+                            let current = target.config.url.scheme().to_string();
+                            let confirmed =
+                                settings_state.pending_url.take().as_ref() == Some(&url);
+                            if url.scheme() != current && !confirmed {
+                                settings_state.notice = Some(format!(
+                                    "Scheme changes {current} → {}; press Enter again to confirm",
+                                    url.scheme()
+                                ));
+                                settings_state.pending_url = Some(url);
+                            } else {
+                                app.update_target_url(target.config.id, url);
+                                applied = true;
+                            }
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::TargetTags => {
+                    if let Some(target_id) = app.selected_target_id() {
+                        app.set_target_tags(target_id, parse_tags(trimmed));
+                        applied = true;
+                    }
+                }
+                SettingsField::TargetJitter => match parse_jitter_pct(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target() {
+                            let mut updated = target.config.clone();
+                            updated.jitter_pct = value;
+                            app.edit_target_config(updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::TargetSchedule => {
+                    if let Some(target) = app.selected_target() {
+                        let mut updated = target.config.clone();
+                        match apply_schedule(&mut updated, trimmed) {
+                            Ok(()) => {
+                                app.edit_target_config(updated);
+                                applied = true;
+                            }
+                            Err(err) => {
+                                settings_state.notice = Some(format!("Invalid schedule: {err}"));
+                            }
+                        }
+                    }
+                }
+                SettingsField::TargetUnixSocket => {
+                    if let Some(target) = app.selected_target() {
+                        let mut updated = target.config.clone();
+                        updated.unix_socket = (!trimmed.is_empty()).then(|| PathBuf::from(trimmed));
+                        app.edit_target_config(updated);
+                        applied = true;
+                    }
+                }
+                SettingsField::TargetConnectTo => {
+                    let parsed = if trimmed.is_empty() {
+                        Ok(None)
+                    } else {
+                        trimmed.parse::<ConnectTo>().map(Some)
+                    };
+                    match parsed {
+                        Ok(connect_to) => {
+                            if let Some(target) = app.selected_target() {
+                                let mut updated = target.config.clone();
+                                updated.connect_to = connect_to;
+                                app.edit_target_config(updated);
+                                applied = true;
+                            }
+                        }
+                        Err(err) => {
+                            settings_state.notice = Some(format!("Invalid connect-to: {err}"));
+                        }
+                    }
+                }
+                SettingsField::ProfileInterval => match parse_interval_override(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target()
+                            && let Some(profile) = target.profiles.get(target.selected_profile)
+                        {
+                            let target_id = target.config.id;
+                            let mut updated = profile.config.clone();
+                            updated.interval_override = value;
+                            app.edit_profile_config(target_id, updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::ProfileExpectedStatus => match parse_expected_status(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target()
+                            && let Some(profile) = target.profiles.get(target.selected_profile)
+                        {
+                            let target_id = target.config.id;
+                            let mut updated = profile.config.clone();
+                            updated.expected_status = value;
+                            app.edit_profile_config(target_id, updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
+                SettingsField::ProfileExpectBody => {
+                    if let Some(target) = app.selected_target()
+                        && let Some(profile) = target.profiles.get(target.selected_profile)
+                    {
+                        let target_id = target.config.id;
+                        let mut updated = profile.config.clone();
+                        updated.expect_body_contains =
+                            Some(trimmed.to_string()).filter(|value| !value.is_empty());
+                        app.edit_profile_config(target_id, updated);
+                        applied = true;
+                    }
+                }
+                SettingsField::ProfileCaptureHeaders => match parse_capture_headers(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target()
+                            && let Some(profile) = target.profiles.get(target.selected_profile)
+                        {
+                            let target_id = target.config.id;
+                            let mut updated = profile.config.clone();
+                            updated.capture_headers = value;
+                            app.edit_profile_config(target_id, updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
+                SettingsField::ProfileMaxReadBytes => match parse_max_read_bytes(trimmed) {
+                    Ok(value) => {
+                        applied = edit_selected_profile(app, |profile| {
+                            profile.max_read_bytes = value;
+                        });
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::ProfileName => {
+                    if let Some(target) = app.selected_target()
+                        && let Some(profile) = target.profiles.get(target.selected_profile)
+                    {
+                        let (target_id, profile_id) = (target.config.id, profile.config.id);
+                        match app.rename_profile(target_id, profile_id, trimmed) {
+                            Ok(()) => applied = true,
+                            Err(message) => settings_state.notice = Some(message),
+                        }
+                    }
+                }
+                SettingsField::ProfileRecvSpeed => match parse_recv_speed(trimmed) {
+                    Ok(value) => {
+                        applied = edit_selected_profile(app, |profile| {
+                            profile.max_recv_speed = value;
+                        });
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::DownloadSlots => match parse_download_slots(trimmed) {
+                    Ok(value) => {
+                        app.set_download_slots(value);
+                        applied = true;
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message.to_string());
+                    }
+                },
+                SettingsField::ProfileAuth => match parse_auth(trimmed) {
+                    Ok(value) => {
+                        if let Some(target) = app.selected_target()
+                            && let Some(profile) = target.profiles.get(target.selected_profile)
+                        {
+                            let target_id = target.config.id;
+                            let mut updated = profile.config.clone();
+                            updated.auth = value;
+                            app.edit_profile_config(target_id, updated);
+                            applied = true;
+                        }
+                    }
+                    Err(message) => {
+                        settings_state.notice = Some(message);
+                    }
+                },
+                SettingsField::BurstSamples
+                | SettingsField::IncludeFailures
+                | SettingsField::PrimingSamples
+                | SettingsField::MissedSlots
+                | SettingsField::ProxyEnv
+                | SettingsField::StatsColumns
+                | SettingsField::ChartStat
+                | SettingsField::Theme
+                | SettingsField::TargetDnsMode
+                | SettingsField::TargetInsecure
+                | SettingsField::TargetPerIp
                 | SettingsField::TargetPane
-                | SettingsField::TargetPaused => {}
+                | SettingsField::TargetPaused
+                | SettingsField::TargetDuplicate
+                | SettingsField::ProfileColor
+                | SettingsField::ProfileMethod
+                | SettingsField::ProfileHttp
+                | SettingsField::ProfileTls
+                | SettingsField::ProfileReuse => {}
             }
 
             if applied {
@@ -146,3 +630,40 @@ pub(in crate::features::ui) fn handle_settings_edit_key(
         _ => {}
     }
 }
+
+/// Applies `edit` to the selected profile of the selected target and sends
+/// the result to its worker. Returns `false` when there is no such profile.
+fn edit_selected_profile(app: &mut AppState, edit: impl FnOnce(&mut ProfileConfig)) -> bool {
+    let Some(target) = app.selected_target() else {
+        return false;
+    };
+    let Some(profile) = target.profiles.get(target.selected_profile) else {
+        return false;
+    };
+    let target_id = target.config.id;
+    let mut updated = profile.config.clone();
+    edit(&mut updated);
+    app.edit_profile_config(target_id, updated);
+    true
+}
+
+/// Cycles positional color → each palette color → positional color.
+fn next_profile_color(current: Option<u8>) -> Option<u8> {
+    match current {
+        None => Some(0),
+        Some(color) if usize::from(color) + 1 < SERIES_NAMES.len() => Some(color + 1),
+        Some(_) => None,
+    }
+}
+
+/// Cycles raw samples → each chartable stat → raw samples.
+fn next_chart_stat(current: Option<StatColumn>) -> Option<StatColumn> {
+    match current {
+        None => StatColumn::CHARTABLE.first().copied(),
+        Some(stat) => StatColumn::CHARTABLE
+            .iter()
+            .position(|candidate| *candidate == stat)
+            .and_then(|idx| StatColumn::CHARTABLE.get(idx + 1))
+            .copied(),
+    }
+}
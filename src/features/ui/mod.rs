@@ -1,180 +1,295 @@
+mod clipboard;
 mod input;
 mod render;
 mod state;
+mod terminal;
+mod theme;
 
-use crate::app::AppState;
+use crate::alerts::deliver_notification;
+use crate::app::{AppState, take_status_dump_request};
 use crate::probe::ProbeSample;
+use crate::session::SessionRecorder;
+use crate::settings::Keymap;
 use crate::storage;
 use crossterm::event::{self, Event};
-use crossterm::terminal::{
-    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
-};
-use crossterm::{QueueableCommand, execute};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
-use std::io::{self, Stdout, Write};
+use std::io::{self, Stdout};
 use std::time::{Duration, Instant};
 
 use input::{
-    handle_confirm_delete_key, handle_glossary_key, handle_help_key, handle_input_key,
-    handle_normal_key, handle_settings_edit_key, handle_settings_key,
+    handle_alert_log_key, handle_annotations_key, handle_baselines_key, handle_change_log_key,
+    handle_chart_cursor_key, handle_confirm_delete_key, handle_confirm_quit_key,
+    handle_conn_reuse_key, handle_error_log_key, handle_filter_key, handle_glossary_key,
+    handle_help_key, handle_input_key, handle_metric_detail_key, handle_metrics_focus_key,
+    handle_mouse_event, handle_normal_action, handle_outages_key, handle_settings_edit_key,
+    handle_settings_key, handle_snapshot_key, handle_spikes_key, handle_traceroute_key,
 };
 use render::{
-    draw_confirm_delete_popup, draw_footer, draw_glossary_popup, draw_header, draw_help_popup,
-    draw_main, draw_settings_popup, draw_terminal_too_small,
+    add_target_preview, draw_alert_log_popup, draw_annotations_popup, draw_baselines_popup,
+    draw_change_log_popup, draw_confirm_delete_popup, draw_confirm_quit_popup,
+    draw_conn_reuse_popup, draw_error_log_popup, draw_footer, draw_glossary_popup, draw_header,
+    draw_help_popup, draw_main, draw_metric_detail_popup, draw_outages_popup, draw_settings_popup,
+    draw_snapshot_popup, draw_spikes_popup, draw_terminal_too_small, draw_traceroute_popup,
 };
-use state::{InputMode, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH, SettingsState};
+use state::{InputMode, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH, ViewState};
+use terminal::{Crossterm, restore_terminal, setup_terminal};
+use theme::Theme;
+
+pub use terminal::{SetupStep, TerminalError, check_terminal};
+
+/// Most samples applied per tick; the rest wait for the next one, so a
+/// backlog after a stalled redraw is worked off without a visible hitch.
+const MAX_SAMPLES_PER_TICK: usize = 512;
 
 pub fn run_ui(
     mut app: AppState,
+    keymap: Keymap,
     sample_rx: crossbeam_channel::Receiver<ProbeSample>,
     sample_tx: crossbeam_channel::Sender<ProbeSample>,
+    mut recorder: Option<SessionRecorder>,
+) -> io::Result<()> {
+    setup_terminal(&mut Crossterm)?;
+    let mut terminal = match Terminal::new(CrosstermBackend::new(io::stdout())) {
+        Ok(terminal) => terminal,
+        Err(err) => {
+            let _ = restore_terminal(&mut Crossterm);
+            return Err(err);
+        }
+    };
+
+    let mut view = ViewState::new(keymap);
+    if !app.pending_headers.is_empty() {
+        view.input_mode = InputMode::HeaderValue;
+    }
+    // An error in the loop still saves state and hands the terminal back
+    let result = event_loop(
+        &mut terminal,
+        &mut app,
+        &mut view,
+        &sample_rx,
+        &sample_tx,
+        &mut recorder,
+    );
+
+    app.shutdown();
+    // A replay must not overwrite the user's own targets or history
+    let history = if app.replaying {
+        None
+    } else {
+        if let Err(err) = storage::save(&app.to_persisted_state()) {
+            tracing::error!("failed to save state on exit: {err}");
+        }
+        spawn_history_save(&app)
+    };
+    let restored = cleanup_terminal(&mut terminal);
+    // The terminal is back before the history is written, so quitting feels instant
+    if let Some(handle) = history {
+        let _ = handle.join();
+    }
+    result.and(restored)
+}
+
+fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut AppState,
+    view: &mut ViewState,
+    sample_rx: &crossbeam_channel::Receiver<ProbeSample>,
+    sample_tx: &crossbeam_channel::Sender<ProbeSample>,
+    recorder: &mut Option<SessionRecorder>,
 ) -> io::Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let mut input_mode = InputMode::Normal;
-    let mut input_buffer = String::new();
-    let mut settings_state = SettingsState::new();
-    let mut glossary_page: usize = 0;
     let mut should_quit = false;
     let mut last_tick = Instant::now();
 
     while !should_quit {
-        while let Ok(sample) = sample_rx.try_recv() {
-            app.apply_sample(sample);
+        apply_pending_samples(app, sample_rx, recorder);
+        if take_status_dump_request() {
+            app.dump_status();
         }
-
-        terminal.draw(|frame| {
-            let size = frame.area();
-
-            // Check minimum terminal size
-            if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
-                draw_terminal_too_small(frame, size);
-                return;
+        for (target_id, annotation) in app.take_new_annotations() {
+            if let Some(active) = recorder.as_mut()
+                && let Err(err) = active.annotate(target_id, &annotation)
+            {
+                tracing::error!("session recording stopped: {err}");
+                *recorder = None;
             }
+        }
+        if let Some(active) = recorder.as_mut()
+            && let Err(err) = active.flush()
+        {
+            tracing::error!("session recording stopped: {err}");
+            *recorder = None;
+        }
+        app.drain_traceroute_updates();
+        if app.drain_tls13_detection(sample_tx) {
+            let _ = storage::save(&app.to_persisted_state());
+        }
+        app.refresh_quiet_hours();
+        app.check_worker_health(sample_tx);
+        app.sync_endpoints(sample_tx);
+        app.reap_workers();
+        app.evaluate_alerts();
+        for notification in app.evaluate_notifications(Instant::now()) {
+            deliver_notification(notification);
+        }
 
-            // Main layout: Header, Content, Input (optional), Footer
-            let mut constraints = vec![
-                Constraint::Length(1), // Header
-                Constraint::Min(10),   // Content
-            ];
-            if matches!(input_mode, InputMode::AddTarget) {
-                constraints.push(Constraint::Length(3)); // Input bar
-            }
-            constraints.push(Constraint::Length(1)); // Footer
-
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints(constraints)
-                .split(size);
-
-            // Header bar
-            draw_header(frame, chunks[0], &app);
-
-            // Main content area
-            draw_main(frame, chunks[1], &app);
-
-            // Input bar (if in input mode)
-            let footer_idx = if matches!(input_mode, InputMode::AddTarget) {
-                let prompt = match input_mode {
-                    InputMode::AddTarget => " Add Target: <url> [profile1,profile2,...] ",
-                    _ => "",
-                };
-                let input = Paragraph::new(Line::from(vec![
-                    Span::styled(prompt, Style::default().fg(Color::Yellow)),
-                    Span::raw(&input_buffer),
-                    Span::styled("█", Style::default().fg(Color::Gray)),
-                ]))
-                .style(Style::default().bg(Color::DarkGray));
-                frame.render_widget(input, chunks[2]);
-                3
-            } else {
-                2
-            };
-
-            // Footer with keybindings
-            draw_footer(frame, chunks[footer_idx], input_mode);
-
-            // Overlay popups
-            match input_mode {
-                InputMode::Help => draw_help_popup(frame, size),
-                InputMode::Glossary => draw_glossary_popup(frame, size, glossary_page),
-                InputMode::Settings | InputMode::SettingsEdit(_) => {
-                    draw_settings_popup(
-                        frame,
-                        size,
-                        &app,
-                        &settings_state,
-                        input_mode,
-                        &input_buffer,
-                    );
-                }
-                InputMode::ConfirmDelete => draw_confirm_delete_popup(frame, size, &app),
-                _ => {}
-            }
-        })?;
+        terminal.draw(|frame| draw_frame(frame, app, view))?;
 
         let tick_rate = Duration::from_secs_f64(1.0 / app.global.ui_refresh_hz as f64);
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)?
-            && let Event::Key(key) = event::read()?
+        let event = if event::poll(timeout)? {
+            Some(event::read()?)
+        } else {
+            None
+        };
+        if let Some(Event::Resize(..)) = event {
+            // Redraw at the new size now rather than on the next tick, and
+            // wipe whatever the old layout left on screen
+            terminal.clear()?;
+            continue;
+        }
+        if let Some(Event::Mouse(mouse)) = event
+            && view.input_mode == InputMode::Normal
         {
-            match input_mode {
+            handle_mouse_event(mouse, app, &view.hits);
+        }
+        if let Some(Event::Key(key)) = event {
+            match view.input_mode {
                 InputMode::Normal => {
-                    if handle_normal_key(
-                        key,
-                        &mut app,
-                        &mut input_mode,
-                        &mut input_buffer,
-                        &mut settings_state,
-                        &mut glossary_page,
-                    ) {
+                    if let Some(action) = view.keymap.action(&key)
+                        && handle_normal_action(
+                            action,
+                            app,
+                            &mut view.input_mode,
+                            &mut view.input_buffer,
+                            &mut view.settings_state,
+                            &mut view.overlays,
+                            sample_tx,
+                        )
+                    {
                         should_quit = true;
                     }
                 }
+                InputMode::Filter => {
+                    handle_filter_key(key, app, &mut view.input_mode, &mut view.input_buffer);
+                }
                 InputMode::Help => {
-                    handle_help_key(key, &mut input_mode);
+                    handle_help_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::AlertLog => {
+                    handle_alert_log_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::ConnReuse => {
+                    handle_conn_reuse_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::Spikes => {
+                    handle_spikes_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::Outages => {
+                    handle_outages_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::Snapshot => {
+                    handle_snapshot_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::ErrorLog => {
+                    handle_error_log_key(
+                        key,
+                        &view.keymap,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.overlays.error_log_scroll,
+                    );
+                }
+                InputMode::ChangeLog => {
+                    handle_change_log_key(
+                        key,
+                        &view.keymap,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.overlays.change_log_scroll,
+                    );
                 }
                 InputMode::Glossary => {
-                    handle_glossary_key(key, &mut input_mode, &mut glossary_page);
+                    handle_glossary_key(
+                        key,
+                        &view.keymap,
+                        &mut view.input_mode,
+                        &mut view.overlays.glossary_page,
+                    );
                 }
                 InputMode::Settings => {
                     handle_settings_key(
                         key,
-                        &mut app,
-                        &mut input_mode,
-                        &mut input_buffer,
-                        &mut settings_state,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.input_buffer,
+                        &mut view.settings_state,
+                        sample_tx,
                     );
                 }
                 InputMode::SettingsEdit(field) => {
                     handle_settings_edit_key(
                         key,
-                        &mut app,
-                        &mut input_mode,
-                        &mut input_buffer,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.input_buffer,
                         field,
-                        &mut settings_state,
+                        &mut view.settings_state,
+                    );
+                }
+                InputMode::Traceroute => {
+                    handle_traceroute_key(key, &view.keymap, app, &mut view.input_mode);
+                }
+                InputMode::ChartCursor => {
+                    handle_chart_cursor_key(key, &view.keymap, app, &mut view.input_mode);
+                }
+                InputMode::MetricsFocus => {
+                    handle_metrics_focus_key(
+                        key,
+                        &view.keymap,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.overlays.metric_cursor,
+                    );
+                }
+                InputMode::MetricDetail => {
+                    handle_metric_detail_key(key, &view.keymap, &mut view.input_mode);
+                }
+                InputMode::Baselines => {
+                    handle_baselines_key(
+                        key,
+                        &view.keymap,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.overlays.baseline_cursor,
+                    );
+                }
+                InputMode::Annotations => {
+                    handle_annotations_key(
+                        key,
+                        &view.keymap,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.overlays.annotation_cursor,
                     );
                 }
                 InputMode::ConfirmDelete => {
-                    handle_confirm_delete_key(key, &mut app, &mut input_mode);
+                    handle_confirm_delete_key(key, app, &mut view.input_mode);
+                }
+                InputMode::ConfirmQuit => {
+                    should_quit = handle_confirm_quit_key(key, &view.keymap, &mut view.input_mode);
                 }
                 _ => {
                     handle_input_key(
                         key,
-                        &mut app,
-                        &mut input_mode,
-                        &mut input_buffer,
-                        &sample_tx,
+                        app,
+                        &mut view.input_mode,
+                        &mut view.input_buffer,
+                        sample_tx,
                     );
                 }
             }
@@ -184,16 +299,302 @@ pub fn run_ui(
             last_tick = Instant::now();
         }
     }
-
-    let _ = storage::save(&app.to_persisted_state());
-    cleanup_terminal(&mut terminal)?;
     Ok(())
 }
 
+/// Names the header, profile and target the typed value goes to.
+fn header_value_prompt(app: &AppState) -> String {
+    let Some(pending) = app.pending_headers.first() else {
+        return String::new();
+    };
+    let target = app
+        .targets
+        .iter()
+        .find(|target| target.config.id == pending.target_id);
+    let profile = target
+        .and_then(|target| {
+            target
+                .profiles
+                .iter()
+                .find(|profile| profile.config.id == pending.profile_id)
+        })
+        .map_or("", |profile| profile.config.name.as_str());
+    let host = target
+        .and_then(|target| target.config.url.host_str())
+        .unwrap_or("");
+    format!(
+        " {} for {host} ({profile}), {} left: ",
+        pending.name,
+        app.pending_headers.len()
+    )
+}
+
+/// Lays out one full frame from the current size; nothing carries over from
+/// the previous frame's layout except the list scroll offset.
+fn draw_frame(frame: &mut ratatui::Frame, app: &AppState, view: &mut ViewState) {
+    let theme = Theme::of(app);
+    let size = frame.area();
+    view.hits.clear();
+
+    // Check minimum terminal size
+    if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+        draw_terminal_too_small(frame, size, theme);
+        return;
+    }
+
+    // Main layout: Header, Content, Input (optional), Footer
+    let mut constraints = vec![
+        Constraint::Length(1), // Header
+        Constraint::Min(10),   // Content
+    ];
+    if matches!(
+        view.input_mode,
+        InputMode::AddTarget
+            | InputMode::ImportTargets
+            | InputMode::ExportTargets
+            | InputMode::HeaderValue
+            | InputMode::Filter
+            | InputMode::BaselineName
+            | InputMode::AnnotationText
+    ) {
+        constraints.push(Constraint::Length(3)); // Input bar
+    }
+    constraints.push(Constraint::Length(1)); // Footer
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(size);
+
+    // Header bar
+    draw_header(frame, chunks[0], app);
+
+    // Main content area
+    draw_main(
+        frame,
+        chunks[1],
+        app,
+        &mut view.target_list_state,
+        view.overlays.metric_cursor,
+        &mut view.hits,
+    );
+
+    // Input bar (if in input mode)
+    let footer_idx = if matches!(
+        view.input_mode,
+        InputMode::AddTarget
+            | InputMode::ImportTargets
+            | InputMode::ExportTargets
+            | InputMode::HeaderValue
+            | InputMode::Filter
+            | InputMode::BaselineName
+            | InputMode::AnnotationText
+    ) {
+        let prompt = match view.input_mode {
+            InputMode::AddTarget => " Add Target: <url> [profile1,profile2,...] ".to_string(),
+            InputMode::ImportTargets => " Import targets from file: ".to_string(),
+            InputMode::ExportTargets => " Export targets to file: ".to_string(),
+            InputMode::HeaderValue => header_value_prompt(app),
+            InputMode::Filter => " Filter: ".to_string(),
+            InputMode::BaselineName => " Baseline name: ".to_string(),
+            InputMode::AnnotationText => " Note: ".to_string(),
+            _ => String::new(),
+        };
+        // Header values are secrets: only their length is shown
+        let typed = if view.input_mode == InputMode::HeaderValue {
+            "•".repeat(view.input_buffer.chars().count())
+        } else {
+            view.input_buffer.clone()
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::styled(prompt, Style::default().fg(theme.warn)),
+            Span::raw(typed),
+            Span::styled("█", Style::default().fg(theme.subtle)),
+        ])];
+        if view.input_mode == InputMode::AddTarget {
+            lines.push(add_target_preview(&view.input_buffer, theme));
+        }
+        let input = Paragraph::new(lines).style(Style::default().bg(theme.muted));
+        frame.render_widget(input, chunks[2]);
+        3
+    } else {
+        2
+    };
+
+    // Footer with keybindings
+    draw_footer(
+        frame,
+        chunks[footer_idx],
+        view.input_mode,
+        &view.keymap,
+        theme,
+    );
+
+    // Overlay popups
+    match view.input_mode {
+        InputMode::Help => draw_help_popup(frame, size, &view.keymap, theme),
+        InputMode::Glossary => draw_glossary_popup(frame, size, view.overlays.glossary_page, theme),
+        InputMode::Settings | InputMode::SettingsEdit(_) => {
+            draw_settings_popup(
+                frame,
+                size,
+                app,
+                &view.settings_state,
+                view.input_mode,
+                &view.input_buffer,
+            );
+        }
+        InputMode::ConfirmDelete => draw_confirm_delete_popup(frame, size, app),
+        InputMode::ConfirmQuit => draw_confirm_quit_popup(frame, size, app),
+        InputMode::AlertLog => draw_alert_log_popup(frame, size, app),
+        InputMode::ConnReuse => draw_conn_reuse_popup(frame, size, app),
+        InputMode::Spikes => draw_spikes_popup(frame, size, app),
+        InputMode::Outages => draw_outages_popup(frame, size, app),
+        InputMode::MetricDetail => {
+            draw_metric_detail_popup(frame, size, app, view.overlays.metric_cursor);
+        }
+        InputMode::Snapshot => draw_snapshot_popup(
+            frame,
+            size,
+            &view.overlays.snapshot,
+            view.overlays.snapshot_error.as_deref(),
+            theme,
+        ),
+        InputMode::ErrorLog => {
+            draw_error_log_popup(frame, size, app, view.overlays.error_log_scroll);
+        }
+        InputMode::ChangeLog => {
+            draw_change_log_popup(frame, size, app, view.overlays.change_log_scroll);
+        }
+        InputMode::Traceroute => draw_traceroute_popup(frame, size, app),
+        InputMode::Baselines => {
+            draw_baselines_popup(frame, size, app, view.overlays.baseline_cursor);
+        }
+        InputMode::Annotations => {
+            draw_annotations_popup(frame, size, app, view.overlays.annotation_cursor);
+        }
+        _ => {}
+    }
+}
+
+/// Applies up to [`MAX_SAMPLES_PER_TICK`] queued samples, recording each one
+/// if a session recording is active. Returns how many were applied.
+fn apply_pending_samples(
+    app: &mut AppState,
+    sample_rx: &crossbeam_channel::Receiver<ProbeSample>,
+    recorder: &mut Option<SessionRecorder>,
+) -> usize {
+    let mut applied = 0;
+    for sample in sample_rx.try_iter().take(MAX_SAMPLES_PER_TICK) {
+        if let Some(active) = recorder.as_mut()
+            && let Err(err) = active.record(&sample, app.target_config(sample.target_id))
+        {
+            tracing::error!("session recording stopped: {err}");
+            *recorder = None;
+        }
+        app.apply_sample(sample);
+        applied += 1;
+    }
+    applied
+}
+
+/// Saves the samples of a network run for `--restore` on a thread of its
+/// own; only the copy of the samples happens on the UI thread.
+fn spawn_history_save(app: &AppState) -> Option<std::thread::JoinHandle<()>> {
+    if !app.saves_history() {
+        return None;
+    }
+    let path = storage::history_file_path()?;
+    let snapshot = app.history_snapshot();
+    Some(std::thread::spawn(move || match snapshot.write(&path) {
+        Ok(count) => tracing::info!(path = %path.display(), count, "history saved"),
+        Err(err) => tracing::error!("failed to save history on exit: {err}"),
+    }))
+}
+
 fn cleanup_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
-    disable_raw_mode()?;
-    terminal.backend_mut().queue(LeaveAlternateScreen)?;
+    let restored = restore_terminal(&mut Crossterm);
     terminal.show_cursor()?;
-    terminal.backend_mut().flush()?;
-    Ok(())
+    restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TargetPaneMode;
+    use crate::config::{GlobalConfig, TargetConfig, default_profiles};
+    use ratatui::backend::TestBackend;
+    use url::Url;
+
+    #[test]
+    fn frames_render_at_sizes_around_the_minimum() {
+        let mut app = AppState::new(GlobalConfig::default());
+        // A replayed target has no workers; drawing needs none
+        app.load_replay(vec![TargetConfig::new(
+            Url::parse("https://a.example").unwrap(),
+            default_profiles(),
+        )]);
+        app.targets[0].pane_mode = TargetPaneMode::Split;
+        app.targets[0].profiles[0].last_error = Some(crate::probe::ProbeErrorKind::HttpTimeout);
+
+        let mut view = ViewState::new(Keymap::default());
+        let sizes = [
+            (MIN_TERMINAL_WIDTH - 1, MIN_TERMINAL_HEIGHT - 1),
+            (MIN_TERMINAL_WIDTH - 1, MIN_TERMINAL_HEIGHT),
+            (MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT - 1),
+            (MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT),
+            (MIN_TERMINAL_WIDTH + 1, MIN_TERMINAL_HEIGHT + 1),
+            (160, 50),
+            (20, 5),
+        ];
+        let mut terminal = Terminal::new(TestBackend::new(200, 60)).unwrap();
+        for (width, height) in sizes {
+            terminal.backend_mut().resize(width, height);
+            terminal.clear().unwrap();
+            let frame = terminal
+                .draw(|frame| draw_frame(frame, &app, &mut view))
+                .unwrap();
+            assert_eq!((frame.area.width, frame.area.height), (width, height));
+
+            let screen: String = frame
+                .buffer
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            let too_small = width < MIN_TERMINAL_WIDTH || height < MIN_TERMINAL_HEIGHT;
+            assert_eq!(screen.contains("Targets"), !too_small, "{width}x{height}");
+        }
+    }
+
+    #[test]
+    fn a_sample_backlog_is_applied_over_several_ticks() {
+        let mut app = AppState::new(GlobalConfig::default());
+        let (sample_tx, sample_rx) = crate::runtime::sample_channel();
+        let backlog = MAX_SAMPLES_PER_TICK + MAX_SAMPLES_PER_TICK / 2;
+        let target_id = crate::config::TargetId::new_v4();
+        for _ in 0..backlog {
+            sample_tx
+                .try_send(
+                    crate::features::probe::testing::SampleBuilder::ok(
+                        target_id,
+                        crate::config::ProfileId::new_v4(),
+                    )
+                    .build(),
+                )
+                .unwrap();
+        }
+
+        let mut recorder = None;
+        assert_eq!(
+            apply_pending_samples(&mut app, &sample_rx, &mut recorder),
+            MAX_SAMPLES_PER_TICK
+        );
+        assert_eq!(sample_rx.len(), backlog - MAX_SAMPLES_PER_TICK);
+        assert_eq!(
+            apply_pending_samples(&mut app, &sample_rx, &mut recorder),
+            backlog - MAX_SAMPLES_PER_TICK
+        );
+        assert!(sample_rx.is_empty());
+    }
 }
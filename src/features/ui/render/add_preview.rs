@@ -0,0 +1,50 @@
+use super::super::theme::Theme;
+use crate::app::parse_add_target_command;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+
+/// What Enter would add, shown under the Add Target prompt while typing:
+/// the resolved URL (or why it is rejected), the profiles and any tokens
+/// that were not understood.
+pub(in crate::features::ui) fn add_target_preview(input: &str, theme: &Theme) -> Line<'static> {
+    let command = parse_add_target_command(input);
+    let dim = Style::default().fg(theme.subtle);
+    let mut spans = vec![Span::raw(" ")];
+    match &command.url {
+        Ok(url) => spans.push(Span::styled(
+            format!("→ {url}"),
+            Style::default().fg(theme.success),
+        )),
+        Err(err) => spans.push(Span::styled(
+            format!("✗ {err} (Enter disabled)"),
+            Style::default().fg(theme.error),
+        )),
+    }
+    let profiles = match &command.profiles {
+        Some(profiles) => profiles
+            .iter()
+            .map(|profile| {
+                format!(
+                    "{}/{}/{}/{}",
+                    profile.http, profile.tls, profile.conn_reuse, profile.method
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "defaults".to_string(),
+    };
+    spans.push(Span::styled(format!("  profiles: {profiles}"), dim));
+    if !command.tags.is_empty() {
+        spans.push(Span::styled(
+            format!("  tags: {}", command.tags.join(" ")),
+            dim,
+        ));
+    }
+    if !command.unknown_tokens.is_empty() {
+        spans.push(Span::styled(
+            format!("  unknown: {}", command.unknown_tokens.join(" ")),
+            Style::default().fg(theme.error),
+        ));
+    }
+    Line::from(spans)
+}
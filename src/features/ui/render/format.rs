@@ -1,8 +1,14 @@
-use crate::app::MetricsCategory;
-use crate::metrics::{MetricKind, MetricStats};
+use super::super::theme::Theme;
+use crate::app::{AppState, FreshnessLevel, MetricsCategory, ProfileRuntime};
+use crate::common::time::{format_local_hms, local_time};
+use crate::config::ProbeMethod;
+use crate::metrics::{MetricKind, MetricStats, SloStatus, StatsPreset};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::Style;
 use ratatui::text::Span;
+use std::time::{Duration, SystemTime};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub(super) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -24,14 +30,73 @@ pub(super) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect
         .split(popup_layout[1])[1]
 }
 
-pub(super) fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+/// Shortens `s` to at most `max_width` terminal columns, ending in "..."
+/// when cut. Whole graphemes are kept and wide (CJK, emoji) characters
+/// count as two columns, so multibyte names never split or overflow.
+pub(super) fn truncate_string(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let budget = max_width.saturating_sub(ELLIPSIS.len());
+    let mut used = 0;
+    let mut out = String::new();
+    for grapheme in s.graphemes(true) {
+        used += grapheme.width();
+        if used > budget {
+            break;
+        }
+        out.push_str(grapheme);
+    }
+    out.push_str(&ELLIPSIS[..max_width.min(ELLIPSIS.len())]);
+    out
+}
+
+const ELLIPSIS: &str = "...";
+
+/// [`truncate_string`] padded with spaces to exactly `width` columns, for
+/// aligned columns of names that may be multibyte or wide.
+pub(super) fn pad_to_width(s: &str, width: usize) -> String {
+    let mut out = truncate_string(s, width);
+    let used = out.width();
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Formats tags the way they are typed, e.g. "#prod #eu".
+pub(super) fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats how long ago `ts` was, e.g. "42s ago" or "3m ago".
+pub(super) fn format_age(ts: SystemTime) -> String {
+    let age = SystemTime::now().duration_since(ts).unwrap_or_default();
+    format!("{} ago", format_elapsed(age))
+}
+
+/// Formats a duration in its largest whole unit, e.g. "42s" or "3m".
+pub(super) fn format_elapsed(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else if secs >= 60 {
+        format!("{}m", secs / 60)
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{secs}s")
     }
 }
 
+/// Local date and time of `ts` as `MM-DD HH:MM`.
+pub(super) fn format_local_timestamp(ts: SystemTime) -> String {
+    let local = local_time(ts);
+    format!(
+        "{:02}-{:02} {:02}:{:02}",
+        local.month, local.day, local.hour, local.minute
+    )
+}
+
 pub(super) fn format_count(count: u64) -> String {
     if count >= 1_000_000 {
         format!("{:.1}M", count as f64 / 1_000_000.0)
@@ -66,38 +131,71 @@ pub(super) fn format_goodput(bps: f64) -> String {
     }
 }
 
-pub(super) fn style_for_success_rate(rate: f64) -> Style {
+/// Compact byte size with binary K/M suffixes, e.g. `512`, `4.0K`, `1.2M`.
+pub(super) fn format_bytes(bytes: f64) -> String {
+    if bytes >= 1024.0 * 1024.0 {
+        format!("{:.1}M", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.1}K", bytes / 1024.0)
+    } else {
+        format!("{:.0}", bytes)
+    }
+}
+
+pub(super) fn style_for_success_rate(rate: f64, theme: &Theme) -> Style {
     if rate >= 99.0 {
-        Style::default().fg(Color::Green)
+        Style::default().fg(theme.success)
     } else if rate >= 95.0 {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.warn)
     } else {
-        Style::default().fg(Color::Red)
+        Style::default().fg(theme.error)
     }
 }
 
-pub(super) fn style_for_latency(ms: f64) -> Style {
+pub(super) fn style_for_freshness(level: FreshnessLevel, theme: &Theme) -> Style {
+    match level {
+        FreshnessLevel::Fresh => Style::default(),
+        FreshnessLevel::Aging => Style::default().fg(theme.warn),
+        FreshnessLevel::Stale => Style::default().fg(theme.error),
+    }
+}
+
+pub(super) fn style_for_latency(ms: f64, theme: &Theme) -> Style {
     if ms <= 100.0 {
-        Style::default().fg(Color::Green)
+        Style::default().fg(theme.success)
     } else if ms <= 500.0 {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.warn)
+    } else {
+        Style::default().fg(theme.error)
+    }
+}
+
+/// Green while more than a quarter of the error budget remains, yellow until
+/// it is exhausted, red once it is overspent.
+pub(super) fn style_for_slo_budget(slo: &SloStatus, theme: &Theme) -> Style {
+    if slo.budget_remaining < 0 {
+        Style::default().fg(theme.error)
+    } else if slo.budget_remaining as f64 > slo.budget_allowed as f64 * 0.25 {
+        Style::default().fg(theme.success)
     } else {
-        Style::default().fg(Color::Red)
+        Style::default().fg(theme.warn)
     }
 }
 
-pub(super) fn style_for_timeout_count(count: u64) -> Style {
+pub(super) fn style_for_timeout_count(count: u64, theme: &Theme) -> Style {
     if count == 0 {
-        Style::default().fg(Color::Green)
+        Style::default().fg(theme.success)
     } else if count <= 3 {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(theme.warn)
     } else {
-        Style::default().fg(Color::Red)
+        Style::default().fg(theme.error)
     }
 }
 
 /// Get metrics for a specific category
-pub(super) fn metrics_for_category(category: MetricsCategory) -> &'static [MetricKind] {
+pub(in crate::features::ui) fn metrics_for_category(
+    category: MetricsCategory,
+) -> &'static [MetricKind] {
     match category {
         MetricsCategory::Latency => &[
             MetricKind::Dns,
@@ -113,14 +211,87 @@ pub(super) fn metrics_for_category(category: MetricsCategory) -> &'static [Metri
             MetricKind::Reordering,
             MetricKind::TransportLoss,
             MetricKind::ProbeLossRate,
+            MetricKind::MissedProbes,
+            MetricKind::Http4xxRate,
+            MetricKind::Http5xxRate,
+            MetricKind::ConnReuseRate,
+        ],
+        MetricsCategory::Throughput => &[
+            MetricKind::GoodputBps,
+            MetricKind::BandwidthUtilization,
+            MetricKind::ResponseBytes,
+            MetricKind::ContentLength,
+            MetricKind::CacheHitRate,
         ],
-        MetricsCategory::Throughput => &[MetricKind::GoodputBps, MetricKind::BandwidthUtilization],
         MetricsCategory::Tcp => &[MetricKind::Cwnd, MetricKind::Ssthresh],
     }
 }
 
-pub(super) fn format_y_axis_labels(min_y: f64, max_y: f64, unit: &str) -> Vec<Span<'static>> {
+/// Why a profile has no TCP state to show: the OS cannot report it, its
+/// probes have no TCP socket, the kernel refused (with the `getsockopt`
+/// errno), or nothing was read yet.
+pub(super) fn tcp_info_note(app: &AppState, profile: &ProfileRuntime) -> String {
+    if !app.tcp_info.is_available() {
+        return "TCP_INFO not available on this OS".to_string();
+    }
+    if profile.config.method == ProbeMethod::Ping {
+        return "no TCP state for ping".to_string();
+    }
+    match profile.last_sample.as_ref().and_then(|s| s.tcp_info_errno) {
+        Some(errno) => format!(
+            "kernel refused: {}",
+            std::io::Error::from_raw_os_error(errno)
+        ),
+        None => "TCP state not yet sampled".to_string(),
+    }
+}
+
+/// Time-axis labels from the window start (left) to `now` (right): ages such
+/// as `-60s`, or four local `HH:MM:SS` ticks when `absolute` is set.
+pub(super) fn format_x_axis_labels(
+    window: Duration,
+    now: SystemTime,
+    absolute: bool,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let now_style = Style::default().fg(theme.success);
+    if !absolute {
+        let window_secs = window.as_secs();
+        return vec![
+            Span::raw(format!("-{window_secs}s")),
+            Span::raw(format!("-{}s", window_secs / 2)),
+            Span::styled("now", now_style),
+        ];
+    }
+
+    const TICKS: u32 = 4;
+    (0..TICKS)
+        .map(|tick| {
+            let age = window.mul_f64(f64::from(TICKS - 1 - tick) / f64::from(TICKS - 1));
+            let label = format_local_hms(now.checked_sub(age).unwrap_or(now));
+            if tick == TICKS - 1 {
+                Span::styled(label, now_style)
+            } else {
+                Span::raw(label)
+            }
+        })
+        .collect()
+}
+
+/// Bottom, middle and top y-axis labels. With `log_scale` the bounds are
+/// log10 values and the labels show the de-transformed magnitudes.
+pub(super) fn format_y_axis_labels(
+    min_y: f64,
+    max_y: f64,
+    unit: &str,
+    log_scale: bool,
+) -> Vec<Span<'static>> {
     let mid_y = (min_y + max_y) / 2.0;
+    let (min_y, mid_y, max_y) = if log_scale {
+        (10f64.powf(min_y), 10f64.powf(mid_y), 10f64.powf(max_y))
+    } else {
+        (min_y, mid_y, max_y)
+    };
 
     let format_value = |v: f64| -> String {
         match unit {
@@ -143,6 +314,7 @@ pub(super) fn format_y_axis_labels(min_y: f64, max_y: f64, unit: &str) -> Vec<Sp
                     format!("{:.0}b", v)
                 }
             }
+            "B" => format_bytes(v),
             "" => {
                 if v >= 1000.0 {
                     format!("{:.1}K", v / 1000.0)
@@ -161,11 +333,62 @@ pub(super) fn format_y_axis_labels(min_y: f64, max_y: f64, unit: &str) -> Vec<Sp
     ]
 }
 
-pub(super) fn format_stat_triplet(metric: MetricKind, stats: Option<&MetricStats>) -> String {
-    let p50 = format_metric_value(metric, stats.and_then(|stats| stats.p50));
-    let p99 = format_metric_value(metric, stats.and_then(|stats| stats.p99));
-    let mean = format_metric_value(metric, stats.and_then(|stats| stats.mean));
-    format!("{p50}/{p99}/{mean}")
+pub(super) fn format_stat_triplet(
+    metric: MetricKind,
+    stats: Option<&MetricStats>,
+    preset: StatsPreset,
+    warm_up: Option<(u64, u64)>,
+) -> String {
+    if let Some((seen, needed)) = warm_up
+        && stats.is_some_and(|stats| stats.n > 0)
+    {
+        return format_warming(seen, needed);
+    }
+    preset
+        .columns()
+        .iter()
+        .map(|column| {
+            let value = format_metric_value(metric, stats.and_then(|stats| column.select(stats)));
+            // Percentiles read after clamping into the histogram bounds
+            let approximate = column.is_percentile()
+                && stats.is_some_and(|stats| stats.clamped > 0 && stats.n > 0);
+            if approximate {
+                format!("≈{value}")
+            } else {
+                value
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Placeholder for the statistics of a warming profile, e.g. `warming (3/5)`.
+pub(super) fn format_warming(seen: u64, needed: u64) -> String {
+    format!("warming ({seen}/{needed})")
+}
+
+/// Block-character sparkline of the last `width` values, scaled between their
+/// own minimum and maximum.
+pub(super) fn sparkline(values: &[f64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let recent = &values[values.len().saturating_sub(width)..];
+    let (min, max) = recent
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+    let span = max - min;
+    recent
+        .iter()
+        .map(|&value| {
+            let level = if span > 0.0 {
+                ((value - min) / span * 7.0).round() as usize
+            } else {
+                0
+            };
+            BARS[level.min(7)]
+        })
+        .collect()
 }
 
 pub(super) fn format_metric_value(metric: MetricKind, value: Option<f64>) -> String {
@@ -200,7 +423,13 @@ pub(super) fn format_metric_value(metric: MetricKind, value: Option<f64>) -> Str
                 format!("{:.1}M", mbps)
             }
         }
-        MetricKind::BandwidthUtilization | MetricKind::ProbeLossRate => {
+        MetricKind::ResponseBytes | MetricKind::ContentLength => format_bytes(value),
+        MetricKind::BandwidthUtilization
+        | MetricKind::ProbeLossRate
+        | MetricKind::Http4xxRate
+        | MetricKind::Http5xxRate
+        | MetricKind::CacheHitRate
+        | MetricKind::ConnReuseRate => {
             format!("{:.1}%", value * 100.0)
         }
         _ => {
@@ -213,24 +442,6 @@ pub(super) fn format_metric_value(metric: MetricKind, value: Option<f64>) -> Str
     }
 }
 
-pub(super) fn list_state(selected: usize) -> ratatui::widgets::ListState {
-    let mut state = ratatui::widgets::ListState::default();
-    state.select(Some(selected));
-    state
-}
-
-pub(super) fn color_for_index(idx: usize) -> Color {
-    const COLORS: [Color; 6] = [
-        Color::Cyan,
-        Color::Yellow,
-        Color::Green,
-        Color::Magenta,
-        Color::Blue,
-        Color::Red,
-    ];
-    COLORS[idx % COLORS.len()]
-}
-
 pub(super) fn update_bounds(points: &[(f64, f64)], min_y: &mut f64, max_y: &mut f64) {
     if points.is_empty() {
         return;
@@ -1,50 +1,250 @@
-use crate::app::AppState;
+use crate::app::{AppState, STATUS_NOTICE_DURATION, format_duration};
+use crate::settings::{Action, Keymap};
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
+use std::time::{Duration, Instant};
 
 use super::super::state::InputMode;
+use super::super::theme::Theme;
+use super::format::{format_count, style_for_success_rate};
+
+/// Header segments with this importance or more are kept at any width.
+const ALWAYS_SHOWN: u8 = 9;
+
+/// One `│`-separated part of the header; the least important segments are
+/// dropped first when the header is wider than the terminal.
+struct Segment {
+    importance: u8,
+    spans: Vec<Span<'static>>,
+}
+
+impl Segment {
+    fn labeled(
+        importance: u8,
+        label: &'static str,
+        value: String,
+        style: Style,
+        theme: &Theme,
+    ) -> Self {
+        Self {
+            importance,
+            spans: vec![
+                Span::styled(label, Style::default().fg(theme.muted)),
+                Span::styled(format!(" {value} "), style),
+            ],
+        }
+    }
+
+    fn badge(importance: u8, text: String, style: Style) -> Self {
+        Self {
+            importance,
+            spans: vec![Span::styled(text, style)],
+        }
+    }
+
+    fn width(&self) -> usize {
+        // Plus the "│ " separator in front
+        2 + self.spans.iter().map(Span::width).sum::<usize>()
+    }
+}
+
+/// Drops the least important segments, latest first among equals, until
+/// the rest fit in `width` columns after the `brand` prefix.
+fn fit_segments(mut segments: Vec<Segment>, width: usize, brand: usize) -> Vec<Segment> {
+    let total = |segments: &[Segment]| brand + segments.iter().map(Segment::width).sum::<usize>();
+    while total(&segments) > width {
+        let Some((index, _)) = segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.importance < ALWAYS_SHOWN)
+            .min_by_key(|(index, segment)| (segment.importance, std::cmp::Reverse(*index)))
+        else {
+            break;
+        };
+        segments.remove(index);
+    }
+    segments
+}
+
+/// Formats session uptime with two units, e.g. "1h02m" or "3m05s".
+/// Uptime in whole seconds for the first minute, whole minutes after.
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let shown = if secs >= 60 { secs - secs % 60 } else { secs };
+    format_duration(Duration::from_secs(shown))
+}
 
 pub(in crate::features::ui) fn draw_header(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
-    let selected_metrics: Vec<_> = app.selected_metrics.iter().map(|m| m.label()).collect();
+    let theme = Theme::of(app);
+    // The selected target's chart metrics, or the defaults for new targets
+    let selected_metrics: Vec<_> = app
+        .selected_target()
+        .map_or(&app.selected_metrics, |target| &target.selected_metrics)
+        .iter()
+        .map(|m| m.label())
+        .collect();
     let metrics_str = if selected_metrics.is_empty() {
         "none".to_string()
     } else {
         selected_metrics.join(",")
     };
 
-    let header = Line::from(vec![
-        Span::styled(
-            " httpulse",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+    let active_alerts = app.active_alert_count();
+    let alerts_style = if active_alerts > 0 {
+        Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.success)
+    };
+
+    let now = Instant::now();
+    let session = &app.session;
+    let mut segments = vec![
+        Segment::labeled(
+            8,
+            "Window:",
+            match app.window_overrides() {
+                0 => app.window.label().to_string(),
+                overrides => format!("{} (overrides: {overrides})", app.window.label()),
+            },
+            Style::default().fg(theme.success),
+            theme,
+        ),
+        Segment::labeled(
+            3,
+            "Stats:",
+            app.global.stats_preset.label().to_string(),
+            Style::default().fg(theme.warn),
+            theme,
         ),
-        Span::raw("│ "),
-        Span::styled("Window:", Style::default().fg(Color::DarkGray)),
-        Span::styled(
-            format!(" {} ", app.window.label()),
-            Style::default().fg(Color::Green),
+        Segment::labeled(
+            2,
+            "Metrics:",
+            metrics_str,
+            Style::default().fg(theme.secondary),
+            theme,
         ),
-        Span::raw("│ "),
-        Span::styled("Stats:", Style::default().fg(Color::DarkGray)),
-        Span::styled(" P50/P99/Mean ", Style::default().fg(Color::Yellow)),
-        Span::raw("│ "),
-        Span::styled("Metrics:", Style::default().fg(Color::DarkGray)),
-        Span::styled(
-            format!(" {} ", metrics_str),
-            Style::default().fg(Color::Magenta),
+        Segment::labeled(
+            6,
+            "Targets:",
+            app.targets.len().to_string(),
+            Style::default().fg(theme.fg),
+            theme,
         ),
-        Span::raw("│ "),
-        Span::styled("Targets:", Style::default().fg(Color::DarkGray)),
-        Span::styled(
-            format!(" {} ", app.targets.len()),
-            Style::default().fg(Color::White),
+        Segment::labeled(8, "Alerts:", active_alerts.to_string(), alerts_style, theme),
+        Segment::labeled(
+            5,
+            "Probes:",
+            format_count(session.probes),
+            Style::default().fg(theme.fg),
+            theme,
         ),
-    ]);
+    ];
+    if let Some(pct) = session.success_pct() {
+        segments.push(Segment::labeled(
+            5,
+            "OK:",
+            format!("{pct:.1}%"),
+            style_for_success_rate(pct, theme),
+            theme,
+        ));
+    }
+    segments.push(Segment::labeled(
+        4,
+        "Rate:",
+        format!("{:.1}/s", session.probes_per_sec(now)),
+        Style::default().fg(theme.fg),
+        theme,
+    ));
+    segments.push(Segment::labeled(
+        1,
+        "Up:",
+        format_uptime(session.uptime(now)),
+        Style::default().fg(theme.fg),
+        theme,
+    ));
 
-    let paragraph = Paragraph::new(header).style(Style::default().bg(Color::DarkGray));
+    let suspended_badge = if app.global_paused {
+        Some(" ALL PAUSED ")
+    } else if app.quiet_active {
+        Some(" QUIET HOURS ")
+    } else {
+        None
+    };
+    if let Some(badge) = suspended_badge {
+        segments.push(Segment::badge(
+            ALWAYS_SHOWN,
+            badge.to_string(),
+            Style::default()
+                .fg(theme.bg)
+                .bg(theme.warn)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(proxy) = app.active_proxy() {
+        segments.push(Segment::badge(
+            7,
+            format!(" via proxy {proxy} "),
+            Style::default().fg(theme.warn),
+        ));
+    }
+    if let Some(window) = app.retention_shortfall() {
+        segments.push(Segment::badge(
+            6,
+            format!(" retention < {window} "),
+            Style::default().fg(theme.alert),
+        ));
+    }
+    // The UI fell behind and workers had to throw samples away
+    let dropped = crate::runtime::dropped_samples();
+    if dropped > 0 {
+        segments.push(Segment::badge(
+            7,
+            format!(" {dropped} samples dropped "),
+            Style::default().fg(theme.alert),
+        ));
+    }
+    if let Some(notice) = app
+        .status_notice
+        .as_ref()
+        .filter(|notice| notice.at.elapsed() < STATUS_NOTICE_DURATION)
+    {
+        let color = if notice.failed {
+            theme.alert
+        } else {
+            theme.success
+        };
+        segments.push(Segment::badge(
+            ALWAYS_SHOWN,
+            format!(" {} ", notice.message),
+            Style::default().fg(color),
+        ));
+    }
+    if let Some(notice) = &app.ebpf_notice {
+        segments.push(Segment::badge(
+            6,
+            format!(" eBPF off: {notice} "),
+            Style::default().fg(theme.alert),
+        ));
+    }
+
+    let brand = Span::styled(
+        " httpulse",
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+    );
+    let segments = fit_segments(segments, area.width as usize, brand.width());
+    let mut header = Line::from(brand);
+    for segment in segments {
+        header.spans.push(Span::raw("│ "));
+        header.spans.extend(segment.spans);
+    }
+
+    let paragraph = Paragraph::new(header).style(Style::default().bg(theme.muted));
     frame.render_widget(paragraph, area);
 }
 
@@ -52,42 +252,129 @@ pub(in crate::features::ui) fn draw_footer(
     frame: &mut ratatui::Frame,
     area: Rect,
     mode: InputMode,
+    keymap: &Keymap,
+    theme: &Theme,
 ) {
-    let hints = match mode {
+    let key = |action| keymap.label(action);
+    let pair = |first, second| format!("{}/{}", keymap.label(first), keymap.label(second));
+    let hints: Vec<(String, &str)> = match mode {
         InputMode::Normal => vec![
-            ("q", "Quit"),
-            ("?", "Help"),
-            ("G", "Glossary"),
-            ("S", "Settings"),
-            ("a", "Add"),
-            ("d", "Delete"),
-            ("p", "Pause"),
-            ("c", "Compare"),
-            ("g", "Pane"),
-            ("w", "Window"),
-            ("[ ]", "Category"),
+            (key(Action::Quit), "Quit"),
+            (key(Action::Help), "Help"),
+            (key(Action::Glossary), "Glossary"),
+            (key(Action::Settings), "Settings"),
+            (key(Action::AddTarget), "Add"),
+            (key(Action::Filter), "Filter"),
+            (pair(Action::MoveTargetDown, Action::MoveTargetUp), "Move"),
+            (key(Action::DeleteTarget), "Delete"),
+            (key(Action::DuplicateTarget), "Duplicate"),
+            (key(Action::Pause), "Pause"),
+            (key(Action::PauseAll), "Pause all"),
+            (key(Action::Burst), "Burst"),
+            (key(Action::CompareToggle), "Compare"),
+            (key(Action::CycleEndpoint), "Per-IP"),
+            (key(Action::ConnReuse), "Warm/Cold"),
+            (key(Action::CyclePane), "Pane"),
+            (key(Action::Overview), "Overview"),
+            (key(Action::ChartCursor), "Cursor"),
+            (key(Action::FocusMetrics), "Metrics"),
+            (pair(Action::CaptureBaseline, Action::Baselines), "Baseline"),
+            (pair(Action::Annotate, Action::Annotations), "Note"),
+            (key(Action::CycleWindow), "Window"),
+            (key(Action::TimeAxis), "Time axis"),
+            (key(Action::LogScale), "Log scale"),
+            (
+                format!(
+                    "{} {}",
+                    keymap.label(Action::PrevCategory),
+                    keymap.label(Action::NextCategory)
+                ),
+                "Category",
+            ),
+            (key(Action::AlertLog), "Alerts"),
+            (key(Action::ChangeLog), "History"),
+            (key(Action::Spikes), "Spikes"),
+            (key(Action::Outages), "Outages"),
+            (key(Action::CopySnapshot), "Copy stats"),
+            (key(Action::DumpStatus), "Dump JSON"),
+            (key(Action::ErrorLog), "Errors"),
+            (key(Action::Traceroute), "Trace"),
         ],
-        InputMode::AddTarget => vec![("Enter", "Confirm"), ("Esc", "Cancel")],
-        InputMode::Help | InputMode::Glossary => vec![("Esc", "Close")],
+        InputMode::AddTarget
+        | InputMode::ImportTargets
+        | InputMode::ExportTargets
+        | InputMode::BaselineName
+        | InputMode::AnnotationText => {
+            vec![("Enter".into(), "Confirm"), ("Esc".into(), "Cancel")]
+        }
+        InputMode::HeaderValue => vec![("Enter".into(), "Set value"), ("Esc".into(), "Skip")],
+        InputMode::Filter => vec![("Enter".into(), "Keep filter"), ("Esc".into(), "Clear")],
+        InputMode::Help
+        | InputMode::Glossary
+        | InputMode::AlertLog
+        | InputMode::ConnReuse
+        | InputMode::Spikes
+        | InputMode::Outages
+        | InputMode::MetricDetail
+        | InputMode::Snapshot => {
+            vec![("Esc".into(), "Close")]
+        }
         InputMode::Settings => vec![
-            ("Enter", "Edit/Toggle"),
-            ("↑↓", "Navigate"),
-            ("Esc", "Close"),
+            ("Enter".into(), "Edit/Toggle"),
+            ("↑↓".into(), "Navigate"),
+            ("Esc".into(), "Close"),
+        ],
+        InputMode::SettingsEdit(_) => vec![("Enter".into(), "Apply"), ("Esc".into(), "Cancel")],
+        InputMode::ConfirmDelete => vec![("y".into(), "Delete"), ("n".into(), "Cancel")],
+        InputMode::ConfirmQuit => vec![("y".into(), "Quit"), ("n".into(), "Cancel")],
+        InputMode::ChartCursor => vec![
+            (
+                pair(Action::CursorOlder, Action::CursorNewer),
+                "Move cursor",
+            ),
+            ("Esc".into(), "Exit cursor"),
+        ],
+        InputMode::MetricsFocus => vec![
+            (pair(Action::Down, Action::Up), "Select metric"),
+            (
+                format!(
+                    "{} {}",
+                    keymap.label(Action::PrevCategory),
+                    keymap.label(Action::NextCategory)
+                ),
+                "Category",
+            ),
+            (key(Action::Activate), "Details"),
+            ("Esc".into(), "Back"),
+        ],
+        InputMode::Traceroute => vec![("r".into(), "Re-run"), ("Esc".into(), "Close")],
+        InputMode::ErrorLog | InputMode::ChangeLog => vec![
+            (pair(Action::Down, Action::Up), "Scroll"),
+            ("Esc".into(), "Close"),
+        ],
+        InputMode::Baselines => vec![
+            (pair(Action::Down, Action::Up), "Navigate"),
+            ("Enter".into(), "Compare/Off"),
+            ("d".into(), "Delete"),
+            ("Esc".into(), "Close"),
+        ],
+        InputMode::Annotations => vec![
+            (pair(Action::Down, Action::Up), "Navigate"),
+            ("d".into(), "Delete"),
+            ("Esc".into(), "Close"),
         ],
-        InputMode::SettingsEdit(_) => vec![("Enter", "Apply"), ("Esc", "Cancel")],
-        InputMode::ConfirmDelete => vec![("y", "Delete"), ("n", "Cancel")],
     };
 
     let spans: Vec<Span> = hints
         .iter()
         .flat_map(|(key, action)| {
             vec![
-                Span::styled(format!(" {key} "), Style::default().fg(Color::Yellow)),
-                Span::styled(format!("{action} "), Style::default().fg(Color::Gray)),
+                Span::styled(format!(" {key} "), Style::default().fg(theme.warn)),
+                Span::styled(format!("{action} "), Style::default().fg(theme.subtle)),
             ]
         })
         .collect();
 
-    let footer = Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::DarkGray));
+    let footer = Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.muted));
     frame.render_widget(footer, area);
 }
@@ -1,12 +1,23 @@
+mod add_preview;
 mod format;
 mod header;
 mod overlays;
 mod settings;
 mod targets;
+#[cfg(test)]
+mod tests;
 
+pub(super) use add_preview::add_target_preview;
+pub(super) use format::metrics_for_category;
 pub(super) use header::{draw_footer, draw_header};
 pub(super) use overlays::{
-    draw_confirm_delete_popup, draw_glossary_popup, draw_help_popup, draw_terminal_too_small,
+    draw_alert_log_popup, draw_annotations_popup, draw_baselines_popup, draw_change_log_popup,
+    draw_confirm_delete_popup, draw_confirm_quit_popup, draw_conn_reuse_popup,
+    draw_error_log_popup, draw_glossary_popup, draw_help_popup, draw_metric_detail_popup,
+    draw_outages_popup, draw_snapshot_popup, draw_spikes_popup, draw_terminal_too_small,
+    draw_traceroute_popup,
 };
 pub(super) use settings::{draw_settings_popup, seed_settings_input, settings_rows};
-pub(super) use targets::draw_main;
+pub(super) use targets::{
+    chart_sample_positions, chart_timestamp_to_x, chart_x_to_timestamp, draw_main,
+};
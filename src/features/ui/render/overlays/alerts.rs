@@ -0,0 +1,82 @@
+use crate::alerts::AlertTransition;
+use crate::app::AppState;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_age, truncate_string};
+
+pub(in crate::features::ui) fn draw_alert_log_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(75, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("  Active: ", Style::default().fg(theme.muted)),
+        Span::styled(
+            app.active_alert_count().to_string(),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("   Logged: ", Style::default().fg(theme.muted)),
+        Span::raw(app.alert_log.len().to_string()),
+    ])];
+    lines.push(Line::from(""));
+
+    if app.alert_log.is_empty() {
+        lines.push(Line::styled(
+            "  No alerts yet. Configure rules in Settings (S).",
+            Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    let visible_rows = popup_area.height.saturating_sub(5) as usize;
+    for event in app.alert_log.iter_recent().take(visible_rows) {
+        let (badge, color) = match event.transition {
+            AlertTransition::Fired => ("FIRED  ", theme.error),
+            AlertTransition::Cleared => ("CLEARED", theme.success),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>8} ", format_age(event.ts)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                badge,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                truncate_string(&event.target_label, 32),
+                Style::default().fg(theme.accent),
+            ),
+            Span::raw(" "),
+            Span::styled(event.rule.clone(), Style::default().fg(theme.warn)),
+            Span::styled(" (", Style::default().fg(theme.muted)),
+            Span::raw(event.value.clone()),
+            Span::styled(")", Style::default().fg(theme.muted)),
+        ]));
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Alert Log ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.error))
+                .padding(Padding::horizontal(1)),
+        )
+        .style(Style::default().bg(theme.bg));
+
+    frame.render_widget(popup, popup_area);
+}
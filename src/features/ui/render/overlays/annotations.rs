@@ -0,0 +1,81 @@
+use crate::app::AppState;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_local_timestamp, truncate_string};
+
+pub(in crate::features::ui) fn draw_annotations_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    cursor: usize,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Notes ─ {} ",
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .padding(Padding::horizontal(1));
+
+    if target.annotations.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                "  No notes yet; press n to mark what just changed.",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+        frame.render_widget(message, popup_area);
+        return;
+    }
+
+    let header = Row::new(vec!["#", "Time", "Note"])
+        .style(Style::default().fg(theme.warn).add_modifier(Modifier::BOLD));
+    let note_width = popup_area.width.saturating_sub(4 + 3 + 11 + 2) as usize;
+    let rows: Vec<Row> = target
+        .annotations
+        .iter()
+        .enumerate()
+        .map(|(index, annotation)| {
+            let row = Row::new(vec![
+                Cell::from((index + 1).to_string()).style(Style::default().fg(theme.accent)),
+                Cell::from(format_local_timestamp(annotation.ts))
+                    .style(Style::default().fg(theme.muted)),
+                Cell::from(truncate_string(&annotation.text, note_width)),
+            ]);
+            if index == cursor {
+                row.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(11),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
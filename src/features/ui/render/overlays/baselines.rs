@@ -0,0 +1,104 @@
+use crate::app::AppState;
+use crate::metrics::MetricKind;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table};
+
+use super::super::super::theme::Theme;
+use super::super::format::{
+    centered_rect, format_local_timestamp, format_metric_value, truncate_string,
+};
+
+pub(in crate::features::ui) fn draw_baselines_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    cursor: usize,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Baselines ─ {} ",
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .padding(Padding::horizontal(1));
+
+    if target.baselines.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                "  No baselines yet; press b to capture the current window.",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+        frame.render_widget(message, popup_area);
+        return;
+    }
+
+    let header = Row::new(vec!["", "Name", "Captured", "Profile", "Window", "P50"])
+        .style(Style::default().fg(theme.warn).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = target
+        .baselines
+        .iter()
+        .enumerate()
+        .map(|(index, baseline)| {
+            let profile = target
+                .config
+                .profiles
+                .iter()
+                .find(|profile| profile.id == baseline.profile_id)
+                .map_or("(removed)", |profile| profile.name.as_str());
+            let p50 = baseline
+                .by_metric
+                .get(&MetricKind::Total)
+                .and_then(|stats| stats.p50);
+            let marker = if target.active_baseline == Some(index) {
+                "●"
+            } else {
+                " "
+            };
+            let row = Row::new(vec![
+                Cell::from(marker).style(Style::default().fg(theme.success)),
+                Cell::from(truncate_string(&baseline.name, 20)),
+                Cell::from(format_local_timestamp(baseline.captured_at))
+                    .style(Style::default().fg(theme.muted)),
+                Cell::from(truncate_string(profile, 16)).style(Style::default().fg(theme.accent)),
+                Cell::from(baseline.window.label()),
+                Cell::from(format_metric_value(MetricKind::Total, p50)),
+            ]);
+            if index == cursor {
+                row.style(Style::default().add_modifier(Modifier::REVERSED))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(1),
+        Constraint::Length(20),
+        Constraint::Length(11),
+        Constraint::Length(16),
+        Constraint::Length(6),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
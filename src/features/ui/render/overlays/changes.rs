@@ -0,0 +1,78 @@
+use crate::app::AppState;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_local_timestamp, pad_to_width};
+
+pub(in crate::features::ui) fn draw_change_log_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    scroll: usize,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(75, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let log = &app.change_log;
+    let scroll = scroll.min(log.len().saturating_sub(1));
+    let mut lines = vec![Line::from(vec![
+        Span::styled("  Logged: ", Style::default().fg(theme.muted)),
+        Span::styled(
+            log.len().to_string(),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            if log.len() > 1 {
+                format!("   Showing from #{}", scroll + 1)
+            } else {
+                String::new()
+            },
+            Style::default().fg(theme.muted),
+        ),
+    ])];
+    lines.push(Line::from(""));
+
+    if log.is_empty() {
+        lines.push(Line::styled(
+            "  No changes yet. Pauses, edits and added or deleted targets show up here.",
+            Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    let visible_rows = popup_area.height.saturating_sub(5) as usize;
+    for entry in log.iter_recent().skip(scroll).take(visible_rows) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {} ", format_local_timestamp(entry.ts)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                pad_to_width(&entry.target, 24),
+                Style::default().fg(theme.accent),
+            ),
+            Span::raw(" "),
+            Span::styled(entry.description.clone(), Style::default().fg(theme.fg)),
+        ]));
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" History ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .padding(Padding::horizontal(1)),
+        )
+        .style(Style::default().bg(theme.bg));
+
+    frame.render_widget(popup, popup_area);
+}
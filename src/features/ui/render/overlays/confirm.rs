@@ -1,9 +1,10 @@
 use crate::app::AppState;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
+use super::super::super::theme::Theme;
 use super::super::format::{centered_rect, truncate_string};
 
 pub(in crate::features::ui) fn draw_confirm_delete_popup(
@@ -11,6 +12,7 @@ pub(in crate::features::ui) fn draw_confirm_delete_popup(
     area: Rect,
     app: &AppState,
 ) {
+    let theme = Theme::of(app);
     let popup_area = centered_rect(40, 25, area);
     frame.render_widget(Clear, popup_area);
 
@@ -23,22 +25,24 @@ pub(in crate::features::ui) fn draw_confirm_delete_popup(
         Line::from(""),
         Line::styled(
             "  Delete this target?  ",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
         ),
         Line::from(""),
         Line::from(vec![
             Span::styled("  ", Style::default()),
             Span::styled(
                 truncate_string(target_name, 30),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(theme.accent),
             ),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::raw("  Press "),
-            Span::styled(" y ", Style::default().fg(Color::Black).bg(Color::Red)),
+            Span::styled(" y ", Style::default().fg(theme.bg).bg(theme.error)),
             Span::raw(" to delete, "),
-            Span::styled(" n ", Style::default().fg(Color::Black).bg(Color::Green)),
+            Span::styled(" n ", Style::default().fg(theme.bg).bg(theme.success)),
             Span::raw(" to cancel"),
         ]),
     ];
@@ -49,9 +53,63 @@ pub(in crate::features::ui) fn draw_confirm_delete_popup(
                 .title(" Confirm Delete ")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Red)),
+                .border_style(Style::default().fg(theme.error)),
         )
-        .style(Style::default().bg(Color::Black));
+        .style(Style::default().bg(theme.bg));
+
+    frame.render_widget(popup, popup_area);
+}
+
+/// Asks before quitting a run whose history would be lost, saying how to
+/// reload it when it is saved.
+pub(in crate::features::ui) fn draw_confirm_quit_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(50, 30, area);
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::styled(
+            "  Quit httpulse?  ",
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+        ),
+        Line::from(""),
+        Line::from(format!(
+            "  {} targets, {} samples collected",
+            app.targets.len(),
+            app.metrics.sample_count()
+        )),
+    ];
+    if app.saves_history() {
+        lines.push(Line::styled(
+            "  Samples are saved; relaunch with --restore to reload them",
+            Style::default().fg(theme.muted),
+        ));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("  Press "),
+            Span::styled(" y ", Style::default().fg(theme.bg).bg(theme.error)),
+            Span::raw(" to quit, "),
+            Span::styled(" n ", Style::default().fg(theme.bg).bg(theme.success)),
+            Span::raw(" to cancel"),
+        ]),
+    ]);
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" Confirm Quit ")
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.warn)),
+        )
+        .style(Style::default().bg(theme.bg));
 
     frame.render_widget(popup, popup_area);
 }
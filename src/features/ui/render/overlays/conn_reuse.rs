@@ -0,0 +1,139 @@
+use crate::app::AppState;
+use crate::metrics::MetricKind;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_latency, truncate_string};
+
+const HANDSHAKE_METRICS: [MetricKind; 4] = [
+    MetricKind::Dns,
+    MetricKind::Connect,
+    MetricKind::Tls,
+    MetricKind::Ttfb,
+];
+
+pub(in crate::features::ui) fn draw_conn_reuse_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Warm vs Cold [{}] ─ {} ",
+        app.target_window(target).label(),
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .padding(Padding::horizontal(1));
+
+    let pairs = app.conn_reuse_pairs(target);
+    if pairs.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                "  No warm/cold profile pair on this target.",
+                Style::default().fg(theme.muted),
+            ),
+            Line::styled(
+                "  Add e.g. h2+tls13+warm,h2+tls13+cold to compare connection reuse.",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+        frame.render_widget(message, popup_area);
+        return;
+    }
+
+    let header_style = Style::default().fg(theme.warn).add_modifier(Modifier::BOLD);
+    let mut rows = Vec::new();
+    for (pair_idx, pair) in pairs.iter().enumerate() {
+        let warm = &target.profiles[pair.warm];
+        let cold = &target.profiles[pair.cold];
+        let warm_aggregate = app.target_aggregate(target, warm);
+        let cold_aggregate = app.target_aggregate(target, cold);
+
+        if pair_idx > 0 {
+            rows.push(Row::new(vec![Cell::from("")]));
+        }
+        rows.push(
+            Row::new(vec![
+                Cell::from("p50"),
+                Cell::from(truncate_string(&warm.config.name, 14)),
+                Cell::from(truncate_string(&cold.config.name, 14)),
+                Cell::from("Δ"),
+                Cell::from("Δ%"),
+            ])
+            .style(header_style),
+        );
+
+        for metric in HANDSHAKE_METRICS {
+            let warm_value = warm_aggregate
+                .by_metric
+                .get(&metric)
+                .and_then(|stats| stats.p50);
+            let cold_value = cold_aggregate
+                .by_metric
+                .get(&metric)
+                .and_then(|stats| stats.p50);
+            let format_value =
+                |value: Option<f64>| value.map(format_latency).unwrap_or_else(|| "—".into());
+
+            let (delta, delta_pct, style) = match (warm_value, cold_value) {
+                (Some(warm), Some(cold)) => {
+                    // Negative deltas mean the warm connection is faster
+                    let delta = warm - cold;
+                    let pct = if cold > 0.0 {
+                        format!("{:+.0}%", delta / cold * 100.0)
+                    } else {
+                        "—".to_string()
+                    };
+                    let style = if warm < cold {
+                        Style::default().fg(theme.success)
+                    } else if warm > cold {
+                        Style::default().fg(theme.error)
+                    } else {
+                        Style::default()
+                    };
+                    let sign = if delta < 0.0 { "-" } else { "+" };
+                    (format!("{sign}{}", format_latency(delta.abs())), pct, style)
+                }
+                _ => ("—".to_string(), "—".to_string(), Style::default()),
+            };
+
+            rows.push(Row::new(vec![
+                Cell::from(metric.label()),
+                Cell::from(format_value(warm_value)),
+                Cell::from(format_value(cold_value)),
+                Cell::from(delta).style(style),
+                Cell::from(delta_pct).style(style),
+            ]));
+        }
+    }
+
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Length(15),
+        Constraint::Length(15),
+        Constraint::Length(10),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(rows, widths)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
@@ -0,0 +1,95 @@
+use crate::app::AppState;
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_age, truncate_string};
+
+pub(in crate::features::ui) fn draw_error_log_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    scroll: usize,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(75, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Errors ─ {} ",
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 40)
+    );
+
+    let log = &target.error_log;
+    let scroll = scroll.min(log.len().saturating_sub(1));
+    let mut lines = vec![Line::from(vec![
+        Span::styled("  Logged: ", Style::default().fg(theme.muted)),
+        Span::styled(
+            log.len().to_string(),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            if log.len() > 1 {
+                format!("   Showing from #{}", scroll + 1)
+            } else {
+                String::new()
+            },
+            Style::default().fg(theme.muted),
+        ),
+    ])];
+    lines.push(Line::from(""));
+
+    if log.is_empty() {
+        lines.push(Line::styled(
+            "  No errors recorded for this target.",
+            Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC),
+        ));
+    }
+
+    for entry in log.iter_recent().skip(scroll) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {:>8} ", format_age(entry.ts)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                truncate_string(&entry.profile, 16),
+                Style::default().fg(theme.accent),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                entry.kind.label(),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        lines.push(Line::styled(
+            format!("    {}", entry.message),
+            Style::default().fg(theme.subtle),
+        ));
+    }
+
+    let popup = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(title)
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.error))
+                .padding(Padding::horizontal(1)),
+        )
+        .style(Style::default().bg(theme.bg));
+
+    frame.render_widget(popup, popup_area);
+}
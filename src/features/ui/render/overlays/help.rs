@@ -1,109 +1,283 @@
+use crate::settings::{Action, Keymap};
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Wrap};
 
+use super::super::super::theme::Theme;
 use super::super::format::centered_rect;
 
-pub(in crate::features::ui) fn draw_help_popup(frame: &mut ratatui::Frame, area: Rect) {
+/// One help row: the keys column, then what they do.
+fn entry(keys: String, description: &str, theme: &Theme) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("  {keys:<10}  "),
+            Style::default().fg(theme.success),
+        ),
+        Span::raw(description.to_string()),
+    ])
+}
+
+fn section(title: &str, theme: &Theme) -> Line<'static> {
+    Line::styled(format!("─── {title} ───"), Style::default().fg(theme.warn))
+}
+
+pub(in crate::features::ui) fn draw_help_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    keymap: &Keymap,
+    theme: &Theme,
+) {
     let popup_area = centered_rect(60, 80, area);
 
     // Clear background
     frame.render_widget(Clear, popup_area);
 
+    let keys = |action| keymap.labels(action);
+    let pair = |first, second| format!("{}/{}", keymap.label(first), keymap.label(second));
+    let metric = |n, name: &str| {
+        [
+            Span::styled(
+                format!("{} ", keymap.label(Action::ToggleMetric(n))),
+                Style::default().fg(theme.success),
+            ),
+            Span::raw(name.to_string()),
+        ]
+    };
+
     let help_text = vec![
         Line::from(vec![Span::styled(
             "  Keyboard Shortcuts  ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
-        Line::styled("─── Navigation ───", Style::default().fg(Color::Yellow)),
-        Line::from(vec![
-            Span::styled("  Up/Down, j/k  ", Style::default().fg(Color::Green)),
-            Span::raw("Select target"),
-        ]),
-        Line::from(vec![
-            Span::styled("  Tab       ", Style::default().fg(Color::Green)),
-            Span::raw("Cycle through profiles"),
-        ]),
+        section("Navigation", theme),
+        entry(
+            format!("{}, {}", keys(Action::Up), keys(Action::Down)),
+            "Select target",
+            theme,
+        ),
+        entry(
+            format!(
+                "{}, {}",
+                keys(Action::MoveTargetUp),
+                keys(Action::MoveTargetDown)
+            ),
+            "Move target up/down the list",
+            theme,
+        ),
+        entry(
+            keys(Action::Filter),
+            &format!(
+                "Filter targets by host or tag ({} clears)",
+                keymap.label(Action::ClearFilter)
+            ),
+            theme,
+        ),
+        entry(
+            keys(Action::Activate),
+            "Collapse/expand the group under the cursor",
+            theme,
+        ),
+        entry(keys(Action::NextProfile), "Cycle through profiles", theme),
         Line::from(""),
-        Line::styled("─── Target Actions ───", Style::default().fg(Color::Yellow)),
-        Line::from(vec![
-            Span::styled("  a         ", Style::default().fg(Color::Green)),
-            Span::raw("Add new target"),
-        ]),
-        Line::from(vec![
-            Span::styled("  d         ", Style::default().fg(Color::Green)),
-            Span::raw("Delete selected target"),
-        ]),
-        Line::from(vec![
-            Span::styled("  e         ", Style::default().fg(Color::Green)),
-            Span::raw("Edit target (Settings)"),
-        ]),
-        Line::from(vec![
-            Span::styled("  p         ", Style::default().fg(Color::Green)),
-            Span::raw("Pause/Resume probing"),
-        ]),
+        section("Target Actions", theme),
+        entry(keys(Action::AddTarget), "Add new target", theme),
+        entry(
+            keys(Action::ImportTargets),
+            "Import targets from a file (URL per line, or an export)",
+            theme,
+        ),
+        entry(
+            keys(Action::ExportTargets),
+            "Export target definitions to share (no secrets)",
+            theme,
+        ),
+        entry(keys(Action::DeleteTarget), "Delete selected target", theme),
+        entry(
+            keys(Action::DuplicateTarget),
+            "Duplicate selected target",
+            theme,
+        ),
+        entry(keys(Action::EditTarget), "Edit target (Settings)", theme),
+        entry(keys(Action::Pause), "Pause/Resume probing", theme),
+        entry(keys(Action::PauseAll), "Pause/Resume all targets", theme),
+        entry(
+            keys(Action::Burst),
+            "Burst: probe every 500ms for 30s",
+            theme,
+        ),
         Line::from(""),
-        Line::styled("─── View Options ───", Style::default().fg(Color::Yellow)),
-        Line::from(vec![
-            Span::styled("  c         ", Style::default().fg(Color::Green)),
-            Span::raw("Toggle compare mode"),
-        ]),
-        Line::from(vec![
-            Span::styled("  g         ", Style::default().fg(Color::Green)),
-            Span::raw("Cycle right pane (Split/Chart/Metrics)"),
-        ]),
-        Line::from(vec![
-            Span::styled("  w         ", Style::default().fg(Color::Green)),
-            Span::raw("Cycle time window (1m/5m/15m/60m)"),
-        ]),
-        Line::from(vec![
-            Span::styled("  1-8       ", Style::default().fg(Color::Green)),
-            Span::raw("Toggle metric series on chart"),
-        ]),
+        section("View Options", theme),
+        entry(keys(Action::CompareToggle), "Toggle compare mode", theme),
+        entry(
+            keys(Action::CycleEndpoint),
+            "Per-IP view: profiles / all IPs / each IP",
+            theme,
+        ),
+        entry(
+            keys(Action::ConnReuse),
+            "Warm vs cold connection comparison",
+            theme,
+        ),
+        entry(
+            keys(Action::CyclePane),
+            "Cycle right pane (Split/Chart/Metrics/Summary/Breakdown/Heatmap/Windows)",
+            theme,
+        ),
+        entry(
+            keys(Action::Overview),
+            &format!(
+                "Overview of all targets ({} sort, {} reverse, {} open)",
+                keymap.label(Action::OverviewSort),
+                keymap.label(Action::OverviewReverse),
+                keymap.label(Action::Activate)
+            ),
+            theme,
+        ),
+        entry(
+            keys(Action::SplitView),
+            &format!(
+                "Side-by-side with the selected target pinned ({} switch side)",
+                keymap.label(Action::SplitFocus)
+            ),
+            theme,
+        ),
+        entry(
+            keys(Action::ChartCursor),
+            &format!(
+                "Chart cursor ({} {} move, {} {} ends, Esc exit)",
+                keymap.labels(Action::CursorOlder),
+                keymap.labels(Action::CursorNewer),
+                keymap.label(Action::CursorOldest),
+                keymap.label(Action::CursorNewest)
+            ),
+            theme,
+        ),
+        entry(
+            keys(Action::FocusMetrics),
+            &format!(
+                "Metrics table cursor ({} details, Esc exit)",
+                keymap.label(Action::Activate)
+            ),
+            theme,
+        ),
+        entry(
+            pair(Action::CaptureBaseline, Action::Baselines),
+            "Capture baseline / pick baseline to compare against",
+            theme,
+        ),
+        entry(
+            pair(Action::Annotate, Action::Annotations),
+            "Add a timestamped note / list and delete notes",
+            theme,
+        ),
+        entry(
+            keys(Action::CycleWindow),
+            "Cycle time window (set with --windows)",
+            theme,
+        ),
+        entry(
+            keys(Action::TargetWindow),
+            "Cycle the selected target's own window / back to global",
+            theme,
+        ),
+        entry(
+            keys(Action::TimeAxis),
+            "Chart time axis: relative ages / wall-clock",
+            theme,
+        ),
+        entry(
+            keys(Action::LogScale),
+            "Chart y axis: linear / log10",
+            theme,
+        ),
+        entry(
+            format!(
+                "{} {}",
+                keymap.label(Action::PrevCategory),
+                keymap.label(Action::NextCategory)
+            ),
+            "Previous/next metrics category",
+            theme,
+        ),
         Line::from(""),
-        Line::styled("─── Metrics (1-8) ───", Style::default().fg(Color::Yellow)),
-        Line::from(vec![
-            Span::styled("  1 ", Style::default().fg(Color::Green)),
-            Span::raw("Total  "),
-            Span::styled("2 ", Style::default().fg(Color::Green)),
-            Span::raw("DNS  "),
-            Span::styled("3 ", Style::default().fg(Color::Green)),
-            Span::raw("Connect  "),
-            Span::styled("4 ", Style::default().fg(Color::Green)),
-            Span::raw("TLS"),
-        ]),
-        Line::from(vec![
-            Span::styled("  5 ", Style::default().fg(Color::Green)),
-            Span::raw("TTFB   "),
-            Span::styled("6 ", Style::default().fg(Color::Green)),
-            Span::raw("Download  "),
-            Span::styled("7 ", Style::default().fg(Color::Green)),
-            Span::raw("RTT  "),
-            Span::styled("8 ", Style::default().fg(Color::Green)),
-            Span::raw("Retrans"),
-        ]),
+        section("Chart Series", theme),
+        Line::from(
+            [
+                vec![Span::raw("  ")],
+                metric(1, "Total  ").to_vec(),
+                metric(2, "DNS  ").to_vec(),
+                metric(3, "Connect  ").to_vec(),
+                metric(4, "TLS").to_vec(),
+            ]
+            .concat(),
+        ),
+        Line::from(
+            [
+                vec![Span::raw("  ")],
+                metric(5, "TTFB   ").to_vec(),
+                metric(6, "Download  ").to_vec(),
+                metric(7, "RTT  ").to_vec(),
+                metric(8, "Retrans").to_vec(),
+            ]
+            .concat(),
+        ),
+        entry(
+            keys(Action::CopyView),
+            "Give every target this target's series and category",
+            theme,
+        ),
         Line::from(""),
-        Line::styled("─── General ───", Style::default().fg(Color::Yellow)),
-        Line::from(vec![
-            Span::styled("  ?         ", Style::default().fg(Color::Green)),
-            Span::raw("Toggle this help"),
-        ]),
-        Line::from(vec![
-            Span::styled("  S         ", Style::default().fg(Color::Green)),
-            Span::raw("Open settings"),
-        ]),
-        Line::from(vec![
-            Span::styled("  q/Ctrl+C  ", Style::default().fg(Color::Green)),
-            Span::raw("Quit application"),
-        ]),
+        section("General", theme),
+        entry(keys(Action::Help), "Toggle this help", theme),
+        entry(keys(Action::Glossary), "Metric glossary", theme),
+        entry(keys(Action::Settings), "Open settings", theme),
+        entry(keys(Action::AlertLog), "Show alert log", theme),
+        entry(
+            keys(Action::ChangeLog),
+            &format!(
+                "History of changes ({} scroll)",
+                pair(Action::Down, Action::Up)
+            ),
+            theme,
+        ),
+        entry(keys(Action::Spikes), "Recent latency spikes", theme),
+        entry(keys(Action::Outages), "Outages and recovery times", theme),
+        entry(
+            keys(Action::CopySnapshot),
+            "Copy a stats snapshot to the clipboard",
+            theme,
+        ),
+        entry(
+            keys(Action::DumpStatus),
+            "Write every target's stats as JSON to the status file",
+            theme,
+        ),
+        entry(
+            format!(
+                "{}/{}",
+                keys(Action::ErrorLog),
+                keymap.label(Action::Activate)
+            ),
+            &format!(
+                "Show full error messages ({} scroll)",
+                pair(Action::Down, Action::Up)
+            ),
+            theme,
+        ),
+        entry(
+            keys(Action::Traceroute),
+            "Traceroute selected target (r re-run)",
+            theme,
+        ),
+        entry(keys(Action::Quit), "Quit application", theme),
         Line::from(""),
         Line::styled(
-            "  Press Esc or ? to close  ",
-            Style::default().fg(Color::DarkGray),
+            format!("  Press Esc or {} to close  ", keymap.label(Action::Help)),
+            Style::default().fg(theme.muted),
         ),
     ];
 
@@ -113,10 +287,10 @@ pub(in crate::features::ui) fn draw_help_popup(frame: &mut ratatui::Frame, area:
                 .title(" Help ")
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.accent))
                 .padding(Padding::horizontal(1)),
         )
-        .style(Style::default().bg(Color::Black))
+        .style(Style::default().bg(theme.bg))
         .wrap(Wrap { trim: false });
 
     frame.render_widget(help, popup_area);
@@ -0,0 +1,137 @@
+use crate::app::AppState;
+use crate::metrics::MetricStats;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Row, Table};
+
+use super::super::super::theme::Theme;
+use super::super::format::{
+    centered_rect, format_count, format_metric_value, metrics_for_category, sparkline,
+    truncate_string,
+};
+
+/// Values drawn in each profile's sparkline.
+const SPARKLINE_LEN: usize = 14;
+
+type StatSelector = fn(&MetricStats) -> Option<f64>;
+
+/// Rows of the popup after the sample count, in display order.
+const STAT_ROWS: [(&str, StatSelector); 8] = [
+    ("Last", |stats| stats.last),
+    ("Min", |stats| stats.min),
+    ("P50", |stats| stats.p50),
+    ("P90", |stats| stats.p90),
+    ("P99", |stats| stats.p99),
+    ("Max", |stats| stats.max),
+    ("Mean", |stats| stats.mean),
+    ("StdDev", |stats| stats.stddev),
+];
+
+/// Every statistic of the metric under the metrics table cursor, one column
+/// per profile.
+pub(in crate::features::ui) fn draw_metric_detail_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    metric_cursor: Option<usize>,
+) {
+    let theme = Theme::of(app);
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let Some(&metric) =
+        metric_cursor.and_then(|row| metrics_for_category(target.metrics_category).get(row))
+    else {
+        return;
+    };
+
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let unit = metric.unit();
+    let label = if unit.is_empty() {
+        metric.label().to_string()
+    } else {
+        format!("{} ({unit})", metric.label())
+    };
+    let title = format!(
+        " {label} [{}] ─ {} ",
+        app.target_window(target).label(),
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.warn))
+        .padding(Padding::horizontal(1));
+
+    let stats: Vec<Option<MetricStats>> = target
+        .profiles
+        .iter()
+        .map(|profile| {
+            app.target_aggregate(target, profile)
+                .by_metric
+                .get(&metric)
+                .cloned()
+        })
+        .collect();
+
+    let header = Row::new(std::iter::once(Cell::from("Stat")).chain(
+        target.profiles.iter().enumerate().map(|(idx, profile)| {
+            Cell::from(Span::styled(
+                truncate_string(&profile.config.name, 16),
+                Style::default().fg(theme.profile_color(idx, &profile.config)),
+            ))
+        }),
+    ))
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let label_style = Style::default().fg(theme.muted);
+    let mut rows = vec![Row::new(
+        std::iter::once(Cell::from("n").style(label_style)).chain(
+            stats
+                .iter()
+                .map(|stats| Cell::from(format_count(stats.as_ref().map_or(0, |stats| stats.n)))),
+        ),
+    )];
+    rows.extend(STAT_ROWS.iter().map(|(name, select)| {
+        Row::new(std::iter::once(Cell::from(*name).style(label_style)).chain(
+            stats.iter().map(|stats| {
+                Cell::from(format_metric_value(metric, stats.as_ref().and_then(select)))
+            }),
+        ))
+    }));
+    rows.push(Row::new(
+        std::iter::once(Cell::from("Recent").style(label_style)).chain(
+            target.profiles.iter().enumerate().map(|(idx, profile)| {
+                let key = target.view_key(profile);
+                let points = app.metrics.timeseries(
+                    app.metric_source_key(target, key, metric),
+                    app.target_window(target),
+                    metric,
+                    app.global.link_capacity_mbps,
+                    app.failure_latency(target),
+                );
+                let values: Vec<f64> = points.into_iter().map(|(_, value)| value).collect();
+                if values.is_empty() {
+                    Cell::from("—").style(label_style)
+                } else {
+                    Cell::from(sparkline(&values, SPARKLINE_LEN))
+                        .style(Style::default().fg(theme.profile_color(idx, &profile.config)))
+                }
+            }),
+        ),
+    ));
+
+    let widths: Vec<Constraint> = std::iter::once(Constraint::Length(7))
+        .chain(target.profiles.iter().map(|_| Constraint::Length(16)))
+        .collect();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
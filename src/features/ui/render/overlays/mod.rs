@@ -1,9 +1,31 @@
+mod alerts;
+mod annotations;
+mod baselines;
+mod changes;
 mod confirm;
+mod conn_reuse;
+mod errors;
 mod glossary;
 mod help;
+mod metric_detail;
+mod outages;
+mod snapshot;
+mod spikes;
 mod terminal;
+mod traceroute;
 
-pub(in crate::features::ui) use confirm::draw_confirm_delete_popup;
+pub(in crate::features::ui) use alerts::draw_alert_log_popup;
+pub(in crate::features::ui) use annotations::draw_annotations_popup;
+pub(in crate::features::ui) use baselines::draw_baselines_popup;
+pub(in crate::features::ui) use changes::draw_change_log_popup;
+pub(in crate::features::ui) use confirm::{draw_confirm_delete_popup, draw_confirm_quit_popup};
+pub(in crate::features::ui) use conn_reuse::draw_conn_reuse_popup;
+pub(in crate::features::ui) use errors::draw_error_log_popup;
 pub(in crate::features::ui) use glossary::draw_glossary_popup;
 pub(in crate::features::ui) use help::draw_help_popup;
+pub(in crate::features::ui) use metric_detail::draw_metric_detail_popup;
+pub(in crate::features::ui) use outages::draw_outages_popup;
+pub(in crate::features::ui) use snapshot::draw_snapshot_popup;
+pub(in crate::features::ui) use spikes::draw_spikes_popup;
 pub(in crate::features::ui) use terminal::draw_terminal_too_small;
+pub(in crate::features::ui) use traceroute::draw_traceroute_popup;
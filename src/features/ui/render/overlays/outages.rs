@@ -0,0 +1,93 @@
+use crate::app::AppState;
+use crate::metrics::OutageSummary;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table};
+use std::time::SystemTime;
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_age, format_elapsed, truncate_string};
+
+pub(in crate::features::ui) fn draw_outages_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(70, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let now = SystemTime::now();
+    let outages = app.target_outages(target);
+    let events: Vec<_> = outages.iter().map(|(_, outage)| *outage).collect();
+    let summary = OutageSummary::from_outages(&events, now);
+    let mut title = format!(
+        " Outages [{}] ─ {} ",
+        app.target_window(target).label(),
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    if let Some(mttr) = summary.mttr {
+        title.push_str(&format!("─ MTTR {} ", format_elapsed(mttr)));
+    }
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.error))
+        .padding(Padding::horizontal(1));
+
+    if outages.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                "  No outages in this window.",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+        frame.render_widget(message, popup_area);
+        return;
+    }
+
+    let header = Row::new(vec!["Started", "Profile", "Duration", "Probes", "Error"])
+        .style(Style::default().fg(theme.warn).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = outages
+        .iter()
+        .map(|(profile, outage)| {
+            let mut duration = format_elapsed(outage.duration(now));
+            let duration_style = if outage.end.is_none() {
+                duration.push_str(" + ongoing");
+                Style::default().fg(theme.error)
+            } else {
+                Style::default().fg(theme.fg)
+            };
+            Row::new(vec![
+                Cell::from(format_age(outage.start)).style(Style::default().fg(theme.muted)),
+                Cell::from(truncate_string(profile, 16)).style(Style::default().fg(theme.accent)),
+                Cell::from(duration).style(duration_style),
+                Cell::from(outage.probe_count.to_string()),
+                Cell::from(outage.dominant_error.short_label())
+                    .style(Style::default().fg(theme.error)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Length(17),
+        Constraint::Length(16),
+        Constraint::Length(6),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
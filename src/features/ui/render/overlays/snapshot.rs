@@ -0,0 +1,38 @@
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+
+use super::super::super::theme::Theme;
+use super::super::format::centered_rect;
+
+pub(in crate::features::ui) fn draw_snapshot_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    text: &str,
+    copy_error: Option<&str>,
+    theme: &Theme,
+) {
+    let popup_area = centered_rect(70, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let (status, color) = match copy_error {
+        None => (" Copied to clipboard (OSC 52) ".to_string(), theme.success),
+        Some(err) => (format!(" Copy failed: {err} "), theme.error),
+    };
+    let block = Block::default()
+        .title(" Snapshot ")
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::styled(status, Style::default().fg(color)).alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .padding(Padding::horizontal(1));
+
+    let lines: Vec<Line> = text.lines().map(Line::raw).collect();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().bg(theme.bg)),
+        popup_area,
+    );
+}
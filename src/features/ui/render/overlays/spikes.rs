@@ -0,0 +1,78 @@
+use crate::app::AppState;
+use ratatui::layout::{Alignment, Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table};
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_age, format_latency, truncate_string};
+
+/// Number of spikes listed in the popup.
+const SPIKE_LIST_LEN: usize = 10;
+
+pub(in crate::features::ui) fn draw_spikes_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(60, 50, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Spikes [{}] ─ {} ",
+        app.target_window(target).label(),
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 32)
+    );
+    let block = Block::default()
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.alert))
+        .padding(Padding::horizontal(1));
+
+    let spikes = app.recent_spikes(target, SPIKE_LIST_LEN);
+    if spikes.is_empty() {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                "  No spikes in this window.",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+        frame.render_widget(message, popup_area);
+        return;
+    }
+
+    let header = Row::new(vec!["When", "Profile", "Metric", "Value"])
+        .style(Style::default().fg(theme.warn).add_modifier(Modifier::BOLD));
+    let rows: Vec<Row> = spikes
+        .iter()
+        .map(|(profile, spike)| {
+            Row::new(vec![
+                Cell::from(format_age(spike.ts)).style(Style::default().fg(theme.muted)),
+                Cell::from(truncate_string(profile, 16)).style(Style::default().fg(theme.accent)),
+                Cell::from(spike.metric.label()),
+                Cell::from(format_latency(spike.value)).style(Style::default().fg(theme.alert)),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(9),
+        Constraint::Length(17),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block)
+        .style(Style::default().bg(theme.bg));
+    frame.render_widget(table, popup_area);
+}
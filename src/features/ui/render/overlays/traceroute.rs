@@ -0,0 +1,135 @@
+use crate::app::AppState;
+use crate::traceroute::{TRACEROUTE_MAX_HOPS, TracerouteStatus};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph};
+use std::time::SystemTime;
+
+use super::super::super::theme::Theme;
+use super::super::format::{centered_rect, format_age, format_latency, truncate_string};
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+pub(in crate::features::ui) fn draw_traceroute_popup(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+) {
+    let theme = Theme::of(app);
+    let popup_area = centered_rect(60, 70, area);
+    frame.render_widget(Clear, popup_area);
+
+    let Some(target) = app.selected_target() else {
+        return;
+    };
+    let title = format!(
+        " Traceroute ─ {} ",
+        truncate_string(target.config.url.host_str().unwrap_or("?"), 40)
+    );
+
+    let mut lines = Vec::new();
+    match &target.traceroute {
+        None => lines.push(Line::styled(
+            "  Press r to trace the path to this target.",
+            Style::default().fg(theme.muted),
+        )),
+        Some(run) => {
+            let destination = run
+                .destination
+                .map(|addr| addr.to_string())
+                .unwrap_or_else(|| "—".to_string());
+            let status = match run.status {
+                TracerouteStatus::Running => {
+                    let elapsed_ms = SystemTime::now()
+                        .duration_since(run.started)
+                        .map(|elapsed| elapsed.as_millis())
+                        .unwrap_or(0);
+                    let frame = SPINNER_FRAMES[(elapsed_ms / 100) as usize % SPINNER_FRAMES.len()];
+                    Span::styled(
+                        format!(
+                            "{frame} tracing (hop {}/{TRACEROUTE_MAX_HOPS})",
+                            run.hops.len() + 1
+                        ),
+                        Style::default().fg(theme.warn),
+                    )
+                }
+                TracerouteStatus::Finished => {
+                    let reached = run.hops.last().is_some_and(|hop| hop.reached);
+                    let (text, color) = if reached {
+                        (format!("reached in {} hops", run.hops.len()), theme.success)
+                    } else {
+                        (
+                            format!("gave up after {} hops", run.hops.len()),
+                            theme.error,
+                        )
+                    };
+                    Span::styled(
+                        format!("{text}, {}", format_age(run.started)),
+                        Style::default().fg(color),
+                    )
+                }
+                TracerouteStatus::Failed => Span::styled(
+                    "failed",
+                    Style::default()
+                        .fg(theme.error)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            };
+            lines.push(Line::from(vec![
+                Span::styled("  To ", Style::default().fg(theme.muted)),
+                Span::raw(destination),
+                Span::styled("  ", Style::default()),
+                status,
+            ]));
+            if let Some(err) = &run.error {
+                lines.push(Line::styled(
+                    format!("  {err}"),
+                    Style::default().fg(theme.error),
+                ));
+            }
+            lines.push(Line::from(""));
+
+            let visible_rows = popup_area.height.saturating_sub(6) as usize;
+            let skip = run.hops.len().saturating_sub(visible_rows);
+            for hop in run.hops.iter().skip(skip) {
+                let addr = hop
+                    .addr
+                    .map(|addr| addr.to_string())
+                    .unwrap_or_else(|| "*".to_string());
+                let rtt = hop
+                    .rtt
+                    .map(|rtt| format_latency(rtt.as_secs_f64() * 1000.0))
+                    .unwrap_or_else(|| "—".to_string());
+                let addr_style = if hop.reached {
+                    Style::default().fg(theme.success)
+                } else if hop.addr.is_none() {
+                    Style::default().fg(theme.muted)
+                } else {
+                    Style::default().fg(theme.accent)
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:>2}  ", hop.ttl),
+                        Style::default().fg(theme.muted),
+                    ),
+                    Span::styled(format!("{addr:<40}"), addr_style),
+                    Span::raw(format!("{rtt:>8}")),
+                ]));
+            }
+        }
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .padding(Padding::horizontal(1)),
+        )
+        .style(Style::default().bg(theme.bg));
+
+    frame.render_widget(popup, popup_area);
+}
@@ -1,11 +1,17 @@
-use crate::app::AppState;
+use crate::alerts::format_alert_rules;
+use crate::app::{AppState, format_duration};
+use crate::config::{AuthConfig, ProbeMethod, ThemeName};
+use crate::probe::ErrorCategory;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table, TableState};
+use std::collections::BTreeSet;
+use std::time::Duration;
 
 use super::super::state::{InputMode, SettingsField, SettingsRow, SettingsState};
-use super::format::{centered_rect, truncate_string};
+use super::super::theme::{SERIES_NAMES, Theme};
+use super::format::{centered_rect, format_bytes, format_tags, truncate_string};
 
 pub(in crate::features::ui) fn draw_settings_popup(
     frame: &mut ratatui::Frame,
@@ -15,10 +21,18 @@ pub(in crate::features::ui) fn draw_settings_popup(
     input_mode: InputMode,
     input_buffer: &str,
 ) {
-    let popup_area = centered_rect(70, 70, area);
+    let theme = Theme::of(app);
+    let rows = settings_rows(app);
+    // Short terminals give up the margin before rows scroll out of view:
+    // borders, header and help take five lines besides the rows
+    let mut popup_area = centered_rect(70, 80, area);
+    let wanted = (rows.len() as u16 + 5).min(area.height);
+    if popup_area.height < wanted {
+        popup_area.y = area.y + (area.height - wanted) / 2;
+        popup_area.height = wanted;
+    }
     frame.render_widget(Clear, popup_area);
 
-    let rows = settings_rows(app);
     let mut table_state = TableState::default();
     let mut selected = settings_state.selected;
     if rows.is_empty() {
@@ -32,21 +46,28 @@ pub(in crate::features::ui) fn draw_settings_popup(
 
     let title = app
         .selected_target()
-        .map(|target| {
-            format!(
-                " Settings - {} ",
-                truncate_string(target.config.url.as_str(), 30)
-            )
-        })
+        .map(
+            |target| match target.profiles.get(target.selected_profile) {
+                Some(profile) => format!(
+                    " Settings - {} - {} ",
+                    truncate_string(target.config.url.as_str(), 30),
+                    profile.config.name
+                ),
+                None => format!(
+                    " Settings - {} ",
+                    truncate_string(target.config.url.as_str(), 30)
+                ),
+            },
+        )
         .unwrap_or_else(|| " Settings ".to_string());
 
     let block = Block::default()
         .title(title)
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.accent))
         .padding(Padding::horizontal(1))
-        .style(Style::default().bg(Color::Black));
+        .style(Style::default().bg(theme.bg));
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
@@ -65,11 +86,7 @@ pub(in crate::features::ui) fn draw_settings_popup(
         Cell::from("Value"),
         Cell::from("Action"),
     ])
-    .style(
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    );
+    .style(Style::default().fg(theme.warn).add_modifier(Modifier::BOLD));
 
     let table_rows = rows.iter().map(|row| {
         Row::new(vec![
@@ -83,53 +100,84 @@ pub(in crate::features::ui) fn draw_settings_popup(
     let widths = [
         Constraint::Length(8),
         Constraint::Length(18),
-        Constraint::Length(18),
+        Constraint::Length(22),
         Constraint::Min(12),
     ];
 
     let table = Table::new(table_rows, widths)
         .header(header)
         .column_spacing(1)
-        .row_highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
+        .row_highlight_style(theme.selection.add_modifier(Modifier::BOLD))
         .highlight_symbol("> ");
 
     frame.render_widget(Clear, sections[0]);
     frame.render_stateful_widget(table, sections[0], &mut table_state);
 
     let mut help_lines = vec![Line::from(vec![
-        Span::styled("  ↑↓ ", Style::default().fg(Color::Green)),
+        Span::styled("  ↑↓ ", Style::default().fg(theme.success)),
         Span::raw("Select  "),
-        Span::styled("Enter ", Style::default().fg(Color::Green)),
-        Span::raw("Edit/Toggle  "),
-        Span::styled("Esc ", Style::default().fg(Color::Green)),
+        Span::styled("Enter ", Style::default().fg(theme.success)),
+        Span::raw("Edit  "),
+        Span::styled("Tab ", Style::default().fg(theme.success)),
+        Span::raw("Profile  "),
+        Span::styled("Esc ", Style::default().fg(theme.success)),
         Span::raw("Close"),
+        Span::styled(
+            format!(
+                "  History ~{}B",
+                format_bytes(app.metrics.approx_bytes() as f64)
+            ),
+            Style::default().fg(theme.muted),
+        ),
     ])];
     if let Some(notice) = &settings_state.notice {
         help_lines.push(Line::styled(
             format!("  {notice}"),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme.error),
         ));
     }
 
-    let help = Paragraph::new(help_lines).style(Style::default().bg(Color::Black));
+    let help = Paragraph::new(help_lines).style(Style::default().bg(theme.bg));
     frame.render_widget(help, sections[1]);
 
     if let InputMode::SettingsEdit(field) = input_mode {
         let prompt = settings_edit_prompt(field);
+        let typed = if field == SettingsField::ProfileAuth {
+            mask_auth_input(input_buffer)
+        } else {
+            input_buffer.to_string()
+        };
         let input_line = Line::from(vec![
-            Span::styled(format!("  {prompt}"), Style::default().fg(Color::Yellow)),
-            Span::raw(input_buffer),
-            Span::styled("█", Style::default().fg(Color::Gray)),
+            Span::styled(format!("  {prompt}"), Style::default().fg(theme.warn)),
+            Span::raw(typed),
+            Span::styled("█", Style::default().fg(theme.subtle)),
         ]);
-        let input = Paragraph::new(input_line).style(Style::default().bg(Color::DarkGray));
+        let input = Paragraph::new(input_line).style(Style::default().bg(theme.muted));
         frame.render_widget(input, sections[2]);
     }
 }
 
+/// Configured interval followed by the one the worker actually achieves,
+/// e.g. "5s (effective 5.02s)".
+fn with_effective(configured: String, effective: Option<Duration>) -> String {
+    match effective {
+        Some(effective) => format!("{configured} (effective {:.2}s)", effective.as_secs_f64()),
+        None => configured,
+    }
+}
+
+/// Hides the secret part of a typed auth value: everything after the
+/// basic user's colon, or after the bearer scheme.
+fn mask_auth_input(input: &str) -> String {
+    let visible = if input.trim_start().to_ascii_lowercase().starts_with("basic") {
+        input.find(':').map_or(input.len(), |colon| colon + 1)
+    } else {
+        input.find(' ').map_or(input.len(), |space| space + 1)
+    };
+    let (shown, secret) = input.split_at(visible);
+    format!("{shown}{}", "•".repeat(secret.chars().count()))
+}
+
 pub(in crate::features::ui) fn settings_rows(app: &AppState) -> Vec<SettingsRow> {
     let mut rows = Vec::new();
     rows.push(SettingsRow {
@@ -150,13 +198,201 @@ pub(in crate::features::ui) fn settings_rows(app: &AppState) -> Vec<SettingsRow>
             .unwrap_or_else(|| "Off".to_string()),
         action: "Enter to edit",
     });
+    rows.push(SettingsRow {
+        field: SettingsField::QuietHours,
+        scope: "Global",
+        label: "Quiet hours",
+        value: app
+            .global
+            .quiet_hours
+            .map(|quiet| quiet.to_string())
+            .unwrap_or_else(|| "Off".to_string()),
+        action: "Enter to edit",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::Notifications,
+        scope: "Global",
+        label: "Notify on loss",
+        value: app
+            .global
+            .notify
+            .map(|rule| rule.to_string())
+            .unwrap_or_else(|| "Off".to_string()),
+        action: "Enter to edit",
+    });
+
+    rows.push(SettingsRow {
+        field: SettingsField::Retention,
+        scope: "Global",
+        label: "Retention",
+        value: format_duration(app.global.sampling.retention),
+        action: "Enter to edit",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::MaxPoints,
+        scope: "Global",
+        label: "Max points",
+        value: app.global.sampling.max_points_per_window.to_string(),
+        action: "Enter to edit",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::BurstSamples,
+        scope: "Global",
+        label: "Burst samples",
+        value: if app.global.exclude_burst_from_long_windows {
+            "Left out of 15m+".to_string()
+        } else {
+            "All windows".to_string()
+        },
+        action: "Enter to toggle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::IncludeFailures,
+        scope: "Global",
+        label: "Failures in latency",
+        value: if app.global.include_failures_in_latency {
+            "Timeouts count".to_string()
+        } else {
+            "Successes only".to_string()
+        },
+        action: "Enter to toggle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::PrimingSamples,
+        scope: "Global",
+        label: "Priming samples",
+        value: if app.global.sampling.include_priming_samples {
+            "Counted".to_string()
+        } else {
+            "Left out".to_string()
+        },
+        action: "Enter to toggle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::MissedSlots,
+        scope: "Global",
+        label: "Missed slots",
+        value: if app.global.count_missed_as_loss {
+            "Count as loss".to_string()
+        } else {
+            "Shown apart".to_string()
+        },
+        action: "Enter to toggle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::ProxyEnv,
+        scope: "Global",
+        label: "Use proxy env",
+        value: match (app.global.use_proxy_env, crate::probe_engine::env_proxy()) {
+            (false, _) => "Off (direct)".to_string(),
+            (true, Some(proxy)) => format!("On ({proxy})"),
+            (true, None) => "On (none set)".to_string(),
+        },
+        action: "Enter to toggle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::DownloadSlots,
+        scope: "Global",
+        label: "Download slots",
+        value: match app.global.download_slots {
+            Some(slots) => format!("{slots} over 64K at once"),
+            None => "Unlimited".to_string(),
+        },
+        action: "Enter to edit",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::StatsColumns,
+        scope: "Global",
+        label: "Stats columns",
+        value: app.global.stats_preset.label(),
+        action: "Enter to cycle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::ChartStat,
+        scope: "Global",
+        label: "Chart stat",
+        value: app
+            .global
+            .chart_stat
+            .map(|stat| format!("{} per bucket", stat.label()))
+            .unwrap_or_else(|| "Raw samples".to_string()),
+        action: "Enter to cycle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::Theme,
+        scope: "Global",
+        label: "Theme",
+        value: if app.global.theme == ThemeName::Auto {
+            format!("Auto ({})", app.theme)
+        } else {
+            app.global.theme.to_string()
+        },
+        action: "Enter to cycle",
+    });
+    rows.push(SettingsRow {
+        field: SettingsField::ErrorMarkers,
+        scope: "Global",
+        label: "Error markers",
+        value: if app.global.hidden_error_markers.is_empty() {
+            "All shown".to_string()
+        } else {
+            format!(
+                "Hiding {}",
+                format_error_categories(&app.global.hidden_error_markers)
+            )
+        },
+        action: "Enter to edit",
+    });
 
     if let Some(target) = app.selected_target() {
+        rows.push(SettingsRow {
+            field: SettingsField::TargetUrl,
+            scope: "Target",
+            label: "URL",
+            value: truncate_string(target.config.url.as_str(), 28),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetTags,
+            scope: "Target",
+            label: "Tags",
+            value: if target.config.tags.is_empty() {
+                "None".to_string()
+            } else {
+                truncate_string(&format_tags(&target.config.tags), 28)
+            },
+            action: "Enter to edit",
+        });
         rows.push(SettingsRow {
             field: SettingsField::TargetInterval,
             scope: "Target",
             label: "Interval",
-            value: format!("{}s", target.config.interval.as_secs()),
+            value: with_effective(
+                format!("{}s", target.config.interval.as_secs()),
+                target.effective_interval(),
+            ),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetJitter,
+            scope: "Target",
+            label: "Jitter",
+            value: match target.config.jitter_pct {
+                0 => "Off".to_string(),
+                pct => format!("±{pct}%"),
+            },
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetSchedule,
+            scope: "Target",
+            label: "Schedule",
+            value: target
+                .config
+                .schedule
+                .as_ref()
+                .map(|schedule| truncate_string(&schedule.to_string(), 28))
+                .unwrap_or_else(|| "Every interval".to_string()),
             action: "Enter to edit",
         });
         rows.push(SettingsRow {
@@ -167,13 +403,61 @@ pub(in crate::features::ui) fn settings_rows(app: &AppState) -> Vec<SettingsRow>
             action: "Enter to edit",
         });
         rows.push(SettingsRow {
-            field: SettingsField::TargetDnsEnabled,
+            field: SettingsField::TargetDnsMode,
             scope: "Target",
             label: "DNS",
-            value: if target.config.dns_enabled {
-                "On".to_string()
-            } else {
+            value: if !target.config.dns_enabled {
                 "Off".to_string()
+            } else if target.config.dns_combined {
+                "Per probe".to_string()
+            } else {
+                format!("Worker {}s", target.config.dns_refresh.as_secs())
+            },
+            action: "Enter to cycle",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetUnixSocket,
+            scope: "Target",
+            label: "Unix Socket",
+            value: target
+                .config
+                .unix_socket
+                .as_ref()
+                .map(|path| truncate_string(&path.display().to_string(), 28))
+                .unwrap_or_else(|| "Off".to_string()),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetConnectTo,
+            scope: "Target",
+            label: "Connect To",
+            value: target
+                .config
+                .connect_to
+                .as_ref()
+                .map(|connect_to| truncate_string(&connect_to.to_string(), 28))
+                .unwrap_or_else(|| "URL host".to_string()),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetInsecure,
+            scope: "Target",
+            label: "TLS Verify",
+            value: if target.config.insecure {
+                "Off ⚠ INSECURE".to_string()
+            } else {
+                "On".to_string()
+            },
+            action: "Enter to toggle",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetPerIp,
+            scope: "Target",
+            label: "Per-IP Streams",
+            value: match (target.config.per_ip, target.config.probes_per_ip()) {
+                (false, _) => "Off".to_string(),
+                (true, true) => format!("On ({} IPs)", target.endpoint_ips().len()),
+                (true, false) => "On (needs DNS, no connect-to)".to_string(),
             },
             action: "Enter to toggle",
         });
@@ -195,6 +479,182 @@ pub(in crate::features::ui) fn settings_rows(app: &AppState) -> Vec<SettingsRow>
             },
             action: "Enter to toggle",
         });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetAlerts,
+            scope: "Target",
+            label: "Alert rules",
+            value: match target.config.alert_rules.len() {
+                0 => "None".to_string(),
+                1 => "1 rule".to_string(),
+                count => format!("{count} rules"),
+            },
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetSloTarget,
+            scope: "Target",
+            label: "SLO target",
+            value: target
+                .config
+                .slo_target
+                .map(|value| format!("{value}%"))
+                .unwrap_or_else(|| "Off".to_string()),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetSloLatency,
+            scope: "Target",
+            label: "SLO latency",
+            value: target
+                .config
+                .slo_latency_ms
+                .map(|value| format!("{value}ms"))
+                .unwrap_or_else(|| "Off".to_string()),
+            action: "Enter to edit",
+        });
+        rows.push(SettingsRow {
+            field: SettingsField::TargetDuplicate,
+            scope: "Target",
+            label: "Duplicate",
+            value: "New copy".to_string(),
+            action: "Enter to copy",
+        });
+        if let Some(profile) = target.profiles.get(target.selected_profile) {
+            let config = &profile.config;
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileName,
+                scope: "Profile",
+                label: "Name",
+                value: truncate_string(&config.name, 28),
+                action: "Enter to edit",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileColor,
+                scope: "Profile",
+                label: "Color",
+                value: match config.color {
+                    Some(color) => {
+                        SERIES_NAMES[usize::from(color) % SERIES_NAMES.len()].to_string()
+                    }
+                    None => format!(
+                        "Auto ({})",
+                        SERIES_NAMES[target.selected_profile % SERIES_NAMES.len()]
+                    ),
+                },
+                action: "Enter to cycle",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileMethod,
+                scope: "Profile",
+                label: "Method",
+                value: config.method.to_string().to_uppercase(),
+                action: if config.method == ProbeMethod::Ping {
+                    "Fixed"
+                } else {
+                    "Enter to toggle"
+                },
+            });
+            // A ping has no HTTP request for the other knobs to shape
+            if config.method != ProbeMethod::Ping {
+                rows.push(SettingsRow {
+                    field: SettingsField::ProfileHttp,
+                    scope: "Profile",
+                    label: "HTTP version",
+                    value: config.http.to_string(),
+                    action: "Enter to cycle",
+                });
+                rows.push(SettingsRow {
+                    field: SettingsField::ProfileTls,
+                    scope: "Profile",
+                    label: "TLS version",
+                    value: config.tls.protocol_name().to_string(),
+                    action: "Enter to toggle",
+                });
+                rows.push(SettingsRow {
+                    field: SettingsField::ProfileReuse,
+                    scope: "Profile",
+                    label: "Connection",
+                    value: config.conn_reuse.to_string(),
+                    action: "Enter to toggle",
+                });
+                rows.push(SettingsRow {
+                    field: SettingsField::ProfileMaxReadBytes,
+                    scope: "Profile",
+                    label: "Max read",
+                    value: format_bytes(f64::from(config.max_read_bytes)),
+                    action: "Enter to edit",
+                });
+                rows.push(SettingsRow {
+                    field: SettingsField::ProfileRecvSpeed,
+                    scope: "Profile",
+                    label: "Recv speed",
+                    value: config
+                        .max_recv_speed
+                        .map(|speed| format!("{}/s", format_bytes(speed as f64)))
+                        .unwrap_or_else(|| "Unlimited".to_string()),
+                    action: "Enter to edit",
+                });
+            }
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileInterval,
+                scope: "Profile",
+                label: "Interval",
+                value: match profile.config.interval_override {
+                    Some(interval) => {
+                        with_effective(format_duration(interval), profile.effective_interval)
+                    }
+                    None => "Target".to_string(),
+                },
+                action: "Enter to edit",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileExpectedStatus,
+                scope: "Profile",
+                label: "Expect status",
+                value: profile
+                    .config
+                    .expected_status
+                    .map(|status| status.to_string())
+                    .unwrap_or_else(|| "< 400".to_string()),
+                action: "Enter to edit",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileExpectBody,
+                scope: "Profile",
+                label: "Expect body",
+                value: profile
+                    .config
+                    .expect_body_contains
+                    .as_deref()
+                    .map(|needle| format!("{:?}", truncate_string(needle, 24)))
+                    .unwrap_or_else(|| "Off".to_string()),
+                action: "Enter to edit",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileCaptureHeaders,
+                scope: "Profile",
+                label: "Capture headers",
+                value: if profile.config.capture_headers.is_empty() {
+                    "Off".to_string()
+                } else {
+                    truncate_string(&profile.config.capture_headers.join(", "), 32)
+                },
+                action: "Enter to edit",
+            });
+            rows.push(SettingsRow {
+                field: SettingsField::ProfileAuth,
+                scope: "Profile",
+                label: "Auth",
+                value: match &profile.config.auth {
+                    Some(AuthConfig::Basic { user, .. }) => {
+                        truncate_string(&format!("Basic {user}"), 32)
+                    }
+                    Some(AuthConfig::Bearer { .. }) => "Bearer token".to_string(),
+                    None => "None".to_string(),
+                },
+                action: "Enter to edit",
+            });
+        }
     }
 
     rows
@@ -204,11 +664,64 @@ pub(super) fn settings_edit_prompt(field: SettingsField) -> &'static str {
     match field {
         SettingsField::UiRefreshHz => "Set UI refresh (Hz): ",
         SettingsField::LinkCapacityMbps => "Set link capacity Mbps (blank=off): ",
+        SettingsField::QuietHours => "Set quiet hours (e.g. 22:00-07:00, blank=off): ",
+        SettingsField::Notifications => {
+            "Notify when 1m probe loss > N% for M checks (e.g. 20% x3, blank=off): "
+        }
+        SettingsField::Retention => "Keep samples for at least (e.g. 60m, 2h): ",
+        SettingsField::MaxPoints => "Keep at least this many samples per profile: ",
+        SettingsField::ErrorMarkers => {
+            "Hide error markers (timeout tls http other, blank=show all): "
+        }
+        SettingsField::TargetUrl => "Set URL (history is kept): ",
+        SettingsField::TargetTags => "Set tags (e.g. #prod #eu, blank=none): ",
         SettingsField::TargetInterval => "Set probe interval (e.g. 5s): ",
         SettingsField::TargetTimeout => "Set timeout (e.g. 10s): ",
-        SettingsField::TargetDnsEnabled
+        SettingsField::TargetAlerts => "Alert rules (e.g. total p99 > 800ms; probe_loss > 5%): ",
+        SettingsField::TargetSloTarget => "Set SLO target % (blank=off): ",
+        SettingsField::TargetSloLatency => "Set SLO latency budget (e.g. 800ms, blank=off): ",
+        SettingsField::TargetJitter => "Set interval jitter % (0-50, blank=off): ",
+        SettingsField::TargetSchedule => {
+            "Set schedule (e.g. 30s or */15 * 9-17 * * MON-FRI, off=interval): "
+        }
+        SettingsField::TargetUnixSocket => "Set unix socket path (blank=TCP): ",
+        SettingsField::TargetConnectTo => "Set connect-to host:port (blank=URL host): ",
+        SettingsField::ProfileName => "Set profile name (unique within the target): ",
+        SettingsField::ProfileInterval => "Set profile interval (e.g. 2s, blank=target): ",
+        SettingsField::ProfileExpectedStatus => {
+            "Set expected status (e.g. 204, 2xx, 200-299, blank=any < 400): "
+        }
+        SettingsField::ProfileExpectBody => "Set required body substring (blank=off): ",
+        SettingsField::ProfileCaptureHeaders => {
+            "Set response headers to capture (e.g. x-cache, age; default; blank=off): "
+        }
+        SettingsField::ProfileMaxReadBytes => "Set max read bytes (e.g. 4096, 64k): ",
+        SettingsField::ProfileRecvSpeed => {
+            "Set download speed cap per second (e.g. 512k, 2m, blank=unlimited): "
+        }
+        SettingsField::DownloadSlots => {
+            "Set how many probes reading over 64K may run at once (blank=unlimited): "
+        }
+        SettingsField::ProfileAuth => "Set auth (basic user:pass, bearer TOKEN, blank=none): ",
+        SettingsField::BurstSamples
+        | SettingsField::IncludeFailures
+        | SettingsField::PrimingSamples
+        | SettingsField::MissedSlots
+        | SettingsField::ProxyEnv
+        | SettingsField::StatsColumns
+        | SettingsField::ChartStat
+        | SettingsField::Theme
+        | SettingsField::TargetDnsMode
+        | SettingsField::TargetInsecure
+        | SettingsField::TargetPerIp
         | SettingsField::TargetPane
-        | SettingsField::TargetPaused => "Press Enter to toggle: ",
+        | SettingsField::TargetPaused
+        | SettingsField::TargetDuplicate
+        | SettingsField::ProfileColor
+        | SettingsField::ProfileMethod
+        | SettingsField::ProfileHttp
+        | SettingsField::ProfileTls
+        | SettingsField::ProfileReuse => "Press Enter to toggle: ",
     }
 }
 
@@ -220,6 +733,27 @@ pub(in crate::features::ui) fn seed_settings_input(app: &AppState, field: Settin
             .link_capacity_mbps
             .map(|value| format!("{value:.1}"))
             .unwrap_or_default(),
+        SettingsField::Retention => format_duration(app.global.sampling.retention),
+        SettingsField::MaxPoints => app.global.sampling.max_points_per_window.to_string(),
+        SettingsField::QuietHours => app
+            .global
+            .quiet_hours
+            .map(|quiet| quiet.to_string())
+            .unwrap_or_default(),
+        SettingsField::Notifications => app
+            .global
+            .notify
+            .map(|rule| rule.to_string())
+            .unwrap_or_default(),
+        SettingsField::ErrorMarkers => format_error_categories(&app.global.hidden_error_markers),
+        SettingsField::TargetUrl => app
+            .selected_target()
+            .map(|target| target.config.url.to_string())
+            .unwrap_or_default(),
+        SettingsField::TargetTags => app
+            .selected_target()
+            .map(|target| format_tags(&target.config.tags))
+            .unwrap_or_default(),
         SettingsField::TargetInterval => app
             .selected_target()
             .map(|target| format!("{}s", target.config.interval.as_secs()))
@@ -228,8 +762,111 @@ pub(in crate::features::ui) fn seed_settings_input(app: &AppState, field: Settin
             .selected_target()
             .map(|target| format!("{}s", target.config.timeout_total.as_secs()))
             .unwrap_or_default(),
-        SettingsField::TargetDnsEnabled
+        SettingsField::TargetAlerts => app
+            .selected_target()
+            .map(|target| format_alert_rules(&target.config.alert_rules))
+            .unwrap_or_default(),
+        SettingsField::TargetSloTarget => app
+            .selected_target()
+            .and_then(|target| target.config.slo_target)
+            .map(|value| value.to_string())
+            .unwrap_or_default(),
+        SettingsField::TargetSloLatency => app
+            .selected_target()
+            .and_then(|target| target.config.slo_latency_ms)
+            .map(|value| format!("{value}ms"))
+            .unwrap_or_default(),
+        SettingsField::TargetJitter => app
+            .selected_target()
+            .filter(|target| target.config.jitter_pct > 0)
+            .map(|target| target.config.jitter_pct.to_string())
+            .unwrap_or_default(),
+        SettingsField::TargetSchedule => app
+            .selected_target()
+            .and_then(|target| target.config.schedule.as_ref())
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        SettingsField::TargetUnixSocket => app
+            .selected_target()
+            .and_then(|target| target.config.unix_socket.as_ref())
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        SettingsField::TargetConnectTo => app
+            .selected_target()
+            .and_then(|target| target.config.connect_to.as_ref())
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        SettingsField::ProfileName => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .map(|profile| profile.config.name.clone())
+            .unwrap_or_default(),
+        SettingsField::ProfileInterval => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .and_then(|profile| profile.config.interval_override)
+            .map(format_duration)
+            .unwrap_or_default(),
+        SettingsField::ProfileExpectedStatus => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .and_then(|profile| profile.config.expected_status)
+            .map(|status| status.to_string())
+            .unwrap_or_default(),
+        SettingsField::ProfileExpectBody => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .and_then(|profile| profile.config.expect_body_contains.clone())
+            .unwrap_or_default(),
+        SettingsField::ProfileCaptureHeaders => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .map(|profile| profile.config.capture_headers.join(", "))
+            .unwrap_or_default(),
+        SettingsField::ProfileMaxReadBytes => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .map(|profile| profile.config.max_read_bytes.to_string())
+            .unwrap_or_default(),
+        SettingsField::ProfileRecvSpeed => app
+            .selected_target()
+            .and_then(|target| target.profiles.get(target.selected_profile))
+            .and_then(|profile| profile.config.max_recv_speed)
+            .map(|speed| speed.to_string())
+            .unwrap_or_default(),
+        SettingsField::DownloadSlots => app
+            .global
+            .download_slots
+            .map(|slots| slots.to_string())
+            .unwrap_or_default(),
+        // Secrets are never echoed back; the credentials are typed afresh
+        SettingsField::ProfileAuth => String::new(),
+        SettingsField::BurstSamples
+        | SettingsField::IncludeFailures
+        | SettingsField::PrimingSamples
+        | SettingsField::MissedSlots
+        | SettingsField::ProxyEnv
+        | SettingsField::StatsColumns
+        | SettingsField::ChartStat
+        | SettingsField::Theme
+        | SettingsField::TargetDnsMode
+        | SettingsField::TargetInsecure
+        | SettingsField::TargetPerIp
         | SettingsField::TargetPane
-        | SettingsField::TargetPaused => String::new(),
+        | SettingsField::TargetPaused
+        | SettingsField::TargetDuplicate
+        | SettingsField::ProfileColor
+        | SettingsField::ProfileMethod
+        | SettingsField::ProfileHttp
+        | SettingsField::ProfileTls
+        | SettingsField::ProfileReuse => String::new(),
     }
 }
+
+fn format_error_categories(categories: &BTreeSet<ErrorCategory>) -> String {
+    categories
+        .iter()
+        .map(ErrorCategory::key)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,126 +1,386 @@
-use crate::app::{AppState, ProfileViewMode, TargetRuntime};
+use crate::app::{AppState, EndpointView, ProfileViewMode, TargetRuntime};
+use crate::config::{ConnReusePolicy, WindowSpec};
 use crate::metrics::MetricKind;
-use crate::metrics_aggregate::ProfileKey;
+use crate::probe::ErrorCategory;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Chart, Clear, Dataset, GraphType};
+use ratatui::widgets::{Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph};
+use std::time::{Duration, SystemTime};
 
-use super::super::format::{color_for_index, format_y_axis_labels, update_bounds};
+use super::super::super::theme::Theme;
+use super::super::format::{
+    format_metric_value, format_x_axis_labels, format_y_axis_labels, update_bounds,
+};
 
 struct SeriesSpec {
     name: String,
+    metric: MetricKind,
     color: Color,
     points: Vec<(f64, f64)>,
 }
 
-pub(super) fn draw_chart(
-    frame: &mut ratatui::Frame,
-    area: Rect,
-    app: &AppState,
-    target: &TargetRuntime,
-) {
-    frame.render_widget(Clear, area);
+struct ChartSeries {
+    series: Vec<SeriesSpec>,
+    /// (x, category) of failed probes in categories that are not hidden
+    error_events: Vec<(f64, ErrorCategory)>,
+    /// (x, value) of spikes among the plotted series
+    spike_points: Vec<(f64, f64)>,
+    /// x of warm-profile probes that could not reuse their connection
+    reconnect_events: Vec<f64>,
+    ip_change_events: Vec<f64>,
+    config_change_events: Vec<f64>,
+    /// (start x, end x) of each outage; an ongoing one ends at the right edge
+    outage_spans: Vec<(f64, f64)>,
+    /// (x, 1-based number) of the target's annotations inside the window
+    annotation_events: Vec<(f64, usize)>,
+    y_axis_unit: &'static str,
+}
 
-    let window_seconds = app.window.duration().as_secs_f64();
-    let mut series_specs: Vec<SeriesSpec> = Vec::new();
-    let mut timeout_events: Vec<f64> = Vec::new();
-    let mut min_y = f64::INFINITY;
-    let mut max_y = f64::NEG_INFINITY;
+fn collect_series(app: &AppState, target: &TargetRuntime) -> Option<ChartSeries> {
+    let theme = Theme::of(app);
+    let window = app.target_window(target);
+    let mut series = Vec::new();
+    let mut error_events = Vec::new();
+    let mut spikes = Vec::new();
+    let mut reconnect_events = Vec::new();
+    let mut outages = Vec::new();
     let mut y_axis_unit = "";
 
     match target.view_mode {
         ProfileViewMode::Compare => {
-            y_axis_unit = app.selected_metric.unit();
+            y_axis_unit = target.selected_metric.unit();
             for (idx, profile) in target.profiles.iter().enumerate() {
-                let points = app.metrics.timeseries(
-                    ProfileKey {
-                        target_id: target.config.id,
-                        profile_id: profile.config.id,
-                    },
-                    app.window,
-                    app.selected_metric,
-                    app.global.link_capacity_mbps,
-                );
-                update_bounds(&points, &mut min_y, &mut max_y);
-                series_specs.push(SeriesSpec {
+                let key = target.view_key(profile);
+                let source = app.metric_source_key(target, key, target.selected_metric);
+                let points = match app.global.chart_stat {
+                    Some(stat) => app.metrics.stat_timeseries(
+                        source,
+                        window,
+                        target.selected_metric,
+                        stat,
+                        app.global.link_capacity_mbps,
+                    ),
+                    None => app.metrics.timeseries(
+                        source,
+                        window,
+                        target.selected_metric,
+                        app.global.link_capacity_mbps,
+                        app.failure_latency(target),
+                    ),
+                };
+                series.push(SeriesSpec {
                     name: profile.config.name.clone(),
-                    color: color_for_index(idx),
+                    metric: target.selected_metric,
+                    color: theme.profile_color(idx, &profile.config),
                     points,
                 });
-                timeout_events.extend(app.metrics.timeout_events(
-                    ProfileKey {
-                        target_id: target.config.id,
-                        profile_id: profile.config.id,
-                    },
-                    app.window,
+                error_events.extend(app.metrics.error_events(key, window));
+                outages.extend(app.metrics.outages(key, window));
+                if profile.config.conn_reuse == ConnReusePolicy::Warm {
+                    reconnect_events.extend(app.metrics.new_connection_events(key, window));
+                }
+                spikes.extend(app.metrics.spikes(
+                    source,
+                    window,
+                    target.selected_metric,
+                    &target.config.sampling,
+                    &app.aggregate_view(),
                 ));
             }
         }
+        ProfileViewMode::Single
+            if target.endpoint_view == EndpointView::Overlay && !target.endpoints.is_empty() =>
+        {
+            // One series per address, of the first selected metric
+            let profile = target.profiles.get(target.selected_profile)?;
+            let metric = target.selected_metrics.iter().next().copied()?;
+            y_axis_unit = metric.unit();
+            for (idx, ip) in target.endpoint_ips().into_iter().enumerate() {
+                let key = target.endpoint_key(profile, ip);
+                series.push(SeriesSpec {
+                    name: ip.to_string(),
+                    metric,
+                    color: theme.series_color(idx),
+                    points: app.metrics.timeseries(
+                        key,
+                        window,
+                        metric,
+                        app.global.link_capacity_mbps,
+                        app.failure_latency(target),
+                    ),
+                });
+                error_events.extend(app.metrics.error_events(key, window));
+                outages.extend(app.metrics.outages(key, window));
+            }
+        }
         ProfileViewMode::Single => {
-            let profile = match target.profiles.get(target.selected_profile) {
-                Some(profile) => profile,
-                None => return,
-            };
-            let selected: Vec<MetricKind> = app.selected_metrics.iter().copied().collect();
+            let profile = target.profiles.get(target.selected_profile)?;
+            let key = target.view_key(profile);
+            let selected: Vec<MetricKind> = target.selected_metrics.iter().copied().collect();
             if let Some(metric) = selected.first() {
                 y_axis_unit = metric.unit();
             }
 
             for (idx, metric) in selected.iter().enumerate() {
-                let points = app.metrics.timeseries(
-                    ProfileKey {
-                        target_id: target.config.id,
-                        profile_id: profile.config.id,
-                    },
-                    app.window,
-                    *metric,
-                    app.global.link_capacity_mbps,
-                );
-                update_bounds(&points, &mut min_y, &mut max_y);
-                series_specs.push(SeriesSpec {
+                let source = app.metric_source_key(target, key, *metric);
+                series.push(SeriesSpec {
                     name: metric.label().to_string(),
-                    color: color_for_index(idx),
-                    points,
+                    metric: *metric,
+                    color: theme.series_color(idx),
+                    points: app.metrics.timeseries(
+                        source,
+                        window,
+                        *metric,
+                        app.global.link_capacity_mbps,
+                        app.failure_latency(target),
+                    ),
                 });
+                spikes.extend(app.metrics.spikes(
+                    source,
+                    window,
+                    *metric,
+                    &target.config.sampling,
+                    &app.aggregate_view(),
+                ));
+            }
+            error_events.extend(app.metrics.error_events(key, window));
+            outages.extend(app.metrics.outages(key, window));
+            if profile.config.conn_reuse == ConnReusePolicy::Warm {
+                reconnect_events.extend(app.metrics.new_connection_events(key, window));
             }
-            timeout_events.extend(app.metrics.timeout_events(
-                ProfileKey {
-                    target_id: target.config.id,
-                    profile_id: profile.config.id,
-                },
-                app.window,
-            ));
         }
     }
+    let error_events = error_events
+        .into_iter()
+        .filter_map(|(x, kind)| Some((x, kind.category()?)))
+        .filter(|(_, category)| !app.global.hidden_error_markers.contains(category))
+        .collect();
+
+    let window_start = SystemTime::now()
+        .checked_sub(window.duration())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let ip_change_events = target
+        .ip_history
+        .changes_since(window_start)
+        .map(|change| chart_timestamp_to_x(window, change.ts).max(0.0))
+        .collect();
+    let spike_points = spikes
+        .iter()
+        .map(|spike| (chart_timestamp_to_x(window, spike.ts).max(0.0), spike.value))
+        .collect();
+    let config_change_events = target
+        .config_changes_since(window_start)
+        .map(|ts| chart_timestamp_to_x(window, ts).max(0.0))
+        .collect();
+    let window_seconds = window.duration().as_secs_f64();
+    let outage_spans = outages
+        .iter()
+        .map(|outage| {
+            let start = chart_timestamp_to_x(window, outage.start).max(0.0);
+            let end = outage
+                .end
+                .map_or(window_seconds, |end| chart_timestamp_to_x(window, end));
+            (start, end)
+        })
+        .collect();
+    let annotation_events = target
+        .annotations_since(window_start)
+        .map(|(number, annotation)| (chart_timestamp_to_x(window, annotation.ts).max(0.0), number))
+        .collect();
+
+    Some(ChartSeries {
+        series,
+        error_events,
+        spike_points,
+        reconnect_events,
+        ip_change_events,
+        config_change_events,
+        outage_spans,
+        annotation_events,
+        y_axis_unit,
+    })
+}
+
+/// Sorted, de-duplicated x positions of every sample currently plotted for `target`.
+pub(in crate::features::ui) fn chart_sample_positions(
+    app: &AppState,
+    target: &TargetRuntime,
+) -> Vec<f64> {
+    let Some(chart) = collect_series(app, target) else {
+        return Vec::new();
+    };
+    let mut positions: Vec<f64> = chart
+        .series
+        .iter()
+        .flat_map(|spec| spec.points.iter().map(|(x, _)| *x))
+        .collect();
+    positions.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    positions.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+    positions
+}
+
+/// Converts a chart x position (seconds since window start) to a wall-clock timestamp.
+pub(in crate::features::ui) fn chart_x_to_timestamp(window: WindowSpec, x: f64) -> SystemTime {
+    let age = (window.duration().as_secs_f64() - x).max(0.0);
+    SystemTime::now()
+        .checked_sub(Duration::from_secs_f64(age))
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Converts a wall-clock timestamp to a chart x position for the current window.
+pub(in crate::features::ui) fn chart_timestamp_to_x(window: WindowSpec, ts: SystemTime) -> f64 {
+    let age = SystemTime::now()
+        .duration_since(ts)
+        .map(|age| age.as_secs_f64())
+        .unwrap_or(0.0);
+    window.duration().as_secs_f64() - age
+}
+
+/// Smallest positive value across all series; zeros are clamped to it on a
+/// log axis. Falls back to 1 when nothing is positive.
+fn min_positive_value(series: &[SeriesSpec]) -> f64 {
+    let min = series
+        .iter()
+        .flat_map(|spec| spec.points.iter().map(|(_, y)| *y))
+        .filter(|y| *y > 0.0)
+        .fold(f64::INFINITY, f64::min);
+    if min.is_finite() { min } else { 1.0 }
+}
+
+/// Points as drawn. On a log axis (`log_floor` set) each value becomes its
+/// log10, after values below the floor are raised to it.
+fn plot_points(points: &[(f64, f64)], log_floor: Option<f64>) -> Vec<(f64, f64)> {
+    match log_floor {
+        Some(floor) => points
+            .iter()
+            .map(|(x, y)| (*x, y.max(floor).log10()))
+            .collect(),
+        None => points.to_vec(),
+    }
+}
+
+fn error_marker_color(category: ErrorCategory, theme: &Theme) -> Color {
+    match category {
+        ErrorCategory::Timeout => theme.error,
+        ErrorCategory::Tls => theme.secondary,
+        ErrorCategory::HttpStatus => theme.warn,
+        ErrorCategory::Other => theme.subtle,
+    }
+}
+
+fn nearest_point(points: &[(f64, f64)], x: f64) -> Option<(f64, f64)> {
+    points.iter().copied().min_by(|a, b| {
+        (a.0 - x)
+            .abs()
+            .partial_cmp(&(b.0 - x).abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+pub(super) fn draw_chart(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    target: &TargetRuntime,
+) {
+    let theme = Theme::of(app);
+    frame.render_widget(Clear, area);
+
+    let window = app.target_window(target);
+    let window_seconds = window.duration().as_secs_f64();
+    let Some(chart_series) = collect_series(app, target) else {
+        return;
+    };
+    let ChartSeries {
+        series: series_specs,
+        error_events,
+        spike_points,
+        reconnect_events,
+        ip_change_events,
+        config_change_events,
+        outage_spans,
+        annotation_events,
+        y_axis_unit,
+    } = chart_series;
+
+    let log_scale = app.global.log_scale_chart;
+    // Raw points stay on the specs for the cursor readout; these are what gets drawn
+    let log_floor = log_scale.then(|| min_positive_value(&series_specs));
+    let plotted: Vec<Vec<(f64, f64)>> = series_specs
+        .iter()
+        .map(|spec| plot_points(&spec.points, log_floor))
+        .collect();
+    let spike_points = plot_points(&spike_points, log_floor);
+
+    let mut min_y = f64::INFINITY;
+    let mut max_y = f64::NEG_INFINITY;
+    for points in &plotted {
+        update_bounds(points, &mut min_y, &mut max_y);
+    }
 
     if min_y == f64::INFINITY || max_y == f64::NEG_INFINITY {
         min_y = 0.0;
         max_y = 1.0;
     }
 
-    // Add 10% padding to y-axis
+    // Add 10% padding to y-axis; log bounds may legitimately go below zero
     let y_range = max_y - min_y;
     let y_padding = if y_range > 0.0 { y_range * 0.1 } else { 0.1 };
-    min_y = (min_y - y_padding).max(0.0);
+    min_y -= y_padding;
+    if !log_scale {
+        min_y = min_y.max(0.0);
+    }
     max_y += y_padding;
 
-    let timeout_y = if max_y > min_y {
-        max_y - (max_y - min_y) * 0.05
-    } else {
-        max_y
-    };
-    let timeout_points: Vec<(f64, f64)> = timeout_events.iter().map(|x| (*x, timeout_y)).collect();
+    // One row per category near the top, so coinciding failures stay apart
+    let error_points: Vec<(ErrorCategory, Vec<(f64, f64)>)> = ErrorCategory::ALL
+        .into_iter()
+        .enumerate()
+        .map(|(row, category)| {
+            let y = max_y - (max_y - min_y) * (0.05 + 0.04 * row as f64);
+            let points: Vec<(f64, f64)> = error_events
+                .iter()
+                .filter(|(_, event)| *event == category)
+                .map(|(x, _)| (*x, y))
+                .collect();
+            (category, points)
+        })
+        .filter(|(_, points)| !points.is_empty())
+        .collect();
+    let ip_change_y = min_y + (max_y - min_y) * 0.05;
+    let ip_change_points: Vec<(f64, f64)> =
+        ip_change_events.iter().map(|x| (*x, ip_change_y)).collect();
+    // Just above the IP change row, so both stay readable
+    let reconnect_y = min_y + (max_y - min_y) * 0.09;
+    let reconnect_points: Vec<(f64, f64)> =
+        reconnect_events.iter().map(|x| (*x, reconnect_y)).collect();
+    // One two-point line per marker; a single dataset would join them diagonally
+    let config_change_lines: Vec<[(f64, f64); 2]> = config_change_events
+        .iter()
+        .map(|x| [(*x, min_y), (*x, max_y)])
+        .collect();
+    // Bracket row along the bottom, under the IP change markers
+    let outage_y = min_y + (max_y - min_y) * 0.01;
+    let outage_lines: Vec<[(f64, f64); 2]> = outage_spans
+        .iter()
+        .map(|(start, end)| [(*start, outage_y), (*end, outage_y)])
+        .collect();
+    let annotation_lines: Vec<[(f64, f64); 2]> = annotation_events
+        .iter()
+        .map(|(x, _)| [(*x, min_y), (*x, max_y)])
+        .collect();
 
     let datasets: Vec<Dataset> = series_specs
         .iter()
-        .map(|spec| {
+        .zip(&plotted)
+        .map(|(spec, points)| {
             Dataset::default()
                 .name(spec.name.clone())
                 .graph_type(GraphType::Line)
                 .style(Style::default().fg(spec.color))
-                .data(&spec.points)
+                .data(points)
         })
         .collect();
 
@@ -141,33 +401,166 @@ pub(super) fn draw_chart(
         .collect();
 
     let mut datasets = datasets;
-    if !timeout_points.is_empty() {
+    for (category, points) in &error_points {
+        let color = error_marker_color(*category, theme);
         if !legend_spans.is_empty() {
             legend_spans.push(Span::styled("  ", Style::default()));
         }
-        legend_spans.push(Span::styled("● ", Style::default().fg(Color::Red)));
-        legend_spans.push(Span::styled("Timeout", Style::default().fg(Color::Red)));
+        legend_spans.push(Span::styled("● ", Style::default().fg(color)));
+        legend_spans.push(Span::styled(category.label(), Style::default().fg(color)));
         datasets.push(
             Dataset::default()
-                .name("Timeout".to_string())
+                .name(category.label().to_string())
                 .graph_type(GraphType::Scatter)
                 .marker(symbols::Marker::Dot)
-                .style(Style::default().fg(Color::Red))
-                .data(&timeout_points),
+                .style(Style::default().fg(color))
+                .data(points),
         );
     }
 
+    if !spike_points.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("▲ ", Style::default().fg(theme.alert)));
+        legend_spans.push(Span::styled("Spike", Style::default().fg(theme.alert)));
+        datasets.push(
+            Dataset::default()
+                .name("Spike".to_string())
+                .graph_type(GraphType::Scatter)
+                .marker(symbols::Marker::HalfBlock)
+                .style(Style::default().fg(theme.alert))
+                .data(&spike_points),
+        );
+    }
+
+    if !ip_change_points.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("◆ ", Style::default().fg(theme.secondary)));
+        legend_spans.push(Span::styled(
+            "IP change",
+            Style::default().fg(theme.secondary),
+        ));
+        datasets.push(
+            Dataset::default()
+                .name("IP change".to_string())
+                .graph_type(GraphType::Scatter)
+                .marker(symbols::Marker::Block)
+                .style(Style::default().fg(theme.secondary))
+                .data(&ip_change_points),
+        );
+    }
+
+    if !reconnect_points.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("○ ", Style::default().fg(theme.info)));
+        legend_spans.push(Span::styled("New conn", Style::default().fg(theme.info)));
+        datasets.push(
+            Dataset::default()
+                .name("New conn".to_string())
+                .graph_type(GraphType::Scatter)
+                .marker(symbols::Marker::Dot)
+                .style(Style::default().fg(theme.info))
+                .data(&reconnect_points),
+        );
+    }
+
+    if !config_change_lines.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("│ ", Style::default().fg(theme.warn)));
+        legend_spans.push(Span::styled(
+            "Config change",
+            Style::default().fg(theme.warn),
+        ));
+        for line in &config_change_lines {
+            datasets.push(
+                Dataset::default()
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(theme.warn))
+                    .data(line),
+            );
+        }
+    }
+
+    if !outage_lines.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("━ ", Style::default().fg(theme.error)));
+        legend_spans.push(Span::styled("Outage", Style::default().fg(theme.error)));
+        for line in &outage_lines {
+            datasets.push(
+                Dataset::default()
+                    .graph_type(GraphType::Line)
+                    .marker(symbols::Marker::Block)
+                    .style(Style::default().fg(theme.error))
+                    .data(line),
+            );
+        }
+    }
+
+    if !annotation_lines.is_empty() {
+        if !legend_spans.is_empty() {
+            legend_spans.push(Span::styled("  ", Style::default()));
+        }
+        legend_spans.push(Span::styled("│ ", Style::default().fg(theme.accent)));
+        legend_spans.push(Span::styled("Note", Style::default().fg(theme.accent)));
+        for line in &annotation_lines {
+            datasets.push(
+                Dataset::default()
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(theme.accent))
+                    .data(line),
+            );
+        }
+    }
+
+    let cursor_x = target
+        .chart_cursor
+        .map(|ts| chart_timestamp_to_x(window, ts))
+        .filter(|x| (0.0..=window_seconds).contains(x));
+    let cursor_points: Vec<(f64, f64)> = cursor_x
+        .map(|x| vec![(x, min_y), (x, max_y)])
+        .unwrap_or_default();
+    if !cursor_points.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.fg))
+                .data(&cursor_points),
+        );
+    }
+
+    let mut window_label = if app.window_partially_covered(target) {
+        format!("{} · partial data", window.label())
+    } else {
+        window.label().to_string()
+    };
+    if log_scale {
+        window_label.push_str(" · log");
+    }
     let chart_title = if target.view_mode == ProfileViewMode::Compare {
-        format!(
-            " Chart ({}) [{}] ",
-            app.selected_metric.label(),
-            app.window.label()
-        )
+        let metric_label = match app.global.chart_stat {
+            Some(stat) => format!("{} {}", target.selected_metric.label(), stat.label()),
+            None => target.selected_metric.label().to_string(),
+        };
+        format!(" Chart ({metric_label}) [{window_label}] ")
     } else {
-        format!(" Chart [{}] ", app.window.label())
+        format!(" Chart [{window_label}] ")
     };
 
-    let y_labels = format_y_axis_labels(min_y, max_y, y_axis_unit);
+    let y_labels = format_y_axis_labels(min_y, max_y, y_axis_unit, log_scale);
+    let y_label_width = y_labels
+        .iter()
+        .map(|label| label.width() as u16)
+        .max()
+        .unwrap_or(0);
 
     let chart = Chart::new(datasets)
         .block(
@@ -175,26 +568,213 @@ pub(super) fn draw_chart(
                 .title(chart_title)
                 .title_bottom(Line::from(legend_spans).alignment(Alignment::Center))
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray)),
+                .border_style(Style::default().fg(theme.muted)),
         )
-        .style(Style::default().bg(Color::Black))
+        .style(Style::default().bg(theme.bg))
         .x_axis(
             ratatui::widgets::Axis::default()
-                .title("Time (ago)")
-                .style(Style::default().fg(Color::Gray))
+                .title(if app.global.absolute_time_axis {
+                    "Time"
+                } else {
+                    "Time (ago)"
+                })
+                .style(Style::default().fg(theme.subtle))
                 .bounds([0.0, window_seconds])
-                .labels(vec![
-                    Span::styled("now", Style::default().fg(Color::Green)),
-                    Span::raw(format!("-{}s", window_seconds as u64 / 2)),
-                    Span::raw(format!("-{}s", window_seconds as u64)),
-                ]),
+                .labels(format_x_axis_labels(
+                    window.duration(),
+                    SystemTime::now(),
+                    app.global.absolute_time_axis,
+                    theme,
+                )),
         )
         .y_axis(
             ratatui::widgets::Axis::default()
                 .title(y_axis_unit)
-                .style(Style::default().fg(Color::Gray))
+                .style(Style::default().fg(theme.subtle))
                 .bounds([min_y, max_y])
                 .labels(y_labels),
         );
     frame.render_widget(chart, area);
+    draw_annotation_numbers(
+        frame,
+        area,
+        y_label_width,
+        window_seconds,
+        &annotation_events,
+        theme,
+    );
+
+    if let Some(x) = cursor_x {
+        let tolerance = (target.config.interval.as_secs_f64() / 2.0).max(0.5);
+        let mut failures: Vec<ErrorCategory> = error_events
+            .iter()
+            .filter(|(event, _)| (event - x).abs() <= tolerance)
+            .map(|(_, category)| *category)
+            .collect();
+        failures.sort();
+        failures.dedup();
+        let ip_changed = ip_change_events
+            .iter()
+            .any(|event| (event - x).abs() <= tolerance);
+        draw_cursor_readout(
+            frame,
+            area,
+            window_seconds - x,
+            &series_specs,
+            x,
+            marker_lines(&failures, ip_changed, theme),
+            theme,
+        );
+    }
+}
+
+/// Writes each annotation's number on the first plot row, above its marker
+/// line. The plot starts after the y-axis labels and the axis itself.
+fn draw_annotation_numbers(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    y_label_width: u16,
+    window_seconds: f64,
+    annotation_events: &[(f64, usize)],
+    theme: &Theme,
+) {
+    let plot_left = area.x + 1 + y_label_width + 1;
+    let plot_right = area.right().saturating_sub(1);
+    if plot_right <= plot_left || area.height < 3 || window_seconds <= 0.0 {
+        return;
+    }
+    let plot_width = f64::from(plot_right - plot_left - 1);
+    let buffer = frame.buffer_mut();
+    for (x, number) in annotation_events {
+        let label = number.to_string();
+        let column = plot_left + ((x / window_seconds).clamp(0.0, 1.0) * plot_width).round() as u16;
+        let column = column.min(plot_right.saturating_sub(label.len() as u16));
+        buffer.set_string(
+            column,
+            area.y + 1,
+            &label,
+            Style::default()
+                .fg(theme.bg)
+                .bg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+}
+
+/// Readout lines for the failures and IP change at the cursor.
+fn marker_lines(failures: &[ErrorCategory], ip_changed: bool, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = failures
+        .iter()
+        .map(|category| {
+            Line::styled(
+                format!("● {}", category.label().to_lowercase()),
+                Style::default().fg(error_marker_color(*category, theme)),
+            )
+        })
+        .collect();
+    if ip_changed {
+        lines.push(Line::styled(
+            "◆ IP change",
+            Style::default().fg(theme.secondary),
+        ));
+    }
+    lines
+}
+
+fn draw_cursor_readout(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    age_secs: f64,
+    series_specs: &[SeriesSpec],
+    x: f64,
+    markers: Vec<Line<'static>>,
+    theme: &Theme,
+) {
+    let mut lines = vec![Line::styled(
+        format!("-{age_secs:.0}s"),
+        Style::default().fg(theme.fg).add_modifier(Modifier::BOLD),
+    )];
+    for spec in series_specs {
+        let value = nearest_point(&spec.points, x).map(|(_, y)| y);
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", spec.name), Style::default().fg(spec.color)),
+            Span::raw(format_metric_value(spec.metric, value)),
+        ]));
+    }
+    lines.extend(markers);
+
+    let width = lines
+        .iter()
+        .map(|line| line.width() as u16)
+        .max()
+        .unwrap_or(0)
+        .saturating_add(2)
+        .min(area.width.saturating_sub(2));
+    let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let readout_area = Rect {
+        x: area.x + area.width.saturating_sub(width + 1),
+        y: area.y + 1,
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, readout_area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.fg)),
+        ),
+        readout_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(points: Vec<(f64, f64)>) -> SeriesSpec {
+        SeriesSpec {
+            name: "total".to_string(),
+            metric: MetricKind::Total,
+            color: Color::Reset,
+            points,
+        }
+    }
+
+    #[test]
+    fn log_axis_plots_log10_values_with_zeros_raised_to_the_floor() {
+        let points = [(0.0, 0.0), (1.0, 10.0), (2.0, 1000.0)];
+        assert_eq!(plot_points(&points, None), points);
+        assert_eq!(
+            plot_points(&points, Some(10.0)),
+            [(0.0, 1.0), (1.0, 1.0), (2.0, 3.0)]
+        );
+    }
+
+    #[test]
+    fn log_floor_is_the_smallest_positive_value_of_any_series() {
+        let series = [
+            spec(vec![(0.0, 0.0), (1.0, 40.0)]),
+            spec(vec![(0.0, 5.0), (1.0, -1.0)]),
+        ];
+        assert_eq!(min_positive_value(&series), 5.0);
+        assert_eq!(min_positive_value(&[spec(vec![(0.0, 0.0)])]), 1.0);
+        assert_eq!(min_positive_value(&[]), 1.0);
+    }
+
+    #[test]
+    fn log_axis_labels_show_the_values_not_their_logs() {
+        let content = |labels: Vec<Span>| -> Vec<String> {
+            labels.iter().map(|span| span.content.to_string()).collect()
+        };
+        assert_eq!(
+            content(format_y_axis_labels(0.0, 4.0, "ms", true)),
+            ["1.0ms", "100ms", "10.0s"]
+        );
+        assert_eq!(
+            content(format_y_axis_labels(0.0, 4.0, "ms", false)),
+            ["0.0ms", "2.0ms", "4.0ms"]
+        );
+    }
 }
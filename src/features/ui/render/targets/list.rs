@@ -1,85 +1,344 @@
-use crate::app::{AppState, ProfileViewMode, TargetPaneMode, TargetRuntime};
+use crate::app::{
+    AppState, EndpointView, FreshnessLevel, GroupSummary, ProfileViewMode, SplitSide,
+    TargetListRow, TargetPaneMode, TargetRuntime, TargetStatus,
+};
+use crate::metrics::{MetricKind, MetricStats};
+use crate::probe::ProbeErrorKind;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use std::collections::BTreeMap;
+use std::time::{Instant, SystemTime};
+use unicode_width::UnicodeWidthStr;
 
-use super::super::format::{list_state, truncate_string};
+use super::super::super::state::{HitRegistry, HitTarget};
+use super::super::super::theme::Theme;
+use super::super::format::{format_elapsed, format_latency, style_for_freshness, truncate_string};
 use super::chart::draw_chart;
-use super::panes::{draw_error_bar, draw_metrics_table, draw_network_info_pane, draw_summary_pane};
+use super::overview::draw_overview;
+use super::panes::{
+    draw_breakdown_pane, draw_error_bar, draw_heatmap_pane, draw_metrics_table,
+    draw_network_info_pane, draw_summary_pane, draw_windows_pane,
+};
 
-pub(in crate::features::ui) fn draw_main(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
+pub(in crate::features::ui) fn draw_main(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    list_state: &mut ListState,
+    metric_cursor: Option<usize>,
+    hits: &mut HitRegistry,
+) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(32), Constraint::Min(10)])
         .split(area);
 
-    draw_target_list(frame, chunks[0], app);
-    draw_target_panes(frame, chunks[1], app);
+    // Merging a group's samples is the costly part; do it once per frame
+    let groups = app.group_summaries(SystemTime::now());
+    draw_target_list(frame, chunks[0], app, &groups, list_state, hits);
+    if app.overview.is_some() {
+        draw_overview(frame, chunks[1], app, &groups);
+    } else {
+        draw_target_panes(frame, chunks[1], app, &groups, metric_cursor, hits);
+    }
 }
 
-fn draw_target_list(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
-    let items: Vec<ListItem> = app
-        .targets
+fn draw_target_list(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    groups: &BTreeMap<String, GroupSummary>,
+    state: &mut ListState,
+    hits: &mut HitRegistry,
+) {
+    let theme = Theme::of(app);
+    let visible = app.visible_targets();
+    let rows = app.target_list_rows();
+    let now = SystemTime::now();
+    let mut items: Vec<ListItem> = rows
         .iter()
-        .enumerate()
-        .map(|(idx, target)| {
-            // Check if any profile has an error
-            let has_error = target.profiles.iter().any(|p| p.last_error.is_some());
-
-            let (status, status_style) = if target.paused {
-                ("⏸", Style::default().fg(Color::Yellow))
-            } else if has_error {
-                ("⚠", Style::default().fg(Color::Red))
-            } else {
-                ("▶", Style::default().fg(Color::Green))
-            };
-
-            let is_selected = idx == app.selected_target;
-            let line = Line::from(vec![
-                Span::styled(format!(" {} ", status), status_style),
-                Span::styled(
-                    truncate_string(target.config.url.host_str().unwrap_or("?"), 24),
-                    if is_selected {
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD)
-                    } else if has_error {
-                        Style::default().fg(Color::Red)
-                    } else {
-                        Style::default().fg(Color::White)
-                    },
-                ),
-            ]);
-            ListItem::new(line)
+        .map(|row| match row {
+            TargetListRow::Group(name) => group_list_item(app, name, groups.get(name)),
+            TargetListRow::Target(idx) => target_list_item(app, *idx, now),
         })
         .collect();
+    if items.is_empty() && !app.targets.is_empty() {
+        items.push(ListItem::new(Line::styled(
+            " No matches",
+            Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC),
+        )));
+    }
 
+    let title = if app.target_filter.is_empty() {
+        " Targets ".to_string()
+    } else {
+        format!(
+            " Targets /{} ({}/{}) ",
+            truncate_string(&app.target_filter, 12),
+            visible.len(),
+            app.targets.len()
+        )
+    };
     let list = List::new(items)
         .block(
             Block::default()
-                .title(" Targets ")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Blue)),
+                .border_style(Style::default().fg(theme.info)),
         )
-        .highlight_style(
+        .highlight_style(theme.selection.add_modifier(Modifier::BOLD))
+        .highlight_symbol("│");
+    state.select(app.cursor_row(&rows));
+    frame.render_stateful_widget(list, area, state);
+
+    hits.register(area, HitTarget::TargetList);
+    let inner = Rect {
+        x: area.x + 1,
+        y: area.y + 1,
+        width: area.width.saturating_sub(2),
+        height: area.height.saturating_sub(2),
+    };
+    // Group headers take two lines and per-IP targets one more per address,
+    // so rows are placed by running height
+    let mut y = 0u16;
+    for row in rows.iter().skip(state.offset()) {
+        let (height, hit) = match row {
+            TargetListRow::Group(name) => {
+                let Some(&first) = visible
+                    .iter()
+                    .find(|&&idx| app.targets[idx].group() == Some(name.as_str()))
+                else {
+                    continue;
+                };
+                (GROUP_ROW_HEIGHT, HitTarget::GroupRow(first))
+            }
+            TargetListRow::Target(idx) => (
+                1 + app.targets[*idx].endpoint_ips().len() as u16,
+                HitTarget::TargetRow(*idx),
+            ),
+        };
+        if y + height > inner.height {
+            break;
+        }
+        hits.register(Rect::new(inner.x, inner.y + y, inner.width, height), hit);
+        y += height;
+    }
+}
+
+/// Shown on every target probed without certificate verification.
+const INSECURE_BADGE: &str = "⚠ INSECURE";
+
+fn insecure_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(theme.fg)
+        .bg(theme.error)
+        .add_modifier(Modifier::BOLD)
+}
+
+/// Lines taken by a group header: its name, then the aggregate line.
+const GROUP_ROW_HEIGHT: u16 = 2;
+
+fn status_glyph(status: TargetStatus, theme: &Theme) -> (&'static str, Style) {
+    match status {
+        TargetStatus::Paused => ("⏸", Style::default().fg(theme.warn)),
+        TargetStatus::Idle => ("◌", Style::default().fg(theme.muted)),
+        TargetStatus::Alerting => (
+            "!",
+            Style::default()
+                .fg(theme.fg)
+                .bg(theme.error)
+                .add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK),
+        ),
+        TargetStatus::Failing => ("⚠", Style::default().fg(theme.error)),
+        TargetStatus::Warming => ("◌", Style::default().fg(theme.accent)),
+        TargetStatus::Running | TargetStatus::Stalled => ("▶", Style::default().fg(theme.success)),
+    }
+}
+
+fn group_list_item<'a>(app: &AppState, name: &str, summary: Option<&GroupSummary>) -> ListItem<'a> {
+    let theme = Theme::of(app);
+    let collapsed = app.collapsed_groups.contains(name);
+    let is_selected = app.selected_group.as_deref() == Some(name);
+    let Some(summary) = summary else {
+        return ListItem::new(Line::raw(""));
+    };
+
+    let (status, status_style) = status_glyph(summary.status, theme);
+    let mut spans = vec![
+        Span::styled(
+            if collapsed { " ▸ " } else { " ▾ " },
+            Style::default().fg(theme.muted),
+        ),
+        Span::styled(
+            truncate_string(&format!("#{name}"), 18),
+            if is_selected {
+                Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD)
+            },
+        ),
+        Span::styled(
+            format!(" ({}) ", summary.targets),
+            Style::default().fg(theme.muted),
+        ),
+        Span::styled(status, status_style),
+    ];
+    if summary.status == TargetStatus::Stalled {
+        spans.push(Span::styled(
+            " ◷",
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let p50 = summary
+        .p50_ms
+        .map_or_else(|| "-".to_string(), format_latency);
+    let p99 = summary
+        .p99_ms
+        .map_or_else(|| "-".to_string(), format_latency);
+    let loss = summary
+        .loss_rate
+        .map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate * 100.0));
+    let aggregate = Line::styled(
+        format!("   p50 {p50} · p99 {p99} · loss {loss}"),
+        Style::default().fg(theme.muted),
+    );
+    ListItem::new(vec![Line::from(spans), aggregate])
+}
+
+fn target_list_item<'a>(app: &AppState, idx: usize, now: SystemTime) -> ListItem<'a> {
+    let theme = Theme::of(app);
+    let target = &app.targets[idx];
+    let status = target.status(now);
+    let is_alerting = status == TargetStatus::Alerting;
+    let has_error = target.profiles.iter().any(|p| p.last_error.is_some());
+    // Members of a group are indented under its header
+    let indent = if target.group().is_some() { "  " } else { "" };
+
+    let (glyph, status_style) = status_glyph(status, theme);
+    let is_selected = app.selected_group.is_none() && idx == app.selected_target;
+    // The insecure badge gives up name width so it can never be cut off
+    let badge_width = if target.config.insecure {
+        INSECURE_BADGE.width() + 1
+    } else {
+        0
+    };
+    let mut spans = vec![
+        Span::raw(indent),
+        Span::styled(format!(" {} ", glyph), status_style),
+        Span::styled(
+            truncate_string(
+                &target.config.display_name(),
+                24 - indent.width() - badge_width,
+            ),
+            if is_selected {
+                Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)
+            } else if has_error || is_alerting {
+                Style::default().fg(theme.error)
+            } else {
+                Style::default().fg(theme.fg)
+            },
+        ),
+    ];
+    if target.config.insecure {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(INSECURE_BADGE, insecure_style(theme)));
+    }
+    // Only overdue data is called out, keeping the list quiet while probing
+    if let Some(freshness) = app.target_freshness(target)
+        && freshness.level != FreshnessLevel::Fresh
+    {
+        spans.push(Span::styled(
+            format!(" {}", format_elapsed(freshness.age)),
+            style_for_freshness(freshness.level, theme),
+        ));
+    }
+    if status == TargetStatus::Idle {
+        spans.push(Span::styled(" idle", Style::default().fg(theme.muted)));
+    } else if status == TargetStatus::Warming {
+        spans.push(Span::styled(" warming", Style::default().fg(theme.accent)));
+    } else if target.stalled_profiles() > 0 {
+        spans.push(Span::styled(
+            " ◷",
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if is_alerting {
+        spans.push(Span::styled(
+            format!(" ⚑{}", target.active_alerts()),
             Style::default()
-                .bg(Color::DarkGray)
+                .fg(theme.error)
                 .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol("│");
-    let mut state = list_state(app.selected_target);
-    frame.render_stateful_widget(list, area, &mut state);
+        ));
+    }
+    let mut lines = vec![Line::from(spans)];
+    lines.extend(endpoint_lines(app, target, indent));
+    ListItem::new(lines)
+}
+
+/// One line per address of a `per_ip` target, with the selected profile's
+/// p50 and loss there; the address picked in the endpoint view is marked.
+fn endpoint_lines<'a>(app: &AppState, target: &TargetRuntime, indent: &str) -> Vec<Line<'a>> {
+    let theme = Theme::of(app);
+    let Some(profile) = target.profiles.get(target.selected_profile) else {
+        return Vec::new();
+    };
+    target
+        .endpoint_ips()
+        .into_iter()
+        .map(|ip| {
+            let aggregate = app.metrics.windowed_aggregate(
+                target.endpoint_key(profile, ip),
+                app.target_window(target),
+                &target.config.sampling,
+                &app.aggregate_view(),
+                None,
+            );
+            let stat = |metric, select: fn(&MetricStats) -> Option<f64>| {
+                aggregate.by_metric.get(&metric).and_then(select)
+            };
+            let p50 = stat(MetricKind::Total, |stats| stats.p50)
+                .map_or_else(|| "-".to_string(), format_latency);
+            let loss = stat(MetricKind::ProbeLossRate, |stats| stats.mean)
+                .map_or_else(|| "-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+            let style = if target.endpoint_view == EndpointView::Ip(ip) {
+                Style::default().fg(theme.warn)
+            } else {
+                Style::default().fg(theme.muted)
+            };
+            Line::styled(
+                format!(
+                    "{indent}   └ {} {p50} {loss}",
+                    truncate_string(&ip.to_string(), 15)
+                ),
+                style,
+            )
+        })
+        .collect()
 }
 
-fn draw_target_panes(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
+fn draw_target_panes(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    groups: &BTreeMap<String, GroupSummary>,
+    metric_cursor: Option<usize>,
+    hits: &mut HitRegistry,
+) {
+    let theme = Theme::of(app);
     if app.targets.is_empty() {
         let empty_lines = vec![
             Line::from(""),
             Line::styled(
                 "  No targets configured  ",
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(theme.muted)
                     .add_modifier(Modifier::ITALIC),
             ),
             Line::from(""),
@@ -88,8 +347,8 @@ fn draw_target_panes(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
                 Span::styled(
                     " a ",
                     Style::default()
-                        .fg(Color::Black)
-                        .bg(Color::Cyan)
+                        .fg(theme.bg)
+                        .bg(theme.accent)
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(" to add a target"),
@@ -97,22 +356,147 @@ fn draw_target_panes(frame: &mut ratatui::Frame, area: Rect, app: &AppState) {
             Line::from(""),
             Line::styled(
                 "  Example: https://google.com h2+tls13+warm",
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.muted),
             ),
         ];
         let empty = Paragraph::new(empty_lines).block(
             Block::default()
                 .title(" Details ")
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Blue)),
+                .border_style(Style::default().fg(theme.info)),
         );
         frame.render_widget(empty, area);
         return;
     }
 
-    if let Some(target) = app.selected_target() {
-        draw_target_pane(frame, area, app, target);
+    if let Some((left, right, focus)) = app.split_targets() {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        // Clicks only reach the focused half, which is the selected target
+        let mut unfocused_hits = HitRegistry::default();
+        let (left_hits, right_hits) = match focus {
+            SplitSide::Left => (&mut *hits, &mut unfocused_hits),
+            SplitSide::Right => (&mut unfocused_hits, &mut *hits),
+        };
+        draw_target_pane(
+            frame,
+            halves[0],
+            app,
+            left,
+            focus == SplitSide::Left,
+            metric_cursor,
+            left_hits,
+        );
+        draw_target_pane(
+            frame,
+            halves[1],
+            app,
+            right,
+            focus == SplitSide::Right,
+            metric_cursor,
+            right_hits,
+        );
+    } else if let Some(target) = app.selected_target() {
+        draw_target_pane(frame, area, app, target, true, metric_cursor, hits);
+    } else if let Some(name) = app.selected_group.as_deref() {
+        draw_group_pane(frame, area, app, name, groups.get(name));
+    } else {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::styled(
+                format!("  No targets match \"{}\"", app.target_filter),
+                Style::default()
+                    .fg(theme.muted)
+                    .add_modifier(Modifier::ITALIC),
+            ),
+            Line::from(""),
+            Line::styled(
+                "  Press Esc to clear the filter",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+        .block(
+            Block::default()
+                .title(" Details ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.info)),
+        );
+        frame.render_widget(message, area);
+    }
+}
+
+fn draw_group_pane(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    name: &str,
+    summary: Option<&GroupSummary>,
+) {
+    let theme = Theme::of(app);
+    let label_style = Style::default().fg(theme.muted);
+    let mut lines = vec![Line::from("")];
+    if let Some(summary) = summary {
+        let (status, status_style) = status_glyph(summary.status, theme);
+        lines.push(Line::from(vec![
+            Span::styled("  Targets   ", label_style),
+            Span::raw(summary.targets.to_string()),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Worst     ", label_style),
+            Span::styled(status, status_style),
+            Span::raw(format!(" {:?}", summary.status)),
+        ]));
+        for (label, value) in [("p50", summary.p50_ms), ("p99", summary.p99_ms)] {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{:<12}", format!("  {label} [{}]", summary.window.label())),
+                    label_style,
+                ),
+                Span::raw(value.map_or_else(|| "-".to_string(), format_latency)),
+                Span::styled(" (all member probes)", label_style),
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Span::styled("  Loss      ", label_style),
+            Span::raw(
+                summary
+                    .loss_rate
+                    .map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate * 100.0)),
+            ),
+        ]));
+        // Failures of every member, most frequent first
+        if !summary.errors.is_empty() {
+            let listed = summary
+                .errors
+                .iter()
+                .take(3)
+                .map(|(kind, count)| format!("{}:{count}", kind.short_label()))
+                .collect::<Vec<_>>()
+                .join(" ");
+            lines.push(Line::from(vec![
+                Span::styled("  Errors    ", label_style),
+                Span::styled(listed, Style::default().fg(theme.error)),
+            ]));
+        }
     }
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        if app.collapsed_groups.contains(name) {
+            "  Press Enter to expand the group"
+        } else {
+            "  Press Enter to collapse the group"
+        },
+        label_style,
+    ));
+    let pane = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(" Group #{} ", truncate_string(name, 24)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.info)),
+    );
+    frame.render_widget(pane, area);
 }
 
 fn draw_target_pane(
@@ -120,13 +504,35 @@ fn draw_target_pane(
     area: Rect,
     app: &AppState,
     target: &TargetRuntime,
+    focused: bool,
+    metric_cursor: Option<usize>,
+    hits: &mut HitRegistry,
 ) {
+    let theme = Theme::of(app);
+    // The cursor belongs to the selected target's table only
+    let metric_cursor = metric_cursor.filter(|_| focused);
     // Check for errors
-    let errors: Vec<_> = target
+    let mut errors: Vec<_> = target
         .profiles
         .iter()
-        .filter_map(|p| p.last_error.as_ref().map(|e| (&p.config.name, e)))
+        .filter_map(|p| p.last_error.map(|e| (&p.config.name, e.short_label())))
         .collect();
+    // A certificate about to expire is surfaced even while probes succeed
+    let cert_label = target
+        .cert_days_left(SystemTime::now())
+        .map(|days| format!("cert {days}d"))
+        .unwrap_or_default();
+    if target.cert_expiring_soon(SystemTime::now()) {
+        errors.push((&cert_label, ProbeErrorKind::CertExpiringSoon.short_label()));
+    }
+    // An h2 profile answered over HTTP/1.x would compare h1 against h1
+    errors.extend(
+        target
+            .profiles
+            .iter()
+            .filter(|p| p.last_error.is_none() && p.http_version_fallback())
+            .map(|p| (&p.config.name, "Not HTTP/2")),
+    );
     let has_error = !errors.is_empty();
     let pane_mode = target.pane_mode;
 
@@ -134,15 +540,19 @@ fn draw_target_pane(
         "⏸ PAUSED"
     } else if has_error {
         "⚠ ERROR"
+    } else if target.is_warming() {
+        "◌ WARMING"
     } else {
         "▶ RUNNING"
     };
     let status_color = if target.paused {
-        Color::Yellow
+        theme.warn
     } else if has_error {
-        Color::Red
+        theme.error
+    } else if target.is_warming() {
+        theme.accent
     } else {
-        Color::Green
+        theme.success
     };
 
     let view_mode_str = match target.view_mode {
@@ -150,22 +560,74 @@ fn draw_target_pane(
         ProfileViewMode::Compare => "Compare",
     };
 
-    let title = Line::from(vec![
+    let mut title_spans = vec![
         Span::styled(" ", Style::default()),
         Span::styled(
             truncate_string(target.config.url.as_str(), 40),
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.accent),
         ),
         Span::raw(" │ "),
         Span::styled(status_indicator, Style::default().fg(status_color)),
+    ];
+    if target.config.insecure {
+        title_spans.push(Span::raw(" "));
+        title_spans.push(Span::styled(INSECURE_BADGE, insecure_style(theme)));
+    }
+    // A burst overrides any backoff, so only one of the two is shown
+    if let Some(left) = target.burst_remaining(Instant::now()) {
+        title_spans.push(Span::styled(
+            format!(" BURST {:.0}s", left.as_secs_f64().ceil()),
+            Style::default()
+                .fg(theme.bg)
+                .bg(theme.secondary)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else if let Some(backoff) = target.backoff() {
+        title_spans.push(Span::styled(
+            format!(" backoff {:.0}s", backoff.as_secs_f64()),
+            Style::default().fg(theme.warn),
+        ));
+    }
+    match target.endpoint_view {
+        EndpointView::Profile => {}
+        EndpointView::Overlay => {
+            title_spans.push(Span::styled(" @ each IP", Style::default().fg(theme.warn)))
+        }
+        EndpointView::Ip(ip) => title_spans.push(Span::styled(
+            format!(" @ {ip}"),
+            Style::default().fg(theme.warn),
+        )),
+    }
+    if let Some(window) = target.window_override {
+        title_spans.push(Span::styled(
+            format!(" ⧗ {}", window.label()),
+            Style::default().fg(theme.secondary),
+        ));
+    }
+    title_spans.extend([
         Span::raw(" │ "),
-        Span::styled(view_mode_str, Style::default().fg(Color::Magenta)),
+        Span::styled(view_mode_str, Style::default().fg(theme.secondary)),
         Span::raw(" │ "),
-        Span::styled(pane_mode.label(), Style::default().fg(Color::Yellow)),
-        Span::raw(" "),
     ]);
+    let pane_label_x = area.x + 1 + Line::from(title_spans.clone()).width() as u16;
+    title_spans.push(Span::styled(
+        pane_mode.label(),
+        Style::default().fg(theme.warn),
+    ));
+    title_spans.push(Span::raw(" "));
+    let title = Line::from(title_spans);
+    hits.register(
+        Rect::new(pane_label_x, area.y, pane_mode.label().len() as u16, 1).intersection(area),
+        HitTarget::PaneLabel,
+    );
 
-    let border_color = if has_error { Color::Red } else { Color::Blue };
+    let border_color = if has_error {
+        theme.error
+    } else if focused {
+        theme.info
+    } else {
+        theme.muted
+    };
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
@@ -177,32 +639,12 @@ fn draw_target_pane(
     match pane_mode {
         TargetPaneMode::Split => {
             // Split mode: [Summary+Stats | Metrics | Network Info] on top, Chart below
-            // Calculate dynamic top row height based on available space
-            let available_height = inner.height;
             let error_height: u16 = if has_error { 2 } else { 0 };
-            let min_chart_height: u16 = 8;
-
-            // Metrics table needs: header(1) + metrics(17) + categories(5) + separators(4) + borders(2) = 29
-            // But we can show partial metrics with scrolling
-            let ideal_metrics_height: u16 = 29;
-            let remaining = available_height.saturating_sub(error_height + min_chart_height);
-
-            // Use percentage-based or capped height for top row
-            // Give 40-60% to top row depending on terminal height
-            let top_row_height = if available_height >= 50 {
-                // Large terminal: show more metrics
-                remaining.min(ideal_metrics_height).max(12)
-            } else if available_height >= 35 {
-                // Medium terminal: balanced split
-                (available_height * 45 / 100).max(12)
-            } else {
-                // Small terminal: minimum viable
-                12
-            };
+            let top_row_height = split_top_row_height(inner.height, error_height);
 
             let mut v_constraints = vec![
                 Constraint::Length(top_row_height),
-                Constraint::Min(min_chart_height),
+                Constraint::Min(SPLIT_MIN_CHART_HEIGHT),
             ];
             if has_error {
                 v_constraints.push(Constraint::Length(2));
@@ -223,7 +665,7 @@ fn draw_target_pane(
                 .split(v_sections[0]);
 
             draw_summary_pane(frame, top_row[0], app, target);
-            draw_metrics_table(frame, top_row[1], app, target);
+            draw_metrics_table(frame, top_row[1], app, target, metric_cursor, hits);
             draw_network_info_pane(frame, top_row[2], app, target);
 
             // Bottom: Chart
@@ -231,7 +673,7 @@ fn draw_target_pane(
 
             // Error bar if needed
             if has_error {
-                draw_error_bar(frame, v_sections[2], &errors);
+                draw_error_bar(frame, v_sections[2], &errors, theme);
             }
         }
         TargetPaneMode::Chart => {
@@ -246,7 +688,7 @@ fn draw_target_pane(
 
             draw_chart(frame, sections[0], app, target);
             if has_error {
-                draw_error_bar(frame, sections[1], &errors);
+                draw_error_bar(frame, sections[1], &errors, theme);
             }
         }
         TargetPaneMode::Metrics => {
@@ -259,9 +701,9 @@ fn draw_target_pane(
                 .constraints(constraints)
                 .split(inner);
 
-            draw_metrics_table(frame, sections[0], app, target);
+            draw_metrics_table(frame, sections[0], app, target, metric_cursor, hits);
             if has_error {
-                draw_error_bar(frame, sections[1], &errors);
+                draw_error_bar(frame, sections[1], &errors, theme);
             }
         }
         TargetPaneMode::Summary => {
@@ -276,8 +718,75 @@ fn draw_target_pane(
 
             draw_summary_pane(frame, sections[0], app, target);
             if has_error {
-                draw_error_bar(frame, sections[1], &errors);
+                draw_error_bar(frame, sections[1], &errors, theme);
+            }
+        }
+        TargetPaneMode::Breakdown => {
+            let mut constraints = vec![Constraint::Min(10)];
+            if has_error {
+                constraints.push(Constraint::Length(2));
+            }
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(inner);
+
+            draw_breakdown_pane(frame, sections[0], app, target);
+            if has_error {
+                draw_error_bar(frame, sections[1], &errors, theme);
+            }
+        }
+        TargetPaneMode::Heatmap => {
+            let mut constraints = vec![Constraint::Min(10)];
+            if has_error {
+                constraints.push(Constraint::Length(2));
+            }
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(inner);
+
+            draw_heatmap_pane(frame, sections[0], app, target);
+            if has_error {
+                draw_error_bar(frame, sections[1], &errors, theme);
+            }
+        }
+        TargetPaneMode::Windows => {
+            let mut constraints = vec![Constraint::Min(10)];
+            if has_error {
+                constraints.push(Constraint::Length(2));
+            }
+            let sections = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(inner);
+
+            draw_windows_pane(frame, sections[0], app, target);
+            if has_error {
+                draw_error_bar(frame, sections[1], &errors, theme);
             }
         }
     }
 }
+
+/// Rows the Split pane's chart keeps however small the terminal gets.
+const SPLIT_MIN_CHART_HEIGHT: u16 = 8;
+/// Top row height that shows the whole metrics table: header(1) + metrics(17)
+/// + categories(5) + separators(4) + borders(2).
+const SPLIT_IDEAL_TOP_HEIGHT: u16 = 29;
+const SPLIT_MIN_TOP_HEIGHT: u16 = 12;
+
+/// Height of the Split pane's top row for `available` rows, recomputed from
+/// the current size on every frame. It grows with the terminal (40-60% on
+/// medium ones) but never takes the chart's minimum or the error bar's rows.
+fn split_top_row_height(available: u16, error_height: u16) -> u16 {
+    let remaining = available.saturating_sub(error_height + SPLIT_MIN_CHART_HEIGHT);
+    let wanted = if available >= 50 {
+        SPLIT_IDEAL_TOP_HEIGHT
+    } else if available >= 35 {
+        available * 45 / 100
+    } else {
+        SPLIT_MIN_TOP_HEIGHT
+    };
+    wanted.max(SPLIT_MIN_TOP_HEIGHT).min(remaining)
+}
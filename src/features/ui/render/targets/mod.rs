@@ -1,5 +1,9 @@
 mod chart;
 mod list;
+mod overview;
 mod panes;
 
+pub(in crate::features::ui) use chart::{
+    chart_sample_positions, chart_timestamp_to_x, chart_x_to_timestamp,
+};
 pub(in crate::features::ui) use list::draw_main;
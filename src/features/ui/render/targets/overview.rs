@@ -0,0 +1,107 @@
+use crate::app::{AppState, GroupSummary, OverviewColumn, OverviewRow};
+use crate::metrics::MetricKind;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use std::collections::BTreeMap;
+
+use super::super::super::theme::Theme;
+use super::super::format::{
+    format_goodput, format_latency, format_metric_value, style_for_latency, truncate_string,
+};
+
+/// Every visible target as one row, sorted by the overview's sort column.
+pub(super) fn draw_overview(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    groups: &BTreeMap<String, GroupSummary>,
+) {
+    let theme = Theme::of(app);
+    let Some(sort) = app.overview else {
+        return;
+    };
+    let arrow = if sort.descending { "▼" } else { "▲" };
+    let block = Block::default()
+        .title(format!(
+            " Overview [{}] sorted by {} {arrow} ",
+            app.window.label(),
+            sort.column.label()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.info));
+
+    let rows = app.overview_rows();
+    if rows.is_empty() {
+        let message = Paragraph::new(Line::styled(
+            " No targets to show",
+            Style::default().fg(theme.muted),
+        ))
+        .block(block);
+        frame.render_widget(message, area);
+        return;
+    }
+
+    let header = Row::new(OverviewColumn::ALL.map(|column| {
+        let label = if column == sort.column {
+            format!("{} {arrow}", column.label())
+        } else {
+            column.label().to_string()
+        };
+        Cell::from(label)
+    }))
+    .style(
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    // Groups follow the targets, set apart by a blank row
+    let group_rows = app.overview_group_rows(groups);
+    let separator = (!group_rows.is_empty()).then(|| Row::new(vec![Cell::from("")]));
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|row| overview_row(row, theme))
+        .chain(separator)
+        .chain(group_rows.iter().map(|row| overview_row(row, theme)))
+        .collect();
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme.selection.add_modifier(Modifier::BOLD));
+    let mut state = TableState::default()
+        .with_selected(rows.iter().position(|row| row.index == app.selected_target));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn overview_row<'a>(row: &OverviewRow, theme: &Theme) -> Row<'a> {
+    let latency_cell = |value: Option<f64>| match value {
+        Some(value) => Cell::from(format_latency(value)).style(style_for_latency(value, theme)),
+        None => Cell::from("—"),
+    };
+    let name_style = if row.is_group {
+        Style::default().fg(theme.secondary)
+    } else {
+        Style::default()
+    };
+    Row::new(vec![
+        Cell::from(truncate_string(&row.name, 30)).style(name_style),
+        latency_cell(row.p50),
+        latency_cell(row.p99),
+        Cell::from(format_metric_value(MetricKind::ProbeLossRate, row.loss)),
+        Cell::from(
+            row.goodput
+                .map(format_goodput)
+                .unwrap_or_else(|| "—".into()),
+        ),
+    ])
+}
@@ -0,0 +1,183 @@
+use crate::app::{AppState, ProfileViewMode, TargetRuntime};
+use crate::config::ProbeMethod;
+use crate::metrics::{PHASE_METRICS, PhaseBreakdown};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use std::time::SystemTime;
+
+use super::super::super::super::theme::Theme;
+use super::super::super::format::{format_latency, truncate_string};
+
+/// Width reserved left of each bar for the probe age.
+const AGE_WIDTH: u16 = 6;
+/// Width reserved right of each bar for the total.
+const TOTAL_WIDTH: u16 = 9;
+
+pub(in crate::features::ui) fn draw_breakdown_pane(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    target: &TargetRuntime,
+) {
+    let theme = Theme::of(app);
+    let profiles: Vec<_> = match target.view_mode {
+        ProfileViewMode::Single => target
+            .profiles
+            .get(target.selected_profile)
+            .into_iter()
+            .collect(),
+        ProfileViewMode::Compare => target.profiles.iter().collect(),
+    };
+
+    // Phases have their own palette, so a segment is never mistaken for a profile
+    let mut legend_spans = Vec::new();
+    for (idx, metric) in PHASE_METRICS.iter().enumerate() {
+        if idx > 0 {
+            legend_spans.push(Span::raw("  "));
+        }
+        legend_spans.push(Span::styled(
+            "■ ",
+            Style::default().fg(theme.phase_color(idx)),
+        ));
+        legend_spans.push(Span::styled(
+            metric.label(),
+            Style::default().fg(theme.phase_color(idx)),
+        ));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            " Breakdown [{}] ",
+            app.target_window(target).label()
+        ))
+        .title_bottom(Line::from(legend_spans).alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.muted));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if profiles.is_empty() || inner.height == 0 {
+        return;
+    }
+
+    // Each profile gets a heading line plus an equal share of the remaining rows
+    let bars_per_profile = (inner.height as usize / profiles.len()).saturating_sub(1);
+    let groups: Vec<Vec<PhaseBreakdown>> = profiles
+        .iter()
+        .map(|profile| {
+            let key = target.view_key(profile);
+            app.metrics
+                .recent_phases(key, app.target_window(target), bars_per_profile)
+        })
+        .collect();
+    let slowest = groups
+        .iter()
+        .flatten()
+        .map(PhaseBreakdown::total_ms)
+        .fold(0.0, f64::max);
+    let bar_width = inner.width.saturating_sub(AGE_WIDTH + TOTAL_WIDTH);
+    let now = SystemTime::now();
+
+    let mut lines = Vec::new();
+    for (idx, (profile, group)) in profiles.iter().zip(&groups).enumerate() {
+        let heading_style = if profiles.len() > 1 {
+            Style::default().fg(theme.profile_color(idx, &profile.config))
+        } else {
+            Style::default().fg(theme.accent)
+        };
+        lines.push(Line::styled(
+            truncate_string(&profile.config.name, inner.width as usize),
+            heading_style.add_modifier(Modifier::BOLD),
+        ));
+        if group.is_empty() {
+            let message = if profile.config.method == ProbeMethod::Ping {
+                "  Ping probes have no HTTP phases"
+            } else {
+                "  No successful probes in this window"
+            };
+            lines.push(Line::styled(message, Style::default().fg(theme.muted)));
+            continue;
+        }
+        for phases in group.iter().rev() {
+            let age = now
+                .duration_since(phases.ts)
+                .map(|age| age.as_secs())
+                .unwrap_or(0);
+            let mut spans = vec![Span::styled(
+                format!("{:>5} ", format!("-{age}s")),
+                Style::default().fg(theme.muted),
+            )];
+            for (phase_idx, width) in segment_widths(phases, slowest, bar_width)
+                .into_iter()
+                .enumerate()
+            {
+                if width > 0 {
+                    spans.push(Span::styled(
+                        "█".repeat(width as usize),
+                        Style::default().fg(theme.phase_color(phase_idx)),
+                    ));
+                }
+            }
+            spans.push(Span::raw(format!(" {}", format_latency(phases.total_ms()))));
+            lines.push(Line::from(spans));
+        }
+    }
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Splits a bar scaled to `slowest` into per-phase cell widths. Segment ends
+/// are rounded cumulatively so the bar length tracks the total exactly.
+fn segment_widths(phases: &PhaseBreakdown, slowest: f64, bar_width: u16) -> [u16; 5] {
+    let mut widths = [0; 5];
+    if slowest <= 0.0 {
+        return widths;
+    }
+    let scale = bar_width as f64 / slowest;
+    let mut elapsed = 0.0;
+    let mut drawn = 0;
+    for (width, phase) in widths.iter_mut().zip(phases.phases_ms) {
+        elapsed += phase.max(0.0);
+        let end = ((elapsed * scale).round() as u16).min(bar_width);
+        *width = end.saturating_sub(drawn);
+        drawn = end;
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phases(phases_ms: [f64; 5]) -> PhaseBreakdown {
+        PhaseBreakdown {
+            ts: SystemTime::now(),
+            phases_ms,
+        }
+    }
+
+    #[test]
+    fn segments_fill_the_bar_in_proportion_to_each_phase() {
+        let probe = phases([10.0, 20.0, 30.0, 0.0, 40.0]);
+        assert_eq!(segment_widths(&probe, 100.0, 50), [5, 10, 15, 0, 20]);
+        // A probe half as slow as the slowest draws half a bar
+        assert_eq!(segment_widths(&probe, 200.0, 50).iter().sum::<u16>(), 25);
+    }
+
+    #[test]
+    fn rounding_keeps_the_bar_as_long_as_the_total() {
+        let probe = phases([1.0, 1.0, 1.0, 0.0, 0.0]);
+        assert_eq!(segment_widths(&probe, 3.0, 10), [3, 4, 3, 0, 0]);
+        // Negative phases count as zero rather than eating into the next one
+        let probe = phases([-5.0, 10.0, 0.0, 0.0, 10.0]);
+        assert_eq!(segment_widths(&probe, 20.0, 10), [0, 5, 0, 0, 5]);
+    }
+
+    #[test]
+    fn nothing_is_drawn_without_a_slowest_probe() {
+        let probe = phases([1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(segment_widths(&probe, 0.0, 40), [0; 5]);
+    }
+}
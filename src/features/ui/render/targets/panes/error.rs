@@ -1,27 +1,29 @@
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 
+use super::super::super::super::theme::Theme;
 use super::super::super::format::truncate_string;
 
 pub(in crate::features::ui) fn draw_error_bar(
     frame: &mut ratatui::Frame,
     area: Rect,
-    errors: &[(&String, &crate::probe::ProbeErrorKind)],
+    errors: &[(&String, &str)],
+    theme: &Theme,
 ) {
     let error_msg: String = errors
         .iter()
-        .map(|(name, err)| format!("{}: {}", name, err.short_label()))
+        .map(|(name, label)| format!("{name}: {label}"))
         .collect::<Vec<_>>()
         .join(" | ");
     let error_line = Line::from(vec![
-        Span::styled(" ⚠ ", Style::default().fg(Color::Red)),
+        Span::styled(" ⚠ ", Style::default().fg(theme.error)),
         Span::styled(
             truncate_string(&error_msg, 60),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme.error),
         ),
     ]);
-    let error_para = Paragraph::new(error_line).style(Style::default().bg(Color::DarkGray));
+    let error_para = Paragraph::new(error_line).style(Style::default().bg(theme.muted));
     frame.render_widget(error_para, area);
 }
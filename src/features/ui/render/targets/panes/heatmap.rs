@@ -0,0 +1,128 @@
+use crate::app::{AppState, TargetRuntime};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use std::time::SystemTime;
+
+use super::super::super::super::theme::Theme;
+use super::super::super::format::{format_metric_value, format_x_axis_labels, truncate_string};
+
+/// Width reserved left of the grid for the bucket boundaries.
+const LABEL_WIDTH: u16 = 8;
+/// Glyph and colour per density step, sparsest first.
+fn ramp(theme: &Theme) -> [(&'static str, Color); 5] {
+    [
+        ("░", theme.info),
+        ("▒", theme.accent),
+        ("▓", theme.success),
+        ("█", theme.warn),
+        ("█", theme.error),
+    ]
+}
+
+pub(in crate::features::ui) fn draw_heatmap_pane(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    target: &TargetRuntime,
+) {
+    let theme = Theme::of(app);
+    let ramp = ramp(theme);
+    let metric = target.selected_metric;
+    let profile = target.profiles.get(target.selected_profile);
+    let profile_name = profile.map_or("", |profile| profile.config.name.as_str());
+
+    let mut legend_spans = vec![Span::styled("sparse ", Style::default().fg(theme.muted))];
+    for (glyph, color) in ramp {
+        legend_spans.push(Span::styled(glyph, Style::default().fg(color)));
+    }
+    legend_spans.push(Span::styled(" dense", Style::default().fg(theme.muted)));
+
+    let block = Block::default()
+        .title(format!(
+            " Heatmap [{}] {} ─ {} ",
+            app.target_window(target).label(),
+            metric.label(),
+            truncate_string(profile_name, 24)
+        ))
+        .title_bottom(Line::from(legend_spans).alignment(Alignment::Center))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.muted));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // One row is left for the time axis
+    let cols = inner.width.saturating_sub(LABEL_WIDTH) as usize;
+    let rows = inner.height.saturating_sub(1) as usize;
+    let matrix = profile.and_then(|profile| {
+        let key = target.view_key(profile);
+        app.metrics
+            .histogram_matrix(key, app.target_window(target), metric, cols, rows)
+    });
+    let Some(matrix) = matrix else {
+        let message = Paragraph::new(Line::styled(
+            "  No samples in this window",
+            Style::default()
+                .fg(theme.muted)
+                .add_modifier(Modifier::ITALIC),
+        ));
+        frame.render_widget(message, inner);
+        return;
+    };
+
+    let max_count = matrix.max_count();
+    let label_style = Style::default().fg(theme.muted);
+    let mut lines: Vec<Line> = (0..rows)
+        .map(|line| {
+            // Highest bucket on top; every other row is labelled with its upper bound
+            let row = rows - 1 - line;
+            let label = if line % 2 == 0 {
+                format_metric_value(metric, Some(matrix.bounds[row + 1]))
+            } else {
+                String::new()
+            };
+            let mut spans = vec![Span::styled(
+                format!("{label:>width$}│", width = LABEL_WIDTH as usize - 1),
+                label_style,
+            )];
+            spans.extend(matrix.counts.iter().map(|column| {
+                let count = column[row];
+                if count == 0 {
+                    return Span::raw(" ");
+                }
+                let step = (f64::from(count) / f64::from(max_count) * ramp.len() as f64).ceil();
+                let (glyph, color) = ramp[(step as usize).clamp(1, ramp.len()) - 1];
+                Span::styled(glyph, Style::default().fg(color))
+            }));
+            Line::from(spans)
+        })
+        .collect();
+
+    let labels = format_x_axis_labels(
+        app.target_window(target).duration(),
+        SystemTime::now(),
+        app.global.absolute_time_axis,
+        theme,
+    );
+    let mut axis = vec![' '; cols];
+    for (index, label) in labels.iter().enumerate() {
+        let text: Vec<char> = label.content.chars().collect();
+        let free = cols.saturating_sub(text.len());
+        let start = free * index / labels.len().saturating_sub(1).max(1);
+        for (offset, ch) in text.into_iter().enumerate() {
+            if let Some(slot) = axis.get_mut(start + offset) {
+                *slot = ch;
+            }
+        }
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:>width$}└", "", width = LABEL_WIDTH as usize - 1),
+            label_style,
+        ),
+        Span::styled(axis.into_iter().collect::<String>(), label_style),
+    ]));
+
+    frame.render_widget(Paragraph::new(lines), inner);
+}
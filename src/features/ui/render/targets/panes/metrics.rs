@@ -1,17 +1,27 @@
-use crate::app::{AppState, MetricsCategory, ProfileViewMode, TargetRuntime};
+use crate::app::{AppState, FreshnessLevel, MetricsCategory, ProfileViewMode, TargetRuntime};
+use crate::metrics::{DeltaStats, DeltaTrend, MetricKind, StatDelta};
+use crate::probe::Phase;
 use ratatui::layout::{Alignment, Constraint, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Row, Table};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
 
-use super::super::super::format::{color_for_index, format_stat_triplet, metrics_for_category};
+use super::super::super::super::state::{HitRegistry, HitTarget};
+use super::super::super::super::theme::Theme;
+use super::super::super::format::{
+    format_count, format_local_timestamp, format_metric_value, format_stat_triplet,
+    metrics_for_category, tcp_info_note,
+};
 
 pub(in crate::features::ui) fn draw_metrics_table(
     frame: &mut ratatui::Frame,
     area: Rect,
     app: &AppState,
     target: &TargetRuntime,
+    metric_cursor: Option<usize>,
+    hits: &mut HitRegistry,
 ) {
+    let theme = Theme::of(app);
     let profiles: Vec<_> = match target.view_mode {
         ProfileViewMode::Single => target
             .profiles
@@ -20,6 +30,26 @@ pub(in crate::features::ui) fn draw_metrics_table(
             .collect(),
         ProfileViewMode::Compare => target.profiles.iter().collect(),
     };
+    let warm_ups: Vec<_> = profiles
+        .iter()
+        .map(|profile| target.warm_up(profile))
+        .collect();
+    // A pair of profiles gets a column of the second against the first
+    let compared = profiles.len() == 2 && target.view_mode == ProfileViewMode::Compare;
+    let aggregates: Vec<_> = profiles
+        .iter()
+        .map(|profile| app.target_aggregate(target, profile))
+        .collect();
+
+    // Current window of the baseline's own profile, whichever profiles are shown
+    let baseline = target.current_baseline().map(|baseline| {
+        let current = target
+            .profiles
+            .iter()
+            .find(|profile| profile.config.id == baseline.profile_id)
+            .map(|profile| app.target_aggregate(target, profile));
+        (baseline, current)
+    });
 
     // Build category tabs
     let tab_spans: Vec<Span> = MetricsCategory::ALL
@@ -28,49 +58,83 @@ pub(in crate::features::ui) fn draw_metrics_table(
         .flat_map(|(i, cat)| {
             let is_selected = *cat == target.metrics_category;
             let style = if is_selected {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(theme.muted)
             };
             let mut spans = vec![Span::styled(format!(" {} ", cat.label()), style)];
             if i < MetricsCategory::ALL.len() - 1 {
-                spans.push(Span::styled("│", Style::default().fg(Color::DarkGray)));
+                spans.push(Span::styled("│", Style::default().fg(theme.muted)));
             }
             spans
         })
         .collect();
     let tabs_line = Line::from(tab_spans);
 
+    // Tabs are centred on the bottom border; record each label for mouse clicks
+    let border_inner_width = area.width.saturating_sub(2);
+    let mut tab_x = area.x + 1 + border_inner_width.saturating_sub(tabs_line.width() as u16) / 2;
+    let tab_y = area.y + area.height.saturating_sub(1);
+    for cat in MetricsCategory::ALL {
+        let width = cat.label().len() as u16 + 2;
+        hits.register(
+            Rect::new(tab_x, tab_y, width, 1).intersection(area),
+            HitTarget::CategoryTab(cat),
+        );
+        tab_x += width + 1;
+    }
+
     // Build header row
     let mut header_cells: Vec<Line> = vec![Line::from(Span::styled(
         "Metric",
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     ))];
     for (idx, profile) in profiles.iter().enumerate() {
-        let color = color_for_index(idx);
+        let color = theme.profile_color(idx, &profile.config);
         header_cells.push(Line::from(vec![
             Span::styled("■ ", Style::default().fg(color)),
             Span::styled(profile.config.name.clone(), Style::default().fg(color)),
         ]));
     }
+    if compared {
+        header_cells.push(Line::from(Span::styled(
+            "Δ P50",
+            Style::default().fg(theme.accent),
+        )));
+    }
+    if baseline.is_some() {
+        header_cells.push(Line::from(Span::styled(
+            "Δ P50/P99",
+            Style::default().fg(theme.secondary),
+        )));
+    }
     let header = Row::new(header_cells).style(Style::default().add_modifier(Modifier::BOLD));
 
     // Build metric rows for selected category
     let metrics = metrics_for_category(target.metrics_category);
-    let rows: Vec<Row> = metrics
+    // A line saying why beats a table of dashes that never fill in
+    let tcp_note = (target.metrics_category == MetricsCategory::Tcp
+        && aggregates.iter().all(|aggregate| {
+            metrics.iter().all(|metric| {
+                aggregate
+                    .by_metric
+                    .get(metric)
+                    .is_none_or(|stats| stats.mean.is_none())
+            })
+        }))
+    .then(|| profiles.first().map(|profile| tcp_info_note(app, profile)))
+    .flatten();
+    let metrics = if tcp_note.is_some() { &[][..] } else { metrics };
+    let mut rows: Vec<Row> = metrics
         .iter()
         .map(|&metric| {
-            let is_selected = app.selected_metrics.contains(&metric);
+            let is_selected = target.selected_metrics.contains(&metric);
             let metric_style = if is_selected {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.fg)
             };
 
             let unit = metric.unit();
@@ -82,25 +146,166 @@ pub(in crate::features::ui) fn draw_metrics_table(
 
             let mut cells: Vec<Cell> = Vec::new();
             cells.push(Cell::from(Span::styled(label_with_unit, metric_style)));
-            for profile in &profiles {
-                let aggregate = app.target_aggregate(target, profile);
+            for (aggregate, warm_up) in aggregates.iter().zip(&warm_ups) {
                 let stats = aggregate.by_metric.get(&metric);
-                cells.push(Cell::from(format_stat_triplet(metric, stats)));
+                cells.push(Cell::from(format_stat_triplet(
+                    metric,
+                    stats,
+                    app.global.stats_preset,
+                    *warm_up,
+                )));
+            }
+            if compared {
+                let pair = (
+                    aggregates[0].by_metric.get(&metric),
+                    aggregates[1].by_metric.get(&metric),
+                );
+                // Warm-up percentiles would make a noisy delta
+                let delta = match pair {
+                    (Some(first), Some(second)) if warm_ups.iter().all(Option::is_none) => {
+                        first.delta(second)
+                    }
+                    _ => DeltaStats::default(),
+                };
+                cells.push(Cell::from(compare_line(metric, delta.p50, theme)));
+            }
+            if let Some((baseline, current)) = &baseline {
+                let delta = match (
+                    current
+                        .as_ref()
+                        .and_then(|current| current.by_metric.get(&metric)),
+                    baseline.by_metric.get(&metric),
+                ) {
+                    (Some(current), Some(baseline)) => DeltaStats::between(current, baseline),
+                    _ => DeltaStats::default(),
+                };
+                cells.push(Cell::from(Line::from(vec![
+                    delta_span(metric, delta.p50, theme),
+                    Span::raw("/"),
+                    delta_span(metric, delta.p99, theme),
+                ])));
             }
             Row::new(cells)
         })
         .collect();
+    // Where failures stopped, under the rates; the metric cursor skips them
+    if target.metrics_category == MetricsCategory::Reliability {
+        for phase in Phase::ALL {
+            let counts: Vec<u64> = aggregates
+                .iter()
+                .map(|aggregate| aggregate.phase_failures.get(&phase).copied().unwrap_or(0))
+                .collect();
+            if counts.iter().all(|count| *count == 0) {
+                continue;
+            }
+            let mut cells = vec![Cell::from(format!("failed@{}", phase.label()))];
+            cells.extend(counts.iter().map(|count| {
+                let color = if *count == 0 {
+                    theme.muted
+                } else {
+                    theme.error
+                };
+                Cell::from(Span::styled(
+                    format_count(*count),
+                    Style::default().fg(color),
+                ))
+            }));
+            rows.push(Row::new(cells));
+        }
+    }
 
     let widths: Vec<Constraint> = std::iter::once(Constraint::Length(18))
         .chain(profiles.iter().map(|_| Constraint::Length(18)))
+        .chain(compared.then_some(Constraint::Length(16)))
+        .chain(baseline.iter().map(|_| Constraint::Length(14)))
         .collect();
 
-    let table = Table::new(rows, widths).header(header).block(
-        Block::default()
-            .title(" Metrics (P50/P99/Mean) ")
-            .title_bottom(tabs_line.alignment(Alignment::Center))
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
+    let failures = if app.global.include_failures_in_latency {
+        " • incl. failures"
+    } else {
+        ""
+    };
+    let mut title = format!(" Metrics ({}{failures}) ", app.global.stats_preset.label());
+    // Numbers from data about to age out of the window look current otherwise
+    if app
+        .target_freshness(target)
+        .is_some_and(|freshness| freshness.level != FreshnessLevel::Fresh)
+    {
+        title.push_str("(stale) ");
+    }
+    if let Some((baseline, _)) = &baseline {
+        title.push_str(&format!(
+            "─ vs {} @ {} ",
+            baseline.name,
+            format_local_timestamp(baseline.captured_at)
+        ));
+    }
+
+    // The cursor row is highlighted while the table has focus
+    let border_color = if metric_cursor.is_some() {
+        theme.warn
+    } else {
+        theme.muted
+    };
+    let table = Table::new(rows, widths)
+        .header(header)
+        .row_highlight_style(theme.selection)
+        .block(
+            Block::default()
+                .title(title)
+                .title_bottom(tabs_line.alignment(Alignment::Center))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color)),
+        );
+    let mut state = TableState::default().with_selected(metric_cursor);
+    frame.render_stateful_widget(table, area, &mut state);
+    if let Some(note) = tcp_note {
+        let line = Rect::new(area.x + 1, area.y + 2, area.width.saturating_sub(2), 1);
+        frame.render_widget(
+            Paragraph::new(Line::styled(note, Style::default().fg(theme.muted))),
+            line.intersection(area),
+        );
+    }
+}
+
+/// Difference of the second compared profile from the first, plus the
+/// percentage for latencies; green when the second does better.
+fn compare_line(metric: MetricKind, delta: Option<StatDelta>, theme: &Theme) -> Line<'static> {
+    let Some(delta) = delta else {
+        return Line::styled("—", Style::default().fg(theme.muted));
+    };
+    let color = match delta.trend(metric) {
+        DeltaTrend::Better => theme.success,
+        DeltaTrend::Worse => theme.error,
+        DeltaTrend::Neutral => theme.subtle,
+    };
+    let difference = delta.difference();
+    let sign = if difference < 0.0 { "-" } else { "+" };
+    let mut text = format!(
+        "{sign}{}",
+        format_metric_value(metric, Some(difference.abs()))
     );
-    frame.render_widget(table, area);
+    if metric.is_latency_metric()
+        && let Some(pct) = delta.change_pct()
+    {
+        text.push_str(&format!(" ({pct:+.0}%)"));
+    }
+    Line::styled(text, Style::default().fg(color))
+}
+
+/// Percentage change of one statistic, green when it improved and red when it
+/// regressed.
+fn delta_span(metric: MetricKind, delta: Option<StatDelta>, theme: &Theme) -> Span<'static> {
+    let Some(delta) = delta else {
+        return Span::styled("—", Style::default().fg(theme.muted));
+    };
+    let Some(pct) = delta.change_pct() else {
+        return Span::styled("—", Style::default().fg(theme.muted));
+    };
+    let color = match delta.trend(metric) {
+        DeltaTrend::Better => theme.success,
+        DeltaTrend::Worse => theme.error,
+        DeltaTrend::Neutral => theme.subtle,
+    };
+    Span::styled(format!("{pct:+.0}%"), Style::default().fg(color))
 }
@@ -1,9 +1,16 @@
+mod breakdown;
 mod error;
+mod heatmap;
 mod metrics;
 mod network;
 mod summary;
+mod waterfall;
+mod windows;
 
+pub(super) use breakdown::draw_breakdown_pane;
 pub(super) use error::draw_error_bar;
+pub(super) use heatmap::draw_heatmap_pane;
 pub(super) use metrics::draw_metrics_table;
 pub(super) use network::draw_network_info_pane;
 pub(super) use summary::draw_summary_pane;
+pub(super) use windows::draw_windows_pane;
@@ -1,19 +1,27 @@
-use crate::app::{AppState, TargetRuntime};
+use crate::app::{AppState, CERT_CRITICAL_DAYS, CERT_WARN_DAYS, TargetRuntime};
+use crate::config::ProbeMethod;
 use crate::metrics::MetricKind;
+use crate::probe::PingMode;
+use crate::probe_engine::detect_h2_support;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 
-use super::super::super::format::format_latency;
+use super::super::super::super::theme::Theme;
+use super::super::super::format::{
+    format_age, format_bytes, format_latency, tcp_info_note, truncate_string,
+};
+use std::time::SystemTime;
 
-/// Combined network info pane showing Profile, Connection, and TCP stats
+/// Combined network info pane showing Profile, Connection, TCP, and DNS stats
 pub(in crate::features::ui) fn draw_network_info_pane(
     frame: &mut ratatui::Frame,
     area: Rect,
     app: &AppState,
     target: &TargetRuntime,
 ) {
+    let theme = Theme::of(app);
     let profile = match target.profiles.get(target.selected_profile) {
         Some(p) => p,
         None => return,
@@ -26,97 +34,387 @@ pub(in crate::features::ui) fn draw_network_info_pane(
     lines.push(Line::styled(
         "─ Profile ─",
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     ));
 
-    let http_tls = format!("{:?}/{:?}", profile.config.http, profile.config.tls);
+    let is_ping = profile.config.method == ProbeMethod::Ping;
+    let http_tls = if is_ping {
+        "Ping".to_string()
+    } else if target.config.url.scheme() != "https" {
+        // The profile's TLS version never applies to a plain-http URL
+        format!("{}/http (cleartext)", profile.config.http)
+    } else {
+        format!("{:?}/{:?}", profile.config.http, profile.config.tls)
+    };
     lines.push(Line::from(vec![
-        Span::styled(" Proto ", Style::default().fg(Color::DarkGray)),
-        Span::styled(http_tls, Style::default().fg(Color::Yellow)),
+        Span::styled(" Proto ", Style::default().fg(theme.muted)),
+        Span::styled(http_tls, Style::default().fg(theme.warn)),
     ]));
 
-    let reuse = format!("{:?}", profile.config.conn_reuse);
-    lines.push(Line::from(vec![
-        Span::styled(" Reuse ", Style::default().fg(Color::DarkGray)),
-        Span::styled(reuse, Style::default().fg(Color::Cyan)),
-    ]));
+    if !is_ping {
+        let reuse = format!("{:?}", profile.config.conn_reuse);
+        let mut spans = vec![
+            Span::styled(" Reuse ", Style::default().fg(theme.muted)),
+            Span::styled(reuse, Style::default().fg(theme.accent)),
+        ];
+        // The last probe opened the connection the next ones reuse
+        if profile
+            .last_sample
+            .as_ref()
+            .is_some_and(|sample| sample.priming)
+        {
+            spans.push(Span::styled(" priming", Style::default().fg(theme.warn)));
+        } else if let Some(rate) = aggregate
+            .by_metric
+            .get(&MetricKind::ConnReuseRate)
+            .and_then(|stats| stats.mean)
+        {
+            // What the server let us reuse, next to what was asked for
+            let color = if rate >= 0.9 {
+                theme.success
+            } else if rate >= 0.5 {
+                theme.warn
+            } else {
+                theme.error
+            };
+            spans.push(Span::styled(
+                format!(" {:.0}%", rate * 100.0),
+                Style::default().fg(color),
+            ));
+        }
+        lines.push(Line::from(spans));
+
+        if let Some(speed) = profile.config.max_recv_speed {
+            lines.push(Line::from(vec![
+                Span::styled(" Speed ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    format!("≤ {}/s", format_bytes(speed as f64)),
+                    Style::default().fg(theme.accent),
+                ),
+            ]));
+        }
+        // The last probe queued behind other downloads, so it ran late
+        if let Some(sample) = &profile.last_sample
+            && let Some(wait) = sample.limiter_wait
+        {
+            let mut text = format!("{} wait", format_latency(wait.as_secs_f64() * 1000.0));
+            if sample.deferred_slots > 0 {
+                text.push_str(&format!(", {} late", sample.deferred_slots));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(" Queue ", Style::default().fg(theme.muted)),
+                Span::styled(text, Style::default().fg(theme.warn)),
+            ]));
+        }
+    }
+
+    if profile.health.stalled {
+        lines.push(Line::from(vec![
+            Span::styled(" Workr ", Style::default().fg(theme.muted)),
+            Span::styled(
+                "◷ STALLED",
+                Style::default()
+                    .fg(theme.bg)
+                    .bg(theme.warn)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
 
     // Section: Connection
     lines.push(Line::styled(
         "─ Connection ─",
         Style::default()
-            .fg(Color::Magenta)
+            .fg(theme.secondary)
             .add_modifier(Modifier::BOLD),
     ));
 
+    // Overridden transports connect somewhere other than the URL host
+    if let Some(destination) = target.config.connect_destination() {
+        lines.push(Line::from(vec![
+            Span::styled(" Via   ", Style::default().fg(theme.muted)),
+            Span::styled(
+                truncate_string(&destination, 20),
+                Style::default().fg(theme.warn),
+            ),
+        ]));
+    }
+
     if let Some(last_sample) = &profile.last_sample {
         if let Some(remote) = &last_sample.remote {
             lines.push(Line::from(vec![
-                Span::styled(" Addr  ", Style::default().fg(Color::DarkGray)),
+                Span::styled(" Addr  ", Style::default().fg(theme.muted)),
                 Span::raw(format!("{}:{}", remote.ip(), remote.port())),
             ]));
         }
-        let alpn = last_sample.negotiated.alpn.as_deref().unwrap_or("—");
-        let tls_ver = last_sample.negotiated.tls_version.as_deref().unwrap_or("—");
+        if let Some(mode) = last_sample.ping {
+            // TCP connect means ICMP sockets were not permitted on this host
+            let color = match mode {
+                PingMode::Icmp => theme.success,
+                PingMode::TcpConnect => theme.warn,
+            };
+            lines.push(Line::from(vec![
+                Span::styled(" Ping  ", Style::default().fg(theme.muted)),
+                Span::styled(mode.label(), Style::default().fg(color)),
+            ]));
+        } else {
+            let negotiated = &last_sample.negotiated;
+            let alpn = negotiated.alpn.as_deref().unwrap_or("—");
+            let tls_ver = negotiated.tls_version.as_deref().unwrap_or("—");
+            // A reported version other than the pinned one means min/max pinning failed
+            let tls_style = if !negotiated.tls_version_configured
+                && negotiated
+                    .tls_version
+                    .as_deref()
+                    .is_some_and(|version| version != profile.config.tls.protocol_name())
+            {
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let alpn_style = if profile.http_version_fallback() {
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.success)
+            };
+            let mut spans = vec![
+                Span::styled(" ALPN  ", Style::default().fg(theme.muted)),
+                Span::styled(alpn, alpn_style),
+                Span::styled(" TLS ", Style::default().fg(theme.muted)),
+                Span::styled(tls_ver, tls_style),
+            ];
+            if negotiated.tls_version_configured {
+                spans.push(Span::styled(" (cfg)", Style::default().fg(theme.muted)));
+            }
+            lines.push(Line::from(spans));
+            if profile.http_version_fallback() {
+                // Tell a libcurl without nghttp2 apart from a server without h2
+                let reason = if detect_h2_support() {
+                    "server gave no h2"
+                } else {
+                    "libcurl lacks HTTP/2"
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(" ⚠ ", Style::default().fg(theme.error)),
+                    Span::styled(reason, Style::default().fg(theme.error)),
+                ]));
+            }
+            if let Some(cipher) = &negotiated.cipher {
+                lines.push(Line::from(vec![
+                    Span::styled(" Ciph  ", Style::default().fg(theme.muted)),
+                    Span::raw(truncate_string(cipher, 19)),
+                ]));
+            }
+        }
+    } else {
+        lines.push(Line::styled(" (no data)", Style::default().fg(theme.muted)));
+    }
+
+    if target.config.url.scheme() == "https" {
+        // Backends without certinfo support never report one
+        let (expiry, expiry_style) = match target.cert_days_left(SystemTime::now()) {
+            Some(days) if days < 0 => (
+                format!("expired {}d ago", -days),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Some(days) => {
+                let color = if days < CERT_CRITICAL_DAYS {
+                    theme.error
+                } else if days < CERT_WARN_DAYS {
+                    theme.warn
+                } else {
+                    theme.success
+                };
+                (format!("expires in {days}d"), Style::default().fg(color))
+            }
+            None => ("—".to_string(), Style::default().fg(theme.muted)),
+        };
         lines.push(Line::from(vec![
-            Span::styled(" ALPN  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(alpn, Style::default().fg(Color::Green)),
-            Span::styled(" TLS ", Style::default().fg(Color::DarkGray)),
-            Span::raw(tls_ver),
+            Span::styled(" Cert  ", Style::default().fg(theme.muted)),
+            Span::styled(expiry, expiry_style),
         ]));
-    } else {
+        if let Some(issuer) = target.cert.as_ref().and_then(|cert| cert.issuer.as_deref()) {
+            lines.push(Line::from(vec![
+                Span::styled(" Issr  ", Style::default().fg(theme.muted)),
+                Span::raw(truncate_string(issuer_common_name(issuer), 20)),
+            ]));
+        }
+    }
+
+    // Section: Headers (allowlisted response headers of the latest probe)
+    if !is_ping && !profile.config.capture_headers.is_empty() {
         lines.push(Line::styled(
-            " (no data)",
-            Style::default().fg(Color::DarkGray),
+            "─ Headers ─",
+            Style::default()
+                .fg(theme.success)
+                .add_modifier(Modifier::BOLD),
         ));
+        let headers = profile
+            .last_sample
+            .as_ref()
+            .map(|sample| sample.response_headers.as_slice())
+            .unwrap_or_default();
+        if headers.is_empty() {
+            lines.push(Line::styled(
+                " (none captured)",
+                Style::default().fg(theme.muted),
+            ));
+        }
+        for (name, value) in headers {
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {name}: "), Style::default().fg(theme.muted)),
+                Span::raw(truncate_string(value, 24)),
+            ]));
+        }
+        if let Some(rate) = aggregate
+            .by_metric
+            .get(&MetricKind::CacheHitRate)
+            .and_then(|stats| stats.mean)
+        {
+            let color = if rate >= 0.8 {
+                theme.success
+            } else if rate >= 0.5 {
+                theme.warn
+            } else {
+                theme.error
+            };
+            lines.push(Line::from(vec![
+                Span::styled(" Hits  ", Style::default().fg(theme.muted)),
+                Span::styled(format!("{:.0}%", rate * 100.0), Style::default().fg(color)),
+            ]));
+        }
     }
 
     // Section: TCP State (from TCP_INFO)
-    lines.push(Line::styled(
-        "─ TCP State ─",
-        Style::default()
-            .fg(Color::Blue)
-            .add_modifier(Modifier::BOLD),
-    ));
-
-    // Get TCP stats
     let cwnd_stats = aggregate.by_metric.get(&MetricKind::Cwnd);
     let ssthresh_stats = aggregate.by_metric.get(&MetricKind::Ssthresh);
     let rtt_stats = aggregate.by_metric.get(&MetricKind::Rtt);
     let rttvar_stats = aggregate.by_metric.get(&MetricKind::RttVar);
+    let has_tcp_state = [cwnd_stats, ssthresh_stats, rtt_stats, rttvar_stats]
+        .iter()
+        .any(|stats| stats.is_some_and(|stats| stats.mean.is_some()));
+
+    if has_tcp_state {
+        lines.push(Line::styled(
+            "─ TCP State ─",
+            Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+        ));
+    } else if !app.tcp_info.is_available() {
+        // It can never fill in here, so the section is a single line
+        lines.push(Line::styled(
+            "─ TCP: n/a on this OS ─",
+            Style::default().fg(theme.muted),
+        ));
+    } else {
+        lines.push(Line::styled(
+            "─ TCP State ─",
+            Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+        ));
+        lines.push(Line::styled(
+            format!(" {}", tcp_info_note(app, profile)),
+            Style::default().fg(theme.muted),
+        ));
+    }
 
     // Display TCP metrics
     if let Some(stats) = &rtt_stats
         && let Some(mean) = stats.mean
     {
         lines.push(Line::from(vec![
-            Span::styled(" RTT   ", Style::default().fg(Color::DarkGray)),
-            Span::styled(format_latency(mean), Style::default().fg(Color::Green)),
+            Span::styled(" RTT   ", Style::default().fg(theme.muted)),
+            Span::styled(format_latency(mean), Style::default().fg(theme.success)),
         ]));
     }
     if let Some(stats) = &rttvar_stats
         && let Some(mean) = stats.mean
     {
         lines.push(Line::from(vec![
-            Span::styled(" RTTV  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(format_latency(mean), Style::default().fg(Color::Yellow)),
+            Span::styled(" RTTV  ", Style::default().fg(theme.muted)),
+            Span::styled(format_latency(mean), Style::default().fg(theme.warn)),
         ]));
     }
     if let Some(stats) = &cwnd_stats
         && let Some(mean) = stats.mean
     {
         lines.push(Line::from(vec![
-            Span::styled(" cwnd  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(format!("{mean:.0}"), Style::default().fg(Color::Cyan)),
+            Span::styled(" cwnd  ", Style::default().fg(theme.muted)),
+            Span::styled(format!("{mean:.0}"), Style::default().fg(theme.accent)),
         ]));
     }
     if let Some(stats) = &ssthresh_stats
         && let Some(mean) = stats.mean
     {
         lines.push(Line::from(vec![
-            Span::styled(" ssth  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(format!("{mean:.0}"), Style::default().fg(Color::Magenta)),
+            Span::styled(" ssth  ", Style::default().fg(theme.muted)),
+            Span::styled(format!("{mean:.0}"), Style::default().fg(theme.secondary)),
+        ]));
+    }
+
+    // Section: DNS (resolved address history)
+    lines.push(Line::styled(
+        "─ DNS ─",
+        Style::default().fg(theme.warn).add_modifier(Modifier::BOLD),
+    ));
+
+    let history = &target.ip_history;
+    match history.current() {
+        Some(ip) => {
+            let mut spans = vec![
+                Span::styled(" IP    ", Style::default().fg(theme.muted)),
+                Span::raw(ip.to_string()),
+            ];
+            if history.resolved().len() > 1 {
+                spans.push(Span::styled(
+                    format!(" +{}", history.resolved().len() - 1),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        None => lines.push(Line::styled(" (no data)", Style::default().fg(theme.muted))),
+    }
+    // Only flagged while the change is inside the window
+    if let Some(changed) = history.answer_changed().filter(|changed| {
+        SystemTime::now()
+            .duration_since(*changed)
+            .is_ok_and(|age| age <= app.target_window(target).duration())
+    }) {
+        lines.push(Line::from(vec![
+            Span::styled(" Set   ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("changed {}", format_age(changed)),
+                Style::default().fg(theme.warn),
+            ),
+        ]));
+    }
+    if let Some(previous) = history.previous() {
+        lines.push(Line::from(vec![
+            Span::styled(" Prev  ", Style::default().fg(theme.muted)),
+            Span::raw(previous.to_string()),
+        ]));
+    }
+    if let Some(changed) = history.last_change() {
+        let cutoff = SystemTime::now()
+            .checked_sub(app.target_window(target).duration())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let flips = history.flips_since(cutoff);
+        let flips_color = if flips > 0 {
+            theme.secondary
+        } else {
+            theme.success
+        };
+        lines.push(Line::from(vec![
+            Span::styled(" Chg   ", Style::default().fg(theme.muted)),
+            Span::raw(format_age(changed)),
+            Span::styled(" flips ", Style::default().fg(theme.muted)),
+            Span::styled(flips.to_string(), Style::default().fg(flips_color)),
         ]));
     }
 
@@ -124,7 +422,19 @@ pub(in crate::features::ui) fn draw_network_info_pane(
         Block::default()
             .title(" Network Info ")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
+            .border_style(Style::default().fg(theme.muted)),
     );
     frame.render_widget(paragraph, area);
 }
+
+/// The `CN` of an issuer such as `C = US, O = Let's Encrypt, CN = R11`, or
+/// the whole string when it has none.
+fn issuer_common_name(issuer: &str) -> &str {
+    issuer
+        .split([',', '/'])
+        .find_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            (key.trim() == "CN").then(|| value.trim())
+        })
+        .unwrap_or(issuer)
+}
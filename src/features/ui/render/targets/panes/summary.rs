@@ -1,13 +1,17 @@
 use crate::app::{AppState, TargetRuntime};
-use crate::metrics::MetricKind;
-use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use crate::metrics::{MetricKind, OutageSummary};
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::Style;
 use ratatui::widgets::{Block, Borders, Cell, Row, Table};
+use std::time::SystemTime;
 
+use super::super::super::super::theme::Theme;
 use super::super::super::format::{
-    format_count, format_goodput, format_latency, style_for_latency, style_for_success_rate,
+    format_bytes, format_count, format_elapsed, format_goodput, format_latency, format_warming,
+    style_for_freshness, style_for_latency, style_for_slo_budget, style_for_success_rate,
     style_for_timeout_count,
 };
+use super::waterfall::{WATERFALL_HEIGHT, draw_last_probe_waterfall};
 
 pub(in crate::features::ui) fn draw_summary_pane(
     frame: &mut ratatui::Frame,
@@ -15,7 +19,12 @@ pub(in crate::features::ui) fn draw_summary_pane(
     app: &AppState,
     target: &TargetRuntime,
 ) {
+    let theme = Theme::of(app);
     let summary = app.target_summary(target);
+    let warm_up = target
+        .profiles
+        .get(target.selected_profile)
+        .and_then(|profile| target.warm_up(profile));
 
     let success_rate = if summary.requests == 0 {
         0.0
@@ -30,30 +39,97 @@ pub(in crate::features::ui) fn draw_summary_pane(
         ]),
         Row::new(vec![
             Cell::from("Success"),
-            Cell::from(format!("{success_rate:.1}%")).style(style_for_success_rate(success_rate)),
+            if let Some((seen, needed)) = warm_up.filter(|_| summary.requests > 0) {
+                Cell::from(format_warming(seen, needed)).style(Style::default().fg(theme.accent))
+            } else {
+                Cell::from(format!("{success_rate:.1}%"))
+                    .style(style_for_success_rate(success_rate, theme))
+            },
         ]),
         Row::new(vec![
             Cell::from("Timeouts"),
             Cell::from(format_count(summary.timeouts))
-                .style(style_for_timeout_count(summary.timeouts)),
+                .style(style_for_timeout_count(summary.timeouts, theme)),
         ]),
     ];
+    // How long the selected profile's outages in the window lasted
+    if let Some(profile) = target.profiles.get(target.selected_profile) {
+        let outages = app.profile_outages(target, profile);
+        let ongoing = outages.last().is_some_and(|outage| outage.end.is_none());
+        let outage_summary = OutageSummary::from_outages(&outages, SystemTime::now());
+        if outage_summary.count > 0 {
+            rows.push(Row::new(vec![
+                Cell::from("Outages"),
+                Cell::from(if ongoing {
+                    format!("{} (ongoing)", outage_summary.count)
+                } else {
+                    outage_summary.count.to_string()
+                })
+                .style(Style::default().fg(if ongoing {
+                    theme.error
+                } else {
+                    theme.warn
+                })),
+            ]));
+        }
+        if let Some(mttr) = outage_summary.mttr {
+            rows.push(Row::new(vec![
+                Cell::from("MTTR"),
+                Cell::from(format_elapsed(mttr)),
+            ]));
+        }
+        if let Some(longest) = outage_summary.longest {
+            rows.push(Row::new(vec![
+                Cell::from("Longest out"),
+                Cell::from(format_elapsed(longest)),
+            ]));
+        }
+    }
+    // Slots the worker never probed, e.g. across a suspend, apart from failures
+    if summary.missed > 0 {
+        rows.push(Row::new(vec![
+            Cell::from("Missed"),
+            Cell::from(format_count(summary.missed)).style(Style::default().fg(theme.warn)),
+        ]));
+    }
+    if target.window_override.is_some() {
+        rows.push(Row::new(vec![
+            Cell::from("Window"),
+            Cell::from(format!("{} (own)", app.target_window(target).label()))
+                .style(Style::default().fg(theme.secondary)),
+        ]));
+    }
+    if let Some(freshness) = app.target_freshness(target) {
+        rows.push(Row::new(vec![
+            Cell::from("Last"),
+            Cell::from(format!("{} ago", format_elapsed(freshness.age)))
+                .style(style_for_freshness(freshness.level, theme)),
+        ]));
+    }
 
     // Add latency stats
     if let Some(profile) = target.profiles.get(target.selected_profile) {
         let aggregate = app.target_aggregate(target, profile);
         if let Some(stats) = aggregate.by_metric.get(&MetricKind::Total) {
-            if let Some(p50) = stats.p50 {
-                rows.push(Row::new(vec![
-                    Cell::from("Latency P50"),
-                    Cell::from(format_latency(p50)).style(style_for_latency(p50)),
-                ]));
-            }
-            if let Some(p99) = stats.p99 {
+            if let Some((seen, needed)) = warm_up.filter(|_| stats.n > 0) {
                 rows.push(Row::new(vec![
-                    Cell::from("Latency P99"),
-                    Cell::from(format_latency(p99)).style(style_for_latency(p99)),
+                    Cell::from("Latency"),
+                    Cell::from(format_warming(seen, needed))
+                        .style(Style::default().fg(theme.accent)),
                 ]));
+            } else {
+                if let Some(p50) = stats.p50 {
+                    rows.push(Row::new(vec![
+                        Cell::from("Latency P50"),
+                        Cell::from(format_latency(p50)).style(style_for_latency(p50, theme)),
+                    ]));
+                }
+                if let Some(p99) = stats.p99 {
+                    rows.push(Row::new(vec![
+                        Cell::from("Latency P99"),
+                        Cell::from(format_latency(p99)).style(style_for_latency(p99, theme)),
+                    ]));
+                }
             }
         }
     }
@@ -70,6 +146,59 @@ pub(in crate::features::ui) fn draw_summary_pane(
                 Cell::from(format_goodput(mean)),
             ]));
         }
+        if let Some(mean) = aggregate
+            .by_metric
+            .get(&MetricKind::ResponseBytes)
+            .and_then(|stats| stats.mean)
+        {
+            rows.push(Row::new(vec![
+                Cell::from("Resp size"),
+                Cell::from(format_bytes(mean)),
+            ]));
+        }
+    }
+
+    // One row: availability against the objective, then the error budget left
+    if let Some(slo) = summary.slo {
+        let availability = slo
+            .availability_pct
+            .map(|value| format!("{value:.2}%"))
+            .unwrap_or_else(|| "—".to_string());
+        rows.push(Row::new(vec![
+            Cell::from("SLO"),
+            Cell::from(format!(
+                "{availability}/{}% ({:+})",
+                slo.target_pct, slo.budget_remaining
+            ))
+            .style(style_for_slo_budget(&slo, theme)),
+        ]));
+    }
+
+    // Add HTTP status breakdown (compact)
+    if !summary.statuses.is_empty() {
+        let status_summary: String = summary
+            .statuses
+            .iter()
+            .map(|(status, count)| format!("{status}:{}", format_count(*count)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let style = if summary.statuses.keys().any(|status| *status >= 400) {
+            Style::default().fg(theme.warn)
+        } else {
+            Style::default()
+        };
+        rows.push(Row::new(vec![
+            Cell::from("Status"),
+            Cell::from(status_summary).style(style),
+        ]));
+    }
+
+    let restarts = target.worker_restarts();
+    if restarts > 0 {
+        rows.push(Row::new(vec![
+            Cell::from("Restarts"),
+            Cell::from(restarts.to_string()).style(Style::default().fg(theme.warn)),
+        ]));
     }
 
     // Add error breakdown (compact)
@@ -83,21 +212,42 @@ pub(in crate::features::ui) fn draw_summary_pane(
             .collect::<Vec<_>>()
             .join(" ");
         rows.push(Row::new(vec![
-            Cell::from("Errors").style(Style::default().fg(Color::Red)),
-            Cell::from(error_summary).style(Style::default().fg(Color::Red)),
+            Cell::from("Errors ⏎").style(Style::default().fg(theme.error)),
+            Cell::from(error_summary).style(Style::default().fg(theme.error)),
         ]));
     }
 
-    let widths = [
-        ratatui::layout::Constraint::Length(12),
-        ratatui::layout::Constraint::Min(12),
-    ];
+    let widths = [Constraint::Length(12), Constraint::Min(12)];
+    let row_count = rows.len() as u16;
+    let table = Table::new(rows, widths).column_spacing(1);
 
-    let table = Table::new(rows, widths).column_spacing(1).block(
-        Block::default()
-            .title(format!(" Summary [{}] ", app.window.label()))
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
-    );
-    frame.render_widget(table, area);
+    let block = Block::default()
+        .title(if app.window_partially_covered(target) {
+            format!(
+                " Summary [{} · partial] ",
+                app.target_window(target).label()
+            )
+        } else {
+            format!(" Summary [{}] ", app.target_window(target).label())
+        })
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.muted));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    frame.render_widget(table, inner);
+
+    // The waterfall only gets room left under the table, after a blank row
+    let waterfall_top = row_count + 1;
+    if inner.height >= waterfall_top + WATERFALL_HEIGHT {
+        let waterfall_area = Rect {
+            y: inner.y + waterfall_top,
+            height: WATERFALL_HEIGHT,
+            ..inner
+        };
+        let sample = target
+            .profiles
+            .get(target.selected_profile)
+            .and_then(|profile| profile.last_sample.as_ref());
+        draw_last_probe_waterfall(frame, waterfall_area, sample, theme);
+    }
 }
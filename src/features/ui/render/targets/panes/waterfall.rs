@@ -0,0 +1,171 @@
+use crate::metrics::PHASE_METRICS;
+use crate::probe::{Phase, ProbeResult, ProbeSample};
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use std::time::SystemTime;
+
+use super::super::super::super::theme::Theme;
+use super::super::super::format::format_latency;
+
+/// Rows the waterfall needs: a heading, one per phase and the total.
+pub(super) const WATERFALL_HEIGHT: u16 = 2 + PHASE_METRICS.len() as u16;
+/// Width reserved left of each bar for the phase name.
+const LABEL_WIDTH: u16 = 9;
+/// Width reserved right of each bar for the duration.
+const VALUE_WIDTH: u16 = 9;
+
+/// Per-phase durations (ms) of `sample`, `None` where the phase did not run.
+/// For a failed probe the phases after the failed one are dropped and the
+/// failed one is whatever time remains of the total. Samples recorded
+/// before curl reported the phase fall back to the error kind's phase.
+fn phase_durations(sample: &ProbeSample) -> ([Option<f64>; 5], Option<usize>) {
+    let ms = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+    let mut phases = [
+        sample.t_dns.map(ms),
+        Some(ms(sample.t_connect)),
+        sample.t_tls.map(ms),
+        Some(ms(sample.t_ttfb)),
+        Some(ms(sample.t_download)),
+    ];
+    let failed = sample
+        .failed_phase
+        .or_else(|| match &sample.result {
+            ProbeResult::Err(err) => err.kind.phase(),
+            ProbeResult::Ok => None,
+        })
+        .map(Phase::index);
+    if let Some(failed) = failed {
+        let completed: f64 = phases[..failed].iter().flatten().sum();
+        phases[failed] = Some((ms(sample.t_total) - completed).max(0.0));
+        for phase in &mut phases[failed + 1..] {
+            *phase = None;
+        }
+    }
+    (phases, failed)
+}
+
+/// Where the most recent probe spent its time: one bar per phase, each
+/// starting where the previous one ended, scaled to the probe's total.
+pub(super) fn draw_last_probe_waterfall(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    sample: Option<&ProbeSample>,
+    theme: &Theme,
+) {
+    let heading = |age: Option<u64>| {
+        let mut spans = vec![Span::styled(
+            "Last probe",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )];
+        if let Some(age) = age {
+            spans.push(Span::styled(
+                format!(" -{age}s"),
+                Style::default().fg(theme.muted),
+            ));
+        }
+        Line::from(spans)
+    };
+    let Some(sample) = sample else {
+        let lines = vec![
+            heading(None),
+            Line::styled("No probes yet", Style::default().fg(theme.muted)),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+        return;
+    };
+    let age = SystemTime::now()
+        .duration_since(sample.ts)
+        .map(|age| age.as_secs())
+        .unwrap_or(0);
+    if sample.ping.is_some() {
+        let lines = vec![
+            heading(Some(age)),
+            Line::styled(
+                "Ping probes have no HTTP phases",
+                Style::default().fg(theme.muted),
+            ),
+        ];
+        frame.render_widget(Paragraph::new(lines), area);
+        return;
+    }
+
+    let (phases, failed) = phase_durations(sample);
+    let total: f64 = phases.iter().flatten().sum();
+    let bar_width = area.width.saturating_sub(LABEL_WIDTH + VALUE_WIDTH);
+    let scale = if total > 0.0 {
+        f64::from(bar_width) / total
+    } else {
+        0.0
+    };
+
+    let mut lines = vec![heading(Some(age))];
+    let mut elapsed = 0.0;
+    for (idx, (metric, phase)) in PHASE_METRICS.iter().zip(phases).enumerate() {
+        let color = if failed == Some(idx) {
+            theme.error
+        } else {
+            theme.phase_color(idx)
+        };
+        let mut spans = vec![Span::styled(
+            format!("{:<width$}", metric.label(), width = LABEL_WIDTH as usize),
+            Style::default().fg(color),
+        )];
+        let Some(duration) = phase else {
+            spans.push(Span::styled(
+                format!(
+                    "{:>width$}",
+                    "—",
+                    width = (bar_width + VALUE_WIDTH) as usize
+                ),
+                Style::default().fg(theme.muted),
+            ));
+            lines.push(Line::from(spans));
+            continue;
+        };
+        // Ends are rounded cumulatively so the last bar ends at the full width
+        let start = ((elapsed * scale).round() as u16).min(bar_width);
+        elapsed += duration;
+        let end = ((elapsed * scale).round() as u16).min(bar_width);
+        spans.push(Span::raw(" ".repeat(start as usize)));
+        spans.push(Span::styled(
+            "█".repeat(end.saturating_sub(start) as usize),
+            Style::default().fg(color),
+        ));
+        spans.push(Span::raw(
+            " ".repeat(bar_width.saturating_sub(end) as usize),
+        ));
+        spans.push(Span::styled(
+            format!(
+                "{:>width$}",
+                format_latency(duration),
+                width = VALUE_WIDTH as usize
+            ),
+            Style::default().fg(color),
+        ));
+        lines.push(Line::from(spans));
+    }
+
+    let total_line = match &sample.result {
+        ProbeResult::Err(err) if failed.is_some() => Line::from(vec![
+            Span::styled(
+                format!("✗ {}", err.kind.short_label()),
+                Style::default().fg(theme.error),
+            ),
+            Span::raw(format!(" after {}", format_latency(total))),
+        ]),
+        _ => Line::from(vec![
+            Span::styled(
+                format!("{:<width$}", "Total", width = LABEL_WIDTH as usize),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format_latency(total)),
+        ]),
+    };
+    lines.push(total_line);
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
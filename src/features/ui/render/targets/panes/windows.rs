@@ -0,0 +1,101 @@
+use crate::app::{AppState, TargetRuntime};
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Row, Table};
+
+use super::super::super::super::theme::Theme;
+use super::super::super::format::{format_stat_triplet, metrics_for_category};
+
+/// The selected profile's metrics of the current category, one column per
+/// configured window, so a recent regression stands out against the longer
+/// windows. The selected window's column is highlighted.
+pub(in crate::features::ui) fn draw_windows_pane(
+    frame: &mut ratatui::Frame,
+    area: Rect,
+    app: &AppState,
+    target: &TargetRuntime,
+) {
+    let theme = Theme::of(app);
+    let block = Block::default()
+        .title(format!(
+            " Windows [{} · {}] ",
+            target.metrics_category.label(),
+            app.global.stats_preset.label()
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.muted));
+    let Some(profile) = target.profiles.get(target.selected_profile) else {
+        frame.render_widget(block, area);
+        return;
+    };
+
+    let windows = &app.global.windows;
+    let current = app.target_window(target);
+    let aggregates: Vec<_> = windows
+        .iter()
+        .map(|window| app.target_aggregate_in(target, profile, *window))
+        .collect();
+    let window_style = |idx: usize| {
+        if windows[idx] == current {
+            Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.accent)
+        }
+    };
+
+    let header = Row::new(
+        std::iter::once(Line::from(Span::styled(
+            profile.config.name.clone(),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )))
+        .chain(
+            windows
+                .iter()
+                .enumerate()
+                .map(|(idx, window)| Line::styled(window.label(), window_style(idx))),
+        )
+        .collect::<Vec<_>>(),
+    )
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let warm_up = target.warm_up(profile);
+    let rows: Vec<Row> = metrics_for_category(target.metrics_category)
+        .iter()
+        .map(|&metric| {
+            let unit = metric.unit();
+            let label = if unit.is_empty() {
+                metric.label().to_string()
+            } else {
+                format!("{} ({})", metric.label(), unit)
+            };
+            let cells = std::iter::once(Cell::from(label)).chain(
+                aggregates.iter().enumerate().map(|(idx, aggregate)| {
+                    let text = format_stat_triplet(
+                        metric,
+                        aggregate.by_metric.get(&metric),
+                        app.global.stats_preset,
+                        warm_up,
+                    );
+                    if windows[idx] == current {
+                        Cell::from(text).style(Style::default().fg(theme.warn))
+                    } else {
+                        Cell::from(text)
+                    }
+                }),
+            );
+            Row::new(cells.collect::<Vec<_>>())
+        })
+        .collect();
+
+    let widths: Vec<Constraint> = std::iter::once(Constraint::Length(18))
+        .chain(windows.iter().map(|_| Constraint::Length(18)))
+        .collect();
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .block(block);
+    frame.render_widget(table, area);
+}
@@ -0,0 +1,1206 @@
+use super::*;
+use crate::app::{AppState, TargetPaneMode};
+use crate::config::{GlobalConfig, KeyBinding, TargetConfig, default_profiles};
+use crate::features::probe::testing::SampleBuilder;
+use crate::probe::ProbeErrorKind;
+use crate::settings::Keymap;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::ListState;
+use std::time::{Duration, SystemTime};
+use url::Url;
+
+use super::super::state::{HitRegistry, InputMode, SettingsField, SettingsState};
+use super::super::theme::Theme;
+
+/// Two replay-loaded targets (no workers) with a few samples on the first
+/// target's first profile.
+fn fixture() -> AppState {
+    let mut app = AppState::new(GlobalConfig::default());
+    let target = |url: &str| TargetConfig::new(Url::parse(url).unwrap(), default_profiles());
+    app.load_replay(vec![
+        target("https://api.example.com"),
+        target("https://web.example.com"),
+    ]);
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let now = SystemTime::now();
+    for (age, total_ms) in [(50, 40), (40, 50), (30, 60), (20, 80), (10, 120)] {
+        app.apply_sample(
+            SampleBuilder::ok(target_id, profile_id)
+                .total_ms(total_ms)
+                .at(now - Duration::from_secs(age))
+                .build(),
+        );
+    }
+    app
+}
+
+fn render(width: u16, height: u16, draw: impl FnOnce(&mut ratatui::Frame)) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(draw).unwrap().buffer.clone()
+}
+
+fn text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn draw_main_at(app: &AppState, width: u16, height: u16) -> Buffer {
+    let mut list_state = ListState::default();
+    let mut hits = HitRegistry::default();
+    render(width, height, |frame| {
+        draw_main(frame, frame.area(), app, &mut list_state, None, &mut hits);
+    })
+}
+
+#[test]
+fn main_view_lists_targets_and_highlights_the_selection() {
+    let app = fixture();
+    for (width, height) in [(100, 24), (160, 48)] {
+        let buffer = draw_main_at(&app, width, height);
+        let screen = text(&buffer);
+        assert!(screen.contains(" Targets "), "{width}x{height}");
+        assert!(screen.contains("api.example.com"));
+        assert!(screen.contains("web.example.com"));
+
+        // The target list is the left column
+        let list = Rect::new(0, 0, 32, height);
+        let (x, y) = find(&buffer, list, "api.example.com").expect("selected target drawn");
+        assert_eq!(buffer[(x, y)].fg, Color::Yellow);
+        assert_eq!(buffer[(x, y)].bg, Color::DarkGray);
+        let (x, y) = find(&buffer, list, "web.example.com").unwrap();
+        assert_eq!(buffer[(x, y)].fg, Color::White);
+        assert_eq!(buffer[(x, y)].bg, Color::Reset);
+    }
+}
+
+/// Position of the first cell of `needle` inside `area`, searching row by row.
+fn find(buffer: &Buffer, area: Rect, needle: &str) -> Option<(u16, u16)> {
+    (area.top()..area.bottom()).find_map(|y| {
+        let line: Vec<&str> = (area.left()..area.right())
+            .map(|x| buffer[(x, y)].symbol())
+            .collect();
+        let x = (0..line.len()).find(|&x| line[x..].concat().starts_with(needle))?;
+        Some((area.x + x as u16, y))
+    })
+}
+
+#[test]
+fn pane_modes_draw_their_sections_and_stat_triplets() {
+    let mut app = fixture();
+    let window = app.window.label();
+    for (width, height) in [(100, 24), (140, 40)] {
+        app.targets[0].pane_mode = TargetPaneMode::Split;
+        let split = text(&draw_main_at(&app, width, height));
+        assert!(
+            split.contains(&format!("Summary [{window}]")),
+            "{width}x{height}"
+        );
+        assert!(split.contains("Metrics (P50/P99/Mean)"));
+        assert!(split.contains(&format!("Chart [{window}]")));
+        assert!(split.contains("Latency P99 120ms"));
+
+        app.targets[0].pane_mode = TargetPaneMode::Chart;
+        let chart = text(&draw_main_at(&app, width, height));
+        assert!(chart.contains(&format!("Chart [{window}]")));
+        assert!(!chart.contains("Metrics ("));
+
+        app.targets[0].pane_mode = TargetPaneMode::Metrics;
+        let metrics = text(&draw_main_at(&app, width, height));
+        assert!(metrics.contains("Metrics (P50/P99/Mean)"));
+        assert!(!metrics.contains("Chart ["));
+        let total = metrics
+            .lines()
+            .find(|line| line.contains("total (ms)"))
+            .expect("total row");
+        assert!(total.contains("60/120/70"), "{total}");
+    }
+}
+
+#[test]
+fn error_bar_appears_once_a_profile_fails() {
+    let mut app = fixture();
+    assert!(!text(&draw_main_at(&app, 120, 30)).contains("HTTP Timeout"));
+
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    app.apply_sample(
+        SampleBuilder::ok(target_id, profile_id)
+            .failed(ProbeErrorKind::HttpTimeout)
+            .build(),
+    );
+    let screen = text(&draw_main_at(&app, 120, 30));
+    assert!(screen.contains("⚠ h2+tls12+warm: HTTP Timeout"));
+    assert!(screen.contains("⚠ api.example.com"));
+}
+
+#[test]
+fn settings_popup_lists_global_and_target_rows() {
+    let app = fixture();
+    let settings = SettingsState::new();
+    for (width, height) in [(100, 24), (120, 40)] {
+        let screen = text(&render(width, height, |frame| {
+            draw_settings_popup(
+                frame,
+                frame.area(),
+                &app,
+                &settings,
+                InputMode::Settings,
+                "",
+            );
+        }));
+        assert!(
+            screen.contains("Settings - https://api.example.com/"),
+            "{width}x{height}"
+        );
+        assert!(screen.contains("> Global   UI refresh"));
+        assert!(screen.contains("Target   URL"));
+        assert!(screen.contains("History ~"), "{width}x{height}");
+    }
+}
+
+#[test]
+fn settings_show_the_interval_the_worker_actually_achieves() {
+    let mut app = fixture();
+    app.targets[0].config.interval = Duration::from_secs(5);
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let probe = |gap_ms: Option<u64>, burst: bool| {
+        let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+        sample.since_last_probe = gap_ms.map(Duration::from_millis);
+        sample.burst = burst;
+        sample
+    };
+    let screen = |app: &AppState| {
+        text(&render(120, 80, |frame| {
+            draw_settings_popup(
+                frame,
+                frame.area(),
+                app,
+                &SettingsState::new(),
+                InputMode::Settings,
+                "",
+            );
+        }))
+    };
+
+    app.apply_sample(probe(Some(5_020), false));
+    // Burst probes run at their own pace and stay out of it
+    app.apply_sample(probe(Some(500), true));
+    let listed = screen(&app);
+    assert!(
+        listed.contains("Interval           5s (effective 5.02s)"),
+        "{listed}"
+    );
+
+    // A restarted schedule drops the estimate until the next gap
+    app.apply_sample(probe(None, false));
+    let listed = screen(&app);
+    assert!(listed.contains("Interval           5s "), "{listed}");
+    assert!(!listed.contains("effective"), "{listed}");
+}
+
+#[test]
+fn settings_list_the_protocol_knobs_of_the_selected_profile() {
+    let mut app = fixture();
+    let screen = |app: &AppState| {
+        text(&render(120, 80, |frame| {
+            draw_settings_popup(
+                frame,
+                frame.area(),
+                app,
+                &SettingsState::new(),
+                InputMode::Settings,
+                "",
+            );
+        }))
+    };
+
+    let listed = screen(&app);
+    let profile = &app.targets[0].profiles[0].config;
+    assert!(
+        listed.contains(&format!(
+            "Settings - https://api.example.com/ - {}",
+            profile.name
+        )),
+        "{listed}"
+    );
+    for row in [
+        format!(
+            "Profile  Method             {}",
+            profile.method.to_string().to_uppercase()
+        ),
+        format!("Profile  HTTP version       {}", profile.http),
+        format!(
+            "Profile  TLS version        {}",
+            profile.tls.protocol_name()
+        ),
+        format!("Profile  Connection         {}", profile.conn_reuse),
+        "Profile  Max read".to_string(),
+    ] {
+        assert!(listed.contains(&row), "{row}\n{listed}");
+    }
+
+    // A ping sends no request, so only its method is listed
+    let target_id = app.targets[0].config.id;
+    let mut ping = app.targets[0].profiles[0].config.clone();
+    ping.method = crate::config::ProbeMethod::Ping;
+    app.update_profile_config(target_id, ping);
+    let listed = screen(&app);
+    assert!(
+        listed.contains("Profile  Method             PING"),
+        "{listed}"
+    );
+    assert!(!listed.contains("HTTP version"), "{listed}");
+}
+
+#[test]
+fn footer_and_help_show_the_configured_keys() {
+    let overrides = [
+        ("pause".to_string(), KeyBinding::Single("space".to_string())),
+        ("quit".to_string(), KeyBinding::Single("Q".to_string())),
+    ]
+    .into();
+    let keymap = Keymap::from_config(&overrides).expect("keymap");
+
+    let footer = text(&render(200, 1, |frame| {
+        draw_footer(
+            frame,
+            frame.area(),
+            InputMode::Normal,
+            &keymap,
+            &Theme::DARK,
+        );
+    }));
+    assert!(footer.contains(" Q Quit"), "{footer}");
+    assert!(footer.contains(" Space Pause "), "{footer}");
+    assert!(footer.contains(" J/K Move"), "{footer}");
+
+    let help = text(&render(120, 80, |frame| {
+        draw_help_popup(frame, frame.area(), &keymap, &Theme::DARK);
+    }));
+    assert!(help.contains("Space       Pause/Resume probing"), "{help}");
+    assert!(help.contains("Q           Quit application"), "{help}");
+}
+
+#[test]
+fn help_and_glossary_popups_are_titled() {
+    for (width, height) in [(100, 24), (120, 40)] {
+        let help = text(&render(width, height, |frame| {
+            draw_help_popup(frame, frame.area(), &Keymap::default(), &Theme::DARK);
+        }));
+        assert!(help.contains(" Help "), "{width}x{height}");
+        assert!(help.contains("Keyboard Shortcuts"));
+
+        for (page, title) in [(0, "Latency Metrics"), (2, "Throughput & TCP")] {
+            let glossary = text(&render(width, height, |frame| {
+                draw_glossary_popup(frame, frame.area(), page, &Theme::DARK);
+            }));
+            assert!(glossary.contains(&format!("Glossary - {title}")));
+        }
+    }
+}
+
+#[test]
+fn metric_detail_popup_lists_every_stat_of_the_focused_row() {
+    use super::super::input::{
+        enter_metrics_focus, handle_metric_detail_key, handle_metrics_focus_key,
+    };
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Metrics;
+    let keymap = Keymap::default();
+    let mut cursor = None;
+    assert!(enter_metrics_focus(&app, &mut cursor));
+    let mut mode = InputMode::MetricsFocus;
+    // Up from the first row wraps around to Total, the last latency row
+    for code in [KeyCode::Up, KeyCode::Enter] {
+        handle_metrics_focus_key(
+            KeyEvent::from(code),
+            &keymap,
+            &mut app,
+            &mut mode,
+            &mut cursor,
+        );
+    }
+    assert_eq!(mode, InputMode::MetricDetail);
+
+    let popup = text(&render(120, 40, |frame| {
+        draw_metric_detail_popup(frame, frame.area(), &app, cursor);
+    }));
+    assert!(popup.contains("total (ms)"), "{popup}");
+    let row = |name: &str| {
+        popup
+            .lines()
+            .find(|line| line.trim_start_matches(['│', ' ']).starts_with(name))
+            .unwrap_or_else(|| panic!("{name} row in {popup}"))
+            .to_string()
+    };
+    assert!(row("n ").contains(" 5 "), "{popup}");
+    assert!(row("Min").contains("40"), "{popup}");
+    assert!(row("Max").contains("120"), "{popup}");
+    for name in ["P90", "StdDev"] {
+        row(name);
+    }
+    assert!(row("Recent").contains("▁▂▃▅█"), "{popup}");
+
+    handle_metric_detail_key(KeyEvent::from(KeyCode::Esc), &keymap, &mut mode);
+    assert_eq!(mode, InputMode::MetricsFocus);
+    handle_metrics_focus_key(
+        KeyEvent::from(KeyCode::Esc),
+        &keymap,
+        &mut app,
+        &mut mode,
+        &mut cursor,
+    );
+    assert_eq!(mode, InputMode::Normal);
+    assert_eq!(cursor, None);
+}
+
+#[test]
+fn chart_cursor_steps_between_samples_and_stops_at_the_ends() {
+    use super::super::input::{enter_chart_cursor, handle_chart_cursor_key};
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Chart;
+    let keymap = Keymap::default();
+    let mut mode = InputMode::ChartCursor;
+    let age = |app: &AppState| {
+        let cursor = app.targets[0].chart_cursor.expect("cursor placed");
+        SystemTime::now()
+            .duration_since(cursor)
+            .unwrap()
+            .as_secs_f64()
+            .round() as u64
+    };
+
+    assert!(enter_chart_cursor(&mut app));
+    assert_eq!(age(&app), 10);
+    let mut press = |app: &mut AppState, code| {
+        handle_chart_cursor_key(KeyEvent::from(code), &keymap, app, &mut mode);
+    };
+    press(&mut app, KeyCode::Left);
+    assert_eq!(age(&app), 20);
+    press(&mut app, KeyCode::Home);
+    assert_eq!(age(&app), 50);
+    // Stepping past either end leaves the cursor where it is
+    press(&mut app, KeyCode::Left);
+    assert_eq!(age(&app), 50);
+    press(&mut app, KeyCode::Right);
+    assert_eq!(age(&app), 40);
+    press(&mut app, KeyCode::End);
+    press(&mut app, KeyCode::Right);
+    assert_eq!(age(&app), 10);
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.targets[0].chart_cursor, None);
+    assert_eq!(mode, InputMode::Normal);
+}
+
+#[test]
+fn annotations_are_marked_on_the_chart_and_listed() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Chart;
+    app.add_annotation(0, "switched to new LB");
+    let screen = text(&draw_main_at(&app, 120, 30));
+    assert!(screen.contains("│ Note"), "{screen}");
+
+    let popup = text(&render(100, 30, |frame| {
+        draw_annotations_popup(frame, frame.area(), &app, 0);
+    }));
+    assert!(popup.contains("Notes ─ api.example.com"), "{popup}");
+    assert!(popup.contains("switched to new LB"), "{popup}");
+}
+
+#[test]
+fn changes_are_listed_newest_first_and_marked_on_the_chart() {
+    let mut app = fixture();
+    let popup = |app: &AppState| {
+        text(&render(110, 30, |frame| {
+            draw_change_log_popup(frame, frame.area(), app, 0);
+        }))
+    };
+    assert!(popup(&app).contains("No changes yet"), "{}", popup(&app));
+
+    let target_id = app.targets[0].config.id;
+    app.toggle_pause(target_id);
+    app.toggle_pause(target_id);
+    let mut updated = app.targets[0].config.clone();
+    updated.interval = Duration::from_secs(30);
+    app.edit_target_config(updated);
+
+    let listed = popup(&app);
+    assert!(listed.contains("Logged: 3"), "{listed}");
+    let position = |needle: &str| listed.find(needle).expect(needle);
+    assert!(
+        position("Interval: 5s → 30s") < position("Resumed"),
+        "{listed}"
+    );
+    assert!(position("Resumed") < position("Paused"), "{listed}");
+    assert!(listed.contains("api.example.com"), "{listed}");
+
+    app.targets[0].pane_mode = TargetPaneMode::Chart;
+    let screen = text(&draw_main_at(&app, 120, 30));
+    assert!(screen.contains("│ Config change"), "{screen}");
+}
+
+#[test]
+fn outages_are_charted_summarized_and_listed() {
+    let mut app = fixture();
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let now = SystemTime::now();
+    for age in [8, 6] {
+        app.apply_sample(
+            SampleBuilder::ok(target_id, profile_id)
+                .failed(ProbeErrorKind::ConnectTimeout)
+                .at(now - Duration::from_secs(age))
+                .build(),
+        );
+    }
+    app.apply_sample(
+        SampleBuilder::ok(target_id, profile_id)
+            .at(now - Duration::from_secs(4))
+            .build(),
+    );
+
+    app.targets[0].pane_mode = TargetPaneMode::Summary;
+    let screen = text(&draw_main_at(&app, 120, 40));
+    assert!(screen.contains("MTTR"), "{screen}");
+    assert!(screen.contains("Longest out"), "{screen}");
+
+    app.targets[0].pane_mode = TargetPaneMode::Chart;
+    let screen = text(&draw_main_at(&app, 120, 30));
+    assert!(screen.contains("━ Outage"), "{screen}");
+
+    let popup = text(&render(110, 30, |frame| {
+        draw_outages_popup(frame, frame.area(), &app);
+    }));
+    assert!(
+        popup.contains("Outages [") && popup.contains("MTTR"),
+        "{popup}"
+    );
+    assert!(popup.contains("Connect Timeout"), "{popup}");
+    assert!(!popup.contains("ongoing"), "{popup}");
+}
+
+#[test]
+fn profiles_with_few_samples_show_warming_instead_of_stats() {
+    let mut app = fixture();
+    app.selected_target = 1;
+    let (target_id, profile_id) = (
+        app.targets[1].config.id,
+        app.targets[1].profiles[0].config.id,
+    );
+    let probe = |app: &mut AppState| {
+        app.apply_sample(
+            SampleBuilder::ok(target_id, profile_id)
+                .total_ms(40)
+                .build(),
+        );
+    };
+    for _ in 0..3 {
+        probe(&mut app);
+    }
+    let screen = text(&draw_main_at(&app, 160, 48));
+    assert!(screen.contains("◌ WARMING"), "{screen}");
+    assert!(screen.contains("web.example.com warming"), "{screen}");
+    // Metrics table and summary both hold back the numbers
+    assert!(screen.matches("warming (3/5)").count() >= 2, "{screen}");
+
+    for _ in 0..2 {
+        probe(&mut app);
+    }
+    let screen = text(&draw_main_at(&app, 160, 48));
+    assert!(!screen.contains("warming (5/5)"), "{screen}");
+    assert!(screen.contains("Latency P50"), "{screen}");
+}
+
+#[test]
+fn windows_pane_shows_one_column_per_configured_window() {
+    let mut app = fixture();
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    // Only the windows of 15m and longer reach back to this slow probe
+    app.apply_sample(
+        SampleBuilder::ok(target_id, profile_id)
+            .total_ms(900)
+            .at(SystemTime::now() - Duration::from_secs(10 * 60))
+            .build(),
+    );
+    app.targets[0].pane_mode = TargetPaneMode::Windows;
+
+    let screen = text(&draw_main_at(&app, 160, 48));
+    assert!(screen.contains(" Windows [Latency"), "{screen}");
+    let header = screen
+        .lines()
+        .find(|line| line.contains(&app.targets[0].profiles[0].config.name))
+        .expect("header row");
+    let labels: Vec<_> = app
+        .global
+        .windows
+        .iter()
+        .map(|window| window.label())
+        .collect();
+    for label in &labels {
+        assert!(header.contains(label.as_str()), "{header}");
+    }
+    let total = screen
+        .lines()
+        .find(|line| line.contains("total (ms)"))
+        .expect("total row");
+    let cells: Vec<_> = total
+        .split_whitespace()
+        .filter(|cell| cell.contains('/'))
+        .collect();
+    assert_eq!(cells.len(), labels.len(), "{total}");
+    assert_eq!(cells[0], cells[1], "{total}");
+    assert_eq!(cells[2], cells[3], "{total}");
+    assert_ne!(cells[1], cells[2], "{total}");
+}
+
+#[test]
+fn summary_pane_draws_a_waterfall_of_the_last_probe() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Summary;
+    let buffer = draw_main_at(&app, 120, 40);
+    let screen = text(&buffer);
+    assert!(screen.contains("Last probe -10s"), "{screen}");
+    let row = |label: &str| {
+        screen
+            .lines()
+            .find(|line| line.contains(&format!("│{label} ")))
+            .unwrap_or_else(|| panic!("{label} row in {screen}"))
+            .to_string()
+    };
+    assert!(row("dns").contains("2.00ms"), "{screen}");
+    assert!(row("tls").contains("8.00ms"), "{screen}");
+    // Each phase starts where the previous one ended
+    let bar_start = |label: &str| row(label).find('█').unwrap();
+    assert!(bar_start("dns") < bar_start("connect"));
+    assert!(bar_start("ttfb") < bar_start("download"));
+    let (x, y) = find(&buffer, buffer.area, "download").unwrap();
+    let bar_x = (x..buffer.area.right())
+        .find(|x| buffer[(*x, y)].symbol() == "█")
+        .unwrap();
+    assert_eq!(buffer[(bar_x, y)].fg, Theme::DARK.phase_color(4));
+}
+
+#[test]
+fn waterfall_marks_the_phase_a_failed_probe_stopped_in() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Summary;
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    app.apply_sample(
+        SampleBuilder::ok(target_id, profile_id)
+            .total_ms(500)
+            .failed(ProbeErrorKind::TlsHandshakeFailed)
+            .build(),
+    );
+    let buffer = draw_main_at(&app, 120, 40);
+    let screen = text(&buffer);
+    assert!(screen.contains("✗ TLS Handshake after 500ms"), "{screen}");
+    let (x, y) = find(&buffer, buffer.area, "tls").unwrap();
+    assert_eq!(buffer[(x, y)].fg, Color::Red);
+    assert!(
+        screen
+            .lines()
+            .any(|line| line.contains("│tls ") && line.contains("493ms"))
+    );
+    for label in ["ttfb", "download"] {
+        let line = screen
+            .lines()
+            .find(|line| line.contains(&format!("│{label} ")))
+            .unwrap();
+        assert!(line.contains('—') && !line.contains('█'), "{screen}");
+    }
+}
+
+#[test]
+fn waterfall_falls_back_to_the_error_kind_phase() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Summary;
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    // As recorded before samples carried the failed phase
+    let mut sample = SampleBuilder::ok(target_id, profile_id)
+        .failed(ProbeErrorKind::ConnectRefused)
+        .build();
+    sample.failed_phase = None;
+    app.apply_sample(sample);
+    let buffer = draw_main_at(&app, 120, 40);
+    let (x, y) = find(&buffer, buffer.area, "connect").unwrap();
+    assert_eq!(buffer[(x, y)].fg, Color::Red);
+}
+
+#[test]
+fn add_target_preview_explains_what_enter_would_add() {
+    let line = |input: &str| {
+        add_target_preview(input, &Theme::DARK)
+            .spans
+            .iter()
+            .map(|span| span.content.to_string())
+            .collect::<String>()
+    };
+    let valid = line("api.example.com h1+cold+head #prod");
+    assert!(valid.contains("→ https://api.example.com/"), "{valid}");
+    assert!(valid.contains("profiles: h1/"), "{valid}");
+    assert!(valid.contains("tags: prod"), "{valid}");
+    assert!(line("api.example.com").contains("profiles: defaults"));
+
+    let invalid = add_target_preview("htps://foo h3", &Theme::DARK);
+    assert!(invalid.spans.iter().any(|span| {
+        span.content.contains("unsupported scheme") && span.style.fg == Some(Color::Red)
+    }));
+    let invalid = line("htps://foo h3");
+    assert!(invalid.contains("Enter disabled"), "{invalid}");
+    assert!(invalid.contains("unknown: h3"), "{invalid}");
+}
+
+#[test]
+fn settings_show_profile_auth_without_its_secret() {
+    let mut app = fixture();
+    let target_id = app.targets[0].config.id;
+    let mut profile = app.targets[0].profiles[0].config.clone();
+    profile.auth = crate::config::AuthConfig::basic("alice:s3cret");
+    app.update_profile_config(target_id, profile);
+    let settings = SettingsState::new();
+    let screen = |mode, input: &str| {
+        text(&render(160, 80, |frame| {
+            draw_settings_popup(frame, frame.area(), &app, &settings, mode, input);
+        }))
+    };
+
+    let listed = screen(InputMode::Settings, "");
+    assert!(
+        listed.contains("Profile  Auth               Basic alice"),
+        "{listed}"
+    );
+    assert!(!listed.contains("s3cret"));
+
+    let editing = screen(
+        InputMode::SettingsEdit(SettingsField::ProfileAuth),
+        "basic alice:s3cret",
+    );
+    assert!(editing.contains("basic alice:••••••"), "{editing}");
+    assert!(!editing.contains("s3cret"));
+}
+
+#[test]
+fn overdue_samples_are_flagged_in_summary_list_and_metrics_title() {
+    let mut app = fixture();
+    // The newest sample is 10s old: fresh at a 60s interval
+    app.targets[0].config.interval = Duration::from_secs(60);
+    let fresh = draw_main_at(&app, 140, 40);
+    let screen = text(&fresh);
+    assert!(!screen.contains("(stale)"));
+    let (x, y) = find(&fresh, fresh.area, "Last").expect("last row");
+    let row: String = (x..fresh.area.right())
+        .map(|x| fresh[(x, y)].symbol())
+        .collect();
+    assert!(row.contains("s ago"), "{row}");
+
+    // ... and more than five 1s intervals old
+    app.targets[0].config.interval = Duration::from_secs(1);
+    let stale = draw_main_at(&app, 140, 40);
+    let screen = text(&stale);
+    assert!(
+        screen.contains("Metrics (P50/P99/Mean) (stale)"),
+        "{screen}"
+    );
+    let (x, y) = find(&stale, stale.area, "s ago").expect("age");
+    assert_eq!(stale[(x, y)].fg, Color::Red);
+    let list = screen
+        .lines()
+        .find(|line| line.starts_with("│") && line.contains("api.example.com"))
+        .expect("list row");
+    assert!(list.contains("api.example.com 1"), "{list}");
+}
+
+#[test]
+fn compared_profile_pair_gets_a_delta_column() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Metrics;
+    app.targets[0].view_mode = crate::app::ProfileViewMode::Compare;
+    app.targets[0].profiles.truncate(2);
+    let (target_id, second) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[1].config.id,
+    );
+    let probe = |app: &mut AppState| {
+        app.apply_sample(SampleBuilder::ok(target_id, second).total_ms(45).build());
+    };
+    for _ in 0..3 {
+        probe(&mut app);
+    }
+    let buffer = draw_main_at(&app, 160, 40);
+    let screen = text(&buffer);
+    assert!(screen.contains("Δ P50"), "{screen}");
+    // The second profile is still warming up
+    let total = screen
+        .lines()
+        .find(|line| line.contains("total (ms)"))
+        .expect("total row");
+    assert!(
+        total.contains("warming (3/5)") && total.contains('—'),
+        "{total}"
+    );
+
+    for _ in 0..2 {
+        probe(&mut app);
+    }
+    let buffer = draw_main_at(&app, 160, 40);
+    let (x, y) = find(&buffer, buffer.area, "-15 (-25%)").expect("delta drawn");
+    assert_eq!(buffer[(x, y)].fg, Color::Green);
+
+    app.targets[0].view_mode = crate::app::ProfileViewMode::Single;
+    assert!(!text(&draw_main_at(&app, 160, 40)).contains("Δ P50"));
+}
+
+#[test]
+fn insecure_targets_carry_a_badge_in_list_and_title() {
+    let mut app = fixture();
+    assert!(!text(&draw_main_at(&app, 140, 30)).contains("INSECURE"));
+
+    // The first target is selected, so its badge is in the pane title too
+    app.targets[0].config.insecure = true;
+    app.targets[1].config.insecure = true;
+    let buffer = draw_main_at(&app, 140, 30);
+    let list = Rect::new(0, 0, 32, 30);
+    let (x, y) = find(&buffer, list, "web.exampl... ⚠ INSECURE").expect("list badge");
+    assert_eq!(buffer[(x + 14, y)].bg, Color::Red);
+    assert!(find(&buffer, list, "api.exampl... ⚠ INSECURE").is_some());
+    assert!(find(&buffer, Rect::new(32, 0, 108, 30), "⚠ INSECURE").is_some());
+}
+
+#[test]
+fn quit_confirmation_counts_what_would_be_left_behind() {
+    let mut app = fixture();
+    // A live network run, whose samples are saved on exit
+    app.replaying = false;
+    let screen = text(&render(100, 30, |frame| {
+        draw_confirm_quit_popup(frame, frame.area(), &app);
+    }));
+    assert!(screen.contains("Confirm Quit"), "{screen}");
+    assert!(
+        screen.contains("2 targets, 5 samples collected"),
+        "{screen}"
+    );
+    assert!(screen.contains("--restore"), "{screen}");
+
+    // Demo samples are never saved, so there is nothing to restore
+    app.probe_source = crate::runtime::ProbeSource::Demo(1);
+    let screen = text(&render(100, 30, |frame| {
+        draw_confirm_quit_popup(frame, frame.area(), &app);
+    }));
+    assert!(screen.contains("5 samples collected"), "{screen}");
+    assert!(!screen.contains("--restore"), "{screen}");
+}
+
+#[test]
+fn truncation_counts_display_width_not_bytes() {
+    use super::format::truncate_string;
+    use unicode_width::UnicodeWidthStr;
+
+    // Each cut lands inside or right after a multibyte character
+    for max in 0..=12 {
+        let cut = truncate_string("münchen.de.example", max);
+        assert!(cut.width() <= max, "{cut:?} wider than {max}");
+    }
+    assert_eq!(truncate_string("münchen.de", 10), "münchen.de");
+    assert_eq!(truncate_string("münchen.de.example", 9), "münche...");
+
+    // CJK and emoji take two columns each and are never split
+    assert_eq!(truncate_string("東京駅.jp", 9), "東京駅.jp");
+    assert_eq!(truncate_string("東京駅.jp", 7), "東京...");
+    assert_eq!(truncate_string("東京駅.jp", 6), "東...");
+    assert_eq!(truncate_string("🚀 launch", 6), "🚀 ...");
+    assert_eq!(truncate_string("e\u{301}tude", 4), "e\u{301}...");
+    assert_eq!(truncate_string("ab東京", 2), "..");
+}
+
+#[test]
+fn padding_fills_columns_by_display_width() {
+    use super::format::pad_to_width;
+    use unicode_width::UnicodeWidthStr;
+
+    for name in [
+        "api",
+        "münchen.de",
+        "東京駅.jp",
+        "東京リージョン監視ターゲット",
+    ] {
+        for width in [6, 10, 24] {
+            let padded = pad_to_width(name, width);
+            assert_eq!(padded.width(), width, "{padded:?}");
+        }
+    }
+    assert_eq!(pad_to_width("東京駅.jp", 6), "東... ");
+}
+
+#[test]
+fn x_axis_labels_show_ages_or_wall_clock_ticks() {
+    use super::format::format_x_axis_labels;
+    use crate::common::time::format_local_hms;
+
+    let theme = Theme::DARK;
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+    let window = Duration::from_secs(60);
+    let content = |labels: &[ratatui::text::Span]| -> Vec<String> {
+        labels.iter().map(|span| span.content.to_string()).collect()
+    };
+
+    let ages = format_x_axis_labels(window, now, false, &theme);
+    assert_eq!(content(&ages), ["-60s", "-30s", "now"]);
+    assert_eq!(ages[2].style.fg, Some(theme.success));
+
+    // Four evenly spaced ticks, oldest first, ending at `now`
+    let ticks = format_x_axis_labels(window, now, true, &theme);
+    let expected: Vec<String> = [60, 40, 20, 0]
+        .into_iter()
+        .map(|age| format_local_hms(now - Duration::from_secs(age)))
+        .collect();
+    assert_eq!(content(&ticks), expected);
+    assert_eq!(ticks[0].style.fg, None);
+    assert_eq!(ticks[3].style.fg, Some(theme.success));
+}
+
+#[test]
+fn multibyte_target_names_keep_the_list_aligned() {
+    let mut app = fixture();
+    app.targets[0].config.name = Some("münchen-straßenbahn-überwachung".to_string());
+    app.targets[1].config.name = Some("東京リージョン監視ターゲット".to_string());
+    let buffer = draw_main_at(&app, 140, 30);
+    let list = Rect::new(0, 0, 32, 30);
+    let (_, y) = find(&buffer, list, "münchen-straßenbahn-ü...").expect("latin row");
+    let row = |y| Rect::new(list.x, y, list.width, 1);
+    let (latin_end, _) = find(&buffer, row(y), "...").unwrap();
+    let (cjk_end, _) = find(&buffer, row(y + 1), "...").expect("cjk row");
+    // A double-width glyph that would straddle the budget is dropped whole
+    assert_eq!(cjk_end + 1, latin_end);
+    assert_eq!(buffer[(cjk_end - 2, y + 1)].symbol(), "タ");
+}
+
+#[test]
+fn header_shows_session_totals_and_drops_minor_segments_when_narrow() {
+    let app = fixture();
+    let header = |width| {
+        text(&render(width, 1, |frame| {
+            draw_header(frame, frame.area(), &app)
+        }))
+    };
+
+    let wide = header(200);
+    for segment in ["Probes: 5 ", "OK: 100.0% ", "Rate: ", "Up: 0s", "Metrics:"] {
+        assert!(wide.contains(segment), "{wide}");
+    }
+
+    // Uptime goes first, then the metric list, the window never
+    let narrow = header(110);
+    assert!(!narrow.contains("Up:"), "{narrow}");
+    assert!(!narrow.contains("Metrics:"), "{narrow}");
+    assert!(narrow.contains("Probes: 5"), "{narrow}");
+    let tiny = header(40);
+    assert!(tiny.starts_with(" httpulse│ Window:"), "{tiny}");
+    assert!(tiny.contains("Alerts: 0"), "{tiny}");
+}
+
+#[test]
+fn per_ip_targets_list_one_line_per_address() {
+    let mut app = fixture();
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    for ip in ["192.0.2.1", "192.0.2.2"] {
+        let mut sample = SampleBuilder::ok(target_id, profile_id)
+            .total_ms(70)
+            .build();
+        sample.endpoint = Some(ip.parse().unwrap());
+        app.apply_sample(sample);
+    }
+    assert!(app.targets[0].cycle_endpoint_view());
+    assert!(app.targets[0].cycle_endpoint_view());
+
+    let buffer = draw_main_at(&app, 140, 30);
+    let list = Rect::new(0, 0, 32, 30);
+    let (_, target_y) = find(&buffer, list, "api.example.com").expect("target row");
+    let (_, first_y) = find(&buffer, list, "└ 192.0.2.1 70.1ms 0%").expect("first address");
+    let (_, second_y) = find(&buffer, list, "└ 192.0.2.2").expect("second address");
+    let (_, next_y) = find(&buffer, list, "web.example.com").expect("next target");
+    assert_eq!(
+        (first_y, second_y, next_y),
+        (target_y + 1, target_y + 2, target_y + 3)
+    );
+    assert_eq!(buffer[(6, first_y)].fg, Color::Yellow);
+    assert_eq!(buffer[(6, second_y)].fg, Color::DarkGray);
+    let title = Rect::new(32, 0, 108, 1);
+    assert!(find(&buffer, title, "@ 192.0.2.1").is_some());
+}
+
+#[test]
+fn window_override_shows_in_header_title_and_summary() {
+    let mut app = fixture();
+    app.cycle_target_window();
+    let own = app.targets[0].window_override.expect("override").label();
+
+    let header = text(&render(200, 1, |frame| {
+        draw_header(frame, frame.area(), &app)
+    }));
+    assert!(
+        header.contains(&format!("Window: {} (overrides: 1)", app.window.label())),
+        "{header}"
+    );
+    let screen = text(&draw_main_at(&app, 140, 40));
+    assert!(
+        screen.contains(&format!("Window      {own} (own)")),
+        "{screen}"
+    );
+    assert!(screen.contains(&format!("Summary [{own}]")));
+}
+
+#[test]
+fn split_view_draws_both_targets_with_their_own_pane_modes() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Summary;
+    app.targets[1].pane_mode = TargetPaneMode::Chart;
+    assert!(app.toggle_split());
+    app.selected_target = 1;
+
+    let buffer = draw_main_at(&app, 200, 40);
+    let (left, right) = (Rect::new(32, 0, 84, 1), Rect::new(116, 0, 84, 1));
+    assert!(find(&buffer, left, "web.example.com").is_some());
+    assert!(find(&buffer, left, "Chart").is_some());
+    assert!(find(&buffer, right, "api.example.com").is_some());
+    assert!(find(&buffer, right, "Summary").is_some());
+    assert_eq!(buffer[(32, 1)].fg, Color::Blue);
+    assert_eq!(buffer[(116, 1)].fg, Color::DarkGray);
+
+    // Focus moves right: the selection follows, the halves stay put
+    assert!(app.switch_split_focus());
+    let buffer = draw_main_at(&app, 200, 40);
+    assert!(find(&buffer, left, "web.example.com").is_some());
+    assert_eq!(buffer[(32, 1)].fg, Color::DarkGray);
+    assert_eq!(buffer[(116, 1)].fg, Color::Blue);
+
+    app.toggle_split();
+    let buffer = draw_main_at(&app, 200, 40);
+    assert!(find(&buffer, Rect::new(32, 0, 168, 1), "api.example.com").is_some());
+    assert!(find(&buffer, Rect::new(32, 0, 168, 1), "web.example.com").is_none());
+}
+
+#[test]
+fn reliability_tab_counts_failures_by_the_phase_they_stopped_in() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Metrics;
+    app.targets[0].metrics_category = crate::app::MetricsCategory::Reliability;
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let screen = text(&draw_main_at(&app, 140, 40));
+    assert!(!screen.contains("failed@"), "{screen}");
+
+    for _ in 0..2 {
+        app.apply_sample(
+            SampleBuilder::ok(target_id, profile_id)
+                .failed(ProbeErrorKind::TlsHandshakeFailed)
+                .build(),
+        );
+    }
+    let buffer = draw_main_at(&app, 140, 40);
+    let (x, y) = find(&buffer, buffer.area, "failed@tls").expect("tls row");
+    let count_x = (x + 10..buffer.area.right())
+        .find(|x| buffer[(*x, y)].symbol() == "2")
+        .expect("count");
+    assert_eq!(buffer[(count_x, y)].fg, Color::Red);
+    assert!(find(&buffer, buffer.area, "failed@connect").is_none());
+}
+
+#[test]
+fn missing_tcp_state_is_explained_instead_of_dashed() {
+    let mut app = fixture();
+    app.targets[0].metrics_category = crate::app::MetricsCategory::Tcp;
+    let screen = text(&draw_main_at(&app, 140, 40));
+    assert!(screen.contains("TCP state not yet sampled"), "{screen}");
+    assert!(!screen.contains("cwnd"), "{screen}");
+
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let mut sample = SampleBuilder::ok(target_id, profile_id).build();
+    sample.tcp_info_errno = Some(95);
+    app.apply_sample(sample);
+    let screen = text(&draw_main_at(&app, 200, 40));
+    let refused = format!("kernel refused: {}", std::io::Error::from_raw_os_error(95));
+    assert!(screen.contains(&refused), "{screen}");
+
+    app.tcp_info = crate::probe::TcpInfoCapability::Unsupported;
+    let screen = text(&draw_main_at(&app, 140, 40));
+    assert!(
+        screen.contains("TCP_INFO not available on this OS"),
+        "{screen}"
+    );
+    assert!(screen.contains("─ TCP: n/a on this OS ─"), "{screen}");
+    assert!(!screen.contains("─ TCP State ─"), "{screen}");
+}
+
+#[test]
+fn mono_theme_draws_without_any_color() {
+    let mut app = fixture();
+    app.theme = crate::config::ThemeName::Mono;
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    app.apply_sample(
+        SampleBuilder::ok(target_id, profile_id)
+            .failed(ProbeErrorKind::ConnectTimeout)
+            .build(),
+    );
+    let colorless = |buffer: &Buffer| {
+        buffer.content.iter().all(|cell| {
+            cell.fg == Color::Reset
+                && cell.bg == Color::Reset
+                && cell.underline_color == Color::Reset
+        })
+    };
+
+    for pane_mode in [
+        TargetPaneMode::Split,
+        TargetPaneMode::Chart,
+        TargetPaneMode::Metrics,
+    ] {
+        app.targets[0].pane_mode = pane_mode;
+        assert!(colorless(&draw_main_at(&app, 140, 40)), "{pane_mode:?}");
+    }
+    assert!(colorless(&render(140, 1, |frame| {
+        draw_header(frame, frame.area(), &app);
+    })));
+    assert!(colorless(&render(120, 40, |frame| {
+        draw_settings_popup(
+            frame,
+            frame.area(),
+            &app,
+            &SettingsState::new(),
+            InputMode::Settings,
+            "",
+        );
+    })));
+    assert!(colorless(&render(120, 80, |frame| {
+        draw_help_popup(frame, frame.area(), &Keymap::default(), &Theme::MONO);
+    })));
+
+    // The same frame in the dark theme does use colors
+    app.theme = crate::config::ThemeName::Dark;
+    assert!(!colorless(&draw_main_at(&app, 140, 40)));
+}
+
+#[test]
+fn download_caps_show_in_settings_and_a_queued_probe_is_flagged() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Split;
+    app.targets[0].profiles[0].config.max_recv_speed = Some(512 * 1024);
+    let (target_id, profile_id) = (
+        app.targets[0].config.id,
+        app.targets[0].profiles[0].config.id,
+    );
+    let mut sample = SampleBuilder::ok(target_id, profile_id)
+        .total_ms(90)
+        .build();
+    sample.limiter_wait = Some(Duration::from_millis(1500));
+    sample.deferred_slots = 2;
+    app.apply_sample(sample);
+
+    let screen = text(&draw_main_at(&app, 160, 48));
+    assert!(screen.contains("Speed ≤ 512.0K/s"), "{screen}");
+    assert!(screen.contains("Queue 1.50s wait, 2 late"), "{screen}");
+
+    let value = |app: &AppState, field| {
+        settings_rows(app)
+            .into_iter()
+            .find(|row| row.field == field)
+            .map(|row| row.value)
+    };
+    assert_eq!(
+        value(&app, SettingsField::ProfileRecvSpeed).as_deref(),
+        Some("512.0K/s")
+    );
+    assert_eq!(
+        value(&app, SettingsField::DownloadSlots).as_deref(),
+        Some("Unlimited")
+    );
+    app.global.download_slots = Some(2);
+    assert_eq!(
+        value(&app, SettingsField::DownloadSlots).as_deref(),
+        Some("2 over 64K at once")
+    );
+}
+
+#[test]
+fn a_profile_color_overrides_its_position_in_the_legend_and_settings() {
+    let mut app = fixture();
+    app.targets[0].pane_mode = TargetPaneMode::Metrics;
+    app.targets[0].view_mode = crate::app::ProfileViewMode::Compare;
+    app.targets[0].profiles[0].config.name = "edge".to_string();
+    app.targets[0].profiles[1].config.color = Some(5);
+
+    let theme = Theme::of(&app);
+    let buffer = draw_main_at(&app, 160, 40);
+    let color_of = |label: &str| {
+        let screen = text(&buffer);
+        let (y, line) = screen
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.contains(label))
+            .expect(label);
+        let x = line[..line.find(label).unwrap()].chars().count() as u16;
+        buffer[(x, y as u16)].fg
+    };
+    assert_eq!(color_of("■ edge"), theme.series_color(0));
+    let second = app.targets[0].profiles[1].config.name.clone();
+    assert_eq!(color_of(&format!("■ {second}")), theme.series[5]);
+
+    app.selected_target = 0;
+    app.targets[0].selected_profile = 1;
+    let color = settings_rows(&app)
+        .into_iter()
+        .find(|row| row.field == SettingsField::ProfileColor)
+        .map(|row| row.value);
+    assert_eq!(color.as_deref(), Some("red"));
+    app.targets[0].profiles[1].config.color = None;
+    let color = settings_rows(&app)
+        .into_iter()
+        .find(|row| row.field == SettingsField::ProfileColor)
+        .map(|row| row.value);
+    assert_eq!(color.as_deref(), Some("Auto (yellow)"));
+}
@@ -1,3 +1,16 @@
+use crate::alerts::NotifyRule;
+use crate::app::{MetricsCategory, TargetError, parse_duration, validate_target};
+use crate::config::{
+    AuthConfig, ExpectedStatus, MAX_JITTER_PCT, QuietHours, default_capture_headers,
+};
+use crate::probe::ErrorCategory;
+use crate::settings::Keymap;
+use ratatui::layout::{Position, Rect};
+use ratatui::widgets::ListState;
+use std::collections::BTreeSet;
+use std::time::Duration;
+use url::Url;
+
 /// Minimum terminal width required (columns)
 pub(super) const MIN_TERMINAL_WIDTH: u16 = 100;
 /// Minimum terminal height required (rows)
@@ -8,22 +21,163 @@ pub(super) const GLOSSARY_PAGE_COUNT: usize = 3;
 pub(super) enum SettingsField {
     UiRefreshHz,
     LinkCapacityMbps,
+    QuietHours,
+    Notifications,
+    Retention,
+    MaxPoints,
+    BurstSamples,
+    IncludeFailures,
+    PrimingSamples,
+    MissedSlots,
+    ProxyEnv,
+    DownloadSlots,
+    StatsColumns,
+    ChartStat,
+    Theme,
+    ErrorMarkers,
+    TargetUrl,
+    TargetTags,
     TargetInterval,
     TargetTimeout,
-    TargetDnsEnabled,
+    TargetDnsMode,
+    TargetUnixSocket,
+    TargetConnectTo,
+    TargetInsecure,
+    TargetPerIp,
     TargetPane,
     TargetPaused,
+    TargetAlerts,
+    TargetSloTarget,
+    TargetSloLatency,
+    TargetJitter,
+    TargetSchedule,
+    TargetDuplicate,
+    ProfileName,
+    ProfileColor,
+    ProfileMethod,
+    ProfileHttp,
+    ProfileTls,
+    ProfileReuse,
+    ProfileMaxReadBytes,
+    ProfileRecvSpeed,
+    ProfileInterval,
+    ProfileExpectedStatus,
+    ProfileExpectBody,
+    ProfileCaptureHeaders,
+    ProfileAuth,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(super) enum InputMode {
     Normal,
     AddTarget,
+    ImportTargets,
+    ExportTargets,
+    /// Value of the first of `AppState::pending_headers`
+    HeaderValue,
+    Filter,
     Help,
     Glossary,
     Settings,
     SettingsEdit(SettingsField),
     ConfirmDelete,
+    ConfirmQuit,
+    AlertLog,
+    ErrorLog,
+    ChangeLog,
+    ConnReuse,
+    Spikes,
+    Outages,
+    ChartCursor,
+    MetricsFocus,
+    MetricDetail,
+    Traceroute,
+    BaselineName,
+    Baselines,
+    AnnotationText,
+    Annotations,
+    Snapshot,
+}
+
+/// Scroll positions of the paged overlays, reset when an overlay opens.
+#[derive(Debug, Default)]
+pub(super) struct OverlayState {
+    /// Row of the selected target's metrics table under the cursor, while
+    /// the table has focus; the detail popup shows that row's metric
+    pub metric_cursor: Option<usize>,
+    pub glossary_page: usize,
+    pub error_log_scroll: usize,
+    pub change_log_scroll: usize,
+    pub baseline_cursor: usize,
+    pub annotation_cursor: usize,
+    /// Text of the last copied snapshot
+    pub snapshot: String,
+    /// Why the snapshot could not be copied
+    pub snapshot_error: Option<String>,
+}
+
+/// UI state kept across frames besides `AppState`.
+pub(super) struct ViewState {
+    pub input_mode: InputMode,
+    pub input_buffer: String,
+    pub settings_state: SettingsState,
+    pub overlays: OverlayState,
+    pub target_list_state: ListState,
+    pub hits: HitRegistry,
+    pub keymap: Keymap,
+}
+
+impl ViewState {
+    pub(super) fn new(keymap: Keymap) -> Self {
+        Self {
+            input_mode: InputMode::Normal,
+            input_buffer: String::new(),
+            settings_state: SettingsState::new(),
+            overlays: OverlayState::default(),
+            target_list_state: ListState::default(),
+            hits: HitRegistry::default(),
+            keymap,
+        }
+    }
+}
+
+/// Clickable element recorded by the renderer for mouse hit-testing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum HitTarget {
+    TargetList,
+    TargetRow(usize),
+    /// Header of the group holding this target
+    GroupRow(usize),
+    CategoryTab(MetricsCategory),
+    PaneLabel,
+}
+
+/// Screen regions of clickable elements from the last drawn frame.
+#[derive(Debug, Default)]
+pub(super) struct HitRegistry {
+    regions: Vec<(Rect, HitTarget)>,
+}
+
+impl HitRegistry {
+    pub(super) fn clear(&mut self) {
+        self.regions.clear();
+    }
+
+    pub(super) fn register(&mut self, area: Rect, target: HitTarget) {
+        if area.width > 0 && area.height > 0 {
+            self.regions.push((area, target));
+        }
+    }
+
+    /// The most recently registered region containing the cell, so nested
+    /// elements registered after their container take precedence.
+    pub(super) fn hit(&self, column: u16, row: u16) -> Option<HitTarget> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, target)| *target)
+    }
 }
 
 pub(super) struct SettingsRow {
@@ -37,6 +191,8 @@ pub(super) struct SettingsRow {
 pub(super) struct SettingsState {
     pub(super) selected: usize,
     pub(super) notice: Option<String>,
+    /// URL whose scheme change was flagged and awaits a second Enter
+    pub(super) pending_url: Option<Url>,
 }
 
 impl SettingsState {
@@ -44,6 +200,7 @@ impl SettingsState {
         Self {
             selected: 0,
             notice: None,
+            pending_url: None,
         }
     }
 
@@ -100,9 +257,384 @@ pub(super) fn parse_link_capacity_mbps(input: &str) -> Result<Option<f64>, &'sta
     Ok(Some(value))
 }
 
+pub(super) fn parse_slo_target(input: &str) -> Result<Option<f64>, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+
+    let numeric = normalized.strip_suffix('%').unwrap_or(&normalized);
+    let value = numeric.parse::<f64>().map_err(|_| "Invalid number")?;
+    if value <= 0.0 || value >= 100.0 {
+        return Err("SLO must be between 0 and 100");
+    }
+
+    Ok(Some(value))
+}
+
+pub(super) fn parse_slo_latency_ms(input: &str) -> Result<Option<f64>, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+
+    let value = if let Some(ms) = normalized.strip_suffix("ms") {
+        ms.parse::<f64>().map_err(|_| "Invalid number")?
+    } else if let Some(secs) = normalized.strip_suffix('s') {
+        secs.parse::<f64>().map_err(|_| "Invalid number")? * 1000.0
+    } else {
+        normalized.parse::<f64>().map_err(|_| "Invalid number")?
+    };
+    if !value.is_finite() || value <= 0.0 {
+        return Err("Value must be > 0");
+    }
+
+    Ok(Some(value))
+}
+
+/// Parses a per-profile interval override; blank means "use the target interval".
+pub(super) fn parse_interval_override(input: &str) -> Result<Option<Duration>, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "inherit" {
+        return Ok(None);
+    }
+
+    let value = parse_duration(&normalized).ok_or("Invalid interval value")?;
+    if value.is_zero() {
+        return Err("Interval must be > 0");
+    }
+
+    Ok(Some(value))
+}
+
+/// Longest retention accepted; a week of samples already outgrows any window.
+const MAX_RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Bounds for the per-profile sample cap.
+const MAX_POINTS_RANGE: std::ops::RangeInclusive<usize> = 16..=100_000;
+
+pub(super) fn parse_retention(input: &str) -> Result<Duration, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    let value = parse_duration(&normalized).ok_or("Invalid duration (e.g. 60m, 2h)")?;
+    if value < Duration::from_secs(60) {
+        return Err("Retention must be at least 1m");
+    }
+    if value > MAX_RETENTION {
+        return Err("Retention must be at most 168h");
+    }
+    Ok(value)
+}
+
+pub(super) fn parse_max_points(input: &str) -> Result<usize, &'static str> {
+    let value = input
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| "Invalid number")?;
+    if !MAX_POINTS_RANGE.contains(&value) {
+        return Err("Max points must be between 16 and 100000");
+    }
+    Ok(value)
+}
+
+/// Parses a byte count, with an optional `k` or `m` suffix (KiB, MiB).
+fn parse_byte_size(input: &str) -> Result<u32, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    let (digits, unit) = match normalized.strip_suffix('k') {
+        Some(digits) => (digits, 1024),
+        None => match normalized.strip_suffix('m') {
+            Some(digits) => (digits, 1024 * 1024),
+            None => (normalized.as_str(), 1),
+        },
+    };
+    let value = digits
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| "Invalid byte count")?
+        .checked_mul(unit)
+        .ok_or("Byte count is too large")?;
+    if value == 0 {
+        return Err("Byte count must be > 0");
+    }
+    Ok(value)
+}
+
+/// Parses a body read cap in bytes, with an optional `k` or `m` suffix
+/// (KiB, MiB).
+pub(super) fn parse_max_read_bytes(input: &str) -> Result<u32, &'static str> {
+    parse_byte_size(input)
+}
+
+/// Parses a download speed cap per second (`512k`, `2m/s`); blank lifts it.
+pub(super) fn parse_recv_speed(input: &str) -> Result<Option<u64>, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+    let rate = normalized.strip_suffix("/s").unwrap_or(&normalized);
+    parse_byte_size(rate).map(|bytes| Some(u64::from(bytes)))
+}
+
+/// Parses how many download-heavy probes may run at once; blank lifts the cap.
+pub(super) fn parse_download_slots(input: &str) -> Result<Option<u32>, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+    match normalized.parse::<u32>() {
+        Ok(0) => Err("Slots must be > 0"),
+        Ok(value) => Ok(Some(value)),
+        Err(_) => Err("Invalid number"),
+    }
+}
+
+/// Parses an accepted status (`204`, `2xx`, `200-299`); blank restores the
+/// default of failing on 4xx/5xx.
+pub(super) fn parse_expected_status(input: &str) -> Result<Option<ExpectedStatus>, String> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "any" {
+        return Ok(None);
+    }
+
+    normalized
+        .parse::<ExpectedStatus>()
+        .map(Some)
+        .map_err(|err| format!("Invalid status: {err}"))
+}
+
+pub(super) fn parse_quiet_hours(input: &str) -> Result<Option<QuietHours>, String> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+
+    normalized
+        .parse::<QuietHours>()
+        .map(Some)
+        .map_err(|err| format!("Invalid quiet hours: {err}"))
+}
+
+pub(super) fn parse_notify_rule(input: &str) -> Result<Option<NotifyRule>, String> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(None);
+    }
+
+    normalized
+        .parse::<NotifyRule>()
+        .map(Some)
+        .map_err(|err| format!("Invalid notification rule: {err}"))
+}
+
+/// Category keys to hide, separated by spaces or commas; blank shows all.
+pub(super) fn parse_hidden_error_markers(input: &str) -> Result<BTreeSet<ErrorCategory>, String> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized == "none" || normalized == "off" {
+        return Ok(BTreeSet::new());
+    }
+    normalized
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            ErrorCategory::ALL
+                .into_iter()
+                .find(|category| category.key() == item)
+                .ok_or_else(|| format!("Unknown category {item:?} (timeout, tls, http, other)"))
+        })
+        .collect()
+}
+
+/// Header names to capture: a comma or space separated list, `default` for
+/// the built-in allowlist, or blank/`off` to capture none.
+pub(super) fn parse_capture_headers(input: &str) -> Result<Vec<String>, String> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(Vec::new());
+    }
+    if normalized == "default" {
+        return Ok(default_capture_headers());
+    }
+    let mut names: Vec<String> = Vec::new();
+    for name in normalized
+        .split(|ch: char| ch == ',' || ch.is_whitespace())
+        .filter(|item| !item.is_empty())
+    {
+        if !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            return Err(format!("Invalid header name {name:?}"));
+        }
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// `basic user:password`, `bearer TOKEN`, or blank/`off` to send none.
+pub(super) fn parse_auth(input: &str) -> Result<Option<AuthConfig>, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty()
+        || trimmed.eq_ignore_ascii_case("off")
+        || trimmed.eq_ignore_ascii_case("none")
+    {
+        return Ok(None);
+    }
+    let (scheme, rest) = trimmed.split_once(' ').unwrap_or((trimmed, ""));
+    let auth = match scheme.to_ascii_lowercase().as_str() {
+        "basic" => AuthConfig::basic(rest.trim()).ok_or("Basic auth needs user:password")?,
+        "bearer" => AuthConfig::bearer(rest).ok_or("Bearer auth needs a token")?,
+        _ => return Err(format!("Unknown auth scheme {scheme:?} (basic, bearer)")),
+    };
+    Ok(Some(auth))
+}
+
+pub(super) fn parse_jitter_pct(input: &str) -> Result<u8, &'static str> {
+    let normalized = input.trim().to_ascii_lowercase();
+    if normalized.is_empty() || normalized == "off" || normalized == "none" {
+        return Ok(0);
+    }
+
+    let numeric = normalized.strip_suffix('%').unwrap_or(&normalized);
+    let value = numeric.parse::<u8>().map_err(|_| "Invalid number")?;
+    if value > MAX_JITTER_PCT {
+        return Err("Jitter must be between 0 and 50%");
+    }
+
+    Ok(value)
+}
+
+pub(super) fn parse_url_edit(input: &str) -> Result<Url, &'static str> {
+    validate_target(input).map_err(|err| match err {
+        TargetError::Empty | TargetError::Malformed(_) => "Invalid URL",
+        TargetError::UnsupportedScheme { .. } => "Only http and https URLs are supported",
+        TargetError::MissingHost => "URL needs a host",
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_link_capacity_mbps;
+    use super::{
+        HitRegistry, HitTarget, parse_auth, parse_capture_headers, parse_download_slots,
+        parse_expected_status, parse_hidden_error_markers, parse_interval_override,
+        parse_jitter_pct, parse_link_capacity_mbps, parse_max_points, parse_max_read_bytes,
+        parse_notify_rule, parse_quiet_hours, parse_recv_speed, parse_retention,
+        parse_slo_latency_ms, parse_slo_target, parse_url_edit,
+    };
+    use crate::app::MetricsCategory;
+    use crate::probe::ErrorCategory;
+    use ratatui::layout::Rect;
+    use std::time::Duration;
+
+    #[test]
+    fn hit_registry_prefers_nested_regions() {
+        let mut hits = HitRegistry::default();
+        hits.register(Rect::new(0, 0, 32, 20), HitTarget::TargetList);
+        hits.register(Rect::new(1, 1, 30, 1), HitTarget::TargetRow(0));
+        hits.register(Rect::new(1, 2, 30, 1), HitTarget::TargetRow(1));
+        hits.register(
+            Rect::new(40, 10, 9, 1),
+            HitTarget::CategoryTab(MetricsCategory::Quality),
+        );
+
+        assert_eq!(hits.hit(5, 2), Some(HitTarget::TargetRow(1)));
+        assert_eq!(hits.hit(5, 10), Some(HitTarget::TargetList));
+        assert_eq!(
+            hits.hit(48, 10),
+            Some(HitTarget::CategoryTab(MetricsCategory::Quality))
+        );
+        assert_eq!(hits.hit(49, 10), None);
+
+        hits.clear();
+        assert_eq!(hits.hit(5, 2), None);
+    }
+
+    #[test]
+    fn parse_max_read_bytes_takes_kib_and_mib_suffixes() {
+        assert_eq!(parse_max_read_bytes("4096"), Ok(4096));
+        assert_eq!(parse_max_read_bytes(" 64K "), Ok(64 * 1024));
+        assert_eq!(parse_max_read_bytes("2m"), Ok(2 * 1024 * 1024));
+        assert!(parse_max_read_bytes("0").is_err());
+        assert!(parse_max_read_bytes("lots").is_err());
+        assert!(parse_max_read_bytes("8000000m").is_err());
+    }
+
+    #[test]
+    fn download_caps_take_suffixes_and_blank_lifts_them() {
+        assert_eq!(parse_recv_speed("512k"), Ok(Some(512 * 1024)));
+        assert_eq!(parse_recv_speed(" 2M/s "), Ok(Some(2 * 1024 * 1024)));
+        assert_eq!(parse_recv_speed(""), Ok(None));
+        assert_eq!(parse_recv_speed("off"), Ok(None));
+        assert!(parse_recv_speed("0").is_err());
+        assert!(parse_recv_speed("fast").is_err());
+
+        assert_eq!(parse_download_slots("2"), Ok(Some(2)));
+        assert_eq!(parse_download_slots(" "), Ok(None));
+        assert!(parse_download_slots("0").is_err());
+        assert!(parse_download_slots("-1").is_err());
+    }
+
+    #[test]
+    fn parse_url_edit_validates_scheme_and_host() {
+        assert_eq!(
+            parse_url_edit("api.example.com/health").unwrap().as_str(),
+            "https://api.example.com/health"
+        );
+        assert_eq!(
+            parse_url_edit("http://localhost:8080").unwrap().scheme(),
+            "http"
+        );
+        assert!(parse_url_edit("").is_err());
+        assert!(parse_url_edit("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn parse_hidden_error_markers_reads_category_keys() {
+        assert!(parse_hidden_error_markers("").unwrap().is_empty());
+        assert!(parse_hidden_error_markers("none").unwrap().is_empty());
+        assert_eq!(
+            parse_hidden_error_markers("TLS, other http").unwrap(),
+            [
+                ErrorCategory::Tls,
+                ErrorCategory::HttpStatus,
+                ErrorCategory::Other
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert!(parse_hidden_error_markers("tls dns").is_err());
+    }
+
+    #[test]
+    fn parse_auth_reads_basic_and_bearer() {
+        assert_eq!(parse_auth(" off "), Ok(None));
+        assert_eq!(
+            parse_auth("basic alice:pa ss").unwrap(),
+            crate::config::AuthConfig::basic("alice:pa ss")
+        );
+        assert_eq!(
+            parse_auth("Bearer tok").unwrap(),
+            crate::config::AuthConfig::bearer("tok")
+        );
+        assert!(parse_auth("basic alice").is_err());
+        assert!(parse_auth("bearer").is_err());
+        assert!(parse_auth("digest a:b").is_err());
+    }
+
+    #[test]
+    fn parse_capture_headers_lowercases_and_dedups() {
+        assert!(parse_capture_headers("").unwrap().is_empty());
+        assert!(parse_capture_headers("off").unwrap().is_empty());
+        assert_eq!(
+            parse_capture_headers("default").unwrap(),
+            crate::config::default_capture_headers()
+        );
+        assert_eq!(
+            parse_capture_headers("X-Cache, age x-cache").unwrap(),
+            vec!["x-cache".to_string(), "age".to_string()]
+        );
+        assert!(parse_capture_headers("x-cache: hit").is_err());
+    }
 
     #[test]
     fn parse_link_capacity_allows_off_values() {
@@ -123,4 +655,105 @@ mod tests {
         assert!(parse_link_capacity_mbps("-1").is_err());
         assert!(parse_link_capacity_mbps("abc").is_err());
     }
+
+    #[test]
+    fn parse_slo_target_accepts_percentages() {
+        assert_eq!(parse_slo_target("99.5").unwrap(), Some(99.5));
+        assert_eq!(parse_slo_target("99.9%").unwrap(), Some(99.9));
+        assert_eq!(parse_slo_target("off").unwrap(), None);
+        assert!(parse_slo_target("100").is_err());
+        assert!(parse_slo_target("abc").is_err());
+    }
+
+    #[test]
+    fn parse_slo_latency_accepts_units() {
+        assert_eq!(parse_slo_latency_ms("800ms").unwrap(), Some(800.0));
+        assert_eq!(parse_slo_latency_ms("1.5s").unwrap(), Some(1500.0));
+        assert_eq!(parse_slo_latency_ms("250").unwrap(), Some(250.0));
+        assert_eq!(parse_slo_latency_ms("").unwrap(), None);
+        assert!(parse_slo_latency_ms("0").is_err());
+        assert!(parse_slo_latency_ms("-5ms").is_err());
+        assert!(parse_slo_latency_ms("NaN").is_err());
+        assert!(parse_slo_latency_ms("inf").is_err());
+        assert!(parse_slo_latency_ms("1e400s").is_err());
+    }
+
+    #[test]
+    fn parse_interval_override_allows_inherit() {
+        assert_eq!(parse_interval_override("").unwrap(), None);
+        assert_eq!(parse_interval_override("inherit").unwrap(), None);
+        assert_eq!(
+            parse_interval_override("2s").unwrap(),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            parse_interval_override("500ms").unwrap(),
+            Some(Duration::from_millis(500))
+        );
+        assert!(parse_interval_override("0s").is_err());
+        assert!(parse_interval_override("soon").is_err());
+    }
+
+    #[test]
+    fn parse_retention_and_max_points_validate_bounds() {
+        assert_eq!(parse_retention("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_retention("90m").unwrap(), Duration::from_secs(5400));
+        assert!(parse_retention("30s").is_err());
+        assert!(parse_retention("forever").is_err());
+        assert_eq!(parse_retention("168h").unwrap(), super::MAX_RETENTION);
+        assert!(parse_retention("169h").is_err());
+        assert!(parse_retention("999999999999999999h").is_err());
+
+        assert_eq!(parse_max_points("4096").unwrap(), 4096);
+        assert!(parse_max_points("8").is_err());
+        assert!(parse_max_points("lots").is_err());
+    }
+
+    #[test]
+    fn parse_expected_status_allows_off_and_ranges() {
+        assert_eq!(parse_expected_status("").unwrap(), None);
+        assert_eq!(parse_expected_status("any").unwrap(), None);
+        assert_eq!(
+            parse_expected_status("2xx")
+                .unwrap()
+                .map(|status| status.to_string()),
+            Some("2xx".to_string())
+        );
+        assert!(parse_expected_status("abc").is_err());
+    }
+
+    #[test]
+    fn parse_quiet_hours_allows_off() {
+        assert_eq!(parse_quiet_hours("").unwrap(), None);
+        assert_eq!(parse_quiet_hours("off").unwrap(), None);
+        assert_eq!(
+            parse_quiet_hours("22:00-07:00")
+                .unwrap()
+                .map(|q| q.to_string()),
+            Some("22:00-07:00".to_string())
+        );
+        assert!(parse_quiet_hours("late").is_err());
+    }
+
+    #[test]
+    fn parse_notify_rule_allows_off() {
+        assert_eq!(parse_notify_rule("").unwrap(), None);
+        assert_eq!(parse_notify_rule("Off").unwrap(), None);
+        assert_eq!(
+            parse_notify_rule("25% x4")
+                .unwrap()
+                .map(|rule| rule.to_string()),
+            Some("25% x4".to_string())
+        );
+        assert!(parse_notify_rule("x4").is_err());
+    }
+
+    #[test]
+    fn parse_jitter_pct_is_bounded() {
+        assert_eq!(parse_jitter_pct("").unwrap(), 0);
+        assert_eq!(parse_jitter_pct("10%").unwrap(), 10);
+        assert_eq!(parse_jitter_pct("25").unwrap(), 25);
+        assert!(parse_jitter_pct("75").is_err());
+        assert!(parse_jitter_pct("-5").is_err());
+    }
 }
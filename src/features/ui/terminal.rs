@@ -0,0 +1,223 @@
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use crossterm::execute;
+use crossterm::terminal::{
+    self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use std::fmt;
+use std::io::{self, IsTerminal};
+use thiserror::Error;
+
+/// One change the UI makes to the terminal, in the order it is made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetupStep {
+    RawMode,
+    AlternateScreen,
+    MouseCapture,
+}
+
+impl SetupStep {
+    const ALL: [SetupStep; 3] = [
+        SetupStep::RawMode,
+        SetupStep::AlternateScreen,
+        SetupStep::MouseCapture,
+    ];
+}
+
+impl fmt::Display for SetupStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SetupStep::RawMode => "enable raw mode",
+            SetupStep::AlternateScreen => "enter the alternate screen",
+            SetupStep::MouseCapture => "enable mouse capture",
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TerminalError {
+    #[error("{stream} is not a terminal; use --once for a one-shot report instead")]
+    NotATerminal { stream: &'static str },
+    #[error("TERM={term} cannot draw the UI; run in a full terminal or use --once")]
+    Unsupported { term: String },
+    #[error("cannot read the terminal size: {0}")]
+    Size(io::Error),
+    #[error("cannot {step}: {source}")]
+    Setup {
+        step: SetupStep,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl From<TerminalError> for io::Error {
+    fn from(err: TerminalError) -> Self {
+        io::Error::other(err)
+    }
+}
+
+/// Checks that the UI can take over the terminal: stdin and stdout are
+/// ttys, `TERM` is not `dumb` and the terminal reports a size. Run before
+/// anything else touches the terminal, so a pipe or a dumb terminal gets a
+/// clear message instead of a half-drawn screen.
+pub fn check_terminal() -> Result<(), TerminalError> {
+    if !io::stdout().is_terminal() {
+        return Err(TerminalError::NotATerminal { stream: "stdout" });
+    }
+    if !io::stdin().is_terminal() {
+        return Err(TerminalError::NotATerminal { stream: "stdin" });
+    }
+    if let Ok(term) = std::env::var("TERM")
+        && term == "dumb"
+    {
+        return Err(TerminalError::Unsupported { term });
+    }
+    terminal::size().map_err(TerminalError::Size)?;
+    Ok(())
+}
+
+/// Terminal changes the UI makes, split out so setup and teardown can be
+/// tested without a terminal.
+pub(super) trait TerminalOps {
+    fn apply(&mut self, step: SetupStep) -> io::Result<()>;
+    fn undo(&mut self, step: SetupStep) -> io::Result<()>;
+}
+
+/// The real terminal, through crossterm.
+pub(super) struct Crossterm;
+
+impl TerminalOps for Crossterm {
+    fn apply(&mut self, step: SetupStep) -> io::Result<()> {
+        match step {
+            SetupStep::RawMode => enable_raw_mode(),
+            SetupStep::AlternateScreen => execute!(io::stdout(), EnterAlternateScreen),
+            SetupStep::MouseCapture => execute!(io::stdout(), EnableMouseCapture),
+        }
+    }
+
+    fn undo(&mut self, step: SetupStep) -> io::Result<()> {
+        match step {
+            SetupStep::RawMode => disable_raw_mode(),
+            SetupStep::AlternateScreen => execute!(io::stdout(), LeaveAlternateScreen),
+            SetupStep::MouseCapture => execute!(io::stdout(), DisableMouseCapture),
+        }
+    }
+}
+
+/// Makes every [`SetupStep`] in order. If one fails, the steps already made
+/// are undone, newest first, so the terminal is left as it was found.
+pub(super) fn setup_terminal(ops: &mut impl TerminalOps) -> Result<(), TerminalError> {
+    for (done, step) in SetupStep::ALL.into_iter().enumerate() {
+        if let Err(source) = ops.apply(step) {
+            for applied in SetupStep::ALL[..done].iter().rev() {
+                let _ = ops.undo(*applied);
+            }
+            return Err(TerminalError::Setup { step, source });
+        }
+    }
+    Ok(())
+}
+
+/// Undoes every [`SetupStep`], newest first. Keeps going past a failed step
+/// so one error does not leave the rest applied; returns the first error.
+pub(super) fn restore_terminal(ops: &mut impl TerminalOps) -> io::Result<()> {
+    let mut result = Ok(());
+    for step in SetupStep::ALL.into_iter().rev() {
+        if let Err(err) = ops.undo(step)
+            && result.is_ok()
+        {
+            result = Err(err);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Apply(SetupStep),
+        Undo(SetupStep),
+    }
+
+    #[derive(Default)]
+    struct MockTerminal {
+        calls: Vec<Call>,
+        fail_apply: Option<SetupStep>,
+        fail_undo: Option<SetupStep>,
+    }
+
+    impl TerminalOps for MockTerminal {
+        fn apply(&mut self, step: SetupStep) -> io::Result<()> {
+            self.calls.push(Call::Apply(step));
+            if self.fail_apply == Some(step) {
+                return Err(io::Error::other("unsupported"));
+            }
+            Ok(())
+        }
+
+        fn undo(&mut self, step: SetupStep) -> io::Result<()> {
+            self.calls.push(Call::Undo(step));
+            if self.fail_undo == Some(step) {
+                return Err(io::Error::other("gone"));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_setup_step_rolls_back_the_earlier_steps_newest_first() {
+        let mut terminal = MockTerminal {
+            fail_apply: Some(SetupStep::MouseCapture),
+            ..MockTerminal::default()
+        };
+        let err = setup_terminal(&mut terminal).unwrap_err();
+        assert_eq!(err.to_string(), "cannot enable mouse capture: unsupported");
+        assert_eq!(
+            terminal.calls,
+            [
+                Call::Apply(SetupStep::RawMode),
+                Call::Apply(SetupStep::AlternateScreen),
+                Call::Apply(SetupStep::MouseCapture),
+                Call::Undo(SetupStep::AlternateScreen),
+                Call::Undo(SetupStep::RawMode),
+            ]
+        );
+
+        // Nothing was applied before the first step, so nothing is undone
+        let mut terminal = MockTerminal {
+            fail_apply: Some(SetupStep::RawMode),
+            ..MockTerminal::default()
+        };
+        let err = setup_terminal(&mut terminal).unwrap_err();
+        assert!(
+            err.to_string().starts_with("cannot enable raw mode"),
+            "{err}"
+        );
+        assert_eq!(terminal.calls, [Call::Apply(SetupStep::RawMode)]);
+    }
+
+    #[test]
+    fn restore_undoes_every_step_even_after_a_failure() {
+        let mut terminal = MockTerminal {
+            fail_undo: Some(SetupStep::MouseCapture),
+            ..MockTerminal::default()
+        };
+        setup_terminal(&mut terminal).unwrap();
+        terminal.calls.clear();
+
+        assert_eq!(
+            restore_terminal(&mut terminal).unwrap_err().to_string(),
+            "gone"
+        );
+        assert_eq!(
+            terminal.calls,
+            [
+                Call::Undo(SetupStep::MouseCapture),
+                Call::Undo(SetupStep::AlternateScreen),
+                Call::Undo(SetupStep::RawMode),
+            ]
+        );
+    }
+}
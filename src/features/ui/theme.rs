@@ -0,0 +1,149 @@
+use crate::app::AppState;
+use crate::config::{ProfileConfig, ThemeName};
+use ratatui::style::{Color, Modifier, Style};
+
+/// Names of the profile palette's colors, as the dark theme draws them.
+pub(super) const SERIES_NAMES: [&str; 6] = ["cyan", "yellow", "green", "magenta", "blue", "red"];
+
+/// Colors the UI draws with, by role. Render code takes every color from
+/// here instead of naming one, so a theme restyles the whole UI.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Theme {
+    /// Regular text
+    pub fg: Color,
+    /// Popup and overlay background
+    pub bg: Color,
+    /// Labels, borders and other secondary text
+    pub muted: Color,
+    /// Hints a step above `muted`
+    pub subtle: Color,
+    /// Section titles and the first profile
+    pub accent: Color,
+    pub success: Color,
+    /// Warnings and the item under the cursor
+    pub warn: Color,
+    pub error: Color,
+    /// Spikes and firing alerts, set apart from plain errors
+    pub alert: Color,
+    /// Baselines, groups and other secondary series
+    pub secondary: Color,
+    pub info: Color,
+    /// Profile colors, in profile order
+    pub series: [Color; 6],
+    /// Request phases, dns to download; kept apart from the profile colors
+    pub phases: [Color; 5],
+    /// Row or list item under the cursor
+    pub selection: Style,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        fg: Color::White,
+        bg: Color::Black,
+        muted: Color::DarkGray,
+        subtle: Color::Gray,
+        accent: Color::Cyan,
+        success: Color::Green,
+        warn: Color::Yellow,
+        error: Color::Red,
+        alert: Color::LightRed,
+        secondary: Color::Magenta,
+        info: Color::Blue,
+        series: [
+            Color::Cyan,
+            Color::Yellow,
+            Color::Green,
+            Color::Magenta,
+            Color::Blue,
+            Color::Red,
+        ],
+        phases: [
+            Color::Indexed(67),
+            Color::Indexed(108),
+            Color::Indexed(139),
+            Color::Indexed(180),
+            Color::Indexed(216),
+        ],
+        selection: Style::new().bg(Color::DarkGray),
+    };
+
+    /// Dark text on the terminal's own background; yellow and cyan, which
+    /// wash out on white, become darker shades from the 256-color palette.
+    pub const LIGHT: Theme = Theme {
+        fg: Color::Black,
+        bg: Color::Reset,
+        muted: Color::DarkGray,
+        subtle: Color::DarkGray,
+        accent: Color::Indexed(25),
+        success: Color::Indexed(28),
+        warn: Color::Indexed(130),
+        error: Color::Indexed(160),
+        alert: Color::Indexed(124),
+        secondary: Color::Indexed(90),
+        info: Color::Indexed(19),
+        series: [
+            Color::Indexed(25),
+            Color::Indexed(130),
+            Color::Indexed(28),
+            Color::Indexed(90),
+            Color::Indexed(30),
+            Color::Indexed(160),
+        ],
+        phases: [
+            Color::Indexed(60),
+            Color::Indexed(65),
+            Color::Indexed(96),
+            Color::Indexed(136),
+            Color::Indexed(166),
+        ],
+        selection: Style::new().bg(Color::Indexed(252)),
+    };
+
+    /// No color at all; the cursor is shown reversed.
+    pub const MONO: Theme = Theme {
+        fg: Color::Reset,
+        bg: Color::Reset,
+        muted: Color::Reset,
+        subtle: Color::Reset,
+        accent: Color::Reset,
+        success: Color::Reset,
+        warn: Color::Reset,
+        error: Color::Reset,
+        alert: Color::Reset,
+        secondary: Color::Reset,
+        info: Color::Reset,
+        series: [Color::Reset; 6],
+        phases: [Color::Reset; 5],
+        selection: Style::new().add_modifier(Modifier::REVERSED),
+    };
+
+    pub fn named(name: ThemeName) -> &'static Theme {
+        match name {
+            // `AppState::theme` is resolved; an unresolved one draws dark
+            ThemeName::Auto | ThemeName::Dark => &Theme::DARK,
+            ThemeName::Light => &Theme::LIGHT,
+            ThemeName::Mono => &Theme::MONO,
+        }
+    }
+
+    /// Theme `app` is drawn with.
+    pub fn of(app: &AppState) -> &'static Theme {
+        Theme::named(app.theme)
+    }
+
+    /// Color of the profile at `idx`.
+    pub fn series_color(&self, idx: usize) -> Color {
+        self.series[idx % self.series.len()]
+    }
+
+    /// Color of the request phase at `idx`, in `PHASE_METRICS` order.
+    pub fn phase_color(&self, idx: usize) -> Color {
+        self.phases[idx % self.phases.len()]
+    }
+
+    /// Color of `profile`, drawn at `idx`: the palette color it was given,
+    /// otherwise the one for its position.
+    pub fn profile_color(&self, idx: usize, profile: &ProfileConfig) -> Color {
+        self.series_color(profile.color.map_or(idx, usize::from))
+    }
+}
@@ -1,14 +1,18 @@
 mod common;
 mod features;
 
+pub mod alerts;
 pub mod app;
 pub mod config;
 pub mod data_model;
+pub mod ebpf;
 pub mod metrics;
 pub mod metrics_aggregate;
 pub mod probe;
 pub mod probe_engine;
 pub mod runtime;
+pub mod session;
 pub mod settings;
 pub mod storage;
+pub mod traceroute;
 pub mod ui;
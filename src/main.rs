@@ -1,40 +1,367 @@
-use httpulse::app::{AppState, parse_target_url};
-use httpulse::settings::{apply_global, load_from_cli};
+#[cfg(unix)]
+use httpulse::app::install_status_dump_signal;
+use httpulse::app::{AppState, SharedTargets, TargetLine, parse_targets_file, validate_target};
+use httpulse::config::{
+    EbpfMode, ProbeBackendKind, TargetConfig, default_profiles_for_capabilities,
+};
+use httpulse::data_model::settings::AppSettings;
+use httpulse::ebpf::start_ebpf;
+use httpulse::probe::ProbeResult;
+use httpulse::probe_engine::{
+    create_backend, detect_h2_support, format_once_table, once_exit_code, once_json, run_once,
+    set_use_proxy_env,
+};
+use httpulse::runtime::{ProbeSource, sample_channel, set_download_slots};
+use httpulse::session::{SessionRecorder, load_recording, spawn_replay};
+use httpulse::settings::{apply_global, init_file_logging, load_from_cli, resolve_keymap};
 use httpulse::storage;
-use httpulse::ui::run_ui;
+use httpulse::ui::{check_terminal, run_ui};
+use std::path::Path;
+use std::sync::Arc;
 
 fn main() -> std::io::Result<()> {
-    let settings = load_from_cli()
-        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string()))?;
+    // Reported before the alternate screen takes over the terminal
+    let settings = match load_from_cli() {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("httpulse: {err}");
+            std::process::exit(2);
+        }
+    };
+    for skipped in &settings.skipped_targets {
+        eprintln!("httpulse: skipping invalid target {skipped}");
+    }
+    if let Some(path) = &settings.log_file {
+        init_file_logging(path, &settings.log_filter)
+            .map_err(|err| std::io::Error::other(err.to_string()))?;
+    }
 
-    let persisted = storage::load();
+    let persisted = storage::load().map_err(|err| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("state file: {err}"),
+        )
+    })?;
+    #[cfg(unix)]
+    install_status_dump_signal();
 
     let mut global = persisted.global_config.clone();
     apply_global(&settings, &mut global);
+    let keymap = resolve_keymap(&global)
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string()))?;
+
+    // Every other mode draws the UI; a pipe or a dumb terminal is reported
+    // here rather than as an error from halfway through terminal setup
+    if !settings.once
+        && settings.export_targets.is_none()
+        && let Err(err) = check_terminal()
+    {
+        eprintln!("httpulse: {err}");
+        std::process::exit(2);
+    }
+
+    if let Some(path) = &settings.replay {
+        let recording = load_recording(path)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string()))?;
+        global.ebpf_mode = EbpfMode::Off;
+        global.ebpf_enabled = false;
+        let (sample_tx, sample_rx) = sample_channel();
+        let mut app = AppState::new(global);
+        app.load_replay(recording.targets);
+        app.load_annotations(recording.annotations);
+        spawn_replay(recording.samples, settings.replay_speed, sample_tx.clone());
+        return report_ui_error(run_ui(app, keymap, sample_rx, sample_tx, None));
+    }
+
+    if settings.once {
+        set_use_proxy_env(global.use_proxy_env);
+        std::process::exit(once(&settings, &persisted));
+    }
+
+    if let Some(path) = &settings.export_targets {
+        std::process::exit(export_targets(&settings, &persisted, path));
+    }
+
+    if settings.demo.is_some() {
+        // Synthetic samples carry no kernel data
+        global.ebpf_mode = EbpfMode::Off;
+        global.ebpf_enabled = false;
+    }
+
+    set_use_proxy_env(global.use_proxy_env);
+    // Runs before the app exists, so no worker probes a target that fails it
+    if settings.preflight
+        && !preflight(
+            &startup_target_configs(&settings, &persisted),
+            global.backend,
+        )
+    {
+        std::process::exit(1);
+    }
+
+    // A collector that cannot load degrades to off instead of aborting startup
+    let ebpf_notice = start_ebpf(global.ebpf_mode).err().map(|err| {
+        global.ebpf_mode = EbpfMode::Off;
+        global.ebpf_enabled = false;
+        err.to_string()
+    });
 
-    let (sample_tx, sample_rx) = crossbeam_channel::unbounded();
+    set_download_slots(global.download_slots);
+    let (sample_tx, sample_rx) = sample_channel();
     let mut app = AppState::new(global);
+    app.ebpf_notice = ebpf_notice;
+    if let Some(seed) = settings.demo {
+        app.probe_source = ProbeSource::Demo(seed);
+    }
 
-    let is_default_target =
-        settings.targets.len() == 1 && settings.targets[0] == "https://google.com";
-    let has_cli_targets = !settings.targets.is_empty() && !is_default_target;
+    let has_cli_targets = settings.targets_from_cli && !settings.targets.is_empty();
 
     if has_cli_targets {
         for target in settings.targets {
-            if let Some(url) = parse_target_url(&target) {
-                app.add_target(url, None, sample_tx.clone());
+            if let Ok(url) = validate_target(&target) {
+                app.add_cli_target(
+                    url,
+                    settings.auth.clone(),
+                    settings.insecure,
+                    sample_tx.clone(),
+                );
             }
         }
     } else if !persisted.targets.is_empty() {
         app.restore_from_persisted(&persisted, sample_tx.clone());
-    } else {
+    } else if settings.targets_file.is_none() && settings.import_targets.is_none() {
         for target in settings.targets {
-            if let Some(url) = parse_target_url(&target) {
+            if let Ok(url) = validate_target(&target) {
                 app.add_target(url, None, sample_tx.clone());
             }
         }
     }
 
-    run_ui(app, sample_rx, sample_tx)?;
+    if settings.restore {
+        restore_history(&mut app);
+    }
+
+    if let Some(path) = &settings.targets_file {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("httpulse: cannot read {}: {err}", path.display());
+                std::process::exit(2);
+            }
+        };
+        let summary = app.import_targets(
+            parse_targets_file(&content),
+            settings.allow_duplicates,
+            settings.auth.as_ref(),
+            &sample_tx,
+        );
+        eprintln!("httpulse: {}: {summary}", path.display());
+    }
+
+    if let Some(path) = &settings.import_targets {
+        let document = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| SharedTargets::from_json(&content).map_err(|err| err.to_string()));
+        let document = match document {
+            Ok(document) => document,
+            Err(err) => {
+                eprintln!("httpulse: {}: {err}", path.display());
+                std::process::exit(2);
+            }
+        };
+        let summary = app.import_shared_targets(document, settings.auth.as_ref(), &sample_tx);
+        eprintln!("httpulse: {}: {summary}", path.display());
+    }
+
+    if settings.demo.is_none() {
+        app.start_tls13_detection();
+    }
+
+    let recorder = match &settings.record {
+        Some(path) => {
+            let targets: Vec<_> = app.targets.iter().map(|t| t.config.clone()).collect();
+            Some(
+                SessionRecorder::create(path, &targets)
+                    .map_err(|err| std::io::Error::other(err.to_string()))?,
+            )
+        }
+        None => None,
+    };
+
+    report_ui_error(run_ui(app, keymap, sample_rx, sample_tx, recorder))
+}
+
+/// Prints a UI error as a message instead of the `io::Error` debug dump
+/// `main` would print; the terminal is restored by then.
+fn report_ui_error(result: std::io::Result<()>) -> std::io::Result<()> {
+    if let Err(err) = result {
+        eprintln!("httpulse: {err}");
+        std::process::exit(1);
+    }
     Ok(())
 }
+
+/// The `--target` targets, or the saved targets when none was given, as
+/// configs; nothing is spawned for them.
+fn startup_target_configs(
+    settings: &AppSettings,
+    persisted: &storage::PersistedState,
+) -> Vec<TargetConfig> {
+    if !settings.targets_from_cli && !persisted.targets.is_empty() {
+        return persisted
+            .targets
+            .iter()
+            .map(|target| target.config.clone())
+            .collect();
+    }
+    settings
+        .targets
+        .iter()
+        .filter_map(|target| validate_target(target).ok())
+        .map(|url| {
+            // TLS 1.3 is only known after a detection probe; stay on the 1.2 defaults
+            let mut profiles = default_profiles_for_capabilities(false, detect_h2_support());
+            for profile in &mut profiles {
+                profile.auth.clone_from(&settings.auth);
+            }
+            let mut target = TargetConfig::new(url, profiles);
+            target.insecure = settings.insecure;
+            target
+        })
+        .collect()
+}
+
+/// Probes every profile of the `--target` targets once, or of the saved
+/// targets when none was given, prints the report to stdout and returns the
+/// exit code.
+fn once(settings: &AppSettings, persisted: &storage::PersistedState) -> i32 {
+    let targets = startup_target_configs(settings, persisted);
+    if targets.is_empty() {
+        eprintln!("httpulse: no targets to probe");
+        return once_exit_code(&[]);
+    }
+
+    let results = run_once(&targets);
+    if settings.json {
+        println!("{}", once_json(&results));
+    } else {
+        print!("{}", format_once_table(&results));
+    }
+    once_exit_code(&results)
+}
+
+/// Writes the definitions of the targets a normal start would probe, merged
+/// with `--targets-file` and `--import-targets` under the same duplicate
+/// rules, to `path` and returns the exit code. Works on configs alone, so no
+/// probe runs.
+fn export_targets(settings: &AppSettings, persisted: &storage::PersistedState, path: &Path) -> i32 {
+    let merging = settings.targets_file.is_some() || settings.import_targets.is_some();
+    let mut configs = if merging && !settings.targets_from_cli && persisted.targets.is_empty() {
+        Vec::new()
+    } else {
+        startup_target_configs(settings, persisted)
+    };
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|err| {
+            eprintln!("httpulse: cannot read {}: {err}", path.display());
+        })
+    };
+    if let Some(file) = &settings.targets_file {
+        let Ok(content) = read(file) else {
+            return 2;
+        };
+        let (lines, summary) = parse_targets_file(&content)
+            .into_new_lines(settings.allow_duplicates, |url| {
+                configs.iter().any(|config| &config.url == url)
+            });
+        configs.extend(lines.into_iter().map(TargetLine::into_config));
+        eprintln!("httpulse: {}: {summary}", file.display());
+    }
+    if let Some(file) = &settings.import_targets {
+        let Ok(content) = read(file) else {
+            return 2;
+        };
+        let document = match SharedTargets::from_json(&content) {
+            Ok(document) => document,
+            Err(err) => {
+                eprintln!("httpulse: {}: {err}", file.display());
+                return 2;
+            }
+        };
+        let (shared, summary) =
+            document.into_configs(|url| configs.iter().any(|config| &config.url == url));
+        configs.extend(shared);
+        eprintln!("httpulse: {}: {summary}", file.display());
+    }
+
+    let document = SharedTargets::from_configs(&configs);
+    if let Err(err) = std::fs::write(path, document.to_json()) {
+        eprintln!("httpulse: cannot write {}: {err}", path.display());
+        return 2;
+    }
+    eprintln!(
+        "httpulse: exported {} targets to {}",
+        document.targets.len(),
+        path.display()
+    );
+    0
+}
+
+/// Probes every target once with its first profile through `backend`, all
+/// in parallel, and prints the outcome of each to stderr. True when every
+/// probe succeeded.
+fn preflight(targets: &[TargetConfig], backend: ProbeBackendKind) -> bool {
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let probes: Vec<_> = targets
+            .iter()
+            .map(|target| {
+                scope.spawn(move || {
+                    let profile = target
+                        .profiles
+                        .first()
+                        .ok_or_else(|| "no profiles".to_string())?;
+                    let mut sampler =
+                        create_backend(backend, Arc::default()).map_err(|err| err.to_string())?;
+                    let sample = sampler.sample(target, profile, None);
+                    match sample.result {
+                        ProbeResult::Ok => Ok(sample.t_total),
+                        ProbeResult::Err(err) => Err(err.to_string()),
+                    }
+                })
+            })
+            .collect();
+        probes
+            .into_iter()
+            .map(|probe| {
+                probe
+                    .join()
+                    .unwrap_or_else(|_| Err("probe panicked".to_string()))
+            })
+            .collect()
+    });
+
+    let mut reachable = true;
+    for (target, result) in targets.iter().zip(results) {
+        match result {
+            Ok(total) => eprintln!("preflight ok    {} ({total:.0?})", target.url),
+            Err(err) => {
+                reachable = false;
+                eprintln!("preflight FAIL  {}: {err}", target.url);
+            }
+        }
+    }
+    reachable
+}
+
+/// Reloads the samples saved on the last quit; a missing or unreadable file
+/// only costs the history, so startup goes on either way.
+fn restore_history(app: &mut AppState) {
+    let Some(path) = storage::history_file_path() else {
+        return;
+    };
+    match app.restore_history(&path) {
+        Ok(count) => eprintln!("httpulse: restored {count} samples from {}", path.display()),
+        Err(err) => eprintln!("httpulse: cannot restore {}: {err}", path.display()),
+    }
+}
@@ -1 +1,2 @@
+pub use crate::features::probe::engine::{ProbeOptions, probe_once};
 pub use crate::features::probe::model::*;
@@ -0,0 +1 @@
+pub use crate::features::session::*;
@@ -0,0 +1,643 @@
+use crate::config::KeyBinding;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Something a key does in the main view. Popups close on Esc or on the
+/// action that opened them; keys local to a popup (y/n, h/l) stay fixed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Glossary,
+    Settings,
+    EditTarget,
+    AddTarget,
+    ImportTargets,
+    ExportTargets,
+    Filter,
+    ClearFilter,
+    DeleteTarget,
+    DuplicateTarget,
+    Pause,
+    PauseAll,
+    Burst,
+    CompareToggle,
+    /// Per-IP streams: profile streams, all IPs overlaid, then each IP
+    CycleEndpoint,
+    ConnReuse,
+    CyclePane,
+    Overview,
+    /// Pin the selected target into a second pane, or unpin
+    SplitView,
+    SplitFocus,
+    OverviewSort,
+    OverviewReverse,
+    /// Enter: open the overview row, fold the group or show the error log
+    Activate,
+    ChartCursor,
+    /// Chart cursor: one plotted sample back or forward
+    CursorOlder,
+    CursorNewer,
+    /// Chart cursor: the oldest or newest plotted sample
+    CursorOldest,
+    CursorNewest,
+    /// Move the cursor into the metrics table; Enter there opens the detail
+    FocusMetrics,
+    CaptureBaseline,
+    Baselines,
+    Annotate,
+    Annotations,
+    CycleWindow,
+    TargetWindow,
+    TimeAxis,
+    LogScale,
+    MoveTargetUp,
+    MoveTargetDown,
+    Up,
+    Down,
+    NextProfile,
+    /// Chart series 1-8, in the order listed in the help
+    ToggleMetric(u8),
+    NextCategory,
+    PrevCategory,
+    /// Copy the selected target's chart metrics and category to every target
+    CopyView,
+    AlertLog,
+    /// History of user changes to targets and probing
+    ChangeLog,
+    Spikes,
+    Outages,
+    CopySnapshot,
+    DumpStatus,
+    ErrorLog,
+    Traceroute,
+}
+
+impl Action {
+    pub const ALL: [Action; 63] = [
+        Action::Quit,
+        Action::Help,
+        Action::Glossary,
+        Action::Settings,
+        Action::EditTarget,
+        Action::AddTarget,
+        Action::ImportTargets,
+        Action::ExportTargets,
+        Action::Filter,
+        Action::ClearFilter,
+        Action::DeleteTarget,
+        Action::DuplicateTarget,
+        Action::Pause,
+        Action::PauseAll,
+        Action::Burst,
+        Action::CompareToggle,
+        Action::CycleEndpoint,
+        Action::ConnReuse,
+        Action::CyclePane,
+        Action::Overview,
+        Action::SplitView,
+        Action::SplitFocus,
+        Action::OverviewSort,
+        Action::OverviewReverse,
+        Action::Activate,
+        Action::ChartCursor,
+        Action::CursorOlder,
+        Action::CursorNewer,
+        Action::CursorOldest,
+        Action::CursorNewest,
+        Action::FocusMetrics,
+        Action::CaptureBaseline,
+        Action::Baselines,
+        Action::Annotate,
+        Action::Annotations,
+        Action::CycleWindow,
+        Action::TargetWindow,
+        Action::TimeAxis,
+        Action::LogScale,
+        Action::MoveTargetUp,
+        Action::MoveTargetDown,
+        Action::Up,
+        Action::Down,
+        Action::NextProfile,
+        Action::ToggleMetric(1),
+        Action::ToggleMetric(2),
+        Action::ToggleMetric(3),
+        Action::ToggleMetric(4),
+        Action::ToggleMetric(5),
+        Action::ToggleMetric(6),
+        Action::ToggleMetric(7),
+        Action::ToggleMetric(8),
+        Action::NextCategory,
+        Action::PrevCategory,
+        Action::CopyView,
+        Action::AlertLog,
+        Action::ChangeLog,
+        Action::Spikes,
+        Action::Outages,
+        Action::CopySnapshot,
+        Action::DumpStatus,
+        Action::ErrorLog,
+        Action::Traceroute,
+    ];
+
+    fn default_keys(self) -> &'static [&'static str] {
+        const METRIC_KEYS: [&str; 8] = ["1", "2", "3", "4", "5", "6", "7", "8"];
+        match self {
+            Action::Quit => &["q", "ctrl+c"],
+            Action::Help => &["?"],
+            Action::Glossary => &["G"],
+            Action::Settings => &["S"],
+            Action::EditTarget => &["e"],
+            Action::AddTarget => &["a"],
+            Action::ImportTargets => &["I"],
+            Action::ExportTargets => &["X"],
+            Action::Filter => &["/"],
+            Action::ClearFilter => &["esc"],
+            Action::DeleteTarget => &["d"],
+            Action::DuplicateTarget => &["D"],
+            Action::Pause => &["p"],
+            Action::PauseAll => &["P"],
+            Action::Burst => &["B"],
+            Action::CompareToggle => &["c"],
+            Action::CycleEndpoint => &["i"],
+            Action::ConnReuse => &["C"],
+            Action::CyclePane => &["g"],
+            Action::Overview => &["o"],
+            Action::SplitView => &["|"],
+            Action::SplitFocus => &["backtab"],
+            Action::OverviewSort => &["s"],
+            Action::OverviewReverse => &["r"],
+            Action::Activate => &["enter"],
+            Action::ChartCursor => &["x"],
+            Action::CursorOlder => &["h", "left"],
+            Action::CursorNewer => &["l", "right"],
+            Action::CursorOldest => &["home"],
+            Action::CursorNewest => &["end"],
+            Action::FocusMetrics => &["f"],
+            Action::CaptureBaseline => &["b"],
+            Action::Baselines => &["v"],
+            Action::Annotate => &["m"],
+            Action::Annotations => &["N"],
+            Action::CycleWindow => &["w"],
+            Action::TargetWindow => &["W"],
+            Action::TimeAxis => &["t"],
+            Action::LogScale => &["L"],
+            Action::MoveTargetUp => &["K", "shift+up"],
+            Action::MoveTargetDown => &["J", "shift+down"],
+            Action::Up => &["k", "up"],
+            Action::Down => &["j", "down"],
+            Action::NextProfile => &["tab"],
+            Action::ToggleMetric(n) => {
+                let index = usize::from(n.clamp(1, 8)) - 1;
+                &METRIC_KEYS[index..=index]
+            }
+            Action::NextCategory => &["]"],
+            Action::PrevCategory => &["["],
+            Action::CopyView => &["V"],
+            Action::AlertLog => &["A"],
+            Action::ChangeLog => &["H"],
+            Action::Spikes => &["O"],
+            Action::Outages => &["u"],
+            Action::CopySnapshot => &["y"],
+            Action::DumpStatus => &["Y"],
+            Action::ErrorLog => &["E"],
+            Action::Traceroute => &["T"],
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Glossary => "glossary",
+            Action::Settings => "settings",
+            Action::EditTarget => "edit_target",
+            Action::AddTarget => "add_target",
+            Action::ImportTargets => "import_targets",
+            Action::ExportTargets => "export_targets",
+            Action::Filter => "filter",
+            Action::ClearFilter => "clear_filter",
+            Action::DeleteTarget => "delete_target",
+            Action::DuplicateTarget => "duplicate_target",
+            Action::Pause => "pause",
+            Action::PauseAll => "pause_all",
+            Action::Burst => "burst",
+            Action::CompareToggle => "compare_toggle",
+            Action::CycleEndpoint => "cycle_endpoint",
+            Action::ConnReuse => "conn_reuse",
+            Action::CyclePane => "cycle_pane",
+            Action::Overview => "overview",
+            Action::SplitView => "split_view",
+            Action::SplitFocus => "split_focus",
+            Action::OverviewSort => "overview_sort",
+            Action::OverviewReverse => "overview_reverse",
+            Action::Activate => "activate",
+            Action::ChartCursor => "chart_cursor",
+            Action::CursorOlder => "cursor_older",
+            Action::CursorNewer => "cursor_newer",
+            Action::CursorOldest => "cursor_oldest",
+            Action::CursorNewest => "cursor_newest",
+            Action::FocusMetrics => "focus_metrics",
+            Action::CaptureBaseline => "capture_baseline",
+            Action::Baselines => "baselines",
+            Action::Annotate => "annotate",
+            Action::Annotations => "annotations",
+            Action::CycleWindow => "cycle_window",
+            Action::TargetWindow => "target_window",
+            Action::TimeAxis => "time_axis",
+            Action::LogScale => "log_scale",
+            Action::MoveTargetUp => "move_target_up",
+            Action::MoveTargetDown => "move_target_down",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::NextProfile => "next_profile",
+            Action::ToggleMetric(n) => return write!(f, "toggle_metric_{n}"),
+            Action::NextCategory => "next_category",
+            Action::PrevCategory => "prev_category",
+            Action::CopyView => "copy_view",
+            Action::AlertLog => "alert_log",
+            Action::ChangeLog => "change_log",
+            Action::Spikes => "spikes",
+            Action::Outages => "outages",
+            Action::CopySnapshot => "copy_snapshot",
+            Action::DumpStatus => "dump_status",
+            Action::ErrorLog => "error_log",
+            Action::Traceroute => "traceroute",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.to_string() == input)
+            .ok_or(())
+    }
+}
+
+/// One key with its modifiers, written like `q`, `G`, `ctrl+c` or `shift+up`
+/// in the config file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Shift on a letter is folded into its case, so `shift+j`, `J` and a
+    /// terminal reporting Shift with either all mean `J`. Other characters
+    /// already arrive shifted (`!`, `?`) and Shift+Tab arrives as its own
+    /// key, so Shift is dropped for them.
+    fn normalized(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            _ => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        *self == Self::normalized(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut rest = input.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl+") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt+") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift+") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            if rest.len() == len {
+                break;
+            }
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                name => {
+                    let n = name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok());
+                    match n {
+                        Some(n @ 1..=12) => KeyCode::F(n),
+                        _ => return Err(()),
+                    }
+                }
+            },
+        };
+        Ok(Self::normalized(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("S-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("S-Tab"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            _ => f.write_str("?"),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum KeymapError {
+    #[error("unknown action {name:?}")]
+    UnknownAction { name: String },
+    #[error("{action}: cannot parse key {key:?}")]
+    InvalidKey { action: Action, key: String },
+    #[error("key {key} is bound to both {first} and {second}")]
+    Conflict {
+        key: String,
+        first: Action,
+        second: Action,
+    },
+}
+
+/// Keys of every action: the defaults with the config file's overrides
+/// applied. An override replaces all of that action's default keys.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeyChord>>,
+}
+
+impl Keymap {
+    pub fn from_config(overrides: &BTreeMap<String, KeyBinding>) -> Result<Self, KeymapError> {
+        let mut bindings = BTreeMap::new();
+        for action in Action::ALL {
+            let chords = action
+                .default_keys()
+                .iter()
+                .map(|key| key.parse().expect("default keys parse"))
+                .collect();
+            bindings.insert(action, chords);
+        }
+        for (name, binding) in overrides {
+            let action = name
+                .parse::<Action>()
+                .map_err(|()| KeymapError::UnknownAction { name: name.clone() })?;
+            let chords = binding
+                .keys()
+                .iter()
+                .map(|key| {
+                    key.parse::<KeyChord>()
+                        .map_err(|()| KeymapError::InvalidKey {
+                            action,
+                            key: key.clone(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            bindings.insert(action, chords);
+        }
+
+        let mut seen: Vec<(KeyChord, Action)> = Vec::new();
+        for (&action, chords) in &bindings {
+            for chord in chords {
+                if let Some(&(_, first)) = seen
+                    .iter()
+                    .find(|(other, first)| other == chord && *first != action)
+                {
+                    return Err(KeymapError::Conflict {
+                        key: chord.to_string(),
+                        first,
+                        second: action,
+                    });
+                }
+                seen.push((*chord, action));
+            }
+        }
+        Ok(Self { bindings })
+    }
+
+    /// Action bound to `key`, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, chords)| chords.iter().any(|chord| chord.matches(key)))
+            .map(|(&action, _)| action)
+    }
+
+    pub fn is(&self, key: &KeyEvent, action: Action) -> bool {
+        self.action(key) == Some(action)
+    }
+
+    /// First key of `action` as shown in the footer; "—" when unbound.
+    pub fn label(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|chords| chords.first())
+            .map_or_else(|| "—".to_string(), KeyChord::to_string)
+    }
+
+    /// Every key of `action`, slash-separated.
+    pub fn labels(&self, action: Action) -> String {
+        let labels: Vec<String> = self
+            .bindings
+            .get(&action)
+            .map(|chords| chords.iter().map(KeyChord::to_string).collect())
+            .unwrap_or_default();
+        if labels.is_empty() {
+            "—".to_string()
+        } else {
+            labels.join("/")
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default keymap has no conflicts")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn overrides(entries: &[(&str, &[&str])]) -> BTreeMap<String, KeyBinding> {
+        entries
+            .iter()
+            .map(|(action, keys)| {
+                (
+                    action.to_string(),
+                    KeyBinding::Multiple(keys.iter().map(|key| key.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_map_matches_the_builtin_keys() {
+        let keymap = Keymap::default();
+        let char_key = |c| press(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&char_key('q')), Some(Action::Quit));
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(keymap.action(&char_key('c')), Some(Action::CompareToggle));
+        // Terminals report Shift along with upper-case letters
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('J'), KeyModifiers::SHIFT)),
+            Some(Action::MoveTargetDown)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('j'), KeyModifiers::SHIFT)),
+            Some(Action::MoveTargetDown)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Down, KeyModifiers::SHIFT)),
+            Some(Action::MoveTargetDown)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Down, KeyModifiers::NONE)),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Action::SplitFocus)
+        );
+        assert_eq!(keymap.action(&char_key('W')), Some(Action::TargetWindow));
+        assert_eq!(keymap.action(&char_key('m')), Some(Action::Annotate));
+        assert_eq!(keymap.action(&char_key('5')), Some(Action::ToggleMetric(5)));
+        assert_eq!(keymap.action(&char_key('z')), None);
+        assert_eq!(keymap.labels(Action::Quit), "q/Ctrl+C");
+        assert_eq!(keymap.label(Action::MoveTargetUp), "K");
+    }
+
+    #[test]
+    fn overrides_replace_the_default_keys() {
+        let keymap = Keymap::from_config(&overrides(&[
+            ("pause", &["space"]),
+            ("quit", &["Q"]),
+            ("toggle_metric_1", &[]),
+        ]))
+        .expect("keymap");
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char(' '), KeyModifiers::NONE)),
+            Some(Action::Pause)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('p'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            None
+        );
+        assert_eq!(keymap.label(Action::Pause), "Space");
+        assert_eq!(keymap.label(Action::ToggleMetric(1)), "—");
+    }
+
+    #[test]
+    fn invalid_and_conflicting_overrides_are_rejected() {
+        assert_eq!(
+            Keymap::from_config(&overrides(&[("pause", &["q"])])).unwrap_err(),
+            KeymapError::Conflict {
+                key: "q".to_string(),
+                first: Action::Quit,
+                second: Action::Pause,
+            }
+        );
+        assert_eq!(
+            Keymap::from_config(&overrides(&[("pause", &["ctrl+"])])).unwrap_err(),
+            KeymapError::InvalidKey {
+                action: Action::Pause,
+                key: "ctrl+".to_string(),
+            }
+        );
+        assert!(matches!(
+            Keymap::from_config(&overrides(&[("launch", &["l"])])),
+            Err(KeymapError::UnknownAction { .. })
+        ));
+    }
+
+    #[test]
+    fn key_chords_parse_modifiers_and_named_keys() {
+        let chord = |input: &str| input.parse::<KeyChord>().map(|chord| chord.to_string());
+        assert_eq!(chord("ctrl+x"), Ok("Ctrl+X".to_string()));
+        assert_eq!(chord("Alt+Enter"), Ok("Alt+Enter".to_string()));
+        assert_eq!(chord("shift+up"), Ok("S-↑".to_string()));
+        assert_eq!(chord("shift+j"), Ok("J".to_string()));
+        assert_eq!(chord("ctrl+shift+x"), Ok("Ctrl+X".to_string()));
+        assert_eq!(chord("ctrl++"), Ok("Ctrl++".to_string()));
+        assert_eq!(chord("f5"), Ok("F5".to_string()));
+        assert_eq!(chord("f13"), Err(()));
+        assert_eq!(chord("enterprise"), Err(()));
+    }
+}
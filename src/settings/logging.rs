@@ -0,0 +1,52 @@
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use thiserror::Error;
+use tracing_subscriber::EnvFilter;
+
+/// Filter used when neither `--log-level` nor `RUST_LOG` is set.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+#[derive(Debug, Error)]
+pub enum LoggingError {
+    #[error("cannot open log file {path}: {source}")]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid log filter {filter:?}: {message}")]
+    Filter { filter: String, message: String },
+    #[error("logging already initialised")]
+    AlreadyInitialised,
+}
+
+/// Parses a `RUST_LOG`-style directive list such as `info,httpulse=debug`.
+pub fn parse_log_filter(filter: &str) -> Result<EnvFilter, LoggingError> {
+    EnvFilter::try_new(filter).map_err(|err| LoggingError::Filter {
+        filter: filter.to_string(),
+        message: err.to_string(),
+    })
+}
+
+/// Routes `tracing` events to `path`. Nothing is ever written to stdout or
+/// stderr, which belong to the TUI; without a log file no subscriber is
+/// installed and every event is discarded.
+pub fn init_file_logging(path: &Path, filter: &str) -> Result<(), LoggingError> {
+    let filter = parse_log_filter(filter)?;
+    let file: File = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|source| LoggingError::Open {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_thread_names(true)
+        .try_init()
+        .map_err(|_| LoggingError::AlreadyInitialised)
+}
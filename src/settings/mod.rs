@@ -1,8 +1,18 @@
-use crate::config::{EbpfMode, GlobalConfig};
+mod keymap;
+mod logging;
+mod targets;
+
+use crate::config::{AuthConfig, EbpfMode, GlobalConfig, ProbeBackendKind, ThemeName, WindowSpec};
 use crate::data_model::settings::AppSettings;
+use crate::session::parse_replay_speed;
 use clap::Parser;
+use std::path::PathBuf;
 use thiserror::Error;
 
+pub use keymap::{Action, KeyChord, Keymap, KeymapError};
+pub use logging::{DEFAULT_LOG_FILTER, LoggingError, init_file_logging, parse_log_filter};
+pub use targets::{InvalidTarget, validate_targets};
+
 const DEFAULT_TARGET: &str = "https://google.com";
 
 #[derive(Parser, Debug)]
@@ -17,19 +27,141 @@ pub struct CliArgs {
     #[arg(long, default_value_t = 10)]
     refresh_hz: u16,
 
+    /// Windows cycled with `w`, comma-separated (e.g. 1m,15m,6h,24h)
+    #[arg(long, value_name = "LIST")]
+    windows: Option<String>,
+
     /// eBPF mode: off|minimal|full
     #[arg(long, default_value = "off")]
     ebpf: String,
+
+    /// Probe backend: curl|tcp-connect; defaults to the saved one
+    #[arg(long, value_name = "NAME")]
+    backend: Option<String>,
+
+    /// Color theme: auto|dark|light|mono; defaults to the saved one. auto honors NO_COLOR
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Write debug logs to this file (nothing is logged without it)
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Log filter, RUST_LOG syntax (e.g. info,httpulse=debug); defaults to RUST_LOG, then info
+    #[arg(long, value_name = "FILTER")]
+    log_level: Option<String>,
+
+    /// Record every probe sample, with the target configs, to this file
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Replay a recorded session instead of probing
+    #[arg(long, value_name = "PATH")]
+    replay: Option<PathBuf>,
+
+    /// Replay speed multiplier (e.g. 10x)
+    #[arg(long, value_name = "SPEED", default_value = "1x", requires = "replay")]
+    replay_speed: String,
+
+    /// Probe with a synthetic, seeded sample generator instead of the network
+    #[arg(
+        long,
+        value_name = "SEED",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        conflicts_with = "replay"
+    )]
+    demo: Option<u64>,
+
+    /// Start with the valid targets instead of exiting when a --target is invalid
+    #[arg(long)]
+    ignore_invalid_targets: bool,
+
+    /// Probe every target once before starting and exit non-zero if any fails
+    #[arg(long, conflicts_with_all = ["replay", "demo"])]
+    preflight: bool,
+
+    /// Probe every profile of every target once, print a report and exit non-zero if any failed
+    #[arg(
+        long,
+        conflicts_with_all = ["replay", "demo", "record", "restore", "preflight", "export_targets"]
+    )]
+    once: bool,
+
+    /// Print the --once report as JSON
+    #[arg(long, requires = "once")]
+    json: bool,
+
+    /// Add every target listed in this file, one `<url> [profiles]` per line
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    targets_file: Option<PathBuf>,
+
+    /// Import URLs from --targets-file even when they are already probed
+    #[arg(long, requires = "targets_file")]
+    allow_duplicates: bool,
+
+    /// Merge the targets of a document written by --export-targets or the `X` key
+    #[arg(long, value_name = "PATH", conflicts_with = "replay")]
+    import_targets: Option<PathBuf>,
+
+    /// Write every target's definition, without secrets, to PATH and exit
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["replay", "demo"])]
+    export_targets: Option<PathBuf>,
+
+    /// Basic auth sent to every --target
+    #[arg(long, value_name = "USER:PASS")]
+    auth: Option<String>,
+
+    /// Bearer token sent to every --target
+    #[arg(long, value_name = "TOKEN", conflicts_with = "auth")]
+    bearer: Option<String>,
+
+    /// Skip TLS certificate verification for every --target (self-signed staging hosts)
+    #[arg(long)]
+    insecure: bool,
+
+    /// Reload the samples saved when httpulse last quit into the saved targets
+    #[arg(long, conflicts_with_all = ["target", "replay", "demo"])]
+    restore: bool,
 }
 
 #[derive(Debug, Error)]
 pub enum SettingsError {
     #[error("ui refresh rate must be greater than zero (got {value})")]
     InvalidRefreshHz { value: u16 },
+    #[error(transparent)]
+    InvalidLogFilter(#[from] LoggingError),
+    #[error("replay speed must be a positive multiplier like 10x (got {value:?})")]
+    InvalidReplaySpeed { value: String },
+    #[error("backend must be curl or tcp-connect (got {value:?})")]
+    InvalidBackend { value: String },
+    #[error("theme must be auto, dark, light or mono (got {value:?})")]
+    InvalidTheme { value: String },
+    #[error("windows must be a comma-separated list of durations like 1m,15m,6h (got {value:?})")]
+    InvalidWindows { value: String },
+    #[error("invalid keymap: {0}")]
+    InvalidKeymap(#[from] KeymapError),
+    #[error(
+        "invalid targets (pass --ignore-invalid-targets to skip them):{}",
+        format_invalid_targets(.0)
+    )]
+    InvalidTargets(Vec<InvalidTarget>),
+    #[error("--auth must be user:password and --bearer must not be empty")]
+    InvalidAuth,
+}
+
+fn format_invalid_targets(invalid: &[InvalidTarget]) -> String {
+    invalid
+        .iter()
+        .map(|target| format!("\n  {target}"))
+        .collect()
 }
 
 pub fn load_from_cli() -> Result<AppSettings, SettingsError> {
-    let args = CliArgs::parse();
+    let mut args = CliArgs::parse();
+    if args.log_level.is_none() {
+        args.log_level = std::env::var("RUST_LOG").ok();
+    }
     from_args(args)
 }
 
@@ -40,42 +172,176 @@ pub fn from_args(args: CliArgs) -> Result<AppSettings, SettingsError> {
         });
     }
 
+    let log_filter = args
+        .log_level
+        .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string());
+    parse_log_filter(&log_filter)?;
+    let replay_speed = parse_replay_speed(&args.replay_speed).ok_or_else(|| {
+        SettingsError::InvalidReplaySpeed {
+            value: args.replay_speed.clone(),
+        }
+    })?;
+
+    let windows = args
+        .windows
+        .as_deref()
+        .map(|value| {
+            parse_windows(value).ok_or_else(|| SettingsError::InvalidWindows {
+                value: value.to_string(),
+            })
+        })
+        .transpose()?;
+
+    let backend = args
+        .backend
+        .as_deref()
+        .map(|value| {
+            ProbeBackendKind::parse_cli(value).ok_or_else(|| SettingsError::InvalidBackend {
+                value: value.to_string(),
+            })
+        })
+        .transpose()?;
+
+    let theme = args
+        .theme
+        .as_deref()
+        .map(|value| {
+            ThemeName::parse_cli(value).ok_or_else(|| SettingsError::InvalidTheme {
+                value: value.to_string(),
+            })
+        })
+        .transpose()?;
+
+    let auth = match (&args.auth, &args.bearer) {
+        (Some(credentials), _) => {
+            Some(AuthConfig::basic(credentials).ok_or(SettingsError::InvalidAuth)?)
+        }
+        (None, Some(token)) => Some(AuthConfig::bearer(token).ok_or(SettingsError::InvalidAuth)?),
+        (None, None) => None,
+    };
+
+    let (targets, invalid_targets) = validate_targets(&args.target);
+    if !invalid_targets.is_empty() && !args.ignore_invalid_targets {
+        return Err(SettingsError::InvalidTargets(invalid_targets));
+    }
+    // Only an empty --target list falls back to the default, not one whose
+    // every entry was skipped
     let targets = if args.target.is_empty() {
         vec![DEFAULT_TARGET.to_string()]
     } else {
-        args.target
+        targets
     };
 
     Ok(AppSettings {
         targets,
+        targets_from_cli: !args.target.is_empty(),
         refresh_hz: args.refresh_hz,
+        windows,
         ebpf_mode: EbpfMode::parse_cli(&args.ebpf),
+        backend,
+        theme,
+        log_file: args.log_file,
+        log_filter,
+        record: args.record,
+        replay: args.replay,
+        replay_speed,
+        demo: args.demo,
+        skipped_targets: invalid_targets.iter().map(ToString::to_string).collect(),
+        preflight: args.preflight,
+        once: args.once,
+        json: args.json,
+        targets_file: args.targets_file,
+        allow_duplicates: args.allow_duplicates,
+        import_targets: args.import_targets,
+        export_targets: args.export_targets,
+        auth,
+        insecure: args.insecure,
+        restore: args.restore,
     })
 }
 
+/// Parses a comma-separated window list, shortest first and without repeats.
+fn parse_windows(value: &str) -> Option<Vec<WindowSpec>> {
+    let mut windows = value
+        .split(',')
+        .map(|item| item.parse::<WindowSpec>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    windows.sort_by_key(|window| window.duration());
+    windows.dedup();
+    Some(windows)
+}
+
 pub fn apply_global(settings: &AppSettings, global: &mut GlobalConfig) {
     global.ui_refresh_hz = settings.refresh_hz;
+    if let Some(windows) = &settings.windows {
+        global.windows = windows.clone();
+        if !global.windows.contains(&global.default_window) {
+            global.default_window = windows[0];
+        }
+    }
     global.ebpf_mode = settings.ebpf_mode;
     global.ebpf_enabled = global.ebpf_mode != EbpfMode::Off;
+    if let Some(backend) = settings.backend {
+        global.backend = backend;
+    }
+    if let Some(theme) = settings.theme {
+        global.theme = theme;
+    }
+}
+
+/// Builds the keymap from the config file's overrides, rejecting unknown
+/// actions, unparsable keys and keys bound to two actions.
+pub fn resolve_keymap(global: &GlobalConfig) -> Result<Keymap, SettingsError> {
+    Ok(Keymap::from_config(&global.keymap)?)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DEFAULT_TARGET, SettingsError, from_args};
-    use crate::config::EbpfMode;
+    use super::{
+        DEFAULT_LOG_FILTER, DEFAULT_TARGET, SettingsError, apply_global, from_args, resolve_keymap,
+    };
+    use crate::config::{
+        AuthConfig, EbpfMode, GlobalConfig, KeyBinding, ProbeBackendKind, WindowSpec,
+    };
+    use clap::Parser;
+    use std::time::Duration;
 
     #[test]
     fn from_args_defaults_target_and_ebpf_off() {
         let settings = from_args(super::CliArgs {
             target: Vec::new(),
             refresh_hz: 10,
+            windows: None,
             ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
         })
         .expect("settings");
 
         assert_eq!(settings.targets, vec![DEFAULT_TARGET.to_string()]);
+        assert!(!settings.targets_from_cli);
         assert_eq!(settings.refresh_hz, 10);
         assert_eq!(settings.ebpf_mode, EbpfMode::Off);
+        assert_eq!(settings.log_filter, DEFAULT_LOG_FILTER);
+        assert_eq!(settings.log_file, None);
     }
 
     #[test]
@@ -83,7 +349,28 @@ mod tests {
         let settings = from_args(super::CliArgs {
             target: vec!["https://example.com".to_string()],
             refresh_hz: 10,
+            windows: None,
             ebpf: "unknown".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
         })
         .expect("settings");
 
@@ -95,12 +382,445 @@ mod tests {
         let err = from_args(super::CliArgs {
             target: Vec::new(),
             refresh_hz: 0,
+            windows: None,
             ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
         })
         .expect_err("should error");
 
         match err {
             SettingsError::InvalidRefreshHz { value } => assert_eq!(value, 0),
+            other => panic!("unexpected error: {other}"),
         }
     }
+
+    #[test]
+    fn from_args_validates_log_filter() {
+        let settings = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: Some("/tmp/httpulse.log".into()),
+            log_level: Some("warn,httpulse=debug".to_string()),
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect("settings");
+        assert_eq!(settings.log_filter, "warn,httpulse=debug");
+        assert_eq!(settings.log_file, Some("/tmp/httpulse.log".into()));
+
+        let err = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: Some("httpulse=loud".to_string()),
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect_err("should error");
+        assert!(matches!(err, SettingsError::InvalidLogFilter(_)));
+    }
+
+    #[test]
+    fn from_args_parses_replay_speed() {
+        let settings = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: Some("/tmp/session.jsonl".into()),
+            replay_speed: "10x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect("settings");
+        assert_eq!(settings.replay, Some("/tmp/session.jsonl".into()));
+        assert_eq!(settings.replay_speed, 10.0);
+
+        let err = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: Some("/tmp/session.jsonl".into()),
+            replay_speed: "-2x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect_err("should error");
+        assert!(matches!(err, SettingsError::InvalidReplaySpeed { .. }));
+    }
+
+    #[test]
+    fn from_args_parses_windows_and_moves_a_dropped_default() {
+        let settings = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: Some("24h, 1m,6h,1m".to_string()),
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect("settings");
+        let hours = |h: u64| WindowSpec::Custom(Duration::from_secs(h * 3600));
+        assert_eq!(
+            settings.windows,
+            Some(vec![WindowSpec::M1, hours(6), hours(24)])
+        );
+
+        let mut global = GlobalConfig::default();
+        apply_global(&settings, &mut global);
+        assert_eq!(global.windows.len(), 3);
+        assert_eq!(global.default_window, WindowSpec::M1);
+
+        let err = from_args(super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: Some("1m,,6h".to_string()),
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        })
+        .expect_err("should error");
+        assert!(matches!(err, SettingsError::InvalidWindows { .. }));
+    }
+
+    #[test]
+    fn from_args_overrides_the_saved_backend_only_when_given() {
+        let args = |backend: Option<&str>| super::CliArgs {
+            target: Vec::new(),
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: backend.map(str::to_string),
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        };
+
+        let mut global = GlobalConfig {
+            backend: ProbeBackendKind::TcpConnect,
+            ..GlobalConfig::default()
+        };
+        apply_global(&from_args(args(None)).expect("settings"), &mut global);
+        assert_eq!(global.backend, ProbeBackendKind::TcpConnect);
+        apply_global(
+            &from_args(args(Some("curl"))).expect("settings"),
+            &mut global,
+        );
+        assert_eq!(global.backend, ProbeBackendKind::Curl);
+
+        let err = from_args(args(Some("h3"))).expect_err("should error");
+        assert!(matches!(err, SettingsError::InvalidBackend { .. }));
+    }
+
+    #[test]
+    fn from_args_rejects_invalid_targets_unless_ignored() {
+        let args = |ignore_invalid_targets: bool| super::CliArgs {
+            target: vec![
+                "htps://foo".to_string(),
+                "https://example.com".to_string(),
+                "http://".to_string(),
+            ],
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: None,
+            bearer: None,
+            insecure: false,
+            restore: false,
+        };
+
+        let err = from_args(args(false)).expect_err("should error");
+        let SettingsError::InvalidTargets(invalid) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(invalid.len(), 2);
+        let message = err.to_string();
+        assert!(
+            message.contains("\"htps://foo\": unsupported scheme"),
+            "{message}"
+        );
+        assert!(message.contains("\"http://\": not a URL"), "{message}");
+
+        let settings = from_args(args(true)).expect("settings");
+        assert_eq!(settings.targets, vec!["https://example.com".to_string()]);
+        assert_eq!(settings.skipped_targets.len(), 2);
+        assert!(settings.targets_from_cli);
+    }
+
+    #[test]
+    fn from_args_parses_auth_flags() {
+        let args = |auth: Option<&str>, bearer: Option<&str>| super::CliArgs {
+            target: vec!["https://example.com".to_string()],
+            refresh_hz: 10,
+            windows: None,
+            ebpf: "off".to_string(),
+            backend: None,
+            theme: None,
+            log_file: None,
+            log_level: None,
+            record: None,
+            replay: None,
+            replay_speed: "1x".to_string(),
+            demo: None,
+            ignore_invalid_targets: false,
+            preflight: false,
+            once: false,
+            json: false,
+            targets_file: None,
+            allow_duplicates: false,
+            import_targets: None,
+            export_targets: None,
+            auth: auth.map(str::to_string),
+            bearer: bearer.map(str::to_string),
+            insecure: false,
+            restore: false,
+        };
+
+        let settings = from_args(args(Some("alice:pw"), None)).expect("settings");
+        assert_eq!(settings.auth, AuthConfig::basic("alice:pw"));
+        let settings = from_args(args(None, Some("tok"))).expect("settings");
+        assert_eq!(settings.auth, AuthConfig::bearer("tok"));
+        assert!(from_args(args(None, None)).unwrap().auth.is_none());
+        assert!(matches!(
+            from_args(args(Some("alice"), None)),
+            Err(SettingsError::InvalidAuth)
+        ));
+        // Secrets stay out of anything that serializes the settings
+        let json = serde_json::to_string(&from_args(args(None, Some("tok"))).unwrap()).unwrap();
+        assert!(!json.contains("tok\""), "{json}");
+    }
+
+    #[test]
+    fn insecure_flag_is_off_unless_given() {
+        let parse = |extra: &[&str]| {
+            let args = ["httpulse", "-t", "https://staging.example.com"]
+                .iter()
+                .chain(extra);
+            from_args(super::CliArgs::try_parse_from(args).expect("args")).expect("settings")
+        };
+        assert!(!parse(&[]).insecure);
+        assert!(parse(&["--insecure"]).insecure);
+    }
+
+    #[test]
+    fn shared_targets_flags_take_paths_but_not_a_replay() {
+        let settings = from_args(
+            super::CliArgs::try_parse_from([
+                "httpulse",
+                "--import-targets",
+                "team.json",
+                "--export-targets",
+                "merged.json",
+            ])
+            .expect("args"),
+        )
+        .expect("settings");
+        assert_eq!(settings.import_targets, Some("team.json".into()));
+        assert_eq!(settings.export_targets, Some("merged.json".into()));
+        assert!(
+            super::CliArgs::try_parse_from([
+                "httpulse",
+                "--export-targets",
+                "out.json",
+                "--replay",
+                "session.jsonl",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn restore_takes_the_saved_targets_instead_of_cli_ones() {
+        let settings =
+            from_args(super::CliArgs::try_parse_from(["httpulse", "--restore"]).expect("args"))
+                .expect("settings");
+        assert!(settings.restore);
+        assert!(
+            super::CliArgs::try_parse_from(["httpulse", "--restore", "-t", "example.com"]).is_err()
+        );
+    }
+
+    #[test]
+    fn json_needs_once_and_once_excludes_the_ui_modes() {
+        let settings = from_args(
+            super::CliArgs::try_parse_from(["httpulse", "--once", "--json", "-t", "example.com"])
+                .expect("args"),
+        )
+        .expect("settings");
+        assert!(settings.once && settings.json);
+        assert!(super::CliArgs::try_parse_from(["httpulse", "--json"]).is_err());
+        assert!(super::CliArgs::try_parse_from(["httpulse", "--once", "--demo"]).is_err());
+    }
+
+    #[test]
+    fn conflicting_key_overrides_name_both_actions() {
+        let mut global = GlobalConfig::default();
+        assert!(resolve_keymap(&global).is_ok());
+
+        global
+            .keymap
+            .insert("pause".to_string(), KeyBinding::Single("q".to_string()));
+        let err = resolve_keymap(&global).expect_err("should error");
+        assert!(matches!(err, SettingsError::InvalidKeymap(_)));
+        assert_eq!(
+            err.to_string(),
+            "invalid keymap: key q is bound to both quit and pause"
+        );
+    }
 }
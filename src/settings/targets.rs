@@ -0,0 +1,55 @@
+use crate::common::net::{TargetError, validate_target};
+use std::fmt;
+
+/// A `--target` value that cannot be probed, with the reason.
+#[derive(Debug, PartialEq)]
+pub struct InvalidTarget {
+    pub input: String,
+    pub error: TargetError,
+}
+
+impl fmt::Display for InvalidTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.input, self.error)
+    }
+}
+
+/// Splits CLI targets into the usable ones, in order, and every rejected one.
+pub fn validate_targets(inputs: &[String]) -> (Vec<String>, Vec<InvalidTarget>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for input in inputs {
+        match validate_target(input) {
+            Ok(_) => valid.push(input.clone()),
+            Err(error) => invalid.push(InvalidTarget {
+                input: input.clone(),
+                error,
+            }),
+        }
+    }
+    (valid, invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_targets_collects_every_invalid_target() {
+        let inputs = [
+            "https://ok.example.com",
+            "htps://foo",
+            "",
+            "api.example.com",
+        ]
+        .map(str::to_string);
+        let (valid, invalid) = validate_targets(&inputs);
+        assert_eq!(valid, vec!["https://ok.example.com", "api.example.com"]);
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(
+            invalid[0].to_string(),
+            "\"htps://foo\": unsupported scheme \"htps\", expected http or https"
+        );
+        assert_eq!(invalid[1].error, TargetError::Empty);
+    }
+}
@@ -1,17 +1,24 @@
-use crate::app::{MetricsCategory, ProfileViewMode, TargetPaneMode};
+use crate::app::{Annotation, MetricsCategory, ProfileViewMode, TargetPaneMode};
 use crate::config::{GlobalConfig, TargetConfig, WindowSpec};
-use crate::metrics::MetricKind;
+use crate::data_model::{SCHEMA_VERSION, SchemaError, SchemaVersion, Versioned};
+use crate::metrics::{Baseline, MetricKind};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fs;
 use std::io;
 use std::path::PathBuf;
 
-const STATE_FILE_VERSION: &str = "1";
+/// Value of the state file's original `version` field. It is frozen:
+/// builds that predate `schema_version` require the field, so it is still
+/// written, but compatibility is decided by `schema_version` alone.
+pub(crate) const STATE_FILE_VERSION: &str = "1";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PersistedState {
+    /// Always [`STATE_FILE_VERSION`]; see `schema_version` instead
     pub version: String,
+    #[serde(default = "SchemaVersion::legacy")]
+    pub schema_version: SchemaVersion,
     pub global_config: GlobalConfig,
     pub targets: Vec<PersistedTarget>,
     pub ui_state: PersistedUiState,
@@ -24,6 +31,17 @@ pub struct PersistedTarget {
     pub selected_profile: usize,
     pub pane_mode: TargetPaneMode,
     pub metrics_category: MetricsCategory,
+    // Older files have neither; the target then takes the saved defaults
+    #[serde(default)]
+    pub selected_metric: Option<MetricKind>,
+    #[serde(default)]
+    pub selected_metrics: Option<HashSet<MetricKind>>,
+    #[serde(default)]
+    pub baselines: Vec<Baseline>,
+    #[serde(default)]
+    pub active_baseline: Option<usize>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -32,6 +50,8 @@ pub struct PersistedUiState {
     pub selected_metric: MetricKind,
     pub selected_metrics: HashSet<MetricKind>,
     pub window: WindowSpec,
+    #[serde(default)]
+    pub collapsed_groups: BTreeSet<String>,
 }
 
 impl Default for PersistedState {
@@ -41,6 +61,7 @@ impl Default for PersistedState {
         selected_metrics.insert(MetricKind::Total);
         Self {
             version: STATE_FILE_VERSION.to_string(),
+            schema_version: SCHEMA_VERSION,
             global_config: global.clone(),
             targets: Vec::new(),
             ui_state: PersistedUiState {
@@ -48,6 +69,7 @@ impl Default for PersistedState {
                 selected_metric: MetricKind::Total,
                 selected_metrics,
                 window: global.default_window,
+                collapsed_groups: BTreeSet::new(),
             },
         }
     }
@@ -61,19 +83,35 @@ fn state_file_path() -> Option<PathBuf> {
     config_dir().map(|p| p.join("state.json"))
 }
 
-pub fn load() -> PersistedState {
+/// Session file the samples are saved to on quit and reloaded from with
+/// `--restore`.
+pub fn history_file_path() -> Option<PathBuf> {
+    config_dir().map(|p| p.join("history.jsonl"))
+}
+
+/// Loads the saved state, or the defaults when there is none or it cannot be
+/// read. A file from a newer schema major is refused instead, so quitting
+/// does not overwrite what a newer httpulse saved.
+pub fn load() -> Result<PersistedState, SchemaError> {
     let Some(path) = state_file_path() else {
-        return PersistedState::default();
+        return Ok(PersistedState::default());
     };
 
     if !path.exists() {
-        return PersistedState::default();
+        return Ok(PersistedState::default());
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(content) => parse_state(&content),
+        Err(_) => Ok(PersistedState::default()),
     }
+}
 
-    fs::read_to_string(&path)
-        .ok()
-        .and_then(|content| serde_json::from_str::<PersistedState>(&content).ok())
-        .unwrap_or_default()
+fn parse_state(content: &str) -> Result<PersistedState, SchemaError> {
+    if let Ok(found) = serde_json::from_str::<Versioned>(content) {
+        found.schema_version.check_compatible()?;
+    }
+    Ok(serde_json::from_str(content).unwrap_or_default())
 }
 
 pub fn save(state: &PersistedState) -> io::Result<()> {
@@ -119,4 +157,22 @@ mod tests {
         let parsed: PersistedState = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.version, state.version);
     }
+
+    #[test]
+    fn state_from_a_newer_major_is_refused_and_unknown_fields_are_ignored() {
+        let mut state: serde_json::Value = serde_json::to_value(PersistedState::default()).unwrap();
+        state["ui_state"]["sidebar_width"] = 30.into();
+        state.as_object_mut().unwrap().remove("schema_version");
+        let parsed = parse_state(&state.to_string()).unwrap();
+        assert_eq!(parsed.schema_version, SchemaVersion::LEGACY);
+
+        state["schema_version"] = "2.0".into();
+        assert!(matches!(
+            parse_state(&state.to_string()),
+            Err(SchemaError::Newer { .. })
+        ));
+
+        // Unreadable files still fall back to the defaults
+        assert!(parse_state("{").unwrap().targets.is_empty());
+    }
 }
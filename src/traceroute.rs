@@ -0,0 +1 @@
+pub use crate::features::probe::traceroute::*;
@@ -0,0 +1,570 @@
+//! End-to-end tests of `ProbeClient::probe` against an in-process server with
+//! scripted behaviours: delayed heads, slow chunked bodies, error statuses,
+//! resets, a self-signed TLS listener and transport overrides.
+#![cfg(unix)]
+
+use httpulse::config::{
+    AuthConfig, ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, SamplingConfig,
+    TargetConfig, TlsVersion, WindowSpec,
+};
+use httpulse::metrics::MetricKind;
+use httpulse::metrics_aggregate::{AggregateView, MetricsStore, ProfileKey};
+use httpulse::probe::{ProbeErrorKind, ProbeResult, ProbeSample};
+use httpulse::probe_engine::ProbeClient;
+use openssl::asn1::Asn1Time;
+use openssl::bn::BigNum;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::PKey;
+use openssl::ssl::{SslAcceptor, SslMethod};
+use openssl::x509::{X509, X509NameBuilder};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+/// Slack allowed on top of injected delays for a loaded CI machine.
+const SLACK: Duration = Duration::from_millis(1500);
+
+#[derive(Clone)]
+enum Behavior {
+    /// Waits `delay` after the request head, then sends a complete response
+    Respond {
+        delay: Duration,
+        status: u16,
+        body: &'static str,
+    },
+    /// Sends the head at once, then `chunks` chunked-encoded pieces `gap` apart
+    SlowChunked {
+        chunks: usize,
+        chunk_size: usize,
+        gap: Duration,
+    },
+    /// Reads the request head, then aborts the connection with a RST
+    Reset,
+}
+
+/// Serves every connection on a fresh loopback port with `behavior`.
+fn spawn_server(behavior: Behavior) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let behavior = behavior.clone();
+            thread::spawn(move || serve(stream, behavior));
+        }
+    });
+    Url::parse(&format!("http://{addr}/")).expect("url")
+}
+
+fn read_request_head(stream: &mut impl Read) -> Option<Vec<u8>> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => return None,
+            Ok(read) => request.extend_from_slice(&buf[..read]),
+        }
+    }
+    Some(request)
+}
+
+fn serve(mut stream: TcpStream, behavior: Behavior) {
+    if read_request_head(&mut stream).is_none() {
+        return;
+    }
+    match behavior {
+        Behavior::Respond {
+            delay,
+            status,
+            body,
+        } => {
+            thread::sleep(delay);
+            let _ = stream.write_all(canned_response(status, body).as_bytes());
+        }
+        Behavior::SlowChunked {
+            chunks,
+            chunk_size,
+            gap,
+        } => {
+            let head = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n";
+            if stream.write_all(head.as_bytes()).is_err() {
+                return;
+            }
+            let chunk = format!("{chunk_size:x}\r\n{}\r\n", "x".repeat(chunk_size));
+            for _ in 0..chunks {
+                thread::sleep(gap);
+                // The client hangs up once it has read enough
+                if stream.write_all(chunk.as_bytes()).is_err() {
+                    return;
+                }
+            }
+            let _ = stream.write_all(b"0\r\n\r\n");
+        }
+        Behavior::Reset => reset(stream),
+    }
+}
+
+/// Answers 200 when the request names `host` in its Host header, else 421.
+fn respond_if_host(stream: &mut (impl Read + Write), host: &str) {
+    let Some(request) = read_request_head(stream) else {
+        return;
+    };
+    let head = String::from_utf8_lossy(&request).to_ascii_lowercase();
+    let status = if head.contains(&format!("\r\nhost: {host}\r\n")) {
+        200
+    } else {
+        421
+    };
+    let _ = stream.write_all(canned_response(status, "").as_bytes());
+}
+
+fn canned_response(status: u16, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status} Scripted\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Closes with SO_LINGER 0 so the kernel sends a RST instead of a FIN.
+fn reset(stream: TcpStream) {
+    use std::os::fd::AsRawFd;
+
+    let linger = libc::linger {
+        l_onoff: 1,
+        l_linger: 0,
+    };
+    // SAFETY: the fd is live for the duration of the call and `linger` is a valid option value.
+    unsafe {
+        libc::setsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_LINGER,
+            (&linger as *const libc::linger).cast(),
+            size_of::<libc::linger>() as libc::socklen_t,
+        );
+    }
+    drop(stream);
+}
+
+/// Serves every connection on a fresh loopback port over TLS with a
+/// throwaway self-signed certificate.
+fn spawn_tls_server() -> Url {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).expect("curve");
+    let key = PKey::from_ec_key(EcKey::generate(&group).expect("ec key")).expect("pkey");
+    let mut name = X509NameBuilder::new().expect("name");
+    name.append_entry_by_nid(Nid::COMMONNAME, "127.0.0.1")
+        .expect("common name");
+    let name = name.build();
+    let mut cert = X509::builder().expect("x509");
+    cert.set_version(2).expect("version");
+    let serial = BigNum::from_u32(1)
+        .and_then(|serial| serial.to_asn1_integer())
+        .expect("serial");
+    cert.set_serial_number(&serial).expect("serial");
+    cert.set_subject_name(&name).expect("subject");
+    cert.set_issuer_name(&name).expect("issuer");
+    cert.set_pubkey(&key).expect("pubkey");
+    cert.set_not_before(&Asn1Time::days_from_now(0).expect("time"))
+        .expect("not before");
+    cert.set_not_after(&Asn1Time::days_from_now(1).expect("time"))
+        .expect("not after");
+    cert.sign(&key, MessageDigest::sha256()).expect("sign");
+    let cert = cert.build();
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).expect("acceptor");
+    acceptor.set_private_key(&key).expect("key");
+    acceptor.set_certificate(&cert).expect("cert");
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            let Ok(mut stream) = acceptor.accept(stream) else {
+                continue;
+            };
+            if read_request_head(&mut stream).is_some() {
+                let _ = stream.write_all(canned_response(200, "ok").as_bytes());
+            }
+        }
+    });
+    Url::parse(&format!("https://{addr}/")).expect("url")
+}
+
+fn profile(max_read_bytes: u32) -> ProfileConfig {
+    ProfileConfig::new(
+        "h1+tls12+cold",
+        HttpVersion::H1,
+        TlsVersion::Tls12,
+        ConnReusePolicy::Cold,
+        ProbeMethod::Get,
+        max_read_bytes,
+    )
+}
+
+fn probe(url: Url, profile: &ProfileConfig) -> ProbeSample {
+    let mut target = TargetConfig::new(url, vec![profile.clone()]);
+    target.timeout_total = Duration::from_secs(10);
+    let mut client = ProbeClient::new().expect("client");
+    client.probe(&target, profile, None)
+}
+
+fn error_kind(sample: &ProbeSample) -> Option<ProbeErrorKind> {
+    match &sample.result {
+        ProbeResult::Ok => None,
+        ProbeResult::Err(err) => Some(err.kind),
+    }
+}
+
+/// Phases are deltas of curl's cumulative timers, so together they never exceed the total.
+fn assert_phases_ordered(sample: &ProbeSample) {
+    let phases = sample.t_dns.unwrap_or_default()
+        + sample.t_connect
+        + sample.t_tls.unwrap_or_default()
+        + sample.t_ttfb
+        + sample.t_download;
+    assert!(
+        phases <= sample.t_total + Duration::from_millis(1),
+        "phases {phases:?} exceed total {:?}",
+        sample.t_total
+    );
+}
+
+#[test]
+fn delayed_response_shows_up_in_ttfb() {
+    let delay = Duration::from_millis(300);
+    let url = spawn_server(Behavior::Respond {
+        delay,
+        status: 200,
+        body: "hello",
+    });
+
+    let sample = probe(url, &profile(4096));
+    assert_eq!(error_kind(&sample), None);
+    assert_eq!(sample.http_status, Some(200));
+    assert_eq!(sample.downloaded_bytes, 5);
+    assert_eq!(sample.negotiated.tls_version, None, "plain http has no TLS");
+    assert_eq!(sample.t_tls, None, "plain http has no handshake");
+    assert_eq!(sample.negotiated.alpn.as_deref(), Some("http/1.1"));
+    assert!(sample.t_ttfb >= delay, "ttfb {:?}", sample.t_ttfb);
+    assert!(sample.t_ttfb < delay + SLACK, "ttfb {:?}", sample.t_ttfb);
+    assert!(sample.t_download < SLACK);
+    assert_phases_ordered(&sample);
+}
+
+#[test]
+fn slow_chunked_body_shows_up_in_download() {
+    let url = spawn_server(Behavior::SlowChunked {
+        chunks: 4,
+        chunk_size: 512,
+        gap: Duration::from_millis(100),
+    });
+
+    let sample = probe(url, &profile(0));
+    assert_eq!(error_kind(&sample), None);
+    assert_eq!(sample.downloaded_bytes, 4 * 512);
+    assert_eq!(sample.content_length, None);
+    // The head goes out before the first gap, so the body carries all four gaps
+    assert!(
+        sample.t_download >= Duration::from_millis(300),
+        "download {:?}",
+        sample.t_download
+    );
+    assert!(sample.t_download < Duration::from_millis(400) + SLACK);
+    assert_phases_ordered(&sample);
+}
+
+#[test]
+fn read_limit_aborts_slow_body_early() {
+    let url = spawn_server(Behavior::SlowChunked {
+        chunks: 30,
+        chunk_size: 1024,
+        gap: Duration::from_millis(100),
+    });
+
+    let sample = probe(url, &profile(2048));
+    assert_eq!(
+        error_kind(&sample),
+        None,
+        "hitting the limit is not a failure"
+    );
+    assert_eq!(sample.downloaded_bytes, 2048);
+    // The full body would take 3s; stopping after two chunks cuts that short
+    assert!(
+        sample.t_total < Duration::from_secs(2),
+        "total {:?}",
+        sample.t_total
+    );
+    assert_phases_ordered(&sample);
+}
+
+#[test]
+fn truncated_body_is_left_out_of_goodput() {
+    let url = spawn_server(Behavior::SlowChunked {
+        chunks: 256,
+        chunk_size: 4096,
+        gap: Duration::ZERO,
+    });
+
+    let truncated = probe(url.clone(), &profile(16 * 1024));
+    assert_eq!(error_kind(&truncated), None);
+    assert!(truncated.truncated);
+    assert_eq!(truncated.downloaded_bytes, 16 * 1024);
+
+    let full = probe(url, &profile(0));
+    assert_eq!(error_kind(&full), None);
+    assert!(!full.truncated);
+    assert_eq!(full.downloaded_bytes, 256 * 4096);
+
+    // Both count toward latency; only the complete transfer toward goodput
+    let key = ProfileKey {
+        target_id: truncated.target_id,
+        profile_id: truncated.profile_id,
+    };
+    let mut store = MetricsStore::new();
+    for mut sample in [truncated, full] {
+        sample.target_id = key.target_id;
+        sample.profile_id = key.profile_id;
+        store.push_sample(key, sample, 16, Duration::ZERO);
+    }
+    let aggregate = store.windowed_aggregate(
+        key,
+        WindowSpec::M1,
+        &SamplingConfig::default(),
+        &AggregateView {
+            link_capacity_mbps: Some(1000.0),
+            ..AggregateView::default()
+        },
+        None,
+    );
+    assert_eq!(aggregate.by_metric[&MetricKind::Total].n, 2);
+    assert_eq!(aggregate.by_metric[&MetricKind::Download].n, 2);
+    assert_eq!(aggregate.by_metric[&MetricKind::GoodputBps].n, 1);
+    assert_eq!(aggregate.by_metric[&MetricKind::BandwidthUtilization].n, 1);
+}
+
+#[test]
+fn error_statuses_are_classified() {
+    for status in [404, 503] {
+        let url = spawn_server(Behavior::Respond {
+            delay: Duration::ZERO,
+            status,
+            body: "",
+        });
+        let sample = probe(url, &profile(4096));
+        assert_eq!(sample.http_status, Some(status));
+        assert_eq!(error_kind(&sample), Some(ProbeErrorKind::HttpStatusError));
+    }
+}
+
+#[test]
+fn refused_connection_is_a_connect_error() {
+    // Bind then release a port so nothing listens on it
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("free port");
+    let url = Url::parse(&format!("http://{addr}/")).expect("url");
+
+    let sample = probe(url, &profile(4096));
+    assert_eq!(error_kind(&sample), Some(ProbeErrorKind::ConnectOther));
+}
+
+#[test]
+fn reset_mid_request_is_an_error() {
+    let url = spawn_server(Behavior::Reset);
+
+    let sample = probe(url, &profile(4096));
+    assert_eq!(error_kind(&sample), Some(ProbeErrorKind::IoError));
+}
+
+#[test]
+fn untrusted_certificate_fails_the_handshake() {
+    let url = spawn_tls_server();
+
+    let sample = probe(url, &profile(4096));
+    assert_eq!(
+        error_kind(&sample),
+        Some(ProbeErrorKind::TlsHandshakeFailed)
+    );
+}
+
+#[test]
+fn unix_socket_target_keeps_logical_host_and_skips_dns() {
+    let path: PathBuf = std::env::temp_dir().join(format!("httpulse-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).expect("bind unix socket");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            respond_if_host(&mut stream, "api.internal");
+        }
+    });
+
+    let profile = profile(4096);
+    let url = Url::parse("http://api.internal/health").expect("url");
+    let mut target = TargetConfig::new(url, vec![profile.clone()]);
+    target.unix_socket = Some(path.clone());
+    let sample = ProbeClient::new()
+        .expect("client")
+        .probe(&target, &profile, None);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(error_kind(&sample), None);
+    assert_eq!(sample.http_status, Some(200));
+    assert_eq!(sample.t_dns, None);
+}
+
+#[test]
+fn connect_to_override_keeps_logical_host() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            respond_if_host(&mut stream, "api.internal");
+        }
+    });
+
+    let profile = profile(4096);
+    let url = Url::parse("http://api.internal/health").expect("url");
+    let mut target = TargetConfig::new(url, vec![profile.clone()]);
+    target.connect_to = Some(addr.to_string().parse().expect("connect_to"));
+    let sample = ProbeClient::new()
+        .expect("client")
+        .probe(&target, &profile, None);
+
+    assert_eq!(error_kind(&sample), None);
+    assert_eq!(sample.http_status, Some(200));
+    assert_eq!(sample.remote, Some(addr));
+}
+
+#[test]
+fn http_1_0_profile_sends_a_1_0_request() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Some(request) = read_request_head(&mut stream) else {
+                continue;
+            };
+            let status = if request.starts_with(b"GET / HTTP/1.0\r\n") {
+                200
+            } else {
+                505
+            };
+            let _ = stream.write_all(canned_response(status, "").as_bytes());
+        }
+    });
+
+    let profile = ProfileConfig::new(
+        "h1.0+cold",
+        HttpVersion::H1_0,
+        TlsVersion::Tls13,
+        ConnReusePolicy::Cold,
+        ProbeMethod::Get,
+        4096,
+    );
+    let sample = probe(
+        Url::parse(&format!("http://{addr}/")).expect("url"),
+        &profile,
+    );
+    assert_eq!(error_kind(&sample), None);
+    assert_eq!(sample.http_status, Some(200));
+    // Plain http pins no TLS version, so none is reported as configured
+    assert_eq!(sample.negotiated.tls_version, None);
+    assert_eq!(sample.t_tls, None);
+}
+
+#[test]
+fn auth_profiles_send_an_authorization_header() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Some(request) = read_request_head(&mut stream) else {
+                continue;
+            };
+            let request = String::from_utf8_lossy(&request);
+            let status = if request.contains("Authorization: Basic YWxpY2U6czNjcmV0\r\n")
+                || request.contains("Authorization: Bearer tok-123\r\n")
+            {
+                200
+            } else {
+                401
+            };
+            let _ = stream.write_all(canned_response(status, "").as_bytes());
+        }
+    });
+    let url = Url::parse(&format!("http://{addr}/")).expect("url");
+
+    let mut profile = profile(4096);
+    assert_eq!(probe(url.clone(), &profile).http_status, Some(401));
+    profile.auth = AuthConfig::basic("alice:s3cret");
+    assert_eq!(probe(url.clone(), &profile).http_status, Some(200));
+    profile.auth = AuthConfig::bearer("tok-123");
+    assert_eq!(probe(url, &profile).http_status, Some(200));
+}
+
+#[test]
+fn warm_profile_reuses_its_connection_across_probes() {
+    // Keeps each connection open and answers every request on it
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            thread::spawn(move || {
+                while read_request_head(&mut stream).is_some() {
+                    let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+                    if stream.write_all(response.as_bytes()).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    let url = Url::parse(&format!("http://{addr}/")).expect("url");
+
+    let opened = |reuse| -> Vec<Option<u32>> {
+        let mut profile = profile(4096);
+        profile.conn_reuse = reuse;
+        let mut target = TargetConfig::new(url.clone(), vec![profile.clone()]);
+        target.timeout_total = Duration::from_secs(10);
+        let mut client = ProbeClient::new().expect("client");
+        (0..3)
+            .map(|_| {
+                let sample = client.probe(&target, &profile, None);
+                assert_eq!(error_kind(&sample), None);
+                sample.new_connections
+            })
+            .collect()
+    };
+    assert_eq!(opened(ConnReusePolicy::Warm), [Some(1), Some(0), Some(0)]);
+    assert_eq!(opened(ConnReusePolicy::Cold), [Some(1), Some(1), Some(1)]);
+}
+
+#[test]
+fn ping_profile_measures_round_trip_only() {
+    // Either mode reaches a loopback listener: ICMP where permitted, else its port
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url =
+        Url::parse(&format!("http://{}/", listener.local_addr().expect("addr"))).expect("url");
+    let mut profile = profile(4096);
+    profile.method = ProbeMethod::Ping;
+
+    let sample = probe(url, &profile);
+    assert_eq!(error_kind(&sample), None);
+    assert!(sample.ping.is_some());
+    assert_eq!(sample.http_status, None);
+    assert_eq!(sample.t_dns, None);
+    assert!(sample.t_total < SLACK, "rtt {:?}", sample.t_total);
+}
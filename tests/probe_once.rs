@@ -0,0 +1,90 @@
+use httpulse::config::{
+    ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig, TlsVersion,
+};
+use httpulse::probe::{ProbeErrorKind, ProbeOptions, probe_once};
+use httpulse::probe_engine::{format_once_table, once_exit_code, run_once};
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener};
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+/// Serves one connection with `response` after reading the request head.
+fn serve_once(response: &'static str) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept");
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => return,
+                Ok(read) => request.extend_from_slice(&buf[..read]),
+            }
+        }
+        let _ = stream.write_all(response.as_bytes());
+    });
+    Url::parse(&format!("http://{addr}/")).expect("url")
+}
+
+fn profile() -> ProfileConfig {
+    ProfileConfig::new(
+        "h1+cold",
+        HttpVersion::H1,
+        TlsVersion::Tls12,
+        ConnReusePolicy::Cold,
+        ProbeMethod::Get,
+        4096,
+    )
+}
+
+#[test]
+fn probe_once_returns_sample_for_canned_response() {
+    let url = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello");
+    let opts = ProbeOptions {
+        timeout: Duration::from_secs(5),
+        ..ProbeOptions::default()
+    };
+
+    let sample = probe_once(&url, &profile(), opts).expect("probe should succeed");
+    assert_eq!(sample.http_status, Some(200));
+    assert_eq!(sample.downloaded_bytes, 5);
+    assert_eq!(sample.content_length, Some(5));
+    assert_eq!(sample.resolved_ips, vec![IpAddr::from([127, 0, 0, 1])]);
+    assert!(sample.t_total >= sample.t_ttfb);
+    assert!(sample.remote.is_some());
+}
+
+#[test]
+fn probe_once_reports_failures_as_errors() {
+    let url = serve_once(
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    );
+
+    let err = probe_once(&url, &profile(), ProbeOptions::default()).expect_err("503 should fail");
+    assert_eq!(err.kind, ProbeErrorKind::HttpStatusError);
+    assert!(err.to_string().starts_with("http_status_error"));
+}
+
+#[test]
+fn run_once_reports_every_target_and_fails_the_exit_code_on_any_failure() {
+    let healthy = serve_once("HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+    let broken = serve_once(
+        "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    );
+    let targets = [
+        TargetConfig::new(healthy, vec![profile()]),
+        TargetConfig::new(broken, vec![profile()]),
+    ];
+
+    let results = run_once(&targets);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].ok);
+    assert_eq!(results[0].status, Some(200));
+    assert!(!results[1].ok);
+    assert_eq!(results[1].error.as_deref(), Some("http_status_error"));
+    assert_eq!(once_exit_code(&results), 1);
+    assert_eq!(once_exit_code(&results[..1]), 0);
+    assert!(format_once_table(&results).contains("FAIL http_status_error"));
+}
@@ -0,0 +1,87 @@
+//! The proxy toggle is process-wide, so this lives in its own test binary.
+
+use httpulse::config::{
+    ConnReusePolicy, HttpVersion, ProbeMethod, ProfileConfig, TargetConfig, TlsVersion,
+};
+use httpulse::probe::ProbeResult;
+use httpulse::probe_engine::{ProbeClient, set_use_proxy_env};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use url::Url;
+
+const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+/// Answers every connection with `RESPONSE` and counts the requests.
+fn serve() -> (u16, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let port = listener.local_addr().expect("local addr").port();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&requests);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            counter.fetch_add(1, Ordering::SeqCst);
+            respond(stream);
+        }
+    });
+    (port, requests)
+}
+
+fn respond(mut stream: TcpStream) {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf) {
+            Ok(0) | Err(_) => return,
+            Ok(read) => request.extend_from_slice(&buf[..read]),
+        }
+    }
+    let _ = stream.write_all(RESPONSE);
+}
+
+#[test]
+fn proxy_environment_is_ignored_unless_enabled() {
+    let (server_port, server_requests) = serve();
+    let (proxy_port, proxy_requests) = serve();
+    // SAFETY: this binary's only test, so no other thread reads the environment
+    unsafe {
+        std::env::set_var("http_proxy", format!("http://127.0.0.1:{proxy_port}"));
+        std::env::remove_var("no_proxy");
+        std::env::remove_var("NO_PROXY");
+    }
+
+    let target = TargetConfig::new(
+        Url::parse(&format!("http://127.0.0.1:{server_port}/")).unwrap(),
+        Vec::new(),
+    );
+    let profile = ProfileConfig::new(
+        "h1+cold",
+        HttpVersion::H1,
+        TlsVersion::Tls12,
+        ConnReusePolicy::Cold,
+        ProbeMethod::Get,
+        1024,
+    );
+    let mut client = ProbeClient::new().expect("client");
+
+    let direct = client.probe(&target, &profile, None);
+    assert!(
+        matches!(direct.result, ProbeResult::Ok),
+        "{:?}",
+        direct.result
+    );
+    assert_eq!(server_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(proxy_requests.load(Ordering::SeqCst), 0);
+
+    set_use_proxy_env(true);
+    let proxied = client.probe(&target, &profile, None);
+    assert!(
+        matches!(proxied.result, ProbeResult::Ok),
+        "{:?}",
+        proxied.result
+    );
+    assert_eq!(server_requests.load(Ordering::SeqCst), 1);
+    assert_eq!(proxy_requests.load(Ordering::SeqCst), 1);
+}